@@ -0,0 +1,53 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "grpc")]
+    grpc::compile();
+}
+
+/// 以 `tonic_build::manual` 產生 gRPC 服務程式碼，不需要安裝 protoc
+///
+/// 訊息型別定義在 `src/grpc/proto.rs`，對外的合約見 `proto/admin.proto`，兩者需保持一致。
+#[cfg(feature = "grpc")]
+mod grpc {
+    use tonic_build::manual::{Builder, Method, Service};
+
+    const METHODS: [(&str, &str, &str, &str); 4] = [
+        ("send_push", "SendPush", "SendPushRequest", "SendResponse"),
+        ("broadcast", "Broadcast", "BroadcastRequest", "SendResponse"),
+        (
+            "get_stats",
+            "GetStats",
+            "GetStatsRequest",
+            "GetStatsResponse",
+        ),
+        (
+            "manage_rich_menu",
+            "ManageRichMenu",
+            "ManageRichMenuRequest",
+            "ManageRichMenuResponse",
+        ),
+    ];
+
+    pub fn compile() {
+        let service = METHODS
+            .iter()
+            .fold(
+                Service::builder()
+                    .name("AdminService")
+                    .package("linebot.admin"),
+                |service, (name, route_name, input, output)| {
+                    service.method(
+                        Method::builder()
+                            .name(name)
+                            .route_name(route_name)
+                            .input_type(format!("super::{}", input))
+                            .output_type(format!("super::{}", output))
+                            .codec_path("tonic::codec::ProstCodec")
+                            .build(),
+                    )
+                },
+            )
+            .build();
+        Builder::new().build_client(false).compile(&[service]);
+    }
+}
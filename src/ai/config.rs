@@ -0,0 +1,47 @@
+use serde::Deserialize;
+use std::fmt;
+
+/// OpenAI 相容 chat completion 端點的設定
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct LlmConfig {
+    /// 例如 `https://api.openai.com/v1`，請求送到 `{base_url}/chat/completions`
+    pub base_url: String,
+    /// 本機模型伺服器等不需驗證的端點可不設定
+    pub api_key: Option<String>,
+    pub model: String,
+    pub system_prompt: Option<String>,
+    /// 單次回覆的 token 上限
+    pub max_tokens: u32,
+    /// 每位使用者每日（UTC）可用的 token 數，未設定時不限制
+    pub user_daily_token_budget: Option<u64>,
+}
+
+impl LlmConfig {
+    pub fn new(model: impl Into<String>) -> Self {
+        Self {
+            base_url: "https://api.openai.com/v1".to_string(),
+            api_key: None,
+            model: model.into(),
+            system_prompt: None,
+            max_tokens: 500,
+            user_daily_token_budget: None,
+        }
+    }
+
+    pub fn completions_url(&self) -> String {
+        format!("{}/chat/completions", self.base_url.trim_end_matches('/'))
+    }
+}
+
+impl fmt::Debug for LlmConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LlmConfig")
+            .field("base_url", &self.base_url)
+            .field("api_key", &self.api_key.as_ref().map(|_| "***"))
+            .field("model", &self.model)
+            .field("system_prompt", &self.system_prompt)
+            .field("max_tokens", &self.max_tokens)
+            .field("user_daily_token_budget", &self.user_daily_token_budget)
+            .finish()
+    }
+}
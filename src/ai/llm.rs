@@ -0,0 +1,367 @@
+use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
+use serde_json::{Value, json};
+use std::{error::Error, fmt, sync::Arc, time::Duration};
+use tracing::{debug, warn};
+
+use crate::ai::LlmConfig;
+use crate::handlers::MessageHandler;
+use crate::models::{Event, MessageType, OutgoingMessage};
+use crate::storage::{KvNamespace, Storage, StorageError, UserDataCleanup};
+use crate::utils::message_limits;
+
+/// 單次請求的逾時
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// 單則訊息預設的字元數，長回覆會切成多則
+const DEFAULT_CHUNK_LENGTH: usize = 1000;
+
+/// 一個字元以 UTF-16 計算最多佔兩個單位，切塊不能超過文字訊息上限的一半
+const MAX_CHUNK_LENGTH: usize = message_limits::MAX_TEXT_LENGTH / 2;
+
+const BUDGET_EXHAUSTED_TEXT: &str = "今日的 AI 對話額度已用完，請明天再試。";
+
+#[derive(Debug)]
+pub struct LlmError {
+    pub message: String,
+    pub status_code: Option<u16>,
+}
+
+impl fmt::Display for LlmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "LLM Error: {}", self.message)
+    }
+}
+
+impl Error for LlmError {}
+
+impl LlmError {
+    fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            status_code: None,
+        }
+    }
+}
+
+/// 把文字訊息轉給 OpenAI 相容的 chat completion 端點，回覆模型的輸出
+///
+/// 以串流方式接收回覆，收齊後依段落切成多則文字訊息（最多 5 則）。
+/// 設定 [`LlmConfig::user_daily_token_budget`] 時依使用者在儲存後端累加當日用量，
+/// 多個實例共用同一份額度，重新啟動也不會歸零；用完後回覆固定文字。
+#[derive(Clone)]
+pub struct LlmHandler {
+    config: Arc<LlmConfig>,
+    client: reqwest::Client,
+    /// 鍵為 `{user_id}:{日期}`，每位使用者只保留最近一天
+    usage: KvNamespace,
+    chunk_length: usize,
+}
+
+impl LlmHandler {
+    pub fn new(config: LlmConfig, storage: Arc<dyn Storage>) -> Self {
+        let client = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(10))
+            .build()
+            .expect("Failed to create HTTP client");
+        Self {
+            config: Arc::new(config),
+            client,
+            usage: KvNamespace::new(storage, "llm_usage"),
+            chunk_length: DEFAULT_CHUNK_LENGTH,
+        }
+    }
+
+    /// 使用共用的 HTTP 用戶端
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// 每則訊息的字元數上限，超過 2500 時以 2500 計
+    pub fn chunk_length(mut self, length: usize) -> Self {
+        self.chunk_length = length.clamp(1, MAX_CHUNK_LENGTH);
+        self
+    }
+
+    /// 使用者今日剩餘的 token 數，未設定額度時回傳 `None`
+    pub async fn remaining_tokens(&self, user_id: &str) -> Result<Option<u64>, StorageError> {
+        let Some(budget) = self.config.user_daily_token_budget else {
+            return Ok(None);
+        };
+        let used: u64 = self
+            .usage
+            .get(&usage_key(user_id, Utc::now().date_naive()))
+            .await?
+            .unwrap_or_default();
+        Ok(Some(budget.saturating_sub(used)))
+    }
+
+    /// 送出一則使用者訊息並回傳切塊後的回覆；沒有使用者 ID 時不計入額度
+    pub async fn complete(
+        &self,
+        user_id: Option<&str>,
+        text: &str,
+    ) -> Result<Vec<OutgoingMessage>, LlmError> {
+        let remaining = match user_id {
+            Some(user_id) => self
+                .remaining_tokens(user_id)
+                .await
+                .map_err(|e| LlmError::new(format!("Failed to read token usage: {}", e)))?,
+            None => None,
+        };
+        if remaining == Some(0) {
+            return Ok(vec![OutgoingMessage::text(BUDGET_EXHAUSTED_TEXT)]);
+        }
+        let max_tokens = remaining.map_or(self.config.max_tokens, |remaining| {
+            u64::from(self.config.max_tokens).min(remaining) as u32
+        });
+
+        let mut messages = Vec::new();
+        if let Some(system_prompt) = &self.config.system_prompt {
+            messages.push(json!({ "role": "system", "content": system_prompt }));
+        }
+        messages.push(json!({ "role": "user", "content": text }));
+        let request = json!({
+            "model": self.config.model,
+            "messages": messages,
+            "max_tokens": max_tokens,
+            "stream": true,
+            "stream_options": { "include_usage": true },
+        });
+
+        let (reply, total_tokens) = self.stream_completion(&request).await?;
+        // 端點未回傳用量時以字元數估計，中文一字約一個 token 以上，估計偏保守
+        let total_tokens = total_tokens.unwrap_or_else(|| {
+            let prompt = self.config.system_prompt.as_deref().unwrap_or_default();
+            (prompt.chars().count() + text.chars().count() + reply.chars().count()) as u64
+        });
+        if let Some(user_id) = user_id
+            && self.config.user_daily_token_budget.is_some()
+            && let Err(e) = self.record_usage(user_id, total_tokens).await
+        {
+            warn!("Failed to record LLM token usage: {}", e);
+        }
+        debug!("LLM reply used {} tokens", total_tokens);
+
+        Ok(split_chunks(reply.trim(), self.chunk_length)
+            .into_iter()
+            .map(OutgoingMessage::text)
+            .collect())
+    }
+
+    /// 讀取 server-sent events，回傳完整回覆與端點回報的 token 總數
+    async fn stream_completion(&self, request: &Value) -> Result<(String, Option<u64>), LlmError> {
+        let mut builder = self
+            .client
+            .post(self.config.completions_url())
+            .timeout(REQUEST_TIMEOUT)
+            .json(request);
+        if let Some(api_key) = &self.config.api_key {
+            builder = builder.bearer_auth(api_key);
+        }
+        let mut response = builder
+            .send()
+            .await
+            .map_err(|e| LlmError::new(format!("Failed to send request: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(LlmError {
+                message: format!("Request failed with status {}: {}", status, body),
+                status_code: Some(status.as_u16()),
+            });
+        }
+
+        let mut reply = String::new();
+        let mut total_tokens = None;
+        let mut buffer = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| LlmError::new(format!("Failed to read stream: {}", e)))?
+        {
+            buffer.extend_from_slice(&chunk);
+            while let Some(newline) = buffer.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=newline).collect();
+                let line = String::from_utf8_lossy(&line);
+                let Some(data) = line.trim().strip_prefix("data:") else {
+                    continue;
+                };
+                let data = data.trim();
+                if data == "[DONE]" {
+                    return Ok((reply, total_tokens));
+                }
+                let event: Value = serde_json::from_str(data)
+                    .map_err(|e| LlmError::new(format!("Invalid stream event: {}", e)))?;
+                if let Some(content) = event["choices"][0]["delta"]["content"].as_str() {
+                    reply.push_str(content);
+                }
+                if let Some(tokens) = event["usage"]["total_tokens"].as_u64() {
+                    total_tokens = Some(tokens);
+                }
+            }
+        }
+        Ok((reply, total_tokens))
+    }
+
+    /// 當日第一次使用時刪除之前的紀錄
+    async fn record_usage(&self, user_id: &str, tokens: u64) -> Result<(), StorageError> {
+        let tokens = i64::try_from(tokens).unwrap_or(i64::MAX);
+        let today = usage_key(user_id, Utc::now().date_naive());
+        if self.usage.increment(&today, tokens).await? == tokens {
+            for key in self.usage.keys(&format!("{}:", user_id)).await? {
+                if key != today {
+                    self.usage.delete(&key).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MessageHandler for LlmHandler {
+    async fn handle_event(
+        &self,
+        event: Event,
+    ) -> Result<Vec<OutgoingMessage>, Box<dyn std::error::Error>> {
+        let Event::Message(message_event) = event else {
+            return Ok(vec![]);
+        };
+        let MessageType::Text { text } = &message_event.message else {
+            return Ok(vec![]);
+        };
+        Ok(self.complete(message_event.source.user_id(), text).await?)
+    }
+}
+
+#[async_trait]
+impl UserDataCleanup for LlmHandler {
+    /// 刪除使用者的 token 用量
+    async fn purge_user(&self, user_id: &str) -> Result<(), StorageError> {
+        for key in self.usage.keys(&format!("{}:", user_id)).await? {
+            self.usage.delete(&key).await?;
+        }
+        Ok(())
+    }
+}
+
+fn usage_key(user_id: &str, date: NaiveDate) -> String {
+    format!("{}:{}", user_id, date)
+}
+
+/// 依換行切成不超過 `max_chars` 字元的片段，最多 5 段，超出部分截斷並加上 `…`
+fn split_chunks(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks: Vec<String> = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        if chunks.len() == message_limits::MAX_MESSAGES {
+            if let Some(last) = chunks.last_mut() {
+                last.pop();
+                last.push('…');
+            }
+            break;
+        }
+        let end = rest
+            .char_indices()
+            .nth(max_chars)
+            .map_or(rest.len(), |(index, _)| index);
+        let split = if end == rest.len() {
+            end
+        } else {
+            rest[..end]
+                .rfind('\n')
+                .filter(|&index| index > 0)
+                .unwrap_or(end)
+        };
+        chunks.push(rest[..split].trim().to_string());
+        rest = rest[split..].trim_start();
+    }
+    chunks.retain(|chunk| !chunk.is_empty());
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+    use axum::{Json, Router, http::HeaderMap, routing::post};
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_split_chunks() {
+        assert_eq!(split_chunks("第一段\n第二段", 5), vec!["第一段", "第二段"]);
+        assert_eq!(split_chunks("abcdefg", 3), vec!["abc", "def", "g"]);
+        assert_eq!(split_chunks("短", 10), vec!["短"]);
+
+        let chunks = split_chunks(&"a".repeat(20), 3);
+        assert_eq!(chunks.len(), message_limits::MAX_MESSAGES);
+        assert_eq!(chunks[4], "aa…");
+    }
+
+    #[tokio::test]
+    async fn test_streamed_reply_and_budget() {
+        let received = Arc::new(Mutex::new(None));
+        let store = received.clone();
+        let app = Router::new().route(
+            "/v1/chat/completions",
+            post(move |headers: HeaderMap, Json(body): Json<Value>| {
+                *store.lock().unwrap() = Some((headers, body));
+                async {
+                    [
+                        r#"data: {"choices":[{"delta":{"role":"assistant","content":"你好"}}]}"#,
+                        r#"data: {"choices":[{"delta":{"content":"，我是機器人"}}]}"#,
+                        r#"data: {"choices":[],"usage":{"total_tokens":30}}"#,
+                        "data: [DONE]",
+                    ]
+                    .join("\n\n")
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let storage = Arc::new(MemoryStorage::new());
+        let config = LlmConfig {
+            base_url: format!("http://{}/v1", addr),
+            api_key: Some("sk-test".to_string()),
+            system_prompt: Some("你是客服".to_string()),
+            max_tokens: 100,
+            user_daily_token_budget: Some(50),
+            ..LlmConfig::new("test-model")
+        };
+        let handler = LlmHandler::new(config.clone(), storage.clone());
+        let yesterday = Utc::now().date_naive().pred_opt().unwrap();
+        handler
+            .usage
+            .set(&usage_key("U1", yesterday), &40)
+            .await
+            .unwrap();
+
+        let reply = handler.complete(Some("U1"), "嗨").await.unwrap();
+        assert_eq!(reply, vec![OutgoingMessage::text("你好，我是機器人")]);
+        assert_eq!(handler.remaining_tokens("U1").await.unwrap(), Some(20));
+        // 只保留當日的用量
+        assert_eq!(handler.usage.keys("U1:").await.unwrap().len(), 1);
+
+        let (headers, body) = received.lock().unwrap().take().unwrap();
+        assert_eq!(headers["authorization"], "Bearer sk-test");
+        assert_eq!(body["model"], "test-model");
+        assert_eq!(body["messages"][0]["role"], "system");
+        assert_eq!(body["max_tokens"], 50);
+
+        handler.complete(Some("U1"), "再一次").await.unwrap();
+        assert_eq!(received.lock().unwrap().take().unwrap().1["max_tokens"], 20);
+        // 用量存在儲存後端，重新建立的處理器（例如重新啟動或其他實例）共用同一份額度
+        let restarted = LlmHandler::new(config, storage);
+        assert_eq!(restarted.remaining_tokens("U1").await.unwrap(), Some(0));
+        assert_eq!(
+            restarted.complete(Some("U1"), "還有嗎").await.unwrap(),
+            vec![OutgoingMessage::text(BUDGET_EXHAUSTED_TEXT)]
+        );
+        assert!(received.lock().unwrap().is_none());
+    }
+}
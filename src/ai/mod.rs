@@ -0,0 +1,11 @@
+//! 以大型語言模型回覆訊息
+//!
+//! [`LlmConfig`] 一律可用以便從環境變數讀取設定，[`LlmHandler`] 需啟用 `ai` feature。
+
+pub mod config;
+#[cfg(feature = "ai")]
+pub mod llm;
+
+pub use config::*;
+#[cfg(feature = "ai")]
+pub use llm::*;
@@ -0,0 +1,45 @@
+use serde::Deserialize;
+use std::fmt;
+
+/// LINE Login channel 設定
+///
+/// 只有 `channel_id` 時僅開放 `/liff/verify`；另外設定 `channel_secret` 與 `redirect_uri` 後
+/// 才開放網頁登入（`/auth/line/*`）。
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct LineLoginConfig {
+    pub channel_id: String,
+    pub channel_secret: Option<String>,
+    /// 在 LINE Developers Console 登記的 callback URL，需指向 `/auth/line/callback`
+    pub redirect_uri: Option<String>,
+    /// 以空白分隔的權限，例如 `profile openid email`
+    pub scope: String,
+    /// 登入成功後導向的網址
+    pub success_url: String,
+}
+
+impl LineLoginConfig {
+    pub fn new<T: Into<String>>(channel_id: T) -> Self {
+        Self {
+            channel_id: channel_id.into(),
+            channel_secret: None,
+            redirect_uri: None,
+            scope: "profile openid".to_string(),
+            success_url: "/".to_string(),
+        }
+    }
+}
+
+impl fmt::Debug for LineLoginConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LineLoginConfig")
+            .field("channel_id", &self.channel_id)
+            .field(
+                "channel_secret",
+                &self.channel_secret.as_ref().map(|_| "***"),
+            )
+            .field("redirect_uri", &self.redirect_uri)
+            .field("scope", &self.scope)
+            .field("success_url", &self.success_url)
+            .finish()
+    }
+}
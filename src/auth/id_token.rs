@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+/// 單次請求的逾時
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+const VERIFY_ENDPOINT: &str = "https://api.line.me/oauth2/v2.1/verify";
+
+#[derive(Debug)]
+pub struct AuthError {
+    pub message: String,
+    /// LINE 回傳的 HTTP 狀態碼，請求未送達時為 `None`
+    pub status_code: Option<u16>,
+}
+
+impl AuthError {
+    pub fn new<T: Into<String>>(message: T) -> Self {
+        Self {
+            message: message.into(),
+            status_code: None,
+        }
+    }
+
+    /// 使用者送來的參數無效（例如過期的 `state`），視同 LINE 回傳 400
+    pub fn rejected<T: Into<String>>(message: T) -> Self {
+        Self {
+            message: message.into(),
+            status_code: Some(400),
+        }
+    }
+
+    /// LINE 拒絕了 token 或授權碼（而非連線失敗）
+    pub fn is_rejected(&self) -> bool {
+        matches!(self.status_code, Some(400..=499))
+    }
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "LINE Login Error: {}", self.message)
+    }
+}
+
+impl Error for AuthError {}
+
+/// 驗證通過的 ID token 內容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IdTokenClaims {
+    pub iss: String,
+    /// LINE 使用者 ID
+    pub sub: String,
+    /// LINE Login channel ID
+    pub aud: String,
+    pub exp: i64,
+    pub iat: i64,
+    pub nonce: Option<String>,
+    #[serde(default)]
+    pub amr: Vec<String>,
+    pub name: Option<String>,
+    pub picture: Option<String>,
+    /// 需取得 `email` 權限
+    pub email: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+/// 以 LINE 的 `/oauth2/v2.1/verify` 端點驗證 ID token
+///
+/// LINE 會檢查簽名、有效期限與 `aud` 是否為指定的 channel ID。
+#[derive(Clone)]
+pub struct IdTokenVerifier {
+    client: reqwest::Client,
+    channel_id: String,
+    endpoint: String,
+}
+
+impl IdTokenVerifier {
+    pub fn new(channel_id: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            channel_id: channel_id.into(),
+            endpoint: VERIFY_ENDPOINT.to_string(),
+        }
+    }
+
+    /// 使用共用的 HTTP 用戶端
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// 覆寫 verify 端點，測試時使用
+    pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    pub fn channel_id(&self) -> &str {
+        &self.channel_id
+    }
+
+    /// 驗證 ID token；指定 `nonce` 時一併檢查與登入時送出的值相同
+    pub async fn verify(
+        &self,
+        id_token: &str,
+        nonce: Option<&str>,
+    ) -> Result<IdTokenClaims, AuthError> {
+        let mut form = vec![
+            ("id_token", id_token),
+            ("client_id", self.channel_id.as_str()),
+        ];
+        if let Some(nonce) = nonce {
+            form.push(("nonce", nonce));
+        }
+        let response = self
+            .client
+            .post(&self.endpoint)
+            .timeout(REQUEST_TIMEOUT)
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| AuthError::new(format!("Failed to send request: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let message = response
+                .json::<ErrorResponse>()
+                .await
+                .ok()
+                .and_then(|body| body.error_description.or(body.error))
+                .unwrap_or_else(|| status.to_string());
+            return Err(AuthError {
+                message,
+                status_code: Some(status.as_u16()),
+            });
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| AuthError::new(format!("Invalid verify response: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Form, Json, Router, http::StatusCode, response::IntoResponse, routing::post};
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_verify_id_token() {
+        let app = Router::new().route(
+            "/oauth2/v2.1/verify",
+            post(|Form(form): Form<HashMap<String, String>>| async move {
+                assert_eq!(form["client_id"], "1234567890");
+                if form["id_token"] != "valid" {
+                    return (
+                        StatusCode::BAD_REQUEST,
+                        Json(json!({
+                            "error": "invalid_request",
+                            "error_description": "IdToken expired."
+                        })),
+                    )
+                        .into_response();
+                }
+                Json(json!({
+                    "iss": "https://access.line.me",
+                    "sub": "U1234567890abcdef1234567890abcdef",
+                    "aud": "1234567890",
+                    "exp": 1504169092,
+                    "iat": 1504263657,
+                    "nonce": form.get("nonce"),
+                    "amr": ["pwd"],
+                    "name": "Taro Line",
+                    "picture": "https://sample_line.me/aBcdefg123456"
+                }))
+                .into_response()
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let verifier = IdTokenVerifier::new("1234567890")
+            .endpoint(format!("http://{}/oauth2/v2.1/verify", addr));
+        let claims = verifier.verify("valid", Some("n-1")).await.unwrap();
+        assert_eq!(claims.sub, "U1234567890abcdef1234567890abcdef");
+        assert_eq!(claims.name.as_deref(), Some("Taro Line"));
+        assert_eq!(claims.nonce.as_deref(), Some("n-1"));
+        assert_eq!(claims.email, None);
+
+        let error = verifier.verify("expired", None).await.unwrap_err();
+        assert!(error.is_rejected());
+        assert_eq!(error.message, "IdToken expired.");
+    }
+}
@@ -0,0 +1,250 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+use crate::auth::{AuthError, IdTokenClaims, IdTokenVerifier};
+
+/// 單次請求的逾時
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+const AUTHORIZE_URL: &str = "https://access.line.me/oauth2/v2.1/authorize";
+const API_BASE_URL: &str = "https://api.line.me";
+
+/// 轉址到 LINE 登入頁面所需的參數，`state`、`code_verifier` 與 `nonce` 需保存到 callback
+#[derive(Debug, Clone)]
+pub struct AuthorizationRequest {
+    pub url: String,
+    pub state: String,
+    pub code_verifier: String,
+    pub nonce: String,
+}
+
+/// Token 端點的回應
+#[derive(Debug, Clone, Deserialize)]
+pub struct LoginToken {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: u64,
+    pub refresh_token: Option<String>,
+    pub scope: Option<String>,
+    /// 權限包含 `openid` 時才有
+    pub id_token: Option<String>,
+}
+
+/// `GET /v2/profile` 取得的登入使用者資料
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginProfile {
+    pub user_id: String,
+    pub display_name: String,
+    pub picture_url: Option<String>,
+    pub status_message: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: Option<String>,
+    error_description: Option<String>,
+    message: Option<String>,
+}
+
+/// LINE Login（OAuth 2.1 authorization code flow 加 PKCE）客戶端
+#[derive(Clone)]
+pub struct LineLoginClient {
+    client: reqwest::Client,
+    channel_id: String,
+    channel_secret: String,
+    redirect_uri: String,
+    scope: String,
+    authorize_url: String,
+    api_base_url: String,
+    verifier: IdTokenVerifier,
+}
+
+impl LineLoginClient {
+    pub fn new(
+        channel_id: impl Into<String>,
+        channel_secret: impl Into<String>,
+        redirect_uri: impl Into<String>,
+    ) -> Self {
+        let channel_id = channel_id.into();
+        Self {
+            client: reqwest::Client::new(),
+            verifier: IdTokenVerifier::new(channel_id.clone()),
+            channel_id,
+            channel_secret: channel_secret.into(),
+            redirect_uri: redirect_uri.into(),
+            scope: "profile openid".to_string(),
+            authorize_url: AUTHORIZE_URL.to_string(),
+            api_base_url: API_BASE_URL.to_string(),
+        }
+    }
+
+    /// 使用共用的 HTTP 用戶端
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.verifier = self.verifier.http_client(client.clone());
+        self.client = client;
+        self
+    }
+
+    /// 以空白分隔的權限，預設為 `profile openid`
+    pub fn scope(mut self, scope: impl Into<String>) -> Self {
+        self.scope = scope.into();
+        self
+    }
+
+    /// 覆寫 `api.line.me`（token、profile 與 verify 端點），測試時使用
+    pub fn api_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.api_base_url = base_url.into().trim_end_matches('/').to_string();
+        self.verifier = self
+            .verifier
+            .endpoint(format!("{}/oauth2/v2.1/verify", self.api_base_url));
+        self
+    }
+
+    pub fn redirect_uri(&self) -> &str {
+        &self.redirect_uri
+    }
+
+    /// 產生新的 `state`、PKCE code verifier 與 nonce，並組出登入頁面網址
+    pub fn authorization_request(&self) -> AuthorizationRequest {
+        let state = random_token();
+        let code_verifier = random_token();
+        let nonce = random_token();
+        let url = reqwest::Url::parse_with_params(
+            &self.authorize_url,
+            [
+                ("response_type", "code"),
+                ("client_id", self.channel_id.as_str()),
+                ("redirect_uri", self.redirect_uri.as_str()),
+                ("state", state.as_str()),
+                ("scope", self.scope.as_str()),
+                ("nonce", nonce.as_str()),
+                ("code_challenge", code_challenge(&code_verifier).as_str()),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .expect("authorize URL is valid")
+        .to_string();
+        AuthorizationRequest {
+            url,
+            state,
+            code_verifier,
+            nonce,
+        }
+    }
+
+    /// 以 callback 收到的授權碼換取 access token
+    pub async fn exchange_code(
+        &self,
+        code: &str,
+        code_verifier: &str,
+    ) -> Result<LoginToken, AuthError> {
+        let response = self
+            .client
+            .post(format!("{}/oauth2/v2.1/token", self.api_base_url))
+            .timeout(REQUEST_TIMEOUT)
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", self.redirect_uri.as_str()),
+                ("client_id", self.channel_id.as_str()),
+                ("client_secret", self.channel_secret.as_str()),
+                ("code_verifier", code_verifier),
+            ])
+            .send()
+            .await
+            .map_err(|e| AuthError::new(format!("Failed to send request: {}", e)))?;
+        parse_response(response).await
+    }
+
+    /// 驗證 token 回應中的 ID token 與登入時送出的 nonce
+    pub async fn verify_id_token(
+        &self,
+        id_token: &str,
+        nonce: &str,
+    ) -> Result<IdTokenClaims, AuthError> {
+        self.verifier.verify(id_token, Some(nonce)).await
+    }
+
+    pub async fn get_profile(&self, access_token: &str) -> Result<LoginProfile, AuthError> {
+        let response = self
+            .client
+            .get(format!("{}/v2/profile", self.api_base_url))
+            .timeout(REQUEST_TIMEOUT)
+            .bearer_auth(access_token)
+            .send()
+            .await
+            .map_err(|e| AuthError::new(format!("Failed to send request: {}", e)))?;
+        parse_response(response).await
+    }
+}
+
+async fn parse_response<T: serde::de::DeserializeOwned>(
+    response: reqwest::Response,
+) -> Result<T, AuthError> {
+    let status = response.status();
+    if !status.is_success() {
+        let message = response
+            .json::<ErrorResponse>()
+            .await
+            .ok()
+            .and_then(|body| body.error_description.or(body.message).or(body.error))
+            .unwrap_or_else(|| status.to_string());
+        return Err(AuthError {
+            message,
+            status_code: Some(status.as_u16()),
+        });
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| AuthError::new(format!("Invalid response: {}", e)))
+}
+
+/// 256 位元的隨機字串（base64url），可直接作為 PKCE code verifier
+pub fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes).expect("OS random number generator is unavailable");
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// PKCE `S256` code challenge：`BASE64URL(SHA256(code_verifier))`
+pub fn code_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_challenge() {
+        // RFC 7636 附錄 B 的範例
+        assert_eq!(
+            code_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+
+    #[test]
+    fn test_authorization_request() {
+        let client = LineLoginClient::new("1234567890", "secret", "https://example.com/callback");
+        let request = client.authorization_request();
+        assert_eq!(request.code_verifier.len(), 43);
+        assert_ne!(request.state, request.nonce);
+
+        let url = reqwest::Url::parse(&request.url).unwrap();
+        let params: std::collections::HashMap<_, _> = url.query_pairs().collect();
+        assert_eq!(params["client_id"], "1234567890");
+        assert_eq!(params["redirect_uri"], "https://example.com/callback");
+        assert_eq!(params["state"], request.state);
+        assert_eq!(params["scope"], "profile openid");
+        assert_eq!(
+            params["code_challenge"],
+            code_challenge(&request.code_verifier)
+        );
+        assert_eq!(params["code_challenge_method"], "S256");
+    }
+}
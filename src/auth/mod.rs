@@ -0,0 +1,15 @@
+//! LINE Login / LIFF 使用者驗證
+//!
+//! [`IdTokenVerifier`] 以 LINE 的 verify 端點驗證 LIFF 或 LINE Login 取得的 ID token，
+//! 並取得對應的 LINE 使用者 ID。[`LineLoginClient`] 實作 LINE Login 的 authorization code flow
+//! （含 PKCE），[`LineLoginFlow`] 再把登入狀態與登入後的 session 存在 storage。
+
+pub mod config;
+pub mod id_token;
+pub mod login;
+pub mod session;
+
+pub use config::*;
+pub use id_token::*;
+pub use login::*;
+pub use session::*;
@@ -0,0 +1,316 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::auth::{AuthError, AuthorizationRequest, LineLoginClient, random_token};
+use crate::storage::{KvNamespace, Storage, StorageError};
+
+/// 從轉址到 LINE 到 callback 的容許時間
+const PENDING_LOGIN_TTL_MINUTES: i64 = 10;
+
+/// 登入 session 的有效時間
+const SESSION_TTL_HOURS: i64 = 24;
+
+/// 等待 callback 的登入，以 `state` 為鍵存在 storage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PendingLogin {
+    code_verifier: String,
+    nonce: String,
+    created_at: DateTime<Utc>,
+}
+
+/// 登入後的 session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginSession {
+    pub user_id: String,
+    pub display_name: String,
+    pub picture_url: Option<String>,
+    /// 權限包含 `email` 且使用者同意時才有
+    pub email: Option<String>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 以 LINE Login 登入網頁
+///
+/// [`start`](Self::start) 產生登入網址並保存 `state`；[`finish`](Self::finish) 在 callback 時
+/// 換取 token、驗證 ID token 並取得個人資料，回傳新的 session token。
+/// `state` 只能使用一次，session 只以 token 的 SHA-256 存放。
+#[derive(Clone)]
+pub struct LineLoginFlow {
+    client: Arc<LineLoginClient>,
+    pending: KvNamespace,
+    sessions: KvNamespace,
+    success_url: String,
+}
+
+impl LineLoginFlow {
+    pub fn new(
+        client: LineLoginClient,
+        storage: Arc<dyn Storage>,
+        success_url: impl Into<String>,
+    ) -> Self {
+        Self {
+            client: Arc::new(client),
+            pending: KvNamespace::new(storage.clone(), "login_state"),
+            sessions: KvNamespace::new(storage, "login_session"),
+            success_url: success_url.into(),
+        }
+    }
+
+    pub fn client(&self) -> &LineLoginClient {
+        &self.client
+    }
+
+    /// 登入成功後導向的網址
+    pub fn success_url(&self) -> &str {
+        &self.success_url
+    }
+
+    /// 開始登入，回傳要轉址的 LINE 登入頁面
+    pub async fn start(&self) -> Result<AuthorizationRequest, AuthError> {
+        let request = self.client.authorization_request();
+        let pending = PendingLogin {
+            code_verifier: request.code_verifier.clone(),
+            nonce: request.nonce.clone(),
+            created_at: Utc::now(),
+        };
+        self.pending
+            .set(&request.state, &pending)
+            .await
+            .map_err(storage_error)?;
+        Ok(request)
+    }
+
+    /// 完成登入，回傳 session token 與 session 內容
+    pub async fn finish(
+        &self,
+        state: &str,
+        code: &str,
+    ) -> Result<(String, LoginSession), AuthError> {
+        let pending = self
+            .pending
+            .get::<PendingLogin>(state)
+            .await
+            .map_err(storage_error)?
+            .ok_or_else(|| AuthError::rejected("Unknown login state"))?;
+        if let Err(e) = self.pending.delete(state).await {
+            warn!("Failed to remove used login state: {}", e);
+        }
+        if Utc::now() - pending.created_at > Duration::minutes(PENDING_LOGIN_TTL_MINUTES) {
+            return Err(AuthError::rejected("Login state expired"));
+        }
+
+        let token = self
+            .client
+            .exchange_code(code, &pending.code_verifier)
+            .await?;
+        let claims = match &token.id_token {
+            Some(id_token) => Some(
+                self.client
+                    .verify_id_token(id_token, &pending.nonce)
+                    .await?,
+            ),
+            None => None,
+        };
+        let profile = self.client.get_profile(&token.access_token).await?;
+        if let Some(claims) = &claims
+            && claims.sub != profile.user_id
+        {
+            return Err(AuthError::rejected("ID token does not match profile"));
+        }
+
+        let session = LoginSession {
+            user_id: profile.user_id,
+            display_name: profile.display_name,
+            picture_url: profile.picture_url,
+            email: claims.and_then(|claims| claims.email),
+            expires_at: Utc::now() + Duration::hours(SESSION_TTL_HOURS),
+        };
+        let session_token = random_token();
+        self.sessions
+            .set(&session_key(&session_token), &session)
+            .await
+            .map_err(storage_error)?;
+        Ok((session_token, session))
+    }
+
+    /// 取得未過期的 session
+    pub async fn session(&self, session_token: &str) -> Result<Option<LoginSession>, AuthError> {
+        let key = session_key(session_token);
+        let Some(session) = self
+            .sessions
+            .get::<LoginSession>(&key)
+            .await
+            .map_err(storage_error)?
+        else {
+            return Ok(None);
+        };
+        if session.expires_at <= Utc::now() {
+            self.sessions.delete(&key).await.map_err(storage_error)?;
+            return Ok(None);
+        }
+        Ok(Some(session))
+    }
+
+    pub async fn logout(&self, session_token: &str) -> Result<(), AuthError> {
+        self.sessions
+            .delete(&session_key(session_token))
+            .await
+            .map_err(storage_error)
+    }
+
+    /// 刪除逾時未完成的登入與過期的 session，回傳刪除的筆數
+    pub async fn remove_expired(&self, now: DateTime<Utc>) -> Result<usize, StorageError> {
+        let pending_ttl = Duration::minutes(PENDING_LOGIN_TTL_MINUTES);
+        let removed = self
+            .pending
+            .remove_where(|pending: &PendingLogin| now - pending.created_at > pending_ttl)
+            .await?;
+        Ok(removed
+            + self
+                .sessions
+                .remove_where(|session: &LoginSession| session.expires_at <= now)
+                .await?)
+    }
+
+    /// 定期清除過期的登入狀態與 session
+    pub fn start_cleanup(&self, interval: std::time::Duration) {
+        let flow = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = flow.remove_expired(Utc::now()).await {
+                    warn!("Failed to remove expired login sessions: {}", e);
+                }
+            }
+        });
+    }
+}
+
+fn session_key(session_token: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(session_token.as_bytes()))
+}
+
+fn storage_error(e: StorageError) -> AuthError {
+    AuthError::new(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+    use axum::{
+        Form, Json, Router,
+        http::HeaderMap,
+        routing::{get, post},
+    };
+    use serde_json::json;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    const USER_ID: &str = "U1234567890abcdef1234567890abcdef";
+
+    #[tokio::test]
+    async fn test_login_flow() {
+        let verifiers = Arc::new(Mutex::new(Vec::new()));
+        let store = verifiers.clone();
+        let app = Router::new()
+            .route(
+                "/oauth2/v2.1/token",
+                post(move |Form(form): Form<HashMap<String, String>>| {
+                    assert_eq!(form["grant_type"], "authorization_code");
+                    assert_eq!(form["code"], "auth-code");
+                    assert_eq!(form["client_secret"], "secret");
+                    store.lock().unwrap().push(form["code_verifier"].clone());
+                    async {
+                        Json(json!({
+                            "access_token": "access-token",
+                            "token_type": "Bearer",
+                            "expires_in": 2592000,
+                            "refresh_token": "refresh-token",
+                            "scope": "profile openid email",
+                            "id_token": "id-token"
+                        }))
+                    }
+                }),
+            )
+            .route(
+                "/oauth2/v2.1/verify",
+                post(|Form(form): Form<HashMap<String, String>>| async move {
+                    Json(json!({
+                        "iss": "https://access.line.me",
+                        "sub": USER_ID,
+                        "aud": form["client_id"],
+                        "exp": 1504169092,
+                        "iat": 1504263657,
+                        "nonce": form["nonce"],
+                        "email": "taro@example.com"
+                    }))
+                }),
+            )
+            .route(
+                "/v2/profile",
+                get(|headers: HeaderMap| async move {
+                    assert_eq!(headers["authorization"], "Bearer access-token");
+                    Json(json!({ "userId": USER_ID, "displayName": "Taro" }))
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = LineLoginClient::new("1234567890", "secret", "https://example.com/callback")
+            .api_base_url(format!("http://{}", addr));
+        let flow = LineLoginFlow::new(client, Arc::new(MemoryStorage::new()), "/dashboard");
+
+        let request = flow.start().await.unwrap();
+        let (token, session) = flow.finish(&request.state, "auth-code").await.unwrap();
+        assert_eq!(*verifiers.lock().unwrap(), vec![request.code_verifier]);
+        assert_eq!(session.user_id, USER_ID);
+        assert_eq!(session.email.as_deref(), Some("taro@example.com"));
+        assert_eq!(flow.session(&token).await.unwrap(), Some(session));
+
+        // state 只能使用一次
+        let error = flow.finish(&request.state, "auth-code").await.unwrap_err();
+        assert!(error.is_rejected());
+
+        flow.logout(&token).await.unwrap();
+        assert_eq!(flow.session(&token).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_remove_expired() {
+        let client = LineLoginClient::new("1234567890", "secret", "https://example.com/callback");
+        let storage = Arc::new(MemoryStorage::new());
+        let flow = LineLoginFlow::new(client, storage.clone(), "/dashboard");
+
+        let abandoned = flow.start().await.unwrap();
+        let session = LoginSession {
+            user_id: USER_ID.to_string(),
+            display_name: "Taro".to_string(),
+            picture_url: None,
+            email: None,
+            expires_at: Utc::now() + Duration::hours(SESSION_TTL_HOURS),
+        };
+        flow.sessions
+            .set(&session_key("session-token"), &session)
+            .await
+            .unwrap();
+
+        assert_eq!(flow.remove_expired(Utc::now()).await.unwrap(), 0);
+        let later = Utc::now() + Duration::hours(SESSION_TTL_HOURS + 1);
+        assert_eq!(flow.remove_expired(later).await.unwrap(), 2);
+        assert!(flow.pending.keys("").await.unwrap().is_empty());
+        assert!(flow.sessions.keys("").await.unwrap().is_empty());
+        let error = flow
+            .finish(&abandoned.state, "auth-code")
+            .await
+            .unwrap_err();
+        assert!(error.is_rejected());
+    }
+}
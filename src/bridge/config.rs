@@ -0,0 +1,84 @@
+use serde::Deserialize;
+use std::fmt;
+
+/// 客服使用的聊天平台
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BridgePlatform {
+    Slack,
+    Discord,
+}
+
+impl BridgePlatform {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "slack" => Some(BridgePlatform::Slack),
+            "discord" => Some(BridgePlatform::Discord),
+            _ => None,
+        }
+    }
+
+    /// 依 incoming webhook 的主機判斷平台
+    pub fn detect(webhook_url: &str) -> Option<Self> {
+        let host = reqwest::Url::parse(webhook_url)
+            .ok()?
+            .host_str()?
+            .to_string();
+        if host == "hooks.slack.com" {
+            Some(BridgePlatform::Slack)
+        } else if host == "discord.com" || host.ends_with(".discord.com") {
+            Some(BridgePlatform::Discord)
+        } else {
+            None
+        }
+    }
+}
+
+/// 客服通知橋接設定
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct BridgeConfig {
+    pub platform: BridgePlatform,
+    /// Slack 或 Discord 的 incoming webhook URL
+    pub webhook_url: String,
+    /// 事件處理與 LINE API 錯誤
+    pub notify_errors: bool,
+    /// 新加入的好友
+    pub notify_follows: bool,
+    /// 文字訊息包含任一關鍵字時轉發（不分大小寫）
+    pub keywords: Vec<String>,
+    /// Slack app 的 signing secret，設定後接受 `/bridge/slack` 的回覆指令
+    pub slack_signing_secret: Option<String>,
+    /// Discord 應用程式的 public key（hex），設定後接受 `/bridge/discord` 的互動
+    pub discord_public_key: Option<String>,
+}
+
+impl BridgeConfig {
+    pub fn new(platform: BridgePlatform, webhook_url: impl Into<String>) -> Self {
+        Self {
+            platform,
+            webhook_url: webhook_url.into(),
+            notify_errors: true,
+            notify_follows: true,
+            keywords: Vec::new(),
+            slack_signing_secret: None,
+            discord_public_key: None,
+        }
+    }
+}
+
+impl fmt::Debug for BridgeConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BridgeConfig")
+            .field("platform", &self.platform)
+            .field("webhook_url", &"***")
+            .field("notify_errors", &self.notify_errors)
+            .field("notify_follows", &self.notify_follows)
+            .field("keywords", &self.keywords)
+            .field(
+                "slack_signing_secret",
+                &self.slack_signing_secret.as_ref().map(|_| "***"),
+            )
+            .field("discord_public_key", &self.discord_public_key)
+            .finish()
+    }
+}
@@ -0,0 +1,13 @@
+//! 客服通知橋接：把錯誤、新好友與含關鍵字的訊息轉到 Slack 或 Discord
+//!
+//! 設定簽章密鑰後，客服可以在 Slack（slash command）或 Discord（slash command）
+//! 以 push 訊息回覆使用者，路由見 `webhook::bridge`。
+//! [`BridgeConfig`] 一律可用以便從環境變數讀取設定，[`OperatorBridge`] 需啟用 `bridge` feature。
+
+pub mod config;
+#[cfg(feature = "bridge")]
+pub mod notifier;
+
+pub use config::*;
+#[cfg(feature = "bridge")]
+pub use notifier::*;
@@ -0,0 +1,207 @@
+use dashmap::DashMap;
+use serde_json::{Value, json};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tracing::warn;
+
+use crate::bridge::{BridgeConfig, BridgePlatform};
+use crate::utils::{ErrorContext, ErrorReporter};
+
+/// 單次請求的逾時
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 同一位置的錯誤在此期間內只通知一次，避免故障時洗版
+const ERROR_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// 轉發訊息內容的長度上限
+const MAX_QUOTED_LENGTH: usize = 500;
+
+/// 把事件轉成 Slack 或 Discord 訊息送到 incoming webhook
+///
+/// 通知在背景送出，失敗只記錄警告。使用者提供的文字會經過跳脫，不會觸發 @ 提及。
+#[derive(Clone)]
+pub struct OperatorBridge {
+    config: Arc<BridgeConfig>,
+    client: reqwest::Client,
+    last_errors: Arc<DashMap<String, Instant>>,
+}
+
+impl OperatorBridge {
+    pub fn new(config: BridgeConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+            client: reqwest::Client::new(),
+            last_errors: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// 使用共用的 HTTP 用戶端
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    pub fn config(&self) -> &BridgeConfig {
+        &self.config
+    }
+
+    pub fn notify_follow(&self, user_id: &str) {
+        if self.config.notify_follows {
+            self.send_in_background(format!(
+                "🆕 新好友：{}{}",
+                user_id,
+                self.reply_hint(user_id)
+            ));
+        }
+    }
+
+    /// 文字包含關鍵字時轉發，回傳是否轉發
+    pub fn notify_message(&self, user_id: &str, text: &str) -> bool {
+        let Some(keyword) = self.matched_keyword(text) else {
+            return false;
+        };
+        let quoted: String = text.chars().take(MAX_QUOTED_LENGTH).collect();
+        self.send_in_background(format!(
+            "💬 {} 的訊息包含「{}」：\n> {}{}",
+            user_id,
+            keyword,
+            self.escape(&quoted).replace('\n', "\n> "),
+            self.reply_hint(user_id)
+        ));
+        true
+    }
+
+    pub fn matched_keyword(&self, text: &str) -> Option<&str> {
+        let text = text.to_lowercase();
+        self.config
+            .keywords
+            .iter()
+            .find(|keyword| text.contains(&keyword.to_lowercase()))
+            .map(String::as_str)
+    }
+
+    /// 送出一則通知，等待送達
+    pub async fn notify(&self, text: &str) -> Result<(), reqwest::Error> {
+        self.client
+            .post(&self.config.webhook_url)
+            .timeout(REQUEST_TIMEOUT)
+            .json(&self.payload(text))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    fn send_in_background(&self, text: String) {
+        let bridge = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = bridge.notify(&text).await {
+                warn!("Failed to notify operators: {}", e);
+            }
+        });
+    }
+
+    fn payload(&self, text: &str) -> Value {
+        match self.config.platform {
+            BridgePlatform::Slack => json!({ "text": text }),
+            BridgePlatform::Discord => json!({
+                "content": text,
+                "allowed_mentions": { "parse": [] },
+            }),
+        }
+    }
+
+    /// Slack 的 `<`、`>`、`&` 有特殊意義；Discord 由 `allowed_mentions` 防止提及
+    fn escape(&self, text: &str) -> String {
+        match self.config.platform {
+            BridgePlatform::Slack => text
+                .replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;"),
+            BridgePlatform::Discord => text.to_string(),
+        }
+    }
+
+    fn reply_hint(&self, user_id: &str) -> String {
+        let can_reply = match self.config.platform {
+            BridgePlatform::Slack => self.config.slack_signing_secret.is_some(),
+            BridgePlatform::Discord => self.config.discord_public_key.is_some(),
+        };
+        if can_reply {
+            format!("\n回覆：`/line {} <訊息>`", user_id)
+        } else {
+            String::new()
+        }
+    }
+}
+
+impl ErrorReporter for OperatorBridge {
+    fn report(&self, error: &dyn std::error::Error, context: &ErrorContext) {
+        if !self.config.notify_errors || tokio::runtime::Handle::try_current().is_err() {
+            return;
+        }
+        let key = format!("{}:{}", context.source, context.operation);
+        let now = Instant::now();
+        if self
+            .last_errors
+            .get(&key)
+            .is_some_and(|last| now.duration_since(*last) < ERROR_COOLDOWN)
+        {
+            return;
+        }
+        self.last_errors.insert(key.clone(), now);
+        self.send_in_background(format!("⚠️ [{}] {}", key, self.escape(&error.to_string())));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Json, Router, routing::post};
+    use std::sync::Mutex;
+
+    #[tokio::test]
+    async fn test_notifications() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let store = received.clone();
+        let app = Router::new().route(
+            "/hook",
+            post(move |Json(body): Json<Value>| {
+                store.lock().unwrap().push(body);
+                async {}
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let bridge = OperatorBridge::new(BridgeConfig {
+            keywords: vec!["退款".to_string(), "Refund".to_string()],
+            slack_signing_secret: Some("secret".to_string()),
+            ..BridgeConfig::new(BridgePlatform::Slack, format!("http://{}/hook", addr))
+        });
+        assert!(!bridge.notify_message("U1", "你好"));
+        assert!(bridge.notify_message("U1", "我要 REFUND <@here>"));
+        for _ in 0..2 {
+            bridge.report(
+                &std::io::Error::other("timeout"),
+                &ErrorContext::line_api("push"),
+            );
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        let texts: Vec<&str> = received
+            .iter()
+            .map(|b| b["text"].as_str().unwrap())
+            .collect();
+        let message = texts.iter().find(|t| t.starts_with("💬")).unwrap();
+        assert!(message.contains("「Refund」"));
+        assert!(message.contains("&lt;@here&gt;"));
+        assert!(message.contains("/line U1 <訊息>"));
+        assert!(texts.contains(&"⚠️ [line_api:push] timeout"));
+    }
+}
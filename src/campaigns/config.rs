@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::models::OutgoingMessage;
+use crate::utils::message_limits;
+
+/// 錯過排程時，在此秒數內仍會補送，不受 [`MisfirePolicy`] 影響
+const DEFAULT_MISFIRE_GRACE_SECS: u64 = 3600;
+
+/// 活動的推播對象
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CampaignAudience {
+    /// 所有加入好友的使用者
+    Broadcast,
+    /// 指定的使用者 ID
+    Users(Vec<String>),
+}
+
+/// 錯過排程超過寬限時間時的處置
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MisfirePolicy {
+    /// 補送一次，錯過多次也只送一次
+    #[default]
+    FireOnce,
+    /// 略過，等下一次排程
+    Skip,
+}
+
+/// 排程推播活動
+///
+/// ```json
+/// { "id": "morning", "schedule": "0 9 * * 1-5", "utc_offset": "+08:00",
+///   "audience": { "users": ["U123"] }, "messages": [{ "type": "text", "text": "早安" }] }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Campaign {
+    /// 透過管理 API 建立時由路徑指定
+    #[serde(default)]
+    pub id: String,
+    /// cron 表示式：五欄（分 時 日 月 週）或含秒的六、七欄
+    pub schedule: String,
+    /// 解讀排程使用的時區位移，預設 `+00:00`
+    #[serde(default)]
+    pub utc_offset: Option<String>,
+    pub audience: CampaignAudience,
+    pub messages: Vec<OutgoingMessage>,
+    #[serde(default)]
+    pub misfire: MisfirePolicy,
+    #[serde(default = "default_misfire_grace_secs")]
+    pub misfire_grace_secs: u64,
+}
+
+fn default_misfire_grace_secs() -> u64 {
+    DEFAULT_MISFIRE_GRACE_SECS
+}
+
+impl Campaign {
+    /// 檢查 ID、訊息數量與推播對象；排程格式由 `CampaignScheduler` 檢查
+    pub fn validate(&self) -> Result<(), String> {
+        if self.id.trim().is_empty() {
+            return Err("campaign id must not be empty".to_string());
+        }
+        if self.messages.is_empty() || self.messages.len() > message_limits::MAX_MESSAGES {
+            return Err(format!(
+                "campaign {} must have 1 to {} messages",
+                self.id,
+                message_limits::MAX_MESSAGES
+            ));
+        }
+        if let CampaignAudience::Users(user_ids) = &self.audience
+            && user_ids.is_empty()
+        {
+            return Err(format!("campaign {} has no users", self.id));
+        }
+        Ok(())
+    }
+}
+
+/// 讀取 JSON 陣列格式的活動定義檔
+pub fn load_campaigns(path: &str) -> Result<Vec<Campaign>, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let campaigns: Vec<Campaign> = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+
+    let mut ids = HashSet::new();
+    for campaign in &campaigns {
+        campaign.validate()?;
+        if !ids.insert(campaign.id.as_str()) {
+            return Err(format!("duplicate campaign id {}", campaign.id));
+        }
+    }
+    Ok(campaigns)
+}
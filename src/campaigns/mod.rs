@@ -0,0 +1,13 @@
+//! 排程推播活動
+//!
+//! 活動以 cron 表示式排程，定義來自 `CAMPAIGNS_FILE` 或管理 API。每次執行的時間記錄在 storage，
+//! 重新啟動後依 [`MisfirePolicy`] 補送或略過停機期間錯過的排程。
+//! [`Campaign`] 一律可用以便從設定檔讀取，[`CampaignScheduler`] 需啟用 `campaigns` feature。
+
+pub mod config;
+#[cfg(feature = "campaigns")]
+pub mod scheduler;
+
+pub use config::*;
+#[cfg(feature = "campaigns")]
+pub use scheduler::*;
@@ -0,0 +1,439 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use cron::Schedule;
+use serde::Serialize;
+use std::{collections::HashMap, error::Error, fmt, str::FromStr, sync::Arc, time::Duration};
+use tracing::{error, info, warn};
+
+use crate::campaigns::{Campaign, CampaignAudience, MisfirePolicy};
+use crate::line_api::{LineApiClient, LineApiError, SendOptions};
+use crate::storage::{KvNamespace, Storage, StorageError};
+use crate::utils::{message_limits, name_based_uuid};
+
+/// 保存管理 API 建立的活動與執行紀錄的命名空間
+const CAMPAIGNS_NAMESPACE: &str = "campaigns";
+
+const DEFINITIONS_KEY: &str = "definitions";
+
+#[derive(Debug)]
+pub enum CampaignError {
+    /// 活動定義不正確
+    Invalid(String),
+    /// 活動定義在設定檔中，無法透過管理 API 修改
+    ReadOnly(String),
+    Storage(StorageError),
+}
+
+impl fmt::Display for CampaignError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CampaignError::Invalid(message) => write!(f, "Invalid campaign: {}", message),
+            CampaignError::ReadOnly(id) => {
+                write!(f, "Campaign {} is defined in CAMPAIGNS_FILE", id)
+            }
+            CampaignError::Storage(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for CampaignError {}
+
+impl From<StorageError> for CampaignError {
+    fn from(e: StorageError) -> Self {
+        CampaignError::Storage(e)
+    }
+}
+
+/// 活動定義的來源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CampaignSource {
+    Config,
+    Api,
+}
+
+/// 管理 API 列出的活動狀態
+#[derive(Debug, Clone, Serialize)]
+pub struct CampaignStatus {
+    #[serde(flatten)]
+    pub campaign: Campaign,
+    pub source: CampaignSource,
+    pub last_run: Option<DateTime<Utc>>,
+    pub next_run: Option<DateTime<Utc>>,
+}
+
+/// 依 cron 排程推播活動
+///
+/// 每次檢查時從上次執行時間往後找出已到期的排程，錯過多次時只送一次。
+/// 最近一次到期時間超過 `misfire_grace_secs` 時依 [`MisfirePolicy`] 補送或略過。
+/// 連不上 LINE 時不記錄為已執行，下次檢查再送；重送使用相同的 retry key，不會重複推播。
+#[derive(Clone)]
+pub struct CampaignScheduler {
+    client: LineApiClient,
+    kv: KvNamespace,
+    configured: Arc<[Campaign]>,
+}
+
+impl CampaignScheduler {
+    /// 建立排程器，設定檔中的活動格式錯誤時回傳錯誤
+    pub fn new(
+        client: LineApiClient,
+        storage: Arc<dyn Storage>,
+        campaigns: Vec<Campaign>,
+    ) -> Result<Self, CampaignError> {
+        for campaign in &campaigns {
+            validate(campaign)?;
+        }
+        Ok(Self {
+            client,
+            kv: KvNamespace::new(storage, CAMPAIGNS_NAMESPACE),
+            configured: campaigns.into(),
+        })
+    }
+
+    /// 設定檔與管理 API 的所有活動
+    pub async fn campaigns(&self) -> Result<Vec<CampaignStatus>, StorageError> {
+        let mut statuses = Vec::new();
+        let configured = self
+            .configured
+            .iter()
+            .cloned()
+            .map(|campaign| (campaign, CampaignSource::Config));
+        let api = self
+            .api_campaigns()
+            .await?
+            .into_values()
+            .map(|campaign| (campaign, CampaignSource::Api));
+        for (campaign, source) in configured.chain(api) {
+            let last_run = self.last_run(&campaign.id).await?;
+            let next_run = parse_schedule(&campaign)
+                .ok()
+                .and_then(|(schedule, offset)| {
+                    let after = last_run.unwrap_or_else(Utc::now).with_timezone(&offset);
+                    schedule
+                        .after(&after)
+                        .next()
+                        .map(|next| next.with_timezone(&Utc))
+                });
+            statuses.push(CampaignStatus {
+                campaign,
+                source,
+                last_run,
+                next_run,
+            });
+        }
+        Ok(statuses)
+    }
+
+    /// 新增或更新管理 API 的活動，新增時回傳 `true`；新活動從現在開始排程
+    pub async fn upsert(&self, campaign: Campaign) -> Result<bool, CampaignError> {
+        if self.is_configured(&campaign.id) {
+            return Err(CampaignError::ReadOnly(campaign.id));
+        }
+        validate(&campaign)?;
+
+        let mut campaigns = self.api_campaigns().await?;
+        let id = campaign.id.clone();
+        let created = campaigns.insert(id.clone(), campaign).is_none();
+        self.kv.set(DEFINITIONS_KEY, &campaigns).await?;
+        if created {
+            self.kv.set(&last_run_key(&id), &Utc::now()).await?;
+        }
+        Ok(created)
+    }
+
+    /// 刪除管理 API 的活動，不存在時回傳 `false`
+    pub async fn remove(&self, id: &str) -> Result<bool, CampaignError> {
+        if self.is_configured(id) {
+            return Err(CampaignError::ReadOnly(id.to_string()));
+        }
+        let mut campaigns = self.api_campaigns().await?;
+        if campaigns.remove(id).is_none() {
+            return Ok(false);
+        }
+        self.kv.set(DEFINITIONS_KEY, &campaigns).await?;
+        self.kv.delete(&last_run_key(id)).await?;
+        Ok(true)
+    }
+
+    /// 啟動背景工作，每隔 `interval` 檢查一次；啟動時立即檢查停機期間錯過的排程
+    pub fn start(&self, interval: Duration) {
+        let scheduler = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                scheduler.run_due(Utc::now()).await;
+            }
+        });
+    }
+
+    /// 執行 `now` 之前到期的活動
+    pub async fn run_due(&self, now: DateTime<Utc>) {
+        let api = match self.api_campaigns().await {
+            Ok(campaigns) => campaigns.into_values().collect(),
+            Err(e) => {
+                error!("Failed to load campaigns: {}", e);
+                Vec::new()
+            }
+        };
+        for campaign in self.configured.iter().chain(api.iter()) {
+            if let Err(e) = self.run_campaign(campaign, now).await {
+                error!("Failed to run campaign {}: {}", campaign.id, e);
+            }
+        }
+    }
+
+    async fn run_campaign(
+        &self,
+        campaign: &Campaign,
+        now: DateTime<Utc>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let key = last_run_key(&campaign.id);
+        let Some(last_run) = self.kv.get::<DateTime<Utc>>(&key).await? else {
+            // 第一次看到的活動從現在開始排程
+            self.kv.set(&key, &now).await?;
+            return Ok(());
+        };
+
+        let (schedule, offset) = parse_schedule(campaign)?;
+        let (missed, due) = schedule
+            .after(&last_run.with_timezone(&offset))
+            .map(|time| time.with_timezone(&Utc))
+            .take_while(|&time| time <= now)
+            .fold((0usize, None), |(count, _), time| (count + 1, Some(time)));
+        let Some(due) = due else {
+            return Ok(());
+        };
+
+        let late = (now - due).num_seconds().max(0) as u64;
+        if late > campaign.misfire_grace_secs && campaign.misfire == MisfirePolicy::Skip {
+            warn!(
+                "Skipping campaign {}: missed {} runs, latest at {}",
+                campaign.id, missed, due
+            );
+        } else {
+            if missed > 1 {
+                warn!(
+                    "Campaign {} missed {} runs, sending once for {}",
+                    campaign.id, missed, due
+                );
+            }
+            match self.deliver(campaign, due).await {
+                Ok(()) => info!("Sent campaign {} scheduled at {}", campaign.id, due),
+                // 下次檢查時以相同的 retry key 重送
+                Err(e) if e.network_error => return Err(e.into()),
+                Err(e) => error!("Campaign {} was rejected: {}", campaign.id, e),
+            }
+        }
+        self.kv.set(&key, &due).await?;
+        Ok(())
+    }
+
+    async fn deliver(&self, campaign: &Campaign, due: DateTime<Utc>) -> Result<(), LineApiError> {
+        let options =
+            |chunk: usize| SendOptions::new().retry_key(retry_key(&campaign.id, due, chunk));
+        match &campaign.audience {
+            CampaignAudience::Broadcast => self
+                .client
+                .broadcast_message_with_options(campaign.messages.clone(), &options(0))
+                .await
+                .map(|_| ()),
+            CampaignAudience::Users(user_ids) => {
+                for (index, to) in user_ids
+                    .chunks(message_limits::MAX_MULTICAST_RECIPIENTS)
+                    .enumerate()
+                {
+                    self.client
+                        .multicast_message_with_options(
+                            to.to_vec(),
+                            campaign.messages.clone(),
+                            &options(index),
+                        )
+                        .await?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    async fn api_campaigns(&self) -> Result<HashMap<String, Campaign>, StorageError> {
+        Ok(self.kv.get(DEFINITIONS_KEY).await?.unwrap_or_default())
+    }
+
+    async fn last_run(&self, id: &str) -> Result<Option<DateTime<Utc>>, StorageError> {
+        self.kv.get(&last_run_key(id)).await
+    }
+
+    fn is_configured(&self, id: &str) -> bool {
+        self.configured.iter().any(|campaign| campaign.id == id)
+    }
+}
+
+fn last_run_key(id: &str) -> String {
+    format!("last_run:{}", id)
+}
+
+fn validate(campaign: &Campaign) -> Result<(), CampaignError> {
+    campaign.validate().map_err(CampaignError::Invalid)?;
+    parse_schedule(campaign)?;
+    Ok(())
+}
+
+/// 解析排程與時區；五欄的表示式補上秒數欄位
+fn parse_schedule(campaign: &Campaign) -> Result<(Schedule, FixedOffset), CampaignError> {
+    let expression = campaign.schedule.trim();
+    let expression = if expression.split_whitespace().count() == 5 {
+        format!("0 {}", expression)
+    } else {
+        expression.to_string()
+    };
+    let schedule = Schedule::from_str(&expression).map_err(|e| {
+        CampaignError::Invalid(format!(
+            "campaign {} has invalid schedule {}: {}",
+            campaign.id, campaign.schedule, e
+        ))
+    })?;
+    let offset = match &campaign.utc_offset {
+        Some(offset) => offset.parse::<FixedOffset>().map_err(|_| {
+            CampaignError::Invalid(format!(
+                "campaign {} has invalid utc_offset {}",
+                campaign.id, offset
+            ))
+        })?,
+        None => FixedOffset::east_opt(0).expect("zero offset is valid"),
+    };
+    Ok((schedule, offset))
+}
+
+/// 由活動與排程時間產生固定的 UUID 格式 retry key
+fn retry_key(id: &str, due: DateTime<Utc>, chunk: usize) -> String {
+    name_based_uuid(&format!("{}:{}:{}", id, due.timestamp(), chunk))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::OutgoingMessage;
+    use crate::storage::MemoryStorage;
+    use axum::{Json, Router, http::HeaderMap, routing::post};
+    use chrono::TimeZone;
+    use serde_json::{Value, json};
+    use std::sync::Mutex;
+
+    /// 收到的 retry key 與請求內容
+    type Received = Arc<Mutex<Vec<(Option<String>, Value)>>>;
+
+    fn campaign(misfire: MisfirePolicy) -> Campaign {
+        Campaign {
+            id: "morning".to_string(),
+            schedule: "0 9 * * *".to_string(),
+            utc_offset: Some("+08:00".to_string()),
+            audience: CampaignAudience::Users(vec!["U1".to_string()]),
+            messages: vec![OutgoingMessage::text("早安")],
+            misfire,
+            misfire_grace_secs: 3600,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_due_with_misfire() {
+        let received: Received = Arc::default();
+        let store = received.clone();
+        let app = Router::new().route(
+            "/v2/bot/message/multicast",
+            post(move |headers: HeaderMap, Json(body): Json<Value>| {
+                let retry_key = headers
+                    .get("x-line-retry-key")
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::to_string);
+                store.lock().unwrap().push((retry_key, body));
+                async { Json(json!({})) }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = LineApiClient::builder("test_token")
+            .base_url(format!("http://{}/v2/bot", addr))
+            .build()
+            .unwrap();
+        let scheduler = CampaignScheduler::new(
+            client,
+            Arc::new(MemoryStorage::new()),
+            vec![campaign(MisfirePolicy::FireOnce)],
+        )
+        .unwrap();
+        assert!(matches!(
+            scheduler.upsert(campaign(MisfirePolicy::Skip)).await,
+            Err(CampaignError::ReadOnly(_))
+        ));
+
+        // 09:00 (+08:00) = 01:00 UTC；第一次檢查只記錄開始時間
+        let day = |d: u32, h: u32, m: u32| Utc.with_ymd_and_hms(2024, 5, d, h, m, 0).unwrap();
+        scheduler.run_due(day(1, 0, 0)).await;
+        scheduler.run_due(day(1, 0, 59)).await;
+        assert!(received.lock().unwrap().is_empty());
+
+        scheduler.run_due(day(1, 1, 0)).await;
+        scheduler.run_due(day(1, 1, 30)).await;
+        // 停機三天後補送一次
+        scheduler.run_due(day(4, 5, 0)).await;
+
+        let received = received.lock().unwrap().clone();
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[0].1["to"], json!(["U1"]));
+        assert_eq!(received[0].1["messages"][0]["text"], "早安");
+        let key = received[0].0.as_deref().unwrap();
+        assert_eq!(key.len(), 36);
+        assert_eq!(&key[14..15], "5");
+        assert_ne!(received[0].0, received[1].0);
+
+        let statuses = scheduler.campaigns().await.unwrap();
+        assert_eq!(statuses[0].last_run, Some(day(4, 1, 0)));
+        assert_eq!(statuses[0].next_run, Some(day(5, 1, 0)));
+    }
+
+    #[tokio::test]
+    async fn test_api_campaign_skips_stale_misfire() {
+        let client = LineApiClient::builder("test_token")
+            .dry_run(true)
+            .build()
+            .unwrap();
+        let scheduler =
+            CampaignScheduler::new(client, Arc::new(MemoryStorage::new()), Vec::new()).unwrap();
+
+        let mut invalid = campaign(MisfirePolicy::Skip);
+        invalid.schedule = "every day".to_string();
+        assert!(matches!(
+            scheduler.upsert(invalid).await,
+            Err(CampaignError::Invalid(_))
+        ));
+
+        assert!(
+            scheduler
+                .upsert(campaign(MisfirePolicy::Skip))
+                .await
+                .unwrap()
+        );
+        assert!(
+            !scheduler
+                .upsert(campaign(MisfirePolicy::Skip))
+                .await
+                .unwrap()
+        );
+        let created = scheduler.campaigns().await.unwrap()[0].last_run.unwrap();
+
+        // 錯過超過寬限時間的排程直接略過，但仍推進執行時間
+        let later = created + chrono::Duration::days(2) + chrono::Duration::hours(2);
+        scheduler.run_due(later).await;
+        let status = &scheduler.campaigns().await.unwrap()[0];
+        assert_eq!(status.source, CampaignSource::Api);
+        assert!(status.last_run.unwrap() > created);
+        assert!(status.last_run.unwrap() <= later);
+
+        assert!(scheduler.remove("morning").await.unwrap());
+        assert!(!scheduler.remove("morning").await.unwrap());
+        assert!(scheduler.campaigns().await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,40 @@
+use serde::Deserialize;
+
+/// 新項目的推播對象
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedDelivery {
+    /// 以「訂閱」指令訂閱的使用者
+    #[default]
+    Subscribers,
+    /// 所有加入好友的使用者
+    Broadcast,
+}
+
+/// 要輪詢的 feed
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct FeedSource {
+    pub url: String,
+    pub delivery: FeedDelivery,
+}
+
+impl FeedSource {
+    /// 解析 `url` 或 `url|broadcast` 格式
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (url, delivery) = match spec.split_once('|') {
+            Some((url, "broadcast")) => (url, FeedDelivery::Broadcast),
+            Some((url, "subscribers")) => (url, FeedDelivery::Subscribers),
+            Some(_) => return None,
+            None => (spec, FeedDelivery::Subscribers),
+        };
+
+        let url = url.trim();
+        if url.is_empty() {
+            return None;
+        }
+        Some(Self {
+            url: url.to_string(),
+            delivery,
+        })
+    }
+}
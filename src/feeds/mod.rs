@@ -0,0 +1,16 @@
+//! RSS/Atom 訂閱推播
+//!
+//! 定期讀取設定的 feed，把新項目以 Flex 卡片推播給訂閱者或所有好友；
+//! 已送出的項目記錄在 storage，重新啟動後不會重複推播。
+
+pub mod config;
+#[cfg(feature = "feeds")]
+pub mod parser;
+#[cfg(feature = "feeds")]
+pub mod scheduler;
+
+pub use config::*;
+#[cfg(feature = "feeds")]
+pub use parser::*;
+#[cfg(feature = "feeds")]
+pub use scheduler::*;
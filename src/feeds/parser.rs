@@ -0,0 +1,218 @@
+use roxmltree::{Document, Node};
+use std::{error::Error, fmt};
+
+/// 摘要保留的字元數
+const MAX_SUMMARY_LENGTH: usize = 100;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Feed {
+    pub title: String,
+    /// 依 feed 中的順序，通常為新到舊
+    pub entries: Vec<FeedEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedEntry {
+    /// 去重用的識別碼：RSS 的 `guid`、Atom 的 `id`，沒有時以連結或標題代替
+    pub id: String,
+    pub title: String,
+    pub link: Option<String>,
+    /// 去除 HTML 標籤後的摘要
+    pub summary: Option<String>,
+    pub image_url: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct FeedError {
+    pub message: String,
+}
+
+impl fmt::Display for FeedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Feed error: {}", self.message)
+    }
+}
+
+impl Error for FeedError {}
+
+/// 解析 RSS 2.0、RSS 1.0（RDF）或 Atom
+pub fn parse_feed(xml: &str) -> Result<Feed, FeedError> {
+    let document = Document::parse(xml).map_err(|e| FeedError {
+        message: format!("Invalid XML: {}", e),
+    })?;
+    let root = document.root_element();
+    match root.tag_name().name() {
+        "rss" | "RDF" => Ok(parse_rss(root)),
+        "feed" => Ok(parse_atom(root)),
+        other => Err(FeedError {
+            message: format!("Unsupported feed format: <{}>", other),
+        }),
+    }
+}
+
+fn parse_rss(root: Node) -> Feed {
+    let title = root
+        .descendants()
+        .find(|node| node.has_tag_name("channel"))
+        .and_then(|channel| child_text(channel, "title"))
+        .unwrap_or_default();
+    let entries = root
+        .descendants()
+        .filter(|node| node.has_tag_name("item"))
+        .filter_map(|item| {
+            let title = child_text(item, "title").unwrap_or_default();
+            let link = child_text(item, "link");
+            let id = child_text(item, "guid")
+                .or_else(|| link.clone())
+                .or_else(|| (!title.is_empty()).then(|| title.clone()))?;
+            let image_url = item
+                .children()
+                .find(|node| {
+                    (node.has_tag_name("enclosure")
+                        && node
+                            .attribute("type")
+                            .is_some_and(|t| t.starts_with("image/")))
+                        || node.has_tag_name("thumbnail")
+                })
+                .and_then(|node| node.attribute("url"))
+                .map(str::to_string);
+            Some(FeedEntry {
+                id,
+                title,
+                link,
+                summary: child_text(item, "description").and_then(|html| summarize(&html)),
+                image_url,
+            })
+        })
+        .collect();
+    Feed { title, entries }
+}
+
+fn parse_atom(root: Node) -> Feed {
+    let title = child_text(root, "title").unwrap_or_default();
+    let entries = root
+        .children()
+        .filter(|node| node.has_tag_name("entry"))
+        .filter_map(|entry| {
+            let title = child_text(entry, "title").unwrap_or_default();
+            let link = entry
+                .children()
+                .find(|node| {
+                    node.has_tag_name("link")
+                        && node.attribute("rel").is_none_or(|rel| rel == "alternate")
+                })
+                .and_then(|node| node.attribute("href"))
+                .map(str::to_string);
+            let id = child_text(entry, "id").or_else(|| link.clone())?;
+            let summary = child_text(entry, "summary")
+                .or_else(|| child_text(entry, "content"))
+                .and_then(|html| summarize(&html));
+            let image_url = entry
+                .descendants()
+                .find(|node| node.has_tag_name("thumbnail"))
+                .and_then(|node| node.attribute("url"))
+                .map(str::to_string);
+            Some(FeedEntry {
+                id,
+                title,
+                link,
+                summary,
+                image_url,
+            })
+        })
+        .collect();
+    Feed { title, entries }
+}
+
+/// 子元素的文字（含 CDATA），忽略命名空間
+fn child_text(node: Node, name: &str) -> Option<String> {
+    let child = node.children().find(|child| child.has_tag_name(name))?;
+    let text: String = child
+        .descendants()
+        .filter(|node| node.is_text())
+        .filter_map(|node| node.text())
+        .collect();
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// 去除 HTML 標籤、合併空白並截斷
+fn summarize(html: &str) -> Option<String> {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        return None;
+    }
+    if text.chars().count() <= MAX_SUMMARY_LENGTH {
+        return Some(text);
+    }
+    let mut truncated: String = text.chars().take(MAX_SUMMARY_LENGTH).collect();
+    truncated.push('…');
+    Some(truncated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rss_and_atom() {
+        let rss = r#"<?xml version="1.0"?>
+            <rss version="2.0"><channel>
+              <title>官方公告</title>
+              <item>
+                <title>系統維護</title>
+                <link>https://example.com/news/2</link>
+                <guid>news-2</guid>
+                <description><![CDATA[<p>本週六 <b>02:00</b> 維護</p>]]></description>
+                <enclosure url="https://example.com/2.jpg" type="image/jpeg" length="0"/>
+              </item>
+              <item><title>新功能</title><link>https://example.com/news/1</link></item>
+            </channel></rss>"#;
+        let feed = parse_feed(rss).unwrap();
+        assert_eq!(feed.title, "官方公告");
+        assert_eq!(feed.entries.len(), 2);
+        assert_eq!(feed.entries[0].id, "news-2");
+        assert_eq!(
+            feed.entries[0].summary.as_deref(),
+            Some("本週六 02:00 維護")
+        );
+        assert_eq!(
+            feed.entries[0].image_url.as_deref(),
+            Some("https://example.com/2.jpg")
+        );
+        assert_eq!(feed.entries[1].id, "https://example.com/news/1");
+
+        let atom = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+              <title>Blog</title>
+              <entry>
+                <id>tag:example.com,2024:1</id>
+                <title>Hello</title>
+                <link rel="self" href="https://example.com/api/1"/>
+                <link href="https://example.com/1"/>
+                <summary>First post</summary>
+              </entry>
+            </feed>"#;
+        let feed = parse_feed(atom).unwrap();
+        assert_eq!(feed.title, "Blog");
+        assert_eq!(
+            feed.entries[0].link.as_deref(),
+            Some("https://example.com/1")
+        );
+        assert_eq!(feed.entries[0].summary.as_deref(), Some("First post"));
+
+        assert!(parse_feed("<html></html>").is_err());
+    }
+}
@@ -0,0 +1,447 @@
+use async_trait::async_trait;
+use serde_json::json;
+use std::{sync::Arc, time::Duration};
+use tracing::{error, info, warn};
+
+use crate::feeds::{FeedDelivery, FeedEntry, FeedSource, parse_feed};
+use crate::line_api::{LineApiClient, LineApiError, SendOptions};
+use crate::models::OutgoingMessage;
+use crate::storage::{KvNamespace, Storage, StorageError, UserDataCleanup};
+use crate::utils::{message_limits, name_based_uuid};
+
+/// 單次請求的逾時
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 保存訂閱者與已送出項目的命名空間
+const FEEDS_NAMESPACE: &str = "feeds";
+
+/// 每位訂閱者一個鍵，避免同時訂閱時互相覆寫
+const SUBSCRIBER_PREFIX: &str = "subscriber:";
+
+/// 舊版以單一清單保存的訂閱者，讀取時轉為每位訂閱者一個鍵
+const LEGACY_SUBSCRIBERS_KEY: &str = "subscribers";
+
+/// 每個 feed 至少保留的已送出項目數；feed 一次列出更多項目時以 feed 的項目數為準
+const MAX_SEEN_ENTRIES: usize = 500;
+
+/// 定期輪詢 feed 並推播新項目
+///
+/// 第一次讀到某個 feed 時只記錄現有項目，不推播，避免一次送出整份歷史。
+/// 連不上 LINE 時不記錄為已送出，下次輪詢再送；其他錯誤（例如訊息格式被拒）則略過該批項目。
+#[derive(Clone)]
+pub struct FeedScheduler {
+    client: LineApiClient,
+    kv: KvNamespace,
+    sources: Arc<[FeedSource]>,
+    http: reqwest::Client,
+}
+
+impl FeedScheduler {
+    pub fn new(client: LineApiClient, storage: Arc<dyn Storage>, sources: Vec<FeedSource>) -> Self {
+        Self {
+            client,
+            kv: KvNamespace::new(storage, FEEDS_NAMESPACE),
+            sources: sources.into(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// 使用共用的 HTTP 用戶端
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.http = client;
+        self
+    }
+
+    pub async fn subscribers(&self) -> Result<Vec<String>, StorageError> {
+        self.migrate_legacy_subscribers().await?;
+        Ok(self
+            .kv
+            .keys(SUBSCRIBER_PREFIX)
+            .await?
+            .into_iter()
+            .map(|key| key[SUBSCRIBER_PREFIX.len()..].to_string())
+            .collect())
+    }
+
+    /// 加入訂閱者，已訂閱時回傳 `false`
+    pub async fn subscribe(&self, user_id: &str) -> Result<bool, StorageError> {
+        self.migrate_legacy_subscribers().await?;
+        let key = subscriber_key(user_id);
+        if self.kv.get::<bool>(&key).await?.is_some() {
+            return Ok(false);
+        }
+        self.kv.set(&key, &true).await?;
+        Ok(true)
+    }
+
+    /// 移除訂閱者，原本未訂閱時回傳 `false`
+    pub async fn unsubscribe(&self, user_id: &str) -> Result<bool, StorageError> {
+        self.migrate_legacy_subscribers().await?;
+        let key = subscriber_key(user_id);
+        if self.kv.get::<bool>(&key).await?.is_none() {
+            return Ok(false);
+        }
+        self.kv.delete(&key).await?;
+        Ok(true)
+    }
+
+    async fn migrate_legacy_subscribers(&self) -> Result<(), StorageError> {
+        if let Some(legacy) = self.kv.get::<Vec<String>>(LEGACY_SUBSCRIBERS_KEY).await? {
+            for user_id in &legacy {
+                self.kv.set(&subscriber_key(user_id), &true).await?;
+            }
+            self.kv.delete(LEGACY_SUBSCRIBERS_KEY).await?;
+        }
+        Ok(())
+    }
+
+    /// 啟動背景工作，每隔 `interval` 輪詢所有 feed
+    pub fn start(&self, interval: Duration) {
+        let scheduler = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                scheduler.poll().await;
+            }
+        });
+    }
+
+    /// 輪詢所有 feed 一次
+    pub async fn poll(&self) {
+        for source in self.sources.iter() {
+            match self.poll_source(source).await {
+                Ok(0) => {}
+                Ok(count) => info!("Sent {} new entries from feed {}", count, source.url),
+                Err(e) => error!("Failed to poll feed {}: {}", source.url, e),
+            }
+        }
+    }
+
+    async fn poll_source(
+        &self,
+        source: &FeedSource,
+    ) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let xml = self
+            .http
+            .get(&source.url)
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        let feed = parse_feed(&xml)?;
+
+        let seen_key = format!("seen:{}", source.url);
+        let Some(seen) = self.kv.get::<Vec<String>>(&seen_key).await? else {
+            let ids: Vec<&str> = feed.entries.iter().map(|entry| entry.id.as_str()).collect();
+            self.kv.set(&seen_key, &ids).await?;
+            info!(
+                "Started tracking feed {} with {} existing entries",
+                source.url,
+                ids.len()
+            );
+            return Ok(0);
+        };
+
+        // feed 通常由新到舊排列，推播時改為由舊到新
+        let new_entries: Vec<&FeedEntry> = feed
+            .entries
+            .iter()
+            .filter(|entry| !seen.contains(&entry.id))
+            .rev()
+            .collect();
+        if new_entries.is_empty() {
+            return Ok(0);
+        }
+
+        let cards: Vec<OutgoingMessage> = new_entries
+            .iter()
+            .map(|entry| entry_card(&feed.title, entry))
+            .collect();
+        // 同一批新項目重送時使用相同的 retry key
+        let retry_base = format!("feed:{}:{}", source.url, new_entries[0].id);
+        match self.deliver(source.delivery, cards, &retry_base).await {
+            Err(e) if e.network_error => return Err(e.into()),
+            Err(e) => warn!(
+                "Skipping {} entries from feed {}: {}",
+                new_entries.len(),
+                source.url,
+                e
+            ),
+            Ok(()) => {}
+        }
+
+        // feed 目前列出的項目一律保留，較舊的已送出項目補到上限為止
+        let mut updated: Vec<String> = feed.entries.iter().map(|entry| entry.id.clone()).collect();
+        let limit = MAX_SEEN_ENTRIES.max(updated.len());
+        let older: Vec<String> = seen
+            .into_iter()
+            .filter(|id| !updated.contains(id))
+            .take(limit - updated.len())
+            .collect();
+        updated.extend(older);
+        self.kv.set(&seen_key, &updated).await?;
+        Ok(new_entries.len())
+    }
+
+    async fn deliver(
+        &self,
+        delivery: FeedDelivery,
+        cards: Vec<OutgoingMessage>,
+        retry_base: &str,
+    ) -> Result<(), LineApiError> {
+        let recipients = match delivery {
+            FeedDelivery::Broadcast => None,
+            FeedDelivery::Subscribers => {
+                let subscribers = self.subscribers().await.map_err(|e| LineApiError {
+                    message: format!("Failed to load subscribers: {}", e),
+                    status_code: None,
+                    network_error: true,
+                })?;
+                if subscribers.is_empty() {
+                    return Ok(());
+                }
+                Some(subscribers)
+            }
+        };
+
+        for (batch_index, batch) in cards.chunks(message_limits::MAX_MESSAGES).enumerate() {
+            match &recipients {
+                None => {
+                    let retry_key = name_based_uuid(&format!("{}:{}", retry_base, batch_index));
+                    self.client
+                        .broadcast_message_with_options(
+                            batch.to_vec(),
+                            &SendOptions::new().retry_key(retry_key),
+                        )
+                        .await?;
+                }
+                Some(recipients) => {
+                    for (chunk, to) in recipients
+                        .chunks(message_limits::MAX_MULTICAST_RECIPIENTS)
+                        .enumerate()
+                    {
+                        let retry_key =
+                            name_based_uuid(&format!("{}:{}:{}", retry_base, batch_index, chunk));
+                        self.client
+                            .multicast_message_with_options(
+                                to.to_vec(),
+                                batch.to_vec(),
+                                &SendOptions::new().retry_key(retry_key),
+                            )
+                            .await?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl UserDataCleanup for FeedScheduler {
+    async fn purge_user(&self, user_id: &str) -> Result<(), StorageError> {
+        self.unsubscribe(user_id).await.map(|_| ())
+    }
+}
+
+fn subscriber_key(user_id: &str) -> String {
+    format!("{}{}", SUBSCRIBER_PREFIX, user_id)
+}
+
+/// 項目的 Flex 卡片；只有 https 的圖片與連結會放進卡片
+pub fn entry_card(feed_title: &str, entry: &FeedEntry) -> OutgoingMessage {
+    let title = if entry.title.is_empty() {
+        "（無標題）"
+    } else {
+        &entry.title
+    };
+
+    let mut body = vec![json!({ "type": "text", "text": title, "weight": "bold", "wrap": true })];
+    if !feed_title.is_empty() {
+        body.insert(
+            0,
+            json!({ "type": "text", "text": feed_title, "size": "xs", "color": "#888888" }),
+        );
+    }
+    if let Some(summary) = &entry.summary {
+        body.push(json!({
+            "type": "text",
+            "text": summary,
+            "size": "sm",
+            "color": "#666666",
+            "wrap": true,
+        }));
+    }
+
+    let mut bubble = json!({
+        "type": "bubble",
+        "body": { "type": "box", "layout": "vertical", "spacing": "sm", "contents": body },
+    });
+    if let Some(image_url) = entry
+        .image_url
+        .as_ref()
+        .filter(|url| url.starts_with("https://"))
+    {
+        bubble["hero"] = json!({
+            "type": "image",
+            "url": image_url,
+            "size": "full",
+            "aspectRatio": "20:13",
+            "aspectMode": "cover",
+        });
+    }
+    if let Some(link) = entry
+        .link
+        .as_ref()
+        .filter(|url| url.starts_with("https://"))
+    {
+        bubble["footer"] = json!({
+            "type": "box",
+            "layout": "vertical",
+            "contents": [{
+                "type": "button",
+                "style": "link",
+                "action": { "type": "uri", "label": "閱讀全文", "uri": link },
+            }],
+        });
+    }
+
+    let alt_text = if feed_title.is_empty() {
+        title.to_string()
+    } else {
+        format!("{}：{}", feed_title, title)
+    };
+    OutgoingMessage::flex(
+        alt_text
+            .chars()
+            .take(message_limits::MAX_ALT_TEXT_LENGTH)
+            .collect::<String>(),
+        bubble,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+    use axum::{Json, Router, extract::State, http::HeaderMap, routing::get, routing::post};
+    use serde_json::Value;
+    use std::sync::Mutex;
+
+    /// 收到的 retry key 與請求內容
+    type Received = Arc<Mutex<Vec<(Option<String>, Value)>>>;
+
+    #[derive(Clone, Default)]
+    struct MockState {
+        feed: Arc<Mutex<String>>,
+        multicasts: Received,
+    }
+
+    fn rss<T: AsRef<str>>(items: &[T]) -> String {
+        let items: String = items
+            .iter()
+            .map(AsRef::as_ref)
+            .map(|id| {
+                format!(
+                    "<item><guid>{id}</guid><title>標題 {id}</title><link>https://example.com/{id}</link></item>"
+                )
+            })
+            .collect();
+        format!("<rss><channel><title>公告</title>{}</channel></rss>", items)
+    }
+
+    #[tokio::test]
+    async fn test_poll_sends_only_new_entries() {
+        let mock = MockState::default();
+        *mock.feed.lock().unwrap() = rss(&["2", "1"]);
+        let app =
+            Router::new()
+                .route(
+                    "/feed.xml",
+                    get(|State(mock): State<MockState>| async move {
+                        mock.feed.lock().unwrap().clone()
+                    }),
+                )
+                .route(
+                    "/v2/bot/message/multicast",
+                    post(
+                        |State(mock): State<MockState>,
+                         headers: HeaderMap,
+                         Json(body): Json<Value>| async move {
+                            let retry_key = headers
+                                .get("x-line-retry-key")
+                                .and_then(|value| value.to_str().ok())
+                                .map(str::to_string);
+                            mock.multicasts.lock().unwrap().push((retry_key, body));
+                            Json(json!({}))
+                        },
+                    ),
+                )
+                .with_state(mock.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = LineApiClient::builder("test_token")
+            .base_url(format!("http://{}/v2/bot", addr))
+            .build()
+            .unwrap();
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let scheduler = FeedScheduler::new(
+            client,
+            storage.clone(),
+            vec![FeedSource::parse(&format!("http://{}/feed.xml", addr)).unwrap()],
+        );
+        // 舊版的訂閱者清單會轉為每位訂閱者一個鍵
+        KvNamespace::new(storage, FEEDS_NAMESPACE)
+            .set(LEGACY_SUBSCRIBERS_KEY, &["U0"])
+            .await
+            .unwrap();
+        assert!(scheduler.subscribe("U1").await.unwrap());
+        assert!(!scheduler.subscribe("U1").await.unwrap());
+        assert!(scheduler.unsubscribe("U0").await.unwrap());
+        assert!(!scheduler.unsubscribe("U0").await.unwrap());
+        assert_eq!(scheduler.subscribers().await.unwrap(), vec!["U1"]);
+
+        // 第一次只記錄現有項目
+        scheduler.poll().await;
+        assert!(mock.multicasts.lock().unwrap().is_empty());
+
+        *mock.feed.lock().unwrap() = rss(&["4", "3", "2", "1"]);
+        scheduler.poll().await;
+        scheduler.poll().await;
+
+        {
+            let multicasts = mock.multicasts.lock().unwrap();
+            assert_eq!(multicasts.len(), 1);
+            let (retry_key, body) = &multicasts[0];
+            assert_eq!(retry_key.as_deref().map(str::len), Some(36));
+            assert_eq!(body["to"], json!(["U1"]));
+            let messages = body["messages"].as_array().unwrap();
+            assert_eq!(messages.len(), 2);
+            assert_eq!(messages[0]["altText"], "公告：標題 3");
+            assert_eq!(
+                messages[0]["contents"]["footer"]["contents"][0]["action"]["uri"],
+                "https://example.com/3"
+            );
+        }
+
+        // feed 列出的項目超過保留上限時也不會重送
+        let ids: Vec<String> = (1..=MAX_SEEN_ENTRIES + 2)
+            .rev()
+            .map(|id| id.to_string())
+            .collect();
+        *mock.feed.lock().unwrap() = rss(&ids);
+        scheduler.poll().await;
+        scheduler.poll().await;
+        let multicasts = mock.multicasts.lock().unwrap();
+        let sent: usize = multicasts[1..]
+            .iter()
+            .map(|(_, body)| body["messages"].as_array().unwrap().len())
+            .sum();
+        assert_eq!(sent, MAX_SEEN_ENTRIES + 2 - 4);
+    }
+}
@@ -0,0 +1,12 @@
+use serde::Deserialize;
+
+/// 問答遊戲設定
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct QuizConfig {
+    /// 題庫 JSON 檔，或包含多個 `.json` 題庫的目錄
+    pub questions_path: String,
+    /// 每題作答時間（秒）
+    pub round_secs: u64,
+    /// 每場遊戲的題數
+    pub questions_per_game: usize,
+}
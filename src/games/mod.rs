@@ -0,0 +1,22 @@
+//! 群組問答遊戲
+//!
+//! [`QuestionBank`] 從 JSON 檔載入題庫，[`QuizEngine`] 以 session 保存每個聊天室進行中的遊戲，
+//! 計分後把累積分數寫入排行榜。[`QuizPlugin`] 把遊戲包成外掛：`quiz` 指令開始或結束遊戲，
+//! 中介層判斷作答，排程工作處理逾時的題目。[`QuizConfig`] 一律可用以便從環境變數讀取設定，
+//! 其餘需啟用 `games` feature。
+
+pub mod config;
+#[cfg(feature = "games")]
+pub mod plugin;
+#[cfg(feature = "games")]
+pub mod question;
+#[cfg(feature = "games")]
+pub mod quiz;
+
+pub use config::*;
+#[cfg(feature = "games")]
+pub use plugin::*;
+#[cfg(feature = "games")]
+pub use question::*;
+#[cfg(feature = "games")]
+pub use quiz::*;
@@ -0,0 +1,196 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use dashmap::DashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+use crate::games::{GameError, QuizEngine};
+use crate::models::{Event, MessageEvent, MessageType, OutgoingMessage};
+use crate::plugins::{
+    CommandHandler, EventMiddleware, MiddlewareAction, Plugin, PluginError, ScheduledJob,
+};
+use crate::utils::SensitiveDataMasker;
+use crate::webhook::server::AppState;
+
+/// 開始、結束遊戲與查看排行榜的指令
+pub const QUIZ_COMMANDS: [&str; 2] = ["quiz", "答題"];
+
+/// 檢查作答時間的最長間隔
+const ROUND_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+const HELP_TEXT: &str =
+    "問答遊戲指令：\nquiz：開始遊戲\nquiz stop：結束遊戲\nquiz rank：查看排行榜";
+
+/// 以外掛提供的問答遊戲
+///
+/// 遊戲進行中時，聊天室內不是指令的文字訊息都視為作答。
+#[derive(Clone)]
+pub struct QuizPlugin {
+    engine: QuizEngine,
+    /// 玩家顯示名稱，避免每則作答都呼叫 profile API
+    names: Arc<DashMap<String, String>>,
+}
+
+impl QuizPlugin {
+    pub fn new(engine: QuizEngine) -> Self {
+        Self {
+            engine,
+            names: Arc::new(DashMap::new()),
+        }
+    }
+
+    pub fn engine(&self) -> &QuizEngine {
+        &self.engine
+    }
+
+    /// 取得玩家顯示名稱，失敗時使用遮罩後的使用者 ID
+    async fn display_name(&self, state: &AppState, user_id: &str) -> String {
+        if let Some(name) = self.names.get(user_id) {
+            return name.clone();
+        }
+        let name = match state.line_client.get_profile(user_id).await {
+            Ok(profile) => profile
+                .get("displayName")
+                .and_then(|name| name.as_str())
+                .map(str::to_string),
+            Err(e) => {
+                warn!("Failed to get quiz player profile: {}", e);
+                None
+            }
+        }
+        .unwrap_or_else(|| SensitiveDataMasker::mask_user_id(user_id));
+        self.names.insert(user_id.to_string(), name.clone());
+        name
+    }
+}
+
+#[async_trait]
+impl Plugin for QuizPlugin {
+    fn name(&self) -> &str {
+        "quiz"
+    }
+
+    fn commands(&self) -> Vec<(String, Arc<dyn CommandHandler>)> {
+        let handler: Arc<dyn CommandHandler> = Arc::new(self.clone());
+        QUIZ_COMMANDS
+            .iter()
+            .map(|name| (name.to_string(), handler.clone()))
+            .collect()
+    }
+
+    fn middlewares(&self) -> Vec<Arc<dyn EventMiddleware>> {
+        vec![Arc::new(self.clone())]
+    }
+
+    fn jobs(&self) -> Vec<Arc<dyn ScheduledJob>> {
+        vec![Arc::new(self.clone())]
+    }
+}
+
+#[async_trait]
+impl CommandHandler for QuizPlugin {
+    async fn handle(
+        &self,
+        _state: &AppState,
+        event: &MessageEvent,
+        args: &str,
+    ) -> Result<Vec<OutgoingMessage>, PluginError> {
+        let chat_id = event.source.chat_id();
+        let now = Utc::now();
+        let messages = match args.to_lowercase().as_str() {
+            "" | "start" | "開始" => self.engine.start(chat_id, now).await,
+            "stop" | "結束" => self.engine.stop(chat_id).await.map(|messages| {
+                messages.unwrap_or_else(|| vec![OutgoingMessage::text("目前沒有進行中的遊戲")])
+            }),
+            "rank" | "排行" => self
+                .engine
+                .leaderboard_message(chat_id)
+                .await
+                .map(|message| vec![message]),
+            _ => Ok(vec![OutgoingMessage::text(HELP_TEXT)]),
+        };
+        messages.map_err(plugin_error)
+    }
+}
+
+#[async_trait]
+impl EventMiddleware for QuizPlugin {
+    async fn handle(&self, state: &AppState, event: &Event) -> MiddlewareAction {
+        let Event::Message(event) = event else {
+            return MiddlewareAction::Continue;
+        };
+        let (MessageType::Text { text, .. }, Some(user_id)) =
+            (&event.message, event.source.user_id())
+        else {
+            return MiddlewareAction::Continue;
+        };
+        let command = text.split_whitespace().next().unwrap_or_default();
+        if state.plugins.has_command(command) {
+            return MiddlewareAction::Continue;
+        }
+        let chat_id = event.source.chat_id();
+        match self.engine.is_active(chat_id).await {
+            Ok(true) => {}
+            Ok(false) => return MiddlewareAction::Continue,
+            Err(e) => {
+                warn!("Failed to load quiz session: {}", e);
+                return MiddlewareAction::Continue;
+            }
+        }
+
+        let name = self.display_name(state, user_id).await;
+        match self
+            .engine
+            .answer(chat_id, user_id, &name, text, Utc::now())
+            .await
+        {
+            Ok(Some(messages)) => {
+                if !messages.is_empty()
+                    && let Err(e) = state
+                        .line_client
+                        .reply_message(&event.reply_token, messages)
+                        .await
+                {
+                    warn!("Failed to reply quiz answer: {}", e);
+                }
+                MiddlewareAction::Handled
+            }
+            // 遊戲剛好結束，交給一般處理
+            Ok(None) => MiddlewareAction::Continue,
+            Err(e) => {
+                warn!("Failed to handle quiz answer: {}", e);
+                MiddlewareAction::Handled
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ScheduledJob for QuizPlugin {
+    fn name(&self) -> &str {
+        "quiz_rounds"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(self.engine.round_secs().max(1)).min(ROUND_CHECK_INTERVAL)
+    }
+
+    async fn run(&self, state: &AppState) -> Result<(), PluginError> {
+        let pushes = self
+            .engine
+            .expire_rounds(Utc::now())
+            .await
+            .map_err(plugin_error)?;
+        for (chat_id, messages) in pushes {
+            if let Err(e) = state.line_client.push_message(&chat_id, messages).await {
+                warn!("Failed to push quiz round to chat: {}", e);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn plugin_error(e: GameError) -> PluginError {
+    PluginError::new(e.to_string())
+}
@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub struct GameError {
+    pub message: String,
+}
+
+impl GameError {
+    pub fn new<T: Into<String>>(message: T) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Game Error: {}", self.message)
+    }
+}
+
+impl Error for GameError {}
+
+impl From<crate::storage::StorageError> for GameError {
+    fn from(e: crate::storage::StorageError) -> Self {
+        GameError::new(e.to_string())
+    }
+}
+
+/// 一道題目
+///
+/// 有 `choices` 時為選擇題，可回答選項文字或編號（從 1 開始）；否則為問答題，需回答 `answer`。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Question {
+    pub question: String,
+    #[serde(default)]
+    pub choices: Vec<String>,
+    pub answer: String,
+    /// 公布答案時附上的說明
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+impl Question {
+    /// 比對答案時忽略大小寫與空白
+    pub fn is_correct(&self, reply: &str) -> bool {
+        let reply = normalize(reply);
+        if reply.is_empty() {
+            return false;
+        }
+        let chosen = reply
+            .parse::<usize>()
+            .ok()
+            .and_then(|number| number.checked_sub(1))
+            .and_then(|index| self.choices.get(index))
+            .map(|choice| normalize(choice));
+        let answer = normalize(&self.answer);
+        reply == answer || chosen.is_some_and(|choice| choice == answer)
+    }
+
+    /// 題目與編號選項
+    pub fn prompt(&self) -> String {
+        let mut text = self.question.clone();
+        for (index, choice) in self.choices.iter().enumerate() {
+            text.push_str(&format!("\n{}. {}", index + 1, choice));
+        }
+        text
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.question.trim().is_empty() || self.answer.trim().is_empty() {
+            return Err("question and answer must not be empty".to_string());
+        }
+        let answer = normalize(&self.answer);
+        if !self.choices.is_empty() && !self.choices.iter().any(|c| normalize(c) == answer) {
+            return Err(format!(
+                "answer {:?} is not one of the choices",
+                self.answer
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// 題庫
+#[derive(Debug, Clone, Default)]
+pub struct QuestionBank {
+    questions: Vec<Question>,
+}
+
+impl QuestionBank {
+    pub fn new(questions: Vec<Question>) -> Result<Self, GameError> {
+        for (index, question) in questions.iter().enumerate() {
+            question
+                .validate()
+                .map_err(|e| GameError::new(format!("Question {}: {}", index + 1, e)))?;
+        }
+        Ok(Self { questions })
+    }
+
+    /// 從 JSON 陣列檔，或目錄下所有 `.json` 檔（依檔名排序）載入
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, GameError> {
+        let path = path.as_ref();
+        let files = if path.is_dir() {
+            let mut files: Vec<PathBuf> = std::fs::read_dir(path)
+                .map_err(|e| GameError::new(format!("Failed to read {}: {}", path.display(), e)))?
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|file| file.extension().is_some_and(|ext| ext == "json"))
+                .collect();
+            files.sort();
+            files
+        } else {
+            vec![path.to_path_buf()]
+        };
+
+        let mut questions = Vec::new();
+        for file in files {
+            let content = std::fs::read_to_string(&file)
+                .map_err(|e| GameError::new(format!("Failed to read {}: {}", file.display(), e)))?;
+            let parsed: Vec<Question> = serde_json::from_str(&content).map_err(|e| {
+                GameError::new(format!("Invalid question bank {}: {}", file.display(), e))
+            })?;
+            questions.extend(parsed);
+        }
+        if questions.is_empty() {
+            return Err(GameError::new(format!(
+                "No questions found in {}",
+                path.display()
+            )));
+        }
+        Self::new(questions)
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Question> {
+        self.questions.get(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.questions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.questions.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_question_answers() {
+        let question = Question {
+            question: "台灣最高的山？".to_string(),
+            choices: vec!["合歡山".to_string(), "玉山".to_string()],
+            answer: "玉山".to_string(),
+            explanation: None,
+        };
+        assert!(question.is_correct("2"));
+        assert!(question.is_correct(" 玉 山 "));
+        assert!(!question.is_correct("1"));
+        assert!(!question.is_correct("3"));
+        assert_eq!(question.prompt(), "台灣最高的山？\n1. 合歡山\n2. 玉山");
+
+        let open = Question {
+            question: "Rust 的吉祥物？".to_string(),
+            choices: Vec::new(),
+            answer: "Ferris".to_string(),
+            explanation: None,
+        };
+        assert!(open.is_correct("ferris"));
+        assert!(!open.is_correct("1"));
+
+        let invalid = Question {
+            answer: "雪山".to_string(),
+            ..question
+        };
+        assert!(QuestionBank::new(vec![invalid]).is_err());
+    }
+}
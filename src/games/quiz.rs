@@ -0,0 +1,480 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::games::{GameError, Question, QuestionBank};
+use crate::models::OutgoingMessage;
+use crate::storage::{KvNamespace, Session, Storage};
+
+/// 遊戲 session 的鍵前綴，後接聊天室 ID
+const SESSION_PREFIX: &str = "quiz:";
+
+/// 遊戲 session 的保存時間，程序重啟或漏掉逾時檢查時也不會永遠卡住
+const GAME_TTL_HOURS: i64 = 1;
+
+/// 排行榜顯示的名次數
+pub const LEADERBOARD_SIZE: usize = 10;
+
+/// 進行中的遊戲
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuizSession {
+    pub chat_id: String,
+    /// 本場題目在題庫中的索引
+    pub questions: Vec<usize>,
+    /// 目前題目在 `questions` 中的位置
+    pub current: usize,
+    pub round_ends_at: DateTime<Utc>,
+    /// 以使用者 ID 為鍵的本場得分
+    pub scores: BTreeMap<String, PlayerScore>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerScore {
+    pub display_name: String,
+    pub points: u32,
+}
+
+/// 聊天室排行榜的一列，累計所有場次的得分
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaderboardEntry {
+    pub user_id: String,
+    pub display_name: String,
+    pub points: u32,
+}
+
+/// 問答遊戲
+///
+/// 每個聊天室同時只有一場遊戲，狀態存在 session store（鍵為 `quiz:<聊天室 ID>`），
+/// 進行中的聊天室清單與排行榜存在 key-value 儲存。每題由第一個答對的人得 1 分；
+/// 作答時間到時由 [`expire_rounds`](Self::expire_rounds) 公布答案並出下一題。
+#[derive(Clone)]
+pub struct QuizEngine {
+    bank: Arc<QuestionBank>,
+    storage: Arc<dyn Storage>,
+    games: KvNamespace,
+    leaderboards: KvNamespace,
+    round_duration: Duration,
+    questions_per_game: usize,
+    lock: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl QuizEngine {
+    pub fn new(bank: QuestionBank, storage: Arc<dyn Storage>) -> Self {
+        Self {
+            bank: Arc::new(bank),
+            games: KvNamespace::new(storage.clone(), "quiz"),
+            leaderboards: KvNamespace::new(storage.clone(), "quiz_leaderboard"),
+            storage,
+            round_duration: Duration::seconds(30),
+            questions_per_game: 5,
+            lock: Arc::new(tokio::sync::Mutex::new(())),
+        }
+    }
+
+    /// 每題的作答時間
+    pub fn round_duration(mut self, duration: std::time::Duration) -> Self {
+        self.round_duration = Duration::from_std(duration).unwrap_or(Duration::seconds(30));
+        self
+    }
+
+    /// 每場的題數，題庫不足時使用全部題目
+    pub fn questions_per_game(mut self, count: usize) -> Self {
+        self.questions_per_game = count.max(1);
+        self
+    }
+
+    pub fn round_secs(&self) -> u64 {
+        self.round_duration.num_seconds().max(0) as u64
+    }
+
+    pub fn bank(&self) -> &QuestionBank {
+        &self.bank
+    }
+
+    /// 聊天室進行中的遊戲
+    pub async fn session(&self, chat_id: &str) -> Result<Option<QuizSession>, GameError> {
+        let Some(session) = self.storage.get_session(&session_key(chat_id)).await? else {
+            return Ok(None);
+        };
+        if session.is_expired(Utc::now()) {
+            return Ok(None);
+        }
+        serde_json::from_value(session.data)
+            .map(Some)
+            .map_err(|e| GameError::new(format!("Invalid quiz session: {}", e)))
+    }
+
+    pub async fn is_active(&self, chat_id: &str) -> Result<bool, GameError> {
+        Ok(self.session(chat_id).await?.is_some())
+    }
+
+    /// 開始新遊戲，回傳開場與第一題；已有遊戲時只回傳提示
+    pub async fn start(
+        &self,
+        chat_id: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<OutgoingMessage>, GameError> {
+        let _guard = self.lock.lock().await;
+        if self.session(chat_id).await?.is_some() {
+            return Ok(vec![OutgoingMessage::text(
+                "遊戲進行中，輸入「quiz stop」可以結束目前的遊戲",
+            )]);
+        }
+
+        let mut questions = shuffled(self.bank.len());
+        questions.truncate(self.questions_per_game);
+        let session = QuizSession {
+            chat_id: chat_id.to_string(),
+            questions,
+            current: 0,
+            round_ends_at: now + self.round_duration,
+            scores: BTreeMap::new(),
+        };
+        self.save(&session, now).await?;
+
+        let mut active = self.active_chats().await?;
+        if !active.iter().any(|id| id == chat_id) {
+            active.push(chat_id.to_string());
+            self.games.set("active", &active).await?;
+        }
+
+        Ok(vec![
+            OutgoingMessage::text(format!(
+                "🎯 問答遊戲開始！共 {} 題，每題 {} 秒，最先答對的人得 1 分",
+                session.questions.len(),
+                self.round_duration.num_seconds()
+            )),
+            self.question_message(&session),
+        ])
+    }
+
+    /// 處理作答；沒有遊戲時回傳 `None`，答錯或逾時回傳空的訊息列表
+    pub async fn answer(
+        &self,
+        chat_id: &str,
+        user_id: &str,
+        display_name: &str,
+        text: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Vec<OutgoingMessage>>, GameError> {
+        let _guard = self.lock.lock().await;
+        let Some(mut session) = self.session(chat_id).await? else {
+            return Ok(None);
+        };
+        let question = self.current_question(&session)?;
+        if now >= session.round_ends_at || !question.is_correct(text) {
+            return Ok(Some(Vec::new()));
+        }
+
+        let score = session
+            .scores
+            .entry(user_id.to_string())
+            .or_insert_with(|| PlayerScore {
+                display_name: display_name.to_string(),
+                points: 0,
+            });
+        score.display_name = display_name.to_string();
+        score.points += 1;
+
+        let mut messages = vec![OutgoingMessage::text(reveal(
+            &format!("✅ {} 答對了！", display_name),
+            question,
+        ))];
+        messages.extend(self.advance(session, now).await?);
+        Ok(Some(messages))
+    }
+
+    /// 提前結束遊戲並公布結果；沒有遊戲時回傳 `None`
+    pub async fn stop(&self, chat_id: &str) -> Result<Option<Vec<OutgoingMessage>>, GameError> {
+        let _guard = self.lock.lock().await;
+        let Some(session) = self.session(chat_id).await? else {
+            return Ok(None);
+        };
+        let question = self.current_question(&session)?;
+        let mut messages = vec![OutgoingMessage::text(reveal("遊戲已結束。", question))];
+        messages.push(self.finish(&session).await?);
+        Ok(Some(messages))
+    }
+
+    /// 公布作答時間已到的題目並出下一題，回傳要推播到各聊天室的訊息
+    pub async fn expire_rounds(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<(String, Vec<OutgoingMessage>)>, GameError> {
+        let _guard = self.lock.lock().await;
+        let active = self.active_chats().await?;
+        let mut remaining = Vec::new();
+        let mut pushes = Vec::new();
+        for chat_id in active {
+            let Some(session) = self.session(&chat_id).await? else {
+                continue;
+            };
+            if now < session.round_ends_at {
+                remaining.push(chat_id);
+                continue;
+            }
+            let question = self.current_question(&session)?;
+            let mut messages = vec![OutgoingMessage::text(reveal("⏰ 時間到！", question))];
+            let finished = session.current + 1 >= session.questions.len();
+            messages.extend(self.advance(session, now).await?);
+            if !finished {
+                remaining.push(chat_id.clone());
+            }
+            pushes.push((chat_id, messages));
+        }
+        self.games.set("active", &remaining).await?;
+        Ok(pushes)
+    }
+
+    /// 聊天室的累計排行榜，依分數由高到低
+    pub async fn leaderboard(&self, chat_id: &str) -> Result<Vec<LeaderboardEntry>, GameError> {
+        Ok(self.leaderboards.get(chat_id).await?.unwrap_or_default())
+    }
+
+    /// 排行榜前幾名的文字訊息
+    pub async fn leaderboard_message(&self, chat_id: &str) -> Result<OutgoingMessage, GameError> {
+        let entries = self.leaderboard(chat_id).await?;
+        if entries.is_empty() {
+            return Ok(OutgoingMessage::text(
+                "排行榜還是空的，輸入「quiz」開始遊戲",
+            ));
+        }
+        let mut text = "🏆 排行榜".to_string();
+        for (rank, entry) in entries.iter().take(LEADERBOARD_SIZE).enumerate() {
+            text.push_str(&format!(
+                "\n{}. {} {} 分",
+                rank + 1,
+                entry.display_name,
+                entry.points
+            ));
+        }
+        Ok(OutgoingMessage::text(text))
+    }
+
+    fn current_question(&self, session: &QuizSession) -> Result<&Question, GameError> {
+        session
+            .questions
+            .get(session.current)
+            .and_then(|&index| self.bank.get(index))
+            .ok_or_else(|| GameError::new("Quiz session refers to a missing question"))
+    }
+
+    fn question_message(&self, session: &QuizSession) -> OutgoingMessage {
+        let prompt = self
+            .current_question(session)
+            .map(Question::prompt)
+            .unwrap_or_default();
+        OutgoingMessage::text(format!(
+            "第 {}/{} 題\n{}",
+            session.current + 1,
+            session.questions.len(),
+            prompt
+        ))
+    }
+
+    /// 出下一題，最後一題時結束遊戲
+    async fn advance(
+        &self,
+        mut session: QuizSession,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<OutgoingMessage>, GameError> {
+        if session.current + 1 >= session.questions.len() {
+            return Ok(vec![self.finish(&session).await?]);
+        }
+        session.current += 1;
+        session.round_ends_at = now + self.round_duration;
+        self.save(&session, now).await?;
+        Ok(vec![self.question_message(&session)])
+    }
+
+    /// 刪除 session 並把本場得分累加到排行榜
+    async fn finish(&self, session: &QuizSession) -> Result<OutgoingMessage, GameError> {
+        self.storage
+            .delete_session(&session_key(&session.chat_id))
+            .await?;
+
+        let mut leaderboard = self.leaderboard(&session.chat_id).await?;
+        for (user_id, score) in &session.scores {
+            match leaderboard
+                .iter_mut()
+                .find(|entry| &entry.user_id == user_id)
+            {
+                Some(entry) => {
+                    entry.display_name = score.display_name.clone();
+                    entry.points += score.points;
+                }
+                None => leaderboard.push(LeaderboardEntry {
+                    user_id: user_id.clone(),
+                    display_name: score.display_name.clone(),
+                    points: score.points,
+                }),
+            }
+        }
+        leaderboard.sort_by(|a, b| {
+            b.points
+                .cmp(&a.points)
+                .then_with(|| a.display_name.cmp(&b.display_name))
+        });
+        self.leaderboards
+            .set(&session.chat_id, &leaderboard)
+            .await?;
+
+        let mut scores: Vec<&PlayerScore> = session.scores.values().collect();
+        scores.sort_by_key(|score| std::cmp::Reverse(score.points));
+        let mut text = "🏁 遊戲結束！".to_string();
+        if scores.is_empty() {
+            text.push_str("這場沒有人答對");
+        } else {
+            for (rank, score) in scores.iter().enumerate() {
+                text.push_str(&format!(
+                    "\n{}. {} {} 分",
+                    rank + 1,
+                    score.display_name,
+                    score.points
+                ));
+            }
+        }
+        text.push_str("\n輸入「quiz rank」查看排行榜");
+        Ok(OutgoingMessage::text(text))
+    }
+
+    async fn save(&self, session: &QuizSession, now: DateTime<Utc>) -> Result<(), GameError> {
+        let data: Value = serde_json::to_value(session)
+            .map_err(|e| GameError::new(format!("Failed to serialize quiz session: {}", e)))?;
+        self.storage
+            .save_session(&Session {
+                user_id: session_key(&session.chat_id),
+                data,
+                expires_at: Some(now + Duration::hours(GAME_TTL_HOURS)),
+            })
+            .await?;
+        Ok(())
+    }
+
+    async fn active_chats(&self) -> Result<Vec<String>, GameError> {
+        Ok(self.games.get("active").await?.unwrap_or_default())
+    }
+}
+
+fn session_key(chat_id: &str) -> String {
+    format!("{}{}", SESSION_PREFIX, chat_id)
+}
+
+/// 公布答案與說明
+fn reveal(prefix: &str, question: &Question) -> String {
+    let mut text = format!("{}答案是：{}", prefix, question.answer);
+    if let Some(explanation) = &question.explanation {
+        text.push('\n');
+        text.push_str(explanation);
+    }
+    text
+}
+
+/// 以作業系統亂數洗牌的 `0..len`
+fn shuffled(len: usize) -> Vec<usize> {
+    let mut indexes: Vec<usize> = (0..len).collect();
+    for i in (1..len).rev() {
+        let mut bytes = [0u8; 8];
+        getrandom::getrandom(&mut bytes).expect("OS random number generator is unavailable");
+        let j = (u64::from_le_bytes(bytes) % (i as u64 + 1)) as usize;
+        indexes.swap(i, j);
+    }
+    indexes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    fn bank() -> QuestionBank {
+        QuestionBank::new(vec![
+            Question {
+                question: "1 + 1 = ?".to_string(),
+                choices: Vec::new(),
+                answer: "2".to_string(),
+                explanation: None,
+            },
+            Question {
+                question: "2 + 2 = ?".to_string(),
+                choices: Vec::new(),
+                answer: "4".to_string(),
+                explanation: Some("二加二等於四".to_string()),
+            },
+        ])
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_quiz_game() {
+        let engine = QuizEngine::new(bank(), Arc::new(MemoryStorage::new()))
+            .round_duration(std::time::Duration::from_secs(20));
+        let now = Utc::now();
+
+        assert_eq!(
+            engine.answer("G1", "U1", "小明", "2", now).await.unwrap(),
+            None
+        );
+        assert_eq!(engine.start("G1", now).await.unwrap().len(), 2);
+        assert!(engine.is_active("G1").await.unwrap());
+
+        // 答錯時不回覆
+        let session = engine.session("G1").await.unwrap().unwrap();
+        let answer = engine
+            .bank()
+            .get(session.questions[0])
+            .unwrap()
+            .answer
+            .clone();
+        let replies = engine.answer("G1", "U2", "小華", "100", now).await.unwrap();
+        assert_eq!(replies, Some(Vec::new()));
+
+        // 答對得分並出下一題
+        let replies = engine
+            .answer("G1", "U1", "小明", &answer, now)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(replies.len(), 2);
+        assert_eq!(engine.session("G1").await.unwrap().unwrap().current, 1);
+
+        // 時間未到時不處理，時間到則公布答案並結束
+        assert!(engine.expire_rounds(now).await.unwrap().is_empty());
+        let pushes = engine
+            .expire_rounds(now + Duration::seconds(21))
+            .await
+            .unwrap();
+        assert_eq!(pushes.len(), 1);
+        assert_eq!(pushes[0].0, "G1");
+        assert!(!engine.is_active("G1").await.unwrap());
+
+        assert_eq!(
+            engine.leaderboard("G1").await.unwrap(),
+            vec![LeaderboardEntry {
+                user_id: "U1".to_string(),
+                display_name: "小明".to_string(),
+                points: 1,
+            }]
+        );
+
+        // 排行榜累計多場得分
+        engine.start("G1", now).await.unwrap();
+        let session = engine.session("G1").await.unwrap().unwrap();
+        let answer = engine
+            .bank()
+            .get(session.questions[0])
+            .unwrap()
+            .answer
+            .clone();
+        engine
+            .answer("G1", "U1", "小明", &answer, now)
+            .await
+            .unwrap();
+        assert!(engine.stop("G1").await.unwrap().is_some());
+        assert_eq!(engine.leaderboard("G1").await.unwrap()[0].points, 2);
+        assert_eq!(engine.stop("G1").await.unwrap(), None);
+    }
+}
@@ -0,0 +1,10 @@
+//! 管理用 gRPC 服務
+//!
+//! 提供 `SendPush`、`Broadcast`、`GetStats` 與 `ManageRichMenu`，給偏好 gRPC 的內部服務使用。
+//! 合約見 `proto/admin.proto`；服務程式碼由 `build.rs` 產生，不需要安裝 protoc。
+
+pub mod proto;
+pub mod service;
+
+pub use proto::*;
+pub use service::*;
@@ -0,0 +1,93 @@
+//! `proto/admin.proto` 的訊息型別，修改時需同步更新 proto 檔
+
+use std::collections::HashMap;
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SendPushRequest {
+    #[prost(string, tag = "1")]
+    pub to: String,
+    #[prost(string, repeated, tag = "2")]
+    pub texts: Vec<String>,
+    #[prost(string, tag = "3")]
+    pub messages_json: String,
+    #[prost(bool, tag = "4")]
+    pub notification_disabled: bool,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BroadcastRequest {
+    #[prost(string, repeated, tag = "1")]
+    pub texts: Vec<String>,
+    #[prost(string, tag = "2")]
+    pub messages_json: String,
+    #[prost(bool, tag = "3")]
+    pub notification_disabled: bool,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SendResponse {
+    #[prost(string, tag = "1")]
+    pub request_id: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GetStatsRequest {}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GetStatsResponse {
+    #[prost(uint64, tag = "1")]
+    pub uptime_seconds: u64,
+    #[prost(uint64, tag = "2")]
+    pub total_events: u64,
+    #[prost(map = "string, uint64", tag = "3")]
+    pub events: HashMap<String, u64>,
+    #[prost(uint64, tag = "4")]
+    pub event_errors: u64,
+    #[prost(uint64, tag = "5")]
+    pub line_api_requests: u64,
+    #[prost(uint64, tag = "6")]
+    pub line_api_errors: u64,
+    #[prost(uint64, tag = "7")]
+    pub rate_limit_hits: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum RichMenuAction {
+    Unspecified = 0,
+    List = 1,
+    Link = 2,
+    Unlink = 3,
+    SetDefault = 4,
+    CancelDefault = 5,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ManageRichMenuRequest {
+    #[prost(enumeration = "RichMenuAction", tag = "1")]
+    pub action: i32,
+    #[prost(string, tag = "2")]
+    pub user_id: String,
+    #[prost(string, tag = "3")]
+    pub rich_menu_id: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct RichMenu {
+    #[prost(string, tag = "1")]
+    pub rich_menu_id: String,
+    #[prost(string, tag = "2")]
+    pub name: String,
+    #[prost(string, tag = "3")]
+    pub chat_bar_text: String,
+    #[prost(bool, tag = "4")]
+    pub selected: bool,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ManageRichMenuResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub rich_menus: Vec<RichMenu>,
+}
+
+include!(concat!(env!("OUT_DIR"), "/linebot.admin.AdminService.rs"));
@@ -0,0 +1,441 @@
+use std::{future::Future, net::SocketAddr, sync::Arc};
+use tonic::{
+    Request, Response, Status,
+    metadata::MetadataMap,
+    service::{Interceptor, interceptor::InterceptedService},
+};
+use tracing::{error, info, warn};
+
+use crate::grpc::admin_service_server::{AdminService, AdminServiceServer};
+use crate::grpc::{
+    BroadcastRequest, GetStatsRequest, GetStatsResponse, ManageRichMenuRequest,
+    ManageRichMenuResponse, RichMenu, RichMenuAction, SendPushRequest, SendResponse,
+};
+use crate::line_api::{LineApiClient, LineApiError, SendOptions};
+use crate::models::OutgoingMessage;
+use crate::storage::{ADMIN_ACTOR_HEADER, AuditEntry, AuditLog, admin_actor, with_audit_actor};
+use crate::utils::{StatsAggregator, token_matches};
+
+/// `linebot.admin.AdminService` 的實作
+#[derive(Clone)]
+pub struct AdminGrpcService {
+    line_client: LineApiClient,
+    stats: Arc<StatsAggregator>,
+    audit_log: Option<AuditLog>,
+}
+
+impl AdminGrpcService {
+    pub fn new(line_client: LineApiClient, stats: Arc<StatsAggregator>) -> Self {
+        Self {
+            line_client,
+            stats,
+            audit_log: None,
+        }
+    }
+
+    /// 每個 RPC 寫入稽核紀錄，操作者取自 `x-admin-actor` metadata
+    pub fn with_audit_log(mut self, audit_log: AuditLog) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
+    async fn audited<T>(
+        &self,
+        method: &str,
+        metadata: &MetadataMap,
+        call: impl Future<Output = Result<T, Status>>,
+    ) -> Result<T, Status> {
+        let actor = admin_actor(
+            metadata
+                .get(ADMIN_ACTOR_HEADER)
+                .and_then(|value| value.to_str().ok()),
+        );
+        let result = with_audit_actor(actor.clone(), call).await;
+        if let Some(audit_log) = &self.audit_log {
+            let entry = AuditEntry::new("admin.grpc")
+                .actor(actor)
+                .target(method)
+                .result(&result);
+            audit_log.record(entry).await;
+        }
+        result
+    }
+
+    /// 包成需要 `authorization: Bearer <token>` metadata 的 tonic 服務
+    pub fn into_server(
+        self,
+        token: String,
+    ) -> InterceptedService<AdminServiceServer<Self>, AdminTokenInterceptor> {
+        AdminServiceServer::with_interceptor(
+            self,
+            AdminTokenInterceptor {
+                token: token.into(),
+            },
+        )
+    }
+
+    /// 綁定位址後在背景啟動 gRPC 伺服器，回傳實際監聽的位址；無法綁定時回傳錯誤
+    pub async fn start(self, bind: SocketAddr, token: String) -> std::io::Result<SocketAddr> {
+        let listener = tokio::net::TcpListener::bind(bind).await?;
+        let addr = listener.local_addr()?;
+        let incoming = tonic::transport::server::TcpIncoming::from_listener(listener, true, None)
+            .map_err(std::io::Error::other)?;
+        let server = self.into_server(token);
+        info!("Starting gRPC admin service on {}", addr);
+        tokio::spawn(async move {
+            if let Err(e) = tonic::transport::Server::builder()
+                .add_service(server)
+                .serve_with_incoming(incoming)
+                .await
+            {
+                error!("gRPC admin service on {} stopped: {}", addr, e);
+            }
+        });
+        Ok(addr)
+    }
+
+    async fn run_rich_menu_action(
+        &self,
+        request: ManageRichMenuRequest,
+    ) -> Result<Response<ManageRichMenuResponse>, Status> {
+        let client = &self.line_client;
+        let result = match RichMenuAction::try_from(request.action) {
+            Ok(RichMenuAction::List) => {
+                let rich_menus = client
+                    .get_rich_menu_list()
+                    .await
+                    .map_err(line_api_status)?
+                    .into_iter()
+                    .map(|menu| RichMenu {
+                        rich_menu_id: menu.rich_menu_id,
+                        name: menu.name,
+                        chat_bar_text: menu.chat_bar_text,
+                        selected: menu.selected,
+                    })
+                    .collect();
+                return Ok(Response::new(ManageRichMenuResponse { rich_menus }));
+            }
+            Ok(RichMenuAction::Link) => {
+                client
+                    .link_rich_menu(&request.user_id, &request.rich_menu_id)
+                    .await
+            }
+            Ok(RichMenuAction::Unlink) => client.unlink_rich_menu(&request.user_id).await,
+            Ok(RichMenuAction::SetDefault) => {
+                client.set_default_rich_menu(&request.rich_menu_id).await
+            }
+            Ok(RichMenuAction::CancelDefault) => client.cancel_default_rich_menu().await,
+            Ok(RichMenuAction::Unspecified) | Err(_) => {
+                return Err(Status::invalid_argument("Unknown rich menu action"));
+            }
+        };
+        result.map_err(line_api_status)?;
+        Ok(Response::new(ManageRichMenuResponse::default()))
+    }
+}
+
+#[tonic::async_trait]
+impl AdminService for AdminGrpcService {
+    async fn send_push(
+        &self,
+        request: Request<SendPushRequest>,
+    ) -> Result<Response<SendResponse>, Status> {
+        let (metadata, _, request) = request.into_parts();
+        self.audited("SendPush", &metadata, async {
+            let messages = outgoing_messages(request.texts, &request.messages_json)
+                .map_err(Status::invalid_argument)?;
+            let options = SendOptions::new().notification_disabled(request.notification_disabled);
+            let response = self
+                .line_client
+                .push_message_with_options(&request.to, messages, &options)
+                .await
+                .map_err(line_api_status)?;
+            Ok(Response::new(SendResponse {
+                request_id: response.request_id.unwrap_or_default(),
+            }))
+        })
+        .await
+    }
+
+    async fn broadcast(
+        &self,
+        request: Request<BroadcastRequest>,
+    ) -> Result<Response<SendResponse>, Status> {
+        let (metadata, _, request) = request.into_parts();
+        self.audited("Broadcast", &metadata, async {
+            let messages = outgoing_messages(request.texts, &request.messages_json)
+                .map_err(Status::invalid_argument)?;
+            let options = SendOptions::new().notification_disabled(request.notification_disabled);
+            let response = self
+                .line_client
+                .broadcast_message_with_options(messages, &options)
+                .await
+                .map_err(line_api_status)?;
+            Ok(Response::new(SendResponse {
+                request_id: response.request_id.unwrap_or_default(),
+            }))
+        })
+        .await
+    }
+
+    async fn get_stats(
+        &self,
+        request: Request<GetStatsRequest>,
+    ) -> Result<Response<GetStatsResponse>, Status> {
+        self.audited("GetStats", request.metadata(), async {
+            let stats = self.stats.snapshot();
+            Ok(Response::new(GetStatsResponse {
+                uptime_seconds: stats.uptime_seconds,
+                total_events: stats.total_events,
+                events: stats.events.into_iter().collect(),
+                event_errors: stats.event_errors,
+                line_api_requests: stats.line_api_requests,
+                line_api_errors: stats.line_api_errors,
+                rate_limit_hits: stats.rate_limit_hits,
+            }))
+        })
+        .await
+    }
+
+    async fn manage_rich_menu(
+        &self,
+        request: Request<ManageRichMenuRequest>,
+    ) -> Result<Response<ManageRichMenuResponse>, Status> {
+        let (metadata, _, request) = request.into_parts();
+        self.audited(
+            "ManageRichMenu",
+            &metadata,
+            self.run_rich_menu_action(request),
+        )
+        .await
+    }
+}
+
+/// 檢查 `authorization: Bearer <token>` metadata
+#[derive(Clone)]
+pub struct AdminTokenInterceptor {
+    token: Arc<str>,
+}
+
+impl Interceptor for AdminTokenInterceptor {
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        let provided = request
+            .metadata()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+
+        if !token_matches(provided, &self.token) {
+            warn!("Rejected gRPC admin request");
+            return Err(Status::unauthenticated("Invalid admin token"));
+        }
+        Ok(request)
+    }
+}
+
+/// `texts` 各送一則文字訊息，`messages_json` 的訊息接在後面
+fn outgoing_messages(
+    texts: Vec<String>,
+    messages_json: &str,
+) -> Result<Vec<OutgoingMessage>, String> {
+    let mut messages: Vec<OutgoingMessage> = texts.into_iter().map(OutgoingMessage::text).collect();
+    if !messages_json.is_empty() {
+        let parsed: Vec<OutgoingMessage> = serde_json::from_str(messages_json)
+            .map_err(|e| format!("Invalid messages_json: {}", e))?;
+        messages.extend(parsed);
+    }
+    if messages.is_empty() {
+        return Err("No messages to send".to_string());
+    }
+    Ok(messages)
+}
+
+fn line_api_status(error: LineApiError) -> Status {
+    let message = error.to_string();
+    match error.status_code {
+        _ if error.network_error => Status::unavailable(message),
+        None | Some(400) => Status::invalid_argument(message),
+        Some(401) | Some(403) => Status::permission_denied(message),
+        Some(404) => Status::not_found(message),
+        Some(429) => Status::resource_exhausted(message),
+        Some(_) => Status::internal(message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{AuditQuery, MemoryStorage, Storage};
+    use axum::{
+        Router,
+        http::{Method, Uri},
+        routing::{any, get},
+    };
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_admin_token_interceptor() {
+        let mut interceptor = AdminTokenInterceptor {
+            token: "secret".into(),
+        };
+        let mut request = Request::new(());
+        request
+            .metadata_mut()
+            .insert("authorization", "Bearer secret".parse().unwrap());
+        assert!(interceptor.call(request).is_ok());
+
+        let status = interceptor.call(Request::new(())).unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Unauthenticated);
+    }
+
+    #[tokio::test]
+    async fn test_start_reports_bind_error() {
+        let service = || {
+            let line_client = LineApiClient::new("token".to_string());
+            AdminGrpcService::new(line_client, Arc::new(StatsAggregator::new()))
+        };
+        let addr = service()
+            .start("127.0.0.1:0".parse().unwrap(), "secret".to_string())
+            .await
+            .unwrap();
+        assert_ne!(addr.port(), 0);
+        assert!(service().start(addr, "secret".to_string()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_manage_rich_menu() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let store = calls.clone();
+        let app = Router::new()
+            .route(
+                "/richmenu/list",
+                get(|| async {
+                    axum::Json(json!({ "richmenus": [{
+                        "richMenuId": "richmenu-88c05ef6921ae53f8b58a25f3a65faf7",
+                        "name": "main",
+                        "chatBarText": "選單",
+                        "selected": true,
+                        "size": { "width": 2500, "height": 843 },
+                        "areas": []
+                    }] }))
+                }),
+            )
+            .route(
+                "/user/*path",
+                any(move |method: Method, uri: Uri| {
+                    store
+                        .lock()
+                        .unwrap()
+                        .push(format!("{} {}", method, uri.path()));
+                    async { axum::Json(json!({})) }
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let storage = Arc::new(MemoryStorage::new());
+        let audit_log = AuditLog::new(storage.clone());
+        let client = LineApiClient::builder("token")
+            .base_url(format!("http://{}", addr))
+            .build()
+            .unwrap()
+            .with_audit_log(audit_log.clone());
+        let service = AdminGrpcService::new(client, Arc::new(StatsAggregator::new()))
+            .with_audit_log(audit_log);
+        let request = |action: RichMenuAction, user_id: &str, rich_menu_id: &str| {
+            let mut request = Request::new(ManageRichMenuRequest {
+                action: action as i32,
+                user_id: user_id.to_string(),
+                rich_menu_id: rich_menu_id.to_string(),
+            });
+            request
+                .metadata_mut()
+                .insert(ADMIN_ACTOR_HEADER, "alice".parse().unwrap());
+            request
+        };
+        let user_id = "U1234567890abcdef1234567890abcdef";
+        let rich_menu_id = "richmenu-88c05ef6921ae53f8b58a25f3a65faf7";
+
+        let list = service
+            .manage_rich_menu(request(RichMenuAction::List, "", ""))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(list.rich_menus.len(), 1);
+        assert_eq!(list.rich_menus[0].chat_bar_text, "選單");
+
+        for (action, user_id, rich_menu_id) in [
+            (RichMenuAction::Link, user_id, rich_menu_id),
+            (RichMenuAction::Unlink, user_id, ""),
+            (RichMenuAction::SetDefault, "", rich_menu_id),
+            (RichMenuAction::CancelDefault, "", ""),
+        ] {
+            service
+                .manage_rich_menu(request(action, user_id, rich_menu_id))
+                .await
+                .unwrap();
+        }
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![
+                format!("POST /user/{}/richmenu/{}", user_id, rich_menu_id),
+                format!("DELETE /user/{}/richmenu", user_id),
+                format!("POST /user/all/richmenu/{}", rich_menu_id),
+                "DELETE /user/all/richmenu".to_string(),
+            ]
+        );
+
+        let status = service
+            .manage_rich_menu(request(RichMenuAction::Link, user_id, "../message/push"))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        let status = service
+            .manage_rich_menu(request(RichMenuAction::Unspecified, "", ""))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+        // 每個 RPC 與預設 rich menu 的異動都以 metadata 中的操作者記錄
+        let audit = |action: &str| AuditQuery {
+            action: Some(action.to_string()),
+            ..Default::default()
+        };
+        let rich_menu_changes = storage.audit_log(&audit("rich_menu.")).await.unwrap();
+        let actions: Vec<_> = rich_menu_changes
+            .iter()
+            .map(|entry| (entry.action.as_str(), entry.success))
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                ("rich_menu.link", false),
+                ("rich_menu.cancel_default", true),
+                ("rich_menu.set_default", true),
+                ("rich_menu.unlink", true),
+                ("rich_menu.link", true),
+            ]
+        );
+        assert!(rich_menu_changes.iter().all(|entry| entry.actor == "alice"));
+        assert_eq!(rich_menu_changes[2].target.as_deref(), Some(rich_menu_id));
+        let calls = storage.audit_log(&audit("admin.grpc")).await.unwrap();
+        assert_eq!(calls.len(), 7);
+        assert!(!calls[0].success);
+        assert!(calls[2].success);
+        assert_eq!(calls[2].target.as_deref(), Some("ManageRichMenu"));
+    }
+
+    #[test]
+    fn test_outgoing_messages() {
+        let messages = outgoing_messages(
+            vec!["hi".to_string()],
+            r#"[{"type": "sticker", "packageId": "446", "stickerId": "1988"}]"#,
+        )
+        .unwrap();
+        assert_eq!(messages.len(), 2);
+        assert!(outgoing_messages(Vec::new(), "").is_err());
+        assert!(outgoing_messages(Vec::new(), "not json").is_err());
+    }
+}
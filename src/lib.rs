@@ -1,11 +1,31 @@
+pub mod ai;
+pub mod auth;
+pub mod bridge;
+pub mod campaigns;
+pub mod feeds;
+pub mod games;
+#[cfg(feature = "grpc")]
+pub mod grpc;
 pub mod handlers;
 pub mod line_api;
+pub mod media;
 pub mod models;
+pub mod nlu;
+pub mod onboarding;
+pub mod pay;
+#[cfg(feature = "server")]
+pub mod plugins;
+pub mod prelude;
+pub mod sinks;
+pub mod storage;
+pub mod translation;
 pub mod utils;
 pub mod webhook;
 
-pub use handlers::*;
-pub use line_api::*;
-pub use models::*;
-pub use utils::*;
-pub use webhook::server::{create_app, start_server};
+pub use line_api::LineApiClient;
+pub use utils::Config;
+#[cfg(feature = "server")]
+pub use webhook::server::{
+    StartupError, create_app, create_app_with_plugins, create_app_with_storage, start_server,
+    start_server_with_plugins,
+};
@@ -1,16 +1,62 @@
+use crate::line_api::{BufferedPush, OfflineBuffer, SendOptions, SendRateLimiter, SendResponse};
 use crate::models::{
-    ApiResponse, MulticastMessageRequest, OutgoingMessage, PushMessageRequest, ReplyMessageRequest,
+    AggregationUnitNames, AggregationUnitStatistics, ApiError, ApiResponse, AudienceGroup, BotInfo,
+    BroadcastMessageRequest, ChannelAccessToken, ChannelAccessTokenKeyIds, ContentTranscoding,
+    CreateAudienceRequest, FollowerDataPoint, FollowerInsight, FollowerTimeSeries, GroupSummary,
+    MemberCount, MessageEventStatistics, MessageQuota, MulticastMessageRequest,
+    NarrowcastMessageRequest, NarrowcastProgress, OAuthErrorResponse, OutgoingMessage,
+    PushMessageRequest, QuotaConsumption, ReplyMessageRequest, RichMenu, RichMenuAlias,
+    RichMenuBatchOperation, RichMenuBatchProgress, RichMenuBatchRequest, RichMenuList,
+    RichMenuSummary, TranscodingStatus,
 };
-use reqwest::{Client, Response};
+use crate::storage::{AuditEntry, AuditLog, ConversationLogger};
+use crate::utils::{
+    ErrorContext, ErrorReporter, GroupIdValidator, Metrics, OutgoingMessageValidator,
+    RichMenuAliasIdValidator, RichMenuIdValidator, RichMenuValidator, SensitiveDataMasker,
+    StatsAggregator, UserIdValidator, ValidationError, check_limit, message_limits, random_uuid,
+};
+use chrono::{NaiveDate, Utc};
+use reqwest::{Client, Proxy, Response};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
 use std::error::Error;
 use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
 
 const LINE_API_BASE_URL: &str = "https://api.line.me/v2/bot";
+/// 內容上傳/下載（圖片、影片、音訊、檔案）使用獨立的網域
+const LINE_DATA_API_BASE_URL: &str = "https://api-data.line.me/v2/bot";
+/// channel access token 的發行與撤銷不在 `/v2/bot` 之下
+const LINE_OAUTH_BASE_URL: &str = "https://api.line.me";
+const CLIENT_ASSERTION_TYPE: &str = "urn:ietf:params:oauth:client-assertion-type:jwt-bearer";
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// 預設的 User-Agent，共用的 HTTP 用戶端也使用這個值
+pub const DEFAULT_USER_AGENT: &str = concat!("linebot-rs/", env!("CARGO_PKG_VERSION"));
+
+/// 統計查詢的最長期間（天，含頭尾）
+pub const MAX_AGGREGATION_STATISTICS_DAYS: i64 = 30;
+
+/// 好友數時間序列一次最多查詢的天數
+pub const MAX_FOLLOWER_SERIES_DAYS: i64 = 366;
+
+/// 受眾名稱的最大字數
+pub const MAX_AUDIENCE_DESCRIPTION_CHARS: usize = 120;
+
+/// 一次 rich menu 批次操作最多的步驟數
+pub const MAX_RICH_MENU_BATCH_OPERATIONS: usize = 1000;
+
+/// 一次 bulk link/unlink rich menu 請求最多的使用者數
+pub const MAX_RICH_MENU_BULK_USERS: usize = 500;
 
 #[derive(Debug)]
 pub struct LineApiError {
     pub message: String,
     pub status_code: Option<u16>,
+    /// 請求未送達 LINE（連線失敗、逾時等），可稍後重試
+    pub network_error: bool,
 }
 
 impl fmt::Display for LineApiError {
@@ -21,163 +67,2723 @@ impl fmt::Display for LineApiError {
 
 impl Error for LineApiError {}
 
+/// 對外連線使用的 HTTP 代理
+///
+/// 未設定時 reqwest 仍會讀取 `HTTPS_PROXY`/`HTTP_PROXY`/`NO_PROXY` 環境變數。
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct ProxyConfig {
+    pub url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+impl ProxyConfig {
+    pub fn new<T: Into<String>>(url: T) -> Self {
+        Self {
+            url: url.into(),
+            username: None,
+            password: None,
+        }
+    }
+
+    pub fn basic_auth<T: Into<String>>(mut self, username: T, password: T) -> Self {
+        self.username = Some(username.into());
+        self.password = Some(password.into());
+        self
+    }
+
+    fn to_proxy(&self) -> Result<Proxy, LineApiError> {
+        let proxy = Proxy::all(&self.url).map_err(|e| LineApiError {
+            message: format!("Invalid proxy URL: {}", e),
+            status_code: None,
+            network_error: false,
+        })?;
+
+        Ok(match &self.username {
+            Some(username) => proxy.basic_auth(username, self.password.as_deref().unwrap_or("")),
+            None => proxy,
+        })
+    }
+}
+
+// 避免密碼出現在日誌中
+impl fmt::Debug for ProxyConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProxyConfig")
+            .field("url", &self.url)
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| "***"))
+            .finish()
+    }
+}
+
+/// LineApiClient 建構器
+///
+/// 預設連線逾時 10 秒、請求逾時 30 秒，其餘沿用 reqwest 預設值。
+#[derive(Clone)]
+pub struct LineApiClientBuilder {
+    channel_access_token: String,
+    base_url: String,
+    data_base_url: String,
+    oauth_base_url: String,
+    connect_timeout: Duration,
+    request_timeout: Duration,
+    pool_max_idle_per_host: Option<usize>,
+    pool_idle_timeout: Option<Duration>,
+    http2_prior_knowledge: bool,
+    user_agent: String,
+    proxy: Option<ProxyConfig>,
+    http_client: Option<Client>,
+    send_rate_limiter: Option<Arc<SendRateLimiter>>,
+    dry_run: bool,
+    offline_buffer: Option<Arc<OfflineBuffer>>,
+    message_validator: Option<Arc<OutgoingMessageValidator>>,
+}
+
+// 避免 channel access token 出現在日誌中
+impl fmt::Debug for LineApiClientBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LineApiClientBuilder")
+            .field("channel_access_token", &"***")
+            .field("base_url", &self.base_url)
+            .field("data_base_url", &self.data_base_url)
+            .field("oauth_base_url", &self.oauth_base_url)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("request_timeout", &self.request_timeout)
+            .field("pool_max_idle_per_host", &self.pool_max_idle_per_host)
+            .field("pool_idle_timeout", &self.pool_idle_timeout)
+            .field("http2_prior_knowledge", &self.http2_prior_knowledge)
+            .field("user_agent", &self.user_agent)
+            .field("proxy", &self.proxy)
+            .field("http_client", &self.http_client)
+            .field("send_rate_limiter", &self.send_rate_limiter)
+            .field("dry_run", &self.dry_run)
+            .field("offline_buffer", &self.offline_buffer)
+            .field("message_validator", &self.message_validator)
+            .finish()
+    }
+}
+
+impl LineApiClientBuilder {
+    fn new(channel_access_token: String) -> Self {
+        Self {
+            channel_access_token,
+            base_url: LINE_API_BASE_URL.to_string(),
+            data_base_url: LINE_DATA_API_BASE_URL.to_string(),
+            oauth_base_url: LINE_OAUTH_BASE_URL.to_string(),
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: None,
+            http2_prior_knowledge: false,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            proxy: None,
+            http_client: None,
+            send_rate_limiter: Some(Arc::new(SendRateLimiter::line_defaults())),
+            dry_run: false,
+            offline_buffer: None,
+            message_validator: Some(Arc::new(OutgoingMessageValidator::new())),
+        }
+    }
+
+    /// 覆寫 API 位址（例如測試用的 mock server），結尾的 `/` 會被移除
+    pub fn base_url<T: Into<String>>(mut self, base_url: T) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// 覆寫內容 API（`api-data.line.me`）位址
+    pub fn data_base_url<T: Into<String>>(mut self, data_base_url: T) -> Self {
+        self.data_base_url = data_base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// 覆寫 channel access token 端點（`/v2/oauth`、`/oauth2/v2.1`）的主機位址
+    pub fn oauth_base_url<T: Into<String>>(mut self, oauth_base_url: T) -> Self {
+        self.oauth_base_url = oauth_base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
+    /// 每個主機保留的閒置連線數上限
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// 直接以 HTTP/2 連線，不經 HTTP/1.1 協商
+    pub fn http2_prior_knowledge(mut self, enabled: bool) -> Self {
+        self.http2_prior_knowledge = enabled;
+        self
+    }
+
+    pub fn user_agent<T: Into<String>>(mut self, user_agent: T) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    pub fn proxy(mut self, proxy: ProxyConfig) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// 使用外部建立的 HTTP 用戶端，與其他元件共用連線池
+    ///
+    /// 設定後忽略連線逾時、連線池、HTTP/2、User-Agent 與代理的設定，請求逾時仍依 `request_timeout`。
+    pub fn http_client(mut self, client: Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// 自訂送出速率限制，預設依 LINE 公告的各端點上限
+    pub fn send_rate_limiter(mut self, limiter: SendRateLimiter) -> Self {
+        self.send_rate_limiter = Some(Arc::new(limiter));
+        self
+    }
+
+    /// 停用送出速率限制
+    pub fn without_send_rate_limit(mut self) -> Self {
+        self.send_rate_limiter = None;
+        self
+    }
+
+    /// 送出類 API 只記錄請求與指標，不實際呼叫 LINE API
+    pub fn dry_run(mut self, enabled: bool) -> Self {
+        self.dry_run = enabled;
+        self
+    }
+
+    /// push 因網路錯誤失敗時暫存訊息，搭配 `start_offline_retry` 重送
+    pub fn offline_buffer(mut self, buffer: OfflineBuffer) -> Self {
+        self.offline_buffer = Some(Arc::new(buffer));
+        self
+    }
+
+    /// 自訂送出前的訊息檢查，預設依 LINE 的 URL 規則
+    pub fn message_validator(mut self, validator: OutgoingMessageValidator) -> Self {
+        self.message_validator = Some(Arc::new(validator));
+        self
+    }
+
+    /// 停用送出前的訊息檢查
+    pub fn without_message_validation(mut self) -> Self {
+        self.message_validator = None;
+        self
+    }
+
+    pub fn build(self) -> Result<LineApiClient, LineApiError> {
+        let client = match &self.http_client {
+            Some(client) => client.clone(),
+            None => self.build_http_client()?,
+        };
+
+        Ok(LineApiClient {
+            client,
+            channel_access_token: self.channel_access_token,
+            base_url: self.base_url,
+            data_base_url: self.data_base_url,
+            oauth_base_url: self.oauth_base_url,
+            request_timeout: self.request_timeout,
+            send_rate_limiter: self.send_rate_limiter,
+            dry_run: self.dry_run,
+            offline_buffer: self.offline_buffer,
+            message_validator: self.message_validator,
+            stats: None,
+            metrics: Metrics::default(),
+            error_reporter: None,
+            audit_log: None,
+            conversation_log: None,
+        })
+    }
+
+    fn build_http_client(&self) -> Result<Client, LineApiError> {
+        let mut builder = Client::builder()
+            .connect_timeout(self.connect_timeout)
+            .user_agent(&self.user_agent);
+
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        if let Some(timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+        if self.http2_prior_knowledge {
+            builder = builder.http2_prior_knowledge();
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(proxy.to_proxy()?);
+        }
+
+        builder.build().map_err(|e| LineApiError {
+            message: format!("Failed to build HTTP client: {}", e),
+            status_code: None,
+            network_error: false,
+        })
+    }
+}
+
+/// 從內容 API 下載的資料
+#[derive(Debug, Clone)]
+pub struct MessageContent {
+    pub content_type: Option<String>,
+    pub data: bytes::Bytes,
+}
+
 #[derive(Clone)]
 pub struct LineApiClient {
     client: Client,
     channel_access_token: String,
+    base_url: String,
+    data_base_url: String,
+    oauth_base_url: String,
+    request_timeout: Duration,
+    send_rate_limiter: Option<Arc<SendRateLimiter>>,
+    dry_run: bool,
+    offline_buffer: Option<Arc<OfflineBuffer>>,
+    message_validator: Option<Arc<OutgoingMessageValidator>>,
+    stats: Option<Arc<StatsAggregator>>,
+    metrics: Metrics,
+    error_reporter: Option<Arc<dyn ErrorReporter>>,
+    audit_log: Option<AuditLog>,
+    conversation_log: Option<ConversationLogger>,
 }
 
 impl LineApiClient {
     pub fn new(channel_access_token: String) -> Self {
-        Self {
-            client: Client::new(),
-            channel_access_token,
-        }
+        Self::builder(channel_access_token)
+            .build()
+            .expect("default HTTP client configuration is valid")
+    }
+
+    /// 建立可調整連線參數的客戶端
+    pub fn builder<T: Into<String>>(channel_access_token: T) -> LineApiClientBuilder {
+        LineApiClientBuilder::new(channel_access_token.into())
+    }
+
+    /// 底層的 HTTP 用戶端，clone 後與此客戶端共用連線池
+    pub fn http_client(&self) -> &Client {
+        &self.client
+    }
+
+    /// 透過指定的 HTTP 代理連線到 LINE API
+    pub fn with_proxy(
+        channel_access_token: String,
+        proxy: &ProxyConfig,
+    ) -> Result<Self, LineApiError> {
+        Self::builder(channel_access_token)
+            .proxy(proxy.clone())
+            .build()
+    }
+
+    /// 將 API 呼叫結果回報到行程內統計
+    pub fn with_stats(mut self, stats: Arc<StatsAggregator>) -> Self {
+        self.stats = Some(stats);
+        self
+    }
+
+    /// 與應用程式共用指標
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// API 呼叫失敗時回報錯誤
+    pub fn with_error_reporter(mut self, reporter: Arc<dyn ErrorReporter>) -> Self {
+        self.error_reporter = Some(reporter);
+        self
+    }
+
+    /// 群發與 rich menu 異動（預設、大量連結與批次操作）寫入稽核紀錄
+    pub fn with_audit_log(mut self, audit_log: AuditLog) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
+    /// 成功送出的 reply、push 與 multicast 訊息寫入對話紀錄，見 [`SendOptions::user_id`]
+    pub fn with_conversation_log(mut self, conversation_log: ConversationLogger) -> Self {
+        self.conversation_log = Some(conversation_log);
+        self
     }
 
     pub async fn reply_message(
         &self,
         reply_token: &str,
         messages: Vec<OutgoingMessage>,
-    ) -> Result<(), LineApiError> {
+    ) -> Result<SendResponse, LineApiError> {
+        self.reply_message_with_options(reply_token, messages, &SendOptions::default())
+            .await
+    }
+
+    /// reply API 不支援 `retry_key` 與 `custom_aggregation_units`，這兩項會被忽略
+    pub async fn reply_message_with_options(
+        &self,
+        reply_token: &str,
+        messages: Vec<OutgoingMessage>,
+        options: &SendOptions,
+    ) -> Result<SendResponse, LineApiError> {
+        self.validate_messages(&messages)?;
         let request = ReplyMessageRequest {
             reply_token: reply_token.to_string(),
             messages,
-            notification_disabled: None,
+            notification_disabled: options.notification_disabled,
         };
 
-        let url = format!("{}/message/reply", LINE_API_BASE_URL);
-        let response = self.send_request(&url, &request).await?;
-        self.handle_response(response).await
+        let url = format!("{}/message/reply", self.base_url);
+        let response = self.post_message("reply", &url, &request, None).await?;
+        if let Some(user_id) = &options.user_id {
+            self.record_outgoing(user_id, &request.messages).await;
+        }
+        Ok(response)
     }
 
     pub async fn push_message(
         &self,
         to: &str,
         messages: Vec<OutgoingMessage>,
-    ) -> Result<(), LineApiError> {
+    ) -> Result<SendResponse, LineApiError> {
+        self.push_message_with_options(to, messages, &SendOptions::default())
+            .await
+    }
+
+    pub async fn push_message_with_options(
+        &self,
+        to: &str,
+        messages: Vec<OutgoingMessage>,
+        options: &SendOptions,
+    ) -> Result<SendResponse, LineApiError> {
+        self.validate_messages(&messages)?;
         let request = PushMessageRequest {
             to: to.to_string(),
             messages,
-            notification_disabled: None,
+            notification_disabled: options.notification_disabled,
+            custom_aggregation_units: options.custom_aggregation_units.clone(),
+        };
+
+        // 有離線暫存區時一律帶 retry key，連線中斷前 LINE 已收到的訊息重送時不會重複
+        let mut options = options.clone();
+        if self.offline_buffer.is_some() && options.retry_key.is_none() {
+            options.retry_key = Some(random_uuid());
+        }
+
+        let url = format!("{}/message/push", self.base_url);
+        let result = self
+            .post_message("push", &url, &request, options.retry_key.as_deref())
+            .await;
+
+        if result.is_ok() {
+            let user_id = options.user_id.as_deref().unwrap_or(&request.to);
+            self.record_outgoing(user_id, &request.messages).await;
+        }
+        if let (Err(e), Some(buffer)) = (&result, &self.offline_buffer)
+            && e.network_error
+        {
+            let entry = BufferedPush {
+                to: request.to,
+                messages: request.messages,
+                options,
+                buffered_at: Utc::now(),
+            };
+            match buffer.store(entry).await {
+                Ok(()) => warn!("LINE API unreachable, push message buffered for retry"),
+                Err(store_error) => error!("Failed to buffer push message: {}", store_error),
+            }
+        }
+
+        result
+    }
+
+    /// 啟動背景工作，定期重送離線暫存區中的 push 訊息
+    pub fn start_offline_retry(&self, interval: Duration) {
+        let Some(buffer) = self.offline_buffer.clone() else {
+            return;
+        };
+        let client = self.clone();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                client.retry_buffered(&buffer).await;
+            }
+        });
+    }
+
+    async fn retry_buffered(&self, buffer: &OfflineBuffer) {
+        let pending = match buffer.take_pending().await {
+            Ok(pending) => pending,
+            Err(e) => {
+                error!("Failed to read offline buffer: {}", e);
+                return;
+            }
         };
 
-        let url = format!("{}/message/push", LINE_API_BASE_URL);
-        let response = self.send_request(&url, &request).await?;
-        self.handle_response(response).await
+        let url = format!("{}/message/push", self.base_url);
+        let mut iter = pending.into_iter();
+        while let Some(mut entry) = iter.next() {
+            // 舊版暫存的訊息沒有 retry key，補上後放回時一併保存
+            let retry_key = entry
+                .options
+                .retry_key
+                .get_or_insert_with(random_uuid)
+                .clone();
+            let request = PushMessageRequest {
+                to: entry.to.clone(),
+                messages: entry.messages.clone(),
+                notification_disabled: entry.options.notification_disabled,
+                custom_aggregation_units: entry.options.custom_aggregation_units.clone(),
+            };
+
+            // 沿用原本的 retry key，LINE 已收過的訊息不會重複送出
+            match self
+                .post_message("push", &url, &request, Some(&retry_key))
+                .await
+            {
+                Ok(_) => {
+                    debug!("Resent buffered push message");
+                    let user_id = entry.options.user_id.as_deref().unwrap_or(&entry.to);
+                    self.record_outgoing(user_id, &entry.messages).await;
+                }
+                // 仍無法連線時保留原本的暫存時間與順序放回，其餘訊息也不再嘗試
+                Err(e) if e.network_error => {
+                    let remaining = std::iter::once(entry).chain(iter).collect();
+                    if let Err(store_error) = buffer.requeue(remaining).await {
+                        error!("Failed to re-buffer push messages: {}", store_error);
+                    }
+                    return;
+                }
+                Err(e) => error!("Dropping buffered push message: {}", e),
+            }
+        }
     }
 
     pub async fn multicast_message(
         &self,
         to: Vec<String>,
         messages: Vec<OutgoingMessage>,
-    ) -> Result<(), LineApiError> {
+    ) -> Result<SendResponse, LineApiError> {
+        self.multicast_message_with_options(to, messages, &SendOptions::default())
+            .await
+    }
+
+    pub async fn multicast_message_with_options(
+        &self,
+        to: Vec<String>,
+        messages: Vec<OutgoingMessage>,
+        options: &SendOptions,
+    ) -> Result<SendResponse, LineApiError> {
+        self.validate_messages(&messages)?;
+        check_limit("to", to.len(), message_limits::MAX_MULTICAST_RECIPIENTS).map_err(|e| {
+            LineApiError {
+                message: format!("Invalid multicast request: {}", e),
+                status_code: None,
+                network_error: false,
+            }
+        })?;
         let request = MulticastMessageRequest {
             to,
             messages,
-            notification_disabled: None,
+            notification_disabled: options.notification_disabled,
+            custom_aggregation_units: options.custom_aggregation_units.clone(),
         };
 
-        let url = format!("{}/message/multicast", LINE_API_BASE_URL);
-        let response = self.send_request(&url, &request).await?;
-        self.handle_response(response).await
+        let url = format!("{}/message/multicast", self.base_url);
+        let response = self
+            .post_message("multicast", &url, &request, options.retry_key.as_deref())
+            .await?;
+        self.record_multicast(request.to, request.messages).await;
+        Ok(response)
     }
 
-    pub async fn get_profile(&self, user_id: &str) -> Result<serde_json::Value, LineApiError> {
-        let url = format!("{}/profile/{}", LINE_API_BASE_URL, user_id);
+    /// 傳送給所有加入好友的使用者
+    pub async fn broadcast_message(
+        &self,
+        messages: Vec<OutgoingMessage>,
+    ) -> Result<SendResponse, LineApiError> {
+        self.broadcast_message_with_options(messages, &SendOptions::default())
+            .await
+    }
 
-        let response = self
-            .client
-            .get(&url)
-            .header(
-                "Authorization",
-                format!("Bearer {}", self.channel_access_token),
-            )
-            .send()
+    /// broadcast 不支援 `custom_aggregation_units`，設定時會被忽略
+    pub async fn broadcast_message_with_options(
+        &self,
+        messages: Vec<OutgoingMessage>,
+        options: &SendOptions,
+    ) -> Result<SendResponse, LineApiError> {
+        let entry = AuditEntry::new("broadcast").detail(format!("{} messages", messages.len()));
+        let result = async {
+            self.validate_messages(&messages)?;
+            let request = BroadcastMessageRequest {
+                messages,
+                notification_disabled: options.notification_disabled,
+            };
+
+            let url = format!("{}/message/broadcast", self.base_url);
+            self.post_message("broadcast", &url, &request, options.retry_key.as_deref())
+                .await
+        }
+        .await;
+        self.audit(entry, &result).await;
+        result
+    }
+
+    /// 傳送給受眾或符合篩選條件的好友；LINE 在背景處理，
+    /// 以回傳的請求 ID 呼叫 [`wait_for_narrowcast`](Self::wait_for_narrowcast) 查詢結果
+    pub async fn narrowcast_message(
+        &self,
+        request: NarrowcastMessageRequest,
+    ) -> Result<SendResponse, LineApiError> {
+        self.narrowcast_message_with_options(request, &SendOptions::default())
             .await
-            .map_err(|e| LineApiError {
-                message: format!("Failed to send request: {}", e),
-                status_code: None,
-            })?;
+    }
 
-        if response.status().is_success() {
-            let profile = response.json().await.map_err(|e| LineApiError {
-                message: format!("Failed to parse profile response: {}", e),
-                status_code: None,
-            })?;
-            Ok(profile)
-        } else {
-            let status_code = response.status().as_u16();
-            let error_text = response
-                .text()
+    /// narrowcast 不支援 `custom_aggregation_units`，設定時會被忽略；
+    /// `notification_disabled` 有設定時優先於請求中的值
+    pub async fn narrowcast_message_with_options(
+        &self,
+        mut request: NarrowcastMessageRequest,
+        options: &SendOptions,
+    ) -> Result<SendResponse, LineApiError> {
+        let entry =
+            AuditEntry::new("narrowcast").detail(format!("{} messages", request.messages.len()));
+        let result = async {
+            self.validate_messages(&request.messages)?;
+            if options.notification_disabled.is_some() {
+                request.notification_disabled = options.notification_disabled;
+            }
+            let url = format!("{}/message/narrowcast", self.base_url);
+            self.post_message("narrowcast", &url, &request, options.retry_key.as_deref())
                 .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            Err(LineApiError {
-                message: format!("Profile API error: {}", error_text),
-                status_code: Some(status_code),
-            })
         }
+        .await;
+        self.audit(entry, &result).await;
+        result
     }
 
-    async fn send_request<T: serde::Serialize>(
+    pub async fn get_profile(&self, user_id: &str) -> Result<serde_json::Value, LineApiError> {
+        let url = format!("{}/profile/{}", self.base_url, user_id);
+        self.get_json("profile", &url).await
+    }
+
+    /// 下載使用者傳送的圖片、影片、音訊或檔案內容
+    pub async fn get_message_content(
         &self,
-        url: &str,
-        request: &T,
-    ) -> Result<Response, LineApiError> {
-        self.client
-            .post(url)
-            .header(
-                "Authorization",
-                format!("Bearer {}", self.channel_access_token),
-            )
-            .header("Content-Type", "application/json")
-            .json(request)
-            .send()
+        message_id: &str,
+    ) -> Result<MessageContent, LineApiError> {
+        self.get_message_content_with_limit(message_id, usize::MAX)
             .await
-            .map_err(|e| LineApiError {
-                message: format!("Failed to send request: {}", e),
+    }
+
+    /// 下載訊息內容，超過 `max_bytes` 時中止下載並回傳錯誤
+    pub async fn get_message_content_with_limit(
+        &self,
+        message_id: &str,
+        max_bytes: usize,
+    ) -> Result<MessageContent, LineApiError> {
+        let url = format!("{}/message/{}/content", self.data_base_url, message_id);
+        self.throttle("content").await;
+        let start = Instant::now();
+        let result = async {
+            let response = self
+                .client
+                .get(&url)
+                .timeout(self.request_timeout)
+                .header(
+                    "Authorization",
+                    format!("Bearer {}", self.channel_access_token),
+                )
+                .send()
+                .await
+                .map_err(|e| LineApiError {
+                    message: format!("Failed to send request: {}", e),
+                    status_code: None,
+                    network_error: true,
+                })?;
+            log_line_request_id("content", &response);
+
+            if !response.status().is_success() {
+                return Err(self.error_from_response(response).await);
+            }
+            // 影片與音訊還在轉檔，先以 get_content_transcoding_status 等待完成
+            if response.status() == reqwest::StatusCode::ACCEPTED {
+                return Err(LineApiError {
+                    message: "Content is still being transcoded".to_string(),
+                    status_code: Some(202),
+                    network_error: false,
+                });
+            }
+
+            let content_type = response
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+            let too_large = || LineApiError {
+                message: format!("Content is larger than {} bytes", max_bytes),
                 status_code: None,
+                network_error: false,
+            };
+            if response
+                .content_length()
+                .is_some_and(|length| length > max_bytes as u64)
+            {
+                return Err(too_large());
+            }
+            let mut response = response;
+            let mut data = bytes::BytesMut::new();
+            while let Some(chunk) = response.chunk().await.map_err(|e| LineApiError {
+                message: format!("Failed to read content: {}", e),
+                status_code: None,
+                network_error: false,
+            })? {
+                if data.len() + chunk.len() > max_bytes {
+                    return Err(too_large());
+                }
+                data.extend_from_slice(&chunk);
+            }
+
+            Ok(MessageContent {
+                content_type,
+                data: data.freeze(),
             })
+        }
+        .await;
+        self.record_request("content", start, &result);
+        result
     }
 
-    async fn handle_response(&self, response: Response) -> Result<(), LineApiError> {
-        if response.status().is_success() {
-            Ok(())
-        } else {
-            let status_code = response.status().as_u16();
-            let error_response: ApiResponse = response.json().await.map_err(|e| LineApiError {
-                message: format!("Failed to parse error response: {}", e),
-                status_code: Some(status_code),
-            })?;
+    /// 查詢影片或音訊內容是否已可下載
+    pub async fn get_content_transcoding_status(
+        &self,
+        message_id: &str,
+    ) -> Result<TranscodingStatus, LineApiError> {
+        let url = format!(
+            "{}/message/{}/content/transcoding",
+            self.data_base_url, message_id
+        );
+        let transcoding: ContentTranscoding = self.get_json("content_transcoding", &url).await?;
+        Ok(transcoding.status)
+    }
+
+    /// 每隔 `interval` 查詢轉檔狀態，直到完成、失敗或超過 `timeout`
+    pub async fn wait_for_content_transcoding(
+        &self,
+        message_id: &str,
+        interval: Duration,
+        timeout: Duration,
+    ) -> Result<(), LineApiError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            match self.get_content_transcoding_status(message_id).await? {
+                TranscodingStatus::Succeeded => return Ok(()),
+                TranscodingStatus::Failed => {
+                    return Err(LineApiError {
+                        message: "Content transcoding failed".to_string(),
+                        status_code: None,
+                        network_error: false,
+                    });
+                }
+                TranscodingStatus::Processing => {}
+            }
+            if Instant::now() + interval > deadline {
+                return Err(LineApiError {
+                    message: format!(
+                        "Content transcoding did not finish within {}s",
+                        timeout.as_secs()
+                    ),
+                    status_code: None,
+                    network_error: false,
+                });
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    /// 取得訊息額度設定
+    pub async fn get_message_quota(&self) -> Result<MessageQuota, LineApiError> {
+        let url = format!("{}/message/quota", self.base_url);
+        self.get_json("quota", &url).await
+    }
+
+    /// 取得本月已使用的訊息數
+    pub async fn get_message_quota_consumption(&self) -> Result<QuotaConsumption, LineApiError> {
+        let url = format!("{}/message/quota/consumption", self.base_url);
+        self.get_json("quota_consumption", &url).await
+    }
+
+    /// 查詢 narrowcast 的處理進度，`request_id` 為送出時回應的 `X-Line-Request-Id`
+    pub async fn get_narrowcast_progress(
+        &self,
+        request_id: &str,
+    ) -> Result<NarrowcastProgress, LineApiError> {
+        validate_request_id(request_id)?;
+        let url = format!(
+            "{}/message/progress/narrowcast?requestId={}",
+            self.base_url, request_id
+        );
+        self.get_json("narrowcast_progress", &url).await
+    }
 
-            let error_message = error_response.message.unwrap_or_else(|| {
-                error_response
-                    .details
-                    .map(|details| {
-                        details
-                            .into_iter()
-                            .map(|e| e.message)
-                            .collect::<Vec<_>>()
-                            .join(", ")
-                    })
-                    .unwrap_or_else(|| "Unknown error".to_string())
+    /// 每隔 `interval` 查詢 narrowcast 進度，直到送完或失敗；超過 `timeout` 時回傳錯誤
+    ///
+    /// 失敗也會回傳 `Ok`，由呼叫端檢查 `phase` 與 `failed_description`。
+    pub async fn wait_for_narrowcast(
+        &self,
+        request_id: &str,
+        interval: Duration,
+        timeout: Duration,
+    ) -> Result<NarrowcastProgress, LineApiError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let progress = self.get_narrowcast_progress(request_id).await?;
+            if progress.is_finished() {
+                return Ok(progress);
+            }
+            if Instant::now() + interval > deadline {
+                return Err(LineApiError {
+                    message: format!(
+                        "Narrowcast {} is still {:?} after {}s",
+                        request_id,
+                        progress.phase,
+                        timeout.as_secs()
+                    ),
+                    status_code: None,
+                    network_error: false,
+                });
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    /// 查詢單次 broadcast 或 narrowcast 的曝光、點擊與影片播放統計
+    ///
+    /// `request_id` 為送出時回應的 `X-Line-Request-Id`；統計約在送出隔天才會出現，
+    /// 且只保留 14 天內送出的訊息。
+    pub async fn get_message_event_statistics(
+        &self,
+        request_id: &str,
+    ) -> Result<MessageEventStatistics, LineApiError> {
+        validate_request_id(request_id)?;
+        let url = format!(
+            "{}/insight/message/event?requestId={}",
+            self.base_url, request_id
+        );
+        self.get_json("message_event_statistics", &url).await
+    }
+
+    /// 查詢指定日期的好友數、可傳送人數與封鎖數
+    pub async fn get_follower_insight(
+        &self,
+        date: NaiveDate,
+    ) -> Result<FollowerInsight, LineApiError> {
+        let url = format!(
+            "{}/insight/followers?date={}",
+            self.base_url,
+            date.format("%Y%m%d")
+        );
+        self.get_json("follower_insight", &url).await
+    }
+
+    /// 逐日查詢 `from` 到 `to`（含）的好友數統計，最多 [`MAX_FOLLOWER_SERIES_DAYS`] 天
+    ///
+    /// 任一天查詢失敗時回傳錯誤；尚未計算完成的日期保留在序列中，各欄位為 `None`。
+    pub async fn get_follower_time_series(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<FollowerTimeSeries, LineApiError> {
+        let days = (to - from).num_days() + 1;
+        if !(1..=MAX_FOLLOWER_SERIES_DAYS).contains(&days) {
+            return Err(LineApiError {
+                message: format!(
+                    "Follower series must be 1 to {} days, got {} to {}",
+                    MAX_FOLLOWER_SERIES_DAYS, from, to
+                ),
+                status_code: None,
+                network_error: false,
             });
+        }
+        let mut points = Vec::with_capacity(days as usize);
+        for date in from.iter_days().take(days as usize) {
+            let insight = self.get_follower_insight(date).await?;
+            points.push(FollowerDataPoint {
+                date,
+                status: insight.status,
+                followers: insight.followers,
+                targeted_reaches: insight.targeted_reaches,
+                blocks: insight.blocks,
+            });
+        }
+        Ok(FollowerTimeSeries { points })
+    }
 
-            Err(LineApiError {
-                message: error_message,
-                status_code: Some(status_code),
-            })
+    /// 以 channel ID 與 channel secret 發行短期 channel access token（v2，有效 30 天）
+    ///
+    /// 同一個 channel 最多同時有 30 個有效的短期 token，超過時最舊的會失效。
+    pub async fn issue_channel_token(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+    ) -> Result<ChannelAccessToken, LineApiError> {
+        let url = format!("{}/v2/oauth/accessToken", self.oauth_base_url);
+        if self.dry_run {
+            info!("[dry-run] LINE API issue_channel_token POST {}", url);
+            return Err(LineApiError {
+                message: "LINE API issue_channel_token is not available in dry-run mode"
+                    .to_string(),
+                status_code: None,
+                network_error: false,
+            });
         }
+        let form = [
+            ("grant_type", "client_credentials"),
+            ("client_id", client_id),
+            ("client_secret", client_secret),
+        ];
+        let response = self
+            .send_oauth("issue_channel_token", self.client.post(&url).form(&form))
+            .await?;
+        response.json().await.map_err(|e| LineApiError {
+            message: format!("Failed to parse response: {}", e),
+            status_code: None,
+            network_error: false,
+        })
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// 撤銷 v2 channel access token；已失效的 token 也會回傳成功
+    pub async fn revoke_channel_token(&self, token: &str) -> Result<(), LineApiError> {
+        let url = format!("{}/v2/oauth/revoke", self.oauth_base_url);
+        if self.dry_run {
+            info!(
+                "[dry-run] LINE API revoke_channel_token POST {} {}",
+                url,
+                SensitiveDataMasker::mask_channel_token(token)
+            );
+            return Ok(());
+        }
+        self.send_oauth(
+            "revoke_channel_token",
+            self.client.post(&url).form(&[("access_token", token)]),
+        )
+        .await?;
+        Ok(())
+    }
 
-    #[test]
-    fn test_line_api_client_creation() {
-        let client = LineApiClient::new("test_token".to_string());
+    /// 列出目前有效的 v2.1 channel access token 的 key ID
+    ///
+    /// `client_assertion` 為以 assertion signing key 簽署的 JWT。不在清單中的 token 已失效，
+    /// 可藉此找出遺失或過期的 token。
+    pub async fn get_valid_channel_token_key_ids(
+        &self,
+        client_assertion: &str,
+    ) -> Result<ChannelAccessTokenKeyIds, LineApiError> {
+        let url = format!("{}/oauth2/v2.1/tokens/kid", self.oauth_base_url);
+        let query = [
+            ("client_assertion_type", CLIENT_ASSERTION_TYPE),
+            ("client_assertion", client_assertion),
+        ];
+        let response = self
+            .send_oauth("channel_token_key_ids", self.client.get(&url).query(&query))
+            .await?;
+        response.json().await.map_err(|e| LineApiError {
+            message: format!("Failed to parse response: {}", e),
+            status_code: None,
+            network_error: false,
+        })
+    }
+
+    /// 列出這個月使用過的自訂彙總單位，`start` 為上一頁回應的 `next`
+    pub async fn get_aggregation_unit_names(
+        &self,
+        limit: Option<u32>,
+        start: Option<&str>,
+    ) -> Result<AggregationUnitNames, LineApiError> {
+        let mut url = reqwest::Url::parse(&format!("{}/message/aggregation/list", self.base_url))
+            .map_err(|e| LineApiError {
+            message: format!("Invalid base URL: {}", e),
+            status_code: None,
+            network_error: false,
+        })?;
+        {
+            let mut query = url.query_pairs_mut();
+            if let Some(limit) = limit {
+                query.append_pair("limit", &limit.to_string());
+            }
+            if let Some(start) = start {
+                query.append_pair("start", start);
+            }
+        }
+        self.get_json("aggregation_unit_list", url.as_str()).await
+    }
+
+    /// 查詢以 `customAggregationUnits` 標記的訊息在 `from` 到 `to`（含）之間的統計
+    pub async fn get_aggregation_unit_statistics(
+        &self,
+        unit: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<AggregationUnitStatistics, LineApiError> {
+        validate_aggregation_unit(unit)?;
+        let days = (to - from).num_days() + 1;
+        if !(1..=MAX_AGGREGATION_STATISTICS_DAYS).contains(&days) {
+            return Err(LineApiError {
+                message: format!(
+                    "Statistics period must be 1 to {} days, got {} to {}",
+                    MAX_AGGREGATION_STATISTICS_DAYS, from, to
+                ),
+                status_code: None,
+                network_error: false,
+            });
+        }
+        let url = format!(
+            "{}/insight/message/event/aggregation?customAggregationUnit={}&from={}&to={}",
+            self.base_url,
+            unit,
+            from.format("%Y%m%d"),
+            to.format("%Y%m%d")
+        );
+        self.get_json("aggregation_unit_statistics", &url).await
+    }
+
+    /// 以點擊過訊息中網址的使用者建立受眾，`request_id` 為送出訊息時的 `X-Line-Request-Id`
+    pub async fn create_click_audience(
+        &self,
+        request: &CreateAudienceRequest,
+    ) -> Result<AudienceGroup, LineApiError> {
+        validate_audience_request(request)?;
+        let url = format!("{}/audienceGroup/click", self.base_url);
+        self.post_json("audience_click", &url, request).await
+    }
+
+    /// 以看過訊息的使用者建立受眾；不使用 `click_url`
+    pub async fn create_impression_audience(
+        &self,
+        request: &CreateAudienceRequest,
+    ) -> Result<AudienceGroup, LineApiError> {
+        validate_audience_request(request)?;
+        if request.click_url.is_some() {
+            return Err(LineApiError {
+                message: "Impression audiences do not accept clickUrl".to_string(),
+                status_code: None,
+                network_error: false,
+            });
+        }
+        let url = format!("{}/audienceGroup/imp", self.base_url);
+        self.post_json("audience_impression", &url, request).await
+    }
+
+    /// 取得 bot 的使用者 ID、名稱等基本資料
+    pub async fn get_bot_info(&self) -> Result<BotInfo, LineApiError> {
+        let url = format!("{}/info", self.base_url);
+        self.get_json("bot_info", &url).await
+    }
+
+    /// 取得群組名稱與圖片
+    pub async fn get_group_summary(&self, group_id: &str) -> Result<GroupSummary, LineApiError> {
+        validate_id("group ID", GroupIdValidator::validate(group_id))?;
+        let url = format!("{}/group/{}/summary", self.base_url, group_id);
+        self.get_json("group_summary", &url).await
+    }
+
+    /// 取得群組成員數
+    pub async fn get_group_member_count(&self, group_id: &str) -> Result<u64, LineApiError> {
+        validate_id("group ID", GroupIdValidator::validate(group_id))?;
+        let url = format!("{}/group/{}/members/count", self.base_url, group_id);
+        let count: MemberCount = self.get_json("group_member_count", &url).await?;
+        Ok(count.count)
+    }
+
+    /// 列出已建立的 rich menu
+    pub async fn get_rich_menu_list(&self) -> Result<Vec<RichMenuSummary>, LineApiError> {
+        let url = format!("{}/richmenu/list", self.base_url);
+        let list: RichMenuList = self.get_json("rich_menu_list", &url).await?;
+        Ok(list.richmenus)
+    }
+
+    /// 指定使用者看到的 rich menu，優先於預設 rich menu
+    pub async fn link_rich_menu(
+        &self,
+        user_id: &str,
+        rich_menu_id: &str,
+    ) -> Result<(), LineApiError> {
+        let result = async {
+            validate_id("user ID", UserIdValidator::validate(user_id))?;
+            validate_id("rich menu ID", RichMenuIdValidator::validate(rich_menu_id))?;
+            let url = format!(
+                "{}/user/{}/richmenu/{}",
+                self.base_url, user_id, rich_menu_id
+            );
+            self.send_without_body("rich_menu_link", reqwest::Method::POST, &url)
+                .await
+        }
+        .await;
+        let entry = AuditEntry::new("rich_menu.link")
+            .target(rich_menu_id)
+            .detail(SensitiveDataMasker::mask_user_id(user_id));
+        self.audit(entry, &result).await;
+        result
+    }
+
+    /// 建立前檢查 rich menu，回傳所有欄位錯誤；沒有錯誤時回傳空的清單
+    ///
+    /// 先在本地檢查大小與區域範圍，通過後再呼叫 LINE 的驗證端點檢查動作等其餘內容。
+    /// dry run 時只做本地檢查。
+    pub async fn validate_rich_menu(&self, menu: &RichMenu) -> Result<Vec<ApiError>, LineApiError> {
+        let errors = RichMenuValidator::validate(menu);
+        if !errors.is_empty() {
+            return Ok(errors);
+        }
+        let api_type = "rich_menu_validate";
+        let url = format!("{}/richmenu/validate", self.base_url);
+        if self.dry_run {
+            log_dry_run(api_type, &url, menu);
+            return Ok(Vec::new());
+        }
+
+        self.throttle(api_type).await;
+        let start = Instant::now();
+        let result = async {
+            let response = self.send_request(&url, menu, None).await?;
+            log_line_request_id(api_type, &response);
+            if response.status().is_success() {
+                return Ok(Vec::new());
+            }
+            if response.status() != reqwest::StatusCode::BAD_REQUEST {
+                return Err(self.error_from_response(response).await);
+            }
+            let error_response: ApiResponse = response.json().await.map_err(|e| LineApiError {
+                message: format!("Failed to parse error response: {}", e),
+                status_code: Some(400),
+                network_error: false,
+            })?;
+            Ok(match error_response.details {
+                Some(details) if !details.is_empty() => details,
+                _ => vec![ApiError {
+                    message: error_response
+                        .message
+                        .unwrap_or_else(|| "Invalid rich menu".to_string()),
+                    property: String::new(),
+                }],
+            })
+        }
+        .await;
+        self.record_request(api_type, start, &result);
+        result
+    }
+
+    /// 查詢 alias 對應的 rich menu
+    pub async fn get_rich_menu_alias(&self, alias_id: &str) -> Result<RichMenuAlias, LineApiError> {
+        validate_id(
+            "rich menu alias ID",
+            RichMenuAliasIdValidator::validate(alias_id),
+        )?;
+        let url = format!("{}/richmenu/alias/{}", self.base_url, alias_id);
+        self.get_json("rich_menu_alias", &url).await
+    }
+
+    /// 一次為多位使用者（最多 [`MAX_RICH_MENU_BULK_USERS`] 位）連結同一個 rich menu，LINE 會非同步處理
+    pub async fn bulk_link_rich_menu(
+        &self,
+        rich_menu_id: &str,
+        user_ids: &[String],
+    ) -> Result<(), LineApiError> {
+        let result = async {
+            validate_id("rich menu ID", RichMenuIdValidator::validate(rich_menu_id))?;
+            validate_bulk_users(user_ids)?;
+            let url = format!("{}/richmenu/bulk/link", self.base_url);
+            let request = serde_json::json!({ "richMenuId": rich_menu_id, "userIds": user_ids });
+            self.post_message("rich_menu_bulk_link", &url, &request, None)
+                .await
+                .map(|_| ())
+        }
+        .await;
+        let entry = AuditEntry::new("rich_menu.bulk_link")
+            .target(rich_menu_id)
+            .detail(format!("{} users", user_ids.len()));
+        self.audit(entry, &result).await;
+        result
+    }
+
+    /// 一次取消多位使用者的 rich menu
+    pub async fn bulk_unlink_rich_menu(&self, user_ids: &[String]) -> Result<(), LineApiError> {
+        let result = async {
+            validate_bulk_users(user_ids)?;
+            let url = format!("{}/richmenu/bulk/unlink", self.base_url);
+            let request = serde_json::json!({ "userIds": user_ids });
+            self.post_message("rich_menu_bulk_unlink", &url, &request, None)
+                .await
+                .map(|_| ())
+        }
+        .await;
+        let entry =
+            AuditEntry::new("rich_menu.bulk_unlink").detail(format!("{} users", user_ids.len()));
+        self.audit(entry, &result).await;
+        result
+    }
+
+    /// 批次替換或取消大量使用者的 rich menu，回傳用來查詢進度的 request ID
+    ///
+    /// LINE 會非同步處理，以 [`get_rich_menu_batch_progress`](Self::get_rich_menu_batch_progress)
+    /// 或 [`wait_for_rich_menu_batch`](Self::wait_for_rich_menu_batch) 追蹤結果。
+    pub async fn rich_menu_batch(
+        &self,
+        request: &RichMenuBatchRequest,
+    ) -> Result<String, LineApiError> {
+        let result = self.send_rich_menu_batch(request).await;
+        let entry = AuditEntry::new("rich_menu.batch")
+            .detail(format!("{} operations", request.operations.len()));
+        self.audit(entry, &result).await;
+        result
+    }
+
+    async fn send_rich_menu_batch(
+        &self,
+        request: &RichMenuBatchRequest,
+    ) -> Result<String, LineApiError> {
+        validate_rich_menu_batch(request)?;
+        let api_type = "rich_menu_batch";
+        let url = format!("{}/richmenu/batch", self.base_url);
+        if self.dry_run {
+            log_dry_run(api_type, &url, request);
+            return Err(LineApiError {
+                message: format!("LINE API {} is not available in dry-run mode", api_type),
+                status_code: None,
+                network_error: false,
+            });
+        }
+
+        self.throttle(api_type).await;
+        let start = Instant::now();
+        let result = async {
+            let response = self.send_request(&url, request, None).await?;
+            log_line_request_id(api_type, &response);
+            if !response.status().is_success() {
+                return Err(self.error_from_response(response).await);
+            }
+            response
+                .headers()
+                .get("x-line-request-id")
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+                .ok_or_else(|| LineApiError {
+                    message: "Missing x-line-request-id in rich menu batch response".to_string(),
+                    status_code: None,
+                    network_error: false,
+                })
+        }
+        .await;
+        self.record_request(api_type, start, &result);
+        result
+    }
+
+    /// 只檢查批次操作內容（rich menu 是否存在等），不實際執行
+    pub async fn validate_rich_menu_batch(
+        &self,
+        request: &RichMenuBatchRequest,
+    ) -> Result<(), LineApiError> {
+        validate_rich_menu_batch(request)?;
+        let url = format!("{}/richmenu/validate/batch", self.base_url);
+        self.post_message("rich_menu_batch_validate", &url, request, None)
+            .await
+            .map(|_| ())
+    }
+
+    /// 查詢 rich menu 批次操作的進度
+    pub async fn get_rich_menu_batch_progress(
+        &self,
+        request_id: &str,
+    ) -> Result<RichMenuBatchProgress, LineApiError> {
+        validate_request_id(request_id)?;
+        let url = format!(
+            "{}/richmenu/progress/batch?requestId={}",
+            self.base_url, request_id
+        );
+        self.get_json("rich_menu_batch_progress", &url).await
+    }
+
+    /// 每隔 `interval` 查詢批次操作進度，直到完成或失敗；超過 `timeout` 時回傳錯誤
+    pub async fn wait_for_rich_menu_batch(
+        &self,
+        request_id: &str,
+        interval: Duration,
+        timeout: Duration,
+    ) -> Result<RichMenuBatchProgress, LineApiError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let progress = self.get_rich_menu_batch_progress(request_id).await?;
+            if progress.is_finished() {
+                return Ok(progress);
+            }
+            if Instant::now() + interval > deadline {
+                return Err(LineApiError {
+                    message: format!(
+                        "Rich menu batch {} is still {:?} after {}s",
+                        request_id,
+                        progress.phase,
+                        timeout.as_secs()
+                    ),
+                    status_code: None,
+                    network_error: false,
+                });
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    /// 取消使用者的 rich menu，改為顯示預設 rich menu
+    pub async fn unlink_rich_menu(&self, user_id: &str) -> Result<(), LineApiError> {
+        let result = async {
+            validate_id("user ID", UserIdValidator::validate(user_id))?;
+            let url = format!("{}/user/{}/richmenu", self.base_url, user_id);
+            self.send_without_body("rich_menu_unlink", reqwest::Method::DELETE, &url)
+                .await
+        }
+        .await;
+        let entry =
+            AuditEntry::new("rich_menu.unlink").detail(SensitiveDataMasker::mask_user_id(user_id));
+        self.audit(entry, &result).await;
+        result
+    }
+
+    /// 設定所有使用者的預設 rich menu
+    pub async fn set_default_rich_menu(&self, rich_menu_id: &str) -> Result<(), LineApiError> {
+        let result = async {
+            validate_id("rich menu ID", RichMenuIdValidator::validate(rich_menu_id))?;
+            let url = format!("{}/user/all/richmenu/{}", self.base_url, rich_menu_id);
+            self.send_without_body("rich_menu_default", reqwest::Method::POST, &url)
+                .await
+        }
+        .await;
+        let entry = AuditEntry::new("rich_menu.set_default").target(rich_menu_id);
+        self.audit(entry, &result).await;
+        result
+    }
+
+    pub async fn cancel_default_rich_menu(&self) -> Result<(), LineApiError> {
+        let url = format!("{}/user/all/richmenu", self.base_url);
+        let result = self
+            .send_without_body("rich_menu_default", reqwest::Method::DELETE, &url)
+            .await;
+        self.audit(AuditEntry::new("rich_menu.cancel_default"), &result)
+            .await;
+        result
+    }
+
+    /// 有設定對話紀錄時寫入送出的訊息
+    async fn record_outgoing(&self, user_id: &str, messages: &[OutgoingMessage]) {
+        if let Some(conversation_log) = &self.conversation_log {
+            conversation_log.record_outgoing(user_id, messages).await;
+        }
+    }
+
+    /// 同時為 multicast 的每個收件人寫入送出的訊息
+    async fn record_multicast(&self, to: Vec<String>, messages: Vec<OutgoingMessage>) {
+        let Some(conversation_log) = &self.conversation_log else {
+            return;
+        };
+        let messages = Arc::new(messages);
+        let mut writes = tokio::task::JoinSet::new();
+        for user_id in to {
+            let conversation_log = conversation_log.clone();
+            let messages = messages.clone();
+            writes
+                .spawn(async move { conversation_log.record_outgoing(&user_id, &messages).await });
+        }
+        while writes.join_next().await.is_some() {}
+    }
+
+    /// 有設定稽核紀錄時依結果寫入
+    async fn audit<T>(&self, entry: AuditEntry, result: &Result<T, LineApiError>) {
+        if let Some(audit_log) = &self.audit_log {
+            audit_log.record(entry.result(result)).await;
+        }
+    }
+
+    async fn post_message<T: serde::Serialize>(
+        &self,
+        api_type: &str,
+        url: &str,
+        request: &T,
+        retry_key: Option<&str>,
+    ) -> Result<SendResponse, LineApiError> {
+        if self.dry_run {
+            let start = Instant::now();
+            log_dry_run(api_type, url, request);
+            let result = Ok(SendResponse::default());
+            self.record_request(api_type, start, &result);
+            return result;
+        }
+
+        self.throttle(api_type).await;
+        let start = Instant::now();
+        let result = match self.send_request(url, request, retry_key).await {
+            // 相同 retry key 的請求已被接受過，回傳當時的請求 ID
+            Ok(response)
+                if retry_key.is_some() && response.status() == reqwest::StatusCode::CONFLICT =>
+            {
+                log_line_request_id(api_type, &response);
+                info!(
+                    "LINE API {} request already accepted for retry key",
+                    api_type
+                );
+                Ok(SendResponse {
+                    request_id: header_value(&response, "x-line-accepted-request-id"),
+                })
+            }
+            Ok(response) => {
+                log_line_request_id(api_type, &response);
+                let request_id = header_value(&response, "x-line-request-id");
+                self.handle_response(response)
+                    .await
+                    .map(|()| SendResponse { request_id })
+            }
+            Err(e) => Err(e),
+        };
+        self.record_request(api_type, start, &result);
+        result
+    }
+
+    /// 送出 JSON 並解析回應；dry run 時無法產生回應，回傳錯誤
+    async fn post_json<T: serde::Serialize, R: DeserializeOwned>(
+        &self,
+        api_type: &str,
+        url: &str,
+        request: &T,
+    ) -> Result<R, LineApiError> {
+        if self.dry_run {
+            log_dry_run(api_type, url, request);
+            return Err(LineApiError {
+                message: format!("LINE API {} is not available in dry-run mode", api_type),
+                status_code: None,
+                network_error: false,
+            });
+        }
+
+        self.throttle(api_type).await;
+        let start = Instant::now();
+        let result = async {
+            let response = self.send_request(url, request, None).await?;
+            log_line_request_id(api_type, &response);
+
+            if !response.status().is_success() {
+                return Err(self.error_from_response(response).await);
+            }
+
+            response.json::<R>().await.map_err(|e| LineApiError {
+                message: format!("Failed to parse response: {}", e),
+                status_code: None,
+                network_error: false,
+            })
+        }
+        .await;
+        self.record_request(api_type, start, &result);
+        result
+    }
+
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        api_type: &str,
+        url: &str,
+    ) -> Result<T, LineApiError> {
+        self.throttle(api_type).await;
+        let start = Instant::now();
+        let result = async {
+            let response = self
+                .client
+                .get(url)
+                .timeout(self.request_timeout)
+                .header(
+                    "Authorization",
+                    format!("Bearer {}", self.channel_access_token),
+                )
+                .send()
+                .await
+                .map_err(|e| LineApiError {
+                    message: format!("Failed to send request: {}", e),
+                    status_code: None,
+                    network_error: true,
+                })?;
+            log_line_request_id(api_type, &response);
+
+            if !response.status().is_success() {
+                return Err(self.error_from_response(response).await);
+            }
+
+            response.json::<T>().await.map_err(|e| LineApiError {
+                message: format!("Failed to parse response: {}", e),
+                status_code: None,
+                network_error: false,
+            })
+        }
+        .await;
+        self.record_request(api_type, start, &result);
+        result
+    }
+
+    /// 送出 OAuth 端點的請求；這些端點以表單或 query 驗證身分，不帶 channel access token
+    async fn send_oauth(
+        &self,
+        api_type: &str,
+        request: reqwest::RequestBuilder,
+    ) -> Result<Response, LineApiError> {
+        let start = Instant::now();
+        let result = async {
+            let response = request
+                .timeout(self.request_timeout)
+                .send()
+                .await
+                .map_err(|e| LineApiError {
+                    message: format!("Failed to send request: {}", e),
+                    status_code: None,
+                    network_error: true,
+                })?;
+            log_line_request_id(api_type, &response);
+            if response.status().is_success() {
+                return Ok(response);
+            }
+            let status_code = response.status().as_u16();
+            let message = match response.json::<OAuthErrorResponse>().await {
+                Ok(error) => match error.error_description {
+                    Some(description) => format!("{}: {}", error.error, description),
+                    None => error.error,
+                },
+                Err(e) => format!("Failed to parse error response: {}", e),
+            };
+            Err(LineApiError {
+                message,
+                status_code: Some(status_code),
+                network_error: false,
+            })
+        }
+        .await;
+        self.record_request(api_type, start, &result);
+        result
+    }
+
+    async fn send_without_body(
+        &self,
+        api_type: &str,
+        method: reqwest::Method,
+        url: &str,
+    ) -> Result<(), LineApiError> {
+        if self.dry_run {
+            info!("[dry-run] LINE API {} {} {}", api_type, method, url);
+            return Ok(());
+        }
+
+        self.throttle(api_type).await;
+        let start = Instant::now();
+        let result = async {
+            let response = self
+                .client
+                .request(method, url)
+                .timeout(self.request_timeout)
+                .header(
+                    "Authorization",
+                    format!("Bearer {}", self.channel_access_token),
+                )
+                .send()
+                .await
+                .map_err(|e| LineApiError {
+                    message: format!("Failed to send request: {}", e),
+                    status_code: None,
+                    network_error: true,
+                })?;
+            log_line_request_id(api_type, &response);
+            self.handle_response(response).await
+        }
+        .await;
+        self.record_request(api_type, start, &result);
+        result
+    }
+
+    fn validate_messages(&self, messages: &[OutgoingMessage]) -> Result<(), LineApiError> {
+        let Some(validator) = &self.message_validator else {
+            return Ok(());
+        };
+        validator.validate(messages).map_err(|e| LineApiError {
+            message: format!("Invalid outgoing message: {}", e),
+            status_code: None,
+            network_error: false,
+        })
+    }
+
+    async fn throttle(&self, api_type: &str) {
+        if let Some(limiter) = &self.send_rate_limiter {
+            limiter.acquire(api_type).await;
+        }
+    }
+
+    fn record_request<T>(&self, api_type: &str, start: Instant, result: &Result<T, LineApiError>) {
+        self.metrics
+            .record_line_api_request(api_type, start.elapsed(), result.is_ok());
+        if let Some(stats) = &self.stats {
+            stats.record_line_api_request(result.is_ok());
+        }
+        if let (Err(e), Some(reporter)) = (result, &self.error_reporter) {
+            reporter.report(e, &ErrorContext::line_api(api_type));
+        }
+    }
+
+    async fn send_request<T: serde::Serialize>(
+        &self,
+        url: &str,
+        request: &T,
+        retry_key: Option<&str>,
+    ) -> Result<Response, LineApiError> {
+        let mut builder = self
+            .client
+            .post(url)
+            .timeout(self.request_timeout)
+            .header(
+                "Authorization",
+                format!("Bearer {}", self.channel_access_token),
+            )
+            .header("Content-Type", "application/json");
+        if let Some(retry_key) = retry_key {
+            builder = builder.header("X-Line-Retry-Key", retry_key);
+        }
+        builder
+            .json(request)
+            .send()
+            .await
+            .map_err(|e| LineApiError {
+                message: format!("Failed to send request: {}", e),
+                status_code: None,
+                network_error: true,
+            })
+    }
+
+    async fn handle_response(&self, response: Response) -> Result<(), LineApiError> {
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(self.error_from_response(response).await)
+        }
+    }
+
+    async fn error_from_response(&self, response: Response) -> LineApiError {
+        let status_code = response.status().as_u16();
+        let error_response: ApiResponse = match response.json().await {
+            Ok(error_response) => error_response,
+            Err(e) => {
+                return LineApiError {
+                    message: format!("Failed to parse error response: {}", e),
+                    status_code: Some(status_code),
+                    network_error: false,
+                };
+            }
+        };
+
+        let error_message = error_response.message.unwrap_or_else(|| {
+            error_response
+                .details
+                .map(|details| {
+                    details
+                        .into_iter()
+                        .map(|e| e.message)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })
+                .unwrap_or_else(|| "Unknown error".to_string())
+        });
+
+        LineApiError {
+            message: error_message,
+            status_code: Some(status_code),
+            network_error: false,
+        }
+    }
+}
+
+/// 自訂彙總單位名稱為 1 到 30 個英數字或 `_`
+fn validate_aggregation_unit(unit: &str) -> Result<(), LineApiError> {
+    if unit.is_empty()
+        || unit.len() > 30
+        || !unit.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        return Err(LineApiError {
+            message: format!("Invalid custom aggregation unit: {}", unit),
+            status_code: None,
+            network_error: false,
+        });
+    }
+    Ok(())
+}
+
+/// `X-Line-Request-Id` 只含英數字與 `-`
+fn validate_request_id(request_id: &str) -> Result<(), LineApiError> {
+    if request_id.is_empty()
+        || !request_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        return Err(LineApiError {
+            message: format!("Invalid request ID: {}", request_id),
+            status_code: None,
+            network_error: false,
+        });
+    }
+    Ok(())
+}
+
+fn validate_audience_request(request: &CreateAudienceRequest) -> Result<(), LineApiError> {
+    validate_request_id(&request.request_id)?;
+    let length = request.description.chars().count();
+    if request.description.trim().is_empty() || length > MAX_AUDIENCE_DESCRIPTION_CHARS {
+        return Err(LineApiError {
+            message: format!(
+                "Audience description must be 1 to {} characters, got {}",
+                MAX_AUDIENCE_DESCRIPTION_CHARS, length
+            ),
+            status_code: None,
+            network_error: false,
+        });
+    }
+    Ok(())
+}
+
+fn validate_rich_menu_batch(request: &RichMenuBatchRequest) -> Result<(), LineApiError> {
+    let count = request.operations.len();
+    if count == 0 || count > MAX_RICH_MENU_BATCH_OPERATIONS {
+        return Err(LineApiError {
+            message: format!(
+                "Rich menu batch needs 1 to {} operations, got {}",
+                MAX_RICH_MENU_BATCH_OPERATIONS, count
+            ),
+            status_code: None,
+            network_error: false,
+        });
+    }
+    for operation in &request.operations {
+        match operation {
+            RichMenuBatchOperation::Link { from, to } => {
+                validate_id("rich menu ID", RichMenuIdValidator::validate(from))?;
+                validate_id("rich menu ID", RichMenuIdValidator::validate(to))?;
+            }
+            RichMenuBatchOperation::Unlink { from } => {
+                validate_id("rich menu ID", RichMenuIdValidator::validate(from))?;
+            }
+            RichMenuBatchOperation::UnlinkAll => {}
+        }
+    }
+    if let Some(key) = &request.resume_request_key {
+        validate_request_id(key)?;
+    }
+    Ok(())
+}
+
+fn validate_bulk_users(user_ids: &[String]) -> Result<(), LineApiError> {
+    if user_ids.is_empty() || user_ids.len() > MAX_RICH_MENU_BULK_USERS {
+        return Err(LineApiError {
+            message: format!(
+                "Bulk rich menu requests need 1 to {} users, got {}",
+                MAX_RICH_MENU_BULK_USERS,
+                user_ids.len()
+            ),
+            status_code: None,
+            network_error: false,
+        });
+    }
+    for user_id in user_ids {
+        validate_id("user ID", UserIdValidator::validate(user_id))?;
+    }
+    Ok(())
+}
+
+/// 在呼叫 API 前拒絕格式錯誤的 ID，避免組出錯誤的路徑
+fn validate_id(kind: &str, result: Result<(), ValidationError>) -> Result<(), LineApiError> {
+    result.map_err(|e| LineApiError {
+        message: format!("Invalid {}: {}", kind, e),
+        status_code: None,
+        network_error: false,
+    })
+}
+
+/// dry-run 模式下記錄原本要送出的請求（已遮罩）
+fn log_dry_run<T: serde::Serialize>(api_type: &str, url: &str, request: &T) {
+    match serde_json::to_value(request) {
+        Ok(mut body) => {
+            SensitiveDataMasker::mask_json_identifiers(&mut body);
+            info!("[dry-run] LINE API {} POST {} {}", api_type, url, body);
+        }
+        Err(e) => info!(
+            "[dry-run] LINE API {} POST {} (unserializable: {})",
+            api_type, url, e
+        ),
+    }
+}
+
+fn header_value(response: &Response, name: &str) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+/// 記錄 LINE 回傳的請求 ID，與目前 span 的關聯 ID 一起輸出方便對照；向 LINE 詢問問題時需要這個 ID
+fn log_line_request_id(api_type: &str, response: &Response) {
+    if let Some(line_request_id) = response
+        .headers()
+        .get("x-line-request-id")
+        .and_then(|value| value.to_str().ok())
+    {
+        info!(
+            "LINE API {} responded {} (x-line-request-id: {})",
+            api_type,
+            response.status(),
+            line_request_id
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{NarrowcastPhase, RichMenuBatchPhase};
+
+    #[test]
+    fn test_line_api_client_creation() {
+        let client = LineApiClient::new("test_token".to_string());
         assert_eq!(client.channel_access_token, "test_token");
     }
+
+    #[test]
+    fn test_line_api_client_with_proxy() {
+        let proxy = ProxyConfig::new("http://proxy.example.com:8080").basic_auth("user", "pass");
+        let client = LineApiClient::with_proxy("test_token".to_string(), &proxy).unwrap();
+        assert_eq!(client.channel_access_token, "test_token");
+
+        assert!(LineApiClient::with_proxy("t".to_string(), &ProxyConfig::new("::bad")).is_err());
+    }
+
+    #[test]
+    fn test_proxy_config_debug_hides_password() {
+        let proxy = ProxyConfig::new("http://proxy.example.com").basic_auth("user", "secret");
+        let debug = format!("{:?}", proxy);
+        assert!(!debug.contains("secret"));
+        assert!(debug.contains("user"));
+    }
+
+    #[test]
+    fn test_line_api_client_builder() {
+        let client = LineApiClient::builder("test_token")
+            .base_url("http://localhost:8080/v2/bot/")
+            .connect_timeout(Duration::from_secs(1))
+            .request_timeout(Duration::from_secs(5))
+            .pool_max_idle_per_host(4)
+            .user_agent("test-agent")
+            .build()
+            .unwrap();
+
+        assert_eq!(client.base_url, "http://localhost:8080/v2/bot");
+        assert_eq!(client.channel_access_token, "test_token");
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_does_not_send() {
+        let stats = Arc::new(StatsAggregator::new());
+        let metrics = Metrics::new();
+        // 無法連線的位址，若實際送出會失敗
+        let client = LineApiClient::builder("test_token")
+            .base_url("http://127.0.0.1:1/v2/bot")
+            .dry_run(true)
+            .build()
+            .unwrap()
+            .with_stats(stats.clone())
+            .with_metrics(metrics.clone());
+
+        let response = client
+            .push_message(
+                "U1234567890abcdef1234567890abcdef",
+                vec![OutgoingMessage::text("hi")],
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.request_id, None);
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.line_api_requests, 1);
+        assert_eq!(snapshot.line_api_errors, 0);
+        assert_eq!(metrics.snapshot().line_api_requests, 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_profile_recorded_in_stats() {
+        let stats = Arc::new(StatsAggregator::new());
+        let client = LineApiClient::builder("test_token")
+            .base_url("http://127.0.0.1:1/v2/bot")
+            .build()
+            .unwrap()
+            .with_stats(stats.clone());
+
+        let error = client
+            .get_profile("U1234567890abcdef1234567890abcdef")
+            .await
+            .unwrap_err();
+        assert!(error.network_error);
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.line_api_requests, 1);
+        assert_eq!(snapshot.line_api_errors, 1);
+    }
+
+    #[tokio::test]
+    async fn test_sent_messages_recorded_after_success() {
+        use crate::storage::{ConversationMasking, HistoryQuery, MemoryStorage, Storage as _};
+
+        let storage = Arc::new(MemoryStorage::new());
+        let conversation_log = ConversationLogger::new(storage.clone(), ConversationMasking::None);
+        let client = LineApiClient::builder("test_token")
+            .base_url("http://127.0.0.1:1/v2/bot")
+            .dry_run(true)
+            .build()
+            .unwrap()
+            .with_conversation_log(conversation_log.clone());
+        let history = |user_id: &'static str| {
+            let storage = storage.clone();
+            async move {
+                storage
+                    .conversation_history(&HistoryQuery::new(user_id))
+                    .await
+                    .unwrap()
+            }
+        };
+
+        // reply 只在指定使用者時記錄
+        client
+            .reply_message("token", vec![OutgoingMessage::text("anonymous")])
+            .await
+            .unwrap();
+        client
+            .reply_message_with_options(
+                "token",
+                vec![OutgoingMessage::text("reply")],
+                &SendOptions::new().user_id("U1"),
+            )
+            .await
+            .unwrap();
+        client
+            .push_message_with_options(
+                "C1234567890abcdef1234567890abcdef",
+                vec![OutgoingMessage::text("push")],
+                &SendOptions::new().user_id("U1"),
+            )
+            .await
+            .unwrap();
+        client
+            .multicast_message(
+                vec!["U1".to_string(), "U2".to_string()],
+                vec![OutgoingMessage::text("multicast")],
+            )
+            .await
+            .unwrap();
+
+        let texts: Vec<_> = history("U1")
+            .await
+            .into_iter()
+            .map(|entry| entry.message["text"].clone())
+            .collect();
+        assert_eq!(texts, ["reply", "push", "multicast"]);
+        assert_eq!(history("U2").await.len(), 1);
+
+        // 送出失敗時不記錄
+        let client = LineApiClient::builder("test_token")
+            .base_url("http://127.0.0.1:1/v2/bot")
+            .build()
+            .unwrap()
+            .with_conversation_log(conversation_log);
+        client
+            .push_message(
+                "U3234567890abcdef1234567890abcdef",
+                vec![OutgoingMessage::text("lost")],
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            history("U3234567890abcdef1234567890abcdef")
+                .await
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_group_id_rejected() {
+        let client = LineApiClient::builder("test_token")
+            .base_url("http://127.0.0.1:1/v2/bot")
+            .build()
+            .unwrap();
+        let err = client.get_group_summary("../profile/U1").await.unwrap_err();
+        assert!(err.message.contains("Invalid group ID"));
+        assert!(!err.network_error);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_action_url_rejected_before_send() {
+        use crate::models::{Action, TemplateType};
+
+        let client = LineApiClient::builder("test_token")
+            .dry_run(true)
+            .build()
+            .unwrap();
+        let message = TemplateType::buttons("Pick one")
+            .action(Action::uri("Open", "javascript:alert(1)"))
+            .into_message("menu");
+
+        let err = client
+            .push_message("U1234567890abcdef1234567890abcdef", vec![message])
+            .await
+            .unwrap_err();
+        assert!(err.message.contains("Invalid outgoing message"));
+        assert!(err.status_code.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_push_buffered_on_network_error() {
+        let path =
+            std::env::temp_dir().join(format!("linebot-client-{}.jsonl", std::process::id()));
+        let client = LineApiClient::builder("test_token")
+            .base_url("http://127.0.0.1:1/v2/bot")
+            .offline_buffer(OfflineBuffer::new(&path, Duration::from_secs(60)))
+            .build()
+            .unwrap();
+
+        let error = client
+            .push_message(
+                "U1234567890abcdef1234567890abcdef",
+                vec![OutgoingMessage::text("hi")],
+            )
+            .await
+            .unwrap_err();
+        assert!(error.network_error);
+
+        let pending = client
+            .offline_buffer
+            .as_ref()
+            .unwrap()
+            .take_pending()
+            .await
+            .unwrap();
+        assert_eq!(pending.len(), 1);
+        assert!(pending[0].options.retry_key.is_some());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_builder_debug_redacts_token() {
+        let builder = LineApiClient::builder("secret_token")
+            .proxy(ProxyConfig::new("http://proxy:3128").basic_auth("user", "proxy_password"));
+        let debug = format!("{:?}", builder);
+        assert!(debug.contains("base_url"));
+        assert!(!debug.contains("secret_token"));
+        assert!(!debug.contains("proxy_password"));
+    }
+
+    #[test]
+    fn test_line_api_client_default_base_url() {
+        let client = LineApiClient::new("test_token".to_string());
+        assert_eq!(client.base_url, LINE_API_BASE_URL);
+        assert_eq!(client.data_base_url, LINE_DATA_API_BASE_URL);
+        assert_eq!(client.oauth_base_url, LINE_OAUTH_BASE_URL);
+    }
+
+    #[tokio::test]
+    async fn test_get_message_content_uses_data_base_url() {
+        use axum::{Router, extract::Path, http::header, routing::get};
+
+        let app = Router::new().route(
+            "/v2/bot/message/:id/content",
+            get(|Path(id): Path<String>| async move {
+                (
+                    [(header::CONTENT_TYPE, "image/jpeg")],
+                    format!("content-{}", id),
+                )
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = LineApiClient::builder("test_token")
+            .base_url("http://127.0.0.1:1/v2/bot")
+            .data_base_url(format!("http://{}/v2/bot", addr))
+            .build()
+            .unwrap();
+
+        let content = client.get_message_content("123").await.unwrap();
+        assert_eq!(content.content_type.as_deref(), Some("image/jpeg"));
+        assert_eq!(&content.data[..], b"content-123");
+
+        let error = client
+            .get_message_content_with_limit("123", 4)
+            .await
+            .unwrap_err();
+        assert_eq!(error.message, "Content is larger than 4 bytes");
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_content_transcoding() {
+        use axum::{Json, Router, http::StatusCode, routing::get};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let polls = Arc::new(AtomicUsize::new(0));
+        let counter = polls.clone();
+        let app = Router::new()
+            .route(
+                "/v2/bot/message/:id/content/transcoding",
+                get(move || async move {
+                    let status = if counter.fetch_add(1, Ordering::SeqCst) < 2 {
+                        "processing"
+                    } else {
+                        "succeeded"
+                    };
+                    Json(serde_json::json!({ "status": status }))
+                }),
+            )
+            .route(
+                "/v2/bot/message/:id/content",
+                get(|| async { StatusCode::ACCEPTED }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = LineApiClient::builder("test_token")
+            .data_base_url(format!("http://{}/v2/bot", addr))
+            .build()
+            .unwrap();
+
+        let error = client.get_message_content("123").await.unwrap_err();
+        assert_eq!(error.status_code, Some(202));
+        assert_eq!(
+            client.get_content_transcoding_status("123").await.unwrap(),
+            TranscodingStatus::Processing
+        );
+        client
+            .wait_for_content_transcoding("123", Duration::from_millis(10), Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(polls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_narrowcast_and_wait() {
+        use axum::{Json, Router, extract::Query, routing::get, routing::post};
+        use std::collections::HashMap;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let polls = Arc::new(AtomicUsize::new(0));
+        let counter = polls.clone();
+        let app = Router::new()
+            .route(
+                "/v2/bot/message/narrowcast",
+                post(|Json(body): Json<serde_json::Value>| async move {
+                    assert_eq!(body["recipient"]["audienceGroupId"], 5614991017776u64);
+                    assert_eq!(body["limit"]["max"], 100);
+                    (
+                        [("x-line-request-id", "7d51557d-f2d1-4d3d-8f3c-0c5e5c3b0b1a")],
+                        Json(serde_json::json!({})),
+                    )
+                }),
+            )
+            .route(
+                "/v2/bot/message/progress/narrowcast",
+                get(
+                    move |Query(query): Query<HashMap<String, String>>| async move {
+                        assert_eq!(query["requestId"], "7d51557d-f2d1-4d3d-8f3c-0c5e5c3b0b1a");
+                        if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                            Json(serde_json::json!({
+                                "phase": "sending",
+                                "acceptedTime": "2024-01-01T00:00:00.123Z"
+                            }))
+                        } else {
+                            Json(serde_json::json!({
+                                "phase": "succeeded",
+                                "successCount": 10,
+                                "failureCount": 0,
+                                "targetCount": 10,
+                                "acceptedTime": "2024-01-01T00:00:00.123Z",
+                                "completedTime": "2024-01-01T00:01:00.456Z"
+                            }))
+                        }
+                    },
+                ),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = LineApiClient::builder("test_token")
+            .base_url(format!("http://{}/v2/bot", addr))
+            .build()
+            .unwrap();
+
+        assert!(client.get_narrowcast_progress("../quota").await.is_err());
+        let request = NarrowcastMessageRequest::new(vec![OutgoingMessage::text("hi")])
+            .recipient(serde_json::json!({
+                "type": "audience",
+                "audienceGroupId": 5614991017776u64
+            }))
+            .max(100);
+        let response = client.narrowcast_message(request).await.unwrap();
+        let request_id = response.request_id.unwrap();
+        let progress = client
+            .wait_for_narrowcast(
+                &request_id,
+                Duration::from_millis(10),
+                Duration::from_secs(5),
+            )
+            .await
+            .unwrap();
+        assert_eq!(progress.phase, NarrowcastPhase::Succeeded);
+        assert_eq!(progress.success_count, Some(10));
+        assert!(progress.completed_time.is_some());
+        assert_eq!(polls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_create_click_and_impression_audiences() {
+        use axum::{Json, Router, extract::Path, routing::post};
+
+        let app = Router::new().route(
+            "/v2/bot/audienceGroup/:kind",
+            post(
+                |Path(kind): Path<String>, Json(body): Json<serde_json::Value>| async move {
+                    let audience_type = if kind == "click" { "CLICK" } else { "IMP" };
+                    Json(serde_json::json!({
+                        "audienceGroupId": 4389303728991_i64,
+                        "type": audience_type,
+                        "description": body["description"],
+                        "created": 1500351844,
+                        "requestId": body["requestId"],
+                        "clickUrl": body.get("clickUrl"),
+                        "expireTimestamp": 1515903844
+                    }))
+                },
+            ),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = LineApiClient::builder("test_token")
+            .base_url(format!("http://{}/v2/bot", addr))
+            .build()
+            .unwrap();
+        let mut request = CreateAudienceRequest {
+            description: "春季活動點擊".to_string(),
+            request_id: "bb9744f9-47fa-4a29-941e-1234567890ab".to_string(),
+            click_url: Some("https://example.com/spring".to_string()),
+        };
+
+        let audience = client.create_click_audience(&request).await.unwrap();
+        assert_eq!(audience.audience_type, "CLICK");
+        assert_eq!(
+            audience.click_url.as_deref(),
+            Some("https://example.com/spring")
+        );
+        assert_eq!(
+            audience.request_id.as_deref(),
+            Some(request.request_id.as_str())
+        );
+
+        assert!(client.create_impression_audience(&request).await.is_err());
+        request.click_url = None;
+        let audience = client.create_impression_audience(&request).await.unwrap();
+        assert_eq!(audience.audience_type, "IMP");
+        assert_eq!(audience.click_url, None);
+
+        request.description = "a".repeat(MAX_AUDIENCE_DESCRIPTION_CHARS + 1);
+        assert!(client.create_click_audience(&request).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_issue_and_revoke_channel_token() {
+        use axum::{Form, Json, Router, http::StatusCode, response::IntoResponse, routing::post};
+        use std::collections::HashMap;
+
+        let app = Router::new()
+            .route(
+                "/v2/oauth/accessToken",
+                post(|Form(form): Form<HashMap<String, String>>| async move {
+                    assert_eq!(form["grant_type"], "client_credentials");
+                    if form["client_secret"] != "secret" {
+                        return (
+                            StatusCode::BAD_REQUEST,
+                            Json(serde_json::json!({
+                                "error": "invalid_client",
+                                "error_description": "invalid client_secret"
+                            })),
+                        )
+                            .into_response();
+                    }
+                    Json(serde_json::json!({
+                        "access_token": format!("token-{}", form["client_id"]),
+                        "expires_in": 2592000,
+                        "token_type": "Bearer"
+                    }))
+                    .into_response()
+                }),
+            )
+            .route(
+                "/v2/oauth/revoke",
+                post(|Form(form): Form<HashMap<String, String>>| async move {
+                    assert_eq!(form["access_token"], "token-1234567890");
+                    StatusCode::OK
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = LineApiClient::builder("")
+            .oauth_base_url(format!("http://{}/", addr))
+            .build()
+            .unwrap();
+
+        let token = client
+            .issue_channel_token("1234567890", "secret")
+            .await
+            .unwrap();
+        assert_eq!(token.access_token, "token-1234567890");
+        assert_eq!(token.expires_in, 2_592_000);
+        client
+            .revoke_channel_token(&token.access_token)
+            .await
+            .unwrap();
+
+        let err = client
+            .issue_channel_token("1234567890", "wrong")
+            .await
+            .unwrap_err();
+        assert_eq!(err.status_code, Some(400));
+        assert_eq!(err.message, "invalid_client: invalid client_secret");
+    }
+
+    #[tokio::test]
+    async fn test_get_valid_channel_token_key_ids() {
+        use axum::{
+            Json, Router, extract::Query, http::StatusCode, response::IntoResponse, routing::get,
+        };
+        use std::collections::HashMap;
+
+        let app = Router::new().route(
+            "/oauth2/v2.1/tokens/kid",
+            get(|Query(query): Query<HashMap<String, String>>| async move {
+                assert_eq!(query["client_assertion_type"], CLIENT_ASSERTION_TYPE);
+                if query["client_assertion"] != "header.payload.signature" {
+                    return (
+                        StatusCode::BAD_REQUEST,
+                        Json(serde_json::json!({ "error": "invalid_client" })),
+                    )
+                        .into_response();
+                }
+                Json(serde_json::json!({ "kids": ["kid-1", "kid-2"] })).into_response()
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = LineApiClient::builder("")
+            .oauth_base_url(format!("http://{}", addr))
+            .build()
+            .unwrap();
+
+        let key_ids = client
+            .get_valid_channel_token_key_ids("header.payload.signature")
+            .await
+            .unwrap();
+        assert_eq!(key_ids.kids, ["kid-1", "kid-2"]);
+
+        let err = client
+            .get_valid_channel_token_key_ids("expired")
+            .await
+            .unwrap_err();
+        assert_eq!(err.status_code, Some(400));
+        assert_eq!(err.message, "invalid_client");
+    }
+
+    #[tokio::test]
+    async fn test_rich_menu_batch() {
+        use axum::{
+            Json, Router,
+            extract::Query,
+            routing::{get, post},
+        };
+        use std::collections::HashMap;
+
+        const OLD_MENU: &str = "richmenu-88c05ef6921ae53f8b58a25f3a65faf7";
+        const NEW_MENU: &str = "richmenu-1d5c4e1f1b3f4c3a9d5e6f7a8b9c0d1e";
+
+        let app = Router::new()
+            .route(
+                "/v2/bot/richmenu/batch",
+                post(|Json(body): Json<serde_json::Value>| async move {
+                    assert_eq!(body["operations"][0]["type"], "link");
+                    assert_eq!(body["operations"][0]["to"], NEW_MENU);
+                    assert_eq!(
+                        body["operations"][1],
+                        serde_json::json!({ "type": "unlinkAll" })
+                    );
+                    assert!(body.get("resumeRequestKey").is_none());
+                    (
+                        axum::http::StatusCode::ACCEPTED,
+                        [("x-line-request-id", "batch-request-1")],
+                    )
+                }),
+            )
+            .route(
+                "/v2/bot/richmenu/progress/batch",
+                get(|Query(query): Query<HashMap<String, String>>| async move {
+                    assert_eq!(query["requestId"], "batch-request-1");
+                    Json(serde_json::json!({
+                        "phase": "succeeded",
+                        "acceptedTime": "2024-03-01T10:00:00.000Z",
+                        "completedTime": "2024-03-01T10:02:00.000Z"
+                    }))
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = LineApiClient::builder("test_token")
+            .base_url(format!("http://{}/v2/bot", addr))
+            .build()
+            .unwrap();
+
+        let request = RichMenuBatchRequest {
+            operations: vec![
+                RichMenuBatchOperation::Link {
+                    from: OLD_MENU.to_string(),
+                    to: NEW_MENU.to_string(),
+                },
+                RichMenuBatchOperation::UnlinkAll,
+            ],
+            resume_request_key: None,
+        };
+        let request_id = client.rich_menu_batch(&request).await.unwrap();
+        assert_eq!(request_id, "batch-request-1");
+        let progress = client
+            .wait_for_rich_menu_batch(
+                &request_id,
+                Duration::from_millis(10),
+                Duration::from_secs(1),
+            )
+            .await
+            .unwrap();
+        assert_eq!(progress.phase, RichMenuBatchPhase::Succeeded);
+        assert!(progress.completed_time.is_some());
+
+        let invalid = RichMenuBatchRequest {
+            operations: vec![RichMenuBatchOperation::Unlink {
+                from: "not-a-menu".to_string(),
+            }],
+            resume_request_key: None,
+        };
+        assert!(client.rich_menu_batch(&invalid).await.is_err());
+        let empty = RichMenuBatchRequest {
+            operations: Vec::new(),
+            resume_request_key: None,
+        };
+        assert!(client.validate_rich_menu_batch(&empty).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_validate_rich_menu() {
+        use crate::models::{Action, RichMenuArea, RichMenuBounds, RichMenuSize};
+        use axum::{Json, Router, http::StatusCode, response::IntoResponse, routing::post};
+
+        let app = Router::new().route(
+            "/v2/bot/richmenu/validate",
+            post(|Json(body): Json<serde_json::Value>| async move {
+                if body["areas"][0]["action"]["uri"] == "https://example.com" {
+                    return StatusCode::OK.into_response();
+                }
+                (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({
+                        "message": "The request body has 1 error(s)",
+                        "details": [{
+                            "message": "invalid uri scheme",
+                            "property": "areas[0].action.uri"
+                        }]
+                    })),
+                )
+                    .into_response()
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = LineApiClient::builder("test_token")
+            .base_url(format!("http://{}/v2/bot", addr))
+            .build()
+            .unwrap();
+
+        let mut menu = RichMenu {
+            size: RichMenuSize {
+                width: 2500,
+                height: 843,
+            },
+            selected: true,
+            name: "main".to_string(),
+            chat_bar_text: "Menu".to_string(),
+            areas: vec![RichMenuArea {
+                bounds: RichMenuBounds {
+                    x: 0,
+                    y: 0,
+                    width: 2500,
+                    height: 843,
+                },
+                action: Action::Uri {
+                    label: "Site".to_string(),
+                    uri: "https://example.com".to_string(),
+                },
+            }],
+        };
+        assert!(client.validate_rich_menu(&menu).await.unwrap().is_empty());
+
+        menu.areas[0].action = Action::Uri {
+            label: "Site".to_string(),
+            uri: "ftp://example.com".to_string(),
+        };
+        let errors = client.validate_rich_menu(&menu).await.unwrap();
+        assert_eq!(errors[0].property, "areas[0].action.uri");
+
+        // 本地檢查失敗時不呼叫 API
+        menu.areas[0].bounds.width = 2600;
+        let errors = client.validate_rich_menu(&menu).await.unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].property, "areas[0].bounds");
+    }
+
+    #[tokio::test]
+    async fn test_get_message_event_statistics() {
+        use axum::{Json, Router, extract::Query, routing::get};
+        use std::collections::HashMap;
+
+        let app = Router::new().route(
+            "/v2/bot/insight/message/event",
+            get(|Query(query): Query<HashMap<String, String>>| async move {
+                assert_eq!(query["requestId"], "f70dd685-499a-4231-a441-f24b8d4fba21");
+                Json(serde_json::json!({
+                    "overview": {
+                        "requestId": "f70dd685-499a-4231-a441-f24b8d4fba21",
+                        "timestamp": 1568214000,
+                        "delivered": 320,
+                        "uniqueImpression": 82,
+                        "uniqueClick": 51,
+                        "uniqueMediaPlayed": null,
+                        "uniqueMediaPlayed100Percent": null
+                    },
+                    "messages": [{ "seq": 1, "impression": 136, "mediaPlayed": null }],
+                    "clicks": [{
+                        "seq": 1,
+                        "url": "https://example.com/sale",
+                        "click": 75,
+                        "uniqueClick": 51,
+                        "uniqueClickOfRequest": 51
+                    }]
+                }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = LineApiClient::builder("test_token")
+            .base_url(format!("http://{}/v2/bot", addr))
+            .build()
+            .unwrap();
+
+        let statistics = client
+            .get_message_event_statistics("f70dd685-499a-4231-a441-f24b8d4fba21")
+            .await
+            .unwrap();
+        assert_eq!(statistics.overview.delivered, Some(320));
+        assert_eq!(statistics.overview.unique_media_played, None);
+        assert_eq!(statistics.messages[0].impression, Some(136));
+        assert_eq!(statistics.clicks[0].unique_click_of_request, Some(51));
+
+        assert!(
+            client
+                .get_message_event_statistics("../quota")
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_follower_time_series() {
+        use crate::models::FollowerInsightStatus;
+        use axum::{Json, Router, extract::Query, routing::get};
+        use std::collections::HashMap;
+
+        let app = Router::new().route(
+            "/v2/bot/insight/followers",
+            get(|Query(query): Query<HashMap<String, String>>| async move {
+                let day: u64 = query["date"][6..].parse().unwrap();
+                if query["date"] == "20240303" {
+                    return Json(serde_json::json!({ "status": "unready" }));
+                }
+                Json(serde_json::json!({
+                    "status": "ready",
+                    "followers": 100 + day,
+                    "targetedReaches": 80 + day,
+                    "blocks": day
+                }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = LineApiClient::builder("test_token")
+            .base_url(format!("http://{}/v2/bot", addr))
+            .build()
+            .unwrap();
+
+        let date = |day| NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+        let series = client
+            .get_follower_time_series(date(1), date(3))
+            .await
+            .unwrap();
+        assert_eq!(series.points.len(), 3);
+        assert_eq!(series.points[0].date, date(1));
+        assert_eq!(series.points[1].followers, Some(102));
+        assert_eq!(series.points[2].status, FollowerInsightStatus::Unready);
+        assert_eq!(series.points[2].followers, None);
+        assert_eq!(series.latest().unwrap().date, date(2));
+
+        assert!(
+            client
+                .get_follower_time_series(date(3), date(1))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_aggregation_unit_statistics() {
+        use axum::{Json, Router, extract::Query, routing::get};
+        use std::collections::HashMap;
+
+        let app = Router::new()
+            .route(
+                "/v2/bot/message/aggregation/list",
+                get(|Query(query): Query<HashMap<String, String>>| async move {
+                    let next = (!query.contains_key("start")).then_some("page2");
+                    Json(serde_json::json!({
+                        "customAggregationUnits": ["promotion_a", "promotion_b"],
+                        "next": next
+                    }))
+                }),
+            )
+            .route(
+                "/v2/bot/insight/message/event/aggregation",
+                get(|Query(query): Query<HashMap<String, String>>| async move {
+                    assert_eq!(query["customAggregationUnit"], "promotion_a");
+                    assert_eq!(query["from"], "20240301");
+                    assert_eq!(query["to"], "20240307");
+                    Json(serde_json::json!({
+                        "overview": {
+                            "uniqueImpression": 40,
+                            "uniqueClick": 30,
+                            "uniqueMediaPlayed": 25,
+                            "uniqueMediaPlayed100Percent": null
+                        },
+                        "messages": [{
+                            "seq": 1,
+                            "impression": 42,
+                            "mediaPlayed": 30,
+                            "mediaPlayed25Percent": null,
+                            "uniqueMediaPlayed100Percent": 21
+                        }],
+                        "clicks": [{
+                            "seq": 1,
+                            "url": "https://example.com/spring",
+                            "click": 35,
+                            "uniqueClick": 25,
+                            "uniqueClickOfRequest": null
+                        }]
+                    }))
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = LineApiClient::builder("test_token")
+            .base_url(format!("http://{}/v2/bot", addr))
+            .build()
+            .unwrap();
+
+        let names = client
+            .get_aggregation_unit_names(Some(2), None)
+            .await
+            .unwrap();
+        assert_eq!(
+            names.custom_aggregation_units,
+            ["promotion_a", "promotion_b"]
+        );
+        assert_eq!(names.next.as_deref(), Some("page2"));
+        let names = client
+            .get_aggregation_unit_names(None, Some("page2"))
+            .await
+            .unwrap();
+        assert_eq!(names.next, None);
+
+        let date = |day| NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+        let statistics = client
+            .get_aggregation_unit_statistics("promotion_a", date(1), date(7))
+            .await
+            .unwrap();
+        assert_eq!(statistics.overview.unique_impression, Some(40));
+        assert_eq!(statistics.overview.unique_media_played_100_percent, None);
+        assert_eq!(
+            statistics.messages[0].unique_media_played_100_percent,
+            Some(21)
+        );
+        assert_eq!(statistics.clicks[0].unique_click, Some(25));
+
+        assert!(
+            client
+                .get_aggregation_unit_statistics("promotion a", date(1), date(7))
+                .await
+                .is_err()
+        );
+        assert!(
+            client
+                .get_aggregation_unit_statistics("promotion_a", date(7), date(1))
+                .await
+                .is_err()
+        );
+        assert!(
+            client
+                .get_aggregation_unit_statistics(
+                    "promotion_a",
+                    date(1),
+                    NaiveDate::from_ymd_opt(2024, 4, 1).unwrap()
+                )
+                .await
+                .is_err()
+        );
+    }
 }
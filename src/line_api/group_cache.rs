@@ -0,0 +1,138 @@
+use dashmap::DashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+use crate::line_api::{LineApiClient, LineApiError};
+use crate::models::GroupSummary;
+
+/// 群組快取預設有效時間
+pub const DEFAULT_GROUP_CACHE_TTL: Duration = Duration::from_secs(600);
+
+/// 快取中的群組資訊
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupInfo {
+    pub summary: GroupSummary,
+    pub member_count: u64,
+}
+
+#[derive(Debug, Clone)]
+struct CachedGroup {
+    info: GroupInfo,
+    fetched_at: Instant,
+}
+
+/// 群組資訊與成員數快取
+///
+/// 未命中或過期時向 LINE API 重新取得；收到 join/leave/memberJoined/memberLeft
+/// 事件時由 webhook 處理流程呼叫 `invalidate`，確保成員數不會過時。
+#[derive(Clone)]
+pub struct GroupCache {
+    client: LineApiClient,
+    entries: Arc<DashMap<String, CachedGroup>>,
+    ttl: Duration,
+}
+
+impl GroupCache {
+    pub fn new(client: LineApiClient, ttl: Duration) -> Self {
+        Self {
+            client,
+            entries: Default::default(),
+            ttl,
+        }
+    }
+
+    /// 取得群組資訊，必要時呼叫 API
+    pub async fn get(&self, group_id: &str) -> Result<GroupInfo, LineApiError> {
+        if let Some(info) = self.cached(group_id) {
+            return Ok(info);
+        }
+
+        debug!("Group cache miss, fetching group info");
+        let (summary, member_count) = tokio::try_join!(
+            self.client.get_group_summary(group_id),
+            self.client.get_group_member_count(group_id),
+        )?;
+        let info = GroupInfo {
+            summary,
+            member_count,
+        };
+        self.entries.insert(
+            group_id.to_string(),
+            CachedGroup {
+                info: info.clone(),
+                fetched_at: Instant::now(),
+            },
+        );
+        Ok(info)
+    }
+
+    /// 只讀取快取，不呼叫 API
+    pub fn cached(&self, group_id: &str) -> Option<GroupInfo> {
+        self.entries
+            .get(group_id)
+            .filter(|entry| entry.fetched_at.elapsed() < self.ttl)
+            .map(|entry| entry.info.clone())
+    }
+
+    pub fn invalidate(&self, group_id: &str) {
+        self.entries.remove(group_id);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const GROUP_ID: &str = "C1234567890abcdef1234567890abcdef";
+
+    #[tokio::test]
+    async fn test_group_cache_fetches_once_until_invalidated() {
+        use axum::{Json, Router, extract::Path, routing::get};
+        use serde_json::json;
+
+        let summary_calls = Arc::new(AtomicUsize::new(0));
+        let calls = summary_calls.clone();
+        let app = Router::new()
+            .route(
+                "/v2/bot/group/:id/summary",
+                get(move |Path(id): Path<String>| {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    async move { Json(json!({"groupId": id, "groupName": "Team"})) }
+                }),
+            )
+            .route(
+                "/v2/bot/group/:id/members/count",
+                get(|| async { Json(json!({"count": 3})) }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = LineApiClient::builder("test_token")
+            .base_url(format!("http://{}/v2/bot", addr))
+            .build()
+            .unwrap();
+        let cache = GroupCache::new(client, DEFAULT_GROUP_CACHE_TTL);
+
+        let info = cache.get(GROUP_ID).await.unwrap();
+        assert_eq!(info.summary.group_name, "Team");
+        assert_eq!(info.member_count, 3);
+        cache.get(GROUP_ID).await.unwrap();
+        assert_eq!(summary_calls.load(Ordering::SeqCst), 1);
+
+        cache.invalidate(GROUP_ID);
+        assert!(cache.cached(GROUP_ID).is_none());
+        cache.get(GROUP_ID).await.unwrap();
+        assert_eq!(summary_calls.load(Ordering::SeqCst), 2);
+    }
+}
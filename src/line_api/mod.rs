@@ -1,3 +1,19 @@
 pub mod client;
+pub mod group_cache;
+pub mod offline_buffer;
+pub mod profile_language;
+pub mod queue;
+pub mod quota_monitor;
+pub mod rich_menu;
+pub mod send;
+pub mod throttle;
 
 pub use client::*;
+pub use group_cache::*;
+pub use offline_buffer::*;
+pub use profile_language::*;
+pub use queue::*;
+pub use quota_monitor::*;
+pub use rich_menu::*;
+pub use send::*;
+pub use throttle::*;
@@ -0,0 +1,232 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{io, path::PathBuf, time::Duration};
+use tokio::{fs, io::AsyncWriteExt, sync::Mutex};
+use tracing::warn;
+
+use crate::line_api::SendOptions;
+use crate::models::OutgoingMessage;
+
+/// 因網路錯誤暫存的 push 訊息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BufferedPush {
+    pub to: String,
+    pub messages: Vec<OutgoingMessage>,
+    #[serde(default)]
+    pub options: SendOptions,
+    pub buffered_at: DateTime<Utc>,
+}
+
+/// 離線暫存區
+///
+/// 以 JSON Lines 檔案保存無法送達的 push 訊息，讓背景工作在 LINE API 恢復後重送；
+/// 超過 `max_age` 的訊息視為過期丟棄，超過 `max_entries` 時丟棄最舊的訊息。
+#[derive(Debug)]
+pub struct OfflineBuffer {
+    path: PathBuf,
+    max_age: Duration,
+    max_entries: usize,
+    lock: Mutex<()>,
+}
+
+impl OfflineBuffer {
+    pub fn new<P: Into<PathBuf>>(path: P, max_age: Duration) -> Self {
+        Self {
+            path: path.into(),
+            max_age,
+            max_entries: 10_000,
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// 暫存一筆 push 訊息
+    pub async fn store(&self, entry: BufferedPush) -> io::Result<()> {
+        let _guard = self.lock.lock().await;
+
+        let mut entries = self.read_entries().await?;
+        entries.push(entry);
+        if entries.len() > self.max_entries {
+            let overflow = entries.len() - self.max_entries;
+            warn!("Offline buffer full, dropping {} oldest messages", overflow);
+            entries.drain(..overflow);
+        }
+        self.write_entries(&entries).await
+    }
+
+    /// 放回重送失敗的訊息，排在重送期間新暫存的訊息之前，整個暫存區只重寫一次
+    pub async fn requeue(&self, entries: Vec<BufferedPush>) -> io::Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let _guard = self.lock.lock().await;
+
+        let mut requeued = entries;
+        requeued.extend(self.read_entries().await?);
+        if requeued.len() > self.max_entries {
+            let overflow = requeued.len() - self.max_entries;
+            warn!("Offline buffer full, dropping {} oldest messages", overflow);
+            requeued.drain(..overflow);
+        }
+        self.write_entries(&requeued).await
+    }
+
+    /// 取出所有未過期的訊息並清空暫存區
+    pub async fn take_pending(&self) -> io::Result<Vec<BufferedPush>> {
+        let _guard = self.lock.lock().await;
+
+        let entries = self.read_entries().await?;
+        if entries.is_empty() {
+            return Ok(entries);
+        }
+        self.write_entries(&[]).await?;
+
+        let total = entries.len();
+        let pending: Vec<BufferedPush> = entries
+            .into_iter()
+            .filter(|entry| !self.is_expired(entry))
+            .collect();
+        if pending.len() < total {
+            warn!(
+                "Dropped {} expired messages from offline buffer",
+                total - pending.len()
+            );
+        }
+        Ok(pending)
+    }
+
+    pub fn is_expired(&self, entry: &BufferedPush) -> bool {
+        let age = Utc::now().signed_duration_since(entry.buffered_at);
+        age.to_std().is_ok_and(|age| age > self.max_age)
+    }
+
+    async fn read_entries(&self) -> io::Result<Vec<BufferedPush>> {
+        let content = match fs::read_to_string(&self.path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        Ok(content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str(line) {
+                Ok(entry) => Some(entry),
+                Err(e) => {
+                    warn!("Skipping corrupt offline buffer entry: {}", e);
+                    None
+                }
+            })
+            .collect())
+    }
+
+    async fn write_entries(&self, entries: &[BufferedPush]) -> io::Result<()> {
+        let mut content = String::new();
+        for entry in entries {
+            content.push_str(&serde_json::to_string(entry).map_err(io::Error::other)?);
+            content.push('\n');
+        }
+
+        // 先寫入暫存檔再改名，避免中途失敗留下不完整的檔案
+        let tmp_path = self.path.with_extension("tmp");
+        let mut file = fs::File::create(&tmp_path).await?;
+        file.write_all(content.as_bytes()).await?;
+        file.sync_all().await?;
+        fs::rename(&tmp_path, &self.path).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "linebot-offline-{}-{}.jsonl",
+            name,
+            std::process::id()
+        ))
+    }
+
+    fn entry(to: &str, buffered_at: DateTime<Utc>) -> BufferedPush {
+        BufferedPush {
+            to: to.to_string(),
+            messages: vec![OutgoingMessage::text("hello")],
+            options: SendOptions::default(),
+            buffered_at,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_store_and_take_pending() {
+        let path = temp_path("store");
+        let buffer = OfflineBuffer::new(&path, Duration::from_secs(3600));
+
+        buffer.store(entry("U1", Utc::now())).await.unwrap();
+        buffer.store(entry("U2", Utc::now())).await.unwrap();
+
+        let pending = buffer.take_pending().await.unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].to, "U1");
+        assert!(buffer.take_pending().await.unwrap().is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_expired_entries_are_dropped() {
+        let path = temp_path("expiry");
+        let buffer = OfflineBuffer::new(&path, Duration::from_secs(60));
+
+        buffer
+            .store(entry("U_old", Utc::now() - chrono::Duration::minutes(5)))
+            .await
+            .unwrap();
+        buffer.store(entry("U_new", Utc::now())).await.unwrap();
+
+        let pending = buffer.take_pending().await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].to, "U_new");
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_requeue_keeps_order() {
+        let path = temp_path("requeue");
+        let buffer = OfflineBuffer::new(&path, Duration::from_secs(3600));
+
+        buffer.store(entry("U1", Utc::now())).await.unwrap();
+        buffer.store(entry("U2", Utc::now())).await.unwrap();
+        let pending = buffer.take_pending().await.unwrap();
+        // 重送期間又暫存了新的訊息
+        buffer.store(entry("U3", Utc::now())).await.unwrap();
+        buffer.requeue(pending).await.unwrap();
+
+        let pending = buffer.take_pending().await.unwrap();
+        let recipients: Vec<&str> = pending.iter().map(|e| e.to.as_str()).collect();
+        assert_eq!(recipients, vec!["U1", "U2", "U3"]);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_max_entries_drops_oldest() {
+        let path = temp_path("overflow");
+        let buffer = OfflineBuffer::new(&path, Duration::from_secs(3600)).max_entries(2);
+
+        for to in ["U1", "U2", "U3"] {
+            buffer.store(entry(to, Utc::now())).await.unwrap();
+        }
+
+        let pending = buffer.take_pending().await.unwrap();
+        let recipients: Vec<&str> = pending.iter().map(|e| e.to.as_str()).collect();
+        assert_eq!(recipients, vec!["U2", "U3"]);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,180 @@
+use async_trait::async_trait;
+use dashmap::DashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::line_api::LineApiClient;
+use crate::storage::{StorageError, UserDataCleanup};
+
+/// 查詢失敗後不再重查的預設時間
+pub const DEFAULT_PROFILE_FAILURE_TTL: Duration = Duration::from_secs(300);
+
+/// 預設最多快取的使用者數
+pub const DEFAULT_PROFILE_CACHE_ENTRIES: usize = 100_000;
+
+#[derive(Debug, Clone)]
+enum CachedLanguage {
+    Found(Option<String>),
+    /// 查詢失敗（例如未加好友），記錄失敗時間
+    Failed(Instant),
+}
+
+#[derive(Debug, Clone)]
+struct CachedEntry {
+    language: CachedLanguage,
+    cached_at: Instant,
+}
+
+/// 快取 LINE 個人資料中的 `language`
+///
+/// 翻譯與內建回覆共用同一份快取，每位使用者只查詢一次；查詢失敗時在 `failure_ttl` 內不再重查，
+/// 超過 `max_entries` 時丟棄最舊的紀錄。
+#[derive(Clone)]
+pub struct ProfileLanguageCache {
+    client: LineApiClient,
+    entries: Arc<DashMap<String, CachedEntry>>,
+    failure_ttl: Duration,
+    max_entries: usize,
+}
+
+impl ProfileLanguageCache {
+    pub fn new(client: LineApiClient) -> Self {
+        Self {
+            client,
+            entries: Default::default(),
+            failure_ttl: DEFAULT_PROFILE_FAILURE_TTL,
+            max_entries: DEFAULT_PROFILE_CACHE_ENTRIES,
+        }
+    }
+
+    pub fn failure_ttl(mut self, ttl: Duration) -> Self {
+        self.failure_ttl = ttl;
+        self
+    }
+
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries.max(1);
+        self
+    }
+
+    /// 使用者設定的語言，個人資料沒有提供或查詢失敗時為 `None`
+    pub async fn get(&self, user_id: &str) -> Option<String> {
+        if let Some(entry) = self.entries.get(user_id) {
+            match &entry.language {
+                CachedLanguage::Found(language) => return language.clone(),
+                CachedLanguage::Failed(at) if at.elapsed() < self.failure_ttl => return None,
+                CachedLanguage::Failed(_) => {}
+            }
+        }
+        let (language, cached) = match self.client.get_profile(user_id).await {
+            Ok(profile) => {
+                let language = profile["language"].as_str().map(str::to_string);
+                (language.clone(), CachedLanguage::Found(language))
+            }
+            Err(_) => (None, CachedLanguage::Failed(Instant::now())),
+        };
+        self.insert(user_id, cached);
+        language
+    }
+
+    /// 移除使用者的快取，例如刪除使用者資料時
+    pub fn remove(&self, user_id: &str) {
+        self.entries.remove(user_id);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn insert(&self, user_id: &str, language: CachedLanguage) {
+        if !self.entries.contains_key(user_id) && self.entries.len() >= self.max_entries {
+            // 先丟棄過期的失敗紀錄，仍然太多時丟棄最舊的一筆
+            let ttl = self.failure_ttl;
+            self.entries.retain(
+                |_, entry| !matches!(entry.language, CachedLanguage::Failed(at) if at.elapsed() >= ttl),
+            );
+            if self.entries.len() >= self.max_entries {
+                let oldest = self
+                    .entries
+                    .iter()
+                    .min_by_key(|entry| entry.cached_at)
+                    .map(|entry| entry.key().clone());
+                if let Some(oldest) = oldest {
+                    self.entries.remove(&oldest);
+                }
+            }
+        }
+        self.entries.insert(
+            user_id.to_string(),
+            CachedEntry {
+                language,
+                cached_at: Instant::now(),
+            },
+        );
+    }
+}
+
+#[async_trait]
+impl UserDataCleanup for ProfileLanguageCache {
+    async fn purge_user(&self, user_id: &str) -> Result<(), StorageError> {
+        self.remove(user_id);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Json, Router, extract::Path, http::StatusCode, routing::get};
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_failures_cached_and_entries_bounded() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let app = Router::new().route(
+            "/v2/bot/profile/:id",
+            get(move |Path(id): Path<String>| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if id == "U_blocked" {
+                        (StatusCode::NOT_FOUND, Json(json!({"message": "Not found"})))
+                    } else {
+                        (
+                            StatusCode::OK,
+                            Json(json!({"userId": id, "language": "ja"})),
+                        )
+                    }
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = LineApiClient::builder("test_token")
+            .base_url(format!("http://{}/v2/bot", addr))
+            .build()
+            .unwrap();
+        let cache = ProfileLanguageCache::new(client).max_entries(2);
+
+        assert_eq!(cache.get("U_blocked").await, None);
+        assert_eq!(cache.get("U_blocked").await, None);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        assert_eq!(cache.get("U1").await.as_deref(), Some("ja"));
+        assert_eq!(cache.get("U2").await.as_deref(), Some("ja"));
+        assert_eq!(cache.len(), 2);
+        // 最舊的失敗紀錄已被丟棄，會再查詢一次
+        assert_eq!(cache.get("U_blocked").await, None);
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+        cache.remove("U_blocked");
+        assert_eq!(cache.len(), 1);
+    }
+}
@@ -0,0 +1,236 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+use tokio::sync::mpsc;
+use tracing::{debug, error};
+
+use crate::line_api::LineApiClient;
+use crate::models::OutgoingMessage;
+use crate::utils::message_limits;
+
+/// 等待送出的 push 訊息
+#[derive(Debug, Clone)]
+pub struct QueuedPush {
+    pub to: String,
+    pub messages: Vec<OutgoingMessage>,
+}
+
+/// 合併後實際要送出的請求
+#[derive(Debug, Clone)]
+pub enum SendBatch {
+    Push {
+        to: String,
+        messages: Vec<OutgoingMessage>,
+    },
+    Multicast {
+        to: Vec<String>,
+        messages: Vec<OutgoingMessage>,
+    },
+}
+
+/// 佇列已關閉（背景工作已結束）
+#[derive(Debug)]
+pub struct QueueClosed;
+
+impl std::fmt::Display for QueueClosed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Message queue is closed")
+    }
+}
+
+impl std::error::Error for QueueClosed {}
+
+/// 送出佇列
+///
+/// 在每個間隔內收集 push 訊息，把內容相同的訊息合併成最多 500 人的 multicast，
+/// 適合通知類型的大量發送，可減少 API 呼叫次數。
+#[derive(Clone)]
+pub struct MessageQueue {
+    sender: mpsc::UnboundedSender<QueuedPush>,
+}
+
+impl MessageQueue {
+    /// 啟動背景送出工作
+    pub fn start(client: LineApiClient, flush_interval: Duration) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(run_worker(client, receiver, flush_interval));
+        Self { sender }
+    }
+
+    pub fn push(&self, to: &str, messages: Vec<OutgoingMessage>) -> Result<(), QueueClosed> {
+        self.sender
+            .send(QueuedPush {
+                to: to.to_string(),
+                messages,
+            })
+            .map_err(|_| QueueClosed)
+    }
+}
+
+async fn run_worker(
+    client: LineApiClient,
+    mut receiver: mpsc::UnboundedReceiver<QueuedPush>,
+    flush_interval: Duration,
+) {
+    let mut pending = Vec::new();
+    let mut interval = tokio::time::interval(flush_interval);
+
+    loop {
+        tokio::select! {
+            item = receiver.recv() => match item {
+                Some(item) => pending.push(item),
+                None => break,
+            },
+            _ = interval.tick() => {
+                flush(&client, std::mem::take(&mut pending)).await;
+            }
+        }
+    }
+
+    // 佇列關閉前送出剩餘訊息
+    flush(&client, pending).await;
+}
+
+async fn flush(client: &LineApiClient, pending: Vec<QueuedPush>) {
+    if pending.is_empty() {
+        return;
+    }
+
+    let batches = coalesce(pending);
+    debug!("Flushing message queue with {} batches", batches.len());
+
+    for batch in batches {
+        let result = match batch {
+            SendBatch::Push { to, messages } => client.push_message(&to, messages).await,
+            SendBatch::Multicast { to, messages } => client.multicast_message(to, messages).await,
+        };
+        if let Err(e) = result {
+            error!("Failed to send queued messages: {}", e);
+        }
+    }
+}
+
+/// 依訊息內容合併收件人
+///
+/// multicast 只接受使用者 ID，群組或聊天室仍個別以 push 送出。
+pub fn coalesce(pending: Vec<QueuedPush>) -> Vec<SendBatch> {
+    let mut groups: Vec<(Vec<OutgoingMessage>, Vec<String>)> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    // 每組已加入的收件人，避免重複
+    let mut added: Vec<HashSet<String>> = Vec::new();
+    let mut batches = Vec::new();
+
+    for item in pending {
+        if !item.to.starts_with('U') {
+            batches.push(SendBatch::Push {
+                to: item.to,
+                messages: item.messages,
+            });
+            continue;
+        }
+
+        let key = match serde_json::to_string(&item.messages) {
+            Ok(key) => key,
+            Err(_) => {
+                batches.push(SendBatch::Push {
+                    to: item.to,
+                    messages: item.messages,
+                });
+                continue;
+            }
+        };
+
+        match index.get(&key) {
+            Some(&i) => {
+                if added[i].insert(item.to.clone()) {
+                    groups[i].1.push(item.to);
+                }
+            }
+            None => {
+                index.insert(key, groups.len());
+                added.push(HashSet::from([item.to.clone()]));
+                groups.push((item.messages, vec![item.to]));
+            }
+        }
+    }
+
+    for (messages, recipients) in groups {
+        if recipients.len() == 1 {
+            batches.push(SendBatch::Push {
+                to: recipients.into_iter().next().unwrap_or_default(),
+                messages,
+            });
+            continue;
+        }
+
+        for chunk in recipients.chunks(message_limits::MAX_MULTICAST_RECIPIENTS) {
+            batches.push(SendBatch::Multicast {
+                to: chunk.to_vec(),
+                messages: messages.clone(),
+            });
+        }
+    }
+
+    batches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(i: usize) -> String {
+        format!("U{:032x}", i)
+    }
+
+    fn queued(to: String, text: &str) -> QueuedPush {
+        QueuedPush {
+            to,
+            messages: vec![OutgoingMessage::text(text)],
+        }
+    }
+
+    #[test]
+    fn test_identical_messages_become_multicast() {
+        let pending = vec![
+            queued(user(1), "news"),
+            queued(user(2), "news"),
+            queued(user(3), "other"),
+        ];
+
+        let batches = coalesce(pending);
+        assert_eq!(batches.len(), 2);
+        assert!(matches!(&batches[0], SendBatch::Multicast { to, .. } if to.len() == 2));
+        assert!(matches!(&batches[1], SendBatch::Push { to, .. } if *to == user(3)));
+    }
+
+    #[test]
+    fn test_multicast_split_into_chunks_of_500() {
+        let pending = (0..1200).map(|i| queued(user(i), "news")).collect();
+
+        let sizes: Vec<usize> = coalesce(pending)
+            .iter()
+            .map(|batch| match batch {
+                SendBatch::Multicast { to, .. } => to.len(),
+                SendBatch::Push { .. } => 1,
+            })
+            .collect();
+        assert_eq!(sizes, vec![500, 500, 200]);
+    }
+
+    #[test]
+    fn test_groups_are_pushed_individually() {
+        let pending = vec![
+            queued("C1234567890abcdef1234567890abcdef".to_string(), "news"),
+            queued("C1234567890abcdef1234567890abcdeg".to_string(), "news"),
+        ];
+
+        let batches = coalesce(pending);
+        assert_eq!(batches.len(), 2);
+        assert!(
+            batches
+                .iter()
+                .all(|batch| matches!(batch, SendBatch::Push { .. }))
+        );
+    }
+}
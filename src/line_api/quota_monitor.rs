@@ -0,0 +1,195 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+use std::time::Duration;
+use tracing::{error, warn};
+
+use crate::line_api::{LineApiClient, LineApiError};
+use crate::models::OutgoingMessage;
+use crate::utils::Metrics;
+
+/// 訊息額度使用狀況
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuotaStatus {
+    pub used: u64,
+    /// 無上限方案（`type: none`）為 `None`
+    pub limit: Option<u64>,
+}
+
+impl QuotaStatus {
+    /// 已使用比例，無上限時為 `None`
+    pub fn usage_ratio(&self) -> Option<f64> {
+        match self.limit {
+            Some(0) => Some(1.0),
+            Some(limit) => Some(self.used as f64 / limit as f64),
+            None => None,
+        }
+    }
+}
+
+/// 定期查詢訊息額度並匯出 `line_quota_used` / `line_quota_limit`
+///
+/// 使用比例超過 `warning_ratio` 時記錄警告並推播給 `alert_user_ids`；
+/// 每次越過門檻只通知一次，直到使用比例回到門檻以下（例如月初重置）。
+#[derive(Clone)]
+pub struct QuotaMonitor {
+    client: LineApiClient,
+    metrics: Metrics,
+    warning_ratio: f64,
+    alert_user_ids: Vec<String>,
+    warned: Arc<AtomicBool>,
+}
+
+impl QuotaMonitor {
+    pub fn new(client: LineApiClient, metrics: Metrics, warning_ratio: f64) -> Self {
+        Self {
+            client,
+            metrics,
+            warning_ratio,
+            alert_user_ids: Vec::new(),
+            warned: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// 超過門檻時推播通知的使用者
+    pub fn alert_user_ids(mut self, user_ids: Vec<String>) -> Self {
+        self.alert_user_ids = user_ids;
+        self
+    }
+
+    /// 查詢一次額度並更新指標，超過門檻時發出警告
+    pub async fn check(&self) -> Result<QuotaStatus, LineApiError> {
+        let (quota, consumption) = tokio::try_join!(
+            self.client.get_message_quota(),
+            self.client.get_message_quota_consumption(),
+        )?;
+        let status = QuotaStatus {
+            used: consumption.total_usage,
+            limit: if quota.quota_type == "limited" {
+                quota.value
+            } else {
+                None
+            },
+        };
+        self.metrics.record_quota(status.used, status.limit);
+
+        let over_threshold = status
+            .usage_ratio()
+            .is_some_and(|ratio| ratio >= self.warning_ratio);
+        if !over_threshold {
+            self.warned.store(false, Ordering::Relaxed);
+        } else if !self.warned.swap(true, Ordering::Relaxed) {
+            self.warn(&status).await;
+        }
+        Ok(status)
+    }
+
+    pub fn start(&self, interval: Duration) {
+        let monitor = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = monitor.check().await {
+                    error!("Failed to poll LINE message quota: {}", e);
+                }
+            }
+        });
+    }
+
+    async fn warn(&self, status: &QuotaStatus) {
+        let limit = status.limit.unwrap_or_default();
+        warn!(
+            "LINE message quota usage is at {}/{} ({:.0}%)",
+            status.used,
+            limit,
+            status.usage_ratio().unwrap_or_default() * 100.0
+        );
+
+        let text = format!(
+            "⚠️ LINE 訊息額度已使用 {}/{}（{:.0}%）",
+            status.used,
+            limit,
+            status.usage_ratio().unwrap_or_default() * 100.0
+        );
+        for user_id in &self.alert_user_ids {
+            if let Err(e) = self
+                .client
+                .push_message(user_id, vec![OutgoingMessage::text(text.clone())])
+                .await
+            {
+                error!("Failed to send quota alert: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn test_usage_ratio() {
+        let status = QuotaStatus {
+            used: 450,
+            limit: Some(500),
+        };
+        assert_eq!(status.usage_ratio(), Some(0.9));
+        let unlimited = QuotaStatus {
+            used: 450,
+            limit: None,
+        };
+        assert_eq!(unlimited.usage_ratio(), None);
+    }
+
+    #[tokio::test]
+    async fn test_alert_sent_once_over_threshold() {
+        use axum::{
+            Json, Router,
+            routing::{get, post},
+        };
+        use serde_json::json;
+
+        let pushes = Arc::new(AtomicUsize::new(0));
+        let counter = pushes.clone();
+        let app = Router::new()
+            .route(
+                "/v2/bot/message/quota",
+                get(|| async { Json(json!({"type": "limited", "value": 500})) }),
+            )
+            .route(
+                "/v2/bot/message/quota/consumption",
+                get(|| async { Json(json!({"totalUsage": 450})) }),
+            )
+            .route(
+                "/v2/bot/message/push",
+                post(move || {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    async { Json(json!({})) }
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = LineApiClient::builder("test_token")
+            .base_url(format!("http://{}/v2/bot", addr))
+            .build()
+            .unwrap();
+        let metrics = Metrics::new();
+        let monitor = QuotaMonitor::new(client, metrics.clone(), 0.8)
+            .alert_user_ids(vec!["U1234567890abcdef1234567890abcdef".to_string()]);
+
+        let status = monitor.check().await.unwrap();
+        assert_eq!(status.used, 450);
+        assert_eq!(status.limit, Some(500));
+        monitor.check().await.unwrap();
+
+        assert_eq!(pushes.load(Ordering::SeqCst), 1);
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.quota_used, 450);
+        assert_eq!(snapshot.quota_limit, Some(500));
+    }
+}
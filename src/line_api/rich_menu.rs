@@ -0,0 +1,307 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use dashmap::DashMap;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+use crate::line_api::{LineApiClient, LineApiError, MAX_RICH_MENU_BULK_USERS};
+use crate::storage::{KvNamespace, Session, Storage, StorageError, UserDataCleanup};
+
+/// 使用者狀態 session 的鍵前綴，後接使用者 ID
+pub const USER_STATE_SESSION_PREFIX: &str = "state:";
+
+/// alias 對應的 rich menu ID 快取時間
+const ALIAS_CACHE_TTL: Duration = Duration::from_secs(600);
+
+/// 依使用者狀態切換 rich menu 的設定
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct RichMenuStateConfig {
+    /// 狀態名稱對應的 rich menu alias
+    pub states: BTreeMap<String, String>,
+    /// 沒有狀態或狀態沒有對應時使用的 alias，未設定時取消連結、改顯示預設 rich menu
+    pub fallback_alias: Option<String>,
+    /// 累積連結請求後批次送出的間隔（秒）
+    pub batch_interval_secs: u64,
+}
+
+impl Default for RichMenuStateConfig {
+    fn default() -> Self {
+        Self {
+            states: BTreeMap::new(),
+            fallback_alias: None,
+            batch_interval_secs: 5,
+        }
+    }
+}
+
+/// 依 session 中的使用者狀態連結 rich menu
+///
+/// 狀態存在 `state:{userId}` session 的 `state` 欄位。狀態改變後不會立即呼叫 API，
+/// 而是依 alias 分組、每 `batch_interval_secs` 秒以 bulk link/unlink 一次送出；
+/// 已連結相同 rich menu 的使用者不會重複送出。連結結果存在儲存後端，
+/// 只取消由這裡連結的 rich menu，不影響透過管理 API 或其他程式連結的使用者。
+#[derive(Clone)]
+pub struct RichMenuManager {
+    client: LineApiClient,
+    storage: Arc<dyn Storage>,
+    config: Arc<RichMenuStateConfig>,
+    /// alias 對應的 rich menu ID 與查詢時間
+    aliases: Arc<DashMap<String, (String, Instant)>>,
+    /// 使用者 ID 對應最後一次成功連結的 alias，取消連結後刪除
+    linked: KvNamespace,
+    /// 等待送出的使用者與 alias
+    pending: Arc<Mutex<HashMap<String, Option<String>>>>,
+}
+
+impl RichMenuManager {
+    pub fn new(
+        client: LineApiClient,
+        storage: Arc<dyn Storage>,
+        config: RichMenuStateConfig,
+    ) -> Self {
+        Self {
+            client,
+            linked: KvNamespace::new(storage.clone(), "rich_menu_linked"),
+            storage,
+            config: Arc::new(config),
+            aliases: Arc::new(DashMap::new()),
+            pending: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn config(&self) -> &RichMenuStateConfig {
+        &self.config
+    }
+
+    /// 使用者目前的狀態，沒有狀態或 session 已過期時為 `None`
+    pub async fn state(&self, user_id: &str) -> Result<Option<String>, StorageError> {
+        let Some(session) = self.storage.get_session(&session_key(user_id)).await? else {
+            return Ok(None);
+        };
+        if session.is_expired(Utc::now()) {
+            return Ok(None);
+        }
+        Ok(session
+            .data
+            .get("state")
+            .and_then(Value::as_str)
+            .map(str::to_string))
+    }
+
+    /// 變更使用者狀態並排入 rich menu 切換，`None` 清除狀態
+    pub async fn set_state(&self, user_id: &str, state: Option<&str>) -> Result<(), StorageError> {
+        let key = session_key(user_id);
+        match state {
+            Some(state) => {
+                self.storage
+                    .save_session(&Session {
+                        user_id: key,
+                        data: json!({ "state": state }),
+                        expires_at: None,
+                    })
+                    .await?
+            }
+            None => self.storage.delete_session(&key).await?,
+        }
+        self.queue(user_id, self.alias_for(state)).await
+    }
+
+    /// 重新讀取使用者狀態，rich menu 不符時排入切換
+    ///
+    /// 給直接修改 session 或 session 過期的情況使用，webhook 處理完使用者事件後會自動呼叫。
+    pub async fn sync(&self, user_id: &str) -> Result<(), StorageError> {
+        let state = self.state(user_id).await?;
+        self.queue(user_id, self.alias_for(state.as_deref())).await
+    }
+
+    /// 狀態對應的 alias
+    pub fn alias_for(&self, state: Option<&str>) -> Option<String> {
+        state
+            .and_then(|state| self.config.states.get(state))
+            .or(self.config.fallback_alias.as_ref())
+            .cloned()
+    }
+
+    /// 等待送出的使用者數
+    pub fn pending_count(&self) -> usize {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// 沒有連結紀錄時目標為 `None` 也視為相同，不會取消別處連結的 rich menu
+    async fn queue(&self, user_id: &str, alias: Option<String>) -> Result<(), StorageError> {
+        let linked: Option<String> = self.linked.get(user_id).await?;
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        if linked == alias {
+            pending.remove(user_id);
+        } else {
+            pending.insert(user_id.to_string(), alias);
+        }
+        Ok(())
+    }
+
+    /// 依 alias 分組送出等待中的切換，回傳成功送出的使用者數
+    ///
+    /// 失敗的使用者不會記錄為已連結，下次 [`sync`](Self::sync) 時會重新排入。
+    pub async fn flush(&self) -> usize {
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap_or_else(|e| e.into_inner()));
+        let mut groups: BTreeMap<Option<String>, Vec<String>> = BTreeMap::new();
+        for (user_id, alias) in pending {
+            groups.entry(alias).or_default().push(user_id);
+        }
+
+        let mut sent = 0;
+        for (alias, mut user_ids) in groups {
+            user_ids.sort();
+            let rich_menu_id = match &alias {
+                Some(alias) => match self.resolve_alias(alias).await {
+                    Ok(rich_menu_id) => Some(rich_menu_id),
+                    Err(e) => {
+                        warn!("Failed to resolve rich menu alias {}: {}", alias, e);
+                        continue;
+                    }
+                },
+                None => None,
+            };
+            for chunk in user_ids.chunks(MAX_RICH_MENU_BULK_USERS) {
+                let result = match &rich_menu_id {
+                    Some(rich_menu_id) => {
+                        self.client.bulk_link_rich_menu(rich_menu_id, chunk).await
+                    }
+                    None => self.client.bulk_unlink_rich_menu(chunk).await,
+                };
+                if let Err(e) = result {
+                    warn!(
+                        "Failed to switch rich menu for {} users: {}",
+                        chunk.len(),
+                        e
+                    );
+                    continue;
+                }
+                for user_id in chunk {
+                    let result = match &alias {
+                        Some(alias) => self.linked.set(user_id, alias).await,
+                        None => self.linked.delete(user_id).await,
+                    };
+                    if let Err(e) = result {
+                        warn!("Failed to record linked rich menu: {}", e);
+                    }
+                }
+                sent += chunk.len();
+            }
+        }
+        if sent > 0 {
+            debug!("Switched rich menu for {} users", sent);
+        }
+        sent
+    }
+
+    /// 定期批次送出切換
+    pub fn start(&self) {
+        let manager = self.clone();
+        let interval = Duration::from_secs(self.config.batch_interval_secs.max(1));
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                manager.flush().await;
+            }
+        });
+    }
+
+    async fn resolve_alias(&self, alias: &str) -> Result<String, LineApiError> {
+        if let Some(cached) = self.aliases.get(alias)
+            && cached.1.elapsed() < ALIAS_CACHE_TTL
+        {
+            return Ok(cached.0.clone());
+        }
+        let rich_menu_id = self.client.get_rich_menu_alias(alias).await?.rich_menu_id;
+        self.aliases
+            .insert(alias.to_string(), (rich_menu_id.clone(), Instant::now()));
+        Ok(rich_menu_id)
+    }
+}
+
+#[async_trait]
+impl UserDataCleanup for RichMenuManager {
+    /// 刪除使用者狀態與連結紀錄，並取消等待中的切換
+    async fn purge_user(&self, user_id: &str) -> Result<(), StorageError> {
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(user_id);
+        self.storage.delete_session(&session_key(user_id)).await?;
+        self.linked.delete(user_id).await
+    }
+}
+
+fn session_key(user_id: &str) -> String {
+    format!("{}{}", USER_STATE_SESSION_PREFIX, user_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    const RICH_MENU_ID: &str = "richmenu-88c05ef6921ae53f8b58a25f3a65faf7";
+
+    fn user(n: u8) -> String {
+        format!("U{:032x}", n)
+    }
+
+    #[tokio::test]
+    async fn test_rich_menu_manager_batches_switches() {
+        let client = LineApiClient::builder("token")
+            .dry_run(true)
+            .build()
+            .unwrap();
+        let config = RichMenuStateConfig {
+            states: BTreeMap::from([("member".to_string(), "menu-member".to_string())]),
+            ..Default::default()
+        };
+        let storage = Arc::new(MemoryStorage::new());
+        let manager = RichMenuManager::new(client.clone(), storage.clone(), config.clone());
+        // dry run 不會查詢 alias，先放入快取
+        manager.aliases.insert(
+            "menu-member".to_string(),
+            (RICH_MENU_ID.to_string(), Instant::now()),
+        );
+
+        manager.set_state(&user(1), Some("member")).await.unwrap();
+        manager.set_state(&user(2), Some("member")).await.unwrap();
+        manager.set_state(&user(3), Some("guest")).await.unwrap();
+        assert_eq!(
+            manager.state(&user(1)).await.unwrap().as_deref(),
+            Some("member")
+        );
+        assert_eq!(manager.alias_for(Some("guest")), None);
+        // 沒有連結過的使用者不會取消連結，以免覆蓋其他地方連結的 rich menu
+        assert_eq!(manager.pending_count(), 2);
+        assert_eq!(manager.flush().await, 2);
+
+        // 已連結相同 rich menu 的使用者不會重複送出，重新啟動後仍記得
+        let restarted = RichMenuManager::new(client, storage, config);
+        restarted.sync(&user(1)).await.unwrap();
+        restarted.sync(&user(3)).await.unwrap();
+        assert_eq!(restarted.pending_count(), 0);
+
+        // 狀態切換後又切回來，取消等待中的切換
+        manager.set_state(&user(1), None).await.unwrap();
+        assert_eq!(manager.pending_count(), 1);
+        manager.set_state(&user(1), Some("member")).await.unwrap();
+        assert_eq!(manager.pending_count(), 0);
+        assert_eq!(manager.flush().await, 0);
+
+        // 取消連結後刪除紀錄，之後不再重複取消
+        manager.set_state(&user(1), None).await.unwrap();
+        assert_eq!(manager.flush().await, 1);
+        assert_eq!(manager.linked.get::<String>(&user(1)).await.unwrap(), None);
+        manager.sync(&user(1)).await.unwrap();
+        assert_eq!(manager.pending_count(), 0);
+    }
+}
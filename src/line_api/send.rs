@@ -0,0 +1,280 @@
+use crate::line_api::{LineApiClient, LineApiError};
+use crate::models::OutgoingMessage;
+use crate::utils::{check_limit, message_limits};
+use serde::{Deserialize, Serialize};
+
+/// 單次送出的設定，預設值與 `reply_message`、`push_message` 等簡易方法相同
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SendOptions {
+    /// 不發出推播通知
+    pub notification_disabled: Option<bool>,
+    /// 統計用的自訂彙總單位（push/multicast）
+    pub custom_aggregation_units: Option<Vec<String>>,
+    /// `X-Line-Retry-Key`（UUID），重送時避免重複傳送（push/multicast）
+    pub retry_key: Option<String>,
+    /// 寫入對話紀錄的使用者（reply/push）；reply 未設定時不記錄，push 未設定時以收件者記錄
+    pub user_id: Option<String>,
+}
+
+impl SendOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn notification_disabled(mut self, disabled: bool) -> Self {
+        self.notification_disabled = Some(disabled);
+        self
+    }
+
+    pub fn custom_aggregation_unit<T: Into<String>>(mut self, unit: T) -> Self {
+        self.custom_aggregation_units
+            .get_or_insert_with(Vec::new)
+            .push(unit.into());
+        self
+    }
+
+    pub fn retry_key<T: Into<String>>(mut self, key: T) -> Self {
+        self.retry_key = Some(key.into());
+        self
+    }
+
+    pub fn user_id<T: Into<String>>(mut self, user_id: T) -> Self {
+        self.user_id = Some(user_id.into());
+        self
+    }
+}
+
+/// 送出訊息的結果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendResponse {
+    /// LINE 回應的 `X-Line-Request-Id`，用來查詢 narrowcast 進度、訊息統計或建立受眾；
+    /// 相同 retry key 已被接受過時為當時的請求 ID，dry run 時為 `None`
+    pub request_id: Option<String>,
+}
+
+enum SendTarget {
+    Reply(String),
+    Push(String),
+    Multicast(Vec<String>),
+    Broadcast,
+}
+
+/// 逐一加入訊息後一次送出
+///
+/// 由 [`LineApiClient::reply`]、[`LineApiClient::push`]、[`LineApiClient::multicast`]、
+/// [`LineApiClient::broadcast`] 建立：
+///
+/// ```ignore
+/// client.reply(token).text("hi").sticker("446", "1988").notification_disabled().send().await?;
+/// ```
+///
+/// 超過單次 5 則的上限時不會送出任何訊息，`send` 直接回傳錯誤。
+#[must_use = "messages are only sent when `send` is awaited"]
+pub struct MessageSend<'a> {
+    client: &'a LineApiClient,
+    target: SendTarget,
+    messages: Vec<OutgoingMessage>,
+    options: SendOptions,
+}
+
+impl<'a> MessageSend<'a> {
+    fn new(client: &'a LineApiClient, target: SendTarget) -> Self {
+        Self {
+            client,
+            target,
+            messages: Vec::new(),
+            options: SendOptions::default(),
+        }
+    }
+
+    pub fn message(mut self, message: impl Into<OutgoingMessage>) -> Self {
+        self.messages.push(message.into());
+        self
+    }
+
+    pub fn text<T: Into<String>>(self, text: T) -> Self {
+        self.message(OutgoingMessage::text(text))
+    }
+
+    pub fn sticker<T: Into<String>>(self, package_id: T, sticker_id: T) -> Self {
+        self.message(OutgoingMessage::sticker(package_id, sticker_id))
+    }
+
+    /// 不發出推播通知
+    pub fn notification_disabled(mut self) -> Self {
+        self.options.notification_disabled = Some(true);
+        self
+    }
+
+    pub fn options(mut self, options: SendOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub async fn send(self) -> Result<SendResponse, LineApiError> {
+        check_limit(
+            "messages",
+            self.messages.len(),
+            message_limits::MAX_MESSAGES,
+        )
+        .map_err(|e| LineApiError {
+            message: format!("Invalid outgoing message: {}", e),
+            status_code: None,
+            network_error: false,
+        })?;
+
+        match self.target {
+            SendTarget::Reply(reply_token) => {
+                self.client
+                    .reply_message_with_options(&reply_token, self.messages, &self.options)
+                    .await
+            }
+            SendTarget::Push(to) => {
+                self.client
+                    .push_message_with_options(&to, self.messages, &self.options)
+                    .await
+            }
+            SendTarget::Multicast(to) => {
+                self.client
+                    .multicast_message_with_options(to, self.messages, &self.options)
+                    .await
+            }
+            SendTarget::Broadcast => {
+                self.client
+                    .broadcast_message_with_options(self.messages, &self.options)
+                    .await
+            }
+        }
+    }
+}
+
+impl LineApiClient {
+    pub fn reply(&self, reply_token: impl Into<String>) -> MessageSend<'_> {
+        MessageSend::new(self, SendTarget::Reply(reply_token.into()))
+    }
+
+    pub fn push(&self, to: impl Into<String>) -> MessageSend<'_> {
+        MessageSend::new(self, SendTarget::Push(to.into()))
+    }
+
+    pub fn multicast(&self, to: Vec<String>) -> MessageSend<'_> {
+        MessageSend::new(self, SendTarget::Multicast(to))
+    }
+
+    pub fn broadcast(&self) -> MessageSend<'_> {
+        MessageSend::new(self, SendTarget::Broadcast)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Json, Router, routing::post};
+    use serde_json::{Value, json};
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test]
+    async fn test_fluent_reply() {
+        let received = Arc::new(Mutex::new(None));
+        let store = received.clone();
+        let app = Router::new().route(
+            "/v2/bot/message/reply",
+            post(move |Json(body): Json<Value>| {
+                *store.lock().unwrap() = Some(body);
+                async { Json(json!({})) }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = LineApiClient::builder("test_token")
+            .base_url(format!("http://{}/v2/bot", addr))
+            .build()
+            .unwrap();
+        client
+            .reply("reply_token_123")
+            .text("hi")
+            .sticker("446", "1988")
+            .notification_disabled()
+            .send()
+            .await
+            .unwrap();
+
+        let body = received.lock().unwrap().take().unwrap();
+        assert_eq!(body["replyToken"], "reply_token_123");
+        assert_eq!(body["messages"].as_array().unwrap().len(), 2);
+        assert_eq!(body["messages"][1]["stickerId"], "1988");
+        assert_eq!(body["notificationDisabled"], true);
+    }
+
+    #[tokio::test]
+    async fn test_push_with_options() {
+        use axum::http::{HeaderMap, StatusCode};
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let store = received.clone();
+        let app = Router::new().route(
+            "/v2/bot/message/push",
+            post(move |headers: HeaderMap, Json(body): Json<Value>| {
+                let mut received = store.lock().unwrap();
+                received.push((headers.get("x-line-retry-key").cloned(), body));
+                // 第二次以相同 retry key 送出時 LINE 回傳 409 與原本的請求 ID
+                let (status, header) = if received.len() > 1 {
+                    (StatusCode::CONFLICT, "x-line-accepted-request-id")
+                } else {
+                    (StatusCode::OK, "x-line-request-id")
+                };
+                async move { (status, [(header, "request-1")], Json(json!({}))) }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = LineApiClient::builder("test_token")
+            .base_url(format!("http://{}/v2/bot", addr))
+            .build()
+            .unwrap();
+        let options = SendOptions::new()
+            .custom_aggregation_unit("promotion_a")
+            .retry_key("123e4567-e89b-12d3-a456-426614174000");
+        for _ in 0..2 {
+            let response = client
+                .push_message_with_options(
+                    "U1234567890abcdef1234567890abcdef",
+                    vec![OutgoingMessage::text("hi")],
+                    &options,
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.request_id.as_deref(), Some("request-1"));
+        }
+
+        let received = received.lock().unwrap();
+        let (retry_key, body) = &received[0];
+        assert_eq!(
+            retry_key.as_ref().unwrap(),
+            "123e4567-e89b-12d3-a456-426614174000"
+        );
+        assert_eq!(body["customAggregationUnits"], json!(["promotion_a"]));
+        assert!(body.get("notificationDisabled").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_too_many_messages_rejected() {
+        let client = LineApiClient::builder("test_token")
+            .dry_run(true)
+            .without_message_validation()
+            .build()
+            .unwrap();
+        let send = (0..6).fold(
+            client.push("U1234567890abcdef1234567890abcdef"),
+            |send, i| send.text(i.to_string()),
+        );
+
+        let err = send.send().await.unwrap_err();
+        assert!(err.message.contains("messages exceeds limit: 6 > 5"));
+    }
+}
@@ -0,0 +1,163 @@
+use std::{collections::HashMap, time::Duration};
+use tokio::{sync::Mutex, time::Instant};
+use tracing::debug;
+
+/// 單一端點的速率上限
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SendRateLimit {
+    pub requests: u32,
+    pub per: Duration,
+}
+
+impl SendRateLimit {
+    pub const fn per_second(requests: u32) -> Self {
+        Self {
+            requests,
+            per: Duration::from_secs(1),
+        }
+    }
+
+    pub const fn per_hour(requests: u32) -> Self {
+        Self {
+            requests,
+            per: Duration::from_secs(3600),
+        }
+    }
+
+    fn rate_per_sec(&self) -> f64 {
+        self.requests as f64 / self.per.as_secs_f64()
+    }
+}
+
+/// 令牌桶，令牌可為負值代表已預約的等待
+#[derive(Debug)]
+struct TokenBucket {
+    limit: SendRateLimit,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: SendRateLimit) -> Self {
+        Self {
+            limit,
+            tokens: limit.requests as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// 取得一個令牌，回傳需要等待的時間
+    fn reserve(&mut self) -> Duration {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        let rate = self.limit.rate_per_sec();
+        self.tokens = (self.tokens + elapsed * rate).min(self.limit.requests as f64);
+        self.last_refill = now;
+
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / rate)
+        }
+    }
+}
+
+/// 對 LINE API 的送出速率限制
+///
+/// 依端點分別計算，超過上限時延後送出而不是直接拿到 429，藉此平滑突發流量。
+#[derive(Debug)]
+pub struct SendRateLimiter {
+    limits: HashMap<String, SendRateLimit>,
+    default_limit: SendRateLimit,
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+impl SendRateLimiter {
+    pub fn new(default_limit: SendRateLimit) -> Self {
+        Self {
+            limits: HashMap::new(),
+            default_limit,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 依 LINE 文件公告的各端點上限
+    pub fn line_defaults() -> Self {
+        Self::new(SendRateLimit::per_second(2_000))
+            .limit("multicast", SendRateLimit::per_second(200))
+            .limit("narrowcast", SendRateLimit::per_hour(60))
+            .limit("broadcast", SendRateLimit::per_hour(60))
+    }
+
+    /// 設定特定端點的上限
+    pub fn limit(mut self, api_type: &str, limit: SendRateLimit) -> Self {
+        self.limits.insert(api_type.to_string(), limit);
+        self
+    }
+
+    /// 等待直到可以呼叫指定端點
+    pub async fn acquire(&self, api_type: &str) {
+        let wait = {
+            let mut buckets = self.buckets.lock().await;
+            let limit = self
+                .limits
+                .get(api_type)
+                .copied()
+                .unwrap_or(self.default_limit);
+            buckets
+                .entry(api_type.to_string())
+                .or_insert_with(|| TokenBucket::new(limit))
+                .reserve()
+        };
+
+        if !wait.is_zero() {
+            debug!("Throttling LINE API {} call for {:?}", api_type, wait);
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+impl Default for SendRateLimiter {
+    fn default() -> Self {
+        Self::line_defaults()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_burst_within_limit_is_not_delayed() {
+        let limiter = SendRateLimiter::new(SendRateLimit::per_second(5));
+        let start = Instant::now();
+        for _ in 0..5 {
+            limiter.acquire("push").await;
+        }
+        assert!(start.elapsed() < Duration::from_millis(10));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_excess_requests_are_spread_out() {
+        let limiter = SendRateLimiter::new(SendRateLimit::per_second(2));
+        let start = Instant::now();
+        for _ in 0..4 {
+            limiter.acquire("push").await;
+        }
+        // 前兩個立即送出，之後每 500ms 一個
+        assert!(start.elapsed() >= Duration::from_millis(1000));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_limits_are_per_endpoint() {
+        let limiter = SendRateLimiter::new(SendRateLimit::per_second(1))
+            .limit("reply", SendRateLimit::per_second(100));
+        let start = Instant::now();
+        limiter.acquire("push").await;
+        for _ in 0..10 {
+            limiter.acquire("reply").await;
+        }
+        assert!(start.elapsed() < Duration::from_millis(10));
+    }
+}
@@ -0,0 +1,75 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use serde::Deserialize;
+use std::path::PathBuf;
+use tokio::fs;
+
+use crate::media::{MediaError, MediaStore};
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LocalMediaConfig {
+    pub dir: PathBuf,
+    /// 對外提供檔案的網址前綴；未設定時回傳 `file://` 網址
+    pub public_base_url: Option<String>,
+}
+
+/// 保存在本機目錄的媒體
+#[derive(Debug, Clone)]
+pub struct LocalMediaStore {
+    config: LocalMediaConfig,
+}
+
+impl LocalMediaStore {
+    pub fn new(config: LocalMediaConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl MediaStore for LocalMediaStore {
+    async fn put(
+        &self,
+        key: &str,
+        _content_type: Option<&str>,
+        data: Bytes,
+    ) -> Result<String, MediaError> {
+        if key.contains("..") || key.starts_with('/') {
+            return Err(MediaError::new(format!("Invalid media key: {}", key)));
+        }
+
+        let path = self.config.dir.join(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::write(&path, &data).await?;
+
+        Ok(match &self.config.public_base_url {
+            Some(base_url) => format!("{}/{}", base_url.trim_end_matches('/'), key),
+            None => format!("file://{}", path.display()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_local_store_writes_file() {
+        let dir = std::env::temp_dir().join(format!("linebot-media-{}", std::process::id()));
+        let store = LocalMediaStore::new(LocalMediaConfig {
+            dir: dir.clone(),
+            public_base_url: Some("https://cdn.example.com/media/".to_string()),
+        });
+
+        let url = store
+            .put("100.jpg", Some("image/jpeg"), Bytes::from_static(b"jpeg"))
+            .await
+            .unwrap();
+        assert_eq!(url, "https://cdn.example.com/media/100.jpg");
+        assert_eq!(std::fs::read(dir.join("100.jpg")).unwrap(), b"jpeg");
+        assert!(store.put("../x", None, Bytes::new()).await.is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,241 @@
+//! 媒體下載與儲存
+//!
+//! 收到圖片、影片、音訊或檔案訊息時，透過 content API 下載內容並交給
+//! `MediaStore` 保存，回傳可供後續處理使用的網址。
+
+pub mod local;
+pub mod s3;
+
+pub use local::*;
+pub use s3::*;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use serde::Deserialize;
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::info;
+
+use crate::line_api::{LineApiClient, LineApiError};
+use crate::models::MessageType;
+
+/// 查詢影片與音訊轉檔狀態的間隔
+const TRANSCODING_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 等待轉檔完成的上限
+const TRANSCODING_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 預設的單一媒體大小上限
+pub const DEFAULT_MAX_MEDIA_BYTES: usize = 20 * 1024 * 1024;
+
+#[derive(Debug)]
+pub struct MediaError {
+    pub message: String,
+}
+
+impl MediaError {
+    pub fn new<T: Into<String>>(message: T) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for MediaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Media Error: {}", self.message)
+    }
+}
+
+impl Error for MediaError {}
+
+impl From<LineApiError> for MediaError {
+    fn from(e: LineApiError) -> Self {
+        Self::new(format!("Failed to download content: {}", e))
+    }
+}
+
+impl From<std::io::Error> for MediaError {
+    fn from(e: std::io::Error) -> Self {
+        Self::new(e.to_string())
+    }
+}
+
+/// 已保存的媒體
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredMedia {
+    pub key: String,
+    pub url: String,
+    pub content_type: Option<String>,
+    pub size: usize,
+}
+
+#[async_trait]
+pub trait MediaStore: Send + Sync {
+    /// 以 `key` 保存內容並回傳存取網址
+    async fn put(
+        &self,
+        key: &str,
+        content_type: Option<&str>,
+        data: Bytes,
+    ) -> Result<String, MediaError>;
+}
+
+/// 媒體儲存設定
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum MediaStoreConfig {
+    Local(LocalMediaConfig),
+    S3(S3MediaConfig),
+}
+
+impl MediaStoreConfig {
+    pub fn build(&self) -> Arc<dyn MediaStore> {
+        self.build_with_client(&reqwest::Client::new())
+    }
+
+    /// S3 上傳使用共用的 HTTP 用戶端
+    pub fn build_with_client(&self, client: &reqwest::Client) -> Arc<dyn MediaStore> {
+        match self {
+            MediaStoreConfig::Local(config) => Arc::new(LocalMediaStore::new(config.clone())),
+            MediaStoreConfig::S3(config) => {
+                Arc::new(S3MediaStore::new(config.clone()).http_client(client.clone()))
+            }
+        }
+    }
+}
+
+/// 下載並保存媒體訊息的內容
+#[derive(Clone)]
+pub struct MediaPipeline {
+    client: LineApiClient,
+    store: Arc<dyn MediaStore>,
+    max_bytes: usize,
+}
+
+impl MediaPipeline {
+    pub fn new(client: LineApiClient, store: Arc<dyn MediaStore>) -> Self {
+        Self {
+            client,
+            store,
+            max_bytes: DEFAULT_MAX_MEDIA_BYTES,
+        }
+    }
+
+    /// 單一媒體的大小上限，超過時中止下載
+    pub fn max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// 處理媒體訊息；文字、貼圖或外部網址的內容回傳 `None`
+    pub async fn process(&self, message: &MessageType) -> Result<Option<StoredMedia>, MediaError> {
+        let Some(message_id) = message.content_message_id() else {
+            return Ok(None);
+        };
+
+        // 大型影片與音訊要等 LINE 轉檔完成才能下載
+        if matches!(
+            message,
+            MessageType::Video { .. } | MessageType::Audio { .. }
+        ) {
+            self.client
+                .wait_for_content_transcoding(
+                    message_id,
+                    TRANSCODING_POLL_INTERVAL,
+                    TRANSCODING_TIMEOUT,
+                )
+                .await?;
+        }
+        let content = self
+            .client
+            .get_message_content_with_limit(message_id, self.max_bytes)
+            .await?;
+        let key = media_key(message, message_id, content.content_type.as_deref());
+        let size = content.data.len();
+        let url = self
+            .store
+            .put(&key, content.content_type.as_deref(), content.data)
+            .await?;
+
+        info!("Stored media {} ({} bytes)", key, size);
+        Ok(Some(StoredMedia {
+            key,
+            url,
+            content_type: content.content_type,
+            size,
+        }))
+    }
+}
+
+/// 以訊息 ID 加上副檔名作為儲存鍵值；檔案訊息沿用原始副檔名
+fn media_key(message: &MessageType, message_id: &str, content_type: Option<&str>) -> String {
+    let extension = match message {
+        MessageType::File { file_name, .. } => file_name
+            .rsplit_once('.')
+            .map(|(_, extension)| extension)
+            .filter(|extension| {
+                !extension.is_empty() && extension.chars().all(|c| c.is_ascii_alphanumeric())
+            })
+            .unwrap_or("bin"),
+        _ => extension_for(content_type),
+    };
+    format!("{}.{}", message_id, extension)
+}
+
+fn extension_for(content_type: Option<&str>) -> &'static str {
+    let mime = content_type
+        .and_then(|value| value.split(';').next())
+        .map(str::trim);
+    match mime {
+        Some("image/jpeg") => "jpg",
+        Some("image/png") => "png",
+        Some("image/gif") => "gif",
+        Some("image/webp") => "webp",
+        Some("video/mp4") => "mp4",
+        Some("audio/m4a") | Some("audio/x-m4a") | Some("audio/mp4") => "m4a",
+        Some("audio/mpeg") => "mp3",
+        _ => "bin",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ContentProvider;
+
+    #[test]
+    fn test_media_key() {
+        let image = MessageType::Image {
+            id: "100".to_string(),
+            content_provider: ContentProvider::Line,
+        };
+        assert_eq!(media_key(&image, "100", Some("image/jpeg")), "100.jpg");
+
+        let file = MessageType::File {
+            id: "200".to_string(),
+            file_name: "report.final.pdf".to_string(),
+            file_size: 10,
+        };
+        assert_eq!(media_key(&file, "200", None), "200.pdf");
+
+        let file = MessageType::File {
+            id: "300".to_string(),
+            file_name: "../../etc/passwd".to_string(),
+            file_size: 10,
+        };
+        assert_eq!(media_key(&file, "300", None), "300.bin");
+    }
+
+    #[test]
+    fn test_external_content_is_skipped() {
+        let image = MessageType::Image {
+            id: "100".to_string(),
+            content_provider: ContentProvider::External {
+                original_content_url: "https://example.com/a.jpg".to_string(),
+            },
+        };
+        assert_eq!(image.content_message_id(), None);
+    }
+}
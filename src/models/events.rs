@@ -1,12 +1,56 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
 
-#[derive(Debug, Deserialize, Serialize)]
+/// 解析 Webhook 時會保留每個事件的原始 JSON，見 [`Event::raw`]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(try_from = "RawWebhookRequest")]
 pub struct WebhookRequest {
     pub destination: String,
     pub events: Vec<Event>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Deserialize)]
+struct RawWebhookRequest {
+    destination: String,
+    events: Vec<Value>,
+}
+
+impl TryFrom<RawWebhookRequest> for WebhookRequest {
+    type Error = serde_json::Error;
+
+    fn try_from(request: RawWebhookRequest) -> Result<Self, Self::Error> {
+        let events = request
+            .events
+            .into_iter()
+            .map(Event::from_raw)
+            .collect::<Result<_, Self::Error>>()?;
+        Ok(Self {
+            destination: request.destination,
+            events,
+        })
+    }
+}
+
+/// 事件的原始 JSON，不參與序列化
+#[derive(Debug, Clone, Default)]
+pub struct RawJson(Option<Arc<Value>>);
+
+impl RawJson {
+    pub fn get(&self) -> Option<&Value> {
+        self.0.as_deref()
+    }
+}
+
+impl PartialEq for RawJson {
+    fn eq(&self, other: &Self) -> bool {
+        self.get() == other.get()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 pub enum Event {
     #[serde(rename = "message")]
@@ -21,9 +65,14 @@ pub enum Event {
     Leave(LeaveEvent),
     #[serde(rename = "postback")]
     Postback(PostbackEvent),
+    #[serde(rename = "memberJoined")]
+    MemberJoined(MemberJoinedEvent),
+    #[serde(rename = "memberLeft")]
+    MemberLeft(MemberLeftEvent),
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct MessageEvent {
     pub reply_token: String,
@@ -31,41 +80,93 @@ pub struct MessageEvent {
     pub timestamp: u64,
     pub source: Source,
     pub mode: String,
+    /// 原始 JSON，只有從 [`WebhookRequest`] 解析時才有
+    #[serde(skip)]
+    pub(crate) raw: RawJson,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct FollowEvent {
     pub reply_token: String,
     pub timestamp: u64,
     pub source: Source,
     pub mode: String,
+    /// 原始 JSON，只有從 [`WebhookRequest`] 解析時才有
+    #[serde(skip)]
+    pub(crate) raw: RawJson,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct UnfollowEvent {
     pub timestamp: u64,
     pub source: Source,
     pub mode: String,
+    /// 原始 JSON，只有從 [`WebhookRequest`] 解析時才有
+    #[serde(skip)]
+    pub(crate) raw: RawJson,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct JoinEvent {
     pub reply_token: String,
     pub timestamp: u64,
     pub source: Source,
     pub mode: String,
+    /// 原始 JSON，只有從 [`WebhookRequest`] 解析時才有
+    #[serde(skip)]
+    pub(crate) raw: RawJson,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct LeaveEvent {
     pub timestamp: u64,
     pub source: Source,
     pub mode: String,
+    /// 原始 JSON，只有從 [`WebhookRequest`] 解析時才有
+    #[serde(skip)]
+    pub(crate) raw: RawJson,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct MemberJoinedEvent {
+    pub reply_token: String,
+    pub joined: Members,
+    pub timestamp: u64,
+    pub source: Source,
+    pub mode: String,
+    /// 原始 JSON，只有從 [`WebhookRequest`] 解析時才有
+    #[serde(skip)]
+    pub(crate) raw: RawJson,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct MemberLeftEvent {
+    pub left: Members,
+    pub timestamp: u64,
+    pub source: Source,
+    pub mode: String,
+    /// 原始 JSON，只有從 [`WebhookRequest`] 解析時才有
+    #[serde(skip)]
+    pub(crate) raw: RawJson,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Members {
+    pub members: Vec<Source>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct PostbackEvent {
     pub reply_token: String,
@@ -73,14 +174,19 @@ pub struct PostbackEvent {
     pub timestamp: u64,
     pub source: Source,
     pub mode: String,
+    /// 原始 JSON，只有從 [`WebhookRequest`] 解析時才有
+    #[serde(skip)]
+    pub(crate) raw: RawJson,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct PostbackData {
     pub data: String,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 pub enum MessageType {
     #[serde(rename = "text")]
@@ -94,12 +200,207 @@ pub enum MessageType {
     },
     #[serde(rename = "image")]
     Image {
+        id: String,
         #[serde(rename = "contentProvider")]
         content_provider: ContentProvider,
     },
+    #[serde(rename = "video")]
+    Video {
+        id: String,
+        duration: Option<u64>,
+        #[serde(rename = "contentProvider")]
+        content_provider: ContentProvider,
+    },
+    #[serde(rename = "audio")]
+    Audio {
+        id: String,
+        duration: Option<u64>,
+        #[serde(rename = "contentProvider")]
+        content_provider: ContentProvider,
+    },
+    #[serde(rename = "file")]
+    File {
+        id: String,
+        #[serde(rename = "fileName")]
+        file_name: String,
+        #[serde(rename = "fileSize")]
+        file_size: u64,
+    },
+}
+
+/// LINE 驗證 Webhook URL 時送出的假 reply token
+pub const VERIFICATION_REPLY_TOKENS: [&str; 2] = [
+    "00000000000000000000000000000000",
+    "ffffffffffffffffffffffffffffffff",
+];
+
+impl WebhookRequest {
+    /// 是否為 LINE Developers Console 的 Webhook 驗證請求：沒有事件，
+    /// 或所有事件都帶著假的 reply token
+    pub fn is_verification(&self) -> bool {
+        self.events.iter().all(|event| {
+            event
+                .reply_token()
+                .is_some_and(|token| VERIFICATION_REPLY_TOKENS.contains(&token))
+        })
+    }
+}
+
+impl Event {
+    /// Webhook 中的事件類型名稱
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            Event::Message(_) => "message",
+            Event::Follow(_) => "follow",
+            Event::Unfollow(_) => "unfollow",
+            Event::Join(_) => "join",
+            Event::Leave(_) => "leave",
+            Event::Postback(_) => "postback",
+            Event::MemberJoined(_) => "memberJoined",
+            Event::MemberLeft(_) => "memberLeft",
+        }
+    }
+
+    /// 事件發生時間（毫秒）
+    pub fn timestamp(&self) -> u64 {
+        match self {
+            Event::Message(e) => e.timestamp,
+            Event::Follow(e) => e.timestamp,
+            Event::Unfollow(e) => e.timestamp,
+            Event::Join(e) => e.timestamp,
+            Event::Leave(e) => e.timestamp,
+            Event::Postback(e) => e.timestamp,
+            Event::MemberJoined(e) => e.timestamp,
+            Event::MemberLeft(e) => e.timestamp,
+        }
+    }
+
+    pub fn source(&self) -> &Source {
+        match self {
+            Event::Message(e) => &e.source,
+            Event::Follow(e) => &e.source,
+            Event::Unfollow(e) => &e.source,
+            Event::Join(e) => &e.source,
+            Event::Leave(e) => &e.source,
+            Event::Postback(e) => &e.source,
+            Event::MemberJoined(e) => &e.source,
+            Event::MemberLeft(e) => &e.source,
+        }
+    }
+
+    /// 事件的原始 JSON，可讀取型別尚未涵蓋的欄位而不必重新解析請求
+    ///
+    /// 只有從 [`WebhookRequest`] 或 [`Event::from_raw`] 解析的事件才有；自行建立或單獨反序列化的事件為 `None`。
+    pub fn raw(&self) -> Option<&Value> {
+        match self {
+            Event::Message(e) => e.raw.get(),
+            Event::Follow(e) => e.raw.get(),
+            Event::Unfollow(e) => e.raw.get(),
+            Event::Join(e) => e.raw.get(),
+            Event::Leave(e) => e.raw.get(),
+            Event::Postback(e) => e.raw.get(),
+            Event::MemberJoined(e) => e.raw.get(),
+            Event::MemberLeft(e) => e.raw.get(),
+        }
+    }
+
+    /// 解析單一事件並保留原始 JSON，例如從佇列或儲存後端讀回的事件
+    pub fn from_raw(value: Value) -> Result<Self, serde_json::Error> {
+        let mut event = Event::deserialize(&value)?;
+        event.raw_mut().0 = Some(Arc::new(value));
+        Ok(event)
+    }
+
+    fn raw_mut(&mut self) -> &mut RawJson {
+        match self {
+            Event::Message(e) => &mut e.raw,
+            Event::Follow(e) => &mut e.raw,
+            Event::Unfollow(e) => &mut e.raw,
+            Event::Join(e) => &mut e.raw,
+            Event::Leave(e) => &mut e.raw,
+            Event::Postback(e) => &mut e.raw,
+            Event::MemberJoined(e) => &mut e.raw,
+            Event::MemberLeft(e) => &mut e.raw,
+        }
+    }
+
+    pub fn reply_token(&self) -> Option<&str> {
+        match self {
+            Event::Message(e) => Some(&e.reply_token),
+            Event::Follow(e) => Some(&e.reply_token),
+            Event::Join(e) => Some(&e.reply_token),
+            Event::Postback(e) => Some(&e.reply_token),
+            Event::MemberJoined(e) => Some(&e.reply_token),
+            Event::Unfollow(_) | Event::Leave(_) | Event::MemberLeft(_) => None,
+        }
+    }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+impl Source {
+    /// 傳送者的使用者 ID，群組或聊天室中未同意提供時為 `None`
+    pub fn user_id(&self) -> Option<&str> {
+        match self {
+            Source::User { user_id } => Some(user_id),
+            Source::Group { user_id, .. } | Source::Room { user_id, .. } => user_id.as_deref(),
+        }
+    }
+
+    /// 群組來源的群組 ID
+    pub fn group_id(&self) -> Option<&str> {
+        match self {
+            Source::Group { group_id, .. } => Some(group_id),
+            _ => None,
+        }
+    }
+
+    /// 回覆或推播的對象：群組 ID、聊天室 ID，一對一聊天時為使用者 ID
+    pub fn chat_id(&self) -> &str {
+        match self {
+            Source::User { user_id } => user_id,
+            Source::Group { group_id, .. } => group_id,
+            Source::Room { room_id, .. } => room_id,
+        }
+    }
+}
+
+impl MessageType {
+    /// Webhook 中的訊息類型名稱
+    pub fn message_type(&self) -> &'static str {
+        match self {
+            MessageType::Text { .. } => "text",
+            MessageType::Sticker { .. } => "sticker",
+            MessageType::Image { .. } => "image",
+            MessageType::Video { .. } => "video",
+            MessageType::Audio { .. } => "audio",
+            MessageType::File { .. } => "file",
+        }
+    }
+
+    /// 內容存放在 LINE 伺服器、可透過 content API 下載的訊息 ID
+    pub fn content_message_id(&self) -> Option<&str> {
+        match self {
+            MessageType::Image {
+                id,
+                content_provider: ContentProvider::Line,
+            }
+            | MessageType::Video {
+                id,
+                content_provider: ContentProvider::Line,
+                ..
+            }
+            | MessageType::Audio {
+                id,
+                content_provider: ContentProvider::Line,
+                ..
+            }
+            | MessageType::File { id, .. } => Some(id),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 pub enum ContentProvider {
     #[serde(rename = "line")]
@@ -111,7 +412,8 @@ pub enum ContentProvider {
     },
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 pub enum Source {
     #[serde(rename = "user")]
@@ -134,3 +436,68 @@ pub enum Source {
         user_id: Option<String>,
     },
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_webhook_verification_detected() {
+        let request = |reply_token: &str| -> WebhookRequest {
+            serde_json::from_value(serde_json::json!({
+                "destination": "U123",
+                "events": [{
+                    "type": "message",
+                    "replyToken": reply_token,
+                    "message": {"type": "text", "text": "Hello, world"},
+                    "timestamp": 1234567890,
+                    "source": {"type": "user", "userId": "Udeadbeefdeadbeefdeadbeefdeadbeef"},
+                    "mode": "active"
+                }]
+            }))
+            .unwrap()
+        };
+
+        assert!(request("00000000000000000000000000000000").is_verification());
+        assert!(!request("nHuyWiB7yP5Zw52FIkcQobQuGDXCTA").is_verification());
+        let empty: WebhookRequest =
+            serde_json::from_str(r#"{"destination": "U123", "events": []}"#).unwrap();
+        assert!(empty.is_verification());
+    }
+
+    #[test]
+    fn test_webhook_events_keep_raw_json() {
+        let request: WebhookRequest = serde_json::from_str(
+            r#"{
+                "destination": "U123",
+                "events": [{
+                    "type": "message",
+                    "replyToken": "nHuyWiB7yP5Zw52FIkcQobQuGDXCTA",
+                    "message": {"type": "text", "text": "Hi", "quoteToken": "q3Plxr4AgKd"},
+                    "timestamp": 1234567890,
+                    "source": {"type": "user", "userId": "Udeadbeefdeadbeefdeadbeefdeadbeef"},
+                    "mode": "active",
+                    "webhookEventId": "01FZ74A0TDDPYRVKNK77XKC3ZR",
+                    "deliveryContext": {"isRedelivery": false}
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        let raw = request.events[0].raw().unwrap();
+        assert_eq!(raw["webhookEventId"], "01FZ74A0TDDPYRVKNK77XKC3ZR");
+        assert_eq!(raw["message"]["quoteToken"], "q3Plxr4AgKd");
+        // 單獨反序列化的事件沒有原始 JSON，比較時會被視為不同
+        let event: Event = serde_json::from_value(raw.clone()).unwrap();
+        assert!(event.raw().is_none());
+        assert_ne!(event, request.events[0]);
+        assert_eq!(Event::from_raw(raw.clone()).unwrap(), request.events[0]);
+        // 原始 JSON 不參與序列化
+        assert!(
+            serde_json::to_value(&request.events[0])
+                .unwrap()
+                .get("webhookEventId")
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// 這個月使用過的自訂彙總單位（`GET /v2/bot/message/aggregation/list`）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct AggregationUnitNames {
+    pub custom_aggregation_units: Vec<String>,
+    /// 還有下一頁時，作為下一次請求的 `start`
+    #[serde(default)]
+    pub next: Option<String>,
+}
+
+/// 自訂彙總單位的訊息統計（`GET /v2/bot/insight/message/event/aggregation`）
+///
+/// 人數少於 20 時 LINE 不回傳數字，對應欄位為 `None`。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AggregationUnitStatistics {
+    pub overview: AggregationOverview,
+    #[serde(default)]
+    pub messages: Vec<AggregationMessageStatistics>,
+    #[serde(default)]
+    pub clicks: Vec<AggregationClickStatistics>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct AggregationOverview {
+    #[serde(default)]
+    pub unique_impression: Option<u64>,
+    #[serde(default)]
+    pub unique_click: Option<u64>,
+    #[serde(default)]
+    pub unique_media_played: Option<u64>,
+    #[serde(default, rename = "uniqueMediaPlayed100Percent")]
+    pub unique_media_played_100_percent: Option<u64>,
+}
+
+/// 每則訊息（以氣泡順序 `seq` 區分）的曝光與影片播放數
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct AggregationMessageStatistics {
+    pub seq: u32,
+    #[serde(default)]
+    pub impression: Option<u64>,
+    #[serde(default)]
+    pub media_played: Option<u64>,
+    #[serde(default, rename = "mediaPlayed25Percent")]
+    pub media_played_25_percent: Option<u64>,
+    #[serde(default, rename = "mediaPlayed50Percent")]
+    pub media_played_50_percent: Option<u64>,
+    #[serde(default, rename = "mediaPlayed75Percent")]
+    pub media_played_75_percent: Option<u64>,
+    #[serde(default, rename = "mediaPlayed100Percent")]
+    pub media_played_100_percent: Option<u64>,
+    #[serde(default)]
+    pub unique_media_played: Option<u64>,
+    #[serde(default, rename = "uniqueMediaPlayed25Percent")]
+    pub unique_media_played_25_percent: Option<u64>,
+    #[serde(default, rename = "uniqueMediaPlayed50Percent")]
+    pub unique_media_played_50_percent: Option<u64>,
+    #[serde(default, rename = "uniqueMediaPlayed75Percent")]
+    pub unique_media_played_75_percent: Option<u64>,
+    #[serde(default, rename = "uniqueMediaPlayed100Percent")]
+    pub unique_media_played_100_percent: Option<u64>,
+}
+
+/// 訊息中每個網址的點擊數
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct AggregationClickStatistics {
+    pub seq: u32,
+    pub url: String,
+    #[serde(default)]
+    pub click: Option<u64>,
+    #[serde(default)]
+    pub unique_click: Option<u64>,
+    #[serde(default)]
+    pub unique_click_of_request: Option<u64>,
+}
+
+/// 單次 broadcast 或 narrowcast 的訊息統計（`GET /v2/bot/insight/message/event`）
+///
+/// `messages` 與 `clicks` 的欄位與自訂彙總單位統計相同。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct MessageEventStatistics {
+    pub overview: MessageEventOverview,
+    #[serde(default)]
+    pub messages: Vec<AggregationMessageStatistics>,
+    #[serde(default)]
+    pub clicks: Vec<AggregationClickStatistics>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct MessageEventOverview {
+    pub request_id: String,
+    /// 送出時間（UNIX 秒）
+    pub timestamp: i64,
+    #[serde(default)]
+    pub delivered: Option<u64>,
+    #[serde(default)]
+    pub unique_impression: Option<u64>,
+    #[serde(default)]
+    pub unique_click: Option<u64>,
+    #[serde(default)]
+    pub unique_media_played: Option<u64>,
+    #[serde(default, rename = "uniqueMediaPlayed100Percent")]
+    pub unique_media_played_100_percent: Option<u64>,
+}
+
+/// 好友數統計的狀態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum FollowerInsightStatus {
+    Ready,
+    /// 當天的統計尚未計算完成
+    Unready,
+    /// 早於 2016/11/1，沒有資料
+    OutOfService,
+}
+
+/// 指定日期的好友數統計（`GET /v2/bot/insight/followers`）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct FollowerInsight {
+    pub status: FollowerInsightStatus,
+    #[serde(default)]
+    pub followers: Option<u64>,
+    /// 可傳送訊息的好友數（依性別、年齡等推估）
+    #[serde(default)]
+    pub targeted_reaches: Option<u64>,
+    #[serde(default)]
+    pub blocks: Option<u64>,
+}
+
+/// 連續多天的好友數統計，依日期排序，每天一筆
+///
+/// 尚未計算完成或沒有資料的日期各欄位為 `None`，繪圖時可視為缺值。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct FollowerTimeSeries {
+    pub points: Vec<FollowerDataPoint>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct FollowerDataPoint {
+    pub date: NaiveDate,
+    pub status: FollowerInsightStatus,
+    pub followers: Option<u64>,
+    pub targeted_reaches: Option<u64>,
+    pub blocks: Option<u64>,
+}
+
+impl FollowerTimeSeries {
+    /// 最後一筆已計算完成的資料
+    pub fn latest(&self) -> Option<&FollowerDataPoint> {
+        self.points
+            .iter()
+            .rev()
+            .find(|point| point.status == FollowerInsightStatus::Ready)
+    }
+}
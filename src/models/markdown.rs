@@ -0,0 +1,305 @@
+//! 簡易 Markdown 轉 LINE 訊息
+//!
+//! 支援標題（`#`）、清單（`-`、`*`、`1.`）、粗體（`**粗體**`）與連結（`[文字](網址)`），
+//! 其他語法原樣保留。
+
+use serde_json::{Value, json};
+
+use crate::models::OutgoingMessage;
+use crate::utils::message_limits;
+
+/// 連結在 Flex 中的顏色
+const LINK_COLOR: &str = "#1E6FD9";
+
+/// Flex 按鈕標籤的長度上限
+const MAX_BUTTON_LABEL_LENGTH: usize = 40;
+
+/// Markdown 的輸出格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MarkdownFormat {
+    /// 文字訊息：移除標記，連結改成 `文字 (網址)`
+    PlainText,
+    /// Flex 訊息：粗體、標題以字重呈現，連結附上按鈕
+    Flex,
+    /// 有粗體或連結時使用 Flex，否則使用文字訊息
+    #[default]
+    Auto,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Inline {
+    Text(String),
+    Bold(String),
+    Link { text: String, url: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Block {
+    Heading(Vec<Inline>),
+    Paragraph(Vec<Inline>),
+    /// `marker` 為 `•` 或 `1.` 等編號
+    ListItem {
+        marker: String,
+        content: Vec<Inline>,
+    },
+}
+
+impl OutgoingMessage {
+    /// 將簡易 Markdown 轉成文字或 Flex 訊息
+    pub fn markdown(source: &str, format: MarkdownFormat) -> Self {
+        let blocks = parse_blocks(source);
+        let use_flex = match format {
+            MarkdownFormat::PlainText => false,
+            MarkdownFormat::Flex => true,
+            MarkdownFormat::Auto => blocks.iter().any(|block| {
+                block
+                    .content()
+                    .iter()
+                    .any(|inline| !matches!(inline, Inline::Text(_)))
+            }),
+        };
+
+        let text = render_plain(&blocks);
+        if use_flex {
+            let alt_text: String = text
+                .chars()
+                .take(message_limits::MAX_ALT_TEXT_LENGTH)
+                .collect();
+            OutgoingMessage::flex(alt_text, render_flex(&blocks))
+        } else {
+            OutgoingMessage::text(text)
+        }
+    }
+}
+
+impl Block {
+    fn content(&self) -> &[Inline] {
+        match self {
+            Block::Heading(content) | Block::Paragraph(content) => content,
+            Block::ListItem { content, .. } => content,
+        }
+    }
+}
+
+fn parse_blocks(source: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let flush = |paragraph: &mut Vec<&str>, blocks: &mut Vec<Block>| {
+        if !paragraph.is_empty() {
+            blocks.push(Block::Paragraph(parse_inline(&paragraph.join("\n"))));
+            paragraph.clear();
+        }
+    };
+
+    for line in source.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            flush(&mut paragraph, &mut blocks);
+        } else if let Some(heading) = parse_heading(trimmed) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::Heading(parse_inline(heading)));
+        } else if let Some((marker, item)) = parse_list_item(trimmed) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::ListItem {
+                marker,
+                content: parse_inline(item),
+            });
+        } else {
+            paragraph.push(trimmed);
+        }
+    }
+    flush(&mut paragraph, &mut blocks);
+    blocks
+}
+
+fn parse_heading(line: &str) -> Option<&str> {
+    let rest = line.trim_start_matches('#');
+    let level = line.len() - rest.len();
+    ((1..=6).contains(&level) && rest.starts_with(' ')).then(|| rest.trim())
+}
+
+fn parse_list_item(line: &str) -> Option<(String, &str)> {
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(item) = line.strip_prefix(bullet) {
+            return Some(("•".to_string(), item.trim()));
+        }
+    }
+    let (number, item) = line.split_once(". ")?;
+    (!number.is_empty() && number.chars().all(|c| c.is_ascii_digit()))
+        .then(|| (format!("{}.", number), item.trim()))
+}
+
+fn parse_inline(text: &str) -> Vec<Inline> {
+    let mut inlines = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("**")
+            && let Some(end) = after.find("**")
+            && end > 0
+        {
+            push_text(&mut inlines, &mut plain);
+            inlines.push(Inline::Bold(after[..end].to_string()));
+            rest = &after[end + 2..];
+        } else if let Some(after) = rest.strip_prefix('[')
+            && let Some((link_text, after)) = after.split_once("](")
+            && let Some((url, after)) = after.split_once(')')
+            && !link_text.is_empty()
+            && !url.is_empty()
+        {
+            push_text(&mut inlines, &mut plain);
+            inlines.push(Inline::Link {
+                text: link_text.to_string(),
+                url: url.to_string(),
+            });
+            rest = after;
+        } else {
+            let mut chars = rest.chars();
+            plain.extend(chars.next());
+            rest = chars.as_str();
+        }
+    }
+    push_text(&mut inlines, &mut plain);
+    inlines
+}
+
+fn push_text(inlines: &mut Vec<Inline>, plain: &mut String) {
+    if !plain.is_empty() {
+        inlines.push(Inline::Text(std::mem::take(plain)));
+    }
+}
+
+fn render_plain(blocks: &[Block]) -> String {
+    let mut lines = Vec::new();
+    for (index, block) in blocks.iter().enumerate() {
+        // 段落與標題前空一行，連續的清單項目不空行
+        let is_list = matches!(block, Block::ListItem { .. });
+        let previous_is_list = index > 0 && matches!(blocks[index - 1], Block::ListItem { .. });
+        if index > 0 && !(is_list && previous_is_list) {
+            lines.push(String::new());
+        }
+        let text = plain_inline(block.content());
+        lines.push(match block {
+            Block::Heading(_) => format!("【{}】", text),
+            Block::Paragraph(_) => text,
+            Block::ListItem { marker, .. } => format!("{} {}", marker, text),
+        });
+    }
+    lines.join("\n")
+}
+
+fn plain_inline(content: &[Inline]) -> String {
+    content
+        .iter()
+        .map(|inline| match inline {
+            Inline::Text(text) | Inline::Bold(text) => text.clone(),
+            Inline::Link { text, url } => format!("{} ({})", text, url),
+        })
+        .collect()
+}
+
+fn render_flex(blocks: &[Block]) -> Value {
+    let mut contents = Vec::new();
+    for block in blocks {
+        match block {
+            Block::Heading(content) => {
+                let mut text = flex_text(content);
+                text["weight"] = json!("bold");
+                text["size"] = json!("lg");
+                contents.push(text);
+            }
+            Block::Paragraph(content) => contents.push(flex_text(content)),
+            Block::ListItem { marker, content } => contents.push(json!({
+                "type": "box",
+                "layout": "horizontal",
+                "spacing": "sm",
+                "contents": [
+                    { "type": "text", "text": marker, "flex": 0 },
+                    flex_text(content),
+                ],
+            })),
+        }
+        contents.extend(block.content().iter().filter_map(flex_link_button));
+    }
+
+    json!({
+        "type": "bubble",
+        "body": {
+            "type": "box",
+            "layout": "vertical",
+            "spacing": "md",
+            "contents": contents,
+        },
+    })
+}
+
+fn flex_text(content: &[Inline]) -> Value {
+    let spans: Vec<Value> = content
+        .iter()
+        .map(|inline| match inline {
+            Inline::Text(text) => json!({ "type": "span", "text": text }),
+            Inline::Bold(text) => json!({ "type": "span", "text": text, "weight": "bold" }),
+            Inline::Link { text, .. } => json!({
+                "type": "span",
+                "text": text,
+                "color": LINK_COLOR,
+                "decoration": "underline",
+            }),
+        })
+        .collect();
+    json!({ "type": "text", "wrap": true, "flex": 1, "contents": spans })
+}
+
+/// span 不能設定 action，連結另外以按鈕呈現
+fn flex_link_button(inline: &Inline) -> Option<Value> {
+    let Inline::Link { text, url } = inline else {
+        return None;
+    };
+    let label: String = text.chars().take(MAX_BUTTON_LABEL_LENGTH).collect();
+    Some(json!({
+        "type": "button",
+        "style": "link",
+        "height": "sm",
+        "action": { "type": "uri", "label": label, "uri": url },
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "# 本週活動\n\n報名**免費**，詳情見[官網](https://example.com/event)\n\n- 週六 10:00\n- 週日 14:00\n1. 填表\n2. 等待確認";
+
+    #[test]
+    fn test_markdown_plain_text() {
+        assert_eq!(
+            OutgoingMessage::markdown(SOURCE, MarkdownFormat::PlainText),
+            OutgoingMessage::text(
+                "【本週活動】\n\n報名免費，詳情見官網 (https://example.com/event)\n\n• 週六 10:00\n• 週日 14:00\n1. 填表\n2. 等待確認"
+            )
+        );
+        assert_eq!(
+            OutgoingMessage::markdown("2 ** 3 = 8 [x]", MarkdownFormat::Auto),
+            OutgoingMessage::text("2 ** 3 = 8 [x]")
+        );
+    }
+
+    #[test]
+    fn test_markdown_flex() {
+        let OutgoingMessage::Flex { alt_text, contents } =
+            OutgoingMessage::markdown(SOURCE, MarkdownFormat::Auto)
+        else {
+            panic!("expected a flex message");
+        };
+        assert!(alt_text.starts_with("【本週活動】"));
+
+        let body = &contents["body"]["contents"];
+        assert_eq!(body[0]["weight"], "bold");
+        assert_eq!(body[1]["contents"][1]["text"], "免費");
+        assert_eq!(body[1]["contents"][1]["weight"], "bold");
+        assert_eq!(body[2]["action"]["uri"], "https://example.com/event");
+        assert_eq!(body[3]["contents"][0]["text"], "•");
+        assert_eq!(body[5]["contents"][0]["text"], "1.");
+    }
+}
@@ -1,10 +1,23 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::utils::{UserContentSanitizer, message_limits};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 pub enum OutgoingMessage {
     #[serde(rename = "text")]
-    Text { text: String },
+    Text {
+        text: String,
+        /// 顯示在輸入框上方的快速回覆按鈕
+        #[serde(
+            rename = "quickReply",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        quick_reply: Option<QuickReply>,
+    },
     #[serde(rename = "sticker")]
     Sticker {
         #[serde(rename = "packageId")]
@@ -18,9 +31,17 @@ pub enum OutgoingMessage {
         alt_text: String,
         template: TemplateType,
     },
+    /// Flex 訊息，`contents` 為 bubble 或 carousel 容器
+    #[serde(rename = "flex")]
+    Flex {
+        #[serde(rename = "altText")]
+        alt_text: String,
+        contents: serde_json::Value,
+    },
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 pub enum TemplateType {
     #[serde(rename = "buttons")]
@@ -41,9 +62,70 @@ pub enum TemplateType {
         #[serde(skip_serializing_if = "Option::is_none")]
         title: Option<String>,
     },
+    /// 兩個按鈕的確認樣板
+    #[serde(rename = "confirm")]
+    Confirm { text: String, actions: Vec<Action> },
+    #[serde(rename = "carousel")]
+    Carousel {
+        columns: Vec<CarouselColumn>,
+        #[serde(rename = "imageAspectRatio", skip_serializing_if = "Option::is_none")]
+        image_aspect_ratio: Option<String>,
+        #[serde(rename = "imageSize", skip_serializing_if = "Option::is_none")]
+        image_size: Option<String>,
+    },
+}
+
+/// 快速回覆，點選後按鈕即消失
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct QuickReply {
+    pub items: Vec<QuickReplyItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct QuickReplyItem {
+    /// 固定為 `action`
+    #[serde(rename = "type")]
+    pub item_type: String,
+    pub action: Action,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_url: Option<String>,
+}
+
+impl QuickReply {
+    pub fn new(actions: Vec<Action>) -> Self {
+        Self {
+            items: actions
+                .into_iter()
+                .map(|action| QuickReplyItem {
+                    item_type: "action".to_string(),
+                    action,
+                    image_url: None,
+                })
+                .collect(),
+        }
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// carousel 樣板的一欄
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CarouselColumn {
+    pub text: String,
+    pub actions: Vec<Action>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail_image_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_background_color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 pub enum Action {
     #[serde(rename = "message")]
@@ -59,7 +141,8 @@ pub enum Action {
     Uri { label: String, uri: String },
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ReplyMessageRequest {
     #[serde(rename = "replyToken")]
     pub reply_token: String,
@@ -71,7 +154,8 @@ pub struct ReplyMessageRequest {
     pub notification_disabled: Option<bool>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct PushMessageRequest {
     pub to: String,
     pub messages: Vec<OutgoingMessage>,
@@ -80,9 +164,15 @@ pub struct PushMessageRequest {
         skip_serializing_if = "Option::is_none"
     )]
     pub notification_disabled: Option<bool>,
+    #[serde(
+        rename = "customAggregationUnits",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub custom_aggregation_units: Option<Vec<String>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct MulticastMessageRequest {
     pub to: Vec<String>,
     pub messages: Vec<OutgoingMessage>,
@@ -91,23 +181,416 @@ pub struct MulticastMessageRequest {
         skip_serializing_if = "Option::is_none"
     )]
     pub notification_disabled: Option<bool>,
+    #[serde(
+        rename = "customAggregationUnits",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub custom_aggregation_units: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct BroadcastMessageRequest {
+    pub messages: Vec<OutgoingMessage>,
+    #[serde(
+        rename = "notificationDisabled",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub notification_disabled: Option<bool>,
+}
+
+/// Narrowcast 請求，依受眾或年齡、性別等屬性篩選對象
+///
+/// `recipient` 與 `filter` 沿用 LINE API 的 JSON 結構，例如
+/// `{"type": "audience", "audienceGroupId": 5614991017776}`；兩者皆未設定時傳送給所有好友。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct NarrowcastMessageRequest {
+    pub messages: Vec<OutgoingMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipient: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<NarrowcastLimit>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notification_disabled: Option<bool>,
+}
+
+impl NarrowcastMessageRequest {
+    pub fn new(messages: Vec<OutgoingMessage>) -> Self {
+        Self {
+            messages,
+            recipient: None,
+            filter: None,
+            limit: None,
+            notification_disabled: None,
+        }
+    }
+
+    pub fn recipient(mut self, recipient: serde_json::Value) -> Self {
+        self.recipient = Some(recipient);
+        self
+    }
+
+    pub fn filter(mut self, filter: serde_json::Value) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// 最多傳送的人數
+    pub fn max(mut self, max: u64) -> Self {
+        self.limit.get_or_insert_with(NarrowcastLimit::default).max = Some(max);
+        self
+    }
+}
+
+/// Narrowcast 的傳送人數上限
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct NarrowcastLimit {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<u64>,
+    /// 以本月剩餘的訊息額度為上限
+    #[serde(default)]
+    pub up_to_remaining_quota: bool,
 }
 
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ApiResponse {
     pub message: Option<String>,
     pub details: Option<Vec<ApiError>>,
 }
 
-#[derive(Debug, Deserialize)]
+/// 錯誤回應中的欄位錯誤，`property` 為 JSON 路徑，例如 `areas[0].bounds`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ApiError {
     pub message: String,
     pub property: String,
 }
 
+/// 訊息額度設定（`GET /v2/bot/message/quota`）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct MessageQuota {
+    #[serde(rename = "type")]
+    pub quota_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<u64>,
+}
+
+/// 本月已使用的訊息數（`GET /v2/bot/message/quota/consumption`）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct QuotaConsumption {
+    #[serde(rename = "totalUsage")]
+    pub total_usage: u64,
+}
+
+/// Bot 的基本資料（`GET /v2/bot/info`）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct BotInfo {
+    /// Webhook 的 `destination` 即為此 ID
+    pub user_id: String,
+    pub basic_id: String,
+    #[serde(default)]
+    pub premium_id: Option<String>,
+    pub display_name: String,
+    #[serde(default)]
+    pub picture_url: Option<String>,
+    /// `chat` 或 `bot`
+    pub chat_mode: String,
+    /// `auto` 或 `manual`
+    pub mark_as_read_mode: String,
+}
+
+/// 群組資訊（`GET /v2/bot/group/{groupId}/summary`）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct GroupSummary {
+    pub group_id: String,
+    pub group_name: String,
+    pub picture_url: Option<String>,
+}
+
+/// Rich menu 物件（建立與驗證時使用）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct RichMenu {
+    pub size: RichMenuSize,
+    /// 是否預設展開
+    pub selected: bool,
+    /// 管理用名稱，使用者看不到
+    pub name: String,
+    /// 聊天室下方選單列的文字
+    pub chat_bar_text: String,
+    pub areas: Vec<RichMenuArea>,
+}
+
+/// Rich menu 圖片大小（像素）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct RichMenuSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Rich menu 上可點擊的區域
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct RichMenuArea {
+    pub bounds: RichMenuBounds,
+    pub action: Action,
+}
+
+/// 區域的位置與大小，以圖片左上角為原點
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct RichMenuBounds {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Rich menu 摘要（`GET /v2/bot/richmenu/list` 的項目，省略版面設定）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct RichMenuSummary {
+    pub rich_menu_id: String,
+    pub name: String,
+    pub chat_bar_text: String,
+    pub selected: bool,
+}
+
+/// `GET /v2/bot/richmenu/list` 回應
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct RichMenuList {
+    pub richmenus: Vec<RichMenuSummary>,
+}
+
+/// 以 client credentials 發行的 channel access token
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ChannelAccessToken {
+    pub access_token: String,
+    /// 有效秒數，短期 token 為 30 天
+    pub expires_in: u64,
+    pub token_type: String,
+}
+
+/// 目前有效的 v2.1 channel access token 的 key ID
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ChannelAccessTokenKeyIds {
+    pub kids: Vec<String>,
+}
+
+/// OAuth 端點的錯誤回應
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct OAuthErrorResponse {
+    pub error: String,
+    #[serde(default)]
+    pub error_description: Option<String>,
+}
+
+/// 由訊息點擊或曝光建立受眾的請求
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAudienceRequest {
+    /// 受眾名稱，最多 120 字
+    pub description: String,
+    /// 送出訊息時回應的 `X-Line-Request-Id`
+    pub request_id: String,
+    /// 只收錄點擊這個網址的使用者（僅點擊受眾），未設定時包含訊息中所有網址
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub click_url: Option<String>,
+}
+
+/// 建立受眾的回應
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct AudienceGroup {
+    pub audience_group_id: i64,
+    /// `CLICK`、`IMP` 等
+    #[serde(rename = "type")]
+    pub audience_type: String,
+    pub description: String,
+    /// 建立時間（UNIX 秒）
+    pub created: i64,
+    #[serde(default)]
+    pub request_id: Option<String>,
+    #[serde(default)]
+    pub click_url: Option<String>,
+    #[serde(default)]
+    pub expire_timestamp: Option<i64>,
+}
+
+/// Narrowcast 的處理階段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum NarrowcastPhase {
+    Waiting,
+    Sending,
+    Succeeded,
+    Failed,
+}
+
+/// Narrowcast 的處理進度（`GET /v2/bot/message/progress/narrowcast`）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct NarrowcastProgress {
+    pub phase: NarrowcastPhase,
+    #[serde(default)]
+    pub success_count: Option<u64>,
+    #[serde(default)]
+    pub failure_count: Option<u64>,
+    #[serde(default)]
+    pub target_count: Option<u64>,
+    /// `phase` 為 `failed` 時的原因
+    #[serde(default)]
+    pub failed_description: Option<String>,
+    #[serde(default)]
+    pub error_code: Option<u32>,
+    #[serde(default)]
+    pub accepted_time: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub completed_time: Option<DateTime<Utc>>,
+}
+
+impl NarrowcastProgress {
+    /// 已送完或失敗
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.phase,
+            NarrowcastPhase::Succeeded | NarrowcastPhase::Failed
+        )
+    }
+}
+
+/// 影片與音訊內容的轉檔狀態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum TranscodingStatus {
+    Processing,
+    Succeeded,
+    Failed,
+}
+
+/// `GET /v2/bot/message/{messageId}/content/transcoding` 回應
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ContentTranscoding {
+    pub status: TranscodingStatus,
+}
+
+/// Rich menu alias（`GET /v2/bot/richmenu/alias/{richMenuAliasId}` 回應）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct RichMenuAlias {
+    pub rich_menu_alias_id: String,
+    pub rich_menu_id: String,
+}
+
+/// Rich menu 批次操作中的單一步驟
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RichMenuBatchOperation {
+    /// 把連結 `from` 的使用者改為連結 `to`
+    Link { from: String, to: String },
+    /// 取消連結 `from` 的使用者
+    Unlink { from: String },
+    /// 取消所有使用者個別連結的 rich menu
+    UnlinkAll,
+}
+
+/// `POST /v2/bot/richmenu/batch` 請求
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct RichMenuBatchRequest {
+    pub operations: Vec<RichMenuBatchOperation>,
+    /// 重新執行失敗的批次時帶入上次的 request ID，已處理的使用者會略過
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resume_request_key: Option<String>,
+}
+
+/// Rich menu 批次操作的處理階段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum RichMenuBatchPhase {
+    Ongoing,
+    Succeeded,
+    Failed,
+}
+
+/// Rich menu 批次操作的處理進度（`GET /v2/bot/richmenu/progress/batch`）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct RichMenuBatchProgress {
+    pub phase: RichMenuBatchPhase,
+    pub accepted_time: DateTime<Utc>,
+    #[serde(default)]
+    pub completed_time: Option<DateTime<Utc>>,
+}
+
+impl RichMenuBatchProgress {
+    /// 已完成或失敗
+    pub fn is_finished(&self) -> bool {
+        self.phase != RichMenuBatchPhase::Ongoing
+    }
+}
+
+/// 群組成員數（`GET /v2/bot/group/{groupId}/members/count`）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct MemberCount {
+    pub count: u64,
+}
+
 impl OutgoingMessage {
     pub fn text<T: Into<String>>(text: T) -> Self {
-        OutgoingMessage::Text { text: text.into() }
+        OutgoingMessage::Text {
+            text: text.into(),
+            quick_reply: None,
+        }
+    }
+
+    /// 附上快速回覆按鈕的文字訊息
+    pub fn text_with_quick_reply<T: Into<String>>(text: T, actions: Vec<Action>) -> Self {
+        OutgoingMessage::Text {
+            text: text.into(),
+            quick_reply: Some(QuickReply::new(actions)),
+        }
+    }
+
+    /// 在固定文字後附上使用者輸入，輸入會先經 [`UserContentSanitizer`] 淨化
+    pub fn echo(prefix: &str, user_text: &str) -> Self {
+        let sanitized = UserContentSanitizer::default().sanitize(user_text);
+        OutgoingMessage::Text {
+            text: format!("{}{}", prefix, sanitized),
+            quick_reply: None,
+        }
     }
 
     pub fn sticker<T: Into<String>>(package_id: T, sticker_id: T) -> Self {
@@ -116,4 +599,254 @@ impl OutgoingMessage {
             sticker_id: sticker_id.into(),
         }
     }
+
+    pub fn template<T: Into<String>>(alt_text: T, template: TemplateType) -> Self {
+        OutgoingMessage::Template {
+            alt_text: alt_text.into(),
+            template,
+        }
+    }
+
+    pub fn flex<T: Into<String>>(alt_text: T, contents: serde_json::Value) -> Self {
+        OutgoingMessage::Flex {
+            alt_text: alt_text.into(),
+            contents,
+        }
+    }
+
+    pub fn buttons<T: Into<String>>(alt_text: T, buttons: ButtonsTemplateBuilder) -> Self {
+        Self::template(alt_text, buttons.build())
+    }
+
+    /// 確認樣板固定為兩個按鈕，例如「是」與「否」
+    pub fn confirm<A: Into<String>, T: Into<String>>(
+        alt_text: A,
+        text: T,
+        yes: Action,
+        no: Action,
+    ) -> Self {
+        Self::template(
+            alt_text,
+            TemplateType::Confirm {
+                text: text.into(),
+                actions: vec![yes, no],
+            },
+        )
+    }
+
+    /// 欄數在編譯期檢查（1 到 10 欄）；欄數在執行期才決定時改用 [`TemplateType::carousel`]
+    pub fn carousel<T: Into<String>, const N: usize>(
+        alt_text: T,
+        columns: [CarouselColumn; N],
+    ) -> Self {
+        const {
+            assert!(
+                N >= 1 && N <= message_limits::MAX_CAROUSEL_COLUMNS,
+                "a carousel needs 1 to 10 columns"
+            )
+        };
+        Self::template(alt_text, TemplateType::carousel(columns))
+    }
+}
+
+impl From<&str> for OutgoingMessage {
+    fn from(text: &str) -> Self {
+        OutgoingMessage::text(text)
+    }
+}
+
+impl From<String> for OutgoingMessage {
+    fn from(text: String) -> Self {
+        OutgoingMessage::text(text)
+    }
+}
+
+/// 建立 `Vec<OutgoingMessage>`：文字直接寫字串，貼圖寫 `sticker(package_id, sticker_id)`，
+/// 其他項目需可轉成 [`OutgoingMessage`]
+///
+/// ```ignore
+/// client.reply_message(token, messages!["收到！", sticker(446, 1988)]).await?;
+/// ```
+#[macro_export]
+macro_rules! messages {
+    (@acc [$($out:expr),*]) => {
+        ::std::vec::Vec::<$crate::models::OutgoingMessage>::from([$($out),*])
+    };
+    (@acc [$($out:expr),*] sticker($package_id:expr, $sticker_id:expr) $(, $($rest:tt)*)?) => {
+        $crate::messages!(@acc [$($out,)* $crate::models::OutgoingMessage::sticker(
+            ::std::string::ToString::to_string(&$package_id),
+            ::std::string::ToString::to_string(&$sticker_id),
+        )] $($($rest)*)?)
+    };
+    (@acc [$($out:expr),*] $message:expr $(, $($rest:tt)*)?) => {
+        $crate::messages!(@acc [$($out,)* $crate::models::OutgoingMessage::from($message)] $($($rest)*)?)
+    };
+    ($($items:tt)*) => {
+        $crate::messages!(@acc [] $($items)*)
+    };
+}
+
+impl TemplateType {
+    /// 建立 buttons 樣板，見 [`ButtonsTemplateBuilder`]
+    pub fn buttons<T: Into<String>>(text: T) -> ButtonsTemplateBuilder {
+        ButtonsTemplateBuilder {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn carousel(columns: impl IntoIterator<Item = CarouselColumn>) -> Self {
+        TemplateType::Carousel {
+            columns: columns.into_iter().collect(),
+            image_aspect_ratio: None,
+            image_size: None,
+        }
+    }
+}
+
+impl CarouselColumn {
+    pub fn new<T: Into<String>>(text: T) -> Self {
+        Self {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn action(mut self, action: Action) -> Self {
+        self.actions.push(action);
+        self
+    }
+
+    pub fn title<T: Into<String>>(mut self, title: T) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn thumbnail_image_url<T: Into<String>>(mut self, url: T) -> Self {
+        self.thumbnail_image_url = Some(url.into());
+        self
+    }
+
+    pub fn image_background_color<T: Into<String>>(mut self, color: T) -> Self {
+        self.image_background_color = Some(color.into());
+        self
+    }
+}
+
+/// buttons 樣板的建構器，未設定的選填欄位不會序列化
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ButtonsTemplateBuilder {
+    text: String,
+    actions: Vec<Action>,
+    thumbnail_image_url: Option<String>,
+    image_aspect_ratio: Option<String>,
+    image_size: Option<String>,
+    image_background_color: Option<String>,
+    title: Option<String>,
+}
+
+impl ButtonsTemplateBuilder {
+    pub fn action(mut self, action: Action) -> Self {
+        self.actions.push(action);
+        self
+    }
+
+    pub fn actions(mut self, actions: impl IntoIterator<Item = Action>) -> Self {
+        self.actions.extend(actions);
+        self
+    }
+
+    pub fn title<T: Into<String>>(mut self, title: T) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn thumbnail_image_url<T: Into<String>>(mut self, url: T) -> Self {
+        self.thumbnail_image_url = Some(url.into());
+        self
+    }
+
+    /// `rectangle` 或 `square`
+    pub fn image_aspect_ratio<T: Into<String>>(mut self, ratio: T) -> Self {
+        self.image_aspect_ratio = Some(ratio.into());
+        self
+    }
+
+    /// `cover` 或 `contain`
+    pub fn image_size<T: Into<String>>(mut self, size: T) -> Self {
+        self.image_size = Some(size.into());
+        self
+    }
+
+    pub fn image_background_color<T: Into<String>>(mut self, color: T) -> Self {
+        self.image_background_color = Some(color.into());
+        self
+    }
+
+    pub fn build(self) -> TemplateType {
+        TemplateType::Buttons {
+            text: self.text,
+            actions: self.actions,
+            thumbnail_image_url: self.thumbnail_image_url,
+            image_aspect_ratio: self.image_aspect_ratio,
+            image_size: self.image_size,
+            image_background_color: self.image_background_color,
+            title: self.title,
+        }
+    }
+
+    /// 直接包成樣板訊息
+    pub fn into_message<T: Into<String>>(self, alt_text: T) -> OutgoingMessage {
+        OutgoingMessage::template(alt_text, self.build())
+    }
+}
+
+impl Action {
+    pub fn message<L: Into<String>, T: Into<String>>(label: L, text: T) -> Self {
+        Action::Message {
+            label: label.into(),
+            text: text.into(),
+        }
+    }
+
+    pub fn postback<L: Into<String>, D: Into<String>>(label: L, data: D) -> Self {
+        Action::Postback {
+            label: label.into(),
+            data: data.into(),
+            display_text: None,
+        }
+    }
+
+    pub fn uri<L: Into<String>, U: Into<String>>(label: L, uri: U) -> Self {
+        Action::Uri {
+            label: label.into(),
+            uri: uri.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages_macro() {
+        let reply = String::from("reply");
+        let messages = messages![
+            "hi",
+            sticker(446, 1988),
+            reply,
+            OutgoingMessage::sticker("1", "2"),
+        ];
+        assert_eq!(
+            messages,
+            vec![
+                OutgoingMessage::text("hi"),
+                OutgoingMessage::sticker("446", "1988"),
+                OutgoingMessage::text("reply"),
+                OutgoingMessage::sticker("1", "2"),
+            ]
+        );
+        assert!(messages![].is_empty());
+    }
 }
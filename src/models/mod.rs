@@ -1,5 +1,14 @@
 pub mod events;
+pub mod insight;
+pub mod markdown;
 pub mod messages;
+#[cfg(feature = "schema")]
+pub mod schema;
+pub mod stickers;
 
 pub use events::*;
+pub use insight::*;
+pub use markdown::*;
 pub use messages::*;
+#[cfg(feature = "schema")]
+pub use schema::*;
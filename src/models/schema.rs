@@ -0,0 +1,55 @@
+use schemars::schema::RootSchema;
+use schemars::schema_for;
+use std::collections::BTreeMap;
+
+use crate::models::{
+    BroadcastMessageRequest, Event, MulticastMessageRequest, OutgoingMessage, PushMessageRequest,
+    ReplyMessageRequest, RichMenu, WebhookRequest,
+};
+
+/// Webhook 請求與送出訊息相關模型的 JSON Schema，以型別名稱為鍵
+///
+/// 其餘模型同樣實作 `schemars::JsonSchema`，需要時可用 `schemars::schema_for!` 個別產生。
+/// Schema 反映本 crate 能解析的欄位，LINE 新增的欄位不會列出，也不會禁止額外欄位。
+pub fn json_schemas() -> BTreeMap<&'static str, RootSchema> {
+    BTreeMap::from([
+        ("WebhookRequest", schema_for!(WebhookRequest)),
+        ("Event", schema_for!(Event)),
+        ("OutgoingMessage", schema_for!(OutgoingMessage)),
+        ("ReplyMessageRequest", schema_for!(ReplyMessageRequest)),
+        ("PushMessageRequest", schema_for!(PushMessageRequest)),
+        (
+            "MulticastMessageRequest",
+            schema_for!(MulticastMessageRequest),
+        ),
+        (
+            "BroadcastMessageRequest",
+            schema_for!(BroadcastMessageRequest),
+        ),
+        ("RichMenu", schema_for!(RichMenu)),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_schemas() {
+        let schemas = json_schemas();
+        assert_eq!(schemas.len(), 8);
+
+        let webhook = serde_json::to_value(&schemas["WebhookRequest"]).unwrap();
+        assert_eq!(
+            webhook["required"],
+            serde_json::json!(["destination", "events"])
+        );
+        let definitions = webhook["definitions"].as_object().unwrap();
+        assert!(definitions.contains_key("Event"));
+        assert!(definitions.contains_key("Source"));
+        // 只保留在記憶體中的原始 JSON 不會出現在 schema
+        let message_event = serde_json::to_string(&definitions["Event"]).unwrap();
+        assert!(message_event.contains("\"replyToken\""));
+        assert!(!message_event.contains("\"raw\""));
+    }
+}
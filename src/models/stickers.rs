@@ -0,0 +1,141 @@
+//! 所有使用者都能傳送的官方貼圖
+//!
+//! 貼圖 ID 來自 LINE Messaging API 文件的 sticker list，Bot 只能傳送這些貼圖。
+
+use crate::models::OutgoingMessage;
+
+/// 單一貼圖
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Sticker {
+    pub package_id: u32,
+    pub sticker_id: u32,
+}
+
+impl Sticker {
+    pub const fn new(package_id: u32, sticker_id: u32) -> Self {
+        Self {
+            package_id,
+            sticker_id,
+        }
+    }
+}
+
+impl From<Sticker> for OutgoingMessage {
+    fn from(sticker: Sticker) -> Self {
+        OutgoingMessage::sticker(
+            sticker.package_id.to_string(),
+            sticker.sticker_id.to_string(),
+        )
+    }
+}
+
+/// 貼圖包，包內貼圖 ID 為連續區間
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StickerPackage {
+    pub package_id: u32,
+    pub name: &'static str,
+    pub first_sticker_id: u32,
+    pub count: u32,
+}
+
+impl StickerPackage {
+    /// 包內第 `index` 張貼圖（從 0 開始）
+    pub fn sticker(&self, index: u32) -> Option<Sticker> {
+        (index < self.count).then(|| Sticker::new(self.package_id, self.first_sticker_id + index))
+    }
+
+    pub fn contains(&self, sticker: Sticker) -> bool {
+        sticker.package_id == self.package_id
+            && (self.first_sticker_id..self.first_sticker_id + self.count)
+                .contains(&sticker.sticker_id)
+    }
+
+    pub fn stickers(&self) -> impl Iterator<Item = Sticker> + '_ {
+        (0..self.count).map(|index| Sticker::new(self.package_id, self.first_sticker_id + index))
+    }
+}
+
+pub const MOON_SPECIAL: StickerPackage = StickerPackage {
+    package_id: 446,
+    name: "Moon Special",
+    first_sticker_id: 1988,
+    count: 40,
+};
+
+pub const SALLY_SPECIAL: StickerPackage = StickerPackage {
+    package_id: 789,
+    name: "Sally Special",
+    first_sticker_id: 10855,
+    count: 40,
+};
+
+pub const BROWN_CONY_SALLY_ANIMATED: StickerPackage = StickerPackage {
+    package_id: 11537,
+    name: "Brown & Cony & Sally: Animated Special",
+    first_sticker_id: 52002734,
+    count: 40,
+};
+
+pub const CHOCO_AND_FRIENDS_ANIMATED: StickerPackage = StickerPackage {
+    package_id: 11538,
+    name: "CHOCO & Friends: Animated Special",
+    first_sticker_id: 51626494,
+    count: 40,
+};
+
+pub const BT21_ANIMATED: StickerPackage = StickerPackage {
+    package_id: 11539,
+    name: "UNIVERSTAR BT21: Animated Special",
+    first_sticker_id: 52114110,
+    count: 40,
+};
+
+pub const PACKAGES: &[StickerPackage] = &[
+    MOON_SPECIAL,
+    SALLY_SPECIAL,
+    BROWN_CONY_SALLY_ANIMATED,
+    CHOCO_AND_FRIENDS_ANIMATED,
+    BT21_ANIMATED,
+];
+
+/// Moon 揮手打招呼
+pub const MOON_HELLO: Sticker = Sticker::new(446, 1988);
+
+/// Brown 打招呼，`sticker` 指令的預設回覆
+pub const BROWN_HELLO: Sticker = Sticker::new(1, 1);
+
+/// 查詢貼圖所屬的貼圖包，不在清單中（Bot 可能無法傳送）時回傳 `None`
+pub fn lookup(package_id: u32, sticker_id: u32) -> Option<&'static StickerPackage> {
+    let sticker = Sticker::new(package_id, sticker_id);
+    PACKAGES.iter().find(|package| package.contains(sticker))
+}
+
+/// 依名稱（不分大小寫）尋找貼圖包
+pub fn find_package(name: &str) -> Option<&'static StickerPackage> {
+    PACKAGES
+        .iter()
+        .find(|package| package.name.eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(lookup(446, 1988), Some(&MOON_SPECIAL));
+        assert_eq!(lookup(446, 2027), Some(&MOON_SPECIAL));
+        assert_eq!(lookup(446, 2028), None);
+        assert_eq!(MOON_SPECIAL.sticker(0), Some(MOON_HELLO));
+        assert_eq!(MOON_SPECIAL.sticker(40), None);
+        assert_eq!(find_package("sally special"), Some(&SALLY_SPECIAL));
+        assert_eq!(
+            OutgoingMessage::from(MOON_HELLO),
+            OutgoingMessage::sticker("446", "1988")
+        );
+        assert_eq!(
+            OutgoingMessage::from(BROWN_HELLO),
+            OutgoingMessage::sticker("1", "1")
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use serde::Deserialize;
+use std::{collections::HashMap, fmt};
+
+/// 意圖解析服務
+#[derive(Clone, Deserialize, PartialEq)]
+pub enum NluProvider {
+    /// Rasa HTTP API（`POST /model/parse`）
+    Rasa { url: String, token: Option<String> },
+    /// Dialogflow CX，`agent` 為 `projects/<專案>/locations/<區域>/agents/<代理 ID>`
+    DialogflowCx {
+        agent: String,
+        language_code: String,
+        /// 未設定時向 GCE/Cloud Run 的 metadata server 取得
+        access_token: Option<String>,
+    },
+}
+
+impl fmt::Debug for NluProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NluProvider::Rasa { url, token } => f
+                .debug_struct("Rasa")
+                .field("url", url)
+                .field("token", &token.as_ref().map(|_| "***"))
+                .finish(),
+            NluProvider::DialogflowCx {
+                agent,
+                language_code,
+                access_token,
+            } => f
+                .debug_struct("DialogflowCx")
+                .field("agent", agent)
+                .field("language_code", language_code)
+                .field("access_token", &access_token.as_ref().map(|_| "***"))
+                .finish(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct NluConfig {
+    pub provider: NluProvider,
+    /// 信心分數低於此值的意圖視為無法判斷
+    pub min_confidence: f32,
+    /// 意圖名稱對應到內建指令，未列出的意圖以同名指令處理
+    pub intent_commands: HashMap<String, String>,
+}
+
+impl NluConfig {
+    pub fn new(provider: NluProvider) -> Self {
+        Self {
+            provider,
+            min_confidence: 0.6,
+            intent_commands: HashMap::new(),
+        }
+    }
+
+    /// 意圖對應的指令名稱
+    pub fn command_for<'a>(&'a self, intent: &'a str) -> &'a str {
+        self.intent_commands
+            .get(intent)
+            .map_or(intent, String::as_str)
+    }
+}
@@ -0,0 +1,242 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::Mutex;
+
+use crate::nlu::{Intent, IntentError, IntentResolver};
+
+/// 單次請求的逾時
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// GCE、Cloud Run 等環境提供服務帳戶 token 的位址
+const METADATA_TOKEN_URL: &str =
+    "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
+
+/// Dialogflow 的 session ID 長度上限
+const MAX_SESSION_ID_LENGTH: usize = 36;
+
+/// 以 Dialogflow CX `detectIntent` 解析意圖
+///
+/// 未設定 [`access_token`](Self::access_token) 時向 metadata server 取得服務帳戶的 token，
+/// 並快取到過期前一分鐘。
+#[derive(Clone)]
+pub struct DialogflowCxResolver {
+    client: reqwest::Client,
+    agent: String,
+    language_code: String,
+    endpoint: String,
+    access_token: Option<String>,
+    cached_token: Arc<Mutex<Option<(String, Instant)>>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DetectIntentResponse {
+    query_result: QueryResult,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QueryResult {
+    #[serde(rename = "match")]
+    intent_match: Option<Match>,
+    #[serde(default)]
+    parameters: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Match {
+    intent: Option<MatchedIntent>,
+    #[serde(default)]
+    confidence: f32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MatchedIntent {
+    display_name: String,
+}
+
+#[derive(Deserialize)]
+struct MetadataToken {
+    access_token: String,
+    expires_in: u64,
+}
+
+impl DialogflowCxResolver {
+    /// `agent` 為 `projects/<專案>/locations/<區域>/agents/<代理 ID>`，依區域選擇 API 端點
+    pub fn new(agent: impl Into<String>, language_code: impl Into<String>) -> Self {
+        let agent = agent.into();
+        let endpoint = match agent.split('/').nth(3) {
+            Some(location) if location != "global" => {
+                format!("https://{}-dialogflow.googleapis.com", location)
+            }
+            _ => "https://dialogflow.googleapis.com".to_string(),
+        };
+        Self {
+            client: reqwest::Client::new(),
+            agent,
+            language_code: language_code.into(),
+            endpoint,
+            access_token: None,
+            cached_token: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// 使用共用的 HTTP 用戶端
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    pub fn access_token(mut self, token: impl Into<String>) -> Self {
+        self.access_token = Some(token.into());
+        self
+    }
+
+    /// 覆寫 API 端點，測試或經由代理時使用
+    pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    async fn token(&self) -> Result<String, IntentError> {
+        if let Some(token) = &self.access_token {
+            return Ok(token.clone());
+        }
+
+        let mut cached = self.cached_token.lock().await;
+        if let Some((token, expires_at)) = cached.as_ref()
+            && Instant::now() < *expires_at
+        {
+            return Ok(token.clone());
+        }
+        let response = self
+            .client
+            .get(METADATA_TOKEN_URL)
+            .timeout(REQUEST_TIMEOUT)
+            .header("Metadata-Flavor", "Google")
+            .send()
+            .await
+            .map_err(|e| IntentError::new(format!("Failed to fetch access token: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(IntentError::from_response(response).await);
+        }
+        let token: MetadataToken = response
+            .json()
+            .await
+            .map_err(|e| IntentError::new(format!("Invalid access token response: {}", e)))?;
+        let expires_at = Instant::now() + Duration::from_secs(token.expires_in.saturating_sub(60));
+        *cached = Some((token.access_token.clone(), expires_at));
+        Ok(token.access_token)
+    }
+}
+
+#[async_trait]
+impl IntentResolver for DialogflowCxResolver {
+    async fn resolve(&self, session_id: &str, text: &str) -> Result<Option<Intent>, IntentError> {
+        let session_id: String = session_id.chars().take(MAX_SESSION_ID_LENGTH).collect();
+        let url = format!(
+            "{}/v3/{}/sessions/{}:detectIntent",
+            self.endpoint.trim_end_matches('/'),
+            self.agent,
+            session_id
+        );
+        let response = self
+            .client
+            .post(&url)
+            .timeout(REQUEST_TIMEOUT)
+            .bearer_auth(self.token().await?)
+            .json(&json!({
+                "queryInput": {
+                    "text": { "text": text },
+                    "languageCode": self.language_code,
+                },
+            }))
+            .send()
+            .await
+            .map_err(|e| IntentError::new(format!("Failed to send request: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(IntentError::from_response(response).await);
+        }
+        let result = response
+            .json::<DetectIntentResponse>()
+            .await
+            .map_err(|e| IntentError::new(format!("Invalid response: {}", e)))?
+            .query_result;
+
+        let Some(Match {
+            intent: Some(intent),
+            confidence,
+        }) = result.intent_match
+        else {
+            return Ok(None);
+        };
+        Ok(Some(Intent {
+            name: intent.display_name,
+            confidence,
+            entities: result.parameters,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Json, Router, extract::Path, http::HeaderMap, routing::post};
+
+    #[tokio::test]
+    async fn test_dialogflow_detect_intent() {
+        let app = Router::new().route(
+            "/v3/projects/demo/locations/asia-northeast1/agents/abc/sessions/:session",
+            post(
+                |Path(session): Path<String>, headers: HeaderMap, Json(body): Json<Value>| async move {
+                    assert_eq!(session, "U123:detectIntent");
+                    assert_eq!(headers["authorization"], "Bearer ya29.test");
+                    assert_eq!(body["queryInput"]["languageCode"], "zh-TW");
+                    let intent_match = if body["queryInput"]["text"]["text"] == "現在幾點" {
+                        json!({
+                            "intent": { "name": "projects/demo/.../intents/1", "displayName": "time" },
+                            "confidence": 0.8,
+                            "matchType": "INTENT",
+                        })
+                    } else {
+                        json!({ "matchType": "NO_MATCH" })
+                    };
+                    Json(json!({
+                        "queryResult": {
+                            "match": intent_match,
+                            "parameters": { "timezone": "Asia/Taipei" },
+                        },
+                    }))
+                },
+            ),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let resolver = DialogflowCxResolver::new(
+            "projects/demo/locations/asia-northeast1/agents/abc",
+            "zh-TW",
+        );
+        assert_eq!(
+            resolver.endpoint,
+            "https://asia-northeast1-dialogflow.googleapis.com"
+        );
+        let resolver = resolver
+            .endpoint(format!("http://{}", addr))
+            .access_token("ya29.test");
+
+        let intent = resolver.resolve("U123", "現在幾點").await.unwrap().unwrap();
+        assert_eq!(intent.name, "time");
+        assert_eq!(intent.entity_str("timezone"), Some("Asia/Taipei"));
+        assert_eq!(resolver.resolve("U123", "隨便").await.unwrap(), None);
+    }
+}
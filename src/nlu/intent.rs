@@ -0,0 +1,59 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use std::{collections::HashMap, error::Error, fmt};
+
+/// 解析出的意圖
+#[derive(Debug, Clone, PartialEq)]
+pub struct Intent {
+    pub name: String,
+    /// 0.0 到 1.0
+    pub confidence: f32,
+    /// 實體名稱對應到解析出的值，同名實體出現多次時只保留第一個
+    pub entities: HashMap<String, Value>,
+}
+
+impl Intent {
+    pub fn entity_str(&self, name: &str) -> Option<&str> {
+        self.entities.get(name).and_then(Value::as_str)
+    }
+}
+
+#[derive(Debug)]
+pub struct IntentError {
+    pub message: String,
+    pub status_code: Option<u16>,
+}
+
+impl fmt::Display for IntentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Intent resolution failed: {}", self.message)
+    }
+}
+
+impl Error for IntentError {}
+
+impl IntentError {
+    pub(crate) fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            status_code: None,
+        }
+    }
+
+    /// 讀取非 2xx 回應的內容作為錯誤訊息
+    pub(crate) async fn from_response(response: reqwest::Response) -> Self {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        Self {
+            message: format!("Request failed with status {}: {}", status, body),
+            status_code: Some(status.as_u16()),
+        }
+    }
+}
+
+/// 意圖解析服務
+#[async_trait]
+pub trait IntentResolver: Send + Sync {
+    /// `session_id` 用於多輪對話的上下文，通常為使用者 ID；無法判斷意圖時回傳 `None`
+    async fn resolve(&self, session_id: &str, text: &str) -> Result<Option<Intent>, IntentError>;
+}
@@ -0,0 +1,14 @@
+//! 自然語言理解：把自由輸入的文字解析為意圖與實體
+//!
+//! 指令比對失敗時，Webhook 處理流程會以 [`IntentResolver`] 解析意圖，
+//! 再依 [`NluConfig::intent_commands`] 對應到內建指令。
+
+pub mod config;
+pub mod dialogflow;
+pub mod intent;
+pub mod rasa;
+
+pub use config::*;
+pub use dialogflow::*;
+pub use intent::*;
+pub use rasa::*;
@@ -0,0 +1,142 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::{collections::HashMap, time::Duration};
+
+use crate::nlu::{Intent, IntentError, IntentResolver};
+
+/// 單次請求的逾時
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 以 Rasa HTTP API 解析意圖
+#[derive(Clone)]
+pub struct RasaResolver {
+    client: reqwest::Client,
+    url: String,
+    token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ParseResponse {
+    intent: Option<RasaIntent>,
+    #[serde(default)]
+    entities: Vec<RasaEntity>,
+}
+
+#[derive(Deserialize)]
+struct RasaIntent {
+    name: Option<String>,
+    confidence: f32,
+}
+
+#[derive(Deserialize)]
+struct RasaEntity {
+    entity: String,
+    value: Value,
+}
+
+impl RasaResolver {
+    /// `url` 為 Rasa 伺服器位址，例如 `http://rasa:5005`
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.into(),
+            token: None,
+        }
+    }
+
+    /// 使用共用的 HTTP 用戶端
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// 以 `--auth-token` 啟動 Rasa 時需要的 token
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+}
+
+#[async_trait]
+impl IntentResolver for RasaResolver {
+    async fn resolve(&self, session_id: &str, text: &str) -> Result<Option<Intent>, IntentError> {
+        let url = format!("{}/model/parse", self.url.trim_end_matches('/'));
+        let mut request = self
+            .client
+            .post(&url)
+            .timeout(REQUEST_TIMEOUT)
+            .json(&json!({ "text": text, "message_id": session_id }));
+        if let Some(token) = &self.token {
+            request = request.query(&[("token", token)]);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| IntentError::new(format!("Failed to send request: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(IntentError::from_response(response).await);
+        }
+        let parsed: ParseResponse = response
+            .json()
+            .await
+            .map_err(|e| IntentError::new(format!("Invalid response: {}", e)))?;
+
+        let Some(RasaIntent {
+            name: Some(name),
+            confidence,
+        }) = parsed.intent
+        else {
+            return Ok(None);
+        };
+        let mut entities = HashMap::new();
+        for entity in parsed.entities {
+            entities.entry(entity.entity).or_insert(entity.value);
+        }
+        Ok(Some(Intent {
+            name,
+            confidence,
+            entities,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Json, Router, extract::Query, routing::post};
+
+    #[tokio::test]
+    async fn test_rasa_parse() {
+        let app = Router::new().route(
+            "/model/parse",
+            post(
+                |Query(query): Query<HashMap<String, String>>, Json(body): Json<Value>| async move {
+                    assert_eq!(query["token"], "secret");
+                    assert_eq!(body["text"], "明天台北天氣");
+                    Json(json!({
+                        "text": "明天台北天氣",
+                        "intent": { "name": "ask_weather", "confidence": 0.93 },
+                        "entities": [
+                            { "entity": "city", "value": "台北", "start": 2, "end": 4 },
+                            { "entity": "city", "value": "其他", "start": 0, "end": 0 },
+                        ],
+                    }))
+                },
+            ),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let resolver = RasaResolver::new(format!("http://{}/", addr)).token("secret");
+        let intent = resolver
+            .resolve("U123", "明天台北天氣")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(intent.name, "ask_weather");
+        assert!(intent.confidence > 0.9);
+        assert_eq!(intent.entity_str("city"), Some("台北"));
+    }
+}
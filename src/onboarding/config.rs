@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// 引導流程設定
+///
+/// ```json
+/// { "welcome": "歡迎！", "languages": [{ "code": "zh-TW", "label": "中文" }, { "code": "en", "label": "English" }],
+///   "enrich_profile": true, "questions": [{ "key": "city", "prompt": "你住在哪個城市？" }] }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OnboardingConfig {
+    #[serde(default = "default_welcome")]
+    pub welcome: String,
+    #[serde(default = "default_language_prompt")]
+    pub language_prompt: String,
+    /// 可選擇的語言，空白時略過選擇語言
+    #[serde(default)]
+    pub languages: Vec<LanguageOption>,
+    /// 以 LINE 個人資料補上使用者名稱與語言
+    #[serde(default)]
+    pub enrich_profile: bool,
+    /// 選填的個人資料問題，依序詢問
+    #[serde(default)]
+    pub questions: Vec<ProfileQuestion>,
+    /// 略過問題的快速回覆文字
+    #[serde(default = "default_skip_label")]
+    pub skip_label: String,
+    #[serde(default = "default_completed")]
+    pub completed: String,
+}
+
+impl Default for OnboardingConfig {
+    fn default() -> Self {
+        Self {
+            welcome: default_welcome(),
+            language_prompt: default_language_prompt(),
+            languages: Vec::new(),
+            enrich_profile: false,
+            questions: Vec::new(),
+            skip_label: default_skip_label(),
+            completed: default_completed(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LanguageOption {
+    /// 語言代碼，例如 `zh-TW`、`en`
+    pub code: String,
+    /// 快速回覆按鈕上的文字
+    pub label: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileQuestion {
+    /// 答案保存在個人資料中的欄位名稱
+    pub key: String,
+    pub prompt: String,
+}
+
+fn default_welcome() -> String {
+    "歡迎使用 LINE Bot！".to_string()
+}
+
+fn default_language_prompt() -> String {
+    "請選擇你想使用的語言".to_string()
+}
+
+fn default_skip_label() -> String {
+    "略過".to_string()
+}
+
+fn default_completed() -> String {
+    "設定完成！輸入「help」查看可以做什麼。".to_string()
+}
+
+impl OnboardingConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.languages.len() > crate::utils::message_limits::MAX_QUICK_REPLY_ITEMS {
+            return Err(format!(
+                "at most {} languages are allowed",
+                crate::utils::message_limits::MAX_QUICK_REPLY_ITEMS
+            ));
+        }
+        let mut keys = HashSet::new();
+        for question in &self.questions {
+            if question.key.trim().is_empty() || question.prompt.trim().is_empty() {
+                return Err("question key and prompt must not be empty".to_string());
+            }
+            if !keys.insert(question.key.as_str()) {
+                return Err(format!("duplicate question key {}", question.key));
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn load_onboarding(path: &str) -> Result<OnboardingConfig, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let config: OnboardingConfig = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+    config.validate()?;
+    Ok(config)
+}
@@ -0,0 +1,370 @@
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::models::{Action, OutgoingMessage};
+use crate::onboarding::OnboardingConfig;
+use crate::storage::{KvNamespace, Session, Storage, StorageError, UserDataCleanup, UserRecord};
+
+/// 引導 session 的鍵前綴，後接使用者 ID
+const SESSION_PREFIX: &str = "onboarding:";
+
+/// 使用者一直沒有完成時保留進度的天數
+const ONBOARDING_TTL_DAYS: i64 = 7;
+
+/// 選擇語言的 postback `action`
+const LANGUAGE_ACTION: &str = "onboarding_language";
+
+/// 目前等待的回答
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum OnboardingStep {
+    Language,
+    Question { index: usize },
+}
+
+/// 加入好友後的引導流程
+///
+/// 語言寫入使用者記錄的 `language`，個人資料問題的答案存在 `profile` 命名空間，
+/// 以使用者 ID 為鍵的物件。答案屬於個人資料，刪除使用者時透過 [`UserDataCleanup`] 一併刪除。
+#[derive(Clone)]
+pub struct Onboarding {
+    config: Arc<OnboardingConfig>,
+    storage: Arc<dyn Storage>,
+    profiles: KvNamespace,
+}
+
+impl Onboarding {
+    pub fn new(config: OnboardingConfig, storage: Arc<dyn Storage>) -> Self {
+        Self {
+            config: Arc::new(config),
+            profiles: KvNamespace::new(storage.clone(), "profile"),
+            storage,
+        }
+    }
+
+    pub fn config(&self) -> &OnboardingConfig {
+        &self.config
+    }
+
+    /// 開始引導，回傳歡迎訊息與第一個問題
+    ///
+    /// `profile` 為 LINE 個人資料（`displayName`、`language`），啟用 `enrich_profile` 時用來補上使用者記錄。
+    pub async fn start(
+        &self,
+        user_id: &str,
+        profile: Option<&Value>,
+    ) -> Result<Vec<OutgoingMessage>, StorageError> {
+        let mut user = self.user(user_id).await?;
+        user.followed = true;
+        if self.config.enrich_profile
+            && let Some(profile) = profile
+        {
+            let field = |name: &str| {
+                profile
+                    .get(name)
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+            };
+            user.display_name = field("displayName").or(user.display_name);
+            user.language = user.language.or_else(|| field("language"));
+        }
+        self.save_user(user).await?;
+
+        let mut messages = vec![OutgoingMessage::text(&self.config.welcome)];
+        let first = if self.config.languages.is_empty() {
+            self.question_step(0)
+        } else {
+            Some(OnboardingStep::Language)
+        };
+        messages.extend(self.enter(user_id, first).await?);
+        Ok(messages)
+    }
+
+    /// 目前等待的回答，沒有進行中的引導時為 `None`
+    pub async fn step(&self, user_id: &str) -> Result<Option<OnboardingStep>, StorageError> {
+        let Some(session) = self.storage.get_session(&session_key(user_id)).await? else {
+            return Ok(None);
+        };
+        if session.is_expired(Utc::now()) {
+            return Ok(None);
+        }
+        Ok(serde_json::from_value(session.data).ok())
+    }
+
+    /// 處理引導中的文字回答；沒有進行中的引導時回傳 `None`
+    pub async fn handle_text(
+        &self,
+        user_id: &str,
+        text: &str,
+    ) -> Result<Option<Vec<OutgoingMessage>>, StorageError> {
+        let Some(step) = self.step(user_id).await? else {
+            return Ok(None);
+        };
+        let text = text.trim();
+        match step {
+            OnboardingStep::Language => {
+                let language = self.config.languages.iter().find(|language| {
+                    language.label == text || language.code.eq_ignore_ascii_case(text)
+                });
+                let Some(language) = language else {
+                    return Ok(Some(vec![self.prompt(step)]));
+                };
+                let code = language.code.clone();
+                self.set_language(user_id, &code).await?;
+                self.enter(user_id, self.question_step(0)).await.map(Some)
+            }
+            OnboardingStep::Question { index } => {
+                if text != self.config.skip_label
+                    && let Some(question) = self.config.questions.get(index)
+                {
+                    let mut profile: BTreeMap<String, String> =
+                        self.profiles.get(user_id).await?.unwrap_or_default();
+                    profile.insert(question.key.clone(), text.to_string());
+                    self.profiles.set(user_id, &profile).await?;
+                }
+                self.enter(user_id, self.question_step(index + 1))
+                    .await
+                    .map(Some)
+            }
+        }
+    }
+
+    /// 處理選擇語言的 postback；不是引導的 postback 時回傳 `None`
+    ///
+    /// 引導結束後按下舊的按鈕仍會切換語言。
+    pub async fn handle_postback(
+        &self,
+        user_id: &str,
+        data: &str,
+    ) -> Result<Option<Vec<OutgoingMessage>>, StorageError> {
+        let Some(code) = parse_language_postback(data) else {
+            return Ok(None);
+        };
+        let Some(language) = self
+            .config
+            .languages
+            .iter()
+            .find(|language| language.code == code)
+        else {
+            return Ok(None);
+        };
+        self.set_language(user_id, &language.code).await?;
+        if self.step(user_id).await? == Some(OnboardingStep::Language) {
+            return self.enter(user_id, self.question_step(0)).await.map(Some);
+        }
+        Ok(Some(vec![OutgoingMessage::text(format!(
+            "已切換語言：{}",
+            language.label
+        ))]))
+    }
+
+    /// 個人資料問題的答案
+    pub async fn profile(&self, user_id: &str) -> Result<BTreeMap<String, String>, StorageError> {
+        Ok(self.profiles.get(user_id).await?.unwrap_or_default())
+    }
+
+    fn question_step(&self, index: usize) -> Option<OnboardingStep> {
+        (index < self.config.questions.len()).then_some(OnboardingStep::Question { index })
+    }
+
+    /// 進入下一步並回傳提示；沒有下一步時結束引導
+    async fn enter(
+        &self,
+        user_id: &str,
+        step: Option<OnboardingStep>,
+    ) -> Result<Vec<OutgoingMessage>, StorageError> {
+        let key = session_key(user_id);
+        let Some(step) = step else {
+            self.storage.delete_session(&key).await?;
+            return Ok(vec![OutgoingMessage::text(&self.config.completed)]);
+        };
+        self.storage
+            .save_session(&Session {
+                user_id: key,
+                data: serde_json::to_value(step)?,
+                expires_at: Some(Utc::now() + Duration::days(ONBOARDING_TTL_DAYS)),
+            })
+            .await?;
+        Ok(vec![self.prompt(step)])
+    }
+
+    fn prompt(&self, step: OnboardingStep) -> OutgoingMessage {
+        match step {
+            OnboardingStep::Language => OutgoingMessage::text_with_quick_reply(
+                &self.config.language_prompt,
+                self.config
+                    .languages
+                    .iter()
+                    .map(|language| Action::Postback {
+                        label: language.label.clone(),
+                        data: format!("action={}&language={}", LANGUAGE_ACTION, language.code),
+                        display_text: Some(language.label.clone()),
+                    })
+                    .collect(),
+            ),
+            OnboardingStep::Question { index } => OutgoingMessage::text_with_quick_reply(
+                self.config
+                    .questions
+                    .get(index)
+                    .map(|question| question.prompt.as_str())
+                    .unwrap_or_default(),
+                vec![Action::Message {
+                    label: self.config.skip_label.clone(),
+                    text: self.config.skip_label.clone(),
+                }],
+            ),
+        }
+    }
+
+    async fn user(&self, user_id: &str) -> Result<UserRecord, StorageError> {
+        Ok(self
+            .storage
+            .get_user(user_id)
+            .await?
+            .unwrap_or_else(|| UserRecord {
+                user_id: user_id.to_string(),
+                display_name: None,
+                language: None,
+                followed: true,
+                updated_at: Utc::now(),
+            }))
+    }
+
+    async fn save_user(&self, mut user: UserRecord) -> Result<(), StorageError> {
+        user.updated_at = Utc::now();
+        self.storage.upsert_user(&user).await
+    }
+
+    async fn set_language(&self, user_id: &str, code: &str) -> Result<(), StorageError> {
+        let mut user = self.user(user_id).await?;
+        user.language = Some(code.to_string());
+        self.save_user(user).await
+    }
+}
+
+#[async_trait]
+impl UserDataCleanup for Onboarding {
+    /// 刪除個人資料問題的答案與進行中的引導
+    async fn purge_user(&self, user_id: &str) -> Result<(), StorageError> {
+        self.profiles.delete(user_id).await?;
+        self.storage.delete_session(&session_key(user_id)).await
+    }
+}
+
+fn session_key(user_id: &str) -> String {
+    format!("{}{}", SESSION_PREFIX, user_id)
+}
+
+fn parse_language_postback(data: &str) -> Option<&str> {
+    let mut action = None;
+    let mut language = None;
+    for (key, value) in data.split('&').filter_map(|pair| pair.split_once('=')) {
+        match key {
+            "action" => action = Some(value),
+            "language" => language = Some(value),
+            _ => {}
+        }
+    }
+    if action == Some(LANGUAGE_ACTION) {
+        language
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::onboarding::{LanguageOption, ProfileQuestion};
+    use crate::storage::MemoryStorage;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_onboarding_flow() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let config = OnboardingConfig {
+            languages: vec![
+                LanguageOption {
+                    code: "zh-TW".to_string(),
+                    label: "中文".to_string(),
+                },
+                LanguageOption {
+                    code: "en".to_string(),
+                    label: "English".to_string(),
+                },
+            ],
+            enrich_profile: true,
+            questions: vec![
+                ProfileQuestion {
+                    key: "city".to_string(),
+                    prompt: "你住在哪個城市？".to_string(),
+                },
+                ProfileQuestion {
+                    key: "job".to_string(),
+                    prompt: "你的職業是？".to_string(),
+                },
+            ],
+            ..Default::default()
+        };
+        let onboarding = Onboarding::new(config, storage.clone());
+
+        let profile = json!({ "displayName": "Taro", "language": "ja" });
+        let messages = onboarding.start("U1", Some(&profile)).await.unwrap();
+        assert_eq!(messages.len(), 2);
+        let OutgoingMessage::Text {
+            quick_reply: Some(quick_reply),
+            ..
+        } = &messages[1]
+        else {
+            panic!("Expected language quick reply");
+        };
+        assert_eq!(quick_reply.items.len(), 2);
+        let user = storage.get_user("U1").await.unwrap().unwrap();
+        assert_eq!(user.display_name.as_deref(), Some("Taro"));
+        assert_eq!(user.language.as_deref(), Some("ja"));
+
+        // 不在選項內的回答會重新詢問
+        let messages = onboarding.handle_text("U1", "日本語").await.unwrap();
+        assert_eq!(
+            onboarding.step("U1").await.unwrap(),
+            Some(OnboardingStep::Language)
+        );
+        assert_eq!(messages.unwrap().len(), 1);
+
+        let messages = onboarding
+            .handle_postback("U1", "action=onboarding_language&language=en")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(
+            matches!(&messages[0], OutgoingMessage::Text { text, .. } if text == "你住在哪個城市？")
+        );
+        let user = storage.get_user("U1").await.unwrap().unwrap();
+        assert_eq!(user.language.as_deref(), Some("en"));
+
+        onboarding.handle_text("U1", "台北").await.unwrap();
+        onboarding.handle_text("U1", "略過").await.unwrap();
+        assert_eq!(onboarding.step("U1").await.unwrap(), None);
+        assert_eq!(
+            onboarding.profile("U1").await.unwrap(),
+            BTreeMap::from([("city".to_string(), "台北".to_string())])
+        );
+        assert_eq!(onboarding.handle_text("U1", "hello").await.unwrap(), None);
+        assert_eq!(
+            onboarding
+                .handle_postback("U1", "action=buy")
+                .await
+                .unwrap(),
+            None
+        );
+
+        onboarding.start("U1", None).await.unwrap();
+        onboarding.purge_user("U1").await.unwrap();
+        assert_eq!(onboarding.step("U1").await.unwrap(), None);
+        assert!(onboarding.profile("U1").await.unwrap().is_empty());
+    }
+}
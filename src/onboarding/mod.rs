@@ -0,0 +1,10 @@
+//! 加入好友後的引導流程
+//!
+//! 收到 `Follow` 事件時送出歡迎訊息，接著以快速回覆選擇語言，再依序詢問選填的個人資料。
+//! 進行到哪一步存在 session store，設定由 [`OnboardingConfig`] 描述，通常從 `ONBOARDING_FILE` 載入。
+
+pub mod config;
+pub mod flow;
+
+pub use config::*;
+pub use flow::*;
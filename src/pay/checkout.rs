@@ -0,0 +1,410 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::models::OutgoingMessage;
+use crate::pay::{LinePayClient, LinePayError, PaymentRequest, RedirectUrls};
+use crate::storage::{KvNamespace, Storage, StorageError};
+
+/// 「我已完成付款」按鈕的 postback `action`
+const CONFIRM_ACTION: &str = "pay_confirm";
+
+/// 建立付款後等待使用者核准的時間，與 LINE Pay 付款網址的有效時間相同
+const PENDING_PAYMENT_TTL_MINUTES: i64 = 20;
+
+/// 等待使用者核准的付款，以 transaction ID 為鍵存在 storage
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingPayment {
+    pub order_id: String,
+    pub user_id: String,
+    pub product_name: String,
+    pub amount: u64,
+    pub currency: String,
+    /// 舊版資料沒有建立時間，視為已過期
+    #[serde(default)]
+    pub created_at: DateTime<Utc>,
+}
+
+impl PendingPayment {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now - self.created_at > Duration::minutes(PENDING_PAYMENT_TTL_MINUTES)
+    }
+}
+
+/// Confirm API 成功、已完成扣款的付款
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfirmedPayment {
+    pub transaction_id: u64,
+    pub order_id: String,
+    pub product_name: String,
+    pub amount: u64,
+    pub currency: String,
+}
+
+/// 以聊天完成 LINE Pay 付款
+///
+/// [`checkout`](Self::checkout) 建立付款並回傳付款卡片；使用者在 LINE Pay 核准後按下卡片上的
+/// 「我已完成付款」，[`handle_postback`](Self::handle_postback) 再呼叫 Confirm API 扣款。
+/// 尚未核准時 Confirm 會失敗，付款仍保留，使用者可以再按一次。
+#[derive(Clone)]
+pub struct LinePayCheckout {
+    client: Arc<LinePayClient>,
+    kv: KvNamespace,
+    redirect_urls: RedirectUrls,
+    currency: String,
+}
+
+impl LinePayCheckout {
+    pub fn new(
+        client: LinePayClient,
+        storage: Arc<dyn Storage>,
+        redirect_urls: RedirectUrls,
+        currency: impl Into<String>,
+    ) -> Self {
+        Self {
+            client: Arc::new(client),
+            kv: KvNamespace::new(storage, "pay"),
+            redirect_urls,
+            currency: currency.into(),
+        }
+    }
+
+    /// 建立付款，回傳要送給使用者的付款卡片
+    pub async fn checkout(
+        &self,
+        user_id: &str,
+        order_id: &str,
+        product_name: &str,
+        amount: u64,
+    ) -> Result<OutgoingMessage, LinePayError> {
+        let request = PaymentRequest::single(
+            order_id,
+            product_name,
+            amount,
+            &self.currency,
+            self.redirect_urls.clone(),
+        );
+        let info = self.client.request_payment(&request).await?;
+        let pending = PendingPayment {
+            order_id: order_id.to_string(),
+            user_id: user_id.to_string(),
+            product_name: product_name.to_string(),
+            amount,
+            currency: self.currency.clone(),
+            created_at: Utc::now(),
+        };
+        self.kv
+            .set(&info.transaction_id.to_string(), &pending)
+            .await
+            .map_err(storage_error)?;
+
+        Ok(payment_card(
+            &pending,
+            &info.payment_url.web,
+            info.transaction_id,
+        ))
+    }
+
+    /// 處理付款卡片的 postback，不是付款 postback 時回傳 `None`
+    pub async fn handle_postback(
+        &self,
+        user_id: &str,
+        data: &str,
+    ) -> Option<Result<ConfirmedPayment, LinePayError>> {
+        let transaction_id = parse_confirm_postback(data)?;
+        Some(self.confirm(user_id, transaction_id).await)
+    }
+
+    async fn confirm(
+        &self,
+        user_id: &str,
+        transaction_id: u64,
+    ) -> Result<ConfirmedPayment, LinePayError> {
+        let key = transaction_id.to_string();
+        // 只能確認自己建立的付款
+        let pending = self
+            .kv
+            .get::<PendingPayment>(&key)
+            .await
+            .map_err(storage_error)?
+            .filter(|pending| pending.user_id == user_id)
+            .ok_or_else(|| LinePayError::new("Unknown or already confirmed payment"))?;
+        if pending.is_expired(Utc::now()) {
+            if let Err(e) = self.kv.delete(&key).await {
+                warn!("Failed to remove expired payment {}: {}", transaction_id, e);
+            }
+            return Err(LinePayError::new("Payment expired"));
+        }
+
+        self.client
+            .confirm_payment(transaction_id, pending.amount, &pending.currency)
+            .await?;
+        if let Err(e) = self.kv.delete(&key).await {
+            warn!(
+                "Failed to remove confirmed payment {}: {}",
+                transaction_id, e
+            );
+        }
+        Ok(ConfirmedPayment {
+            transaction_id,
+            order_id: pending.order_id,
+            product_name: pending.product_name,
+            amount: pending.amount,
+            currency: pending.currency,
+        })
+    }
+}
+
+impl LinePayCheckout {
+    /// 刪除逾時未確認的付款，回傳刪除的筆數
+    pub async fn remove_expired(&self, now: DateTime<Utc>) -> Result<usize, StorageError> {
+        self.kv
+            .remove_where(|pending: &PendingPayment| pending.is_expired(now))
+            .await
+    }
+
+    /// 定期清除逾時未確認的付款
+    pub fn start_cleanup(&self, interval: std::time::Duration) {
+        let checkout = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = checkout.remove_expired(Utc::now()).await {
+                    warn!("Failed to remove expired payments: {}", e);
+                }
+            }
+        });
+    }
+}
+
+fn storage_error(e: StorageError) -> LinePayError {
+    LinePayError::new(e.to_string())
+}
+
+/// 付款卡片：前往 LINE Pay 的按鈕與「我已完成付款」postback
+pub fn payment_card(
+    payment: &PendingPayment,
+    payment_url: &str,
+    transaction_id: u64,
+) -> OutgoingMessage {
+    let price = format!("{} {}", payment.amount, payment.currency);
+    let bubble = json!({
+        "type": "bubble",
+        "body": {
+            "type": "box",
+            "layout": "vertical",
+            "spacing": "sm",
+            "contents": [
+                { "type": "text", "text": "LINE Pay", "size": "xs", "color": "#06C755" },
+                { "type": "text", "text": payment.product_name, "weight": "bold", "wrap": true },
+                { "type": "text", "text": price, "size": "xl", "weight": "bold" },
+            ],
+        },
+        "footer": {
+            "type": "box",
+            "layout": "vertical",
+            "spacing": "sm",
+            "contents": [
+                {
+                    "type": "button",
+                    "style": "primary",
+                    "color": "#06C755",
+                    "action": { "type": "uri", "label": "前往付款", "uri": payment_url },
+                },
+                {
+                    "type": "button",
+                    "style": "secondary",
+                    "action": {
+                        "type": "postback",
+                        "label": "我已完成付款",
+                        "data": confirm_postback_data(transaction_id),
+                        "displayText": "我已完成付款",
+                    },
+                },
+            ],
+        },
+    });
+    OutgoingMessage::flex(format!("LINE Pay 付款：{}", price), bubble)
+}
+
+pub fn confirm_postback_data(transaction_id: u64) -> String {
+    format!("action={}&transactionId={}", CONFIRM_ACTION, transaction_id)
+}
+
+/// 解析 `action=pay_confirm&transactionId=...`
+pub fn parse_confirm_postback(data: &str) -> Option<u64> {
+    let mut action = None;
+    let mut transaction_id = None;
+    for (key, value) in data.split('&').filter_map(|pair| pair.split_once('=')) {
+        match key {
+            "action" => action = Some(value),
+            "transactionId" => transaction_id = value.parse().ok(),
+            _ => {}
+        }
+    }
+    if action == Some(CONFIRM_ACTION) {
+        transaction_id
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+    use axum::{Json, Router, body::Bytes, extract::Path, http::HeaderMap, routing::post};
+    use base64::{Engine, engine::general_purpose::STANDARD};
+    use hmac::{Hmac, Mac};
+    use serde_json::Value;
+    use sha2::Sha256;
+    use std::sync::Mutex;
+
+    const TRANSACTION_ID: u64 = 2024050112345678901;
+
+    fn verify(headers: &HeaderMap, path: &str, body: &[u8]) -> bool {
+        let nonce = headers["x-line-authorization-nonce"].to_str().unwrap();
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"secret").unwrap();
+        mac.update(format!("secret{}", path).as_bytes());
+        mac.update(body);
+        mac.update(nonce.as_bytes());
+        headers["x-line-authorization"].to_str().unwrap()
+            == STANDARD.encode(mac.finalize().into_bytes())
+            && headers["x-line-channelid"] == "1234"
+    }
+
+    #[tokio::test]
+    async fn test_checkout_and_confirm() {
+        let confirmed = Arc::new(Mutex::new(Vec::new()));
+        let store = confirmed.clone();
+        let app = Router::new()
+            .route(
+                "/v3/payments/request",
+                post(|headers: HeaderMap, body: Bytes| async move {
+                    assert!(verify(&headers, "/v3/payments/request", &body));
+                    let request: Value = serde_json::from_slice(&body).unwrap();
+                    assert_eq!(request["packages"][0]["products"][0]["price"], 300);
+                    assert_eq!(request["currency"], "TWD");
+                    // transactionId 超過 2^53，需以整數解析
+                    let body = format!(
+                        r#"{{"returnCode":"0000","returnMessage":"Success.","info":{{
+                            "transactionId":{},"paymentAccessToken":"187568751124",
+                            "paymentUrl":{{"web":"https://pay.example/web","app":"line://pay/1"}}}}}}"#,
+                        TRANSACTION_ID
+                    );
+                    ([("content-type", "application/json")], body)
+                }),
+            )
+            .route(
+                "/v3/payments/:id/confirm",
+                post(
+                    move |Path(id): Path<String>, headers: HeaderMap, body: Bytes| {
+                        let path = format!("/v3/payments/{}/confirm", id);
+                        assert!(verify(&headers, &path, &body));
+                        let mut confirmed = store.lock().unwrap();
+                        confirmed.push(serde_json::from_slice::<Value>(&body).unwrap());
+                        // 第一次模擬使用者尚未核准
+                        let response = if confirmed.len() == 1 {
+                            json!({ "returnCode": "1104", "returnMessage": "Not approved." })
+                        } else {
+                            json!({
+                                "returnCode": "0000",
+                                "returnMessage": "Success.",
+                                "info": { "orderId": "order-1", "transactionId": TRANSACTION_ID },
+                            })
+                        };
+                        async move { Json(response) }
+                    },
+                ),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = LinePayClient::new("1234", "secret").endpoint(format!("http://{}", addr));
+        let checkout = LinePayCheckout::new(
+            client,
+            Arc::new(MemoryStorage::new()),
+            RedirectUrls {
+                confirm_url: "https://example.com/paid".to_string(),
+                cancel_url: "https://example.com/cancel".to_string(),
+            },
+            "TWD",
+        );
+
+        let card = checkout
+            .checkout("U1", "order-1", "咖啡豆", 300)
+            .await
+            .unwrap();
+        let card = serde_json::to_value(&card).unwrap();
+        let buttons = &card["contents"]["footer"]["contents"];
+        assert_eq!(buttons[0]["action"]["uri"], "https://pay.example/web");
+        let data = buttons[1]["action"]["data"].as_str().unwrap();
+        assert_eq!(parse_confirm_postback(data), Some(TRANSACTION_ID));
+
+        assert!(
+            checkout
+                .handle_postback("U1", "action=other")
+                .await
+                .is_none()
+        );
+        // 其他使用者不能確認
+        assert!(checkout.handle_postback("U2", data).await.unwrap().is_err());
+        let error = checkout
+            .handle_postback("U1", data)
+            .await
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(error.return_code.as_deref(), Some("1104"));
+
+        let payment = checkout.handle_postback("U1", data).await.unwrap().unwrap();
+        assert_eq!(payment.order_id, "order-1");
+        assert_eq!(payment.amount, 300);
+        assert_eq!(
+            confirmed.lock().unwrap()[1],
+            json!({ "amount": 300, "currency": "TWD" })
+        );
+        // 完成後不能重複確認
+        assert!(checkout.handle_postback("U1", data).await.unwrap().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_expired_payments_removed() {
+        let storage = Arc::new(MemoryStorage::new());
+        let checkout = LinePayCheckout::new(
+            LinePayClient::new("1234", "secret").endpoint("http://127.0.0.1:1"),
+            storage.clone(),
+            RedirectUrls {
+                confirm_url: "https://example.com/paid".to_string(),
+                cancel_url: "https://example.com/cancel".to_string(),
+            },
+            "TWD",
+        );
+        let now = Utc::now();
+        let pending = |minutes_ago: i64| PendingPayment {
+            order_id: "order-1".to_string(),
+            user_id: "U1".to_string(),
+            product_name: "咖啡豆".to_string(),
+            amount: 300,
+            currency: "TWD".to_string(),
+            created_at: now - Duration::minutes(minutes_ago),
+        };
+        checkout.kv.set("1", &pending(30)).await.unwrap();
+        checkout.kv.set("2", &pending(5)).await.unwrap();
+
+        // 逾時的付款不會呼叫 Confirm API
+        let error = checkout
+            .handle_postback("U1", &confirm_postback_data(1))
+            .await
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(error.message, "Payment expired");
+
+        checkout.kv.set("1", &pending(30)).await.unwrap();
+        assert_eq!(checkout.remove_expired(now).await.unwrap(), 1);
+        assert_eq!(checkout.kv.keys("").await.unwrap(), vec!["2".to_string()]);
+    }
+}
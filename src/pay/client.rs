@@ -0,0 +1,264 @@
+use base64::{Engine, engine::general_purpose::STANDARD};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::json;
+use sha2::Sha256;
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use crate::utils::random_uuid;
+
+/// 單次請求的逾時
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(40);
+
+const PRODUCTION_ENDPOINT: &str = "https://api-pay.line.me";
+const SANDBOX_ENDPOINT: &str = "https://sandbox-api-pay.line.me";
+
+/// 成功時的 `returnCode`
+const SUCCESS_RETURN_CODE: &str = "0000";
+
+#[derive(Debug)]
+pub struct LinePayError {
+    pub message: String,
+    /// LINE Pay 回傳的 `returnCode`，請求未完成時為 `None`
+    pub return_code: Option<String>,
+}
+
+impl LinePayError {
+    pub fn new<T: Into<String>>(message: T) -> Self {
+        Self {
+            message: message.into(),
+            return_code: None,
+        }
+    }
+}
+
+impl fmt::Display for LinePayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.return_code {
+            Some(code) => write!(f, "LINE Pay Error {}: {}", code, self.message),
+            None => write!(f, "LINE Pay Error: {}", self.message),
+        }
+    }
+}
+
+impl Error for LinePayError {}
+
+/// Request API 的請求內容（`POST /v3/payments/request`）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentRequest {
+    pub amount: u64,
+    pub currency: String,
+    pub order_id: String,
+    pub packages: Vec<PaymentPackage>,
+    pub redirect_urls: RedirectUrls,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PaymentPackage {
+    pub id: String,
+    pub amount: u64,
+    pub name: String,
+    pub products: Vec<PaymentProduct>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentProduct {
+    pub name: String,
+    pub quantity: u32,
+    pub price: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_url: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedirectUrls {
+    pub confirm_url: String,
+    pub cancel_url: String,
+}
+
+impl PaymentRequest {
+    /// 只有一項商品的付款請求
+    pub fn single(
+        order_id: impl Into<String>,
+        product_name: impl Into<String>,
+        amount: u64,
+        currency: impl Into<String>,
+        redirect_urls: RedirectUrls,
+    ) -> Self {
+        let order_id = order_id.into();
+        let product_name = product_name.into();
+        Self {
+            amount,
+            currency: currency.into(),
+            packages: vec![PaymentPackage {
+                id: order_id.clone(),
+                amount,
+                name: product_name.clone(),
+                products: vec![PaymentProduct {
+                    name: product_name,
+                    quantity: 1,
+                    price: amount,
+                    image_url: None,
+                }],
+            }],
+            order_id,
+            redirect_urls,
+        }
+    }
+}
+
+/// Request API 回傳的付款資訊
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentInfo {
+    pub transaction_id: u64,
+    pub payment_access_token: String,
+    pub payment_url: PaymentUrl,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PaymentUrl {
+    /// 在瀏覽器開啟的付款頁面
+    pub web: String,
+    /// 直接開啟 LINE Pay 的 app URL
+    pub app: String,
+}
+
+/// Confirm API 回傳的結果
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmInfo {
+    pub order_id: String,
+    pub transaction_id: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiResponse<T> {
+    return_code: String,
+    return_message: String,
+    info: Option<T>,
+}
+
+/// LINE Pay v3 API 客戶端
+///
+/// 每個請求以 `X-LINE-Authorization`（channel secret 的 HMAC-SHA256）簽名。
+pub struct LinePayClient {
+    client: reqwest::Client,
+    channel_id: String,
+    channel_secret: String,
+    endpoint: String,
+}
+
+impl LinePayClient {
+    pub fn new(channel_id: impl Into<String>, channel_secret: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            channel_id: channel_id.into(),
+            channel_secret: channel_secret.into(),
+            endpoint: PRODUCTION_ENDPOINT.to_string(),
+        }
+    }
+
+    /// 使用共用的 HTTP 用戶端
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// 改用 sandbox 環境
+    pub fn sandbox(self, enabled: bool) -> Self {
+        let endpoint = if enabled {
+            SANDBOX_ENDPOINT
+        } else {
+            PRODUCTION_ENDPOINT
+        };
+        self.endpoint(endpoint)
+    }
+
+    /// 覆寫 API 端點，測試時使用
+    pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// 建立付款，使用者需開啟回傳的 `payment_url` 核准
+    pub async fn request_payment(
+        &self,
+        request: &PaymentRequest,
+    ) -> Result<PaymentInfo, LinePayError> {
+        self.post("/v3/payments/request", request).await
+    }
+
+    /// 使用者核准後完成扣款，金額與幣別需與建立付款時相同
+    pub async fn confirm_payment(
+        &self,
+        transaction_id: u64,
+        amount: u64,
+        currency: &str,
+    ) -> Result<ConfirmInfo, LinePayError> {
+        let path = format!("/v3/payments/{}/confirm", transaction_id);
+        self.post(&path, &json!({ "amount": amount, "currency": currency }))
+            .await
+    }
+
+    async fn post<B: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, LinePayError> {
+        let body = serde_json::to_string(body)
+            .map_err(|e| LinePayError::new(format!("Invalid request: {}", e)))?;
+        // 每個請求不同的 nonce
+        let nonce = random_uuid();
+        let response = self
+            .client
+            .post(format!("{}{}", self.endpoint, path))
+            .timeout(REQUEST_TIMEOUT)
+            .header("Content-Type", "application/json")
+            .header("X-LINE-ChannelId", &self.channel_id)
+            .header("X-LINE-Authorization-Nonce", &nonce)
+            .header(
+                "X-LINE-Authorization",
+                signature(&self.channel_secret, path, &body, &nonce),
+            )
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| LinePayError::new(format!("Failed to send request: {}", e)))?;
+
+        let status = response.status();
+        let text = response
+            .text()
+            .await
+            .map_err(|e| LinePayError::new(format!("Failed to read response: {}", e)))?;
+        let response: ApiResponse<T> = serde_json::from_str(&text).map_err(|e| {
+            LinePayError::new(format!("Invalid response with status {}: {}", status, e))
+        })?;
+        if response.return_code != SUCCESS_RETURN_CODE {
+            return Err(LinePayError {
+                message: response.return_message,
+                return_code: Some(response.return_code),
+            });
+        }
+        response
+            .info
+            .ok_or_else(|| LinePayError::new("Response has no info"))
+    }
+}
+
+/// `Base64(HMAC-SHA256(secret, secret + path + body + nonce))`
+fn signature(channel_secret: &str, path: &str, body: &str, nonce: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(channel_secret.as_bytes())
+        .expect("HMAC accepts any key size");
+    mac.update(channel_secret.as_bytes());
+    mac.update(path.as_bytes());
+    mac.update(body.as_bytes());
+    mac.update(nonce.as_bytes());
+    STANDARD.encode(mac.finalize().into_bytes())
+}
@@ -0,0 +1,30 @@
+use serde::Deserialize;
+use std::fmt;
+
+/// LINE Pay 設定
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct LinePayConfig {
+    pub channel_id: String,
+    pub channel_secret: String,
+    /// 使用 sandbox 環境（`sandbox-api-pay.line.me`）
+    pub sandbox: bool,
+    /// 使用者在 LINE Pay 核准付款後導向的網址
+    pub confirm_url: String,
+    /// 使用者取消付款後導向的網址，未設定時與 `confirm_url` 相同
+    pub cancel_url: Option<String>,
+    /// ISO 4217 幣別，例如 `TWD`、`JPY`、`THB`
+    pub currency: String,
+}
+
+impl fmt::Debug for LinePayConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LinePayConfig")
+            .field("channel_id", &self.channel_id)
+            .field("channel_secret", &"***")
+            .field("sandbox", &self.sandbox)
+            .field("confirm_url", &self.confirm_url)
+            .field("cancel_url", &self.cancel_url)
+            .field("currency", &self.currency)
+            .finish()
+    }
+}
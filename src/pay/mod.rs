@@ -0,0 +1,17 @@
+//! LINE Pay 付款
+//!
+//! [`LinePayClient`] 實作 Request 與 Confirm API；[`LinePayCheckout`] 建立付款、送出付款 Flex 訊息，
+//! 並在使用者按下「我已完成付款」postback 時呼叫 Confirm API 完成扣款。
+//! [`LinePayConfig`] 一律可用以便從環境變數讀取設定，其餘需啟用 `pay` feature。
+
+#[cfg(feature = "pay")]
+pub mod checkout;
+#[cfg(feature = "pay")]
+pub mod client;
+pub mod config;
+
+#[cfg(feature = "pay")]
+pub use checkout::*;
+#[cfg(feature = "pay")]
+pub use client::*;
+pub use config::*;
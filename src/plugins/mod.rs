@@ -0,0 +1,11 @@
+//! 外掛系統
+//!
+//! 大型 Bot 可把功能拆成多個 [`Plugin`]，每個外掛提供自己的指令、事件中介層與排程工作，
+//! 並在啟動與關閉時收到 [`Plugin::init`]／[`Plugin::shutdown`]。外掛登記在 [`PluginRegistry`]，
+//! 再以 `create_app_with_plugins` 或 `start_server_with_plugins` 啟動。
+
+pub mod plugin;
+pub mod registry;
+
+pub use plugin::*;
+pub use registry::*;
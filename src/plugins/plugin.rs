@@ -0,0 +1,95 @@
+use async_trait::async_trait;
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::models::{Event, MessageEvent, OutgoingMessage};
+use crate::webhook::server::AppState;
+
+#[derive(Debug)]
+pub struct PluginError {
+    pub message: String,
+}
+
+impl PluginError {
+    pub fn new<T: Into<String>>(message: T) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for PluginError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Plugin Error: {}", self.message)
+    }
+}
+
+impl Error for PluginError {}
+
+/// 一組可組合的功能
+///
+/// 指令、中介層與排程工作在登記到 [`PluginRegistry`](crate::plugins::PluginRegistry) 時取得，
+/// 需要共用狀態時以 `Arc` 欄位分享給回傳的處理器。
+#[async_trait]
+pub trait Plugin: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// 指令名稱（不分大小寫）與處理器，比對文字訊息的第一個字
+    fn commands(&self) -> Vec<(String, Arc<dyn CommandHandler>)> {
+        Vec::new()
+    }
+
+    /// 在內建處理前依序看到每個事件
+    fn middlewares(&self) -> Vec<Arc<dyn EventMiddleware>> {
+        Vec::new()
+    }
+
+    fn jobs(&self) -> Vec<Arc<dyn ScheduledJob>> {
+        Vec::new()
+    }
+
+    /// 伺服器開始接受請求前呼叫，失敗時伺服器不會啟動
+    async fn init(&self, _state: Arc<AppState>) -> Result<(), PluginError> {
+        Ok(())
+    }
+
+    /// 伺服器關閉時呼叫，順序與登記相反
+    async fn shutdown(&self) {}
+}
+
+#[async_trait]
+pub trait CommandHandler: Send + Sync {
+    /// `args` 為指令後的文字（已去除前後空白）
+    async fn handle(
+        &self,
+        state: &AppState,
+        event: &MessageEvent,
+        args: &str,
+    ) -> Result<Vec<OutgoingMessage>, PluginError>;
+}
+
+/// 中介層處理後的動作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MiddlewareAction {
+    /// 交給下一個中介層與內建處理
+    Continue,
+    /// 事件已處理完畢（需要回覆時由中介層自行呼叫 LINE API）
+    Handled,
+}
+
+#[async_trait]
+pub trait EventMiddleware: Send + Sync {
+    async fn handle(&self, state: &AppState, event: &Event) -> MiddlewareAction;
+}
+
+#[async_trait]
+pub trait ScheduledJob: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// 執行間隔，第一次在啟動後經過一個間隔才執行
+    fn interval(&self) -> Duration;
+
+    async fn run(&self, state: &AppState) -> Result<(), PluginError>;
+}
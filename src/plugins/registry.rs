@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::models::{Event, MessageEvent, OutgoingMessage};
+use crate::plugins::{
+    CommandHandler, EventMiddleware, MiddlewareAction, Plugin, PluginError, ScheduledJob,
+};
+use crate::webhook::server::AppState;
+
+/// 已登記的外掛與其指令、中介層與排程工作，複本共用同一組排程
+#[derive(Clone, Default)]
+pub struct PluginRegistry {
+    plugins: Vec<Arc<dyn Plugin>>,
+    commands: HashMap<String, Arc<dyn CommandHandler>>,
+    middlewares: Vec<Arc<dyn EventMiddleware>>,
+    jobs: Vec<Arc<dyn ScheduledJob>>,
+    job_handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
+}
+
+impl PluginRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 登記外掛；指令名稱重複時保留先登記的
+    pub fn register(mut self, plugin: impl Plugin + 'static) -> Self {
+        for (name, handler) in plugin.commands() {
+            let name = name.to_lowercase();
+            if self.commands.contains_key(&name) {
+                warn!(
+                    "Plugin {} command {} is already registered, ignoring",
+                    plugin.name(),
+                    name
+                );
+                continue;
+            }
+            self.commands.insert(name, handler);
+        }
+        self.middlewares.extend(plugin.middlewares());
+        self.jobs.extend(plugin.jobs());
+        self.plugins.push(Arc::new(plugin));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.plugins.iter().map(|plugin| plugin.name()).collect()
+    }
+
+    pub fn has_command(&self, name: &str) -> bool {
+        self.commands.contains_key(&name.to_lowercase())
+    }
+
+    /// 第一個字符合外掛指令時執行，否則回傳 `None`
+    pub async fn handle_command(
+        &self,
+        state: &AppState,
+        event: &MessageEvent,
+        text: &str,
+    ) -> Option<Result<Vec<OutgoingMessage>, PluginError>> {
+        let text = text.trim();
+        let (name, args) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+        let handler = self.commands.get(&name.to_lowercase())?;
+        Some(handler.handle(state, event, args.trim()).await)
+    }
+
+    /// 依登記順序執行中介層，任一個回傳 [`MiddlewareAction::Handled`] 時停止
+    pub async fn run_middlewares(&self, state: &AppState, event: &Event) -> MiddlewareAction {
+        for middleware in &self.middlewares {
+            if middleware.handle(state, event).await == MiddlewareAction::Handled {
+                return MiddlewareAction::Handled;
+            }
+        }
+        MiddlewareAction::Continue
+    }
+
+    /// 依序初始化外掛，全部成功後啟動排程工作
+    pub async fn init(&self, state: Arc<AppState>) -> Result<(), PluginError> {
+        for plugin in &self.plugins {
+            plugin.init(state.clone()).await.map_err(|e| {
+                PluginError::new(format!("{} failed to initialize: {}", plugin.name(), e))
+            })?;
+            info!("Initialized plugin {}", plugin.name());
+        }
+
+        let mut handles = self.job_handles.lock().unwrap();
+        for job in &self.jobs {
+            let job = job.clone();
+            let state = state.clone();
+            handles.push(tokio::spawn(async move {
+                let interval = job.interval();
+                let mut ticker =
+                    tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+                loop {
+                    ticker.tick().await;
+                    if let Err(e) = job.run(&state).await {
+                        warn!("Scheduled job {} failed: {}", job.name(), e);
+                    }
+                }
+            }));
+        }
+        Ok(())
+    }
+
+    /// 停止排程工作，再以相反順序關閉外掛
+    pub async fn shutdown(&self) {
+        for handle in self.job_handles.lock().unwrap().drain(..) {
+            handle.abort();
+        }
+        for plugin in self.plugins.iter().rev() {
+            plugin.shutdown().await;
+            info!("Shut down plugin {}", plugin.name());
+        }
+    }
+}
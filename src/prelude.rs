@@ -0,0 +1,24 @@
+//! 常用型別
+//!
+//! ```ignore
+//! use linebot_rs::prelude::*;
+//! ```
+
+pub use crate::handlers::{DefaultMessageHandler, MessageHandler};
+pub use crate::line_api::{
+    LineApiClient, LineApiClientBuilder, LineApiError, MessageSend, SendOptions,
+};
+pub use crate::messages;
+pub use crate::models::stickers::{self, Sticker};
+pub use crate::models::{
+    Action, ButtonsTemplateBuilder, CarouselColumn, Event, MarkdownFormat, MessageEvent,
+    MessageType, OutgoingMessage, QuickReply, Source, TemplateType, WebhookRequest,
+};
+#[cfg(feature = "server")]
+pub use crate::plugins::{
+    CommandHandler, EventMiddleware, MiddlewareAction, Plugin, PluginError, PluginRegistry,
+    ScheduledJob,
+};
+pub use crate::utils::Config;
+#[cfg(feature = "server")]
+pub use crate::webhook::{AppState, LineSignatureLayer, VerifiedJson, create_app, start_server};
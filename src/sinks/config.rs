@@ -0,0 +1,43 @@
+use serde::Deserialize;
+use std::fmt;
+
+use crate::sinks::CsvRotation;
+use crate::storage::ConversationMasking;
+
+/// CSV 目的地設定
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct CsvSinkConfig {
+    pub dir: String,
+    pub rotation: CsvRotation,
+    /// 保留的檔案數，未設定時不刪除
+    pub max_files: Option<usize>,
+}
+
+/// Google Sheets 目的地設定
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct GoogleSheetsConfig {
+    pub spreadsheet_id: String,
+    /// A1 表示法的範圍，例如 `Events!A:G`
+    pub range: String,
+    /// 未設定時向 metadata server 取得服務帳戶的 token
+    pub access_token: Option<String>,
+}
+
+impl fmt::Debug for GoogleSheetsConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GoogleSheetsConfig")
+            .field("spreadsheet_id", &self.spreadsheet_id)
+            .field("range", &self.range)
+            .field("access_token", &self.access_token.as_ref().map(|_| "***"))
+            .finish()
+    }
+}
+
+/// 事件匯出設定，至少設定一個目的地
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct EventSinkConfig {
+    pub csv: Option<CsvSinkConfig>,
+    pub google_sheets: Option<GoogleSheetsConfig>,
+    pub masking: ConversationMasking,
+    pub flush_interval_secs: u64,
+}
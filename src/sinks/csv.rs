@@ -0,0 +1,196 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::Deserialize;
+use std::path::PathBuf;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+use crate::sinks::{EventRow, EventSink, SinkError};
+
+/// CSV 檔名前綴，刪除舊檔時只會處理此前綴的檔案
+const FILE_PREFIX: &str = "events-";
+
+/// CSV 檔的輪替週期
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CsvRotation {
+    /// `events-2024-05-01.csv`
+    #[default]
+    Daily,
+    /// `events-2024-05-01-13.csv`
+    Hourly,
+}
+
+impl CsvRotation {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "daily" => Some(CsvRotation::Daily),
+            "hourly" => Some(CsvRotation::Hourly),
+            _ => None,
+        }
+    }
+
+    fn file_name(&self) -> String {
+        let format = match self {
+            CsvRotation::Daily => "%Y-%m-%d",
+            CsvRotation::Hourly => "%Y-%m-%d-%H",
+        };
+        format!("{}{}.csv", FILE_PREFIX, Utc::now().format(format))
+    }
+}
+
+/// 依日期（UTC）輪替的 CSV 檔，新檔案第一列為欄位名稱
+///
+/// 以 `=`、`+`、`-`、`@` 開頭的內容會加上 `'`，避免試算表軟體當成公式執行。
+pub struct CsvFileSink {
+    dir: PathBuf,
+    rotation: CsvRotation,
+    max_files: Option<usize>,
+    lock: Mutex<()>,
+}
+
+impl CsvFileSink {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            rotation: CsvRotation::default(),
+            max_files: None,
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn rotation(mut self, rotation: CsvRotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// 只保留最新的幾個檔案，未設定時不刪除
+    pub fn max_files(mut self, max_files: usize) -> Self {
+        self.max_files = Some(max_files.max(1));
+        self
+    }
+
+    async fn remove_old_files(&self) -> std::io::Result<()> {
+        let Some(max_files) = self.max_files else {
+            return Ok(());
+        };
+        let mut files = Vec::new();
+        let mut entries = tokio::fs::read_dir(&self.dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with(FILE_PREFIX) && name.ends_with(".csv") {
+                files.push(name);
+            }
+        }
+        // 檔名依時間排序
+        files.sort();
+        let excess = files.len().saturating_sub(max_files);
+        for name in &files[..excess] {
+            tokio::fs::remove_file(self.dir.join(name)).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EventSink for CsvFileSink {
+    async fn write(&self, rows: &[EventRow]) -> Result<(), SinkError> {
+        let _guard = self.lock.lock().await;
+        let io_error = |e: std::io::Error| SinkError::new(format!("{}: {}", self.dir.display(), e));
+
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .map_err(io_error)?;
+        let path = self.dir.join(self.rotation.file_name());
+        let is_new = !path.exists();
+
+        let mut content = String::new();
+        if is_new {
+            content.push_str(&csv_line(EventRow::HEADERS.iter().copied()));
+        }
+        for row in rows {
+            content.push_str(&csv_line(row.cells().iter().map(String::as_str)));
+        }
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await
+            .map_err(io_error)?;
+        file.write_all(content.as_bytes()).await.map_err(io_error)?;
+        file.flush().await.map_err(io_error)?;
+
+        if is_new {
+            self.remove_old_files().await.map_err(io_error)?;
+        }
+        Ok(())
+    }
+}
+
+fn csv_line<'a>(cells: impl Iterator<Item = &'a str>) -> String {
+    let mut line = cells.map(csv_cell).collect::<Vec<_>>().join(",");
+    line.push_str("\r\n");
+    line
+}
+
+fn csv_cell(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Event;
+    use crate::storage::ConversationMasking;
+
+    #[tokio::test]
+    async fn test_csv_sink_writes_and_rotates() {
+        let dir = std::env::temp_dir().join(format!("linebot-csv-sink-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("events-2000-01-01.csv"), "old").unwrap();
+        std::fs::write(dir.join("notes.csv"), "keep").unwrap();
+
+        let event: Event = serde_json::from_value(serde_json::json!({
+            "type": "message",
+            "replyToken": "token",
+            "timestamp": 1714521600000u64,
+            "mode": "active",
+            "source": { "type": "user", "userId": "U123" },
+            "message": { "type": "text", "id": "1", "text": "=1+1, \"hi\"" }
+        }))
+        .unwrap();
+        let row = EventRow::from_event(&event, ConversationMasking::None);
+
+        let sink = CsvFileSink::new(&dir).max_files(1);
+        sink.write(std::slice::from_ref(&row)).await.unwrap();
+        sink.write(&[row]).await.unwrap();
+
+        let path = dir.join(CsvRotation::Daily.file_name());
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(
+            lines[0],
+            "timestamp,event_type,source_type,user_id,chat_id,message_type,content"
+        );
+        assert_eq!(
+            lines[1],
+            "2024-05-01T00:00:00+00:00,message,user,U123,,text,\"'=1+1, \"\"hi\"\"\""
+        );
+        assert_eq!(lines.len(), 3);
+        assert!(!dir.join("events-2000-01-01.csv").exists());
+        assert!(dir.join("notes.csv").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,16 @@
+//! 把收到的事件寫到試算表或 CSV 檔，不需存取資料庫就能查看 Bot 的活動
+//!
+//! [`EventSinkWriter`] 在背景批次寫入，寫入失敗只記錄錯誤，不影響 Webhook 處理。
+//! 其他目的地可實作 [`EventSink`]。
+
+pub mod config;
+pub mod csv;
+pub mod sheets;
+pub mod sink;
+pub mod writer;
+
+pub use config::*;
+pub use csv::*;
+pub use sheets::*;
+pub use sink::*;
+pub use writer::*;
@@ -0,0 +1,202 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use crate::sinks::{EventRow, EventSink, SinkError};
+
+/// 單次請求的逾時
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// GCE、Cloud Run 等環境提供服務帳戶 token 的位址
+const METADATA_TOKEN_URL: &str =
+    "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
+
+const DEFAULT_ENDPOINT: &str = "https://sheets.googleapis.com";
+
+#[derive(Deserialize)]
+struct MetadataToken {
+    access_token: String,
+    expires_in: u64,
+}
+
+/// 以 Sheets API `values.append` 把事件附加到試算表
+///
+/// 資料以 `RAW` 寫入，不會被當成公式；欄位名稱需自行填在第一列（見 [`EventRow::HEADERS`]）。
+/// 未設定 [`access_token`](Self::access_token) 時向 metadata server 取得服務帳戶的 token，
+/// 服務帳戶需有該試算表的編輯權限。
+pub struct GoogleSheetsSink {
+    client: reqwest::Client,
+    spreadsheet_id: String,
+    range: String,
+    endpoint: String,
+    access_token: Option<String>,
+    cached_token: Mutex<Option<(String, Instant)>>,
+}
+
+impl GoogleSheetsSink {
+    /// `range` 為 A1 表示法，例如 `Events!A:G`
+    pub fn new(spreadsheet_id: impl Into<String>, range: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            spreadsheet_id: spreadsheet_id.into(),
+            range: range.into(),
+            endpoint: DEFAULT_ENDPOINT.to_string(),
+            access_token: None,
+            cached_token: Mutex::new(None),
+        }
+    }
+
+    /// 使用共用的 HTTP 用戶端
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    pub fn access_token(mut self, token: impl Into<String>) -> Self {
+        self.access_token = Some(token.into());
+        self
+    }
+
+    /// 覆寫 API 端點，測試或經由代理時使用
+    pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    fn append_url(&self) -> Result<reqwest::Url, SinkError> {
+        let mut url = reqwest::Url::parse(self.endpoint.trim_end_matches('/'))
+            .map_err(|e| SinkError::new(format!("Invalid endpoint: {}", e)))?;
+        url.path_segments_mut()
+            .map_err(|_| SinkError::new("Invalid endpoint"))?
+            .pop_if_empty()
+            .extend([
+                "v4",
+                "spreadsheets",
+                &self.spreadsheet_id,
+                "values",
+                &format!("{}:append", self.range),
+            ]);
+        url.query_pairs_mut()
+            .append_pair("valueInputOption", "RAW")
+            .append_pair("insertDataOption", "INSERT_ROWS");
+        Ok(url)
+    }
+
+    async fn token(&self) -> Result<String, SinkError> {
+        if let Some(token) = &self.access_token {
+            return Ok(token.clone());
+        }
+
+        let mut cached = self.cached_token.lock().await;
+        if let Some((token, expires_at)) = cached.as_ref()
+            && Instant::now() < *expires_at
+        {
+            return Ok(token.clone());
+        }
+        let response = self
+            .client
+            .get(METADATA_TOKEN_URL)
+            .timeout(REQUEST_TIMEOUT)
+            .header("Metadata-Flavor", "Google")
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| SinkError::new(format!("Failed to fetch access token: {}", e)))?;
+        let token: MetadataToken = response
+            .json()
+            .await
+            .map_err(|e| SinkError::new(format!("Invalid access token response: {}", e)))?;
+        let expires_at = Instant::now() + Duration::from_secs(token.expires_in.saturating_sub(60));
+        *cached = Some((token.access_token.clone(), expires_at));
+        Ok(token.access_token)
+    }
+}
+
+#[async_trait]
+impl EventSink for GoogleSheetsSink {
+    async fn write(&self, rows: &[EventRow]) -> Result<(), SinkError> {
+        let values: Vec<[String; 7]> = rows.iter().map(EventRow::cells).collect();
+        let response = self
+            .client
+            .post(self.append_url()?)
+            .timeout(REQUEST_TIMEOUT)
+            .bearer_auth(self.token().await?)
+            .json(&json!({ "values": values }))
+            .send()
+            .await
+            .map_err(|e| SinkError::new(format!("Failed to send request: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(SinkError::new(format!(
+                "Append failed with status {}: {}",
+                status, body
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        Json, Router,
+        extract::{Path, RawQuery},
+        http::HeaderMap,
+        routing::post,
+    };
+    use chrono::Utc;
+    use serde_json::Value;
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test]
+    async fn test_append_rows() {
+        let received = Arc::new(Mutex::new(None));
+        let store = received.clone();
+        let app = Router::new().route(
+            "/v4/spreadsheets/:id/values/:range",
+            post(
+                move |Path((id, range)): Path<(String, String)>,
+                      RawQuery(query): RawQuery,
+                      headers: HeaderMap,
+                      Json(body): Json<Value>| {
+                    let authorization = headers["authorization"].to_str().unwrap().to_string();
+                    *store.lock().unwrap() = Some((id, range, query, authorization, body));
+                    async { Json(json!({})) }
+                },
+            ),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let sink = GoogleSheetsSink::new("sheet-1", "Bot Events!A:G")
+            .access_token("ya29.test")
+            .endpoint(format!("http://{}", addr));
+        let row = EventRow {
+            timestamp: Utc::now(),
+            event_type: "follow".to_string(),
+            source_type: "user".to_string(),
+            user_id: "U1".to_string(),
+            chat_id: String::new(),
+            message_type: String::new(),
+            content: "=SUM(A1)".to_string(),
+        };
+        sink.write(&[row]).await.unwrap();
+
+        let (id, range, query, authorization, body) = received.lock().unwrap().take().unwrap();
+        assert_eq!(id, "sheet-1");
+        assert_eq!(range, "Bot Events!A:G:append");
+        assert_eq!(
+            query.as_deref(),
+            Some("valueInputOption=RAW&insertDataOption=INSERT_ROWS")
+        );
+        assert_eq!(authorization, "Bearer ya29.test");
+        assert_eq!(body["values"][0][1], "follow");
+        assert_eq!(body["values"][0][6], "=SUM(A1)");
+    }
+}
@@ -0,0 +1,121 @@
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+use serde::Serialize;
+use std::{error::Error, fmt};
+
+use crate::models::{Event, MessageType, Source};
+use crate::storage::ConversationMasking;
+use crate::utils::SensitiveDataMasker;
+
+#[derive(Debug)]
+pub struct SinkError {
+    pub message: String,
+}
+
+impl SinkError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for SinkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Event sink error: {}", self.message)
+    }
+}
+
+impl Error for SinkError {}
+
+/// 事件的寫入目的地
+#[async_trait]
+pub trait EventSink: Send + Sync {
+    /// 依序附加多筆資料列
+    async fn write(&self, rows: &[EventRow]) -> Result<(), SinkError>;
+}
+
+/// 一個事件對應的一列資料
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EventRow {
+    pub timestamp: DateTime<Utc>,
+    pub event_type: String,
+    /// `user`、`group` 或 `room`
+    pub source_type: String,
+    pub user_id: String,
+    /// 群組或聊天室 ID
+    pub chat_id: String,
+    pub message_type: String,
+    /// 文字訊息內容、postback 資料或貼圖 ID
+    pub content: String,
+}
+
+impl EventRow {
+    /// 欄位名稱，順序與 [`cells`](Self::cells) 相同
+    pub const HEADERS: [&'static str; 7] = [
+        "timestamp",
+        "event_type",
+        "source_type",
+        "user_id",
+        "chat_id",
+        "message_type",
+        "content",
+    ];
+
+    /// 依遮罩規則轉換事件；`full` 時連使用者 ID 也會遮罩
+    pub fn from_event(event: &Event, masking: ConversationMasking) -> Self {
+        let source = event.source();
+        let (source_type, chat_id) = match source {
+            Source::User { .. } => ("user", None),
+            Source::Group { group_id, .. } => ("group", Some(group_id)),
+            Source::Room { room_id, .. } => ("room", Some(room_id)),
+        };
+        let user_id = source.user_id().unwrap_or_default();
+        let user_id = if masking == ConversationMasking::Full {
+            SensitiveDataMasker::mask_user_id(user_id)
+        } else {
+            user_id.to_string()
+        };
+
+        let (message_type, content) = match event {
+            Event::Message(e) => {
+                let content = match &e.message {
+                    MessageType::Text { text } => masking.mask_text(text),
+                    MessageType::Sticker {
+                        package_id,
+                        sticker_id,
+                    } => format!("{}/{}", package_id, sticker_id),
+                    _ => String::new(),
+                };
+                (e.message.message_type(), content)
+            }
+            Event::Postback(e) => ("", masking.mask_text(&e.postback.data)),
+            _ => ("", String::new()),
+        };
+
+        Self {
+            timestamp: Utc
+                .timestamp_millis_opt(event.timestamp() as i64)
+                .single()
+                .unwrap_or_else(Utc::now),
+            event_type: event.event_type().to_string(),
+            source_type: source_type.to_string(),
+            user_id,
+            chat_id: chat_id.cloned().unwrap_or_default(),
+            message_type: message_type.to_string(),
+            content,
+        }
+    }
+
+    pub fn cells(&self) -> [String; 7] {
+        [
+            self.timestamp.to_rfc3339(),
+            self.event_type.clone(),
+            self.source_type.clone(),
+            self.user_id.clone(),
+            self.chat_id.clone(),
+            self.message_type.clone(),
+            self.content.clone(),
+        ]
+    }
+}
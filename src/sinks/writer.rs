@@ -0,0 +1,100 @@
+use std::{sync::Arc, time::Duration};
+use tokio::sync::mpsc;
+use tracing::{error, warn};
+
+use crate::models::Event;
+use crate::sinks::{EventRow, EventSink};
+use crate::storage::ConversationMasking;
+
+/// 待寫入資料列的上限，超過時丟棄新事件
+const QUEUE_CAPACITY: usize = 10_000;
+
+/// 累積到此筆數時不等計時直接寫入
+const MAX_BATCH_SIZE: usize = 500;
+
+/// 把事件轉成資料列，在背景批次寫到所有目的地
+///
+/// 各目的地各自寫入，其中一個失敗不影響其他目的地；失敗的批次只記錄錯誤，不會重試。
+#[derive(Clone)]
+pub struct EventSinkWriter {
+    sender: mpsc::Sender<EventRow>,
+    masking: ConversationMasking,
+    sinks: Vec<Arc<dyn EventSink>>,
+    flush_interval: Duration,
+    /// 呼叫 [`start`](Self::start) 前保留接收端，之前加入的事件會等到啟動後寫入
+    receiver: Arc<std::sync::Mutex<Option<mpsc::Receiver<EventRow>>>>,
+}
+
+impl EventSinkWriter {
+    /// 建立寫入器，呼叫 [`start`](Self::start) 後每隔 `flush_interval` 寫入一次
+    pub fn new(
+        sinks: Vec<Arc<dyn EventSink>>,
+        masking: ConversationMasking,
+        flush_interval: Duration,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel(QUEUE_CAPACITY);
+        Self {
+            sender,
+            masking,
+            sinks,
+            flush_interval,
+            receiver: Arc::new(std::sync::Mutex::new(Some(receiver))),
+        }
+    }
+
+    /// 啟動背景寫入工作，重複呼叫時不做任何事
+    pub fn start(&self) {
+        let receiver = self
+            .receiver
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        if let Some(receiver) = receiver {
+            tokio::spawn(run(self.sinks.clone(), receiver, self.flush_interval));
+        }
+    }
+
+    /// 加入一個事件，不等待寫入完成
+    pub fn record(&self, event: &Event) {
+        let row = EventRow::from_event(event, self.masking);
+        if let Err(e) = self.sender.try_send(row) {
+            warn!("Dropping event for sinks: {}", e);
+        }
+    }
+}
+
+async fn run(
+    sinks: Vec<Arc<dyn EventSink>>,
+    mut receiver: mpsc::Receiver<EventRow>,
+    flush_interval: Duration,
+) {
+    let mut ticker = tokio::time::interval(flush_interval);
+    let mut batch = Vec::new();
+    loop {
+        let closed = tokio::select! {
+            row = receiver.recv() => match row {
+                Some(row) => {
+                    batch.push(row);
+                    if batch.len() < MAX_BATCH_SIZE {
+                        continue;
+                    }
+                    false
+                }
+                None => true,
+            },
+            _ = ticker.tick() => false,
+        };
+
+        if !batch.is_empty() {
+            for sink in &sinks {
+                if let Err(e) = sink.write(&batch).await {
+                    error!("Failed to write {} events: {}", batch.len(), e);
+                }
+            }
+            batch.clear();
+        }
+        if closed {
+            break;
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use chrono::Utc;
+use std::fmt::Display;
+use std::future::Future;
+use std::path::Path;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::storage::{AuditEntry, Storage};
+
+/// 未在 [`with_audit_actor`] 範圍內執行的動作（例如排程與 Webhook 觸發）記錄的操作者
+pub const SYSTEM_ACTOR: &str = "system";
+
+/// 管理 API 標示操作者的 HTTP 標頭與 gRPC metadata；管理 API 共用同一組 token，由呼叫端自行帶入
+pub const ADMIN_ACTOR_HEADER: &str = "x-admin-actor";
+
+/// 未帶 [`ADMIN_ACTOR_HEADER`] 時記錄的操作者
+pub const DEFAULT_ADMIN_ACTOR: &str = "admin";
+
+/// 操作者名稱的長度上限
+const MAX_ACTOR_LEN: usize = 64;
+
+tokio::task_local! {
+    static ACTOR: String;
+}
+
+/// 在此範圍內執行的動作以 `actor` 記錄，例如管理 API 呼叫觸發的推播
+pub async fn with_audit_actor<F: Future>(actor: String, future: F) -> F::Output {
+    ACTOR.scope(actor, future).await
+}
+
+/// 目前的操作者，不在 [`with_audit_actor`] 範圍內時為 [`SYSTEM_ACTOR`]
+pub fn current_audit_actor() -> String {
+    ACTOR
+        .try_with(Clone::clone)
+        .unwrap_or_else(|_| SYSTEM_ACTOR.to_string())
+}
+
+/// 由 [`ADMIN_ACTOR_HEADER`] 的值取得操作者
+pub fn admin_actor(value: Option<&str>) -> String {
+    value
+        .map(str::trim)
+        .filter(|actor| !actor.is_empty())
+        .map(|actor| actor.chars().take(MAX_ACTOR_LEN).collect())
+        .unwrap_or_else(|| DEFAULT_ADMIN_ACTOR.to_string())
+}
+
+impl AuditEntry {
+    /// 以目前的操作者建立一筆成功的紀錄
+    pub fn new<T: Into<String>>(action: T) -> Self {
+        Self {
+            actor: current_audit_actor(),
+            action: action.into(),
+            target: None,
+            success: true,
+            detail: None,
+            recorded_at: Utc::now(),
+        }
+    }
+
+    pub fn actor<T: Into<String>>(mut self, actor: T) -> Self {
+        self.actor = actor.into();
+        self
+    }
+
+    pub fn target<T: Into<String>>(mut self, target: T) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    pub fn detail<T: Into<String>>(mut self, detail: T) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// 設定檔重新載入的紀錄，成功時以載入筆數作為說明
+    pub fn reload<E: Display>(kind: &str, path: &Path, result: Result<usize, E>) -> Self {
+        let entry = Self::new(format!("reload.{}", kind)).target(path.display().to_string());
+        match result {
+            Ok(count) => entry.detail(format!("{} loaded", count)),
+            Err(e) => entry.success(false).detail(e.to_string()),
+        }
+    }
+
+    pub fn success(mut self, success: bool) -> Self {
+        self.success = success;
+        self
+    }
+
+    /// 依結果設定是否成功，失敗時以錯誤訊息作為說明
+    pub fn result<T, E: Display>(mut self, result: &Result<T, E>) -> Self {
+        self.success = result.is_ok();
+        if let Err(e) = result {
+            self.detail = Some(e.to_string());
+        }
+        self
+    }
+}
+
+/// 稽核紀錄器
+///
+/// 記錄管理 API 呼叫、群發、Rich Menu 異動與設定重新載入；寫入失敗只記錄警告，不影響原本的動作。
+#[derive(Clone)]
+pub struct AuditLog {
+    storage: Arc<dyn Storage>,
+}
+
+impl AuditLog {
+    pub fn new(storage: Arc<dyn Storage>) -> Self {
+        Self { storage }
+    }
+
+    pub async fn record(&self, entry: AuditEntry) {
+        if let Err(e) = self.storage.append_audit(&entry).await {
+            warn!("Failed to record audit entry {}: {}", entry.action, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{AuditQuery, MemoryStorage, StorageError};
+
+    #[tokio::test]
+    async fn test_audit_log_uses_scoped_actor() {
+        let storage = Arc::new(MemoryStorage::new());
+        let audit_log = AuditLog::new(storage.clone());
+
+        audit_log
+            .record(AuditEntry::new("reload.templates").target("templates.yaml"))
+            .await;
+        with_audit_actor("alice".to_string(), async {
+            let result: Result<(), StorageError> = Err(StorageError::new("boom"));
+            audit_log
+                .record(AuditEntry::new("broadcast").result(&result))
+                .await;
+        })
+        .await;
+
+        let entries = storage.audit_log(&AuditQuery::default()).await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].actor, "alice");
+        assert!(!entries[0].success);
+        assert_eq!(entries[0].detail.as_deref(), Some("Storage Error: boom"));
+        assert_eq!(entries[1].actor, SYSTEM_ACTOR);
+        assert_eq!(entries[1].target.as_deref(), Some("templates.yaml"));
+    }
+}
@@ -0,0 +1,45 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::storage::StorageError;
+
+/// 刪除使用者資料時執行的清理工作
+///
+/// 在儲存後端的 `users`、session 與對話紀錄以外保存使用者資料的元件實作此 trait，
+/// 並在建立應用程式時註冊到 [`UserDataCleanups`]。
+#[async_trait]
+pub trait UserDataCleanup: Send + Sync {
+    async fn purge_user(&self, user_id: &str) -> Result<(), StorageError>;
+}
+
+/// 已註冊的清理工作，依註冊順序執行
+#[derive(Clone, Default)]
+pub struct UserDataCleanups {
+    cleanups: Vec<Arc<dyn UserDataCleanup>>,
+}
+
+impl UserDataCleanups {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register<T: UserDataCleanup + 'static>(&mut self, cleanup: T) {
+        self.cleanups.push(Arc::new(cleanup));
+    }
+
+    pub fn len(&self) -> usize {
+        self.cleanups.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cleanups.is_empty()
+    }
+
+    /// 依序執行所有清理工作，任一項失敗時回傳錯誤
+    pub async fn purge_user(&self, user_id: &str) -> Result<(), StorageError> {
+        for cleanup in &self.cleanups {
+            cleanup.purge_user(user_id).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,208 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::models::{Event, MessageType, OutgoingMessage};
+use crate::storage::{ConversationDirection, ConversationEntry, Storage};
+use crate::utils::SensitiveDataMasker;
+
+/// 會包含使用者輸入文字的欄位
+const TEXT_FIELDS: &[&str] = &["text", "altText", "title", "label", "data"];
+
+/// 對話紀錄的遮罩規則
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConversationMasking {
+    /// 原文保存
+    None,
+    /// 遮罩文字中的電子郵件與電話號碼
+    #[default]
+    Pii,
+    /// 不保存任何文字內容，只保留訊息類型等結構
+    Full,
+}
+
+impl ConversationMasking {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "none" => Some(ConversationMasking::None),
+            "pii" => Some(ConversationMasking::Pii),
+            "full" => Some(ConversationMasking::Full),
+            _ => None,
+        }
+    }
+
+    /// 依規則遮罩訊息 JSON 中的文字欄位
+    pub fn apply(&self, value: &mut Value) {
+        if *self == ConversationMasking::None {
+            return;
+        }
+
+        match value {
+            Value::Object(map) => {
+                for (key, field) in map.iter_mut() {
+                    match field {
+                        Value::String(text) if TEXT_FIELDS.contains(&key.as_str()) => {
+                            *text = self.mask_text(text);
+                        }
+                        _ => self.apply(field),
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| self.apply(item)),
+            _ => {}
+        }
+    }
+
+    /// 依規則遮罩單一文字
+    pub fn mask_text(&self, text: &str) -> String {
+        match self {
+            ConversationMasking::None => text.to_string(),
+            ConversationMasking::Full => "***".to_string(),
+            ConversationMasking::Pii => text
+                .split(' ')
+                .map(|word| {
+                    let digits = word.chars().filter(char::is_ascii_digit).count();
+                    if word.contains('@') {
+                        SensitiveDataMasker::mask_email(word)
+                    } else if digits >= 8 {
+                        SensitiveDataMasker::mask_phone(word)
+                    } else {
+                        word.to_string()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+}
+
+/// 對話紀錄器
+///
+/// 將收到與送出的訊息及其他 Webhook 事件遮罩後寫入儲存後端；寫入失敗只記錄警告，不影響訊息處理。
+#[derive(Clone)]
+pub struct ConversationLogger {
+    storage: Arc<dyn Storage>,
+    masking: ConversationMasking,
+}
+
+impl ConversationLogger {
+    pub fn new(storage: Arc<dyn Storage>, masking: ConversationMasking) -> Self {
+        Self { storage, masking }
+    }
+
+    pub async fn record_incoming(&self, user_id: &str, message: &MessageType) {
+        self.record(user_id, ConversationDirection::Incoming, message)
+            .await;
+    }
+
+    pub async fn record_outgoing(&self, user_id: &str, messages: &[OutgoingMessage]) {
+        for message in messages {
+            self.record(user_id, ConversationDirection::Outgoing, message)
+                .await;
+        }
+    }
+
+    /// 記錄訊息以外的事件；不保存只能使用一次的 `replyToken`
+    pub async fn record_event(&self, user_id: &str, event: &Event) {
+        let Some(mut value) = Self::to_value(event) else {
+            return;
+        };
+        if let Some(map) = value.as_object_mut() {
+            map.remove("replyToken");
+        }
+        self.append(user_id, ConversationDirection::Event, value)
+            .await;
+    }
+
+    async fn record<T: Serialize>(
+        &self,
+        user_id: &str,
+        direction: ConversationDirection,
+        message: &T,
+    ) {
+        if let Some(value) = Self::to_value(message) {
+            self.append(user_id, direction, value).await;
+        }
+    }
+
+    fn to_value<T: Serialize>(message: &T) -> Option<Value> {
+        serde_json::to_value(message)
+            .map_err(|e| warn!("Failed to serialize message for conversation log: {}", e))
+            .ok()
+    }
+
+    async fn append(&self, user_id: &str, direction: ConversationDirection, mut value: Value) {
+        self.masking.apply(&mut value);
+
+        let entry = ConversationEntry {
+            user_id: user_id.to_string(),
+            direction,
+            message: value,
+            recorded_at: Utc::now(),
+        };
+        if let Err(e) = self.storage.append_conversation(&entry).await {
+            warn!("Failed to record conversation: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{HistoryQuery, MemoryStorage};
+
+    #[test]
+    fn test_pii_masking() {
+        let mut value = serde_json::json!({
+            "type": "text",
+            "text": "mail me at alice@example.com or 0912345678"
+        });
+        ConversationMasking::Pii.apply(&mut value);
+        assert_eq!(value["text"], "mail me at a***@example.com or 09***78");
+        assert_eq!(value["type"], "text");
+    }
+
+    #[tokio::test]
+    async fn test_logger_records_messages_and_events() {
+        let storage = Arc::new(MemoryStorage::new());
+        let logger = ConversationLogger::new(storage.clone(), ConversationMasking::Full);
+
+        logger
+            .record_incoming(
+                "U1",
+                &MessageType::Text {
+                    text: "secret".to_string(),
+                },
+            )
+            .await;
+        logger
+            .record_outgoing("U1", &[OutgoingMessage::text("reply")])
+            .await;
+        let event: Event = serde_json::from_value(serde_json::json!({
+            "type": "postback",
+            "replyToken": "token",
+            "timestamp": 0,
+            "source": {"type": "user", "userId": "U1"},
+            "mode": "active",
+            "postback": {"data": "action=buy"}
+        }))
+        .unwrap();
+        logger.record_event("U1", &event).await;
+
+        let history = storage
+            .conversation_history(&HistoryQuery::new("U1"))
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].direction, ConversationDirection::Incoming);
+        assert_eq!(history[0].message["text"], "***");
+        assert_eq!(history[1].direction, ConversationDirection::Outgoing);
+        assert_eq!(history[2].direction, ConversationDirection::Event);
+        assert_eq!(history[2].message["type"], "postback");
+        assert_eq!(history[2].message["postback"]["data"], "***");
+        assert!(history[2].message.get("replyToken").is_none());
+    }
+}
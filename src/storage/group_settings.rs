@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use crate::storage::{KvNamespace, Storage, StorageError};
+
+/// 可在群組中個別停用的功能
+pub const GROUP_FEATURES: [&str; 4] = ["welcome", "replies", "scripts", "llm"];
+
+/// Bot 加入群組時的預設歡迎訊息
+pub const DEFAULT_JOIN_WELCOME: &str = "大家好！我是你們的 LINE Bot 助手！";
+
+/// 群組或聊天室的設定，未設定的項目使用預設行為
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupSettings {
+    /// 回覆語言，啟用翻譯時把回覆翻成這個語言
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Bot 加入時的歡迎訊息
+    #[serde(default)]
+    pub welcome_template: Option<String>,
+    /// 新成員加入時的歡迎訊息，未設定時不發送
+    #[serde(default)]
+    pub member_welcome_template: Option<String>,
+    /// 停用的功能，見 [`GROUP_FEATURES`]
+    #[serde(default)]
+    pub disabled_features: BTreeSet<String>,
+}
+
+impl GroupSettings {
+    pub fn is_enabled(&self, feature: &str) -> bool {
+        !self.disabled_features.contains(feature)
+    }
+
+    /// Bot 加入時的歡迎訊息，`{group}` 代入群組名稱
+    pub fn join_welcome(&self, group_name: &str) -> String {
+        let template = self
+            .welcome_template
+            .as_deref()
+            .unwrap_or(DEFAULT_JOIN_WELCOME);
+        render(template, group_name, 0)
+    }
+
+    /// 新成員的歡迎訊息，`{group}` 代入群組名稱、`{count}` 代入加入人數
+    pub fn member_welcome(&self, group_name: &str, count: usize) -> Option<String> {
+        self.member_welcome_template
+            .as_deref()
+            .map(|template| render(template, group_name, count))
+    }
+}
+
+fn render(template: &str, group_name: &str, count: usize) -> String {
+    template
+        .replace("{group}", group_name)
+        .replace("{count}", &count.to_string())
+}
+
+/// 以群組或聊天室 ID 保存 [`GroupSettings`]
+#[derive(Clone)]
+pub struct GroupSettingsStore {
+    kv: KvNamespace,
+}
+
+impl GroupSettingsStore {
+    pub fn new(storage: Arc<dyn Storage>) -> Self {
+        Self {
+            kv: KvNamespace::new(storage, "group_settings"),
+        }
+    }
+
+    /// 沒有保存過設定時回傳預設值
+    pub async fn get(&self, chat_id: &str) -> Result<GroupSettings, StorageError> {
+        Ok(self.kv.get(chat_id).await?.unwrap_or_default())
+    }
+
+    pub async fn save(&self, chat_id: &str, settings: &GroupSettings) -> Result<(), StorageError> {
+        self.kv.set(chat_id, settings).await
+    }
+
+    pub async fn delete(&self, chat_id: &str) -> Result<(), StorageError> {
+        self.kv.delete(chat_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_group_settings_store() {
+        let store = GroupSettingsStore::new(Arc::new(MemoryStorage::new()));
+        let settings = store.get("C1").await.unwrap();
+        assert_eq!(settings, GroupSettings::default());
+        assert_eq!(settings.join_welcome("讀書會"), DEFAULT_JOIN_WELCOME);
+        assert_eq!(settings.member_welcome("讀書會", 2), None);
+
+        let mut settings = GroupSettings {
+            welcome_template: Some("大家好，我是{group}的小幫手".to_string()),
+            member_welcome_template: Some("歡迎 {count} 位新朋友加入{group}！".to_string()),
+            ..Default::default()
+        };
+        settings.disabled_features.insert("llm".to_string());
+        store.save("C1", &settings).await.unwrap();
+
+        let saved = store.get("C1").await.unwrap();
+        assert!(!saved.is_enabled("llm"));
+        assert!(saved.is_enabled("welcome"));
+        assert_eq!(saved.join_welcome("讀書會"), "大家好，我是讀書會的小幫手");
+        assert_eq!(
+            saved.member_welcome("讀書會", 2).as_deref(),
+            Some("歡迎 2 位新朋友加入讀書會！")
+        );
+
+        store.delete("C1").await.unwrap();
+        assert_eq!(store.get("C1").await.unwrap(), GroupSettings::default());
+    }
+}
@@ -0,0 +1,113 @@
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+
+use crate::storage::{Storage, StorageError};
+
+/// 以命名空間區隔的 key-value 存取
+///
+/// 讓處理器不必定義資料表就能保存少量狀態，例如 `state.kv("game").set("score", &10)`。
+/// 實際鍵值為 `kv:{namespace}:{key}`，不同命名空間互不影響。
+#[derive(Clone)]
+pub struct KvNamespace {
+    storage: Arc<dyn Storage>,
+    namespace: String,
+}
+
+impl KvNamespace {
+    pub fn new<T: Into<String>>(storage: Arc<dyn Storage>, namespace: T) -> Self {
+        Self {
+            storage,
+            namespace: namespace.into(),
+        }
+    }
+
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    pub async fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, StorageError> {
+        match self.storage.kv_get(&self.full_key(key)).await? {
+            Some(value) => Ok(Some(serde_json::from_value(value)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn set<T: Serialize + ?Sized>(
+        &self,
+        key: &str,
+        value: &T,
+    ) -> Result<(), StorageError> {
+        let value = serde_json::to_value(value)?;
+        self.storage.kv_set(&self.full_key(key), &value).await
+    }
+
+    pub async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        self.storage.kv_delete(&self.full_key(key)).await
+    }
+
+    /// 把整數值原子地加上 `delta` 並回傳新值，多個實例同時累加也不會遺漏
+    pub async fn increment(&self, key: &str, delta: i64) -> Result<i64, StorageError> {
+        self.storage.kv_increment(&self.full_key(key), delta).await
+    }
+
+    /// 命名空間內以 `prefix` 開頭的鍵，不含命名空間前綴
+    pub async fn keys(&self, prefix: &str) -> Result<Vec<String>, StorageError> {
+        let full_prefix = self.full_key(prefix);
+        let namespace_len = full_prefix.len() - prefix.len();
+        Ok(self
+            .storage
+            .kv_keys(&full_prefix)
+            .await?
+            .into_iter()
+            .map(|key| key[namespace_len..].to_string())
+            .collect())
+    }
+
+    /// 刪除 `expired` 判定為過期的值，無法解析的值也一併刪除，避免永遠留在 storage；回傳刪除的筆數
+    pub async fn remove_where<T: DeserializeOwned>(
+        &self,
+        expired: impl Fn(&T) -> bool,
+    ) -> Result<usize, StorageError> {
+        let mut removed = 0;
+        for key in self.keys("").await? {
+            let remove = match self.get::<T>(&key).await {
+                Ok(Some(value)) => expired(&value),
+                Ok(None) => false,
+                Err(_) => true,
+            };
+            if remove {
+                self.delete(&key).await?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    fn full_key(&self, key: &str) -> String {
+        format!("kv:{}:{}", self.namespace, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_namespaces_are_isolated() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let game = KvNamespace::new(storage.clone(), "game");
+        let quiz = KvNamespace::new(storage, "quiz");
+
+        game.set("score", &10u32).await.unwrap();
+        quiz.set("score", &3u32).await.unwrap();
+
+        assert_eq!(game.get::<u32>("score").await.unwrap(), Some(10));
+        assert_eq!(quiz.get::<u32>("score").await.unwrap(), Some(3));
+
+        game.delete("score").await.unwrap();
+        assert_eq!(game.get::<u32>("score").await.unwrap(), None);
+        assert_eq!(quiz.get::<u32>("score").await.unwrap(), Some(3));
+    }
+}
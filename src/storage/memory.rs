@@ -0,0 +1,233 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::ops::Bound;
+use std::sync::atomic::{AtomicI64, Ordering};
+
+use crate::storage::{
+    AuditEntry, AuditQuery, ConversationCursor, ConversationEntry, ConversationPage, HistoryQuery,
+    NewReminder, Reminder, Session, Storage, StorageError, UserRecord,
+};
+use std::sync::RwLock;
+
+/// 記憶體儲存，重新啟動後資料即消失；適合開發與測試
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    users: DashMap<String, UserRecord>,
+    sessions: DashMap<String, Session>,
+    kv: DashMap<String, Value>,
+    reminders: DashMap<i64, Reminder>,
+    next_reminder_id: AtomicI64,
+    /// 依 `(recorded_at, 序號)` 排序，匯出時不需每頁重新排序
+    conversations: RwLock<BTreeMap<(DateTime<Utc>, i64), ConversationEntry>>,
+    next_conversation_id: AtomicI64,
+    audit: RwLock<Vec<AuditEntry>>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl Storage for MemoryStorage {
+    async fn get_user(&self, user_id: &str) -> Result<Option<UserRecord>, StorageError> {
+        Ok(self.users.get(user_id).map(|user| user.clone()))
+    }
+
+    async fn upsert_user(&self, user: &UserRecord) -> Result<(), StorageError> {
+        self.users.insert(user.user_id.clone(), user.clone());
+        Ok(())
+    }
+
+    async fn get_session(&self, user_id: &str) -> Result<Option<Session>, StorageError> {
+        Ok(self
+            .sessions
+            .get(user_id)
+            .map(|session| session.clone())
+            .filter(|session| !session.is_expired(Utc::now())))
+    }
+
+    async fn save_session(&self, session: &Session) -> Result<(), StorageError> {
+        self.sessions
+            .insert(session.user_id.clone(), session.clone());
+        Ok(())
+    }
+
+    async fn delete_session(&self, user_id: &str) -> Result<(), StorageError> {
+        self.sessions.remove(user_id);
+        Ok(())
+    }
+
+    async fn kv_get(&self, key: &str) -> Result<Option<Value>, StorageError> {
+        Ok(self.kv.get(key).map(|value| value.clone()))
+    }
+
+    async fn kv_set(&self, key: &str, value: &Value) -> Result<(), StorageError> {
+        self.kv.insert(key.to_string(), value.clone());
+        Ok(())
+    }
+
+    async fn kv_delete(&self, key: &str) -> Result<(), StorageError> {
+        self.kv.remove(key);
+        Ok(())
+    }
+
+    async fn kv_keys(&self, prefix: &str) -> Result<Vec<String>, StorageError> {
+        let mut keys: Vec<String> = self
+            .kv
+            .iter()
+            .filter(|entry| entry.key().starts_with(prefix))
+            .map(|entry| entry.key().clone())
+            .collect();
+        keys.sort();
+        Ok(keys)
+    }
+
+    async fn kv_increment(&self, key: &str, delta: i64) -> Result<i64, StorageError> {
+        let mut entry = self.kv.entry(key.to_string()).or_insert(Value::from(0));
+        let value = entry
+            .as_i64()
+            .ok_or_else(|| StorageError::new(format!("Value of {} is not an integer", key)))?
+            + delta;
+        *entry = Value::from(value);
+        Ok(value)
+    }
+
+    async fn add_reminder(&self, reminder: NewReminder) -> Result<Reminder, StorageError> {
+        let id = self.next_reminder_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let reminder = Reminder {
+            id,
+            user_id: reminder.user_id,
+            message: reminder.message,
+            due_at: reminder.due_at,
+        };
+        self.reminders.insert(id, reminder.clone());
+        Ok(reminder)
+    }
+
+    async fn due_reminders(&self, now: DateTime<Utc>) -> Result<Vec<Reminder>, StorageError> {
+        let mut due: Vec<Reminder> = self
+            .reminders
+            .iter()
+            .filter(|reminder| reminder.due_at <= now)
+            .map(|reminder| reminder.clone())
+            .collect();
+        due.sort_by_key(|reminder| (reminder.due_at, reminder.id));
+        Ok(due)
+    }
+
+    async fn delete_reminder(&self, id: i64) -> Result<(), StorageError> {
+        self.reminders.remove(&id);
+        Ok(())
+    }
+
+    async fn append_conversation(&self, entry: &ConversationEntry) -> Result<(), StorageError> {
+        let id = self.next_conversation_id.fetch_add(1, Ordering::SeqCst) + 1;
+        self.conversations
+            .write()
+            .map_err(|_| StorageError::new("Conversation log lock poisoned"))?
+            .insert((entry.recorded_at, id), entry.clone());
+        Ok(())
+    }
+
+    async fn conversation_history(
+        &self,
+        query: &HistoryQuery,
+    ) -> Result<Vec<ConversationEntry>, StorageError> {
+        let conversations = self
+            .conversations
+            .read()
+            .map_err(|_| StorageError::new("Conversation log lock poisoned"))?;
+        Ok(conversations
+            .values()
+            .filter(|entry| query.matches(entry))
+            .take(query.limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn conversation_page(
+        &self,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+        after: Option<ConversationCursor>,
+        limit: u32,
+    ) -> Result<ConversationPage, StorageError> {
+        let conversations = self
+            .conversations
+            .read()
+            .map_err(|_| StorageError::new("Conversation log lock poisoned"))?;
+        let start = match (after, since) {
+            (Some(after), _) => Bound::Excluded((after.recorded_at, after.id)),
+            (None, Some(since)) => Bound::Included((since, i64::MIN)),
+            (None, None) => Bound::Unbounded,
+        };
+        let rows: Vec<_> = conversations
+            .range((start, Bound::Unbounded))
+            .filter(|((recorded_at, _), _)| since.is_none_or(|since| *recorded_at >= since))
+            .take_while(|((recorded_at, _), _)| until.is_none_or(|until| *recorded_at < until))
+            .take(limit as usize)
+            .collect();
+        let next = match rows.last() {
+            Some(((recorded_at, id), _)) if rows.len() == limit as usize => {
+                Some(ConversationCursor {
+                    recorded_at: *recorded_at,
+                    id: *id,
+                })
+            }
+            _ => None,
+        };
+        Ok(ConversationPage {
+            entries: rows.into_iter().map(|(_, entry)| entry.clone()).collect(),
+            next,
+        })
+    }
+
+    async fn append_audit(&self, entry: &AuditEntry) -> Result<(), StorageError> {
+        self.audit
+            .write()
+            .map_err(|_| StorageError::new("Audit log lock poisoned"))?
+            .push(entry.clone());
+        Ok(())
+    }
+
+    async fn audit_log(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>, StorageError> {
+        let audit = self
+            .audit
+            .read()
+            .map_err(|_| StorageError::new("Audit log lock poisoned"))?;
+        Ok(audit
+            .iter()
+            .rev()
+            .filter(|entry| query.matches(entry))
+            .take(query.limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn purge_user(&self, user_id: &str) -> Result<(), StorageError> {
+        self.users.remove(user_id);
+        self.sessions.remove(user_id);
+        self.reminders
+            .retain(|_, reminder| reminder.user_id != user_id);
+        self.conversations
+            .write()
+            .map_err(|_| StorageError::new("Conversation log lock poisoned"))?
+            .retain(|_, entry| entry.user_id != user_id);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_memory_storage() {
+        crate::storage::tests::exercise_storage(&MemoryStorage::new()).await;
+    }
+}
@@ -0,0 +1,590 @@
+//! 狀態儲存：使用者、對話 session、key-value 與提醒
+//!
+//! 預設使用記憶體實作；啟用 `sqlite` 或 `postgres` feature 後可透過
+//! `STORAGE_URL` 改用 sqlx 後端。
+
+pub mod audit;
+pub mod cleanup;
+pub mod conversation;
+pub mod export;
+pub mod group_settings;
+pub mod kv;
+pub mod memory;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+pub use audit::*;
+pub use cleanup::*;
+pub use conversation::*;
+pub use export::*;
+pub use group_settings::*;
+pub use kv::*;
+pub use memory::*;
+#[cfg(feature = "postgres")]
+pub use postgres::*;
+#[cfg(feature = "sqlite")]
+pub use sqlite::*;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub struct StorageError {
+    pub message: String,
+}
+
+impl StorageError {
+    pub fn new<T: Into<String>>(message: T) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Storage Error: {}", self.message)
+    }
+}
+
+impl Error for StorageError {}
+
+impl From<serde_json::Error> for StorageError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::new(format!("Invalid JSON value: {}", e))
+    }
+}
+
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+impl From<sqlx::Error> for StorageError {
+    fn from(e: sqlx::Error) -> Self {
+        Self::new(e.to_string())
+    }
+}
+
+/// 使用者資料
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserRecord {
+    pub user_id: String,
+    pub display_name: Option<String>,
+    pub language: Option<String>,
+    /// 是否仍為好友（收到 unfollow 後為 false）
+    pub followed: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 對話 session，用於多步驟流程
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub user_id: String,
+    pub data: Value,
+    /// 到期後視為不存在
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl Session {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+/// 尚未儲存的提醒
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewReminder {
+    pub user_id: String,
+    pub message: String,
+    pub due_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reminder {
+    pub id: i64,
+    pub user_id: String,
+    pub message: String,
+    pub due_at: DateTime<Utc>,
+}
+
+/// 對話紀錄的方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConversationDirection {
+    Incoming,
+    Outgoing,
+    /// 訊息以外的 Webhook 事件，例如加入好友與 postback
+    Event,
+}
+
+impl ConversationDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConversationDirection::Incoming => "incoming",
+            ConversationDirection::Outgoing => "outgoing",
+            ConversationDirection::Event => "event",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, StorageError> {
+        match value {
+            "incoming" => Ok(ConversationDirection::Incoming),
+            "outgoing" => Ok(ConversationDirection::Outgoing),
+            "event" => Ok(ConversationDirection::Event),
+            other => Err(StorageError::new(format!(
+                "Unknown conversation direction: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// 一則收到或送出的訊息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConversationEntry {
+    pub user_id: String,
+    pub direction: ConversationDirection,
+    /// 訊息內容（已依設定遮罩）
+    pub message: Value,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// 匯出分頁的位置，即上一頁最後一筆的時間與序號
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ConversationCursor {
+    pub recorded_at: DateTime<Utc>,
+    pub id: i64,
+}
+
+/// 一頁對話紀錄；`next` 為 `None` 時表示已無下一頁
+#[derive(Debug, Clone, Default)]
+pub struct ConversationPage {
+    pub entries: Vec<ConversationEntry>,
+    pub next: Option<ConversationCursor>,
+}
+
+/// 對話紀錄查詢條件，時間區間為 `[since, until)`
+#[derive(Debug, Clone, Deserialize)]
+pub struct HistoryQuery {
+    pub user_id: String,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    #[serde(default = "default_history_limit")]
+    pub limit: u32,
+}
+
+fn default_history_limit() -> u32 {
+    100
+}
+
+impl HistoryQuery {
+    pub fn new<T: Into<String>>(user_id: T) -> Self {
+        Self {
+            user_id: user_id.into(),
+            since: None,
+            until: None,
+            limit: default_history_limit(),
+        }
+    }
+
+    pub fn matches(&self, entry: &ConversationEntry) -> bool {
+        entry.user_id == self.user_id
+            && self.since.is_none_or(|since| entry.recorded_at >= since)
+            && self.until.is_none_or(|until| entry.recorded_at < until)
+    }
+}
+
+/// 一筆稽核紀錄：誰在何時做了什麼、結果如何
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub actor: String,
+    /// 動作名稱，例如 `admin.request`、`broadcast`、`rich_menu.create`、`reload.templates`
+    pub action: String,
+    /// 動作對象，例如 API 路徑、Rich Menu ID 或設定檔路徑
+    pub target: Option<String>,
+    pub success: bool,
+    pub detail: Option<String>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// 稽核紀錄查詢條件，時間區間為 `[since, until)`
+#[derive(Debug, Clone, Deserialize)]
+pub struct AuditQuery {
+    pub actor: Option<String>,
+    /// 以前綴比對，例如 `rich_menu.` 會符合所有 Rich Menu 相關動作
+    pub action: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    #[serde(default = "default_history_limit")]
+    pub limit: u32,
+}
+
+impl Default for AuditQuery {
+    fn default() -> Self {
+        Self {
+            actor: None,
+            action: None,
+            since: None,
+            until: None,
+            limit: default_history_limit(),
+        }
+    }
+}
+
+impl AuditQuery {
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        self.actor
+            .as_ref()
+            .is_none_or(|actor| entry.actor == *actor)
+            && self
+                .action
+                .as_ref()
+                .is_none_or(|action| entry.action.starts_with(action.as_str()))
+            && self.since.is_none_or(|since| entry.recorded_at >= since)
+            && self.until.is_none_or(|until| entry.recorded_at < until)
+    }
+}
+
+#[async_trait]
+pub trait Storage: Send + Sync {
+    async fn get_user(&self, user_id: &str) -> Result<Option<UserRecord>, StorageError>;
+
+    async fn upsert_user(&self, user: &UserRecord) -> Result<(), StorageError>;
+
+    /// 取得未過期的 session
+    async fn get_session(&self, user_id: &str) -> Result<Option<Session>, StorageError>;
+
+    async fn save_session(&self, session: &Session) -> Result<(), StorageError>;
+
+    async fn delete_session(&self, user_id: &str) -> Result<(), StorageError>;
+
+    async fn kv_get(&self, key: &str) -> Result<Option<Value>, StorageError>;
+
+    async fn kv_set(&self, key: &str, value: &Value) -> Result<(), StorageError>;
+
+    async fn kv_delete(&self, key: &str) -> Result<(), StorageError>;
+
+    /// 以 `prefix` 開頭的鍵，依字典順序排列
+    async fn kv_keys(&self, prefix: &str) -> Result<Vec<String>, StorageError>;
+
+    /// 把整數值原子地加上 `delta` 並回傳新值，鍵不存在時從 0 開始
+    async fn kv_increment(&self, key: &str, delta: i64) -> Result<i64, StorageError>;
+
+    async fn add_reminder(&self, reminder: NewReminder) -> Result<Reminder, StorageError>;
+
+    /// 取得 `due_at` 不晚於 `now` 的提醒，依時間排序
+    async fn due_reminders(&self, now: DateTime<Utc>) -> Result<Vec<Reminder>, StorageError>;
+
+    async fn delete_reminder(&self, id: i64) -> Result<(), StorageError>;
+
+    async fn append_conversation(&self, entry: &ConversationEntry) -> Result<(), StorageError>;
+
+    /// 依時間先後回傳符合條件的紀錄，最多 `limit` 筆
+    async fn conversation_history(
+        &self,
+        query: &HistoryQuery,
+    ) -> Result<Vec<ConversationEntry>, StorageError>;
+
+    /// 依 `(recorded_at, id)` 順序讀取 `after` 之後的所有使用者對話紀錄，用於匯出
+    async fn conversation_page(
+        &self,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+        after: Option<ConversationCursor>,
+        limit: u32,
+    ) -> Result<ConversationPage, StorageError>;
+
+    /// 新增稽核紀錄；稽核紀錄只能新增，不提供修改或刪除
+    async fn append_audit(&self, entry: &AuditEntry) -> Result<(), StorageError>;
+
+    /// 由新到舊回傳符合條件的稽核紀錄，最多 `limit` 筆
+    async fn audit_log(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>, StorageError>;
+
+    /// 刪除與使用者相關的所有資料（資料、session、對話紀錄與提醒）
+    async fn purge_user(&self, user_id: &str) -> Result<(), StorageError>;
+}
+
+/// 依 URL 建立儲存後端
+///
+/// 未設定時使用記憶體；`sqlite:` 與 `postgres://`（或 `postgresql://`）需啟用對應 feature。
+pub async fn connect_storage(url: Option<&str>) -> Result<Arc<dyn Storage>, StorageError> {
+    let Some(url) = url else {
+        return Ok(Arc::new(MemoryStorage::new()));
+    };
+
+    if url.starts_with("sqlite:") {
+        #[cfg(feature = "sqlite")]
+        return Ok(Arc::new(SqliteStorage::connect(url).await?));
+        #[cfg(not(feature = "sqlite"))]
+        return Err(StorageError::new(
+            "STORAGE_URL uses sqlite but the `sqlite` feature is disabled",
+        ));
+    }
+
+    if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+        #[cfg(feature = "postgres")]
+        return Ok(Arc::new(PostgresStorage::connect(url).await?));
+        #[cfg(not(feature = "postgres"))]
+        return Err(StorageError::new(
+            "STORAGE_URL uses postgres but the `postgres` feature is disabled",
+        ));
+    }
+
+    Err(StorageError::new(format!(
+        "Unsupported STORAGE_URL scheme: {}",
+        url.split(':').next().unwrap_or_default()
+    )))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use chrono::Duration;
+    use serde_json::json;
+
+    /// 各後端共用的行為測試
+    pub(crate) async fn exercise_storage(storage: &dyn Storage) {
+        let now = Utc::now();
+
+        assert!(storage.get_user("U1").await.unwrap().is_none());
+        let mut user = UserRecord {
+            user_id: "U1".to_string(),
+            display_name: Some("Alice".to_string()),
+            language: Some("ja".to_string()),
+            followed: true,
+            updated_at: now,
+        };
+        storage.upsert_user(&user).await.unwrap();
+        user.followed = false;
+        storage.upsert_user(&user).await.unwrap();
+        let stored = storage.get_user("U1").await.unwrap().unwrap();
+        assert!(!stored.followed);
+        assert_eq!(stored.display_name.as_deref(), Some("Alice"));
+
+        let session = Session {
+            user_id: "U1".to_string(),
+            data: json!({"step": 2}),
+            expires_at: Some(now + Duration::minutes(5)),
+        };
+        storage.save_session(&session).await.unwrap();
+        assert_eq!(
+            storage.get_session("U1").await.unwrap().unwrap().data,
+            json!({"step": 2})
+        );
+        storage
+            .save_session(&Session {
+                expires_at: Some(now - Duration::minutes(1)),
+                ..session
+            })
+            .await
+            .unwrap();
+        assert!(storage.get_session("U1").await.unwrap().is_none());
+        storage.delete_session("U1").await.unwrap();
+
+        storage.kv_set("counter", &json!(1)).await.unwrap();
+        storage.kv_set("counter", &json!(2)).await.unwrap();
+        assert_eq!(storage.kv_get("counter").await.unwrap(), Some(json!(2)));
+        storage.kv_delete("counter").await.unwrap();
+        assert!(storage.kv_get("counter").await.unwrap().is_none());
+        for key in ["feed:b", "feed:a", "feed_x", "other"] {
+            storage.kv_set(key, &json!(true)).await.unwrap();
+        }
+        assert_eq!(
+            storage.kv_keys("feed:").await.unwrap(),
+            vec!["feed:a".to_string(), "feed:b".to_string()]
+        );
+        assert!(storage.kv_keys("%").await.unwrap().is_empty());
+        assert_eq!(storage.kv_increment("counter", 5).await.unwrap(), 5);
+        assert_eq!(storage.kv_increment("counter", 3).await.unwrap(), 8);
+        assert_eq!(storage.kv_get("counter").await.unwrap(), Some(json!(8)));
+        storage.kv_delete("counter").await.unwrap();
+        for key in ["feed:b", "feed:a", "feed_x", "other"] {
+            storage.kv_delete(key).await.unwrap();
+        }
+
+        let later = storage
+            .add_reminder(NewReminder {
+                user_id: "U1".to_string(),
+                message: "later".to_string(),
+                due_at: now + Duration::hours(1),
+            })
+            .await
+            .unwrap();
+        let due = storage
+            .add_reminder(NewReminder {
+                user_id: "U1".to_string(),
+                message: "now".to_string(),
+                due_at: now - Duration::seconds(1),
+            })
+            .await
+            .unwrap();
+        assert_ne!(later.id, due.id);
+
+        let reminders = storage.due_reminders(now).await.unwrap();
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].message, "now");
+        storage.delete_reminder(due.id).await.unwrap();
+        assert!(storage.due_reminders(now).await.unwrap().is_empty());
+
+        for (offset, direction) in [
+            (3, ConversationDirection::Incoming),
+            (2, ConversationDirection::Outgoing),
+            (1, ConversationDirection::Incoming),
+        ] {
+            storage
+                .append_conversation(&ConversationEntry {
+                    user_id: "U1".to_string(),
+                    direction,
+                    message: json!({"type": "text", "text": offset.to_string()}),
+                    recorded_at: now - Duration::minutes(offset),
+                })
+                .await
+                .unwrap();
+        }
+        let history = storage
+            .conversation_history(&HistoryQuery {
+                since: Some(now - Duration::minutes(2)),
+                ..HistoryQuery::new("U1")
+            })
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].direction, ConversationDirection::Outgoing);
+        assert_eq!(history[1].message["text"], "1");
+        assert!(
+            storage
+                .conversation_history(&HistoryQuery::new("U2"))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // 同一時間的紀錄也依寫入順序分頁，不重複也不遺漏
+        for text in ["a", "b", "c"] {
+            storage
+                .append_conversation(&ConversationEntry {
+                    user_id: "U3".to_string(),
+                    direction: ConversationDirection::Event,
+                    message: json!({"type": "follow", "text": text}),
+                    recorded_at: now,
+                })
+                .await
+                .unwrap();
+        }
+        let mut exported = Vec::new();
+        let mut after = None;
+        loop {
+            let page = storage
+                .conversation_page(None, Some(now + Duration::seconds(1)), after, 2)
+                .await
+                .unwrap();
+            exported.extend(
+                page.entries
+                    .iter()
+                    .map(|entry| entry.message["text"].clone()),
+            );
+            match page.next {
+                Some(next) => after = Some(next),
+                None => break,
+            }
+        }
+        assert_eq!(exported, ["3", "2", "1", "a", "b", "c"]);
+        let page = storage
+            .conversation_page(Some(now), None, None, 10)
+            .await
+            .unwrap();
+        assert_eq!(page.entries.len(), 3);
+        assert_eq!(page.entries[0].direction, ConversationDirection::Event);
+        assert!(page.next.is_none());
+
+        storage
+            .save_session(&Session {
+                user_id: "U1".to_string(),
+                data: json!({}),
+                expires_at: None,
+            })
+            .await
+            .unwrap();
+        storage
+            .add_reminder(NewReminder {
+                user_id: "U1".to_string(),
+                message: "purged".to_string(),
+                due_at: now - Duration::seconds(1),
+            })
+            .await
+            .unwrap();
+        for (offset, actor, action) in [
+            (3, "alice", "admin.request"),
+            (2, "bob", "rich_menu.create"),
+            (1, "alice", "rich_menu.delete"),
+        ] {
+            storage
+                .append_audit(&AuditEntry {
+                    actor: actor.to_string(),
+                    action: action.to_string(),
+                    target: Some("richmenu-1".to_string()),
+                    success: true,
+                    detail: None,
+                    recorded_at: now - Duration::minutes(offset),
+                })
+                .await
+                .unwrap();
+        }
+        let audit = storage
+            .audit_log(&AuditQuery {
+                action: Some("rich_menu.".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(audit.len(), 2);
+        assert_eq!(audit[0].action, "rich_menu.delete");
+        assert_eq!(audit[1].actor, "bob");
+        let audit = storage
+            .audit_log(&AuditQuery {
+                actor: Some("alice".to_string()),
+                until: Some(now - Duration::minutes(2)),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].action, "admin.request");
+
+        storage.purge_user("U1").await.unwrap();
+        assert!(storage.get_user("U1").await.unwrap().is_none());
+        assert!(storage.get_session("U1").await.unwrap().is_none());
+        assert!(storage.due_reminders(now).await.unwrap().is_empty());
+        assert!(
+            storage
+                .conversation_history(&HistoryQuery::new("U1"))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        // 稽核紀錄不隨使用者刪除
+        assert_eq!(
+            storage
+                .audit_log(&AuditQuery::default())
+                .await
+                .unwrap()
+                .len(),
+            3
+        );
+    }
+
+    #[tokio::test]
+    async fn test_connect_storage_defaults_to_memory() {
+        let storage = connect_storage(None).await.unwrap();
+        exercise_storage(storage.as_ref()).await;
+    }
+
+    #[tokio::test]
+    async fn test_connect_storage_rejects_unknown_scheme() {
+        assert!(connect_storage(Some("redis://localhost")).await.is_err());
+    }
+}
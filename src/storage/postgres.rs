@@ -0,0 +1,421 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::Row;
+use sqlx::postgres::{PgPool, PgPoolOptions, PgRow};
+
+use crate::storage::{
+    AuditEntry, AuditQuery, ConversationCursor, ConversationDirection, ConversationEntry,
+    ConversationPage, HistoryQuery, NewReminder, Reminder, Session, Storage, StorageError,
+    UserRecord,
+};
+
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE IF NOT EXISTS users (
+        user_id TEXT PRIMARY KEY,
+        display_name TEXT,
+        language TEXT,
+        followed BOOLEAN NOT NULL,
+        updated_at TIMESTAMPTZ NOT NULL
+    )",
+    "CREATE TABLE IF NOT EXISTS sessions (
+        user_id TEXT PRIMARY KEY,
+        data TEXT NOT NULL,
+        expires_at TIMESTAMPTZ
+    )",
+    "CREATE TABLE IF NOT EXISTS kv (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    )",
+    "CREATE TABLE IF NOT EXISTS reminders (
+        id BIGSERIAL PRIMARY KEY,
+        user_id TEXT NOT NULL,
+        message TEXT NOT NULL,
+        due_at TIMESTAMPTZ NOT NULL
+    )",
+    "CREATE INDEX IF NOT EXISTS reminders_due_at ON reminders (due_at)",
+    "CREATE TABLE IF NOT EXISTS conversations (
+        id BIGSERIAL PRIMARY KEY,
+        user_id TEXT NOT NULL,
+        direction TEXT NOT NULL,
+        message TEXT NOT NULL,
+        recorded_at TIMESTAMPTZ NOT NULL
+    )",
+    "CREATE INDEX IF NOT EXISTS conversations_user_time ON conversations (user_id, recorded_at)",
+    "CREATE INDEX IF NOT EXISTS conversations_time ON conversations (recorded_at, id)",
+    "CREATE TABLE IF NOT EXISTS audit_log (
+        id BIGSERIAL PRIMARY KEY,
+        actor TEXT NOT NULL,
+        action TEXT NOT NULL,
+        target TEXT,
+        success BOOLEAN NOT NULL,
+        detail TEXT,
+        recorded_at TIMESTAMPTZ NOT NULL
+    )",
+    "CREATE INDEX IF NOT EXISTS audit_log_recorded_at ON audit_log (recorded_at)",
+];
+
+/// PostgreSQL 儲存（`postgres://` URL）
+#[derive(Debug, Clone)]
+pub struct PostgresStorage {
+    pool: PgPool,
+}
+
+impl PostgresStorage {
+    /// 連線並建立資料表
+    pub async fn connect(url: &str) -> Result<Self, StorageError> {
+        let pool = PgPoolOptions::new().connect(url).await?;
+        let storage = Self { pool };
+        storage.migrate().await?;
+        Ok(storage)
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    async fn migrate(&self) -> Result<(), StorageError> {
+        for statement in MIGRATIONS {
+            sqlx::query(statement).execute(&self.pool).await?;
+        }
+        Ok(())
+    }
+}
+
+fn reminder_from_row(row: &PgRow) -> Result<Reminder, StorageError> {
+    Ok(Reminder {
+        id: row.try_get("id")?,
+        user_id: row.try_get("user_id")?,
+        message: row.try_get("message")?,
+        due_at: row.try_get("due_at")?,
+    })
+}
+
+fn conversation_from_row(row: &PgRow) -> Result<ConversationEntry, StorageError> {
+    let direction: String = row.try_get("direction")?;
+    let message: String = row.try_get("message")?;
+    Ok(ConversationEntry {
+        user_id: row.try_get("user_id")?,
+        direction: ConversationDirection::parse(&direction)?,
+        message: serde_json::from_str(&message)?,
+        recorded_at: row.try_get("recorded_at")?,
+    })
+}
+
+fn audit_from_row(row: &PgRow) -> Result<AuditEntry, StorageError> {
+    Ok(AuditEntry {
+        actor: row.try_get("actor")?,
+        action: row.try_get("action")?,
+        target: row.try_get("target")?,
+        success: row.try_get("success")?,
+        detail: row.try_get("detail")?,
+        recorded_at: row.try_get("recorded_at")?,
+    })
+}
+
+#[async_trait]
+impl Storage for PostgresStorage {
+    async fn get_user(&self, user_id: &str) -> Result<Option<UserRecord>, StorageError> {
+        let row = sqlx::query(
+            "SELECT user_id, display_name, language, followed, updated_at FROM users WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(|row| {
+            Ok(UserRecord {
+                user_id: row.try_get("user_id")?,
+                display_name: row.try_get("display_name")?,
+                language: row.try_get("language")?,
+                followed: row.try_get("followed")?,
+                updated_at: row.try_get("updated_at")?,
+            })
+        })
+        .transpose()
+    }
+
+    async fn upsert_user(&self, user: &UserRecord) -> Result<(), StorageError> {
+        sqlx::query(
+            "INSERT INTO users (user_id, display_name, language, followed, updated_at)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (user_id) DO UPDATE SET
+                display_name = excluded.display_name,
+                language = excluded.language,
+                followed = excluded.followed,
+                updated_at = excluded.updated_at",
+        )
+        .bind(&user.user_id)
+        .bind(&user.display_name)
+        .bind(&user.language)
+        .bind(user.followed)
+        .bind(user.updated_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_session(&self, user_id: &str) -> Result<Option<Session>, StorageError> {
+        let row = sqlx::query("SELECT data, expires_at FROM sessions WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let data: String = row.try_get("data")?;
+        let session = Session {
+            user_id: user_id.to_string(),
+            data: serde_json::from_str(&data)?,
+            expires_at: row.try_get("expires_at")?,
+        };
+        Ok(Some(session).filter(|session| !session.is_expired(Utc::now())))
+    }
+
+    async fn save_session(&self, session: &Session) -> Result<(), StorageError> {
+        sqlx::query(
+            "INSERT INTO sessions (user_id, data, expires_at) VALUES ($1, $2, $3)
+             ON CONFLICT (user_id) DO UPDATE SET
+                data = excluded.data,
+                expires_at = excluded.expires_at",
+        )
+        .bind(&session.user_id)
+        .bind(serde_json::to_string(&session.data)?)
+        .bind(session.expires_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_session(&self, user_id: &str) -> Result<(), StorageError> {
+        sqlx::query("DELETE FROM sessions WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn kv_get(&self, key: &str) -> Result<Option<Value>, StorageError> {
+        let value: Option<String> = sqlx::query_scalar("SELECT value FROM kv WHERE key = $1")
+            .bind(key)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(value
+            .map(|value| serde_json::from_str(&value))
+            .transpose()?)
+    }
+
+    async fn kv_set(&self, key: &str, value: &Value) -> Result<(), StorageError> {
+        sqlx::query(
+            "INSERT INTO kv (key, value) VALUES ($1, $2)
+             ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+        )
+        .bind(key)
+        .bind(serde_json::to_string(value)?)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn kv_delete(&self, key: &str) -> Result<(), StorageError> {
+        sqlx::query("DELETE FROM kv WHERE key = $1")
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn kv_keys(&self, prefix: &str) -> Result<Vec<String>, StorageError> {
+        // 不用 LIKE，避免前綴中的 `%`、`_` 被當成萬用字元
+        let keys = sqlx::query_scalar(
+            "SELECT key FROM kv WHERE substr(key, 1, length($1)) = $1 ORDER BY key",
+        )
+        .bind(prefix)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(keys)
+    }
+
+    async fn kv_increment(&self, key: &str, delta: i64) -> Result<i64, StorageError> {
+        let value: String = sqlx::query_scalar(
+            "INSERT INTO kv (key, value) VALUES ($1, $2::TEXT)
+             ON CONFLICT (key) DO UPDATE SET value = (kv.value::BIGINT + $2)::TEXT
+             RETURNING value",
+        )
+        .bind(key)
+        .bind(delta)
+        .fetch_one(&self.pool)
+        .await?;
+        value
+            .parse()
+            .map_err(|_| StorageError::new(format!("Value of {} is not an integer", key)))
+    }
+
+    async fn add_reminder(&self, reminder: NewReminder) -> Result<Reminder, StorageError> {
+        let row = sqlx::query(
+            "INSERT INTO reminders (user_id, message, due_at) VALUES ($1, $2, $3)
+             RETURNING id, user_id, message, due_at",
+        )
+        .bind(&reminder.user_id)
+        .bind(&reminder.message)
+        .bind(reminder.due_at)
+        .fetch_one(&self.pool)
+        .await?;
+        reminder_from_row(&row)
+    }
+
+    async fn due_reminders(&self, now: DateTime<Utc>) -> Result<Vec<Reminder>, StorageError> {
+        let rows = sqlx::query(
+            "SELECT id, user_id, message, due_at FROM reminders
+             WHERE due_at <= $1 ORDER BY due_at, id",
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter().map(reminder_from_row).collect()
+    }
+
+    async fn delete_reminder(&self, id: i64) -> Result<(), StorageError> {
+        sqlx::query("DELETE FROM reminders WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn append_conversation(&self, entry: &ConversationEntry) -> Result<(), StorageError> {
+        sqlx::query(
+            "INSERT INTO conversations (user_id, direction, message, recorded_at)
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(&entry.user_id)
+        .bind(entry.direction.as_str())
+        .bind(serde_json::to_string(&entry.message)?)
+        .bind(entry.recorded_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn conversation_history(
+        &self,
+        query: &HistoryQuery,
+    ) -> Result<Vec<ConversationEntry>, StorageError> {
+        let rows = sqlx::query(
+            "SELECT user_id, direction, message, recorded_at FROM conversations
+             WHERE user_id = $1
+               AND ($2::TIMESTAMPTZ IS NULL OR recorded_at >= $3)
+               AND ($4::TIMESTAMPTZ IS NULL OR recorded_at < $5)
+             ORDER BY recorded_at, id
+             LIMIT $6",
+        )
+        .bind(&query.user_id)
+        .bind(query.since)
+        .bind(query.since)
+        .bind(query.until)
+        .bind(query.until)
+        .bind(i64::from(query.limit))
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(conversation_from_row).collect()
+    }
+
+    async fn conversation_page(
+        &self,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+        after: Option<ConversationCursor>,
+        limit: u32,
+    ) -> Result<ConversationPage, StorageError> {
+        let after_at = after.map(|after| after.recorded_at);
+        let rows = sqlx::query(
+            "SELECT id, user_id, direction, message, recorded_at FROM conversations
+             WHERE ($1::TIMESTAMPTZ IS NULL OR recorded_at >= $2)
+               AND ($3::TIMESTAMPTZ IS NULL OR recorded_at < $4)
+               AND ($5::TIMESTAMPTZ IS NULL OR (recorded_at, id) > ($6, $7))
+             ORDER BY recorded_at, id
+             LIMIT $8",
+        )
+        .bind(since)
+        .bind(since)
+        .bind(until)
+        .bind(until)
+        .bind(after_at)
+        .bind(after_at)
+        .bind(after.map(|after| after.id))
+        .bind(i64::from(limit))
+        .fetch_all(&self.pool)
+        .await?;
+
+        let next = match rows.last() {
+            Some(row) if rows.len() == limit as usize => Some(ConversationCursor {
+                recorded_at: row.try_get("recorded_at")?,
+                id: row.try_get("id")?,
+            }),
+            _ => None,
+        };
+        Ok(ConversationPage {
+            entries: rows
+                .iter()
+                .map(conversation_from_row)
+                .collect::<Result<_, _>>()?,
+            next,
+        })
+    }
+
+    async fn append_audit(&self, entry: &AuditEntry) -> Result<(), StorageError> {
+        sqlx::query(
+            "INSERT INTO audit_log (actor, action, target, success, detail, recorded_at)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(&entry.actor)
+        .bind(&entry.action)
+        .bind(&entry.target)
+        .bind(entry.success)
+        .bind(&entry.detail)
+        .bind(entry.recorded_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn audit_log(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>, StorageError> {
+        let rows = sqlx::query(
+            "SELECT actor, action, target, success, detail, recorded_at FROM audit_log
+             WHERE ($1::TEXT IS NULL OR actor = $2)
+               AND ($3::TEXT IS NULL OR substr(action, 1, length($4)) = $5)
+               AND ($6::TIMESTAMPTZ IS NULL OR recorded_at >= $7)
+               AND ($8::TIMESTAMPTZ IS NULL OR recorded_at < $9)
+             ORDER BY recorded_at DESC, id DESC
+             LIMIT $10",
+        )
+        .bind(&query.actor)
+        .bind(&query.actor)
+        .bind(&query.action)
+        .bind(&query.action)
+        .bind(&query.action)
+        .bind(query.since)
+        .bind(query.since)
+        .bind(query.until)
+        .bind(query.until)
+        .bind(i64::from(query.limit))
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(audit_from_row).collect()
+    }
+
+    async fn purge_user(&self, user_id: &str) -> Result<(), StorageError> {
+        let mut tx = self.pool.begin().await?;
+        for table in ["users", "sessions", "reminders", "conversations"] {
+            sqlx::query(&format!("DELETE FROM {} WHERE user_id = $1", table))
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,439 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::Row;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions, SqliteRow};
+use std::str::FromStr;
+
+use crate::storage::{
+    AuditEntry, AuditQuery, ConversationCursor, ConversationDirection, ConversationEntry,
+    ConversationPage, HistoryQuery, NewReminder, Reminder, Session, Storage, StorageError,
+    UserRecord,
+};
+
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE IF NOT EXISTS users (
+        user_id TEXT PRIMARY KEY,
+        display_name TEXT,
+        language TEXT,
+        followed BOOLEAN NOT NULL,
+        updated_at TEXT NOT NULL
+    )",
+    "CREATE TABLE IF NOT EXISTS sessions (
+        user_id TEXT PRIMARY KEY,
+        data TEXT NOT NULL,
+        expires_at TEXT
+    )",
+    "CREATE TABLE IF NOT EXISTS kv (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    )",
+    "CREATE TABLE IF NOT EXISTS reminders (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        user_id TEXT NOT NULL,
+        message TEXT NOT NULL,
+        due_at TEXT NOT NULL
+    )",
+    "CREATE INDEX IF NOT EXISTS reminders_due_at ON reminders (due_at)",
+    "CREATE TABLE IF NOT EXISTS conversations (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        user_id TEXT NOT NULL,
+        direction TEXT NOT NULL,
+        message TEXT NOT NULL,
+        recorded_at TEXT NOT NULL
+    )",
+    "CREATE INDEX IF NOT EXISTS conversations_user_time ON conversations (user_id, recorded_at)",
+    "CREATE INDEX IF NOT EXISTS conversations_time ON conversations (recorded_at, id)",
+    "CREATE TABLE IF NOT EXISTS audit_log (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        actor TEXT NOT NULL,
+        action TEXT NOT NULL,
+        target TEXT,
+        success BOOLEAN NOT NULL,
+        detail TEXT,
+        recorded_at TEXT NOT NULL
+    )",
+    "CREATE INDEX IF NOT EXISTS audit_log_recorded_at ON audit_log (recorded_at)",
+];
+
+/// SQLite 儲存（`sqlite:` URL，例如 `sqlite://bot.db`）
+#[derive(Debug, Clone)]
+pub struct SqliteStorage {
+    pool: SqlitePool,
+}
+
+impl SqliteStorage {
+    /// 連線並建立資料表，檔案不存在時自動建立
+    pub async fn connect(url: &str) -> Result<Self, StorageError> {
+        let options = SqliteConnectOptions::from_str(url)?.create_if_missing(true);
+        let mut pool_options = SqlitePoolOptions::new();
+        if url.contains(":memory:") {
+            // 每個記憶體連線都是獨立的資料庫，只能使用單一連線
+            pool_options = pool_options.max_connections(1);
+        }
+        let pool = pool_options.connect_with(options).await?;
+        let storage = Self { pool };
+        storage.migrate().await?;
+        Ok(storage)
+    }
+
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+
+    async fn migrate(&self) -> Result<(), StorageError> {
+        for statement in MIGRATIONS {
+            sqlx::query(statement).execute(&self.pool).await?;
+        }
+        Ok(())
+    }
+}
+
+fn reminder_from_row(row: &SqliteRow) -> Result<Reminder, StorageError> {
+    Ok(Reminder {
+        id: row.try_get("id")?,
+        user_id: row.try_get("user_id")?,
+        message: row.try_get("message")?,
+        due_at: row.try_get("due_at")?,
+    })
+}
+
+fn conversation_from_row(row: &SqliteRow) -> Result<ConversationEntry, StorageError> {
+    let direction: String = row.try_get("direction")?;
+    let message: String = row.try_get("message")?;
+    Ok(ConversationEntry {
+        user_id: row.try_get("user_id")?,
+        direction: ConversationDirection::parse(&direction)?,
+        message: serde_json::from_str(&message)?,
+        recorded_at: row.try_get("recorded_at")?,
+    })
+}
+
+fn audit_from_row(row: &SqliteRow) -> Result<AuditEntry, StorageError> {
+    Ok(AuditEntry {
+        actor: row.try_get("actor")?,
+        action: row.try_get("action")?,
+        target: row.try_get("target")?,
+        success: row.try_get("success")?,
+        detail: row.try_get("detail")?,
+        recorded_at: row.try_get("recorded_at")?,
+    })
+}
+
+#[async_trait]
+impl Storage for SqliteStorage {
+    async fn get_user(&self, user_id: &str) -> Result<Option<UserRecord>, StorageError> {
+        let row = sqlx::query(
+            "SELECT user_id, display_name, language, followed, updated_at FROM users WHERE user_id = ?",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(|row| {
+            Ok(UserRecord {
+                user_id: row.try_get("user_id")?,
+                display_name: row.try_get("display_name")?,
+                language: row.try_get("language")?,
+                followed: row.try_get("followed")?,
+                updated_at: row.try_get("updated_at")?,
+            })
+        })
+        .transpose()
+    }
+
+    async fn upsert_user(&self, user: &UserRecord) -> Result<(), StorageError> {
+        sqlx::query(
+            "INSERT INTO users (user_id, display_name, language, followed, updated_at)
+             VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT (user_id) DO UPDATE SET
+                display_name = excluded.display_name,
+                language = excluded.language,
+                followed = excluded.followed,
+                updated_at = excluded.updated_at",
+        )
+        .bind(&user.user_id)
+        .bind(&user.display_name)
+        .bind(&user.language)
+        .bind(user.followed)
+        .bind(user.updated_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_session(&self, user_id: &str) -> Result<Option<Session>, StorageError> {
+        let row = sqlx::query("SELECT data, expires_at FROM sessions WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let data: String = row.try_get("data")?;
+        let session = Session {
+            user_id: user_id.to_string(),
+            data: serde_json::from_str(&data)?,
+            expires_at: row.try_get("expires_at")?,
+        };
+        Ok(Some(session).filter(|session| !session.is_expired(Utc::now())))
+    }
+
+    async fn save_session(&self, session: &Session) -> Result<(), StorageError> {
+        sqlx::query(
+            "INSERT INTO sessions (user_id, data, expires_at) VALUES (?, ?, ?)
+             ON CONFLICT (user_id) DO UPDATE SET
+                data = excluded.data,
+                expires_at = excluded.expires_at",
+        )
+        .bind(&session.user_id)
+        .bind(serde_json::to_string(&session.data)?)
+        .bind(session.expires_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_session(&self, user_id: &str) -> Result<(), StorageError> {
+        sqlx::query("DELETE FROM sessions WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn kv_get(&self, key: &str) -> Result<Option<Value>, StorageError> {
+        let value: Option<String> = sqlx::query_scalar("SELECT value FROM kv WHERE key = ?")
+            .bind(key)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(value
+            .map(|value| serde_json::from_str(&value))
+            .transpose()?)
+    }
+
+    async fn kv_set(&self, key: &str, value: &Value) -> Result<(), StorageError> {
+        sqlx::query(
+            "INSERT INTO kv (key, value) VALUES (?, ?)
+             ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+        )
+        .bind(key)
+        .bind(serde_json::to_string(value)?)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn kv_delete(&self, key: &str) -> Result<(), StorageError> {
+        sqlx::query("DELETE FROM kv WHERE key = ?")
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn kv_keys(&self, prefix: &str) -> Result<Vec<String>, StorageError> {
+        // 不用 LIKE，避免前綴中的 `%`、`_` 被當成萬用字元
+        let keys = sqlx::query_scalar(
+            "SELECT key FROM kv WHERE substr(key, 1, length(?1)) = ?1 ORDER BY key",
+        )
+        .bind(prefix)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(keys)
+    }
+
+    async fn kv_increment(&self, key: &str, delta: i64) -> Result<i64, StorageError> {
+        let value: String = sqlx::query_scalar(
+            "INSERT INTO kv (key, value) VALUES (?1, CAST(?2 AS TEXT))
+             ON CONFLICT (key) DO UPDATE SET value = CAST(CAST(kv.value AS INTEGER) + ?2 AS TEXT)
+             RETURNING value",
+        )
+        .bind(key)
+        .bind(delta)
+        .fetch_one(&self.pool)
+        .await?;
+        value
+            .parse()
+            .map_err(|_| StorageError::new(format!("Value of {} is not an integer", key)))
+    }
+
+    async fn add_reminder(&self, reminder: NewReminder) -> Result<Reminder, StorageError> {
+        let row = sqlx::query(
+            "INSERT INTO reminders (user_id, message, due_at) VALUES (?, ?, ?)
+             RETURNING id, user_id, message, due_at",
+        )
+        .bind(&reminder.user_id)
+        .bind(&reminder.message)
+        .bind(reminder.due_at)
+        .fetch_one(&self.pool)
+        .await?;
+        reminder_from_row(&row)
+    }
+
+    async fn due_reminders(&self, now: DateTime<Utc>) -> Result<Vec<Reminder>, StorageError> {
+        let rows = sqlx::query(
+            "SELECT id, user_id, message, due_at FROM reminders
+             WHERE due_at <= ? ORDER BY due_at, id",
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter().map(reminder_from_row).collect()
+    }
+
+    async fn delete_reminder(&self, id: i64) -> Result<(), StorageError> {
+        sqlx::query("DELETE FROM reminders WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn append_conversation(&self, entry: &ConversationEntry) -> Result<(), StorageError> {
+        sqlx::query(
+            "INSERT INTO conversations (user_id, direction, message, recorded_at)
+             VALUES (?, ?, ?, ?)",
+        )
+        .bind(&entry.user_id)
+        .bind(entry.direction.as_str())
+        .bind(serde_json::to_string(&entry.message)?)
+        .bind(entry.recorded_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn conversation_history(
+        &self,
+        query: &HistoryQuery,
+    ) -> Result<Vec<ConversationEntry>, StorageError> {
+        let rows = sqlx::query(
+            "SELECT user_id, direction, message, recorded_at FROM conversations
+             WHERE user_id = ?
+               AND (? IS NULL OR recorded_at >= ?)
+               AND (? IS NULL OR recorded_at < ?)
+             ORDER BY recorded_at, id
+             LIMIT ?",
+        )
+        .bind(&query.user_id)
+        .bind(query.since)
+        .bind(query.since)
+        .bind(query.until)
+        .bind(query.until)
+        .bind(i64::from(query.limit))
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(conversation_from_row).collect()
+    }
+
+    async fn conversation_page(
+        &self,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+        after: Option<ConversationCursor>,
+        limit: u32,
+    ) -> Result<ConversationPage, StorageError> {
+        let after_at = after.map(|after| after.recorded_at);
+        let rows = sqlx::query(
+            "SELECT id, user_id, direction, message, recorded_at FROM conversations
+             WHERE (? IS NULL OR recorded_at >= ?)
+               AND (? IS NULL OR recorded_at < ?)
+               AND (? IS NULL OR (recorded_at, id) > (?, ?))
+             ORDER BY recorded_at, id
+             LIMIT ?",
+        )
+        .bind(since)
+        .bind(since)
+        .bind(until)
+        .bind(until)
+        .bind(after_at)
+        .bind(after_at)
+        .bind(after.map(|after| after.id))
+        .bind(i64::from(limit))
+        .fetch_all(&self.pool)
+        .await?;
+
+        let next = match rows.last() {
+            Some(row) if rows.len() == limit as usize => Some(ConversationCursor {
+                recorded_at: row.try_get("recorded_at")?,
+                id: row.try_get("id")?,
+            }),
+            _ => None,
+        };
+        Ok(ConversationPage {
+            entries: rows
+                .iter()
+                .map(conversation_from_row)
+                .collect::<Result<_, _>>()?,
+            next,
+        })
+    }
+
+    async fn append_audit(&self, entry: &AuditEntry) -> Result<(), StorageError> {
+        sqlx::query(
+            "INSERT INTO audit_log (actor, action, target, success, detail, recorded_at)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&entry.actor)
+        .bind(&entry.action)
+        .bind(&entry.target)
+        .bind(entry.success)
+        .bind(&entry.detail)
+        .bind(entry.recorded_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn audit_log(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>, StorageError> {
+        let rows = sqlx::query(
+            "SELECT actor, action, target, success, detail, recorded_at FROM audit_log
+             WHERE (? IS NULL OR actor = ?)
+               AND (? IS NULL OR substr(action, 1, length(?)) = ?)
+               AND (? IS NULL OR recorded_at >= ?)
+               AND (? IS NULL OR recorded_at < ?)
+             ORDER BY recorded_at DESC, id DESC
+             LIMIT ?",
+        )
+        .bind(&query.actor)
+        .bind(&query.actor)
+        .bind(&query.action)
+        .bind(&query.action)
+        .bind(&query.action)
+        .bind(query.since)
+        .bind(query.since)
+        .bind(query.until)
+        .bind(query.until)
+        .bind(i64::from(query.limit))
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(audit_from_row).collect()
+    }
+
+    async fn purge_user(&self, user_id: &str) -> Result<(), StorageError> {
+        let mut tx = self.pool.begin().await?;
+        for table in ["users", "sessions", "reminders", "conversations"] {
+            sqlx::query(&format!("DELETE FROM {} WHERE user_id = ?", table))
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sqlite_storage() {
+        let storage = SqliteStorage::connect("sqlite::memory:").await.unwrap();
+        crate::storage::tests::exercise_storage(&storage).await;
+    }
+}
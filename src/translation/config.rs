@@ -0,0 +1,35 @@
+use serde::Deserialize;
+use std::fmt;
+
+#[derive(Clone, Deserialize, PartialEq)]
+pub enum TranslationProvider {
+    /// Google Cloud Translation（v2 Basic），以 API key 驗證
+    Google { api_key: String },
+    /// 自架或託管的 LibreTranslate
+    Libre {
+        url: String,
+        api_key: Option<String>,
+    },
+}
+
+impl fmt::Debug for TranslationProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TranslationProvider::Google { .. } => {
+                f.debug_struct("Google").field("api_key", &"***").finish()
+            }
+            TranslationProvider::Libre { url, api_key } => f
+                .debug_struct("Libre")
+                .field("url", url)
+                .field("api_key", &api_key.as_ref().map(|_| "***"))
+                .finish(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct TranslationConfig {
+    pub provider: TranslationProvider,
+    /// Bot 的工作語言，指令、禁用詞與內建回覆都以此語言撰寫
+    pub working_language: String,
+}
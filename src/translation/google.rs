@@ -0,0 +1,124 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+
+use crate::translation::{TranslationError, Translator};
+
+/// 單次請求的逾時
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+const DEFAULT_ENDPOINT: &str = "https://translation.googleapis.com/language/translate/v2";
+
+/// Google Cloud Translation（v2 Basic）
+#[derive(Clone)]
+pub struct GoogleTranslator {
+    client: reqwest::Client,
+    api_key: String,
+    endpoint: String,
+}
+
+#[derive(Deserialize)]
+struct Response<T> {
+    data: T,
+}
+
+#[derive(Deserialize)]
+struct Translations {
+    translations: Vec<Translation>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Translation {
+    translated_text: String,
+}
+
+#[derive(Deserialize)]
+struct Detections {
+    detections: Vec<Vec<Detection>>,
+}
+
+#[derive(Deserialize)]
+struct Detection {
+    language: String,
+}
+
+impl GoogleTranslator {
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key: api_key.into(),
+            endpoint: DEFAULT_ENDPOINT.to_string(),
+        }
+    }
+
+    /// 使用共用的 HTTP 用戶端
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// 覆寫 API 端點，測試或經由代理時使用
+    pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    async fn post<T: serde::de::DeserializeOwned>(
+        &self,
+        url: &str,
+        body: serde_json::Value,
+    ) -> Result<T, TranslationError> {
+        let response = self
+            .client
+            .post(url)
+            .timeout(REQUEST_TIMEOUT)
+            .query(&[("key", &self.api_key)])
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| TranslationError::new(format!("Failed to send request: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(TranslationError::from_response(response).await);
+        }
+        let response: Response<T> = response
+            .json()
+            .await
+            .map_err(|e| TranslationError::new(format!("Invalid response: {}", e)))?;
+        Ok(response.data)
+    }
+}
+
+#[async_trait]
+impl Translator for GoogleTranslator {
+    async fn detect(&self, text: &str) -> Result<Option<String>, TranslationError> {
+        let url = format!("{}/detect", self.endpoint.trim_end_matches('/'));
+        let data: Detections = self.post(&url, json!({ "q": text })).await?;
+        Ok(data
+            .detections
+            .into_iter()
+            .flatten()
+            .next()
+            .map(|detection| detection.language)
+            .filter(|language| language != "und"))
+    }
+
+    async fn translate(
+        &self,
+        text: &str,
+        source: Option<&str>,
+        target: &str,
+    ) -> Result<String, TranslationError> {
+        let mut body = json!({ "q": text, "target": target, "format": "text" });
+        if let Some(source) = source {
+            body["source"] = json!(source);
+        }
+        let data: Translations = self.post(&self.endpoint, body).await?;
+        data.translations
+            .into_iter()
+            .next()
+            .map(|translation| translation.translated_text)
+            .ok_or_else(|| TranslationError::new("Empty translation response"))
+    }
+}
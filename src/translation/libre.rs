@@ -0,0 +1,146 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::time::Duration;
+
+use crate::translation::{TranslationError, Translator};
+
+/// 單次請求的逾時
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// LibreTranslate，語言代碼依伺服器支援的清單（例如 `en`、`zh`、`ja`）
+#[derive(Clone)]
+pub struct LibreTranslator {
+    client: reqwest::Client,
+    url: String,
+    api_key: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TranslateResponse {
+    translated_text: String,
+}
+
+#[derive(Deserialize)]
+struct Detection {
+    language: String,
+}
+
+impl LibreTranslator {
+    /// `url` 為伺服器位址，例如 `http://libretranslate:5000`
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.into(),
+            api_key: None,
+        }
+    }
+
+    /// 使用共用的 HTTP 用戶端
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    async fn post<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        mut body: Value,
+    ) -> Result<T, TranslationError> {
+        if let Some(api_key) = &self.api_key {
+            body["api_key"] = json!(api_key);
+        }
+        let url = format!("{}/{}", self.url.trim_end_matches('/'), path);
+        let response = self
+            .client
+            .post(&url)
+            .timeout(REQUEST_TIMEOUT)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| TranslationError::new(format!("Failed to send request: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(TranslationError::from_response(response).await);
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| TranslationError::new(format!("Invalid response: {}", e)))
+    }
+}
+
+#[async_trait]
+impl Translator for LibreTranslator {
+    async fn detect(&self, text: &str) -> Result<Option<String>, TranslationError> {
+        let detections: Vec<Detection> = self.post("detect", json!({ "q": text })).await?;
+        Ok(detections
+            .into_iter()
+            .next()
+            .map(|detection| detection.language))
+    }
+
+    async fn translate(
+        &self,
+        text: &str,
+        source: Option<&str>,
+        target: &str,
+    ) -> Result<String, TranslationError> {
+        let response: TranslateResponse = self
+            .post(
+                "translate",
+                json!({
+                    "q": text,
+                    "source": source.unwrap_or("auto"),
+                    "target": target,
+                    "format": "text",
+                }),
+            )
+            .await?;
+        Ok(response.translated_text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Json, Router, routing::post};
+
+    #[tokio::test]
+    async fn test_libre_translate() {
+        let app = Router::new()
+            .route(
+                "/detect",
+                post(|Json(body): Json<Value>| async move {
+                    assert_eq!(body["api_key"], "key");
+                    Json(json!([{ "language": "en", "confidence": 90.0 }]))
+                }),
+            )
+            .route(
+                "/translate",
+                post(|Json(body): Json<Value>| async move {
+                    assert_eq!(body["source"], "auto");
+                    assert_eq!(body["target"], "zh");
+                    Json(json!({ "translatedText": "你好" }))
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let translator = LibreTranslator::new(format!("http://{}", addr)).api_key("key");
+        assert_eq!(
+            translator.detect("hello").await.unwrap().as_deref(),
+            Some("en")
+        );
+        assert_eq!(
+            translator.translate("hello", None, "zh").await.unwrap(),
+            "你好"
+        );
+    }
+}
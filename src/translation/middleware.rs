@@ -0,0 +1,188 @@
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::line_api::ProfileLanguageCache;
+use crate::models::OutgoingMessage;
+use crate::translation::Translator;
+
+/// 收到的文字翻成工作語言、回覆翻回使用者的語言
+///
+/// 使用者語言優先取自 LINE 個人資料的 `language`（結果會快取），取不到時以翻譯服務偵測。
+/// 翻譯失敗時沿用原文，不影響訊息處理。
+#[derive(Clone)]
+pub struct TranslationMiddleware {
+    translator: Arc<dyn Translator>,
+    working_language: String,
+    profile_languages: Option<ProfileLanguageCache>,
+}
+
+/// 翻譯後的收到訊息
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranslatedText {
+    /// 工作語言的文字，不需翻譯時為原文
+    pub text: String,
+    /// 與工作語言不同時為使用者的語言，回覆需翻回此語言
+    pub reply_language: Option<String>,
+}
+
+impl TranslationMiddleware {
+    pub fn new(translator: Arc<dyn Translator>, working_language: impl Into<String>) -> Self {
+        Self {
+            translator,
+            working_language: working_language.into(),
+            profile_languages: None,
+        }
+    }
+
+    /// 以此快取查詢使用者個人資料的語言設定
+    pub fn profile_languages(mut self, cache: ProfileLanguageCache) -> Self {
+        self.profile_languages = Some(cache);
+        self
+    }
+
+    pub async fn inbound(&self, user_id: Option<&str>, text: &str) -> TranslatedText {
+        let untranslated = TranslatedText {
+            text: text.to_string(),
+            reply_language: None,
+        };
+        let language = match self.sender_language(user_id, text).await {
+            Some(language) if !same_language(&language, &self.working_language) => language,
+            _ => return untranslated,
+        };
+
+        match self
+            .translator
+            .translate(text, Some(&language), &self.working_language)
+            .await
+        {
+            Ok(translated) => TranslatedText {
+                text: translated,
+                reply_language: Some(language),
+            },
+            Err(e) => {
+                warn!("Failed to translate incoming message: {}", e);
+                untranslated
+            }
+        }
+    }
+
+    /// 將文字訊息翻成 `language`，其他類型的訊息原樣保留
+    pub async fn outbound(
+        &self,
+        messages: Vec<OutgoingMessage>,
+        language: &str,
+    ) -> Vec<OutgoingMessage> {
+        let mut translated = Vec::with_capacity(messages.len());
+        for message in messages {
+            translated.push(match message {
+                OutgoingMessage::Text { text, quick_reply } => match self
+                    .translator
+                    .translate(&text, Some(&self.working_language), language)
+                    .await
+                {
+                    Ok(text) => OutgoingMessage::Text { text, quick_reply },
+                    Err(e) => {
+                        warn!("Failed to translate reply: {}", e);
+                        OutgoingMessage::Text { text, quick_reply }
+                    }
+                },
+                message => message,
+            });
+        }
+        translated
+    }
+
+    async fn sender_language(&self, user_id: Option<&str>, text: &str) -> Option<String> {
+        if let Some(user_id) = user_id
+            && let Some(profile_languages) = &self.profile_languages
+            && let Some(language) = profile_languages.get(user_id).await
+        {
+            return Some(language);
+        }
+        match self.translator.detect(text).await {
+            Ok(language) => language,
+            Err(e) => {
+                warn!("Failed to detect message language: {}", e);
+                None
+            }
+        }
+    }
+}
+
+/// 比較語言標籤；中文依繁簡區分，其他語言只比較主要語言
+fn same_language(a: &str, b: &str) -> bool {
+    language_key(a) == language_key(b)
+}
+
+fn language_key(tag: &str) -> String {
+    let tag = tag.to_lowercase().replace('_', "-");
+    let primary = tag.split('-').next().unwrap_or_default();
+    if primary != "zh" {
+        return primary.to_string();
+    }
+    let traditional = tag
+        .split('-')
+        .skip(1)
+        .any(|subtag| matches!(subtag, "hant" | "tw" | "hk" | "mo"));
+    if traditional { "zh-hant" } else { "zh-hans" }.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translation::TranslationError;
+    use async_trait::async_trait;
+
+    /// 在文字前加上目標語言，偵測時以是否含 ASCII 字母判斷
+    struct FakeTranslator;
+
+    #[async_trait]
+    impl Translator for FakeTranslator {
+        async fn detect(&self, text: &str) -> Result<Option<String>, TranslationError> {
+            let english = text.chars().any(|c| c.is_ascii_alphabetic());
+            Ok(Some(if english { "en" } else { "zh-Hant" }.to_string()))
+        }
+
+        async fn translate(
+            &self,
+            text: &str,
+            _source: Option<&str>,
+            target: &str,
+        ) -> Result<String, TranslationError> {
+            Ok(format!("[{}]{}", target, text))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_translation_round_trip() {
+        let middleware = TranslationMiddleware::new(Arc::new(FakeTranslator), "zh-TW");
+
+        let inbound = middleware.inbound(Some("U123"), "hello").await;
+        assert_eq!(inbound.text, "[zh-TW]hello");
+        assert_eq!(inbound.reply_language.as_deref(), Some("en"));
+
+        let replies = middleware
+            .outbound(
+                vec![
+                    OutgoingMessage::text("你好"),
+                    OutgoingMessage::sticker("446", "1988"),
+                ],
+                "en",
+            )
+            .await;
+        assert_eq!(replies[0], OutgoingMessage::text("[en]你好"));
+        assert_eq!(replies[1], OutgoingMessage::sticker("446", "1988"));
+
+        let same = middleware.inbound(None, "你好").await;
+        assert_eq!(same.text, "你好");
+        assert_eq!(same.reply_language, None);
+    }
+
+    #[test]
+    fn test_same_language() {
+        assert!(same_language("zh-TW", "zh-Hant"));
+        assert!(same_language("en-US", "en"));
+        assert!(!same_language("zh-TW", "zh-CN"));
+        assert!(!same_language("ja", "en"));
+    }
+}
@@ -0,0 +1,15 @@
+//! 自動翻譯：收到的文字翻成 Bot 的工作語言，回覆再翻回使用者的語言
+//!
+//! 翻譯服務透過 [`Translator`] 抽換，內建 Google Cloud Translation 與 LibreTranslate。
+
+pub mod config;
+pub mod google;
+pub mod libre;
+pub mod middleware;
+pub mod translator;
+
+pub use config::*;
+pub use google::*;
+pub use libre::*;
+pub use middleware::*;
+pub use translator::*;
@@ -0,0 +1,49 @@
+use async_trait::async_trait;
+use std::{error::Error, fmt};
+
+#[derive(Debug)]
+pub struct TranslationError {
+    pub message: String,
+    pub status_code: Option<u16>,
+}
+
+impl fmt::Display for TranslationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Translation failed: {}", self.message)
+    }
+}
+
+impl Error for TranslationError {}
+
+impl TranslationError {
+    pub(crate) fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            status_code: None,
+        }
+    }
+
+    /// 讀取非 2xx 回應的內容作為錯誤訊息
+    pub(crate) async fn from_response(response: reqwest::Response) -> Self {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        Self {
+            message: format!("Request failed with status {}: {}", status, body),
+            status_code: Some(status.as_u16()),
+        }
+    }
+}
+
+/// 翻譯服務，語言以 BCP 47 標籤表示（例如 `en`、`zh-TW`）
+#[async_trait]
+pub trait Translator: Send + Sync {
+    /// 偵測文字的語言，無法判斷時回傳 `None`
+    async fn detect(&self, text: &str) -> Result<Option<String>, TranslationError>;
+
+    async fn translate(
+        &self,
+        text: &str,
+        source: Option<&str>,
+        target: &str,
+    ) -> Result<String, TranslationError>;
+}
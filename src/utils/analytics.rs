@@ -0,0 +1,305 @@
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use metrics::{counter, gauge};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+use crate::storage::{Storage, StorageError};
+
+/// 統計區間
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnalyticsPeriod {
+    Hourly,
+    Daily,
+}
+
+impl AnalyticsPeriod {
+    fn as_str(&self) -> &'static str {
+        match self {
+            AnalyticsPeriod::Hourly => "hourly",
+            AnalyticsPeriod::Daily => "daily",
+        }
+    }
+
+    fn length(&self) -> Duration {
+        match self {
+            AnalyticsPeriod::Hourly => Duration::hours(1),
+            AnalyticsPeriod::Daily => Duration::days(1),
+        }
+    }
+
+    /// 時間所屬區間的開始時間（UTC）
+    pub fn bucket_start(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        at.duration_trunc(self.length()).unwrap_or(at)
+    }
+
+    fn storage_key(&self, start: DateTime<Utc>) -> String {
+        format!(
+            "analytics:{}:{}",
+            self.as_str(),
+            start.format("%Y-%m-%dT%H")
+        )
+    }
+}
+
+/// 單一區間的統計結果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AnalyticsBucket {
+    pub start: Option<DateTime<Utc>>,
+    pub active_users: u64,
+    pub messages: BTreeMap<String, u64>,
+    pub commands: BTreeMap<String, u64>,
+    pub errors: u64,
+}
+
+/// `/admin/stats` 顯示的近期統計
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalyticsReport {
+    pub hourly: Vec<AnalyticsBucket>,
+    pub daily: Vec<AnalyticsBucket>,
+}
+
+#[derive(Debug, Default)]
+struct BucketState {
+    users: HashSet<String>,
+    messages: BTreeMap<String, u64>,
+    commands: BTreeMap<String, u64>,
+    errors: u64,
+    /// 重新啟動前已寫入的統計，首次寫回時載入
+    persisted: Option<AnalyticsBucket>,
+}
+
+impl BucketState {
+    fn to_bucket(&self, start: DateTime<Utc>) -> AnalyticsBucket {
+        let mut bucket = self.persisted.clone().unwrap_or_default();
+        bucket.start = Some(start);
+        // 無法得知重啟前的使用者是否重複出現，活躍人數以相加近似
+        bucket.active_users += self.users.len() as u64;
+        for (message_type, count) in &self.messages {
+            *bucket.messages.entry(message_type.clone()).or_default() += count;
+        }
+        for (command, count) in &self.commands {
+            *bucket.commands.entry(command.clone()).or_default() += count;
+        }
+        bucket.errors += self.errors;
+        bucket
+    }
+}
+
+/// 使用量分析聚合器
+///
+/// 在記憶體中累計每小時與每日的活躍使用者、訊息類型、指令使用次數與錯誤數，
+/// 由背景工作定期透過 `Storage` 的 key-value 介面寫回，不需額外的資料表。
+#[derive(Debug, Default)]
+pub struct AnalyticsAggregator {
+    buckets: Mutex<HashMap<(AnalyticsPeriod, DateTime<Utc>), BucketState>>,
+}
+
+impl AnalyticsAggregator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_message(&self, user_id: &str, message_type: &str) {
+        counter!("analytics_messages_total", "type" => message_type.to_string()).increment(1);
+        self.update(|state| {
+            state.users.insert(user_id.to_string());
+            *state.messages.entry(message_type.to_string()).or_default() += 1;
+        });
+    }
+
+    pub fn record_command(&self, command: &str) {
+        counter!("analytics_commands_total", "command" => command.to_string()).increment(1);
+        self.update(|state| {
+            *state.commands.entry(command.to_string()).or_default() += 1;
+        });
+    }
+
+    pub fn record_error(&self) {
+        self.update(|state| state.errors += 1);
+    }
+
+    fn update(&self, apply: impl Fn(&mut BucketState)) {
+        let now = Utc::now();
+        let Ok(mut buckets) = self.buckets.lock() else {
+            return;
+        };
+        for period in [AnalyticsPeriod::Hourly, AnalyticsPeriod::Daily] {
+            let state = buckets
+                .entry((period, period.bucket_start(now)))
+                .or_default();
+            apply(state);
+            gauge!("analytics_active_users", "period" => period.as_str())
+                .set(state.users.len() as f64);
+        }
+    }
+
+    /// 將目前的統計寫入儲存後端，並移除已結束的區間
+    pub async fn flush(&self, storage: &dyn Storage) -> Result<(), StorageError> {
+        let now = Utc::now();
+        let keys: Vec<(AnalyticsPeriod, DateTime<Utc>)> = match self.buckets.lock() {
+            Ok(buckets) => buckets.keys().copied().collect(),
+            Err(_) => return Err(StorageError::new("Analytics lock poisoned")),
+        };
+
+        for (period, start) in keys {
+            let storage_key = period.storage_key(start);
+            let needs_baseline = self
+                .buckets
+                .lock()
+                .map(|buckets| {
+                    buckets
+                        .get(&(period, start))
+                        .is_some_and(|state| state.persisted.is_none())
+                })
+                .unwrap_or(false);
+            let baseline = if needs_baseline {
+                match storage.kv_get(&storage_key).await? {
+                    Some(value) => serde_json::from_value(value)?,
+                    None => AnalyticsBucket::default(),
+                }
+            } else {
+                AnalyticsBucket::default()
+            };
+
+            let bucket = {
+                let Ok(mut buckets) = self.buckets.lock() else {
+                    return Err(StorageError::new("Analytics lock poisoned"));
+                };
+                let Some(state) = buckets.get_mut(&(period, start)) else {
+                    continue;
+                };
+                if needs_baseline {
+                    state.persisted = Some(baseline);
+                }
+                state.to_bucket(start)
+            };
+            storage
+                .kv_set(&storage_key, &serde_json::to_value(&bucket)?)
+                .await?;
+
+            if !period_is_current(period, start, now)
+                && let Ok(mut buckets) = self.buckets.lock()
+            {
+                buckets.remove(&(period, start));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 寫回目前統計後，讀取最近 `hours` 小時與 `days` 天的資料（由新到舊）
+    pub async fn report(
+        &self,
+        storage: &dyn Storage,
+        hours: u32,
+        days: u32,
+    ) -> Result<AnalyticsReport, StorageError> {
+        self.flush(storage).await?;
+        let now = Utc::now();
+        Ok(AnalyticsReport {
+            hourly: load_buckets(storage, AnalyticsPeriod::Hourly, now, hours).await?,
+            daily: load_buckets(storage, AnalyticsPeriod::Daily, now, days).await?,
+        })
+    }
+
+    /// 啟動定期寫回的背景工作
+    pub fn start_flush_task(
+        self: &Arc<Self>,
+        storage: Arc<dyn Storage>,
+        interval: std::time::Duration,
+    ) {
+        let aggregator = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = aggregator.flush(storage.as_ref()).await {
+                    warn!("Failed to persist analytics: {}", e);
+                }
+            }
+        });
+    }
+}
+
+fn period_is_current(period: AnalyticsPeriod, start: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+    period.bucket_start(now) == start
+}
+
+async fn load_buckets(
+    storage: &dyn Storage,
+    period: AnalyticsPeriod,
+    now: DateTime<Utc>,
+    count: u32,
+) -> Result<Vec<AnalyticsBucket>, StorageError> {
+    let mut buckets = Vec::new();
+    let mut start = period.bucket_start(now);
+    for _ in 0..count {
+        if let Some(value) = storage.kv_get(&period.storage_key(start)).await? {
+            buckets.push(serde_json::from_value(value)?);
+        }
+        start -= period.length();
+    }
+    Ok(buckets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_analytics_report() {
+        let storage = MemoryStorage::new();
+        let aggregator = AnalyticsAggregator::new();
+
+        aggregator.record_message("U1", "text");
+        aggregator.record_message("U1", "text");
+        aggregator.record_message("U2", "image");
+        aggregator.record_command("help");
+        aggregator.record_error();
+
+        let report = aggregator.report(&storage, 24, 7).await.unwrap();
+        let hour = &report.hourly[0];
+        assert_eq!(hour.active_users, 2);
+        assert_eq!(hour.messages["text"], 2);
+        assert_eq!(hour.commands["help"], 1);
+        assert_eq!(hour.errors, 1);
+        assert_eq!(report.daily[0].active_users, 2);
+    }
+
+    #[tokio::test]
+    async fn test_flush_merges_previously_persisted_counts() {
+        let storage = MemoryStorage::new();
+
+        let before_restart = AnalyticsAggregator::new();
+        before_restart.record_message("U1", "text");
+        before_restart.flush(&storage).await.unwrap();
+
+        let after_restart = AnalyticsAggregator::new();
+        after_restart.record_message("U2", "text");
+        after_restart.flush(&storage).await.unwrap();
+        after_restart.flush(&storage).await.unwrap();
+
+        let report = after_restart.report(&storage, 1, 1).await.unwrap();
+        assert_eq!(report.hourly[0].messages["text"], 2);
+        assert_eq!(report.hourly[0].active_users, 2);
+    }
+
+    #[test]
+    fn test_bucket_start() {
+        let at = DateTime::parse_from_rfc3339("2024-01-01T12:34:56Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            AnalyticsPeriod::Hourly.bucket_start(at).to_rfc3339(),
+            "2024-01-01T12:00:00+00:00"
+        );
+        assert_eq!(
+            AnalyticsPeriod::Daily.bucket_start(at).to_rfc3339(),
+            "2024-01-01T00:00:00+00:00"
+        );
+    }
+}
@@ -1,12 +1,237 @@
 use serde::Deserialize;
-use std::env;
+use std::{env, net::SocketAddr};
+
+use crate::ai::LlmConfig;
+use crate::auth::LineLoginConfig;
+use crate::bridge::{BridgeConfig, BridgePlatform};
+use crate::campaigns::{Campaign, load_campaigns};
+use crate::feeds::FeedSource;
+use crate::games::QuizConfig;
+use crate::line_api::{ProxyConfig, RichMenuStateConfig};
+use crate::media::{DEFAULT_MAX_MEDIA_BYTES, LocalMediaConfig, MediaStoreConfig, S3MediaConfig};
+use crate::nlu::{NluConfig, NluProvider};
+use crate::onboarding::{OnboardingConfig, load_onboarding};
+use crate::pay::LinePayConfig;
+use crate::sinks::{CsvRotation, CsvSinkConfig, EventSinkConfig, GoogleSheetsConfig};
+use crate::storage::ConversationMasking;
+use crate::translation::{TranslationConfig, TranslationProvider};
+#[cfg(feature = "server")]
+use crate::utils::RateLimitConfig;
+use crate::utils::{
+    DuplicateFilterConfig, HttpClientConfig, I18nConfig, Locale, MetricsAuth,
+    MetricsExporterConfig, ModerationPolicy, OtlpExporterConfig, StatsdExporterConfig,
+};
+use crate::webhook::{ForwardTarget, OverflowPolicy, WebhookQueueConfig, WebhookRecorderConfig};
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
     pub channel_access_token: String,
     pub channel_secret: String,
+    /// 輪替期間仍接受的舊 channel secret
+    pub secondary_channel_secrets: Vec<String>,
     pub port: u16,
     pub host: String,
+    /// 管理端點 (`/admin/*`) 使用的 Bearer token，未設定時不開放管理端點
+    pub admin_token: Option<String>,
+    /// gRPC 管理服務的位址（需啟用 `grpc` feature），驗證方式同管理端點
+    pub grpc_bind: Option<SocketAddr>,
+    /// LINE Login channel，未設定時不開放 `/liff/verify` 與 `/auth/line/*`
+    pub line_login: Option<LineLoginConfig>,
+    /// Webhook 轉發目標
+    pub forward_targets: Vec<ForwardTarget>,
+    /// 轉發時重新簽名使用的 secret，未設定時沿用 channel secret
+    pub forward_secret: Option<String>,
+    pub forward_max_retries: u32,
+    /// Sentry DSN，需啟用 `sentry` feature
+    pub sentry_dsn: Option<String>,
+    /// 管理端點回應是否啟用 gzip 壓縮
+    pub compression_enabled: bool,
+    /// 是否接受 `Content-Encoding: gzip` 的請求內容
+    pub request_decompression_enabled: bool,
+    /// LINE API 連線使用的 HTTP 代理
+    pub line_api_proxy: Option<ProxyConfig>,
+    /// 覆寫 LINE API 位址（例如 mock server）
+    pub line_api_base_url: Option<String>,
+    /// 覆寫 LINE 內容 API（`api-data.line.me`）位址
+    pub line_data_api_base_url: Option<String>,
+    /// 送出訊息時只記錄不實際呼叫 LINE API
+    pub dry_run: bool,
+    /// 只接受帶 `sha256=` 前綴的 `x-line-signature`
+    pub signature_require_prefix: bool,
+    /// 拒絕 `destination` 不是這個 bot 的 Webhook
+    pub verify_destination: bool,
+    /// Bot 的使用者 ID，未設定時啟動後以 `GET /v2/bot/info` 取得
+    pub bot_user_id: Option<String>,
+    /// 開發用：略過 Webhook 簽名驗證
+    pub insecure_skip_signature: bool,
+    /// 允許在非 loopback 位址上略過簽名驗證
+    pub insecure_skip_signature_force: bool,
+    /// Webhook 請求內容的大小上限（位元組）
+    pub webhook_max_body_bytes: usize,
+    /// 設定 `WEBHOOK_QUEUE_CAPACITY` 時改為排入佇列、由背景 worker 處理事件
+    pub webhook_queue: Option<WebhookQueueConfig>,
+    /// 設定 `WEBHOOK_RECORDING_ENABLED` 時保存收到的 Webhook 內容，供管理端點重新處理
+    pub webhook_recording: Option<WebhookRecorderConfig>,
+    /// 設定 `RATE_LIMIT_MAX_REQUESTS` 時依來源 IP 限制管理、LIFF、登入與橋接端點的請求數
+    #[cfg(feature = "server")]
+    pub rate_limit: Option<RateLimitConfig>,
+    /// LINE API 無法連線時暫存 push 訊息的檔案
+    pub offline_buffer_path: Option<String>,
+    /// 暫存訊息的保留時間（秒），超過即丟棄
+    pub offline_buffer_max_age_secs: u64,
+    /// 儲存後端 URL（`sqlite:`、`postgres://`），未設定時使用記憶體
+    pub storage_url: Option<String>,
+    /// 是否將收發訊息寫入儲存後端
+    pub conversation_log_enabled: bool,
+    pub conversation_log_masking: ConversationMasking,
+    /// 是否將管理操作、群發、Rich Menu 異動與設定重新載入寫入稽核紀錄
+    pub audit_log_enabled: bool,
+    /// 媒體訊息內容的儲存位置，未設定時不下載
+    pub media_store: Option<MediaStoreConfig>,
+    /// 單一媒體的下載大小上限（位元組）
+    pub media_max_bytes: usize,
+    /// 群組資訊快取有效時間（秒）
+    pub group_cache_ttl_secs: u64,
+    /// 收到 unfollow 時刪除該使用者的所有資料
+    pub purge_on_unfollow: bool,
+    /// Prometheus 匯出器設定，未設定 `METRICS_BIND` 時不啟動
+    pub metrics_exporter: Option<MetricsExporterConfig>,
+    /// StatsD 匯出器設定，與 Prometheus 匯出器擇一使用
+    pub statsd_exporter: Option<StatsdExporterConfig>,
+    /// OTLP 匯出器設定，與 Prometheus、StatsD 匯出器擇一使用
+    pub otlp_exporter: Option<OtlpExporterConfig>,
+    /// 訊息額度查詢間隔（秒），未設定時不查詢
+    pub quota_poll_interval_secs: Option<u64>,
+    /// 額度使用達此百分比時發出警告
+    pub quota_warning_percent: u8,
+    /// 額度警告推播對象
+    pub quota_alert_user_ids: Vec<String>,
+    /// 禁用詞清單檔案，修改後會自動重新載入；未設定時使用內建清單
+    pub forbidden_words_path: Option<String>,
+    /// 回覆樣板目錄（需啟用 `templates` feature），修改後會自動重新載入
+    pub reply_templates_dir: Option<String>,
+    /// Rhai 回覆腳本目錄（需啟用 `scripting` feature），修改後會自動重新載入
+    pub reply_scripts_dir: Option<String>,
+    /// 功能開關規則檔（JSON），修改後會自動重新載入；未設定時只使用管理 API 的覆寫
+    pub feature_flags_path: Option<String>,
+    /// 未符合指令的文字訊息交給 LLM 回覆（需啟用 `ai` feature），未設定 `LLM_MODEL` 時停用
+    pub llm: Option<LlmConfig>,
+    /// 指令比對失敗時以 Rasa 或 Dialogflow CX 解析意圖，未設定時停用
+    pub nlu: Option<NluConfig>,
+    /// 自動翻譯收到的文字與回覆，未設定翻譯服務時停用
+    pub translation: Option<TranslationConfig>,
+    /// 定期輪詢並推播的 RSS/Atom feed（需啟用 `feeds` feature）
+    pub feed_sources: Vec<FeedSource>,
+    /// feed 輪詢間隔（秒）
+    pub feed_poll_interval_secs: u64,
+    /// 把錯誤、新好友與含關鍵字的訊息轉到 Slack/Discord（需啟用 `bridge` feature）
+    pub operator_bridge: Option<BridgeConfig>,
+    /// LINE Pay 付款（需啟用 `pay` feature），未設定 `LINE_PAY_CHANNEL_ID` 時停用
+    pub line_pay: Option<LinePayConfig>,
+    /// 加入好友後的引導流程，未設定 `ONBOARDING_FILE` 時只送出預設歡迎訊息
+    pub onboarding: Option<OnboardingConfig>,
+    /// 群組問答遊戲（需啟用 `games` feature），未設定 `QUIZ_QUESTIONS_PATH` 時停用
+    pub quiz: Option<QuizConfig>,
+    /// 事件匯出到 CSV 檔或 Google Sheets，未設定目的地時停用
+    pub event_sinks: Option<EventSinkConfig>,
+    /// 由 `CAMPAIGNS_FILE` 讀取的排程推播活動（需啟用 `campaigns` feature）
+    pub campaigns: Vec<Campaign>,
+    /// 送出訊息中的 URL 只允許這些主機（含子網域），空白表示不限制
+    pub outgoing_url_allowed_hosts: Vec<String>,
+    /// 各嚴重程度禁用詞的處置，可透過 `/admin/moderation/policy` 在執行期間調整
+    pub moderation_policy: ModerationPolicy,
+    /// 審核處置為 `notify_admin` 時推播的對象
+    pub moderation_alert_user_ids: Vec<String>,
+    /// 另外接收審核回報與每日摘要的營運群組
+    pub moderation_report_group_id: Option<String>,
+    /// 每天最多即時推播的審核回報數，其餘只列入每日摘要
+    pub moderation_report_daily_limit: u32,
+    /// 可在群組中以 `/group` 指令變更群組設定的使用者，未設定時停用指令
+    pub group_admin_user_ids: Vec<String>,
+    /// 略過同一使用者連續送出的相同訊息，未設定 `DUPLICATE_MESSAGE_WINDOW_SECS` 時停用
+    pub duplicate_filter: Option<DuplicateFilterConfig>,
+    /// 依使用者狀態切換的 rich menu，未設定 `RICH_MENU_STATES` 時停用
+    pub rich_menu_states: Option<RichMenuStateConfig>,
+    /// LINE API 用戶端與各整合共用的 HTTP 連線池
+    pub http: HttpClientConfig,
+    /// 內建回覆的預設語系與各語系時區
+    pub i18n: I18nConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            channel_access_token: String::new(),
+            channel_secret: String::new(),
+            secondary_channel_secrets: Vec::new(),
+            port: 3000,
+            host: "0.0.0.0".to_string(),
+            admin_token: None,
+            grpc_bind: None,
+            line_login: None,
+            forward_targets: Vec::new(),
+            forward_secret: None,
+            forward_max_retries: 3,
+            sentry_dsn: None,
+            compression_enabled: true,
+            request_decompression_enabled: true,
+            line_api_proxy: None,
+            line_api_base_url: None,
+            line_data_api_base_url: None,
+            dry_run: false,
+            signature_require_prefix: false,
+            verify_destination: false,
+            bot_user_id: None,
+            insecure_skip_signature: false,
+            insecure_skip_signature_force: false,
+            webhook_max_body_bytes: 1024 * 1024,
+            webhook_queue: None,
+            webhook_recording: None,
+            #[cfg(feature = "server")]
+            rate_limit: None,
+            offline_buffer_path: None,
+            offline_buffer_max_age_secs: 3600,
+            storage_url: None,
+            conversation_log_enabled: false,
+            conversation_log_masking: ConversationMasking::default(),
+            audit_log_enabled: false,
+            media_store: None,
+            media_max_bytes: DEFAULT_MAX_MEDIA_BYTES,
+            group_cache_ttl_secs: 600,
+            purge_on_unfollow: false,
+            metrics_exporter: None,
+            statsd_exporter: None,
+            otlp_exporter: None,
+            quota_poll_interval_secs: None,
+            quota_warning_percent: 80,
+            quota_alert_user_ids: Vec::new(),
+            forbidden_words_path: None,
+            reply_templates_dir: None,
+            reply_scripts_dir: None,
+            feature_flags_path: None,
+            llm: None,
+            nlu: None,
+            translation: None,
+            feed_sources: Vec::new(),
+            feed_poll_interval_secs: 900,
+            operator_bridge: None,
+            line_pay: None,
+            onboarding: None,
+            quiz: None,
+            event_sinks: None,
+            campaigns: Vec::new(),
+            outgoing_url_allowed_hosts: Vec::new(),
+            moderation_policy: ModerationPolicy::default(),
+            moderation_alert_user_ids: Vec::new(),
+            moderation_report_group_id: None,
+            moderation_report_daily_limit: 20,
+            group_admin_user_ids: Vec::new(),
+            duplicate_filter: None,
+            rich_menu_states: None,
+            http: HttpClientConfig::default(),
+            i18n: I18nConfig::default(),
+        }
+    }
 }
 
 impl Config {
@@ -18,6 +243,16 @@ impl Config {
 
         let channel_secret = env::var("CHANNEL_SECRET")
             .map_err(|_| "CHANNEL_SECRET environment variable is required")?;
+        let secondary_channel_secrets = env::var("SECONDARY_CHANNEL_SECRETS")
+            .map(|secrets| {
+                secrets
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|secret| !secret.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
 
         let port = env::var("PORT")
             .unwrap_or_else(|_| "3000".to_string())
@@ -26,12 +261,875 @@ impl Config {
 
         let host = env::var("HOST").unwrap_or_else(|_| "0.0.0.0".to_string());
 
+        let admin_token = env::var("ADMIN_TOKEN")
+            .ok()
+            .filter(|token| !token.is_empty());
+
+        let grpc_bind = env::var("GRPC_BIND")
+            .ok()
+            .map(|bind| bind.parse())
+            .transpose()
+            .map_err(|_| "GRPC_BIND must be a socket address such as 0.0.0.0:50051")?;
+
+        // 以 `;` 分隔多個目標，每個目標為 `url` 或 `url|message,follow`
+        let forward_targets = env::var("WEBHOOK_FORWARD_TARGETS")
+            .map(|targets| {
+                targets
+                    .split(';')
+                    .filter_map(ForwardTarget::parse)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let forward_secret = env::var("WEBHOOK_FORWARD_SECRET")
+            .ok()
+            .filter(|secret| !secret.is_empty());
+
+        let forward_max_retries = env::var("WEBHOOK_FORWARD_MAX_RETRIES")
+            .unwrap_or_else(|_| "3".to_string())
+            .parse::<u32>()
+            .map_err(|_| "WEBHOOK_FORWARD_MAX_RETRIES must be a valid number")?;
+
+        let sentry_dsn = env::var("SENTRY_DSN").ok().filter(|dsn| !dsn.is_empty());
+
+        let compression_enabled = parse_bool_env("COMPRESSION_ENABLED", true)?;
+        let request_decompression_enabled = parse_bool_env("REQUEST_DECOMPRESSION_ENABLED", true)?;
+
+        let line_api_proxy = env::var("LINE_API_PROXY")
+            .ok()
+            .filter(|url| !url.is_empty())
+            .map(|url| {
+                reqwest::Url::parse(&url).map_err(|_| "LINE_API_PROXY must be a valid URL")?;
+                Ok::<_, &str>(url)
+            })
+            .transpose()?
+            .map(|url| ProxyConfig {
+                url,
+                username: env::var("LINE_API_PROXY_USERNAME").ok(),
+                password: env::var("LINE_API_PROXY_PASSWORD").ok(),
+            });
+
+        let line_api_base_url = env::var("LINE_API_BASE_URL")
+            .ok()
+            .filter(|url| !url.is_empty());
+        let line_data_api_base_url = env::var("LINE_DATA_API_BASE_URL")
+            .ok()
+            .filter(|url| !url.is_empty());
+
+        let dry_run = parse_bool_env("DRY_RUN", false)?;
+        let signature_require_prefix = parse_bool_env("SIGNATURE_REQUIRE_PREFIX", false)?;
+        let verify_destination = parse_bool_env("VERIFY_DESTINATION", false)?;
+        let bot_user_id = env::var("BOT_USER_ID").ok().filter(|id| !id.is_empty());
+        let insecure_skip_signature = parse_bool_env("LINEBOT_INSECURE_SKIP_SIGNATURE", false)?;
+        let insecure_skip_signature_force =
+            parse_bool_env("LINEBOT_INSECURE_SKIP_SIGNATURE_FORCE", false)?;
+        let webhook_max_body_bytes = env::var("WEBHOOK_MAX_BODY_BYTES")
+            .unwrap_or_else(|_| "1048576".to_string())
+            .parse::<usize>()
+            .map_err(|_| "WEBHOOK_MAX_BODY_BYTES must be a valid number")?;
+
+        let offline_buffer_path = env::var("OFFLINE_BUFFER_PATH")
+            .ok()
+            .filter(|path| !path.is_empty());
+        let offline_buffer_max_age_secs = env::var("OFFLINE_BUFFER_MAX_AGE_SECS")
+            .unwrap_or_else(|_| "3600".to_string())
+            .parse::<u64>()
+            .map_err(|_| "OFFLINE_BUFFER_MAX_AGE_SECS must be a valid number")?;
+
+        let storage_url = env::var("STORAGE_URL").ok().filter(|url| !url.is_empty());
+        let conversation_log_enabled = parse_bool_env("CONVERSATION_LOG_ENABLED", false)?;
+        let conversation_log_masking = match env::var("CONVERSATION_LOG_MASKING") {
+            Ok(value) => ConversationMasking::parse(&value)
+                .ok_or("CONVERSATION_LOG_MASKING must be one of: none, pii, full")?,
+            Err(_) => ConversationMasking::default(),
+        };
+        let audit_log_enabled = parse_bool_env("AUDIT_LOG_ENABLED", false)?;
+
+        let group_cache_ttl_secs = env::var("GROUP_CACHE_TTL_SECS")
+            .unwrap_or_else(|_| "600".to_string())
+            .parse::<u64>()
+            .map_err(|_| "GROUP_CACHE_TTL_SECS must be a valid number")?;
+
+        let quota_poll_interval_secs = env::var("QUOTA_POLL_INTERVAL_SECS")
+            .ok()
+            .map(|secs| {
+                secs.parse::<u64>()
+                    .ok()
+                    .filter(|secs| *secs > 0)
+                    .ok_or("QUOTA_POLL_INTERVAL_SECS must be a positive number")
+            })
+            .transpose()?;
+        let quota_warning_percent = env::var("QUOTA_WARNING_PERCENT")
+            .unwrap_or_else(|_| "80".to_string())
+            .parse::<u8>()
+            .ok()
+            .filter(|percent| (1..=100).contains(percent))
+            .ok_or("QUOTA_WARNING_PERCENT must be between 1 and 100")?;
+        let quota_alert_user_ids = env::var("QUOTA_ALERT_USER_IDS")
+            .map(|ids| {
+                ids.split(',')
+                    .map(str::trim)
+                    .filter(|id| !id.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let outgoing_url_allowed_hosts = env::var("OUTGOING_URL_ALLOWED_HOSTS")
+            .map(|hosts| {
+                hosts
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|host| !host.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let feed_sources = env::var("FEED_SOURCES")
+            .map(|sources| sources.split(';').filter_map(FeedSource::parse).collect())
+            .unwrap_or_default();
+        let feed_poll_interval_secs = env::var("FEED_POLL_INTERVAL_SECS")
+            .unwrap_or_else(|_| "900".to_string())
+            .parse::<u64>()
+            .ok()
+            .filter(|&secs| secs > 0)
+            .ok_or("FEED_POLL_INTERVAL_SECS must be a positive number")?;
+
+        let onboarding = match env::var("ONBOARDING_FILE") {
+            Ok(path) if !path.is_empty() => Some(
+                load_onboarding(&path)
+                    .map_err(|e| format!("ONBOARDING_FILE {} is invalid: {}", path, e))?,
+            ),
+            _ => None,
+        };
+
+        let campaigns = match env::var("CAMPAIGNS_FILE") {
+            Ok(path) if !path.is_empty() => load_campaigns(&path)
+                .map_err(|e| format!("CAMPAIGNS_FILE {} is invalid: {}", path, e))?,
+            _ => Vec::new(),
+        };
+
+        let moderation_policy = match env::var("MODERATION_POLICY") {
+            Ok(spec) => ModerationPolicy::parse(&spec)
+                .map_err(|e| format!("MODERATION_POLICY is invalid: {}", e))?,
+            Err(_) => ModerationPolicy::default(),
+        };
+        let moderation_alert_user_ids = env::var("MODERATION_ALERT_USER_IDS")
+            .map(|ids| {
+                ids.split(',')
+                    .map(str::trim)
+                    .filter(|id| !id.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let media_max_bytes = env::var("MEDIA_MAX_BYTES")
+            .map_or(Ok(DEFAULT_MAX_MEDIA_BYTES), |value| value.parse::<usize>())
+            .ok()
+            .filter(|&max| max > 0)
+            .ok_or("MEDIA_MAX_BYTES must be a positive number")?;
+        let moderation_report_daily_limit = env::var("MODERATION_REPORT_DAILY_LIMIT")
+            .unwrap_or_else(|_| "20".to_string())
+            .parse::<u32>()
+            .map_err(|_| "MODERATION_REPORT_DAILY_LIMIT must be a number")?;
+        let group_admin_user_ids = env::var("GROUP_ADMIN_USER_IDS")
+            .map(|ids| {
+                ids.split(',')
+                    .map(str::trim)
+                    .filter(|id| !id.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+
         Ok(Config {
             channel_access_token,
             channel_secret,
+            secondary_channel_secrets,
             port,
             host,
+            admin_token,
+            grpc_bind,
+            line_login: line_login_from_env()?,
+            forward_targets,
+            forward_secret,
+            forward_max_retries,
+            sentry_dsn,
+            compression_enabled,
+            request_decompression_enabled,
+            line_api_proxy,
+            line_api_base_url,
+            line_data_api_base_url,
+            dry_run,
+            signature_require_prefix,
+            verify_destination,
+            bot_user_id,
+            insecure_skip_signature,
+            insecure_skip_signature_force,
+            webhook_max_body_bytes,
+            webhook_queue: webhook_queue_from_env()?,
+            webhook_recording: webhook_recording_from_env()?,
+            #[cfg(feature = "server")]
+            rate_limit: rate_limit_from_env()?,
+            offline_buffer_path,
+            offline_buffer_max_age_secs,
+            storage_url,
+            conversation_log_enabled,
+            conversation_log_masking,
+            audit_log_enabled,
+            media_store: media_store_from_env()?,
+            media_max_bytes,
+            group_cache_ttl_secs,
+            purge_on_unfollow: parse_bool_env("PURGE_ON_UNFOLLOW", false)?,
+            metrics_exporter: metrics_exporter_from_env()?,
+            statsd_exporter: statsd_exporter_from_env()?,
+            otlp_exporter: otlp_exporter_from_env()?,
+            quota_poll_interval_secs,
+            quota_warning_percent,
+            quota_alert_user_ids,
+            forbidden_words_path: env::var("FORBIDDEN_WORDS_PATH")
+                .ok()
+                .filter(|path| !path.is_empty()),
+            reply_templates_dir: env::var("REPLY_TEMPLATES_DIR")
+                .ok()
+                .filter(|dir| !dir.is_empty()),
+            reply_scripts_dir: env::var("REPLY_SCRIPTS_DIR")
+                .ok()
+                .filter(|dir| !dir.is_empty()),
+            feature_flags_path: env::var("FEATURE_FLAGS_FILE")
+                .ok()
+                .filter(|path| !path.is_empty()),
+            llm: llm_from_env()?,
+            nlu: nlu_from_env()?,
+            translation: translation_from_env()?,
+            feed_sources,
+            feed_poll_interval_secs,
+            operator_bridge: operator_bridge_from_env()?,
+            line_pay: line_pay_from_env()?,
+            onboarding,
+            quiz: quiz_from_env()?,
+            event_sinks: event_sinks_from_env()?,
+            campaigns,
+            outgoing_url_allowed_hosts,
+            moderation_policy,
+            moderation_alert_user_ids,
+            moderation_report_group_id: env::var("MODERATION_REPORT_GROUP_ID")
+                .ok()
+                .filter(|id| !id.is_empty()),
+            moderation_report_daily_limit,
+            group_admin_user_ids,
+            duplicate_filter: duplicate_filter_from_env()?,
+            rich_menu_states: rich_menu_states_from_env()?,
+            http: http_client_from_env()?,
+            i18n: i18n_from_env()?,
+        })
+    }
+}
+
+/// `MEDIA_S3_BUCKET` 優先於 `MEDIA_STORE_DIR`
+fn media_store_from_env() -> Result<Option<MediaStoreConfig>, Box<dyn std::error::Error>> {
+    let public_base_url = env::var("MEDIA_PUBLIC_BASE_URL").ok();
+
+    if let Ok(bucket) = env::var("MEDIA_S3_BUCKET") {
+        let required = |name: &str| {
+            env::var(name).map_err(|_| format!("{} is required when MEDIA_S3_BUCKET is set", name))
+        };
+        return Ok(Some(MediaStoreConfig::S3(S3MediaConfig {
+            endpoint: required("MEDIA_S3_ENDPOINT")?,
+            bucket,
+            region: env::var("MEDIA_S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+            access_key: required("MEDIA_S3_ACCESS_KEY")?,
+            secret_key: required("MEDIA_S3_SECRET_KEY")?,
+            public_base_url,
+        })));
+    }
+
+    Ok(env::var("MEDIA_STORE_DIR").ok().map(|dir| {
+        MediaStoreConfig::Local(LocalMediaConfig {
+            dir: dir.into(),
+            public_base_url,
+        })
+    }))
+}
+
+/// `METRICS_AUTH_TOKEN`（Bearer）優先於 `METRICS_BASIC_AUTH`（`user:password`）
+fn metrics_exporter_from_env() -> Result<Option<MetricsExporterConfig>, Box<dyn std::error::Error>>
+{
+    let Ok(bind) = env::var("METRICS_BIND") else {
+        return Ok(None);
+    };
+    let bind = bind
+        .parse()
+        .map_err(|_| "METRICS_BIND must be a socket address such as 127.0.0.1:9090")?;
+
+    let auth = if let Ok(token) = env::var("METRICS_AUTH_TOKEN") {
+        Some(MetricsAuth::Bearer(token))
+    } else if let Ok(credentials) = env::var("METRICS_BASIC_AUTH") {
+        let (username, password) = credentials
+            .split_once(':')
+            .ok_or("METRICS_BASIC_AUTH must be in the form user:password")?;
+        Some(MetricsAuth::Basic {
+            username: username.to_string(),
+            password: password.to_string(),
         })
+    } else {
+        None
+    };
+
+    let prefix = env::var("METRICS_PREFIX")
+        .ok()
+        .filter(|prefix| !prefix.is_empty());
+    let histogram_buckets = env::var("METRICS_HISTOGRAM_BUCKETS")
+        .ok()
+        .map(|buckets| parse_buckets(&buckets))
+        .transpose()?;
+
+    Ok(Some(MetricsExporterConfig {
+        bind,
+        auth,
+        prefix,
+        histogram_buckets,
+    }))
+}
+
+/// 解析以逗號分隔、遞增的 bucket 上界，例如 `0.01,0.05,0.1,0.5,1`
+fn parse_buckets(value: &str) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    let buckets = value
+        .split(',')
+        .map(|bucket| bucket.trim().parse::<f64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| "METRICS_HISTOGRAM_BUCKETS must be a comma-separated list of numbers")?;
+    if buckets.is_empty() || buckets.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err("METRICS_HISTOGRAM_BUCKETS must be in increasing order".into());
+    }
+    Ok(buckets)
+}
+
+fn statsd_exporter_from_env() -> Result<Option<StatsdExporterConfig>, Box<dyn std::error::Error>> {
+    let Ok(host) = env::var("STATSD_HOST") else {
+        return Ok(None);
+    };
+    let port = env::var("STATSD_PORT")
+        .unwrap_or_else(|_| "8125".to_string())
+        .parse::<u16>()
+        .map_err(|_| "STATSD_PORT must be a valid port number")?;
+
+    Ok(Some(StatsdExporterConfig {
+        host,
+        port,
+        prefix: env::var("STATSD_PREFIX")
+            .ok()
+            .filter(|prefix| !prefix.is_empty()),
+    }))
+}
+
+/// 沿用 OpenTelemetry SDK 的標準環境變數
+fn otlp_exporter_from_env() -> Result<Option<OtlpExporterConfig>, Box<dyn std::error::Error>> {
+    let Ok(endpoint) = env::var("OTEL_EXPORTER_OTLP_ENDPOINT") else {
+        return Ok(None);
+    };
+    let interval_ms = env::var("OTEL_METRIC_EXPORT_INTERVAL")
+        .unwrap_or_else(|_| "60000".to_string())
+        .parse::<u64>()
+        .map_err(|_| "OTEL_METRIC_EXPORT_INTERVAL must be a number of milliseconds")?;
+
+    Ok(Some(OtlpExporterConfig {
+        endpoint,
+        service_name: env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| "linebot-rs".to_string()),
+        interval: std::time::Duration::from_millis(interval_ms),
+    }))
+}
+
+fn llm_from_env() -> Result<Option<LlmConfig>, Box<dyn std::error::Error>> {
+    let Some(model) = env::var("LLM_MODEL").ok().filter(|model| !model.is_empty()) else {
+        return Ok(None);
+    };
+    let mut config = LlmConfig::new(model);
+    if let Ok(base_url) = env::var("LLM_BASE_URL") {
+        reqwest::Url::parse(&base_url).map_err(|_| "LLM_BASE_URL must be a valid URL")?;
+        config.base_url = base_url;
+    }
+    config.api_key = env::var("LLM_API_KEY").ok().filter(|key| !key.is_empty());
+    config.system_prompt = env::var("LLM_SYSTEM_PROMPT")
+        .ok()
+        .filter(|prompt| !prompt.is_empty());
+    if let Ok(max_tokens) = env::var("LLM_MAX_TOKENS") {
+        config.max_tokens = max_tokens
+            .parse()
+            .map_err(|_| "LLM_MAX_TOKENS must be a valid number")?;
+    }
+    config.user_daily_token_budget = env::var("LLM_USER_DAILY_TOKENS")
+        .ok()
+        .map(|budget| budget.parse::<u64>())
+        .transpose()
+        .map_err(|_| "LLM_USER_DAILY_TOKENS must be a valid number")?;
+
+    Ok(Some(config))
+}
+
+/// `RASA_URL` 與 `DIALOGFLOW_CX_AGENT` 擇一設定
+fn nlu_from_env() -> Result<Option<NluConfig>, Box<dyn std::error::Error>> {
+    let rasa_url = env::var("RASA_URL").ok().filter(|url| !url.is_empty());
+    let dialogflow_agent = env::var("DIALOGFLOW_CX_AGENT")
+        .ok()
+        .filter(|agent| !agent.is_empty());
+    let provider = match (rasa_url, dialogflow_agent) {
+        (None, None) => return Ok(None),
+        (Some(_), Some(_)) => {
+            return Err("Only one of RASA_URL and DIALOGFLOW_CX_AGENT can be set".into());
+        }
+        (Some(url), None) => NluProvider::Rasa {
+            url,
+            token: env::var("RASA_TOKEN")
+                .ok()
+                .filter(|token| !token.is_empty()),
+        },
+        (None, Some(agent)) => {
+            let parts: Vec<&str> = agent.split('/').collect();
+            if !matches!(
+                parts.as_slice(),
+                ["projects", _, "locations", _, "agents", _]
+            ) {
+                return Err(
+                    "DIALOGFLOW_CX_AGENT must be projects/<project>/locations/<location>/agents/<agent>"
+                        .into(),
+                );
+            }
+            NluProvider::DialogflowCx {
+                agent,
+                language_code: env::var("DIALOGFLOW_CX_LANGUAGE")
+                    .unwrap_or_else(|_| "zh-TW".to_string()),
+                access_token: env::var("DIALOGFLOW_ACCESS_TOKEN")
+                    .ok()
+                    .filter(|token| !token.is_empty()),
+            }
+        }
+    };
+
+    let mut config = NluConfig::new(provider);
+    if let Ok(min_confidence) = env::var("NLU_MIN_CONFIDENCE") {
+        config.min_confidence = min_confidence
+            .parse()
+            .map_err(|_| "NLU_MIN_CONFIDENCE must be a number between 0 and 1")?;
+    }
+    // 以 `,` 分隔，每項為 `意圖=指令`
+    if let Ok(mappings) = env::var("NLU_INTENT_COMMANDS") {
+        for mapping in mappings.split(',').map(str::trim).filter(|m| !m.is_empty()) {
+            let (intent, command) = mapping
+                .split_once('=')
+                .ok_or("NLU_INTENT_COMMANDS entries must be intent=command")?;
+            config
+                .intent_commands
+                .insert(intent.trim().to_string(), command.trim().to_string());
+        }
+    }
+
+    Ok(Some(config))
+}
+
+/// `GOOGLE_TRANSLATE_API_KEY` 與 `LIBRETRANSLATE_URL` 擇一設定
+fn translation_from_env() -> Result<Option<TranslationConfig>, Box<dyn std::error::Error>> {
+    let google_api_key = env::var("GOOGLE_TRANSLATE_API_KEY")
+        .ok()
+        .filter(|key| !key.is_empty());
+    let libre_url = env::var("LIBRETRANSLATE_URL")
+        .ok()
+        .filter(|url| !url.is_empty());
+    let provider = match (google_api_key, libre_url) {
+        (None, None) => return Ok(None),
+        (Some(_), Some(_)) => {
+            return Err(
+                "Only one of GOOGLE_TRANSLATE_API_KEY and LIBRETRANSLATE_URL can be set".into(),
+            );
+        }
+        (Some(api_key), None) => TranslationProvider::Google { api_key },
+        (None, Some(url)) => TranslationProvider::Libre {
+            url,
+            api_key: env::var("LIBRETRANSLATE_API_KEY")
+                .ok()
+                .filter(|key| !key.is_empty()),
+        },
+    };
+
+    Ok(Some(TranslationConfig {
+        provider,
+        working_language: env::var("BOT_LANGUAGE").unwrap_or_else(|_| "zh-TW".to_string()),
+    }))
+}
+
+fn operator_bridge_from_env() -> Result<Option<BridgeConfig>, Box<dyn std::error::Error>> {
+    let Some(webhook_url) = env::var("OPERATOR_WEBHOOK_URL")
+        .ok()
+        .filter(|url| !url.is_empty())
+    else {
+        return Ok(None);
+    };
+    let platform = match env::var("OPERATOR_BRIDGE_PLATFORM") {
+        Ok(value) => BridgePlatform::parse(&value)
+            .ok_or("OPERATOR_BRIDGE_PLATFORM must be one of: slack, discord")?,
+        Err(_) => BridgePlatform::detect(&webhook_url).ok_or(
+            "Cannot detect the platform of OPERATOR_WEBHOOK_URL, set OPERATOR_BRIDGE_PLATFORM",
+        )?,
+    };
+
+    let mut config = BridgeConfig::new(platform, webhook_url);
+    config.notify_errors = parse_bool_env("OPERATOR_NOTIFY_ERRORS", true)?;
+    config.notify_follows = parse_bool_env("OPERATOR_NOTIFY_FOLLOWS", true)?;
+    config.keywords = env::var("OPERATOR_KEYWORDS")
+        .map(|keywords| {
+            keywords
+                .split(',')
+                .map(str::trim)
+                .filter(|keyword| !keyword.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    config.slack_signing_secret = env::var("SLACK_SIGNING_SECRET")
+        .ok()
+        .filter(|secret| !secret.is_empty());
+    config.discord_public_key = env::var("DISCORD_PUBLIC_KEY")
+        .ok()
+        .filter(|key| !key.is_empty());
+    Ok(Some(config))
+}
+
+fn line_login_from_env() -> Result<Option<LineLoginConfig>, Box<dyn std::error::Error>> {
+    let Some(channel_id) = env::var("LINE_LOGIN_CHANNEL_ID")
+        .ok()
+        .filter(|id| !id.is_empty())
+    else {
+        return Ok(None);
+    };
+    let mut config = LineLoginConfig::new(channel_id);
+    config.channel_secret = env::var("LINE_LOGIN_CHANNEL_SECRET")
+        .ok()
+        .filter(|secret| !secret.is_empty());
+    config.redirect_uri = env::var("LINE_LOGIN_REDIRECT_URI")
+        .ok()
+        .filter(|uri| !uri.is_empty());
+    if let Some(uri) = &config.redirect_uri {
+        reqwest::Url::parse(uri).map_err(|_| "LINE_LOGIN_REDIRECT_URI must be a valid URL")?;
+    }
+    if config.channel_secret.is_some() != config.redirect_uri.is_some() {
+        return Err(
+            "LINE_LOGIN_CHANNEL_SECRET and LINE_LOGIN_REDIRECT_URI must be set together".into(),
+        );
+    }
+    if let Ok(scope) = env::var("LINE_LOGIN_SCOPE") {
+        config.scope = scope;
+    }
+    if let Ok(url) = env::var("LINE_LOGIN_SUCCESS_URL") {
+        config.success_url = url;
+    }
+    Ok(Some(config))
+}
+
+fn quiz_from_env() -> Result<Option<QuizConfig>, Box<dyn std::error::Error>> {
+    let Some(questions_path) = env::var("QUIZ_QUESTIONS_PATH")
+        .ok()
+        .filter(|path| !path.is_empty())
+    else {
+        return Ok(None);
+    };
+    let round_secs = env::var("QUIZ_ROUND_SECS")
+        .unwrap_or_else(|_| "30".to_string())
+        .parse::<u64>()
+        .ok()
+        .filter(|&secs| secs > 0)
+        .ok_or("QUIZ_ROUND_SECS must be a positive number")?;
+    let questions_per_game = env::var("QUIZ_QUESTIONS_PER_GAME")
+        .unwrap_or_else(|_| "5".to_string())
+        .parse::<usize>()
+        .ok()
+        .filter(|&count| count > 0)
+        .ok_or("QUIZ_QUESTIONS_PER_GAME must be a positive number")?;
+    Ok(Some(QuizConfig {
+        questions_path,
+        round_secs,
+        questions_per_game,
+    }))
+}
+
+fn duplicate_filter_from_env() -> Result<Option<DuplicateFilterConfig>, Box<dyn std::error::Error>>
+{
+    let Ok(window_secs) = env::var("DUPLICATE_MESSAGE_WINDOW_SECS") else {
+        return Ok(None);
+    };
+    let window_secs = window_secs
+        .parse::<u64>()
+        .ok()
+        .filter(|&secs| secs > 0)
+        .ok_or("DUPLICATE_MESSAGE_WINDOW_SECS must be a positive number")?;
+    let max_repeats = env::var("DUPLICATE_MESSAGE_MAX_REPEATS")
+        .unwrap_or_else(|_| "2".to_string())
+        .parse::<u32>()
+        .ok()
+        .filter(|&count| count > 0)
+        .ok_or("DUPLICATE_MESSAGE_MAX_REPEATS must be a positive number")?;
+    let mut config = DuplicateFilterConfig {
+        window_secs,
+        max_repeats,
+        ..Default::default()
+    };
+    if let Ok(notice) = env::var("DUPLICATE_MESSAGE_NOTICE")
+        && !notice.is_empty()
+    {
+        config.notice = notice;
+    }
+    Ok(Some(config))
+}
+
+fn rich_menu_states_from_env() -> Result<Option<RichMenuStateConfig>, Box<dyn std::error::Error>> {
+    let Some(states) = env::var("RICH_MENU_STATES")
+        .ok()
+        .filter(|states| !states.trim().is_empty())
+    else {
+        return Ok(None);
+    };
+    let states = states
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            entry
+                .split_once('=')
+                .map(|(state, alias)| (state.trim().to_string(), alias.trim().to_string()))
+                .filter(|(state, alias)| !state.is_empty() && !alias.is_empty())
+                .ok_or("RICH_MENU_STATES must be a comma-separated list of state=alias")
+        })
+        .collect::<Result<_, _>>()?;
+    let fallback_alias = env::var("RICH_MENU_FALLBACK_ALIAS")
+        .ok()
+        .filter(|alias| !alias.is_empty());
+    let batch_interval_secs = env::var("RICH_MENU_BATCH_INTERVAL_SECS")
+        .unwrap_or_else(|_| "5".to_string())
+        .parse::<u64>()
+        .ok()
+        .filter(|&secs| secs > 0)
+        .ok_or("RICH_MENU_BATCH_INTERVAL_SECS must be a positive number")?;
+    Ok(Some(RichMenuStateConfig {
+        states,
+        fallback_alias,
+        batch_interval_secs,
+    }))
+}
+
+fn line_pay_from_env() -> Result<Option<LinePayConfig>, Box<dyn std::error::Error>> {
+    let Some(channel_id) = env::var("LINE_PAY_CHANNEL_ID")
+        .ok()
+        .filter(|id| !id.is_empty())
+    else {
+        return Ok(None);
+    };
+    let channel_secret = env::var("LINE_PAY_CHANNEL_SECRET")
+        .map_err(|_| "LINE_PAY_CHANNEL_SECRET is required when LINE_PAY_CHANNEL_ID is set")?;
+    let confirm_url = env::var("LINE_PAY_CONFIRM_URL")
+        .map_err(|_| "LINE_PAY_CONFIRM_URL is required when LINE_PAY_CHANNEL_ID is set")?;
+
+    Ok(Some(LinePayConfig {
+        channel_id,
+        channel_secret,
+        sandbox: parse_bool_env("LINE_PAY_SANDBOX", false)?,
+        confirm_url,
+        cancel_url: env::var("LINE_PAY_CANCEL_URL").ok(),
+        currency: env::var("LINE_PAY_CURRENCY").unwrap_or_else(|_| "TWD".to_string()),
+    }))
+}
+
+fn event_sinks_from_env() -> Result<Option<EventSinkConfig>, Box<dyn std::error::Error>> {
+    let csv = match env::var("EVENT_SINK_CSV_DIR") {
+        Ok(dir) if !dir.is_empty() => Some(CsvSinkConfig {
+            dir,
+            rotation: match env::var("EVENT_SINK_CSV_ROTATION") {
+                Ok(value) => CsvRotation::parse(&value)
+                    .ok_or("EVENT_SINK_CSV_ROTATION must be one of: daily, hourly")?,
+                Err(_) => CsvRotation::default(),
+            },
+            max_files: env::var("EVENT_SINK_CSV_MAX_FILES")
+                .ok()
+                .map(|count| {
+                    count
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|&count| count > 0)
+                        .ok_or("EVENT_SINK_CSV_MAX_FILES must be a positive number")
+                })
+                .transpose()?,
+        }),
+        _ => None,
+    };
+    let google_sheets = match env::var("GOOGLE_SHEETS_SPREADSHEET_ID") {
+        Ok(spreadsheet_id) if !spreadsheet_id.is_empty() => Some(GoogleSheetsConfig {
+            spreadsheet_id,
+            range: env::var("GOOGLE_SHEETS_RANGE").unwrap_or_else(|_| "Events!A:G".to_string()),
+            access_token: env::var("GOOGLE_SHEETS_ACCESS_TOKEN")
+                .ok()
+                .filter(|token| !token.is_empty()),
+        }),
+        _ => None,
+    };
+    if csv.is_none() && google_sheets.is_none() {
+        return Ok(None);
+    }
+
+    Ok(Some(EventSinkConfig {
+        csv,
+        google_sheets,
+        masking: match env::var("EVENT_SINK_MASKING") {
+            Ok(value) => ConversationMasking::parse(&value)
+                .ok_or("EVENT_SINK_MASKING must be one of: none, pii, full")?,
+            Err(_) => ConversationMasking::default(),
+        },
+        flush_interval_secs: env::var("EVENT_SINK_FLUSH_INTERVAL_SECS")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse::<u64>()
+            .ok()
+            .filter(|&secs| secs > 0)
+            .ok_or("EVENT_SINK_FLUSH_INTERVAL_SECS must be a positive number")?,
+    }))
+}
+
+fn webhook_queue_from_env() -> Result<Option<WebhookQueueConfig>, Box<dyn std::error::Error>> {
+    let Ok(capacity) = env::var("WEBHOOK_QUEUE_CAPACITY") else {
+        return Ok(None);
+    };
+    let capacity = capacity
+        .parse::<usize>()
+        .ok()
+        .filter(|&capacity| capacity > 0)
+        .ok_or("WEBHOOK_QUEUE_CAPACITY must be a positive number")?;
+    let workers = env::var("WEBHOOK_WORKERS")
+        .unwrap_or_else(|_| "4".to_string())
+        .parse::<usize>()
+        .ok()
+        .filter(|&workers| workers > 0)
+        .ok_or("WEBHOOK_WORKERS must be a positive number")?;
+    let overflow = match env::var("WEBHOOK_QUEUE_OVERFLOW")
+        .unwrap_or_else(|_| "block".to_string())
+        .to_lowercase()
+        .as_str()
+    {
+        "block" => OverflowPolicy::Block,
+        "shed" => OverflowPolicy::Shed,
+        "spill" => OverflowPolicy::Spill,
+        _ => return Err("WEBHOOK_QUEUE_OVERFLOW must be block, shed or spill".into()),
+    };
+    let block_timeout_ms = env::var("WEBHOOK_QUEUE_BLOCK_MS")
+        .unwrap_or_else(|_| "200".to_string())
+        .parse::<u64>()
+        .map_err(|_| "WEBHOOK_QUEUE_BLOCK_MS must be a valid number")?;
+    Ok(Some(WebhookQueueConfig {
+        capacity,
+        workers,
+        overflow,
+        block_timeout_ms,
+    }))
+}
+
+#[cfg(feature = "server")]
+fn rate_limit_from_env() -> Result<Option<RateLimitConfig>, Box<dyn std::error::Error>> {
+    let Ok(max_requests) = env::var("RATE_LIMIT_MAX_REQUESTS") else {
+        return Ok(None);
+    };
+    let max_requests = max_requests
+        .parse::<u32>()
+        .ok()
+        .filter(|&max_requests| max_requests > 0)
+        .ok_or("RATE_LIMIT_MAX_REQUESTS must be a positive number")?;
+    let window_secs = env::var("RATE_LIMIT_WINDOW_SECS")
+        .unwrap_or_else(|_| "60".to_string())
+        .parse::<u64>()
+        .ok()
+        .filter(|&secs| secs > 0)
+        .ok_or("RATE_LIMIT_WINDOW_SECS must be a positive number")?;
+    let max_entries = env::var("RATE_LIMIT_MAX_ENTRIES")
+        .unwrap_or_else(|_| "10000".to_string())
+        .parse::<usize>()
+        .ok()
+        .filter(|&entries| entries > 0)
+        .ok_or("RATE_LIMIT_MAX_ENTRIES must be a positive number")?;
+    Ok(Some(RateLimitConfig {
+        max_requests,
+        window_duration: std::time::Duration::from_secs(window_secs),
+        max_entries,
+        ietf_headers: parse_bool_env("RATE_LIMIT_IETF_HEADERS", false)?,
+        ..RateLimitConfig::default()
+    }))
+}
+
+fn webhook_recording_from_env() -> Result<Option<WebhookRecorderConfig>, Box<dyn std::error::Error>>
+{
+    if !parse_bool_env("WEBHOOK_RECORDING_ENABLED", false)? {
+        return Ok(None);
+    }
+    let max_recordings = env::var("WEBHOOK_RECORDING_MAX")
+        .unwrap_or_else(|_| "200".to_string())
+        .parse::<usize>()
+        .ok()
+        .filter(|&max| max > 0)
+        .ok_or("WEBHOOK_RECORDING_MAX must be a positive number")?;
+    let masking = match env::var("WEBHOOK_RECORDING_MASKING") {
+        Ok(value) => ConversationMasking::parse(&value)
+            .ok_or("WEBHOOK_RECORDING_MASKING must be one of: none, pii, full")?,
+        Err(_) => ConversationMasking::default(),
+    };
+    Ok(Some(WebhookRecorderConfig {
+        max_recordings,
+        masking,
+    }))
+}
+
+fn i18n_from_env() -> Result<I18nConfig, Box<dyn std::error::Error>> {
+    let default_locale = match env::var("DEFAULT_LOCALE") {
+        Ok(value) => {
+            Locale::from_language(&value).ok_or("DEFAULT_LOCALE must be one of: zh-Hant, en, ja")?
+        }
+        Err(_) => Locale::ZhHant,
+    };
+    let utc_offsets = match env::var("LOCALE_UTC_OFFSETS") {
+        Ok(value) => I18nConfig::parse_utc_offsets(&value)?,
+        Err(_) => Default::default(),
+    };
+    Ok(I18nConfig {
+        default_locale,
+        utc_offsets,
+    })
+}
+
+fn http_client_from_env() -> Result<HttpClientConfig, Box<dyn std::error::Error>> {
+    let defaults = HttpClientConfig::default();
+    let parse = |name: &str, default: u64| -> Result<u64, Box<dyn std::error::Error>> {
+        match env::var(name) {
+            Ok(value) => Ok(value
+                .parse::<u64>()
+                .map_err(|_| format!("{} must be a valid number", name))?),
+            Err(_) => Ok(default),
+        }
+    };
+    Ok(HttpClientConfig {
+        pool_max_idle_per_host: parse(
+            "HTTP_POOL_MAX_IDLE_PER_HOST",
+            defaults.pool_max_idle_per_host as u64,
+        )? as usize,
+        pool_idle_timeout_secs: parse(
+            "HTTP_POOL_IDLE_TIMEOUT_SECS",
+            defaults.pool_idle_timeout_secs,
+        )?,
+        keepalive_secs: parse("HTTP_KEEPALIVE_SECS", defaults.keepalive_secs)?,
+        connect_timeout_secs: parse("HTTP_CONNECT_TIMEOUT_SECS", defaults.connect_timeout_secs)?,
+    })
+}
+
+fn parse_bool_env(name: &str, default: bool) -> Result<bool, Box<dyn std::error::Error>> {
+    match env::var(name) {
+        Ok(value) => match value.to_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => Ok(true),
+            "0" | "false" | "no" | "off" => Ok(false),
+            _ => Err(format!("{} must be a boolean", name).into()),
+        },
+        Err(_) => Ok(default),
     }
 }
 
@@ -54,4 +1152,11 @@ mod tests {
         assert_eq!(config.port, 8080);
         assert_eq!(config.host, "127.0.0.1");
     }
+
+    #[test]
+    fn test_parse_buckets() {
+        assert_eq!(parse_buckets("0.01, 0.1,1").unwrap(), vec![0.01, 0.1, 1.0]);
+        assert!(parse_buckets("0.1,0.01").is_err());
+        assert!(parse_buckets("fast").is_err());
+    }
 }
@@ -0,0 +1,266 @@
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+use dashmap::DashMap;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+#[cfg(feature = "server")]
+use tracing::{debug, warn};
+
+#[cfg(feature = "server")]
+use crate::models::{Event, OutgoingMessage};
+use crate::models::{MessageEvent, MessageType};
+#[cfg(feature = "server")]
+use crate::plugins::{EventMiddleware, MiddlewareAction};
+#[cfg(feature = "server")]
+use crate::webhook::server::AppState;
+
+/// 預設的提醒訊息
+pub const DEFAULT_DUPLICATE_NOTICE: &str = "您重複傳送了相同的訊息，請稍候片刻，我們會盡快處理。";
+
+/// 重複訊息過濾設定
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct DuplicateFilterConfig {
+    /// 與上一則相同的訊息在這段時間（秒）內送出才視為重複
+    pub window_secs: u64,
+    /// 連續相同的訊息最多處理幾則，之後的不再交給處理器
+    pub max_repeats: u32,
+    /// 第一次略過時回覆的提醒
+    pub notice: String,
+}
+
+impl Default for DuplicateFilterConfig {
+    fn default() -> Self {
+        Self {
+            window_secs: 30,
+            max_repeats: 2,
+            notice: DEFAULT_DUPLICATE_NOTICE.to_string(),
+        }
+    }
+}
+
+/// 過濾結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateVerdict {
+    /// 交給處理器
+    Allow,
+    /// 略過並回覆提醒（每輪重複只有一次）
+    Notify,
+    /// 直接略過
+    Suppress,
+}
+
+#[derive(Debug, Clone)]
+struct DuplicateEntry {
+    fingerprint: String,
+    count: u32,
+    last_seen: Instant,
+    notified: bool,
+}
+
+/// 偵測同一使用者在同一聊天室中連續送出的相同訊息
+///
+/// 只比對文字與貼圖；其他類型的訊息會重設計數。每則重複訊息都會延長時間窗，
+/// 使用者持續洗版時會一直略過，直到停止超過 `window_secs` 或改傳不同的內容。
+#[derive(Debug, Clone)]
+pub struct DuplicateFilter {
+    config: Arc<DuplicateFilterConfig>,
+    entries: Arc<DashMap<String, DuplicateEntry>>,
+}
+
+impl DuplicateFilter {
+    pub fn new(config: DuplicateFilterConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+            entries: Arc::new(DashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &DuplicateFilterConfig {
+        &self.config
+    }
+
+    /// 檢查訊息事件，沒有使用者 ID 的事件一律放行
+    pub fn check_event(&self, event: &MessageEvent) -> DuplicateVerdict {
+        let Some(user_id) = event.source.user_id() else {
+            return DuplicateVerdict::Allow;
+        };
+        let key = format!("{}:{}", event.source.chat_id(), user_id);
+        self.check(&key, fingerprint(&event.message).as_deref(), Instant::now())
+    }
+
+    /// `fingerprint` 為 `None` 時只重設計數
+    pub fn check(&self, key: &str, fingerprint: Option<&str>, now: Instant) -> DuplicateVerdict {
+        let Some(fingerprint) = fingerprint else {
+            self.entries.remove(key);
+            return DuplicateVerdict::Allow;
+        };
+        let window = Duration::from_secs(self.config.window_secs);
+        let mut entry = self
+            .entries
+            .entry(key.to_string())
+            .or_insert_with(|| DuplicateEntry {
+                fingerprint: String::new(),
+                count: 0,
+                last_seen: now,
+                notified: false,
+            });
+        if entry.fingerprint != fingerprint || now.duration_since(entry.last_seen) > window {
+            *entry = DuplicateEntry {
+                fingerprint: fingerprint.to_string(),
+                count: 0,
+                last_seen: now,
+                notified: false,
+            };
+        }
+        entry.count += 1;
+        entry.last_seen = now;
+        if entry.count <= self.config.max_repeats {
+            DuplicateVerdict::Allow
+        } else if entry.notified {
+            DuplicateVerdict::Suppress
+        } else {
+            entry.notified = true;
+            DuplicateVerdict::Notify
+        }
+    }
+
+    /// 移除超過時間窗的記錄
+    pub fn cleanup(&self, now: Instant) {
+        let window = Duration::from_secs(self.config.window_secs);
+        self.entries
+            .retain(|_, entry| now.duration_since(entry.last_seen) <= window);
+    }
+
+    /// 定期清除過期記錄
+    pub fn start_cleanup(&self, interval: Duration) {
+        let filter = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                filter.cleanup(Instant::now());
+            }
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// 在其他中介層與內建處理前略過重複訊息，第一次略過時回覆提醒
+#[cfg(feature = "server")]
+#[async_trait]
+impl EventMiddleware for DuplicateFilter {
+    async fn handle(&self, state: &AppState, event: &Event) -> MiddlewareAction {
+        let Event::Message(event) = event else {
+            return MiddlewareAction::Continue;
+        };
+        match self.check_event(event) {
+            DuplicateVerdict::Allow => MiddlewareAction::Continue,
+            DuplicateVerdict::Notify => {
+                debug!("Suppressing duplicate message, sending notice");
+                if let Err(e) = state
+                    .line_client
+                    .reply_message(
+                        &event.reply_token,
+                        vec![OutgoingMessage::text(&self.config.notice)],
+                    )
+                    .await
+                {
+                    warn!("Failed to reply duplicate message notice: {}", e);
+                }
+                MiddlewareAction::Handled
+            }
+            DuplicateVerdict::Suppress => {
+                debug!("Suppressing duplicate message");
+                MiddlewareAction::Handled
+            }
+        }
+    }
+}
+
+/// 用來比對是否相同的內容，不比對的訊息類型回傳 `None`
+fn fingerprint(message: &MessageType) -> Option<String> {
+    match message {
+        MessageType::Text { text } => Some(format!("text:{}", text.trim())),
+        MessageType::Sticker {
+            sticker_id,
+            package_id,
+        } => Some(format!("sticker:{}:{}", package_id, sticker_id)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duplicate_filter() {
+        let filter = DuplicateFilter::new(DuplicateFilterConfig {
+            window_secs: 10,
+            max_repeats: 2,
+            ..Default::default()
+        });
+        let now = Instant::now();
+        let at = |secs| now + Duration::from_secs(secs);
+
+        assert_eq!(
+            filter.check("U1", Some("text:hi"), at(0)),
+            DuplicateVerdict::Allow
+        );
+        assert_eq!(
+            filter.check("U1", Some("text:hi"), at(1)),
+            DuplicateVerdict::Allow
+        );
+        assert_eq!(
+            filter.check("U1", Some("text:hi"), at(2)),
+            DuplicateVerdict::Notify
+        );
+        assert_eq!(
+            filter.check("U1", Some("text:hi"), at(3)),
+            DuplicateVerdict::Suppress
+        );
+        // 其他使用者不受影響
+        assert_eq!(
+            filter.check("U2", Some("text:hi"), at(3)),
+            DuplicateVerdict::Allow
+        );
+        // 持續重複會延長時間窗
+        assert_eq!(
+            filter.check("U1", Some("text:hi"), at(12)),
+            DuplicateVerdict::Suppress
+        );
+        // 超過時間窗後重新計算
+        assert_eq!(
+            filter.check("U1", Some("text:hi"), at(30)),
+            DuplicateVerdict::Allow
+        );
+        // 不同內容或不比對的訊息重設計數
+        assert_eq!(
+            filter.check("U1", Some("text:hi"), at(31)),
+            DuplicateVerdict::Allow
+        );
+        assert_eq!(filter.check("U1", None, at(32)), DuplicateVerdict::Allow);
+        assert_eq!(
+            filter.check("U1", Some("text:hi"), at(33)),
+            DuplicateVerdict::Allow
+        );
+        assert_eq!(
+            filter.check("U1", Some("text:bye"), at(34)),
+            DuplicateVerdict::Allow
+        );
+
+        filter.cleanup(at(40));
+        assert_eq!(filter.len(), 1);
+        filter.cleanup(at(60));
+        assert!(filter.is_empty());
+    }
+}
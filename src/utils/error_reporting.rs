@@ -0,0 +1,132 @@
+use std::{fmt, sync::Arc};
+use tracing::error;
+
+/// 錯誤發生的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorSource {
+    EventProcessing,
+    LineApi,
+}
+
+impl fmt::Display for ErrorSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorSource::EventProcessing => write!(f, "event_processing"),
+            ErrorSource::LineApi => write!(f, "line_api"),
+        }
+    }
+}
+
+/// 回報錯誤時附帶的資訊
+#[derive(Debug, Clone)]
+pub struct ErrorContext {
+    pub source: ErrorSource,
+    /// 事件類型或 API 名稱
+    pub operation: String,
+}
+
+impl ErrorContext {
+    pub fn event(event_type: &str) -> Self {
+        Self {
+            source: ErrorSource::EventProcessing,
+            operation: event_type.to_string(),
+        }
+    }
+
+    pub fn line_api(api_type: &str) -> Self {
+        Self {
+            source: ErrorSource::LineApi,
+            operation: api_type.to_string(),
+        }
+    }
+}
+
+/// 錯誤回報介面
+///
+/// 事件處理或 LINE API 呼叫失敗時呼叫，讓錯誤能送到告警系統而不只是留在日誌裡。
+pub trait ErrorReporter: Send + Sync {
+    fn report(&self, error: &dyn std::error::Error, context: &ErrorContext);
+}
+
+/// 只寫入日誌的預設實作
+pub struct LogErrorReporter;
+
+impl ErrorReporter for LogErrorReporter {
+    fn report(&self, error: &dyn std::error::Error, context: &ErrorContext) {
+        error!("[{}:{}] {}", context.source, context.operation, error);
+    }
+}
+
+/// 依序交給多個回報器，例如同時送到 Sentry 與客服頻道
+pub struct CompositeErrorReporter(pub Vec<Arc<dyn ErrorReporter>>);
+
+impl ErrorReporter for CompositeErrorReporter {
+    fn report(&self, error: &dyn std::error::Error, context: &ErrorContext) {
+        for reporter in &self.0 {
+            reporter.report(error, context);
+        }
+    }
+}
+
+/// Sentry 錯誤回報
+#[cfg(feature = "sentry")]
+pub struct SentryErrorReporter {
+    _guard: sentry::ClientInitGuard,
+}
+
+#[cfg(feature = "sentry")]
+impl SentryErrorReporter {
+    pub fn new(dsn: &str) -> Self {
+        let guard = sentry::init((
+            dsn,
+            sentry::ClientOptions {
+                release: sentry::release_name!(),
+                ..Default::default()
+            },
+        ));
+        Self { _guard: guard }
+    }
+}
+
+#[cfg(feature = "sentry")]
+impl ErrorReporter for SentryErrorReporter {
+    fn report(&self, error: &dyn std::error::Error, context: &ErrorContext) {
+        sentry::with_scope(
+            |scope| {
+                scope.set_tag("source", context.source.to_string());
+                scope.set_tag("operation", &context.operation);
+            },
+            || sentry::capture_error(error),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    struct RecordingReporter(Mutex<Vec<String>>);
+
+    impl ErrorReporter for RecordingReporter {
+        fn report(&self, error: &dyn std::error::Error, context: &ErrorContext) {
+            self.0.lock().unwrap().push(format!(
+                "{}:{}:{}",
+                context.source, context.operation, error
+            ));
+        }
+    }
+
+    #[test]
+    fn test_reporter_receives_context() {
+        let reporter = RecordingReporter(Mutex::new(Vec::new()));
+        let error: Box<dyn std::error::Error> = "boom".into();
+
+        reporter.report(error.as_ref(), &ErrorContext::line_api("push"));
+        reporter.report(error.as_ref(), &ErrorContext::event("message"));
+
+        let reports = reporter.0.lock().unwrap();
+        assert_eq!(reports[0], "line_api:push:boom");
+        assert_eq!(reports[1], "event_processing:message:boom");
+    }
+}
@@ -0,0 +1,76 @@
+use serde_json::Value;
+use tokio::sync::broadcast;
+
+use crate::models::Event;
+use crate::utils::SensitiveDataMasker;
+
+/// 即時事件廣播器
+///
+/// 將收到的 Webhook 事件（遮罩後）廣播給所有訂閱者；
+/// 沒有訂閱者時發送會直接被丟棄，不影響事件處理。
+#[derive(Debug, Clone)]
+pub struct EventBroadcaster {
+    sender: broadcast::Sender<Value>,
+}
+
+impl EventBroadcaster {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    /// 廣播事件，無訂閱者時不做序列化
+    pub fn publish(&self, event: &Event) {
+        if self.sender.receiver_count() == 0 {
+            return;
+        }
+
+        match serde_json::to_value(event) {
+            Ok(mut value) => {
+                SensitiveDataMasker::mask_json_identifiers(&mut value);
+                let _ = self.sender.send(value);
+            }
+            Err(e) => tracing::warn!("Failed to serialize event for stream: {}", e),
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Value> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventBroadcaster {
+    fn default() -> Self {
+        Self::new(256)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_published_events_are_masked() {
+        let broadcaster = EventBroadcaster::default();
+        let mut receiver = broadcaster.subscribe();
+
+        let event: Event = serde_json::from_value(json!({
+            "type": "follow",
+            "replyToken": "reply_token_456",
+            "timestamp": 1234567890,
+            "source": {
+                "type": "user",
+                "userId": "U1234567890abcdef1234567890abcdef"
+            },
+            "mode": "active"
+        }))
+        .unwrap();
+        broadcaster.publish(&event);
+
+        let value = receiver.recv().await.unwrap();
+        assert_eq!(value["source"]["userId"], "U12...def");
+        assert_eq!(value["replyToken"], "rep...456");
+        assert_eq!(value["type"], "follow");
+    }
+}
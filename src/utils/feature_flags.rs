@@ -0,0 +1,282 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::warn;
+
+use crate::storage::{AuditLog, KvNamespace, Storage, StorageError};
+use crate::utils::watch_path;
+
+/// 執行期間覆寫的規則在 `feature_flags` 命名空間中的鍵
+const OVERRIDES_KEY: &str = "overrides";
+
+/// 單一功能開關的規則
+///
+/// `enabled` 為 `false` 時所有人關閉；否則 `users` 中的使用者一律開啟，其餘使用者依
+/// `percentage` 分批開啟。分批以旗標名稱與使用者 ID 的雜湊決定，同一位使用者的結果固定，
+/// 調高比例時已開啟的使用者不會被關閉。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FlagRule {
+    pub enabled: bool,
+    pub users: BTreeSet<String>,
+    /// 0–100
+    pub percentage: u8,
+}
+
+impl Default for FlagRule {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            users: BTreeSet::new(),
+            percentage: 100,
+        }
+    }
+}
+
+impl FlagRule {
+    /// 沒有使用者 ID（例如群組中的匿名成員）時只有全面開啟才算開啟
+    pub fn is_enabled_for(&self, flag: &str, user_id: Option<&str>) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        match user_id {
+            Some(user_id) => {
+                self.users.contains(user_id) || rollout_bucket(flag, user_id) < self.percentage
+            }
+            None => self.percentage >= 100,
+        }
+    }
+}
+
+/// 使用者在某個旗標的分桶（0–99）
+pub fn rollout_bucket(flag: &str, user_id: &str) -> u8 {
+    let digest = Sha256::digest(format!("{}:{}", flag, user_id).as_bytes());
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    (u64::from_be_bytes(bytes) % 100) as u8
+}
+
+#[derive(Debug, Default)]
+struct FlagSet {
+    /// `FEATURE_FLAGS_FILE` 中的規則
+    file: BTreeMap<String, FlagRule>,
+    /// 透過管理 API 覆寫的規則，優先於檔案
+    overrides: BTreeMap<String, FlagRule>,
+}
+
+/// 執行期間可調整的功能開關
+///
+/// 規則來自 JSON 檔（旗標名稱對應 [`FlagRule`]，修改後自動重新載入）與儲存後端中的覆寫。
+/// 覆寫保存在 `feature_flags` 命名空間，多個實例共用同一個儲存後端時會定期同步。
+/// 未定義的旗標視為關閉。
+#[derive(Clone)]
+pub struct FeatureFlags {
+    flags: Arc<RwLock<FlagSet>>,
+    kv: KvNamespace,
+}
+
+impl FeatureFlags {
+    pub fn new(storage: Arc<dyn Storage>) -> Self {
+        Self {
+            flags: Default::default(),
+            kv: KvNamespace::new(storage, "feature_flags"),
+        }
+    }
+
+    /// 讀取規則檔；解析失敗時保留原本的規則
+    pub fn load_file(&self, path: impl AsRef<Path>) -> Result<usize, Box<dyn std::error::Error>> {
+        let content = std::fs::read_to_string(path)?;
+        let rules: BTreeMap<String, FlagRule> = serde_json::from_str(&content)?;
+        if let Some((name, _)) = rules.iter().find(|(_, rule)| rule.percentage > 100) {
+            return Err(
+                format!("Feature flag {} percentage must be between 0 and 100", name).into(),
+            );
+        }
+        let count = rules.len();
+        self.write().file = rules;
+        Ok(count)
+    }
+
+    /// 定期檢查規則檔修改時間，有變更時重新載入，並寫入稽核紀錄
+    pub fn watch(&self, path: impl Into<PathBuf>, interval: Duration, audit_log: Option<AuditLog>) {
+        let flags = self.clone();
+        watch_path(
+            path.into(),
+            interval,
+            "feature_flags",
+            "feature flags",
+            audit_log,
+            move |path| flags.load_file(path),
+        );
+    }
+
+    /// 從儲存後端重新讀取覆寫
+    pub async fn refresh(&self) -> Result<(), StorageError> {
+        let overrides = self.kv.get(OVERRIDES_KEY).await?.unwrap_or_default();
+        self.write().overrides = overrides;
+        Ok(())
+    }
+
+    /// 定期同步其他實例寫入的覆寫
+    pub fn start_refresh(&self, interval: Duration) {
+        let flags = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = flags.refresh().await {
+                    warn!("Failed to refresh feature flags: {}", e);
+                }
+            }
+        });
+    }
+
+    /// 旗標是否對這位使用者開啟
+    pub fn is_enabled(&self, flag: &str, user_id: Option<&str>) -> bool {
+        self.rule(flag)
+            .is_some_and(|rule| rule.is_enabled_for(flag, user_id))
+    }
+
+    /// 目前生效的規則，覆寫優先於檔案
+    pub fn rule(&self, flag: &str) -> Option<FlagRule> {
+        let flags = self.read();
+        flags
+            .overrides
+            .get(flag)
+            .or_else(|| flags.file.get(flag))
+            .cloned()
+    }
+
+    /// 所有生效的規則
+    pub fn rules(&self) -> BTreeMap<String, FlagRule> {
+        let flags = self.read();
+        let mut rules = flags.file.clone();
+        rules.extend(flags.overrides.clone());
+        rules
+    }
+
+    /// 覆寫規則並寫入儲存後端
+    pub async fn set(&self, flag: &str, rule: FlagRule) -> Result<(), StorageError> {
+        if rule.percentage > 100 {
+            return Err(StorageError::new(
+                "Feature flag percentage must be between 0 and 100",
+            ));
+        }
+        let mut overrides = self.stored_overrides().await?;
+        overrides.insert(flag.to_string(), rule);
+        self.save_overrides(overrides).await
+    }
+
+    /// 移除覆寫，改回檔案中的規則；沒有覆寫時回傳 `false`
+    pub async fn remove(&self, flag: &str) -> Result<bool, StorageError> {
+        let mut overrides = self.stored_overrides().await?;
+        if overrides.remove(flag).is_none() {
+            return Ok(false);
+        }
+        self.save_overrides(overrides).await?;
+        Ok(true)
+    }
+
+    async fn stored_overrides(&self) -> Result<BTreeMap<String, FlagRule>, StorageError> {
+        Ok(self.kv.get(OVERRIDES_KEY).await?.unwrap_or_default())
+    }
+
+    async fn save_overrides(
+        &self,
+        overrides: BTreeMap<String, FlagRule>,
+    ) -> Result<(), StorageError> {
+        self.kv.set(OVERRIDES_KEY, &overrides).await?;
+        self.write().overrides = overrides;
+        Ok(())
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, FlagSet> {
+        self.flags.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, FlagSet> {
+        self.flags.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_feature_flags() {
+        let dir = std::env::temp_dir().join(format!("linebot-flags-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("flags.json");
+        std::fs::write(
+            &path,
+            r#"{
+                "new_flow": { "percentage": 30, "users": ["Ubeta"] },
+                "killed": { "enabled": false },
+                "everyone": {}
+            }"#,
+        )
+        .unwrap();
+
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let flags = FeatureFlags::new(storage.clone());
+        assert_eq!(flags.load_file(&path).unwrap(), 3);
+
+        assert!(flags.is_enabled("new_flow", Some("Ubeta")));
+        assert!(!flags.is_enabled("new_flow", None));
+        assert!(!flags.is_enabled("killed", Some("Ubeta")));
+        assert!(flags.is_enabled("everyone", None));
+        assert!(!flags.is_enabled("unknown", Some("Ubeta")));
+
+        // 約 30% 的使用者開啟，且同一位使用者結果固定
+        let users: Vec<String> = (0..1000).map(|i| format!("U{}", i)).collect();
+        let enabled = users
+            .iter()
+            .filter(|user| flags.is_enabled("new_flow", Some(user)))
+            .count();
+        assert!((200..400).contains(&enabled), "enabled for {}", enabled);
+        assert_eq!(
+            flags.is_enabled("new_flow", Some("U1")),
+            rollout_bucket("new_flow", "U1") < 30
+        );
+
+        // 覆寫優先於檔案，其他實例 refresh 後生效
+        flags
+            .set(
+                "new_flow",
+                FlagRule {
+                    percentage: 0,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert!(!flags.is_enabled("new_flow", Some("Ubeta")));
+        let other = FeatureFlags::new(storage);
+        other.refresh().await.unwrap();
+        assert_eq!(other.rule("new_flow").unwrap().percentage, 0);
+        assert!(
+            flags
+                .set(
+                    "bad",
+                    FlagRule {
+                        percentage: 101,
+                        ..Default::default()
+                    }
+                )
+                .await
+                .is_err()
+        );
+
+        assert!(flags.remove("new_flow").await.unwrap());
+        assert!(!flags.remove("new_flow").await.unwrap());
+        assert!(flags.is_enabled("new_flow", Some("Ubeta")));
+        assert_eq!(flags.rules().len(), 3);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,86 @@
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::line_api::DEFAULT_USER_AGENT;
+
+/// 共用 HTTP 連線池的設定
+///
+/// LINE API 用戶端與各整合（轉發、LLM、翻譯、NLU、LINE Pay 等）共用同一個 [`reqwest::Client`]，
+/// 連到同一個主機的請求會重複使用連線。HTTPS 連線以 ALPN 協商 HTTP/2，對方不支援時改用 HTTP/1.1。
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct HttpClientConfig {
+    /// 每個主機保留的閒置連線數上限
+    pub pool_max_idle_per_host: usize,
+    /// 閒置連線保留時間（秒）
+    pub pool_idle_timeout_secs: u64,
+    /// TCP 與 HTTP/2 keep-alive 的間隔（秒），0 停用
+    pub keepalive_secs: u64,
+    /// 建立連線的逾時（秒），整個請求的逾時由各元件自行設定
+    pub connect_timeout_secs: u64,
+}
+
+impl Default for HttpClientConfig {
+    fn default() -> Self {
+        Self {
+            pool_max_idle_per_host: 32,
+            pool_idle_timeout_secs: 90,
+            keepalive_secs: 60,
+            connect_timeout_secs: 10,
+        }
+    }
+}
+
+impl HttpClientConfig {
+    /// 建立共用的用戶端，`Client` 內部以 `Arc` 共用連線池，clone 後傳給各元件即可
+    pub fn build(&self) -> Result<reqwest::Client, reqwest::Error> {
+        let mut builder = reqwest::Client::builder()
+            .user_agent(DEFAULT_USER_AGENT)
+            .connect_timeout(Duration::from_secs(self.connect_timeout_secs))
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .pool_idle_timeout(Duration::from_secs(self.pool_idle_timeout_secs))
+            .http2_adaptive_window(true);
+        if self.keepalive_secs > 0 {
+            let interval = Duration::from_secs(self.keepalive_secs);
+            builder = builder
+                .tcp_keepalive(interval)
+                .http2_keep_alive_interval(interval)
+                .http2_keep_alive_while_idle(true);
+        }
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_shared_client_reuses_connections() {
+        use axum::{Router, extract::ConnectInfo, routing::get};
+        use std::net::SocketAddr;
+
+        let app = Router::new().route(
+            "/",
+            get(|ConnectInfo(peer): ConnectInfo<SocketAddr>| async move { peer.to_string() }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .await
+            .unwrap();
+        });
+
+        let client = HttpClientConfig::default().build().unwrap();
+        let other = client.clone();
+        let url = format!("http://{}/", addr);
+        let first = client.get(&url).send().await.unwrap().text().await.unwrap();
+        let second = other.get(&url).send().await.unwrap().text().await.unwrap();
+        // 兩次請求來自同一個來源埠，表示共用同一條連線
+        assert_eq!(first, second);
+    }
+}
@@ -0,0 +1,333 @@
+use chrono::{FixedOffset, Utc};
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+
+use crate::line_api::ProfileLanguageCache;
+
+/// 內建回覆支援的語系
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Locale {
+    ZhHant,
+    En,
+    Ja,
+}
+
+impl Locale {
+    /// 由 BCP 47 語言標籤（例如 LINE 個人資料的 `language`）取得語系，沒有對應的翻譯時回傳 `None`
+    pub fn from_language(tag: &str) -> Option<Self> {
+        let tag = tag.to_lowercase().replace('_', "-");
+        let mut subtags = tag.split('-');
+        match subtags.next()? {
+            "en" => Some(Locale::En),
+            "ja" => Some(Locale::Ja),
+            // 簡體中文沒有對應的翻譯
+            "zh" if subtags.any(|subtag| matches!(subtag, "hant" | "tw" | "hk" | "mo")) => {
+                Some(Locale::ZhHant)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Locale::ZhHant => "zh-Hant",
+            Locale::En => "en",
+            Locale::Ja => "ja",
+        }
+    }
+
+    /// 未設定 `LOCALE_UTC_OFFSETS` 時各語系使用的時區
+    fn default_utc_offset(&self) -> FixedOffset {
+        let hours = match self {
+            Locale::ZhHant => 8,
+            Locale::En => 0,
+            Locale::Ja => 9,
+        };
+        FixedOffset::east_opt(hours * 3600).expect("offset is within a day")
+    }
+}
+
+impl TryFrom<String> for Locale {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Locale::from_language(&value).ok_or_else(|| format!("Unsupported locale: {}", value))
+    }
+}
+
+/// 內建回覆的文字
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplyText {
+    Welcome,
+    Greeting,
+    Help,
+    UnknownCommand,
+    /// 後面接上目前時間
+    CurrentTime,
+    /// 後面接上使用者的文字
+    Echo,
+    NoSubscription,
+    InvalidText,
+    InappropriateText,
+    StickerReceived,
+    ImageReceived,
+    VideoReceived,
+    AudioReceived,
+    FileReceived,
+    /// 後面接上保存後的網址
+    MediaStored,
+}
+
+impl ReplyText {
+    pub fn get(&self, locale: Locale) -> &'static str {
+        use Locale::*;
+        use ReplyText::*;
+
+        match (self, locale) {
+            (Welcome, ZhHant) => "歡迎使用 LINE Bot！",
+            (Welcome, En) => "Welcome to LINE Bot!",
+            (Welcome, Ja) => "LINE Bot へようこそ！",
+            (Greeting, ZhHant) => "你好！有什麼可以幫助你的嗎？",
+            (Greeting, En) => "Hello! How can I help you?",
+            (Greeting, Ja) => "こんにちは！何かお手伝いできることはありますか？",
+            (Help, ZhHant) => {
+                "可用指令：\n• hello - 打招呼\n• help - 顯示說明\n• time - 顯示目前時間\n• sticker - 發送貼圖"
+            }
+            (Help, En) => {
+                "Available commands:\n• hello - say hello\n• help - show this help\n• time - show the current time\n• sticker - send a sticker"
+            }
+            (Help, Ja) => {
+                "使えるコマンド：\n• hello - あいさつ\n• help - ヘルプを表示\n• time - 現在時刻を表示\n• sticker - スタンプを送る"
+            }
+            (UnknownCommand, ZhHant) => "我不太理解你的意思，試試輸入 'help' 查看可用指令。",
+            (UnknownCommand, En) => {
+                "Sorry, I didn't understand that. Type 'help' to see the available commands."
+            }
+            (UnknownCommand, Ja) => {
+                "すみません、よくわかりませんでした。'help' と入力するとコマンド一覧を表示します。"
+            }
+            (CurrentTime, ZhHant) => "目前時間：",
+            (CurrentTime, En) => "Current time: ",
+            (CurrentTime, Ja) => "現在時刻：",
+            (Echo, ZhHant) => "回音：",
+            (Echo, En) => "Echo: ",
+            (Echo, Ja) => "エコー：",
+            (NoSubscription, ZhHant) => "目前沒有可訂閱的內容。",
+            (NoSubscription, En) => "There is nothing to subscribe to yet.",
+            (NoSubscription, Ja) => "購読できるコンテンツはまだありません。",
+            (InvalidText, ZhHant) => "抱歉，您的訊息包含無效內容。",
+            (InvalidText, En) => "Sorry, your message contains invalid content.",
+            (InvalidText, Ja) => "申し訳ありません。メッセージに無効な内容が含まれています。",
+            (InappropriateText, ZhHant) => "抱歉，您的訊息包含不當內容。",
+            (InappropriateText, En) => "Sorry, your message contains inappropriate content.",
+            (InappropriateText, Ja) => {
+                "申し訳ありません。メッセージに不適切な内容が含まれています。"
+            }
+            (StickerReceived, ZhHant) => "收到貼圖！",
+            (StickerReceived, En) => "Got your sticker!",
+            (StickerReceived, Ja) => "スタンプを受け取りました！",
+            (ImageReceived, ZhHant) => "收到圖片！",
+            (ImageReceived, En) => "Got your image!",
+            (ImageReceived, Ja) => "画像を受け取りました！",
+            (VideoReceived, ZhHant) => "收到影片！",
+            (VideoReceived, En) => "Got your video!",
+            (VideoReceived, Ja) => "動画を受け取りました！",
+            (AudioReceived, ZhHant) => "收到語音！",
+            (AudioReceived, En) => "Got your voice message!",
+            (AudioReceived, Ja) => "音声を受け取りました！",
+            (FileReceived, ZhHant) => "收到檔案！",
+            (FileReceived, En) => "Got your file!",
+            (FileReceived, Ja) => "ファイルを受け取りました！",
+            (MediaStored, ZhHant) => "已保存：",
+            (MediaStored, En) => "Saved: ",
+            (MediaStored, Ja) => "保存しました：",
+        }
+    }
+}
+
+/// 語系設定
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct I18nConfig {
+    /// 無法判斷使用者語言時使用的語系
+    pub default_locale: Locale,
+    /// 覆寫各語系的時區，未列出的語系使用預設值（zh-Hant +08:00、ja +09:00、en +00:00）
+    #[serde(deserialize_with = "deserialize_utc_offsets")]
+    pub utc_offsets: BTreeMap<Locale, FixedOffset>,
+}
+
+impl Default for I18nConfig {
+    fn default() -> Self {
+        Self {
+            default_locale: Locale::ZhHant,
+            utc_offsets: BTreeMap::new(),
+        }
+    }
+}
+
+impl I18nConfig {
+    /// 解析 `zh-Hant=+08:00,en=-05:00` 格式的時區設定
+    pub fn parse_utc_offsets(value: &str) -> Result<BTreeMap<Locale, FixedOffset>, String> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (language, offset) = entry
+                    .split_once('=')
+                    .ok_or_else(|| format!("Invalid locale UTC offset: {}", entry))?;
+                let locale = Locale::from_language(language.trim())
+                    .ok_or_else(|| format!("Unsupported locale: {}", language))?;
+                let offset = offset
+                    .trim()
+                    .parse::<FixedOffset>()
+                    .map_err(|_| format!("Invalid UTC offset: {}", offset))?;
+                Ok((locale, offset))
+            })
+            .collect()
+    }
+}
+
+fn deserialize_utc_offsets<'de, D>(
+    deserializer: D,
+) -> Result<BTreeMap<Locale, FixedOffset>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    BTreeMap::<Locale, String>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(locale, offset)| {
+            offset
+                .parse::<FixedOffset>()
+                .map(|offset| (locale, offset))
+                .map_err(|_| serde::de::Error::custom(format!("Invalid UTC offset: {}", offset)))
+        })
+        .collect()
+}
+
+/// 依使用者語言選擇內建回覆的語系與時區
+///
+/// 使用者語言取自 LINE 個人資料的 `language`，沒有對應的翻譯時使用預設語系。
+#[derive(Clone)]
+pub struct I18n {
+    config: I18nConfig,
+    profile_languages: Option<ProfileLanguageCache>,
+}
+
+impl I18n {
+    pub fn new(config: I18nConfig) -> Self {
+        Self {
+            config,
+            profile_languages: None,
+        }
+    }
+
+    /// 以此快取查詢使用者個人資料的語言設定，未設定時一律使用預設語系
+    pub fn profile_languages(mut self, cache: ProfileLanguageCache) -> Self {
+        self.profile_languages = Some(cache);
+        self
+    }
+
+    pub fn default_locale(&self) -> Locale {
+        self.config.default_locale
+    }
+
+    /// 使用者的語系，`preferred`（例如群組設定的語言）優先於個人資料
+    pub async fn locale_for(&self, user_id: Option<&str>, preferred: Option<&str>) -> Locale {
+        if let Some(locale) = preferred.and_then(Locale::from_language) {
+            return locale;
+        }
+        let language = match (&self.profile_languages, user_id) {
+            (Some(cache), Some(user_id)) => cache.get(user_id).await,
+            _ => None,
+        };
+        language
+            .as_deref()
+            .and_then(Locale::from_language)
+            .unwrap_or(self.config.default_locale)
+    }
+
+    pub fn utc_offset(&self, locale: Locale) -> FixedOffset {
+        self.config
+            .utc_offsets
+            .get(&locale)
+            .copied()
+            .unwrap_or_else(|| locale.default_utc_offset())
+    }
+
+    /// 以該語系的文字與時區回覆
+    pub fn replies(&self, locale: Locale) -> LocalizedReplies {
+        LocalizedReplies::new(locale, self.utc_offset(locale))
+    }
+}
+
+/// 已決定語系與時區的內建回覆
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalizedReplies {
+    locale: Locale,
+    utc_offset: FixedOffset,
+}
+
+impl LocalizedReplies {
+    pub fn new(locale: Locale, utc_offset: FixedOffset) -> Self {
+        Self { locale, utc_offset }
+    }
+
+    pub fn locale(&self) -> Locale {
+        self.locale
+    }
+
+    pub fn text(&self, key: ReplyText) -> &'static str {
+        key.get(self.locale)
+    }
+
+    /// 目前時間，附上時區
+    pub fn current_time(&self) -> String {
+        Utc::now()
+            .with_timezone(&self.utc_offset)
+            .format("%Y-%m-%d %H:%M:%S (UTC%:z)")
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_from_language() {
+        assert_eq!(Locale::from_language("en-US"), Some(Locale::En));
+        assert_eq!(Locale::from_language("ja"), Some(Locale::Ja));
+        assert_eq!(Locale::from_language("zh-TW"), Some(Locale::ZhHant));
+        assert_eq!(Locale::from_language("zh_Hant_HK"), Some(Locale::ZhHant));
+        assert_eq!(Locale::from_language("zh-CN"), None);
+        assert_eq!(Locale::from_language("ko"), None);
+    }
+
+    #[tokio::test]
+    async fn test_i18n_locale_and_time_zone() {
+        let config = I18nConfig {
+            default_locale: Locale::En,
+            utc_offsets: I18nConfig::parse_utc_offsets("en=-05:00, ja=+09:00").unwrap(),
+        };
+        let i18n = I18n::new(config);
+
+        assert_eq!(i18n.locale_for(Some("U1"), None).await, Locale::En);
+        assert_eq!(
+            i18n.locale_for(Some("U1"), Some("zh-TW")).await,
+            Locale::ZhHant
+        );
+        assert_eq!(i18n.utc_offset(Locale::En).local_minus_utc(), -5 * 3600);
+        assert_eq!(i18n.utc_offset(Locale::ZhHant).local_minus_utc(), 8 * 3600);
+        let replies = i18n.replies(Locale::Ja);
+        assert!(replies.current_time().ends_with("(UTC+09:00)"));
+        assert_eq!(
+            replies.text(ReplyText::Greeting),
+            "こんにちは！何かお手伝いできることはありますか？"
+        );
+
+        assert!(I18nConfig::parse_utc_offsets("ko=+09:00").is_err());
+        assert!(I18nConfig::parse_utc_offsets("en=EST").is_err());
+    }
+}
@@ -0,0 +1,51 @@
+use sha2::{Digest, Sha256};
+
+/// 由名稱產生固定的 UUID 字串，同一個名稱總是得到同一個值，適合作為重送時的 `X-Line-Retry-Key`
+pub fn name_based_uuid(name: &str) -> String {
+    let mut bytes: [u8; 16] = Sha256::digest(name)[..16]
+        .try_into()
+        .expect("digest is longer than 16 bytes");
+    // 標示為 name-based (v5) UUID
+    bytes[6] = (bytes[6] & 0x0f) | 0x50;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    format_uuid(&bytes)
+}
+
+/// 隨機產生的 (v4) UUID 字串
+pub fn random_uuid() -> String {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes).expect("OS random number generator is unavailable");
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    format_uuid(&bytes)
+}
+
+fn format_uuid(bytes: &[u8; 16]) -> String {
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uuid_versions() {
+        let name_based = name_based_uuid("campaign:1");
+        assert_eq!(name_based, name_based_uuid("campaign:1"));
+        assert_eq!(&name_based[14..15], "5");
+
+        let random = random_uuid();
+        assert_ne!(random, random_uuid());
+        assert_eq!(random.len(), 36);
+        assert_eq!(&random[14..15], "4");
+        assert!(matches!(&random[19..20], "8" | "9" | "a" | "b"));
+    }
+}
@@ -1,6 +1,20 @@
-use axum::{extract::Request, middleware::Next, response::Response};
+#[cfg(feature = "server")]
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
 use metrics::{counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram};
+use serde::Deserialize;
+use std::net::SocketAddr;
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
 use std::time::Instant;
+use sysinfo::{Pid, ProcessRefreshKind, System};
+
+use crate::utils::token_matches;
 
 /// 初始化指標收集系統
 pub fn init_metrics() {
@@ -18,193 +32,909 @@ pub fn init_metrics() {
         "http_request_duration_seconds",
         "HTTP request duration in seconds"
     );
+    describe_histogram!(
+        "http_response_size_bytes",
+        "HTTP response body size in bytes, by route template"
+    );
+    describe_histogram!(
+        "event_processing_duration_seconds",
+        "Webhook event processing duration in seconds"
+    );
     describe_histogram!(
         "line_api_duration_seconds",
         "LINE API request duration in seconds"
     );
-    describe_gauge!("active_connections", "Number of active connections");
+    describe_gauge!(
+        "memory_usage_bytes",
+        "Resident memory of the bot process in bytes"
+    );
+    describe_gauge!(
+        "virtual_memory_bytes",
+        "Virtual memory of the bot process in bytes"
+    );
+    describe_gauge!(
+        "cpu_usage_percent",
+        "CPU usage of the bot process in percent"
+    );
+    describe_gauge!(
+        "line_quota_used",
+        "LINE messages sent this month against the quota"
+    );
+    describe_gauge!("line_quota_limit", "Monthly LINE message quota");
+    describe_gauge!(
+        "active_connections",
+        "Number of HTTP requests currently being processed"
+    );
+    describe_gauge!(
+        "webhook_queue_depth",
+        "Webhook events waiting for a worker when asynchronous processing is enabled"
+    );
+    describe_gauge!(
+        "webhook_queue_spilled",
+        "Webhook events spilled to storage because the queue was full"
+    );
+    describe_counter!(
+        "webhook_events_shed_total",
+        "Webhook events rejected with 503 because the queue was full"
+    );
+    describe_counter!(
+        "webhook_signature_matches_total",
+        "Verified webhooks by index of the matching channel secret (0 is the primary)"
+    );
 }
 
-/// 設定 Prometheus 匯出器
+/// Prometheus 端點的驗證方式
+#[derive(Clone, PartialEq, Deserialize)]
+pub enum MetricsAuth {
+    Bearer(String),
+    Basic { username: String, password: String },
+}
+
+impl std::fmt::Debug for MetricsAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetricsAuth::Bearer(_) => write!(f, "Bearer(***)"),
+            MetricsAuth::Basic { username, .. } => f
+                .debug_struct("Basic")
+                .field("username", username)
+                .field("password", &"***")
+                .finish(),
+        }
+    }
+}
+
+impl MetricsAuth {
+    /// 檢查 `Authorization` 標頭
+    pub fn verify(&self, authorization: Option<&str>) -> bool {
+        let Some(authorization) = authorization else {
+            return false;
+        };
+
+        match self {
+            MetricsAuth::Bearer(token) => {
+                token_matches(authorization.strip_prefix("Bearer "), token)
+            }
+            MetricsAuth::Basic { username, password } => {
+                use base64::{Engine, engine::general_purpose::STANDARD};
+
+                authorization
+                    .strip_prefix("Basic ")
+                    .and_then(|encoded| STANDARD.decode(encoded).ok())
+                    .and_then(|decoded| String::from_utf8(decoded).ok())
+                    .is_some_and(|credentials| {
+                        token_matches(Some(&credentials), &format!("{}:{}", username, password))
+                    })
+            }
+        }
+    }
+}
+
+/// `http_response_size_bytes` 直方圖的 bucket 上界（位元組）
+pub const RESPONSE_SIZE_BUCKETS: &[f64] =
+    &[256.0, 1024.0, 4096.0, 16384.0, 65536.0, 262144.0, 1048576.0];
+
+/// Prometheus 匯出器設定
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MetricsExporterConfig {
+    /// 監聽位址，建議只綁定內部網路介面（例如 `127.0.0.1:9090`）
+    pub bind: SocketAddr,
+    pub auth: Option<MetricsAuth>,
+    /// 指標名稱前綴，例如 `mybot` 會輸出 `mybot_http_requests_total`
+    pub prefix: Option<String>,
+    /// 直方圖的 bucket 上界（秒）；未設定時輸出 summary。回應大小固定使用 [`RESPONSE_SIZE_BUCKETS`]
+    pub histogram_buckets: Option<Vec<f64>>,
+}
+
+/// 啟動 Prometheus 匯出器，於獨立的位址提供 `/metrics`
 #[cfg(feature = "metrics")]
-pub fn setup_prometheus_exporter(port: u16) -> Result<(), Box<dyn std::error::Error>> {
-    use metrics_exporter_prometheus::PrometheusBuilder;
+pub async fn start_metrics_exporter(
+    config: &MetricsExporterConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use axum::{
+        Router,
+        http::{StatusCode, header},
+        response::IntoResponse,
+        routing::get,
+    };
+    use metrics_exporter_prometheus::{Matcher, PrometheusBuilder};
+    use metrics_util::layers::{Layer, PrefixLayer};
+
+    let mut builder = PrometheusBuilder::new();
+    if let Some(buckets) = &config.histogram_buckets {
+        builder = builder.set_buckets(buckets)?.set_buckets_for_metric(
+            Matcher::Suffix("http_response_size_bytes".to_string()),
+            RESPONSE_SIZE_BUCKETS,
+        )?;
+    }
+    let recorder = builder.build_recorder();
+    let handle = recorder.handle();
+    // PrefixLayer 以 `.` 連接前綴，Prometheus 輸出時會轉為 `_`
+    let installed = match config.prefix.as_deref() {
+        Some(prefix) => metrics::set_global_recorder(
+            PrefixLayer::new(prefix.trim_end_matches(['_', '.']).to_string()).layer(recorder),
+        )
+        .map_err(|e| e.to_string()),
+        None => metrics::set_global_recorder(recorder).map_err(|e| e.to_string()),
+    };
+    installed.map_err(|e| format!("Failed to install Prometheus recorder: {}", e))?;
+    init_metrics();
+    if config.auth.is_none() {
+        tracing::warn!("Metrics endpoint on {} has no authentication", config.bind);
+    }
 
-    let builder = PrometheusBuilder::new().with_http_listener(([0, 0, 0, 0], port));
+    let auth = config.auth.clone();
+    let app = Router::new().route(
+        "/metrics",
+        get(move |headers: axum::http::HeaderMap| async move {
+            if let Some(auth) = &auth {
+                let authorization = headers
+                    .get(header::AUTHORIZATION)
+                    .and_then(|value| value.to_str().ok());
+                if !auth.verify(authorization) {
+                    return (
+                        StatusCode::UNAUTHORIZED,
+                        [(header::WWW_AUTHENTICATE, "Basic realm=\"metrics\"")],
+                    )
+                        .into_response();
+                }
+            }
+            handle.render().into_response()
+        }),
+    );
 
-    builder.install()?;
+    let listener = tokio::net::TcpListener::bind(config.bind).await?;
+    tracing::info!("Serving metrics on {}", listener.local_addr()?);
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            tracing::error!("Metrics exporter stopped: {}", e);
+        }
+    });
     Ok(())
 }
 
 #[cfg(not(feature = "metrics"))]
-pub fn setup_prometheus_exporter(_port: u16) -> Result<(), Box<dyn std::error::Error>> {
+pub async fn start_metrics_exporter(
+    config: &MetricsExporterConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
     // 當沒有啟用 metrics 特性時，不做任何事
+    tracing::warn!(
+        "METRICS_BIND is set to {} but the `metrics` feature is disabled",
+        config.bind
+    );
     Ok(())
 }
 
-/// HTTP 請求指標收集中介軟體
-pub async fn metrics_middleware(request: Request, next: Next) -> Response {
-    let start = Instant::now();
-    let method = request.method().clone();
-    let uri = request.uri().path().to_string();
+/// StatsD/DogStatsD 匯出器設定
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct StatsdExporterConfig {
+    pub host: String,
+    pub port: u16,
+    /// 指標名稱前綴，例如 `linebot` 會送出 `linebot.webhook_events_total`
+    pub prefix: Option<String>,
+}
 
-    // 增加請求計數
-    counter!("http_requests_total", "method" => method.to_string(), "endpoint" => uri.clone())
-        .increment(1);
+/// 將指標以 UDP 送到 StatsD/DogStatsD，標籤會轉為 DogStatsD tags
+#[cfg(feature = "statsd")]
+pub fn start_statsd_exporter(
+    config: &StatsdExporterConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use metrics_exporter_statsd::StatsdBuilder;
 
-    let response = next.run(request).await;
+    let recorder =
+        StatsdBuilder::from(config.host.clone(), config.port).build(config.prefix.as_deref())?;
+    metrics::set_global_recorder(recorder)
+        .map_err(|e| format!("Failed to install StatsD recorder: {}", e))?;
+    init_metrics();
 
-    // 記錄回應時間
-    let duration = start.elapsed();
-    let status = response.status().as_u16().to_string();
+    tracing::info!(
+        "Sending metrics to StatsD at {}:{}",
+        config.host,
+        config.port
+    );
+    Ok(())
+}
 
-    histogram!("http_request_duration_seconds", "method" => method.to_string(), "endpoint" => uri, "status" => status).record(duration.as_secs_f64());
+#[cfg(not(feature = "statsd"))]
+pub fn start_statsd_exporter(
+    config: &StatsdExporterConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // 當沒有啟用 statsd 特性時，不做任何事
+    tracing::warn!(
+        "STATSD_HOST is set to {} but the `statsd` feature is disabled",
+        config.host
+    );
+    Ok(())
+}
 
-    response
+/// OpenTelemetry OTLP 匯出器設定
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct OtlpExporterConfig {
+    /// Collector 位址，例如 `http://otel-collector:4318`，指標會送到 `/v1/metrics`
+    pub endpoint: String,
+    pub service_name: String,
+    /// 推送間隔
+    pub interval: std::time::Duration,
 }
 
-/// 記錄 Webhook 事件指標
-pub fn record_webhook_event(event_type: &str) {
-    counter!("webhook_events_total", "type" => event_type.to_string()).increment(1);
+impl OtlpExporterConfig {
+    pub fn metrics_url(&self) -> String {
+        format!("{}/v1/metrics", self.endpoint.trim_end_matches('/'))
+    }
 }
 
-/// 記錄 LINE API 請求指標
-pub fn record_line_api_request(api_type: &str, duration: std::time::Duration, success: bool) {
-    let status = if success { "success" } else { "error" };
+/// 以 OTLP（HTTP/protobuf）定期推送指標到 OpenTelemetry Collector
+#[cfg(feature = "otlp")]
+pub fn start_otlp_exporter(config: &OtlpExporterConfig) -> Result<(), Box<dyn std::error::Error>> {
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::{MetricExporter, WithExportConfig};
+    use opentelemetry_sdk::{
+        Resource,
+        metrics::{PeriodicReader, SdkMeterProvider},
+        runtime,
+    };
+
+    let exporter = MetricExporter::builder()
+        .with_http()
+        .with_endpoint(config.metrics_url())
+        .build()?;
+    let reader = PeriodicReader::builder(exporter, runtime::Tokio)
+        .with_interval(config.interval)
+        .build();
+    let provider = SdkMeterProvider::builder()
+        .with_reader(reader)
+        .with_resource(Resource::new(vec![KeyValue::new(
+            "service.name",
+            config.service_name.clone(),
+        )]))
+        .build();
 
-    counter!("line_api_requests_total", "api" => api_type.to_string(), "status" => status.to_string()).increment(1);
-    histogram!("line_api_duration_seconds", "api" => api_type.to_string(), "status" => status.to_string()).record(duration.as_secs_f64());
+    metrics::set_global_recorder(otlp::OtlpRecorder::new(provider))
+        .map_err(|e| format!("Failed to install OTLP recorder: {}", e))?;
+    init_metrics();
+
+    tracing::info!("Sending metrics to OTLP collector at {}", config.endpoint);
+    Ok(())
 }
 
-/// 系統指標收集器
-pub struct SystemMetrics {
-    start_time: Instant,
+#[cfg(not(feature = "otlp"))]
+pub fn start_otlp_exporter(config: &OtlpExporterConfig) -> Result<(), Box<dyn std::error::Error>> {
+    // 當沒有啟用 otlp 特性時，不做任何事
+    tracing::warn!(
+        "OTEL_EXPORTER_OTLP_ENDPOINT is set to {} but the `otlp` feature is disabled",
+        config.endpoint
+    );
+    Ok(())
 }
 
-impl SystemMetrics {
-    pub fn new() -> Self {
-        Self {
-            start_time: Instant::now(),
-        }
+/// 將 `metrics` 巨集的呼叫轉為 OpenTelemetry 儀表
+#[cfg(feature = "otlp")]
+mod otlp {
+    use dashmap::DashMap;
+    use metrics::{
+        Counter, CounterFn, Gauge, GaugeFn, Histogram, HistogramFn, Key, KeyName, Metadata,
+        Recorder, SharedString, Unit,
+    };
+    use opentelemetry::{KeyValue, metrics::Meter, metrics::MeterProvider};
+    use opentelemetry_sdk::metrics::SdkMeterProvider;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    };
+
+    pub struct OtlpRecorder {
+        // provider 被 drop 時會停止推送，需與 recorder 同生命週期
+        _provider: SdkMeterProvider,
+        meter: Meter,
+        descriptions: DashMap<String, SharedString>,
+        counters: DashMap<Key, Arc<OtlpCounter>>,
+        gauges: DashMap<Key, Arc<OtlpGauge>>,
+        histograms: DashMap<Key, Arc<OtlpHistogram>>,
     }
 
-    /// 更新系統指標
-    pub fn update_system_metrics(&self) {
-        // 運行時間
-        let uptime = self.start_time.elapsed().as_secs() as f64;
-        gauge!("uptime_seconds").set(uptime);
+    impl OtlpRecorder {
+        pub fn new(provider: SdkMeterProvider) -> Self {
+            Self {
+                meter: provider.meter("linebot-rs"),
+                _provider: provider,
+                descriptions: DashMap::new(),
+                counters: DashMap::new(),
+                gauges: DashMap::new(),
+                histograms: DashMap::new(),
+            }
+        }
 
-        // 記憶體使用量 (簡化版本)
-        if let Ok(memory_usage) = get_memory_usage() {
-            gauge!("memory_usage_bytes").set(memory_usage as f64);
+        fn description(&self, key: &Key) -> String {
+            self.descriptions
+                .get(key.name())
+                .map(|d| d.to_string())
+                .unwrap_or_default()
         }
+    }
 
-        // 活躍連接數 (這裡是示例，實際需要從應用狀態獲取)
-        // gauge!("active_connections").set(active_connections as f64);
+    fn attributes(key: &Key) -> Vec<KeyValue> {
+        key.labels()
+            .map(|label| KeyValue::new(label.key().to_string(), label.value().to_string()))
+            .collect()
     }
 
-    /// 啟動定期指標更新任務
-    pub fn start_periodic_update(&self) {
-        let metrics = SystemMetrics::new();
-        tokio::spawn(async move {
-            let mut interval = tokio::time::interval(std::time::Duration::from_secs(30));
-            loop {
-                interval.tick().await;
-                metrics.update_system_metrics();
+    struct OtlpCounter {
+        instrument: opentelemetry::metrics::Counter<u64>,
+        attributes: Vec<KeyValue>,
+        total: AtomicU64,
+    }
+
+    impl CounterFn for OtlpCounter {
+        fn increment(&self, value: u64) {
+            self.total.fetch_add(value, Ordering::Relaxed);
+            self.instrument.add(value, &self.attributes);
+        }
+
+        fn absolute(&self, value: u64) {
+            let previous = self.total.fetch_max(value, Ordering::Relaxed);
+            if value > previous {
+                self.instrument.add(value - previous, &self.attributes);
             }
-        });
+        }
     }
-}
 
-impl Default for SystemMetrics {
-    fn default() -> Self {
-        Self::new()
+    struct OtlpGauge {
+        instrument: opentelemetry::metrics::Gauge<f64>,
+        attributes: Vec<KeyValue>,
+        // f64 以位元形式保存，供 increment/decrement 計算
+        value: AtomicU64,
     }
-}
 
-/// 取得記憶體使用量 (簡化實作)
-fn get_memory_usage() -> Result<u64, Box<dyn std::error::Error>> {
-    #[cfg(unix)]
-    {
-        use std::fs;
-        let status = fs::read_to_string("/proc/self/status")?;
-        for line in status.lines() {
-            if line.starts_with("VmRSS:") {
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() >= 2 {
-                    let kb: u64 = parts[1].parse()?;
-                    return Ok(kb * 1024); // 轉換為位元組
+    impl OtlpGauge {
+        fn update(&self, f: impl Fn(f64) -> f64) {
+            let mut current = self.value.load(Ordering::Relaxed);
+            loop {
+                let next = f(f64::from_bits(current));
+                match self.value.compare_exchange_weak(
+                    current,
+                    next.to_bits(),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        self.instrument.record(next, &self.attributes);
+                        return;
+                    }
+                    Err(actual) => current = actual,
                 }
             }
         }
     }
 
-    #[cfg(windows)]
-    {
-        // Windows 的記憶體使用量取得需要額外的依賴
-        // 這裡返回一個模擬值
-        Ok(50 * 1024 * 1024) // 50MB
+    impl GaugeFn for OtlpGauge {
+        fn increment(&self, value: f64) {
+            self.update(|current| current + value);
+        }
+
+        fn decrement(&self, value: f64) {
+            self.update(|current| current - value);
+        }
+
+        fn set(&self, value: f64) {
+            self.update(|_| value);
+        }
+    }
+
+    struct OtlpHistogram {
+        instrument: opentelemetry::metrics::Histogram<f64>,
+        attributes: Vec<KeyValue>,
+    }
+
+    impl HistogramFn for OtlpHistogram {
+        fn record(&self, value: f64) {
+            self.instrument.record(value, &self.attributes);
+        }
+    }
+
+    impl Recorder for OtlpRecorder {
+        fn describe_counter(&self, key: KeyName, _: Option<Unit>, description: SharedString) {
+            self.descriptions
+                .insert(key.as_str().to_string(), description);
+        }
+
+        fn describe_gauge(&self, key: KeyName, _: Option<Unit>, description: SharedString) {
+            self.descriptions
+                .insert(key.as_str().to_string(), description);
+        }
+
+        fn describe_histogram(&self, key: KeyName, _: Option<Unit>, description: SharedString) {
+            self.descriptions
+                .insert(key.as_str().to_string(), description);
+        }
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            let handle = self
+                .counters
+                .entry(key.clone())
+                .or_insert_with(|| {
+                    Arc::new(OtlpCounter {
+                        instrument: self
+                            .meter
+                            .u64_counter(key.name().to_string())
+                            .with_description(self.description(key))
+                            .build(),
+                        attributes: attributes(key),
+                        total: AtomicU64::new(0),
+                    })
+                })
+                .clone();
+            Counter::from_arc(handle)
+        }
+
+        fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+            let handle = self
+                .gauges
+                .entry(key.clone())
+                .or_insert_with(|| {
+                    Arc::new(OtlpGauge {
+                        instrument: self
+                            .meter
+                            .f64_gauge(key.name().to_string())
+                            .with_description(self.description(key))
+                            .build(),
+                        attributes: attributes(key),
+                        value: AtomicU64::new(0f64.to_bits()),
+                    })
+                })
+                .clone();
+            Gauge::from_arc(handle)
+        }
+
+        fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+            let handle = self
+                .histograms
+                .entry(key.clone())
+                .or_insert_with(|| {
+                    Arc::new(OtlpHistogram {
+                        instrument: self
+                            .meter
+                            .f64_histogram(key.name().to_string())
+                            .with_description(self.description(key))
+                            .build(),
+                        attributes: attributes(key),
+                    })
+                })
+                .clone();
+            Histogram::from_arc(handle)
+        }
     }
+}
 
-    #[cfg(not(any(unix, windows)))]
-    Err("Unable to get memory usage".into())
+/// 應用程式指標
+///
+/// 由 `AppState` 持有並傳給 `LineApiClient`，除了送到全域 `metrics` recorder 外也會累計在
+/// 行程內，方便測試直接檢查。未啟用任何匯出 feature 時預設為不做事的 no-op。
+#[derive(Debug, Clone)]
+pub struct Metrics {
+    inner: Option<Arc<MetricsCounters>>,
 }
 
-/// LINE Bot 特定指標
-pub struct LineBotMetrics {
-    pub total_users: u64,
-    pub total_messages_sent: u64,
-    pub total_messages_received: u64,
-    pub api_errors: u64,
+#[derive(Debug)]
+struct MetricsCounters {
+    http_requests: AtomicU64,
+    webhook_events: AtomicU64,
+    event_errors: AtomicU64,
+    line_api_requests: AtomicU64,
+    line_api_errors: AtomicU64,
+    health_checks: AtomicU64,
+    active_connections: AtomicU64,
+    secondary_secret_matches: AtomicU64,
+    quota_used: AtomicU64,
+    // 無上限時為 u64::MAX
+    quota_limit: AtomicU64,
 }
 
-impl LineBotMetrics {
+/// 行程內累計的指標數值
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    pub http_requests: u64,
+    pub webhook_events: u64,
+    pub event_errors: u64,
+    pub line_api_requests: u64,
+    pub line_api_errors: u64,
+    pub health_checks: u64,
+    /// 目前處理中的請求數
+    pub active_connections: u64,
+    /// 以非主要 channel secret 驗證通過的 Webhook 數
+    pub secondary_secret_matches: u64,
+    pub quota_used: u64,
+    pub quota_limit: Option<u64>,
+}
+
+impl Metrics {
     pub fn new() -> Self {
         Self {
-            total_users: 0,
-            total_messages_sent: 0,
-            total_messages_received: 0,
-            api_errors: 0,
+            inner: Some(Arc::new(MetricsCounters {
+                http_requests: AtomicU64::new(0),
+                webhook_events: AtomicU64::new(0),
+                event_errors: AtomicU64::new(0),
+                line_api_requests: AtomicU64::new(0),
+                line_api_errors: AtomicU64::new(0),
+                health_checks: AtomicU64::new(0),
+                active_connections: AtomicU64::new(0),
+                secondary_secret_matches: AtomicU64::new(0),
+                quota_used: AtomicU64::new(0),
+                quota_limit: AtomicU64::new(u64::MAX),
+            })),
+        }
+    }
+
+    /// 不記錄任何指標
+    pub fn noop() -> Self {
+        Self { inner: None }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.inner.is_some()
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let Some(inner) = &self.inner else {
+            return MetricsSnapshot::default();
+        };
+        MetricsSnapshot {
+            http_requests: inner.http_requests.load(Ordering::Relaxed),
+            webhook_events: inner.webhook_events.load(Ordering::Relaxed),
+            event_errors: inner.event_errors.load(Ordering::Relaxed),
+            line_api_requests: inner.line_api_requests.load(Ordering::Relaxed),
+            line_api_errors: inner.line_api_errors.load(Ordering::Relaxed),
+            health_checks: inner.health_checks.load(Ordering::Relaxed),
+            active_connections: inner.active_connections.load(Ordering::Relaxed),
+            secondary_secret_matches: inner.secondary_secret_matches.load(Ordering::Relaxed),
+            quota_used: inner.quota_used.load(Ordering::Relaxed),
+            quota_limit: Some(inner.quota_limit.load(Ordering::Relaxed))
+                .filter(|limit| *limit != u64::MAX),
         }
     }
 
-    pub fn increment_users(&mut self) {
-        self.total_users += 1;
-        gauge!("line_bot_users_total").set(self.total_users as f64);
+    /// 記錄 HTTP 請求指標
+    pub fn record_http_request(
+        &self,
+        method: &str,
+        endpoint: &str,
+        status: u16,
+        duration: std::time::Duration,
+    ) {
+        let Some(inner) = &self.inner else {
+            return;
+        };
+        inner.http_requests.fetch_add(1, Ordering::Relaxed);
+
+        counter!("http_requests_total", "method" => method.to_string(), "endpoint" => endpoint.to_string())
+            .increment(1);
+        histogram!("http_request_duration_seconds", "method" => method.to_string(), "endpoint" => endpoint.to_string(), "status" => status.to_string()).record(duration.as_secs_f64());
+    }
+
+    /// 記錄回應內容大小，`endpoint` 與 [`record_http_request`](Self::record_http_request) 相同
+    pub fn record_http_response_size(&self, method: &str, endpoint: &str, bytes: u64) {
+        if self.inner.is_none() {
+            return;
+        }
+
+        histogram!("http_response_size_bytes", "method" => method.to_string(), "endpoint" => endpoint.to_string()).record(bytes as f64);
     }
 
-    pub fn increment_messages_sent(&mut self) {
-        self.total_messages_sent += 1;
-        counter!("line_bot_messages_sent_total").increment(1);
+    /// 開始追蹤一個處理中的請求，回傳的 guard 被 drop 時（包含請求被取消）結束追蹤
+    pub fn track_connection(&self) -> ConnectionGuard {
+        if let Some(inner) = &self.inner {
+            let current = inner.active_connections.fetch_add(1, Ordering::Relaxed) + 1;
+            gauge!("active_connections").set(current as f64);
+        }
+        ConnectionGuard {
+            inner: self.inner.clone(),
+        }
     }
 
-    pub fn increment_messages_received(&mut self) {
-        self.total_messages_received += 1;
-        counter!("line_bot_messages_received_total").increment(1);
+    /// 記錄 Webhook 事件指標
+    pub fn record_webhook_event(&self, event_type: &str) {
+        let Some(inner) = &self.inner else {
+            return;
+        };
+        inner.webhook_events.fetch_add(1, Ordering::Relaxed);
+
+        counter!("webhook_events_total", "type" => event_type.to_string()).increment(1);
     }
 
-    pub fn increment_api_errors(&mut self) {
-        self.api_errors += 1;
-        counter!("line_bot_api_errors_total").increment(1);
+    /// 記錄單一事件的處理時間與結果
+    pub fn record_event_processing(
+        &self,
+        event_type: &str,
+        duration: std::time::Duration,
+        success: bool,
+    ) {
+        let Some(inner) = &self.inner else {
+            return;
+        };
+        if !success {
+            inner.event_errors.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let outcome = if success { "success" } else { "error" };
+        histogram!("event_processing_duration_seconds", "type" => event_type.to_string(), "outcome" => outcome.to_string()).record(duration.as_secs_f64());
     }
 
-    pub fn update_gauges(&self) {
-        gauge!("line_bot_users_total").set(self.total_users as f64);
-        gauge!("line_bot_api_errors_total").set(self.api_errors as f64);
+    /// 記錄非同步處理佇列中等待的事件數與暫存到儲存後端的事件數
+    pub fn record_webhook_queue(&self, depth: usize, spilled: usize) {
+        if self.inner.is_none() {
+            return;
+        }
+
+        gauge!("webhook_queue_depth").set(depth as f64);
+        gauge!("webhook_queue_spilled").set(spilled as f64);
+    }
+
+    /// 佇列已滿、以 503 拒絕的事件
+    pub fn record_webhook_shed(&self, count: usize) {
+        if self.inner.is_none() {
+            return;
+        }
+
+        counter!("webhook_events_shed_total").increment(count as u64);
+    }
+
+    /// 記錄 LINE API 請求指標
+    pub fn record_line_api_request(
+        &self,
+        api_type: &str,
+        duration: std::time::Duration,
+        success: bool,
+    ) {
+        let Some(inner) = &self.inner else {
+            return;
+        };
+        inner.line_api_requests.fetch_add(1, Ordering::Relaxed);
+        if !success {
+            inner.line_api_errors.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let status = if success { "success" } else { "error" };
+        counter!("line_api_requests_total", "api" => api_type.to_string(), "status" => status.to_string()).increment(1);
+        histogram!("line_api_duration_seconds", "api" => api_type.to_string(), "status" => status.to_string()).record(duration.as_secs_f64());
+    }
+
+    /// 記錄驗證通過的 channel secret，輪替時用來確認舊 secret 已不再被使用
+    pub fn record_signature_match(&self, secret_index: usize) {
+        let Some(inner) = &self.inner else {
+            return;
+        };
+        if secret_index > 0 {
+            inner
+                .secondary_secret_matches
+                .fetch_add(1, Ordering::Relaxed);
+        }
+
+        counter!("webhook_signature_matches_total", "secret_index" => secret_index.to_string())
+            .increment(1);
+    }
+
+    /// 記錄本月訊息額度，無上限方案不會輸出 `line_quota_limit`
+    pub fn record_quota(&self, used: u64, limit: Option<u64>) {
+        let Some(inner) = &self.inner else {
+            return;
+        };
+        inner.quota_used.store(used, Ordering::Relaxed);
+        inner
+            .quota_limit
+            .store(limit.unwrap_or(u64::MAX), Ordering::Relaxed);
+
+        gauge!("line_quota_used").set(used as f64);
+        if let Some(limit) = limit {
+            gauge!("line_quota_limit").set(limit as f64);
+        }
+    }
+
+    /// 健康檢查指標
+    pub fn record_health_check(&self, healthy: bool) {
+        let Some(inner) = &self.inner else {
+            return;
+        };
+        inner.health_checks.fetch_add(1, Ordering::Relaxed);
+
+        let status = if healthy { "healthy" } else { "unhealthy" };
+        counter!("health_checks_total", "status" => status.to_string()).increment(1);
+        gauge!("service_healthy").set(if healthy { 1.0 } else { 0.0 });
+    }
+}
+
+/// 見 [`Metrics::track_connection`]
+#[must_use]
+pub struct ConnectionGuard {
+    inner: Option<Arc<MetricsCounters>>,
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        if let Some(inner) = &self.inner {
+            let current = inner.active_connections.fetch_sub(1, Ordering::Relaxed) - 1;
+            gauge!("active_connections").set(current as f64);
+        }
     }
 }
 
-impl Default for LineBotMetrics {
+impl Default for Metrics {
+    /// 有編入任何匯出器時記錄指標，否則為 no-op
     fn default() -> Self {
-        Self::new()
+        if cfg!(any(
+            feature = "metrics",
+            feature = "statsd",
+            feature = "otlp"
+        )) {
+            Self::new()
+        } else {
+            Self::noop()
+        }
+    }
+}
+
+/// HTTP 請求指標收集中介軟體
+///
+/// `endpoint` 標籤使用比對到的路由樣式（例如 `/admin/users/:user_id`），未比對到任何路由的
+/// 請求（掃描器等）一律記為 `unmatched`，避免標籤數量無限增長。
+#[cfg(feature = "server")]
+pub async fn metrics_middleware(
+    State(metrics): State<Metrics>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let start = Instant::now();
+    let method = method_label(request.method());
+    let endpoint = endpoint_label(&request);
+
+    let _connection = metrics.track_connection();
+    let response = next.run(request).await;
+
+    metrics.record_http_request(
+        method,
+        &endpoint,
+        response.status().as_u16(),
+        start.elapsed(),
+    );
+    if let Some(bytes) = response_size(&response) {
+        metrics.record_http_response_size(method, &endpoint, bytes);
     }
+    response
 }
 
-/// 健康檢查指標
-pub fn record_health_check(healthy: bool) {
-    let status = if healthy { "healthy" } else { "unhealthy" };
-    counter!("health_checks_total", "status" => status.to_string()).increment(1);
-    gauge!("service_healthy").set(if healthy { 1.0 } else { 0.0 });
+/// 回應內容的大小；串流回應（例如 SSE）事先無法得知，不記錄
+#[cfg(feature = "server")]
+fn response_size(response: &Response) -> Option<u64> {
+    use axum::body::HttpBody;
+
+    response.body().size_hint().exact().or_else(|| {
+        response
+            .headers()
+            .get(axum::http::header::CONTENT_LENGTH)?
+            .to_str()
+            .ok()?
+            .parse()
+            .ok()
+    })
+}
+
+/// 未比對到路由時的 `endpoint` 標籤
+#[cfg(feature = "server")]
+pub const UNMATCHED_ENDPOINT: &str = "unmatched";
+
+#[cfg(feature = "server")]
+fn endpoint_label(request: &Request) -> String {
+    request
+        .extensions()
+        .get::<axum::extract::MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| UNMATCHED_ENDPOINT.to_string())
+}
+
+/// 非標準的 HTTP 方法一律記為 `OTHER`
+#[cfg(feature = "server")]
+fn method_label(method: &axum::http::Method) -> &'static str {
+    use axum::http::Method;
+
+    match *method {
+        Method::GET => "GET",
+        Method::POST => "POST",
+        Method::PUT => "PUT",
+        Method::DELETE => "DELETE",
+        Method::PATCH => "PATCH",
+        Method::HEAD => "HEAD",
+        Method::OPTIONS => "OPTIONS",
+        _ => "OTHER",
+    }
+}
+
+/// 系統指標收集器
+///
+/// 透過 sysinfo 取得本行程的常駐記憶體（RSS）、虛擬記憶體與 CPU 使用率，
+/// 支援 Linux、macOS 與 Windows。
+pub struct SystemMetrics {
+    start_time: Instant,
+    system: System,
+    pid: Option<Pid>,
+}
+
+/// 本行程的資源使用量
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProcessUsage {
+    pub resident_memory_bytes: u64,
+    pub virtual_memory_bytes: u64,
+    /// 自上次更新以來的 CPU 使用率，多核心時可能超過 100
+    pub cpu_percent: f32,
+}
+
+impl SystemMetrics {
+    pub fn new() -> Self {
+        Self {
+            start_time: Instant::now(),
+            system: System::new(),
+            pid: sysinfo::get_current_pid().ok(),
+        }
+    }
+
+    /// 重新讀取本行程的資源使用量
+    pub fn process_usage(&mut self) -> Option<ProcessUsage> {
+        let pid = self.pid?;
+        self.system
+            .refresh_process_specifics(pid, ProcessRefreshKind::new().with_memory().with_cpu());
+        let process = self.system.process(pid)?;
+        Some(ProcessUsage {
+            resident_memory_bytes: process.memory(),
+            virtual_memory_bytes: process.virtual_memory(),
+            cpu_percent: process.cpu_usage(),
+        })
+    }
+
+    /// 更新系統指標
+    pub fn update_system_metrics(&mut self) {
+        // 運行時間
+        let uptime = self.start_time.elapsed().as_secs() as f64;
+        gauge!("uptime_seconds").set(uptime);
+
+        if let Some(usage) = self.process_usage() {
+            gauge!("memory_usage_bytes").set(usage.resident_memory_bytes as f64);
+            gauge!("virtual_memory_bytes").set(usage.virtual_memory_bytes as f64);
+            gauge!("cpu_usage_percent").set(f64::from(usage.cpu_percent));
+        }
+    }
+
+    /// 啟動定期指標更新任務
+    pub fn start_periodic_update(mut self, interval: std::time::Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                self.update_system_metrics();
+            }
+        });
+    }
+}
+
+impl Default for SystemMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[cfg(test)]
@@ -219,20 +949,118 @@ mod tests {
     }
 
     #[test]
-    fn test_line_bot_metrics() {
-        let mut metrics = LineBotMetrics::new();
+    fn test_process_usage() {
+        let mut metrics = SystemMetrics::new();
+        let usage = metrics.process_usage().expect("current process is visible");
+        assert!(usage.resident_memory_bytes > 0);
+        assert!(usage.virtual_memory_bytes >= usage.resident_memory_bytes);
+    }
+
+    #[test]
+    fn test_metrics_snapshot() {
+        let metrics = Metrics::new();
+        metrics.record_webhook_event("message");
+        metrics.record_event_processing("message", std::time::Duration::from_millis(5), false);
+        metrics.record_line_api_request("reply", std::time::Duration::from_millis(5), true);
+        metrics.record_line_api_request("push", std::time::Duration::from_millis(5), false);
+
+        // clone 共用同一份計數
+        let snapshot = metrics.clone().snapshot();
+        assert_eq!(snapshot.webhook_events, 1);
+        assert_eq!(snapshot.event_errors, 1);
+        assert_eq!(snapshot.line_api_requests, 2);
+        assert_eq!(snapshot.line_api_errors, 1);
+    }
+
+    #[test]
+    fn test_active_connections() {
+        let metrics = Metrics::new();
+        let first = metrics.track_connection();
+        let second = metrics.track_connection();
+        assert_eq!(metrics.snapshot().active_connections, 2);
+
+        drop(first);
+        assert_eq!(metrics.snapshot().active_connections, 1);
+        drop(second);
+        assert_eq!(metrics.snapshot().active_connections, 0);
+    }
+
+    #[cfg(feature = "server")]
+    #[tokio::test]
+    async fn test_endpoint_label_uses_route_pattern() {
+        use axum::{Router, body::Body, routing::get};
+        use tower::ServiceExt;
+
+        let label = |request: Request| async move { endpoint_label(&request) };
+        let app = Router::new()
+            .route("/users/:user_id", get(label))
+            .fallback(label);
+
+        for (uri, expected) in [
+            ("/users/U123", "/users/:user_id"),
+            ("/wp-login.php", UNMATCHED_ENDPOINT),
+        ] {
+            let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+            let response = app.clone().oneshot(request).await.unwrap();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            assert_eq!(body, expected);
+        }
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn test_response_size() {
+        use axum::{body::Body, response::IntoResponse};
 
-        assert_eq!(metrics.total_users, 0);
-        metrics.increment_users();
-        assert_eq!(metrics.total_users, 1);
+        assert_eq!(response_size(&"hello".into_response()), Some(5));
+        assert_eq!(
+            response_size(&axum::Json(serde_json::json!({"ok": true})).into_response()),
+            Some(11)
+        );
+        let stream = tokio_stream::iter([Ok::<_, std::io::Error>("chunk")]);
+        assert_eq!(
+            response_size(&Response::new(Body::from_stream(stream))),
+            None
+        );
+    }
 
-        assert_eq!(metrics.total_messages_sent, 0);
-        metrics.increment_messages_sent();
-        assert_eq!(metrics.total_messages_sent, 1);
+    #[test]
+    fn test_noop_metrics() {
+        let metrics = Metrics::noop();
+        metrics.record_webhook_event("message");
+        assert!(!metrics.is_enabled());
+        assert_eq!(metrics.snapshot(), MetricsSnapshot::default());
+    }
+
+    #[test]
+    fn test_metrics_auth_verify() {
+        let bearer = MetricsAuth::Bearer("secret".to_string());
+        assert!(bearer.verify(Some("Bearer secret")));
+        assert!(!bearer.verify(Some("Bearer wrong")));
+        assert!(!bearer.verify(None));
 
-        assert_eq!(metrics.total_messages_received, 0);
-        metrics.increment_messages_received();
-        assert_eq!(metrics.total_messages_received, 1);
+        // "prom:pass" 的 base64
+        let basic = MetricsAuth::Basic {
+            username: "prom".to_string(),
+            password: "pass".to_string(),
+        };
+        assert!(basic.verify(Some("Basic cHJvbTpwYXNz")));
+        assert!(!basic.verify(Some("Bearer secret")));
+    }
+
+    #[test]
+    fn test_otlp_metrics_url() {
+        let config = OtlpExporterConfig {
+            endpoint: "http://otel-collector:4318/".to_string(),
+            service_name: "linebot-rs".to_string(),
+            interval: std::time::Duration::from_secs(60),
+        };
+        assert_eq!(
+            config.metrics_url(),
+            "http://otel-collector:4318/v1/metrics"
+        );
     }
 
     #[test]
@@ -1,11 +1,46 @@
+pub mod analytics;
 pub mod config;
+pub mod duplicate_filter;
+pub mod error_reporting;
+pub mod event_stream;
+pub mod feature_flags;
+pub mod http;
+pub mod i18n;
+pub mod ids;
 pub mod metrics;
+pub mod moderation;
+pub mod moderation_report;
+#[cfg(feature = "server")]
 pub mod rate_limit;
+#[cfg(feature = "scripting")]
+pub mod scripts;
 pub mod signature;
+pub mod stats;
+pub mod systemd;
+#[cfg(feature = "templates")]
+pub mod templates;
 pub mod validation;
+pub mod watch;
 
+pub use analytics::*;
 pub use config::*;
+pub use duplicate_filter::*;
+pub use error_reporting::*;
+pub use event_stream::*;
+pub use feature_flags::*;
+pub use http::*;
+pub use i18n::*;
+pub use ids::*;
 pub use metrics::*;
+pub use moderation::*;
+pub use moderation_report::*;
+#[cfg(feature = "server")]
 pub use rate_limit::*;
+#[cfg(feature = "scripting")]
+pub use scripts::*;
 pub use signature::*;
+pub use stats::*;
+#[cfg(feature = "templates")]
+pub use templates::*;
 pub use validation::*;
+pub use watch::*;
@@ -0,0 +1,234 @@
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+
+use crate::utils::ForbiddenWordList;
+
+/// 禁用詞規則的嚴重程度
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Low,
+    #[default]
+    Medium,
+    High,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "low" => Some(Severity::Low),
+            "medium" => Some(Severity::Medium),
+            "high" => Some(Severity::High),
+            _ => None,
+        }
+    }
+}
+
+/// 命中禁用詞時的處置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationAction {
+    /// 照常處理訊息
+    Ignore,
+    /// 回覆提醒訊息，不處理內容
+    WarnUser,
+    /// 不回覆也不處理
+    DropMessage,
+    /// 不處理訊息並通知管理者
+    NotifyAdmin,
+}
+
+impl ModerationAction {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "ignore" => Some(ModerationAction::Ignore),
+            "warn_user" => Some(ModerationAction::WarnUser),
+            "drop_message" => Some(ModerationAction::DropMessage),
+            "notify_admin" => Some(ModerationAction::NotifyAdmin),
+            _ => None,
+        }
+    }
+}
+
+/// 各嚴重程度對應的處置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModerationPolicy {
+    pub low: ModerationAction,
+    pub medium: ModerationAction,
+    pub high: ModerationAction,
+}
+
+impl Default for ModerationPolicy {
+    fn default() -> Self {
+        Self {
+            low: ModerationAction::Ignore,
+            medium: ModerationAction::WarnUser,
+            high: ModerationAction::DropMessage,
+        }
+    }
+}
+
+impl ModerationPolicy {
+    pub fn action_for(&self, severity: Severity) -> ModerationAction {
+        match severity {
+            Severity::Low => self.low,
+            Severity::Medium => self.medium,
+            Severity::High => self.high,
+        }
+    }
+
+    pub fn set_action(&mut self, severity: Severity, action: ModerationAction) {
+        match severity {
+            Severity::Low => self.low = action,
+            Severity::Medium => self.medium = action,
+            Severity::High => self.high = action,
+        }
+    }
+
+    /// 解析 `low=ignore,high=notify_admin` 格式，未指定的嚴重程度沿用預設值
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut policy = Self::default();
+        for item in spec
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+        {
+            let (severity, action) = item
+                .split_once('=')
+                .ok_or_else(|| format!("expected severity=action, got `{}`", item))?;
+            let severity = Severity::parse(severity.trim())
+                .ok_or_else(|| format!("unknown severity `{}`", severity.trim()))?;
+            let action = ModerationAction::parse(action.trim())
+                .ok_or_else(|| format!("unknown action `{}`", action.trim()))?;
+            policy.set_action(severity, action);
+        }
+        Ok(policy)
+    }
+}
+
+/// 審核結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModerationDecision {
+    pub severity: Severity,
+    pub action: ModerationAction,
+}
+
+/// 內容審核
+///
+/// 以禁用詞清單判斷嚴重程度，再依可在執行期間調整的 [`ModerationPolicy`] 決定處置。
+#[derive(Debug, Clone)]
+pub struct Moderator {
+    words: ForbiddenWordList,
+    policy: Arc<RwLock<ModerationPolicy>>,
+}
+
+impl Default for Moderator {
+    fn default() -> Self {
+        Self::new(ForbiddenWordList::default(), ModerationPolicy::default())
+    }
+}
+
+impl Moderator {
+    pub fn new(words: ForbiddenWordList, policy: ModerationPolicy) -> Self {
+        Self {
+            words,
+            policy: Arc::new(RwLock::new(policy)),
+        }
+    }
+
+    pub fn words(&self) -> &ForbiddenWordList {
+        &self.words
+    }
+
+    pub fn policy(&self) -> ModerationPolicy {
+        *self.policy.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn set_policy(&self, policy: ModerationPolicy) {
+        *self.policy.write().unwrap_or_else(|e| e.into_inner()) = policy;
+    }
+
+    /// 未命中任何規則時回傳 `None`
+    pub fn check(&self, text: &str) -> Option<ModerationDecision> {
+        let severity = self.words.highest_severity(text)?;
+        Some(ModerationDecision {
+            severity,
+            action: self.policy().action_for(severity),
+        })
+    }
+
+    /// 檢查同一則訊息的多個版本（例如原文與譯文），回傳最嚴重的結果
+    pub fn check_all<'a>(
+        &self,
+        texts: impl IntoIterator<Item = &'a str>,
+    ) -> Option<ModerationDecision> {
+        texts
+            .into_iter()
+            .filter_map(|text| self.check(text))
+            .max_by_key(|decision| decision.severity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_moderator_uses_highest_severity() {
+        let words =
+            ForbiddenWordList::from_entries(["low: darn", "casino", "high: free*money"]).unwrap();
+        let moderator = Moderator::new(words, ModerationPolicy::default());
+
+        assert_eq!(moderator.check("hello"), None);
+        assert_eq!(
+            moderator.check("darn"),
+            Some(ModerationDecision {
+                severity: Severity::Low,
+                action: ModerationAction::Ignore,
+            })
+        );
+        assert_eq!(
+            moderator
+                .check("darn casino with free money")
+                .map(|d| d.severity),
+            Some(Severity::High)
+        );
+
+        assert_eq!(
+            moderator
+                .check_all(["hello", "darn", "casino"])
+                .map(|d| d.severity),
+            Some(Severity::Medium)
+        );
+        assert_eq!(moderator.check_all(["hello", "hi"]), None);
+
+        let mut policy = moderator.policy();
+        policy.set_action(Severity::Medium, ModerationAction::NotifyAdmin);
+        moderator.set_policy(policy);
+        assert_eq!(
+            moderator.check("casino").map(|d| d.action),
+            Some(ModerationAction::NotifyAdmin)
+        );
+    }
+
+    #[test]
+    fn test_policy_parse() {
+        let policy = ModerationPolicy::parse("low=warn_user, high=notify_admin").unwrap();
+        assert_eq!(policy.low, ModerationAction::WarnUser);
+        assert_eq!(policy.medium, ModerationAction::WarnUser);
+        assert_eq!(policy.high, ModerationAction::NotifyAdmin);
+
+        assert!(ModerationPolicy::parse("critical=ignore").is_err());
+        assert!(ModerationPolicy::parse("low").is_err());
+    }
+}
@@ -0,0 +1,248 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::error;
+
+use crate::line_api::LineApiClient;
+use crate::models::OutgoingMessage;
+use crate::utils::{ModerationAction, ModerationDecision, SensitiveDataMasker, Severity};
+
+/// 回報中節錄的最多字數
+const EXCERPT_CHARS: usize = 30;
+
+/// 摘要列出的使用者數
+const DIGEST_TOP_USERS: usize = 5;
+
+/// 一天（UTC）內的審核統計
+#[derive(Debug, Clone, PartialEq)]
+struct DailyTally {
+    date: NaiveDate,
+    flagged: BTreeMap<Severity, u32>,
+    /// 已即時回報的數量
+    reported: u32,
+    /// 超過每日上限、只列入摘要的數量
+    suppressed: u32,
+    /// 以遮罩後的使用者 ID 為鍵的命中次數
+    users: HashMap<String, u32>,
+}
+
+impl DailyTally {
+    fn new(date: NaiveDate) -> Self {
+        Self {
+            date,
+            flagged: BTreeMap::new(),
+            reported: 0,
+            suppressed: 0,
+            users: HashMap::new(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.flagged.is_empty()
+    }
+
+    fn digest(&self) -> String {
+        let total: u32 = self.flagged.values().sum();
+        let mut text = format!(
+            "📋 {} 內容審核摘要（UTC）\n命中禁用詞：{} 則",
+            self.date, total
+        );
+        for (severity, count) in self.flagged.iter().rev() {
+            text.push_str(&format!("\n- {}：{} 則", severity.as_str(), count));
+        }
+        text.push_str(&format!(
+            "\n即時回報 {} 則，超過每日上限未回報 {} 則",
+            self.reported, self.suppressed
+        ));
+        let mut users: Vec<(&String, &u32)> = self.users.iter().collect();
+        users.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        if !users.is_empty() {
+            text.push_str("\n最常命中的使用者：");
+            for (user, count) in users.into_iter().take(DIGEST_TOP_USERS) {
+                text.push_str(&format!("\n- {}：{} 則", user, count));
+            }
+        }
+        text
+    }
+}
+
+#[derive(Debug)]
+struct ReportState {
+    today: DailyTally,
+    /// 已結束、還沒送出摘要的那一天
+    pending_digest: Option<DailyTally>,
+}
+
+/// 把審核結果回報給管理者
+///
+/// 處置為 [`ModerationAction::NotifyAdmin`] 的訊息會立即推播遮罩後的使用者 ID、節錄與嚴重程度，
+/// 每天最多 `daily_limit` 則；所有命中禁用詞的訊息（包含超過上限的）另外彙整成每日摘要，
+/// 在 UTC 換日後由 [`start_digest`](Self::start_digest) 送出。
+#[derive(Clone)]
+pub struct ModerationReporter {
+    client: LineApiClient,
+    targets: Vec<String>,
+    daily_limit: u32,
+    state: Arc<Mutex<ReportState>>,
+}
+
+impl ModerationReporter {
+    /// `targets` 可以是使用者 ID 或群組 ID
+    pub fn new(client: LineApiClient, targets: Vec<String>, daily_limit: u32) -> Self {
+        Self {
+            client,
+            targets,
+            daily_limit,
+            state: Arc::new(Mutex::new(ReportState {
+                today: DailyTally::new(Utc::now().date_naive()),
+                pending_digest: None,
+            })),
+        }
+    }
+
+    pub fn targets(&self) -> &[String] {
+        &self.targets
+    }
+
+    /// 記錄審核結果，需要即時回報時回傳回報內容
+    pub fn record(
+        &self,
+        user_id: &str,
+        text: &str,
+        decision: ModerationDecision,
+        now: DateTime<Utc>,
+    ) -> Option<String> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        roll_over(&mut state, now.date_naive());
+
+        let masked_user = SensitiveDataMasker::mask_user_id(user_id);
+        let tally = &mut state.today;
+        *tally.flagged.entry(decision.severity).or_default() += 1;
+        *tally.users.entry(masked_user.clone()).or_default() += 1;
+        if decision.action != ModerationAction::NotifyAdmin {
+            return None;
+        }
+        if tally.reported >= self.daily_limit {
+            tally.suppressed += 1;
+            return None;
+        }
+        tally.reported += 1;
+        Some(format!(
+            "⚠️ 內容審核回報\n使用者：{}\n嚴重程度：{}\n節錄：{}",
+            masked_user,
+            decision.severity.as_str(),
+            excerpt(text)
+        ))
+    }
+
+    /// 記錄審核結果並在需要時推播回報
+    pub async fn report(&self, user_id: &str, text: &str, decision: ModerationDecision) {
+        if let Some(report) = self.record(user_id, text, decision, Utc::now()) {
+            self.push(report).await;
+        }
+    }
+
+    /// 換日後取出前一天的摘要，當天沒有命中時回傳 `None`
+    pub fn take_digest(&self, now: DateTime<Utc>) -> Option<String> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        roll_over(&mut state, now.date_naive());
+        state.pending_digest.take().map(|tally| tally.digest())
+    }
+
+    /// 定期檢查是否換日並送出摘要
+    pub fn start_digest(&self, interval: Duration) {
+        let reporter = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Some(digest) = reporter.take_digest(Utc::now()) {
+                    reporter.push(digest).await;
+                }
+            }
+        });
+    }
+
+    async fn push(&self, text: String) {
+        for target in &self.targets {
+            if let Err(e) = self
+                .client
+                .push_message(target, vec![OutgoingMessage::text(text.clone())])
+                .await
+            {
+                error!("Failed to send moderation report: {}", e);
+            }
+        }
+    }
+}
+
+/// 換日時把當天的統計移到待送摘要；沒有命中的日子不送摘要
+fn roll_over(state: &mut ReportState, date: NaiveDate) {
+    if date <= state.today.date {
+        return;
+    }
+    let finished = std::mem::replace(&mut state.today, DailyTally::new(date));
+    if !finished.is_empty() {
+        state.pending_digest = Some(finished);
+    }
+}
+
+/// 取前幾個字並把換行改成空白
+fn excerpt(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= EXCERPT_CHARS {
+        return text;
+    }
+    let mut excerpt: String = text.chars().take(EXCERPT_CHARS).collect();
+    excerpt.push('…');
+    excerpt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration as ChronoDuration;
+
+    const USER_ID: &str = "U1234567890abcdef1234567890abcdef";
+
+    fn decision(severity: Severity, action: ModerationAction) -> ModerationDecision {
+        ModerationDecision { severity, action }
+    }
+
+    #[tokio::test]
+    async fn test_reports_are_limited_and_digested() {
+        let client = LineApiClient::builder("token")
+            .dry_run(true)
+            .build()
+            .unwrap();
+        let reporter = ModerationReporter::new(client, vec!["Cadmin".to_string()], 1);
+        let now = Utc::now();
+
+        let report = reporter
+            .record(
+                USER_ID,
+                "這是一段很長很長的訊息，裡面有 free money 的字眼，還有更多更多的內容",
+                decision(Severity::High, ModerationAction::NotifyAdmin),
+                now,
+            )
+            .unwrap();
+        assert!(report.contains("U12...def"));
+        assert!(!report.contains(USER_ID));
+        assert!(report.contains("high"));
+        assert!(report.ends_with('…'));
+
+        // 超過每日上限與非 notify_admin 的處置只列入摘要
+        let high = decision(Severity::High, ModerationAction::NotifyAdmin);
+        assert_eq!(reporter.record(USER_ID, "free money", high, now), None);
+        let low = decision(Severity::Low, ModerationAction::Ignore);
+        assert_eq!(reporter.record("U2", "darn", low, now), None);
+
+        assert_eq!(reporter.take_digest(now), None);
+        let digest = reporter.take_digest(now + ChronoDuration::days(1)).unwrap();
+        assert!(digest.contains("命中禁用詞：3 則"));
+        assert!(digest.contains("未回報 1 則"));
+        assert!(digest.contains("U12...def：2 則"));
+        assert_eq!(reporter.take_digest(now + ChronoDuration::days(2)), None);
+    }
+}
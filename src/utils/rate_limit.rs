@@ -1,10 +1,11 @@
 use axum::{
-    extract::{ConnectInfo, Request},
-    http::StatusCode,
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
 };
 use dashmap::DashMap;
+use serde::Deserialize;
 use std::{
     net::SocketAddr,
     sync::Arc,
@@ -13,6 +14,8 @@ use std::{
 use tokio::time::sleep;
 use tracing::{debug, warn};
 
+use crate::utils::StatsAggregator;
+
 /// 速率限制條目
 #[derive(Debug, Clone)]
 struct RateLimitEntry {
@@ -54,11 +57,16 @@ impl RateLimitEntry {
 }
 
 /// 速率限制器配置
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
 pub struct RateLimitConfig {
     pub max_requests: u32,
     pub window_duration: Duration,
     pub cleanup_interval: Duration,
+    /// 最多追蹤的鍵數，超過時移除最久沒有請求的鍵，避免偽造來源 IP 讓表格無限成長
+    pub max_entries: usize,
+    /// 另外送出 IETF 草案（draft-ietf-httpapi-ratelimit-headers）的 `RateLimit-*` 標頭
+    pub ietf_headers: bool,
 }
 
 impl Default for RateLimitConfig {
@@ -67,6 +75,8 @@ impl Default for RateLimitConfig {
             max_requests: 10,
             window_duration: Duration::from_secs(60),
             cleanup_interval: Duration::from_secs(300), // 5 分鐘
+            max_entries: 10_000,
+            ietf_headers: false,
         }
     }
 }
@@ -76,27 +86,35 @@ impl Default for RateLimitConfig {
 pub struct RateLimiter {
     entries: Arc<DashMap<String, RateLimitEntry>>,
     config: RateLimitConfig,
+    stats: Option<Arc<StatsAggregator>>,
 }
 
 impl RateLimiter {
     pub fn new(config: RateLimitConfig) -> Self {
-        let rate_limiter = Self {
+        Self {
             entries: Arc::new(DashMap::new()),
             config,
-        };
+            stats: None,
+        }
+    }
 
-        // 啟動清理任務
-        let cleanup_entries = rate_limiter.entries.clone();
-        let cleanup_config = rate_limiter.config.clone();
+    /// 依 `cleanup_interval` 定期清除過期的條目
+    pub fn start_cleanup(&self) {
+        let entries = self.entries.clone();
+        let config = self.config.clone();
         tokio::spawn(async move {
-            let mut interval = tokio::time::interval(cleanup_config.cleanup_interval);
+            let mut interval = tokio::time::interval(config.cleanup_interval);
             loop {
                 interval.tick().await;
-                Self::cleanup_expired_entries(&cleanup_entries, &cleanup_config);
+                Self::cleanup_expired_entries(&entries, &config);
             }
         });
+    }
 
-        rate_limiter
+    /// 將速率限制觸發次數回報到行程內統計
+    pub fn with_stats(mut self, stats: Arc<StatsAggregator>) -> Self {
+        self.stats = Some(stats);
+        self
     }
 
     pub fn check_rate_limit(&self, key: &str) -> RateLimitResult {
@@ -114,7 +132,12 @@ impl RateLimiter {
 
             // 檢查是否超過限制
             if entry.count >= self.config.max_requests {
+                // 被拒絕的請求也算使用，持續送出請求的來源不會因為表格已滿被移除而重新計算
+                entry.last_request = now;
                 warn!("Rate limit exceeded for key: {}", key);
+                if let Some(stats) = &self.stats {
+                    stats.record_rate_limit_hit();
+                }
                 return RateLimitResult::Exceeded {
                     retry_after: entry.time_until_reset(self.config.window_duration),
                 };
@@ -127,6 +150,9 @@ impl RateLimiter {
             }
         } else {
             // 新條目
+            if self.entries.len() >= self.config.max_entries {
+                self.evict_least_recently_used();
+            }
             self.entries.insert(key.to_string(), RateLimitEntry::new());
             RateLimitResult::Allowed {
                 remaining: self.config.max_requests - 1,
@@ -135,6 +161,73 @@ impl RateLimiter {
         }
     }
 
+    /// 依檢查結果加上速率限制標頭，上限與窗口取自實際設定
+    ///
+    /// 一律送出 `X-RateLimit-Limit`、`X-RateLimit-Remaining` 與 `X-RateLimit-Reset`（距離窗口重置的秒數），
+    /// 超過限制時加上 `Retry-After`；啟用 `ietf_headers` 時另外送出 `RateLimit-Limit`、
+    /// `RateLimit-Remaining`、`RateLimit-Reset` 與 `RateLimit-Policy`。
+    pub fn insert_headers(&self, headers: &mut HeaderMap, result: &RateLimitResult) {
+        let (remaining, reset_after) = match result {
+            RateLimitResult::Allowed {
+                remaining,
+                reset_after,
+            } => (*remaining, *reset_after),
+            RateLimitResult::Exceeded { retry_after } => {
+                headers.insert("Retry-After", HeaderValue::from(ceil_secs(*retry_after)));
+                (0, *retry_after)
+            }
+        };
+        let limit = HeaderValue::from(self.config.max_requests);
+        let remaining = HeaderValue::from(remaining);
+        let reset = HeaderValue::from(ceil_secs(reset_after));
+
+        if self.config.ietf_headers {
+            headers.insert("RateLimit-Limit", limit.clone());
+            headers.insert("RateLimit-Remaining", remaining.clone());
+            headers.insert("RateLimit-Reset", reset.clone());
+            let policy = format!(
+                "{};w={}",
+                self.config.max_requests,
+                ceil_secs(self.config.window_duration)
+            );
+            if let Ok(policy) = HeaderValue::from_str(&policy) {
+                headers.insert("RateLimit-Policy", policy);
+            }
+        }
+        headers.insert("X-RateLimit-Limit", limit);
+        headers.insert("X-RateLimit-Remaining", remaining);
+        headers.insert("X-RateLimit-Reset", reset);
+    }
+
+    /// 目前追蹤的鍵數
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 表格已滿時一次移除最久沒有請求的一成條目，分攤掃描整個表格的成本
+    fn evict_least_recently_used(&self) {
+        let mut entries: Vec<(String, Instant)> = self
+            .entries
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().last_request))
+            .collect();
+        let count = (self.config.max_entries / 10).max(1).min(entries.len());
+        if count == 0 {
+            return;
+        }
+        if count < entries.len() {
+            entries.select_nth_unstable_by_key(count - 1, |(_, last_request)| *last_request);
+        }
+        for (key, _) in entries.into_iter().take(count) {
+            self.entries.remove(&key);
+        }
+        debug!("Evicted {} least recently used rate limit entries", count);
+    }
+
     fn cleanup_expired_entries(
         entries: &DashMap<String, RateLimitEntry>,
         config: &RateLimitConfig,
@@ -173,6 +266,11 @@ pub enum RateLimitResult {
     },
 }
 
+/// 無條件進位的秒數，剩餘不到一秒時不會顯示為 0
+fn ceil_secs(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
+
 /// 從請求中提取速率限制鍵
 fn extract_rate_limit_key(request: &Request) -> String {
     // 優先使用 X-Forwarded-For 頭
@@ -199,94 +297,51 @@ fn extract_rate_limit_key(request: &Request) -> String {
     "unknown".to_string()
 }
 
-/// 速率限制中介軟體
-pub async fn rate_limit_middleware(request: Request, next: Next) -> Response {
-    // 從應用狀態獲取速率限制器
-    // 這裡需要從應用狀態中提取 RateLimiter
-    let rate_limiter = RateLimiter::new(RateLimitConfig::default());
-
+/// 速率限制中介軟體，所有請求共用同一個限制器
+///
+/// 以 `middleware::from_fn_with_state(Arc<RateLimiter>, rate_limit_middleware)` 掛載，
+/// 回應標頭依限制器的設定計算。
+pub async fn rate_limit_middleware(
+    State(rate_limiter): State<Arc<RateLimiter>>,
+    request: Request,
+    next: Next,
+) -> Response {
     let key = extract_rate_limit_key(&request);
 
-    match rate_limiter.check_rate_limit(&key) {
-        RateLimitResult::Allowed {
-            remaining,
-            reset_after,
-        } => {
-            let mut response = next.run(request).await;
-            let headers = response.headers_mut();
-            headers.insert(
-                "X-RateLimit-Remaining",
-                remaining.to_string().parse().unwrap(),
-            );
-            headers.insert(
-                "X-RateLimit-Reset",
-                reset_after.as_secs().to_string().parse().unwrap(),
-            );
-            response
-        }
+    let result = rate_limiter.check_rate_limit(&key);
+    let mut response = match result {
+        RateLimitResult::Allowed { .. } => next.run(request).await,
         RateLimitResult::Exceeded { retry_after } => {
-            let retry_after_secs = retry_after.as_secs();
             warn!(
                 "Rate limit exceeded for {}, retry after {} seconds",
-                key, retry_after_secs
+                key,
+                ceil_secs(retry_after)
             );
 
-            let mut response = StatusCode::TOO_MANY_REQUESTS.into_response();
-            let headers = response.headers_mut();
-            headers.insert("Retry-After", retry_after_secs.to_string().parse().unwrap());
-            headers.insert("X-RateLimit-Remaining", "0".parse().unwrap());
-            response
+            // 添加延遲以減緩攻擊
+            sleep(Duration::from_millis(100)).await;
+
+            StatusCode::TOO_MANY_REQUESTS.into_response()
         }
-    }
+    };
+    rate_limiter.insert_headers(response.headers_mut(), &result);
+    response
 }
 
-/// 創建帶有自定義配置的速率限制中介軟體
+/// 創建帶有自定義配置的速率限制中介軟體，回傳的閉包共用同一個限制器
 pub fn create_rate_limit_middleware(
     config: RateLimitConfig,
 ) -> impl Fn(Request, Next) -> std::pin::Pin<Box<dyn std::future::Future<Output = Response> + Send>>
 + Clone {
     let rate_limiter = Arc::new(RateLimiter::new(config));
+    rate_limiter.start_cleanup();
 
     move |request: Request, next: Next| {
-        let rate_limiter = rate_limiter.clone();
-        Box::pin(async move {
-            let key = extract_rate_limit_key(&request);
-
-            match rate_limiter.check_rate_limit(&key) {
-                RateLimitResult::Allowed {
-                    remaining,
-                    reset_after,
-                } => {
-                    let mut response = next.run(request).await;
-                    let headers = response.headers_mut();
-                    headers.insert(
-                        "X-RateLimit-Remaining",
-                        remaining.to_string().parse().unwrap(),
-                    );
-                    headers.insert(
-                        "X-RateLimit-Reset",
-                        reset_after.as_secs().to_string().parse().unwrap(),
-                    );
-                    response
-                }
-                RateLimitResult::Exceeded { retry_after } => {
-                    let retry_after_secs = retry_after.as_secs();
-                    warn!(
-                        "Rate limit exceeded for {}, retry after {} seconds",
-                        key, retry_after_secs
-                    );
-
-                    // 添加延遲以減緩攻擊
-                    sleep(Duration::from_millis(100)).await;
-
-                    let mut response = StatusCode::TOO_MANY_REQUESTS.into_response();
-                    let headers = response.headers_mut();
-                    headers.insert("Retry-After", retry_after_secs.to_string().parse().unwrap());
-                    headers.insert("X-RateLimit-Remaining", "0".parse().unwrap());
-                    response
-                }
-            }
-        })
+        Box::pin(rate_limit_middleware(
+            State(rate_limiter.clone()),
+            request,
+            next,
+        ))
     }
 }
 
@@ -325,6 +380,8 @@ mod tests {
             max_requests: 5,
             window_duration: Duration::from_secs(60),
             cleanup_interval: Duration::from_secs(300),
+            max_entries: 10_000,
+            ietf_headers: false,
         };
 
         let limiter = RateLimiter::new(config);
@@ -347,6 +404,142 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_rate_limiter_evicts_least_recently_used() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            max_requests: 1,
+            max_entries: 20,
+            ..Default::default()
+        });
+
+        limiter.check_rate_limit("attacker-0");
+        std::thread::sleep(Duration::from_millis(2));
+        for i in 1..20 {
+            limiter.check_rate_limit(&format!("spoofed-{}", i));
+        }
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(matches!(
+            limiter.check_rate_limit("spoofed-1"),
+            RateLimitResult::Exceeded { .. }
+        ));
+
+        // 表格已滿時移除最久沒有請求的鍵，最近有請求的鍵保留原本的限制
+        limiter.check_rate_limit("spoofed-20");
+        assert!(!limiter.entries.contains_key("attacker-0"));
+        assert!(matches!(
+            limiter.check_rate_limit("spoofed-1"),
+            RateLimitResult::Exceeded { .. }
+        ));
+
+        for i in 21..1000 {
+            limiter.check_rate_limit(&format!("spoofed-{}", i));
+            assert!(limiter.len() <= 20);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_headers_follow_config() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            max_requests: 2,
+            window_duration: Duration::from_secs(30),
+            ..Default::default()
+        });
+        let mut headers = HeaderMap::new();
+        limiter.insert_headers(&mut headers, &limiter.check_rate_limit("key"));
+        assert_eq!(headers["X-RateLimit-Limit"], "2");
+        assert_eq!(headers["X-RateLimit-Remaining"], "1");
+        assert_eq!(headers["X-RateLimit-Reset"], "30");
+        assert!(!headers.contains_key("RateLimit-Limit"));
+
+        let limiter = RateLimiter::new(RateLimitConfig {
+            max_requests: 1,
+            window_duration: Duration::from_millis(1500),
+            ietf_headers: true,
+            ..Default::default()
+        });
+        limiter.check_rate_limit("key");
+        let mut headers = HeaderMap::new();
+        limiter.insert_headers(&mut headers, &limiter.check_rate_limit("key"));
+        // 不到一秒的剩餘時間無條件進位
+        assert_eq!(headers["Retry-After"], "2");
+        assert_eq!(headers["X-RateLimit-Remaining"], "0");
+        assert_eq!(headers["RateLimit-Limit"], "1");
+        assert_eq!(headers["RateLimit-Remaining"], "0");
+        assert_eq!(headers["RateLimit-Reset"], "2");
+        assert_eq!(headers["RateLimit-Policy"], "1;w=2");
+    }
+
+    fn limited_router(limiter: Arc<RateLimiter>) -> axum::Router {
+        axum::Router::new()
+            .route("/", axum::routing::get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(
+                limiter,
+                rate_limit_middleware,
+            ))
+    }
+
+    fn request_from(ip: &str) -> Request {
+        Request::builder()
+            .uri("/")
+            .header("x-forwarded-for", ip)
+            .body(axum::body::Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_middleware_shares_limiter() {
+        use tower::ServiceExt;
+
+        let limiter = Arc::new(RateLimiter::new(RateLimitConfig {
+            max_requests: 2,
+            ..Default::default()
+        }));
+        let router = limited_router(limiter.clone());
+
+        for remaining in ["1", "0"] {
+            let response = router
+                .clone()
+                .oneshot(request_from("203.0.113.1"))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers()["X-RateLimit-Limit"], "2");
+            assert_eq!(response.headers()["X-RateLimit-Remaining"], remaining);
+        }
+        let response = router
+            .clone()
+            .oneshot(request_from("203.0.113.1"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        // 其他來源不受影響
+        let response = router.oneshot(request_from("203.0.113.2")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(limiter.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_middleware_bounded_with_rotating_forwarded_for() {
+        use tower::ServiceExt;
+
+        let limiter = Arc::new(RateLimiter::new(RateLimitConfig {
+            max_requests: 1,
+            max_entries: 50,
+            ..Default::default()
+        }));
+        let router = limited_router(limiter.clone());
+
+        // 每個請求偽造不同的 X-Forwarded-For，表格仍不超過上限
+        for i in 0..500 {
+            let ip = format!("198.51.{}.{}", i / 256, i % 256);
+            let response = router.clone().oneshot(request_from(&ip)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert!(limiter.len() <= 50);
+        }
+        assert_eq!(limiter.len(), 50);
+    }
+
     #[test]
     fn test_rate_limit_config_default() {
         let config = RateLimitConfig::default();
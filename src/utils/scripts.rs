@@ -0,0 +1,278 @@
+//! 以 Rhai 腳本定義的回覆規則
+//!
+//! 目錄下每個 `<名稱>.rhai` 是一條規則，依檔名順序比對。腳本需定義兩個函式：
+//!
+//! ```rhai
+//! fn pattern() { "^天氣\\s*(.+)$" }
+//!
+//! fn reply(ctx) {
+//!     // ctx.text、ctx.user_id、ctx.captures（ctx.captures[0] 為整段符合的文字）
+//!     if ctx.captures[1] == "台北" {
+//!         return ["台北今天晴", #{ type: "sticker", packageId: "446", stickerId: "1988" }];
+//!     }
+//!     ()
+//! }
+//! ```
+//!
+//! `reply` 回傳字串為文字訊息，物件為 LINE 訊息物件，陣列可混合多則；回傳 `()` 時改由下一條規則處理。
+//! 搭配 [`ReplyScripts::watch`] 時修改腳本會自動重新載入，不用重新編譯。
+
+use regex::Regex;
+use rhai::{AST, Dynamic, Engine, Scope};
+use serde_json::{Value, json};
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use crate::models::OutgoingMessage;
+use crate::storage::AuditLog;
+use crate::utils::watch_path;
+
+/// 腳本檔的副檔名
+const SCRIPT_EXTENSION: &str = "rhai";
+
+/// 單次執行可用的運算次數，避免無窮迴圈卡住 webhook
+const MAX_OPERATIONS: u64 = 100_000;
+
+#[derive(Debug)]
+pub struct ScriptError {
+    pub message: String,
+}
+
+impl ScriptError {
+    pub fn new<T: Into<String>>(message: T) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Script Error: {}", self.message)
+    }
+}
+
+impl Error for ScriptError {}
+
+struct ReplyScript {
+    name: String,
+    pattern: Regex,
+    ast: AST,
+}
+
+/// 從目錄載入的回覆腳本，複本共用同一份腳本
+#[derive(Clone)]
+pub struct ReplyScripts {
+    dir: Arc<PathBuf>,
+    engine: Arc<Engine>,
+    scripts: Arc<RwLock<Vec<ReplyScript>>>,
+}
+
+impl ReplyScripts {
+    pub fn from_dir(dir: impl Into<PathBuf>) -> Result<Self, ScriptError> {
+        let dir = dir.into();
+        let engine = create_engine();
+        let scripts = load(&engine, &dir)?;
+        Ok(Self {
+            dir: Arc::new(dir),
+            engine: Arc::new(engine),
+            scripts: Arc::new(RwLock::new(scripts)),
+        })
+    }
+
+    /// 重新讀取整個目錄，任一腳本有錯時保留原本的腳本；回傳腳本數量
+    pub fn reload(&self) -> Result<usize, ScriptError> {
+        let scripts = load(&self.engine, &self.dir)?;
+        let count = scripts.len();
+        *self.scripts.write().unwrap() = scripts;
+        Ok(count)
+    }
+
+    pub fn len(&self) -> usize {
+        self.scripts.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 依序執行符合的腳本，全部回傳 `()` 或沒有符合時回傳 `None`
+    pub fn reply(
+        &self,
+        user_id: &str,
+        text: &str,
+    ) -> Result<Option<Vec<OutgoingMessage>>, ScriptError> {
+        let scripts = self.scripts.read().unwrap();
+        for script in scripts.iter() {
+            let Some(captures) = script.pattern.captures(text) else {
+                continue;
+            };
+            let captures: Vec<&str> = captures
+                .iter()
+                .map(|capture| capture.map_or("", |capture| capture.as_str()))
+                .collect();
+            let ctx = rhai::serde::to_dynamic(json!({
+                "text": text,
+                "user_id": user_id,
+                "captures": captures,
+            }))
+            .map_err(|e| ScriptError::new(e.to_string()))?;
+
+            let result: Dynamic = self
+                .engine
+                .call_fn(&mut Scope::new(), &script.ast, "reply", (ctx,))
+                .map_err(|e| ScriptError::new(format!("{}: {}", script.name, e)))?;
+            if result.is_unit() {
+                continue;
+            }
+            let messages = to_messages(&result)
+                .map_err(|e| ScriptError::new(format!("{}: {}", script.name, e)))?;
+            return Ok(Some(messages));
+        }
+        Ok(None)
+    }
+
+    /// 定期檢查目錄內檔案的修改時間，有變更時重新載入，並寫入稽核紀錄
+    pub fn watch(&self, interval: Duration, audit_log: Option<AuditLog>) {
+        let scripts = self.clone();
+        watch_path(
+            self.dir.to_path_buf(),
+            interval,
+            "reply_scripts",
+            "reply scripts",
+            audit_log,
+            move |_| scripts.reload(),
+        );
+    }
+}
+
+fn create_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_call_levels(32);
+    engine.set_max_expr_depths(64, 32);
+    engine.set_max_string_size(10_000);
+    engine.set_max_array_size(1_000);
+    engine.set_max_map_size(1_000);
+    engine
+}
+
+fn load(engine: &Engine, dir: &Path) -> Result<Vec<ReplyScript>, ScriptError> {
+    let entries = std::fs::read_dir(dir).map_err(|e| {
+        ScriptError::new(format!(
+            "Failed to read script directory {}: {}",
+            dir.display(),
+            e
+        ))
+    })?;
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == SCRIPT_EXTENSION))
+        .collect();
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| {
+            let name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let error = |message: String| ScriptError::new(format!("{}: {}", name, message));
+            let ast = engine
+                .compile_file(path.clone())
+                .map_err(|e| error(e.to_string()))?;
+            let pattern: String = engine
+                .call_fn(&mut Scope::new(), &ast, "pattern", ())
+                .map_err(|e| error(format!("pattern() failed: {}", e)))?;
+            let pattern = Regex::new(&pattern).map_err(|e| error(e.to_string()))?;
+            Ok(ReplyScript { name, pattern, ast })
+        })
+        .collect()
+}
+
+/// 字串為文字訊息，物件為 LINE 訊息物件，陣列為多則訊息
+fn to_messages(result: &Dynamic) -> Result<Vec<OutgoingMessage>, String> {
+    let value: Value = rhai::serde::from_dynamic(result).map_err(|e| e.to_string())?;
+    let values = match value {
+        Value::Array(values) => values,
+        value => vec![value],
+    };
+    values
+        .into_iter()
+        .map(|value| match value {
+            Value::String(text) => Ok(OutgoingMessage::text(text)),
+            value @ Value::Object(_) => {
+                serde_json::from_value(value).map_err(|e| format!("Invalid message: {}", e))
+            }
+            value => Err(format!("Unsupported reply value: {}", value)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reply_scripts() {
+        let dir = std::env::temp_dir().join(format!("linebot-scripts-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("10-weather.rhai"),
+            r#"
+            fn pattern() { "^天氣\\s*(.+)$" }
+            fn reply(ctx) {
+                if ctx.captures[1] == "台北" {
+                    return ["台北今天晴", #{ type: "sticker", packageId: "446", stickerId: "1988" }];
+                }
+                ()
+            }
+            "#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("20-fallback.rhai"),
+            r#"
+            fn pattern() { "^天氣" }
+            fn reply(ctx) { "查不到 " + ctx.user_id + " 要的城市" }
+            "#,
+        )
+        .unwrap();
+
+        let scripts = ReplyScripts::from_dir(&dir).unwrap();
+        assert_eq!(scripts.len(), 2);
+        assert_eq!(
+            scripts.reply("U1", "天氣 台北").unwrap(),
+            Some(vec![
+                OutgoingMessage::text("台北今天晴"),
+                OutgoingMessage::sticker("446", "1988"),
+            ])
+        );
+        assert_eq!(
+            scripts.reply("U1", "天氣 火星").unwrap(),
+            Some(vec![OutgoingMessage::text("查不到 U1 要的城市")])
+        );
+        assert_eq!(scripts.reply("U1", "你好").unwrap(), None);
+
+        // 無窮迴圈會被運算次數上限中斷
+        std::fs::write(
+            dir.join("00-loop.rhai"),
+            r#"fn pattern() { "^loop$" } fn reply(ctx) { loop {} }"#,
+        )
+        .unwrap();
+        assert_eq!(scripts.reload().unwrap(), 3);
+        assert!(scripts.reply("U1", "loop").is_err());
+
+        // 語法錯誤時保留原本的腳本
+        std::fs::write(dir.join("30-broken.rhai"), "fn pattern( {").unwrap();
+        assert!(scripts.reload().is_err());
+        assert_eq!(scripts.len(), 3);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
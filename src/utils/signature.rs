@@ -1,15 +1,27 @@
 use base64::{Engine, engine::general_purpose::STANDARD};
 use hmac::{Hmac, Mac};
 use sha2::Sha256;
+use subtle::ConstantTimeEq;
 
 type HmacSha256 = Hmac<Sha256>;
 
+/// 驗證 `x-line-signature`
+///
+/// LINE 送出的是未加前綴的 Base64 HMAC，也接受帶 `sha256=` 前綴的格式。
 pub fn verify_signature(channel_secret: &str, body: &[u8], signature: &str) -> bool {
-    let signature = match signature.strip_prefix("sha256=") {
-        Some(sig) => sig,
-        None => return false,
-    };
+    let signature = signature.strip_prefix("sha256=").unwrap_or(signature);
+    verify_encoded(channel_secret, body, signature)
+}
+
+/// 嚴格模式：只接受帶 `sha256=` 前綴的簽名
+pub fn verify_prefixed_signature(channel_secret: &str, body: &[u8], signature: &str) -> bool {
+    match signature.strip_prefix("sha256=") {
+        Some(signature) => verify_encoded(channel_secret, body, signature),
+        None => false,
+    }
+}
 
+fn verify_encoded(channel_secret: &str, body: &[u8], signature: &str) -> bool {
     let decoded_signature = match STANDARD.decode(signature) {
         Ok(decoded) => decoded,
         Err(_) => return false,
@@ -25,6 +37,69 @@ pub fn verify_signature(channel_secret: &str, body: &[u8], signature: &str) -> b
     mac.verify_slice(&decoded_signature).is_ok()
 }
 
+/// 以固定時間比較 token，避免由回應時間逐字猜出內容；只有長度會影響比較時間
+pub fn token_matches(provided: Option<&str>, expected: &str) -> bool {
+    provided.is_some_and(|provided| bool::from(provided.as_bytes().ct_eq(expected.as_bytes())))
+}
+
+/// 依序以每個 secret 驗證，回傳第一個相符的索引
+pub fn find_matching_secret<S: AsRef<str>>(
+    channel_secrets: &[S],
+    body: &[u8],
+    signature: &str,
+    require_prefix: bool,
+) -> Option<usize> {
+    let mut hasher = SignatureHasher::new(channel_secrets);
+    hasher.update(body);
+    hasher.find_match(signature, require_prefix)
+}
+
+/// 逐段計算每個 secret 的 HMAC，不必先取得完整內容即可驗證簽名
+pub struct SignatureHasher {
+    macs: Vec<HmacSha256>,
+}
+
+impl SignatureHasher {
+    pub fn new<S: AsRef<str>>(channel_secrets: &[S]) -> Self {
+        Self {
+            macs: channel_secrets
+                .iter()
+                .map(|secret| {
+                    HmacSha256::new_from_slice(secret.as_ref().as_bytes())
+                        .expect("HMAC accepts any key size")
+                })
+                .collect(),
+        }
+    }
+
+    pub fn update(&mut self, chunk: &[u8]) {
+        for mac in &mut self.macs {
+            mac.update(chunk);
+        }
+    }
+
+    /// 回傳第一個與簽名相符的 secret 索引，規則同 [`find_matching_secret`]
+    pub fn find_match(self, signature: &str, require_prefix: bool) -> Option<usize> {
+        let encoded = match signature.strip_prefix("sha256=") {
+            Some(encoded) => encoded,
+            None if require_prefix => return None,
+            None => signature,
+        };
+        let decoded = STANDARD.decode(encoded).ok()?;
+        self.macs
+            .into_iter()
+            .position(|mac| mac.verify_slice(&decoded).is_ok())
+    }
+}
+
+/// 以 channel secret 計算內容的 Base64 HMAC-SHA256 簽名
+pub fn generate_signature(channel_secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(channel_secret.as_bytes()).expect("HMAC accepts any key size");
+    mac.update(body);
+    STANDARD.encode(mac.finalize().into_bytes())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -44,4 +119,69 @@ mod tests {
         assert!(verify_signature(channel_secret, body, &signature_header));
         assert!(!verify_signature(channel_secret, body, "invalid_signature"));
     }
+
+    #[test]
+    fn test_generate_signature_roundtrip() {
+        let signature = generate_signature("test_secret", b"test_body");
+        let header = format!("sha256={}", signature);
+        assert!(verify_signature("test_secret", b"test_body", &header));
+        assert!(!verify_signature("other_secret", b"test_body", &header));
+    }
+
+    #[test]
+    fn test_find_matching_secret() {
+        let secrets = ["new_secret", "old_secret"];
+        let signature = generate_signature("old_secret", b"test_body");
+
+        assert_eq!(
+            find_matching_secret(&secrets, b"test_body", &signature, false),
+            Some(1)
+        );
+        assert_eq!(
+            find_matching_secret(&secrets[..1], b"test_body", &signature, false),
+            None
+        );
+    }
+
+    #[test]
+    fn test_signature_hasher_streams_chunks() {
+        let secrets = ["new_secret", "old_secret"];
+        let signature = generate_signature("old_secret", b"test_body");
+
+        let mut hasher = SignatureHasher::new(&secrets);
+        hasher.update(b"test_");
+        hasher.update(b"body");
+        assert_eq!(hasher.find_match(&signature, false), Some(1));
+
+        let mut hasher = SignatureHasher::new(&secrets);
+        hasher.update(b"test_body");
+        assert_eq!(hasher.find_match(&signature, true), None);
+    }
+
+    #[test]
+    fn test_signature_prefix_optional_unless_strict() {
+        let signature = generate_signature("test_secret", b"test_body");
+        let prefixed = format!("sha256={}", signature);
+
+        assert!(verify_signature("test_secret", b"test_body", &signature));
+        assert!(verify_signature("test_secret", b"test_body", &prefixed));
+        assert!(verify_prefixed_signature(
+            "test_secret",
+            b"test_body",
+            &prefixed
+        ));
+        assert!(!verify_prefixed_signature(
+            "test_secret",
+            b"test_body",
+            &signature
+        ));
+    }
+
+    #[test]
+    fn test_token_matches() {
+        assert!(token_matches(Some("secret"), "secret"));
+        assert!(!token_matches(Some("secreT"), "secret"));
+        assert!(!token_matches(Some("secret-longer"), "secret"));
+        assert!(!token_matches(None, "secret"));
+    }
 }
@@ -0,0 +1,150 @@
+use dashmap::DashMap;
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Instant,
+};
+
+/// 行程內統計聚合器
+///
+/// Prometheus 指標適合長期監控，但無法在行程內直接讀回；
+/// 這裡另外保存一份簡單的計數，供 `/admin/stats` 與儀表板使用。
+#[derive(Debug)]
+pub struct StatsAggregator {
+    started_at: Instant,
+    events: DashMap<String, AtomicU64>,
+    event_errors: AtomicU64,
+    line_api_requests: AtomicU64,
+    line_api_errors: AtomicU64,
+    rate_limit_hits: AtomicU64,
+}
+
+impl StatsAggregator {
+    pub fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+            events: DashMap::new(),
+            event_errors: AtomicU64::new(0),
+            line_api_requests: AtomicU64::new(0),
+            line_api_errors: AtomicU64::new(0),
+            rate_limit_hits: AtomicU64::new(0),
+        }
+    }
+
+    /// 記錄收到的事件
+    pub fn record_event(&self, event_type: &str) {
+        if let Some(counter) = self.events.get(event_type) {
+            counter.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        self.events
+            .entry(event_type.to_string())
+            .or_insert_with(|| AtomicU64::new(0))
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 記錄事件處理失敗
+    pub fn record_event_error(&self) {
+        self.event_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 記錄 LINE API 呼叫結果
+    pub fn record_line_api_request(&self, success: bool) {
+        self.line_api_requests.fetch_add(1, Ordering::Relaxed);
+        if !success {
+            self.line_api_errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// 記錄速率限制被觸發
+    pub fn record_rate_limit_hit(&self) {
+        self.rate_limit_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 取得目前統計快照
+    pub fn snapshot(&self) -> StatsSnapshot {
+        let events: BTreeMap<String, u64> = self
+            .events
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().load(Ordering::Relaxed)))
+            .collect();
+        let total_events: u64 = events.values().sum();
+        let event_errors = self.event_errors.load(Ordering::Relaxed);
+        let line_api_requests = self.line_api_requests.load(Ordering::Relaxed);
+        let line_api_errors = self.line_api_errors.load(Ordering::Relaxed);
+
+        StatsSnapshot {
+            uptime_seconds: self.started_at.elapsed().as_secs(),
+            total_events,
+            events,
+            event_errors,
+            event_error_rate: ratio(event_errors, total_events),
+            line_api_requests,
+            line_api_errors,
+            line_api_error_rate: ratio(line_api_errors, line_api_requests),
+            rate_limit_hits: self.rate_limit_hits.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Default for StatsAggregator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn ratio(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+
+/// 統計快照
+#[derive(Debug, Clone, Serialize)]
+pub struct StatsSnapshot {
+    pub uptime_seconds: u64,
+    pub total_events: u64,
+    pub events: BTreeMap<String, u64>,
+    pub event_errors: u64,
+    pub event_error_rate: f64,
+    pub line_api_requests: u64,
+    pub line_api_errors: u64,
+    pub line_api_error_rate: f64,
+    pub rate_limit_hits: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_snapshot_counts() {
+        let stats = StatsAggregator::new();
+        stats.record_event("message");
+        stats.record_event("message");
+        stats.record_event("follow");
+        stats.record_event_error();
+        stats.record_line_api_request(true);
+        stats.record_line_api_request(false);
+        stats.record_rate_limit_hit();
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.total_events, 3);
+        assert_eq!(snapshot.events.get("message"), Some(&2));
+        assert_eq!(snapshot.events.get("follow"), Some(&1));
+        assert_eq!(snapshot.event_errors, 1);
+        assert_eq!(snapshot.line_api_requests, 2);
+        assert_eq!(snapshot.line_api_error_rate, 0.5);
+        assert_eq!(snapshot.rate_limit_hits, 1);
+    }
+
+    #[test]
+    fn test_stats_error_rate_without_events() {
+        let snapshot = StatsAggregator::new().snapshot();
+        assert_eq!(snapshot.event_error_rate, 0.0);
+        assert_eq!(snapshot.line_api_error_rate, 0.0);
+    }
+}
@@ -0,0 +1,128 @@
+//! systemd 整合：socket activation 與 readiness 通知
+//!
+//! 非 systemd 環境（或非 unix 平台）下所有函式都不做任何事。
+
+use std::env;
+
+/// socket activation 傳入的第一個 fd（SD_LISTEN_FDS_START）
+#[cfg(unix)]
+const SD_LISTEN_FDS_START: i32 = 3;
+
+/// 取得 systemd 透過 `LISTEN_FDS` 傳入的監聽 socket
+///
+/// 只有當 `LISTEN_PID` 等於目前行程時才會使用，避免子行程誤用繼承的環境變數；
+/// 同一個行程只會接手一次，之後的呼叫回傳 `None`。
+#[cfg(unix)]
+pub fn take_activated_listener() -> std::io::Result<Option<std::net::TcpListener>> {
+    use std::os::unix::io::FromRawFd;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    // 此時已在多執行緒的 runtime 中，修改環境變數並不安全，改以旗標記錄 fd 已被接手
+    static TAKEN: AtomicBool = AtomicBool::new(false);
+
+    if listen_fds_for_current_process() == 0 || TAKEN.swap(true, Ordering::SeqCst) {
+        return Ok(None);
+    }
+
+    // 只使用第一個 fd
+    // SAFETY: systemd 保證 fd 3 在 LISTEN_FDS >= 1 時是有效且屬於本行程的 socket，TAKEN 確保只包裝一次
+    let listener = unsafe { std::net::TcpListener::from_raw_fd(SD_LISTEN_FDS_START) };
+    listener.set_nonblocking(true)?;
+    Ok(Some(listener))
+}
+
+#[cfg(not(unix))]
+pub fn take_activated_listener() -> std::io::Result<Option<std::net::TcpListener>> {
+    Ok(None)
+}
+
+#[cfg(unix)]
+fn listen_fds_for_current_process() -> u32 {
+    let pid_matches = env::var("LISTEN_PID")
+        .ok()
+        .and_then(|pid| pid.parse::<u32>().ok())
+        .is_some_and(|pid| pid == std::process::id());
+
+    if !pid_matches {
+        return 0;
+    }
+
+    env::var("LISTEN_FDS")
+        .ok()
+        .and_then(|fds| fds.parse().ok())
+        .unwrap_or(0)
+}
+
+/// 通知 systemd 服務已就緒（`READY=1`），未設定 `NOTIFY_SOCKET` 時回傳 false
+pub fn notify_ready() -> std::io::Result<bool> {
+    notify("READY=1")
+}
+
+/// 傳送狀態字串到 `NOTIFY_SOCKET`
+#[cfg(unix)]
+pub fn notify(state: &str) -> std::io::Result<bool> {
+    use std::os::unix::net::UnixDatagram;
+
+    let Ok(socket_path) = env::var("NOTIFY_SOCKET") else {
+        return Ok(false);
+    };
+
+    let socket = UnixDatagram::unbound()?;
+
+    // `@` 開頭代表 Linux abstract namespace
+    #[cfg(target_os = "linux")]
+    if let Some(name) = socket_path.strip_prefix('@') {
+        use std::os::linux::net::SocketAddrExt;
+        let addr = std::os::unix::net::SocketAddr::from_abstract_name(name.as_bytes())?;
+        socket.send_to_addr(state.as_bytes(), &addr)?;
+        return Ok(true);
+    }
+
+    socket.send_to(state.as_bytes(), &socket_path)?;
+    Ok(true)
+}
+
+#[cfg(not(unix))]
+pub fn notify(_state: &str) -> std::io::Result<bool> {
+    Ok(false)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_listen_fds_ignored_for_other_pid() {
+        unsafe {
+            env::set_var("LISTEN_PID", "1");
+            env::set_var("LISTEN_FDS", "1");
+        }
+        assert_eq!(listen_fds_for_current_process(), 0);
+        unsafe {
+            env::remove_var("LISTEN_PID");
+            env::remove_var("LISTEN_FDS");
+        }
+    }
+
+    #[test]
+    fn test_notify_sends_ready() {
+        use std::os::unix::net::UnixDatagram;
+
+        let path = env::temp_dir().join(format!("linebot-notify-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let receiver = UnixDatagram::bind(&path).unwrap();
+
+        unsafe {
+            env::set_var("NOTIFY_SOCKET", &path);
+        }
+        assert!(notify_ready().unwrap());
+        unsafe {
+            env::remove_var("NOTIFY_SOCKET");
+        }
+
+        let mut buf = [0u8; 16];
+        let len = receiver.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"READY=1");
+        let _ = std::fs::remove_file(&path);
+    }
+}
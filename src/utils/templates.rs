@@ -0,0 +1,170 @@
+//! 回覆訊息樣板
+//!
+//! 樣板放在同一目錄下，檔名為 `<名稱>.txt`，使用 Tera 語法。修改文案只需編輯檔案，
+//! 不用重新編譯；搭配 [`ReplyTemplates::watch`] 時檔案變更會自動重新載入。
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tera::Tera;
+
+use crate::models::OutgoingMessage;
+use crate::storage::AuditLog;
+use crate::utils::{UserContentSanitizer, watch_path};
+
+/// 樣板檔的副檔名
+const TEMPLATE_EXTENSION: &str = "txt";
+
+/// 從目錄載入的回覆樣板，複本共用同一份樣板
+#[derive(Clone)]
+pub struct ReplyTemplates {
+    dir: Arc<PathBuf>,
+    tera: Arc<RwLock<Tera>>,
+}
+
+impl ReplyTemplates {
+    pub fn from_dir(dir: impl Into<PathBuf>) -> Result<Self, tera::Error> {
+        let dir = dir.into();
+        let tera = load(&dir)?;
+        Ok(Self {
+            dir: Arc::new(dir),
+            tera: Arc::new(RwLock::new(tera)),
+        })
+    }
+
+    /// 重新讀取整個目錄，失敗時保留原本的樣板；回傳樣板數量
+    pub fn reload(&self) -> Result<usize, tera::Error> {
+        let tera = load(&self.dir)?;
+        let count = tera.get_template_names().count();
+        *self.tera.write().unwrap() = tera;
+        Ok(count)
+    }
+
+    pub fn has(&self, name: &str) -> bool {
+        self.tera
+            .read()
+            .unwrap()
+            .get_template_names()
+            .any(|template| template == file_name(name))
+    }
+
+    pub fn render(&self, name: &str, context: &TemplateContext) -> Result<String, tera::Error> {
+        self.tera
+            .read()
+            .unwrap()
+            .render(&file_name(name), &context.inner)
+    }
+
+    /// 渲染成文字訊息，結果為空白時回傳 `None`（不回覆）
+    pub fn render_message(
+        &self,
+        name: &str,
+        context: &TemplateContext,
+    ) -> Result<Option<OutgoingMessage>, tera::Error> {
+        let text = self.render(name, context)?;
+        let text = text.trim();
+        Ok((!text.is_empty()).then(|| OutgoingMessage::text(text)))
+    }
+
+    /// 定期檢查目錄內檔案的修改時間，有變更時重新載入，並寫入稽核紀錄
+    pub fn watch(&self, interval: Duration, audit_log: Option<AuditLog>) {
+        let templates = self.clone();
+        watch_path(
+            self.dir.to_path_buf(),
+            interval,
+            "reply_templates",
+            "reply templates",
+            audit_log,
+            move |_| templates.reload(),
+        );
+    }
+}
+
+/// 樣板變數，使用者提供的內容都會先經過 [`UserContentSanitizer`]
+#[derive(Debug, Clone, Default)]
+pub struct TemplateContext {
+    inner: tera::Context,
+    sanitizer: UserContentSanitizer,
+}
+
+impl TemplateContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `{{ user_name }}`
+    pub fn user_name(self, name: &str) -> Self {
+        self.insert("user_name", name)
+    }
+
+    /// `{{ args }}`（陣列）與 `{{ args_text }}`（原始字串）
+    pub fn args(mut self, args: &str) -> Self {
+        let args_text = self.sanitizer.sanitize(args.trim());
+        let args: Vec<&str> = args_text.split_whitespace().collect();
+        self.inner.insert("args", &args);
+        self.inner.insert("args_text", &args_text);
+        self
+    }
+
+    pub fn insert(mut self, key: &str, value: &str) -> Self {
+        self.inner.insert(key, &self.sanitizer.sanitize(value));
+        self
+    }
+
+    /// 不經過清理的值，只用於程式產生的內容
+    pub fn insert_trusted<T: serde::Serialize + ?Sized>(mut self, key: &str, value: &T) -> Self {
+        self.inner.insert(key, value);
+        self
+    }
+}
+
+fn file_name(name: &str) -> String {
+    format!("{}.{}", name, TEMPLATE_EXTENSION)
+}
+
+fn load(dir: &Path) -> Result<Tera, tera::Error> {
+    if !dir.is_dir() {
+        return Err(tera::Error::msg(format!(
+            "Template directory {} does not exist",
+            dir.display()
+        )));
+    }
+    Tera::new(&format!("{}/**/*.{}", dir.display(), TEMPLATE_EXTENSION))
+}
+
+/// 目錄（含子目錄）中最新的修改時間，刪除檔案時目錄本身的時間也會改變
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_from_dir() {
+        let dir = std::env::temp_dir().join(format!("linebot-templates-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("hello.txt"),
+            "你好 {{ user_name }}！{% if args %}參數：{{ args | join(sep=\",\") }}{% endif %}\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("silent.txt"), "{# 不回覆 #}").unwrap();
+
+        let templates = ReplyTemplates::from_dir(&dir).unwrap();
+        assert!(templates.has("hello"));
+        assert!(!templates.has("help"));
+
+        let context = TemplateContext::new().user_name("小明").args(" a  ${b} ");
+        assert_eq!(
+            templates.render_message("hello", &context).unwrap(),
+            Some(OutgoingMessage::text("你好 小明！參數：a,＄｛b｝"))
+        );
+        assert_eq!(templates.render_message("silent", &context).unwrap(), None);
+
+        std::fs::write(dir.join("help.txt"), "說明").unwrap();
+        assert_eq!(templates.reload().unwrap(), 3);
+        assert!(templates.has("help"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(templates.reload().is_err());
+        assert!(templates.has("help"));
+    }
+}
@@ -1,13 +1,44 @@
-use std::collections::HashSet;
+use regex::Regex;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::models::{Action, ApiError, CarouselColumn, OutgoingMessage, RichMenu, TemplateType};
+use crate::storage::AuditLog;
+use crate::utils::{Severity, watch_path};
+
+/// JSON 中會被遮罩的識別欄位
+const IDENTIFIER_FIELDS: &[&str] = &["userId", "groupId", "roomId", "replyToken", "to"];
 
 /// 輸入驗證錯誤
 #[derive(Debug, PartialEq)]
 pub enum ValidationError {
-    TooLong { max_length: usize, actual: usize },
-    TooShort { min_length: usize, actual: usize },
+    TooLong {
+        max_length: usize,
+        actual: usize,
+    },
+    TooShort {
+        min_length: usize,
+        actual: usize,
+    },
     InvalidCharacters,
     Forbidden,
     Empty,
+    InvalidUrl(String),
+    /// 超過 LINE 訊息格式的限制，`field` 為 JSON 路徑，例如 `messages[0].altText`
+    LimitExceeded {
+        field: String,
+        max: usize,
+        actual: usize,
+    },
+    /// 數量必須剛好為 `expected`，例如 confirm 樣板的按鈕數
+    CountMismatch {
+        field: String,
+        expected: usize,
+        actual: usize,
+    },
 }
 
 impl std::fmt::Display for ValidationError {
@@ -22,32 +53,294 @@ impl std::fmt::Display for ValidationError {
             ValidationError::InvalidCharacters => write!(f, "Contains invalid characters"),
             ValidationError::Forbidden => write!(f, "Contains forbidden content"),
             ValidationError::Empty => write!(f, "Input is empty"),
+            ValidationError::InvalidUrl(reason) => write!(f, "Invalid URL: {}", reason),
+            ValidationError::LimitExceeded { field, max, actual } => {
+                write!(f, "{} exceeds limit: {} > {}", field, actual, max)
+            }
+            ValidationError::CountMismatch {
+                field,
+                expected,
+                actual,
+            } => write!(f, "{} must have {} items, got {}", field, expected, actual),
         }
     }
 }
 
 impl std::error::Error for ValidationError {}
 
+/// 內建的禁用詞彙，未設定 `FORBIDDEN_WORDS_PATH` 時使用
+const DEFAULT_FORBIDDEN_WORDS: &[&str] = &["spam", "垃圾", "廣告"];
+
+/// 外觀與拉丁字母相同的希臘、西里爾字母
+const CONFUSABLES: &[(char, char)] = &[
+    ('а', 'a'),
+    ('в', 'b'),
+    ('с', 'c'),
+    ('ԁ', 'd'),
+    ('е', 'e'),
+    ('һ', 'h'),
+    ('і', 'i'),
+    ('ј', 'j'),
+    ('к', 'k'),
+    ('м', 'm'),
+    ('н', 'h'),
+    ('о', 'o'),
+    ('р', 'p'),
+    ('ԛ', 'q'),
+    ('ѕ', 's'),
+    ('т', 't'),
+    ('у', 'y'),
+    ('ԝ', 'w'),
+    ('х', 'x'),
+    ('α', 'a'),
+    ('β', 'b'),
+    ('ε', 'e'),
+    ('η', 'n'),
+    ('ι', 'i'),
+    ('κ', 'k'),
+    ('ν', 'v'),
+    ('ο', 'o'),
+    ('ρ', 'p'),
+    ('τ', 't'),
+    ('υ', 'u'),
+    ('χ', 'x'),
+];
+
+/// 比對禁用詞前的正規化
+///
+/// NFKC（全形轉半形、相容字元展開）、轉小寫、移除零寬字元並把常見的同形字
+/// 折疊成拉丁字母，避免以 `ｓｐａｍ`、`sрam`（西里爾 р）等寫法繞過檢查。
+pub fn normalize_for_matching(text: &str) -> String {
+    text.nfkc()
+        .flat_map(char::to_lowercase)
+        .filter(|c| {
+            !matches!(
+                c,
+                '\u{200B}'..='\u{200D}' | '\u{2060}' | '\u{FEFF}' | '\u{00AD}'
+            )
+        })
+        .map(|c| {
+            CONFUSABLES
+                .iter()
+                .find(|(confusable, _)| *confusable == c)
+                .map_or(c, |(_, latin)| *latin)
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+enum Matcher {
+    Word(String),
+    Regex(Regex),
+}
+
+/// 單一禁用規則
+#[derive(Debug, Clone)]
+struct ForbiddenPattern {
+    matcher: Matcher,
+    severity: Severity,
+}
+
+impl ForbiddenPattern {
+    /// 可用 `high:`、`medium:`、`low:` 前綴指定嚴重程度，預設為 medium；
+    /// `/.../` 為正規表示式，含 `*` 或 `?` 的為萬用字元，其餘為一般詞彙；皆不分大小寫
+    fn parse(entry: &str) -> Result<Self, regex::Error> {
+        let (severity, entry) = match entry.split_once(':') {
+            Some((prefix, rest)) => match Severity::parse(prefix.trim()) {
+                Some(severity) => (severity, rest.trim()),
+                None => (Severity::default(), entry),
+            },
+            None => (Severity::default(), entry),
+        };
+        Ok(Self {
+            matcher: Self::parse_matcher(entry)?,
+            severity,
+        })
+    }
+
+    fn parse_matcher(entry: &str) -> Result<Matcher, regex::Error> {
+        if let Some(pattern) = entry
+            .strip_prefix('/')
+            .and_then(|rest| rest.strip_suffix('/'))
+            .filter(|pattern| !pattern.is_empty())
+        {
+            return Regex::new(&format!("(?i){}", pattern)).map(Matcher::Regex);
+        }
+        if entry.contains(['*', '?']) {
+            let pattern: String = normalize_for_matching(entry)
+                .chars()
+                .map(|c| match c {
+                    '*' => ".*".to_string(),
+                    '?' => ".".to_string(),
+                    c => regex::escape(&c.to_string()),
+                })
+                .collect();
+            return Regex::new(&format!("(?i){}", pattern)).map(Matcher::Regex);
+        }
+        Ok(Matcher::Word(normalize_for_matching(entry)))
+    }
+
+    fn matches(&self, normalized: &str) -> bool {
+        match &self.matcher {
+            Matcher::Word(word) => normalized.contains(word.as_str()),
+            Matcher::Regex(regex) => regex.is_match(normalized),
+        }
+    }
+}
+
+/// 可共用、可熱更新的禁用詞清單
+///
+/// 檔案每行一個規則，空行與 `#` 開頭的行會被忽略，例如：
+///
+/// ```text
+/// casino
+/// high: free*money
+/// low: /\bdamn\b/
+/// ```
+#[derive(Debug, Clone)]
+pub struct ForbiddenWordList {
+    patterns: Arc<RwLock<Vec<ForbiddenPattern>>>,
+}
+
+impl Default for ForbiddenWordList {
+    fn default() -> Self {
+        Self::from_entries(DEFAULT_FORBIDDEN_WORDS.iter().copied())
+            .expect("default forbidden words are valid")
+    }
+}
+
+impl ForbiddenWordList {
+    /// 空清單
+    pub fn empty() -> Self {
+        Self {
+            patterns: Default::default(),
+        }
+    }
+
+    pub fn from_entries<'a>(
+        entries: impl IntoIterator<Item = &'a str>,
+    ) -> Result<Self, regex::Error> {
+        Ok(Self {
+            patterns: Arc::new(RwLock::new(parse_entries(entries)?)),
+        })
+    }
+
+    /// 從檔案載入
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        let list = Self::empty();
+        list.reload(path)?;
+        Ok(list)
+    }
+
+    /// 重新讀取檔案；解析失敗時保留原本的清單
+    pub fn reload(&self, path: impl AsRef<Path>) -> Result<usize, Box<dyn std::error::Error>> {
+        let content = std::fs::read_to_string(path)?;
+        let patterns = parse_entries(content.lines())?;
+        let count = patterns.len();
+        *self.patterns.write().unwrap_or_else(|e| e.into_inner()) = patterns;
+        Ok(count)
+    }
+
+    pub fn add(&self, entry: &str) -> Result<(), regex::Error> {
+        let pattern = ForbiddenPattern::parse(entry)?;
+        self.patterns
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(pattern);
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.patterns
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 以 [`normalize_for_matching`] 正規化後比對
+    pub fn matches(&self, text: &str) -> bool {
+        self.highest_severity(text).is_some()
+    }
+
+    /// 所有命中規則中最高的嚴重程度，未命中時為 `None`
+    pub fn highest_severity(&self, text: &str) -> Option<Severity> {
+        let normalized = normalize_for_matching(text);
+        self.patterns
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|pattern| pattern.matches(&normalized))
+            .map(|pattern| pattern.severity)
+            .max()
+    }
+
+    /// 定期檢查檔案修改時間，有變更時重新載入，並寫入稽核紀錄
+    pub fn watch(&self, path: impl Into<PathBuf>, interval: Duration, audit_log: Option<AuditLog>) {
+        let list = self.clone();
+        watch_path(
+            path.into(),
+            interval,
+            "forbidden_words",
+            "forbidden word rules",
+            audit_log,
+            move |path| list.reload(path),
+        );
+    }
+}
+
+fn parse_entries<'a>(
+    entries: impl IntoIterator<Item = &'a str>,
+) -> Result<Vec<ForbiddenPattern>, regex::Error> {
+    entries
+        .into_iter()
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty() && !entry.starts_with('#'))
+        .map(ForbiddenPattern::parse)
+        .collect()
+}
+
+/// 長度計算方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LengthMode {
+    /// Unicode 字元數
+    #[default]
+    Chars,
+    /// UTF-16 code unit 數，與 LINE API 的長度限制一致
+    Utf16,
+    /// UTF-8 位元組數
+    Bytes,
+}
+
+impl LengthMode {
+    pub fn count(&self, text: &str) -> usize {
+        match self {
+            LengthMode::Chars => text.chars().count(),
+            LengthMode::Utf16 => text.encode_utf16().count(),
+            LengthMode::Bytes => text.len(),
+        }
+    }
+}
+
 /// 文字訊息驗證器
 pub struct TextValidator {
     max_length: usize,
     min_length: usize,
-    forbidden_words: HashSet<String>,
+    length_mode: LengthMode,
+    forbidden_words: ForbiddenWordList,
     allow_empty: bool,
 }
 
 impl Default for TextValidator {
     fn default() -> Self {
-        let mut forbidden_words = HashSet::new();
-        // 添加一些常見的禁用詞彙
-        forbidden_words.insert("spam".to_string());
-        forbidden_words.insert("垃圾".to_string());
-        forbidden_words.insert("廣告".to_string());
-
         Self {
             max_length: 2000,
             min_length: 0,
-            forbidden_words,
+            length_mode: LengthMode::default(),
+            forbidden_words: ForbiddenWordList::default(),
             allow_empty: true,
         }
     }
@@ -68,8 +361,32 @@ impl TextValidator {
         self
     }
 
+    pub fn length_mode(mut self, mode: LengthMode) -> Self {
+        self.length_mode = mode;
+        self
+    }
+
     pub fn add_forbidden_word(mut self, word: &str) -> Self {
-        self.forbidden_words.insert(word.to_lowercase());
+        // 複製一份清單，不影響其他共用同一份清單的驗證器
+        let mut patterns = self
+            .forbidden_words
+            .patterns
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        patterns.push(ForbiddenPattern {
+            matcher: Matcher::Word(normalize_for_matching(word)),
+            severity: Severity::default(),
+        });
+        self.forbidden_words = ForbiddenWordList {
+            patterns: Arc::new(RwLock::new(patterns)),
+        };
+        self
+    }
+
+    /// 使用共用的禁用詞清單（例如從檔案載入並熱更新的清單）
+    pub fn forbidden_words(mut self, list: ForbiddenWordList) -> Self {
+        self.forbidden_words = list;
         self
     }
 
@@ -89,7 +406,7 @@ impl TextValidator {
         }
 
         // 檢查長度
-        let text_len = text.chars().count();
+        let text_len = self.length_mode.count(text);
         if text_len > self.max_length {
             return Err(ValidationError::TooLong {
                 max_length: self.max_length,
@@ -105,11 +422,8 @@ impl TextValidator {
         }
 
         // 檢查禁用詞彙
-        let text_lower = text.to_lowercase();
-        for forbidden_word in &self.forbidden_words {
-            if text_lower.contains(forbidden_word) {
-                return Err(ValidationError::Forbidden);
-            }
+        if self.forbidden_words.matches(text) {
+            return Err(ValidationError::Forbidden);
         }
 
         // 檢查控制字符
@@ -124,34 +438,604 @@ impl TextValidator {
     }
 }
 
+/// LINE 的使用者、群組、聊天室 ID：前綴字母加 32 個十六進位字元
+fn validate_line_id(id: &str, prefix: char) -> Result<(), ValidationError> {
+    if id.is_empty() {
+        return Err(ValidationError::Empty);
+    }
+
+    // LINE ID 應該是 33 個字符
+    if id.len() != 33 {
+        return Err(ValidationError::InvalidCharacters);
+    }
+
+    if !id.starts_with(prefix) {
+        return Err(ValidationError::InvalidCharacters);
+    }
+
+    // 其餘字符應該是十六進制（包括大寫和小寫）
+    if !id.chars().skip(1).all(|c| c.is_ascii_hexdigit()) {
+        return Err(ValidationError::InvalidCharacters);
+    }
+
+    Ok(())
+}
+
 /// 用戶 ID 驗證器
 pub struct UserIdValidator;
 
 impl UserIdValidator {
     pub fn validate(user_id: &str) -> Result<(), ValidationError> {
-        if user_id.is_empty() {
+        validate_line_id(user_id, 'U')
+    }
+}
+
+/// 群組 ID 驗證器（`C` 開頭）
+pub struct GroupIdValidator;
+
+impl GroupIdValidator {
+    pub fn validate(group_id: &str) -> Result<(), ValidationError> {
+        validate_line_id(group_id, 'C')
+    }
+}
+
+/// 聊天室 ID 驗證器（`R` 開頭）
+pub struct RoomIdValidator;
+
+impl RoomIdValidator {
+    pub fn validate(room_id: &str) -> Result<(), ValidationError> {
+        validate_line_id(room_id, 'R')
+    }
+}
+
+/// Rich menu ID 驗證器（`richmenu-` 加 32 個十六進位字元）
+pub struct RichMenuIdValidator;
+
+impl RichMenuIdValidator {
+    pub fn validate(rich_menu_id: &str) -> Result<(), ValidationError> {
+        if rich_menu_id.is_empty() {
             return Err(ValidationError::Empty);
         }
+        match rich_menu_id.strip_prefix("richmenu-") {
+            Some(hex) if hex.len() == 32 && hex.chars().all(|c| c.is_ascii_hexdigit()) => Ok(()),
+            _ => Err(ValidationError::InvalidCharacters),
+        }
+    }
+}
+
+/// Rich menu alias ID 驗證器（最多 32 個英數字、`-` 或 `_`）
+pub struct RichMenuAliasIdValidator;
+
+impl RichMenuAliasIdValidator {
+    pub const MAX_LENGTH: usize = 32;
 
-        // LINE 用戶 ID 應該是 33 個字符
-        if user_id.len() != 33 {
+    pub fn validate(alias_id: &str) -> Result<(), ValidationError> {
+        if alias_id.is_empty() {
+            return Err(ValidationError::Empty);
+        }
+        if alias_id.len() > Self::MAX_LENGTH {
+            return Err(ValidationError::TooLong {
+                max_length: Self::MAX_LENGTH,
+                actual: alias_id.len(),
+            });
+        }
+        if !alias_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
             return Err(ValidationError::InvalidCharacters);
         }
+        Ok(())
+    }
+}
 
-        // 應該以 U 開頭
-        if !user_id.starts_with('U') {
-            return Err(ValidationError::InvalidCharacters);
+/// URL 驗證器
+///
+/// 檢查 scheme、長度與允許的主機（含子網域）；`tel:` 等沒有主機的 URL 不檢查主機。
+#[derive(Debug, Clone)]
+pub struct UrlValidator {
+    allowed_schemes: Vec<String>,
+    allowed_hosts: Option<Vec<String>>,
+    max_length: usize,
+}
+
+impl Default for UrlValidator {
+    fn default() -> Self {
+        Self::for_media()
+    }
+}
+
+impl UrlValidator {
+    /// 圖片、影片等媒體 URL：只允許 HTTPS，最長 2000 字元
+    pub fn for_media() -> Self {
+        Self {
+            allowed_schemes: vec!["https".to_string()],
+            allowed_hosts: None,
+            max_length: 2000,
+        }
+    }
+
+    /// URI action：允許 `http`、`https`、`line`、`tel`，最長 1000 字元
+    pub fn for_actions() -> Self {
+        Self {
+            allowed_schemes: ["http", "https", "line", "tel"]
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            allowed_hosts: None,
+            max_length: 1000,
+        }
+    }
+
+    pub fn allowed_schemes(mut self, schemes: &[&str]) -> Self {
+        self.allowed_schemes = schemes.iter().map(|s| s.to_lowercase()).collect();
+        self
+    }
+
+    /// 只允許這些主機與其子網域
+    pub fn allowed_hosts(mut self, hosts: Vec<String>) -> Self {
+        self.allowed_hosts = Some(hosts.into_iter().map(|h| h.to_lowercase()).collect());
+        self
+    }
+
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    pub fn validate(&self, url: &str) -> Result<(), ValidationError> {
+        if url.is_empty() {
+            return Err(ValidationError::Empty);
+        }
+        if url.chars().count() > self.max_length {
+            return Err(ValidationError::TooLong {
+                max_length: self.max_length,
+                actual: url.chars().count(),
+            });
+        }
+
+        let parsed = reqwest::Url::parse(url)
+            .map_err(|e| ValidationError::InvalidUrl(format!("{}: {}", url, e)))?;
+        if !self
+            .allowed_schemes
+            .iter()
+            .any(|scheme| scheme == parsed.scheme())
+        {
+            return Err(ValidationError::InvalidUrl(format!(
+                "scheme `{}` is not allowed",
+                parsed.scheme()
+            )));
+        }
+
+        if let (Some(allowed), Some(host)) = (&self.allowed_hosts, parsed.host_str()) {
+            let permitted = allowed.iter().any(|allowed| {
+                host == allowed
+                    || host
+                        .strip_suffix(allowed.as_str())
+                        .is_some_and(|prefix| prefix.ends_with('.'))
+            });
+            if !permitted {
+                return Err(ValidationError::InvalidUrl(format!(
+                    "host `{}` is not allowed",
+                    host
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// LINE Messaging API 的訊息格式限制
+pub mod message_limits {
+    /// 單次 reply/push/multicast 的訊息數
+    pub const MAX_MESSAGES: usize = 5;
+    pub const MAX_MULTICAST_RECIPIENTS: usize = 500;
+    pub const MAX_TEXT_LENGTH: usize = 5000;
+    pub const MAX_ALT_TEXT_LENGTH: usize = 400;
+    pub const MAX_BUTTONS_ACTIONS: usize = 4;
+    pub const MAX_BUTTONS_TITLE_LENGTH: usize = 40;
+    pub const MAX_BUTTONS_TEXT_LENGTH: usize = 160;
+    /// 有圖片或標題時 buttons 內文的上限
+    pub const MAX_BUTTONS_TEXT_LENGTH_WITH_HEADER: usize = 60;
+    pub const MAX_ACTION_LABEL_LENGTH: usize = 20;
+    pub const MAX_ACTION_TEXT_LENGTH: usize = 300;
+    pub const MAX_POSTBACK_DATA_LENGTH: usize = 300;
+    pub const MAX_CONFIRM_TEXT_LENGTH: usize = 240;
+    pub const CONFIRM_ACTIONS: usize = 2;
+    pub const MAX_CAROUSEL_COLUMNS: usize = 10;
+    pub const MAX_CAROUSEL_ACTIONS: usize = 3;
+    pub const MAX_CAROUSEL_TITLE_LENGTH: usize = 40;
+    pub const MAX_CAROUSEL_TEXT_LENGTH: usize = 120;
+    /// 有圖片或標題時 carousel 欄位內文的上限
+    pub const MAX_CAROUSEL_TEXT_LENGTH_WITH_HEADER: usize = 60;
+    pub const MAX_QUICK_REPLY_ITEMS: usize = 13;
+}
+
+/// LINE Messaging API 的 rich menu 限制
+pub mod rich_menu_limits {
+    pub const MIN_WIDTH: u32 = 800;
+    pub const MAX_WIDTH: u32 = 2500;
+    pub const MIN_HEIGHT: u32 = 250;
+    /// 寬高比（寬 / 高）的下限
+    pub const MIN_ASPECT_RATIO: f64 = 1.45;
+    pub const MAX_NAME_LENGTH: usize = 300;
+    pub const MAX_CHAT_BAR_TEXT_LENGTH: usize = 14;
+    pub const MAX_AREAS: usize = 20;
+}
+
+/// 建立 rich menu 前在本地檢查大小、文字長度與區域範圍
+///
+/// 回傳所有不符合的欄位，欄位名稱與 LINE 驗證端點回傳的 `property` 相同。
+pub struct RichMenuValidator;
+
+impl RichMenuValidator {
+    pub fn validate(menu: &RichMenu) -> Vec<ApiError> {
+        use rich_menu_limits::*;
+
+        let mut errors = Vec::new();
+        let mut error =
+            |property: String, message: String| errors.push(ApiError { message, property });
+
+        let size = menu.size;
+        if !(MIN_WIDTH..=MAX_WIDTH).contains(&size.width) {
+            error(
+                "size.width".to_string(),
+                format!(
+                    "must be between {} and {}, got {}",
+                    MIN_WIDTH, MAX_WIDTH, size.width
+                ),
+            );
+        }
+        if size.height < MIN_HEIGHT {
+            error(
+                "size.height".to_string(),
+                format!("must be at least {}, got {}", MIN_HEIGHT, size.height),
+            );
+        } else if (size.width as f64) / (size.height as f64) < MIN_ASPECT_RATIO {
+            error(
+                "size".to_string(),
+                format!(
+                    "aspect ratio (width / height) must be at least {}, got {}x{}",
+                    MIN_ASPECT_RATIO, size.width, size.height
+                ),
+            );
+        }
+
+        let name_length = menu.name.chars().count();
+        if name_length == 0 || name_length > MAX_NAME_LENGTH {
+            error(
+                "name".to_string(),
+                format!(
+                    "must be 1 to {} characters, got {}",
+                    MAX_NAME_LENGTH, name_length
+                ),
+            );
+        }
+        let chat_bar_length = menu.chat_bar_text.chars().count();
+        if chat_bar_length == 0 || chat_bar_length > MAX_CHAT_BAR_TEXT_LENGTH {
+            error(
+                "chatBarText".to_string(),
+                format!(
+                    "must be 1 to {} characters, got {}",
+                    MAX_CHAT_BAR_TEXT_LENGTH, chat_bar_length
+                ),
+            );
         }
 
-        // 其餘字符應該是十六進制（包括大寫和小寫）
-        for c in user_id.chars().skip(1) {
-            if !c.is_ascii_hexdigit() {
-                return Err(ValidationError::InvalidCharacters);
+        if menu.areas.len() > MAX_AREAS {
+            error(
+                "areas".to_string(),
+                format!(
+                    "must have at most {} areas, got {}",
+                    MAX_AREAS,
+                    menu.areas.len()
+                ),
+            );
+        }
+        for (i, area) in menu.areas.iter().enumerate() {
+            let bounds = area.bounds;
+            if bounds.width == 0 || bounds.height == 0 {
+                error(
+                    format!("areas[{}].bounds", i),
+                    "width and height must be greater than 0".to_string(),
+                );
+            } else if bounds.x as u64 + bounds.width as u64 > size.width as u64
+                || bounds.y as u64 + bounds.height as u64 > size.height as u64
+            {
+                error(
+                    format!("areas[{}].bounds", i),
+                    format!(
+                        "({}, {}, {}x{}) is outside the {}x{} menu",
+                        bounds.x, bounds.y, bounds.width, bounds.height, size.width, size.height
+                    ),
+                );
             }
         }
+        errors
+    }
+}
+
+/// 送出前檢查訊息內容
+///
+/// `LineApiClient` 在 reply/push/multicast 前自動套用，檢查 URL 與 LINE 的訊息數、
+/// 文字長度、按鈕數等限制，讓錯誤在本地就被發現，而不是收到 LINE 回傳的 400。
+/// 文字長度預設以 UTF-16 code unit 計算，與 LINE 的計算方式相同。
+#[derive(Debug, Clone)]
+pub struct OutgoingMessageValidator {
+    action_urls: UrlValidator,
+    media_urls: UrlValidator,
+    length_mode: LengthMode,
+}
+
+impl Default for OutgoingMessageValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OutgoingMessageValidator {
+    pub fn new() -> Self {
+        Self {
+            action_urls: UrlValidator::for_actions(),
+            media_urls: UrlValidator::for_media(),
+            length_mode: LengthMode::Utf16,
+        }
+    }
+
+    pub fn length_mode(mut self, mode: LengthMode) -> Self {
+        self.length_mode = mode;
+        self
+    }
+
+    pub fn action_urls(mut self, validator: UrlValidator) -> Self {
+        self.action_urls = validator;
+        self
+    }
+
+    pub fn media_urls(mut self, validator: UrlValidator) -> Self {
+        self.media_urls = validator;
+        self
+    }
+
+    pub fn validate(&self, messages: &[OutgoingMessage]) -> Result<(), ValidationError> {
+        if messages.is_empty() {
+            return Err(ValidationError::Empty);
+        }
+        check_limit("messages", messages.len(), message_limits::MAX_MESSAGES)?;
 
+        for (index, message) in messages.iter().enumerate() {
+            self.validate_message(&format!("messages[{}]", index), message)?;
+        }
         Ok(())
     }
+
+    fn validate_message(
+        &self,
+        path: &str,
+        message: &OutgoingMessage,
+    ) -> Result<(), ValidationError> {
+        match message {
+            OutgoingMessage::Text { text, quick_reply } => {
+                self.check_length(
+                    &format!("{}.text", path),
+                    text,
+                    message_limits::MAX_TEXT_LENGTH,
+                )?;
+                let Some(quick_reply) = quick_reply else {
+                    return Ok(());
+                };
+                let path = format!("{}.quickReply.items", path);
+                check_limit(
+                    &path,
+                    quick_reply.items.len(),
+                    message_limits::MAX_QUICK_REPLY_ITEMS,
+                )?;
+                let actions: Vec<Action> = quick_reply
+                    .items
+                    .iter()
+                    .map(|item| item.action.clone())
+                    .collect();
+                self.validate_actions(&path, &actions)
+            }
+            OutgoingMessage::Sticker { .. } => Ok(()),
+            OutgoingMessage::Flex { alt_text, contents } => {
+                self.check_length(
+                    &format!("{}.altText", path),
+                    alt_text,
+                    message_limits::MAX_ALT_TEXT_LENGTH,
+                )?;
+                self.validate_flex_uris(contents)
+            }
+            OutgoingMessage::Template { alt_text, template } => {
+                self.check_length(
+                    &format!("{}.altText", path),
+                    alt_text,
+                    message_limits::MAX_ALT_TEXT_LENGTH,
+                )?;
+                let path = format!("{}.template", path);
+                match template {
+                    TemplateType::Buttons {
+                        text,
+                        actions,
+                        thumbnail_image_url,
+                        title,
+                        ..
+                    } => {
+                        if let Some(url) = thumbnail_image_url {
+                            self.media_urls.validate(url)?;
+                        }
+                        if let Some(title) = title {
+                            self.check_length(
+                                &format!("{}.title", path),
+                                title,
+                                message_limits::MAX_BUTTONS_TITLE_LENGTH,
+                            )?;
+                        }
+                        let max_text = if thumbnail_image_url.is_some() || title.is_some() {
+                            message_limits::MAX_BUTTONS_TEXT_LENGTH_WITH_HEADER
+                        } else {
+                            message_limits::MAX_BUTTONS_TEXT_LENGTH
+                        };
+                        self.check_length(&format!("{}.text", path), text, max_text)?;
+                        check_limit(
+                            &format!("{}.actions", path),
+                            actions.len(),
+                            message_limits::MAX_BUTTONS_ACTIONS,
+                        )?;
+                        self.validate_actions(&format!("{}.actions", path), actions)
+                    }
+                    TemplateType::Confirm { text, actions } => {
+                        self.check_length(
+                            &format!("{}.text", path),
+                            text,
+                            message_limits::MAX_CONFIRM_TEXT_LENGTH,
+                        )?;
+                        check_count(
+                            &format!("{}.actions", path),
+                            actions.len(),
+                            message_limits::CONFIRM_ACTIONS,
+                        )?;
+                        self.validate_actions(&format!("{}.actions", path), actions)
+                    }
+                    TemplateType::Carousel { columns, .. } => {
+                        check_limit(
+                            &format!("{}.columns", path),
+                            columns.len(),
+                            message_limits::MAX_CAROUSEL_COLUMNS,
+                        )?;
+                        for (index, column) in columns.iter().enumerate() {
+                            let path = format!("{}.columns[{}]", path, index);
+                            self.validate_column(&path, column)?;
+                            // LINE 要求每一欄的按鈕數相同
+                            check_count(
+                                &format!("{}.actions", path),
+                                column.actions.len(),
+                                columns[0].actions.len(),
+                            )?;
+                        }
+                        Ok(())
+                    }
+                }
+            }
+        }
+    }
+
+    fn validate_column(&self, path: &str, column: &CarouselColumn) -> Result<(), ValidationError> {
+        if let Some(url) = &column.thumbnail_image_url {
+            self.media_urls.validate(url)?;
+        }
+        if let Some(title) = &column.title {
+            self.check_length(
+                &format!("{}.title", path),
+                title,
+                message_limits::MAX_CAROUSEL_TITLE_LENGTH,
+            )?;
+        }
+        let max_text = if column.thumbnail_image_url.is_some() || column.title.is_some() {
+            message_limits::MAX_CAROUSEL_TEXT_LENGTH_WITH_HEADER
+        } else {
+            message_limits::MAX_CAROUSEL_TEXT_LENGTH
+        };
+        self.check_length(&format!("{}.text", path), &column.text, max_text)?;
+        check_limit(
+            &format!("{}.actions", path),
+            column.actions.len(),
+            message_limits::MAX_CAROUSEL_ACTIONS,
+        )?;
+        self.validate_actions(&format!("{}.actions", path), &column.actions)
+    }
+
+    fn check_length(&self, field: &str, value: &str, max: usize) -> Result<(), ValidationError> {
+        check_limit(field, self.length_mode.count(value), max)
+    }
+
+    /// Flex 內容不逐一檢查格式，只檢查 URI action 的網址
+    fn validate_flex_uris(&self, value: &Value) -> Result<(), ValidationError> {
+        match value {
+            Value::Object(object) => {
+                if object.get("type").and_then(Value::as_str) == Some("uri")
+                    && let Some(uri) = object.get("uri").and_then(Value::as_str)
+                {
+                    self.action_urls.validate(uri)?;
+                }
+                object
+                    .values()
+                    .try_for_each(|value| self.validate_flex_uris(value))
+            }
+            Value::Array(values) => values
+                .iter()
+                .try_for_each(|value| self.validate_flex_uris(value)),
+            _ => Ok(()),
+        }
+    }
+
+    fn validate_actions(&self, path: &str, actions: &[Action]) -> Result<(), ValidationError> {
+        for (index, action) in actions.iter().enumerate() {
+            let path = format!("{}[{}]", path, index);
+            let label = match action {
+                Action::Message { label, text } => {
+                    self.check_length(
+                        &format!("{}.text", path),
+                        text,
+                        message_limits::MAX_ACTION_TEXT_LENGTH,
+                    )?;
+                    label
+                }
+                Action::Postback {
+                    label,
+                    data,
+                    display_text,
+                } => {
+                    self.check_length(
+                        &format!("{}.data", path),
+                        data,
+                        message_limits::MAX_POSTBACK_DATA_LENGTH,
+                    )?;
+                    if let Some(display_text) = display_text {
+                        self.check_length(
+                            &format!("{}.displayText", path),
+                            display_text,
+                            message_limits::MAX_ACTION_TEXT_LENGTH,
+                        )?;
+                    }
+                    label
+                }
+                Action::Uri { label, uri } => {
+                    self.action_urls.validate(uri)?;
+                    label
+                }
+            };
+            self.check_length(
+                &format!("{}.label", path),
+                label,
+                message_limits::MAX_ACTION_LABEL_LENGTH,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn check_count(field: &str, actual: usize, expected: usize) -> Result<(), ValidationError> {
+    if actual != expected {
+        return Err(ValidationError::CountMismatch {
+            field: field.to_string(),
+            expected,
+            actual,
+        });
+    }
+    Ok(())
+}
+
+pub(crate) fn check_limit(field: &str, actual: usize, max: usize) -> Result<(), ValidationError> {
+    if actual > max {
+        return Err(ValidationError::LimitExceeded {
+            field: field.to_string(),
+            max,
+            actual,
+        });
+    }
+    Ok(())
 }
 
 /// Reply Token 驗證器
@@ -184,6 +1068,58 @@ impl ReplyTokenValidator {
     }
 }
 
+/// 回傳給使用者的內容中，使用者輸入部分的淨化工具
+///
+/// 移除控制字元與會改變文字方向的格式字元、限制長度，並把 LINE 用於 emoji 與
+/// 文字替換的 `$`、`{`、`}` 換成全形字元，避免使用者輸入被解讀為佔位符。
+#[derive(Debug, Clone)]
+pub struct UserContentSanitizer {
+    max_length: usize,
+}
+
+impl Default for UserContentSanitizer {
+    fn default() -> Self {
+        Self { max_length: 1000 }
+    }
+}
+
+impl UserContentSanitizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    pub fn sanitize(&self, text: &str) -> String {
+        let mut sanitized = String::with_capacity(text.len());
+        let mut length = 0;
+        for c in text.chars() {
+            let c = match c {
+                '$' => '＄',
+                '{' => '｛',
+                '}' => '｝',
+                '\n' => '\n',
+                c if c.is_control() => continue,
+                // 零寬字元與雙向文字控制字元
+                '\u{200B}'..='\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}' => {
+                    continue;
+                }
+                c => c,
+            };
+            if length == self.max_length {
+                sanitized.push('…');
+                break;
+            }
+            sanitized.push(c);
+            length += 1;
+        }
+        sanitized
+    }
+}
+
 /// 敏感資料遮罩工具
 pub struct SensitiveDataMasker;
 
@@ -220,6 +1156,31 @@ impl SensitiveDataMasker {
         }
     }
 
+    /// 遞迴遮罩 JSON 中的使用者/群組 ID、reply token 與收件人
+    pub fn mask_json_identifiers(value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, field) in map.iter_mut() {
+                    if IDENTIFIER_FIELDS.contains(&key.as_str()) {
+                        Self::mask_json_strings(field);
+                    } else {
+                        Self::mask_json_identifiers(field);
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(Self::mask_json_identifiers),
+            _ => {}
+        }
+    }
+
+    fn mask_json_strings(value: &mut Value) {
+        match value {
+            Value::String(s) => *s = Self::mask_user_id(s),
+            Value::Array(items) => items.iter_mut().for_each(Self::mask_json_strings),
+            _ => {}
+        }
+    }
+
     /// 遮罩電話號碼
     pub fn mask_phone(phone: &str) -> String {
         if phone.len() <= 4 {
@@ -249,6 +1210,23 @@ mod tests {
         assert!(matches!(result, Err(ValidationError::TooLong { .. })));
     }
 
+    #[test]
+    fn test_length_modes() {
+        // 😀 是 1 個字元、2 個 UTF-16 code unit、4 個位元組
+        assert_eq!(LengthMode::Chars.count("a😀"), 2);
+        assert_eq!(LengthMode::Utf16.count("a😀"), 3);
+        assert_eq!(LengthMode::Bytes.count("a😀"), 5);
+
+        let validator = TextValidator::new()
+            .max_length(2)
+            .length_mode(LengthMode::Utf16);
+        assert!(matches!(
+            validator.validate("a😀"),
+            Err(ValidationError::TooLong { actual: 3, .. })
+        ));
+        assert!(TextValidator::new().max_length(2).validate("a😀").is_ok());
+    }
+
     #[test]
     fn test_text_validator_forbidden_word() {
         let validator = TextValidator::new();
@@ -256,6 +1234,69 @@ mod tests {
         assert_eq!(result, Err(ValidationError::Forbidden));
     }
 
+    #[test]
+    fn test_forbidden_word_patterns() {
+        let list = ForbiddenWordList::from_entries([
+            "# comment",
+            "",
+            "casino",
+            "free*money",
+            r"/\bv[i1]agra\b/",
+        ])
+        .unwrap();
+        assert_eq!(list.len(), 3);
+        let validator = TextValidator::new().forbidden_words(list);
+
+        assert_eq!(
+            validator.validate("Best CASINO in town"),
+            Err(ValidationError::Forbidden)
+        );
+        assert_eq!(
+            validator.validate("get free easy money"),
+            Err(ValidationError::Forbidden)
+        );
+        assert_eq!(
+            validator.validate("cheap v1agra"),
+            Err(ValidationError::Forbidden)
+        );
+        // 已不再使用內建清單
+        assert!(validator.validate("This is spam content").is_ok());
+    }
+
+    #[test]
+    fn test_forbidden_words_resist_lookalikes() {
+        let validator = TextValidator::new();
+        // 全形
+        assert!(validator.validate("ｓｐａｍ").is_err());
+        // 西里爾字母 р 與 а
+        assert!(validator.validate("sраm offer").is_err());
+        // 零寬空白
+        assert!(validator.validate("sp\u{200B}am").is_err());
+        assert!(validator.validate("special").is_ok());
+    }
+
+    #[test]
+    fn test_forbidden_word_list_reload() {
+        let path =
+            std::env::temp_dir().join(format!("linebot-forbidden-{}.txt", std::process::id()));
+        std::fs::write(&path, "alpha\n").unwrap();
+        let list = ForbiddenWordList::from_file(&path).unwrap();
+        let validator = TextValidator::new().forbidden_words(list.clone());
+        assert!(validator.validate("alpha").is_err());
+
+        std::fs::write(&path, "beta\n").unwrap();
+        list.reload(&path).unwrap();
+        assert!(validator.validate("alpha").is_ok());
+        assert!(validator.validate("beta").is_err());
+
+        // 無效的規則不會清掉原本的清單
+        std::fs::write(&path, "/(/\n").unwrap();
+        assert!(list.reload(&path).is_err());
+        assert!(validator.validate("beta").is_err());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
     #[test]
     fn test_text_validator_control_characters() {
         let validator = TextValidator::new();
@@ -281,6 +1322,187 @@ mod tests {
         assert!(UserIdValidator::validate("U123456789gabcdef1234567890abcdef").is_err());
     }
 
+    #[test]
+    fn test_group_and_room_id_validators() {
+        assert!(GroupIdValidator::validate("C1234567890abcdef1234567890abcdef").is_ok());
+        assert!(GroupIdValidator::validate("U1234567890abcdef1234567890abcdef").is_err());
+        assert!(GroupIdValidator::validate("C123").is_err());
+        assert!(RoomIdValidator::validate("R1234567890ABCDEF1234567890abcdef").is_ok());
+        assert!(RoomIdValidator::validate("C1234567890abcdef1234567890abcdef").is_err());
+        assert_eq!(RoomIdValidator::validate(""), Err(ValidationError::Empty));
+        assert!(RichMenuIdValidator::validate("richmenu-88c05ef6921ae53f8b58a25f3a65faf7").is_ok());
+        assert!(RichMenuIdValidator::validate("richmenu-../../message/push").is_err());
+        assert!(RichMenuIdValidator::validate("88c05ef6921ae53f8b58a25f3a65faf7").is_err());
+        assert!(RichMenuAliasIdValidator::validate("menu-member_1").is_ok());
+        assert!(RichMenuAliasIdValidator::validate("../list").is_err());
+        assert!(RichMenuAliasIdValidator::validate(&"a".repeat(33)).is_err());
+    }
+
+    #[test]
+    fn test_rich_menu_validator() {
+        use crate::models::{RichMenuArea, RichMenuBounds, RichMenuSize};
+
+        let area = |x, y, width, height| RichMenuArea {
+            bounds: RichMenuBounds {
+                x,
+                y,
+                width,
+                height,
+            },
+            action: Action::Message {
+                label: "A".to_string(),
+                text: "a".to_string(),
+            },
+        };
+        let mut menu = RichMenu {
+            size: RichMenuSize {
+                width: 2500,
+                height: 1686,
+            },
+            selected: false,
+            name: "main".to_string(),
+            chat_bar_text: "選單".to_string(),
+            areas: vec![area(0, 0, 1250, 1686), area(1250, 0, 1250, 1686)],
+        };
+        assert!(RichMenuValidator::validate(&menu).is_empty());
+
+        menu.size.height = 2000;
+        menu.chat_bar_text = "這是一段超過十四個字的選單列文字".to_string();
+        menu.areas.push(area(2000, 0, 600, 100));
+        menu.areas.push(area(0, 0, 0, 100));
+        let properties: Vec<String> = RichMenuValidator::validate(&menu)
+            .into_iter()
+            .map(|e| e.property)
+            .collect();
+        assert_eq!(
+            properties,
+            ["size", "chatBarText", "areas[2].bounds", "areas[3].bounds"]
+        );
+    }
+
+    #[test]
+    fn test_url_validator() {
+        let media = UrlValidator::for_media();
+        assert!(media.validate("https://cdn.example.com/a.jpg").is_ok());
+        assert!(matches!(
+            media.validate("http://cdn.example.com/a.jpg"),
+            Err(ValidationError::InvalidUrl(_))
+        ));
+        assert!(media.validate("not a url").is_err());
+
+        let actions = UrlValidator::for_actions()
+            .allowed_hosts(vec!["example.com".to_string()])
+            .max_length(40);
+        assert!(actions.validate("https://shop.example.com/item").is_ok());
+        assert!(actions.validate("tel:0912345678").is_ok());
+        assert!(actions.validate("https://evil-example.com/").is_err());
+        assert!(matches!(
+            actions.validate("https://example.com/a-very-long-path-segment"),
+            Err(ValidationError::TooLong { .. })
+        ));
+    }
+
+    #[test]
+    fn test_outgoing_message_validator_checks_template_urls() {
+        let validator = OutgoingMessageValidator::new();
+        let message = |uri: &str| {
+            TemplateType::buttons("Pick one")
+                .action(Action::uri("Open", uri))
+                .into_message("menu")
+        };
+
+        assert!(
+            validator
+                .validate(&[message("https://example.com")])
+                .is_ok()
+        );
+        assert!(
+            validator
+                .validate(&[OutgoingMessage::text("hi"), message("javascript:alert(1)")])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_outgoing_message_limits() {
+        let validator = OutgoingMessageValidator::new();
+        let texts = |count: usize| vec![OutgoingMessage::text("hi"); count];
+
+        assert!(validator.validate(&texts(5)).is_ok());
+
+        // 預設以 UTF-16 計算，3000 個 emoji 佔 6000 個 code unit
+        let emoji = [OutgoingMessage::text("😀".repeat(3000))];
+        assert!(matches!(
+            validator.validate(&emoji),
+            Err(ValidationError::LimitExceeded { actual: 6000, .. })
+        ));
+        assert!(
+            OutgoingMessageValidator::new()
+                .length_mode(LengthMode::Chars)
+                .validate(&emoji)
+                .is_ok()
+        );
+
+        assert_eq!(
+            validator.validate(&texts(6)),
+            Err(ValidationError::LimitExceeded {
+                field: "messages".to_string(),
+                max: 5,
+                actual: 6,
+            })
+        );
+        assert_eq!(validator.validate(&[]), Err(ValidationError::Empty));
+
+        let long_text = OutgoingMessage::text("字".repeat(5001));
+        let err = validator
+            .validate(&[OutgoingMessage::text("ok"), long_text])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "messages[1].text exceeds limit: 5001 > 5000"
+        );
+
+        let confirm = OutgoingMessage::confirm(
+            "confirm",
+            "Are you sure?",
+            Action::message("Yes", "yes"),
+            Action::message("No", "no"),
+        );
+        assert!(validator.validate(&[confirm]).is_ok());
+
+        let carousel = OutgoingMessage::carousel(
+            "menu",
+            [
+                CarouselColumn::new("First").action(Action::postback("Pick", "id=1")),
+                CarouselColumn::new("Second"),
+            ],
+        );
+        assert_eq!(
+            validator.validate(&[carousel]),
+            Err(ValidationError::CountMismatch {
+                field: "messages[0].template.columns[1].actions".to_string(),
+                expected: 1,
+                actual: 0,
+            })
+        );
+        let too_many = OutgoingMessage::template(
+            "menu",
+            TemplateType::carousel((0..11).map(|i| CarouselColumn::new(i.to_string()))),
+        );
+        assert!(matches!(
+            validator.validate(&[too_many]),
+            Err(ValidationError::LimitExceeded { field, .. }) if field == "messages[0].template.columns"
+        ));
+
+        let buttons = TemplateType::buttons("Pick one")
+            .actions((0..5).map(|i| Action::message(format!("Option {}", i), i.to_string())))
+            .into_message("menu");
+        assert!(matches!(
+            validator.validate(&[buttons]),
+            Err(ValidationError::LimitExceeded { field, .. }) if field == "messages[0].template.actions"
+        ));
+    }
+
     #[test]
     fn test_reply_token_validator() {
         assert!(ReplyTokenValidator::validate("valid_reply_token_123").is_ok());
@@ -289,6 +1511,14 @@ mod tests {
         assert!(ReplyTokenValidator::validate("invalid token with spaces").is_err());
     }
 
+    #[test]
+    fn test_user_content_sanitizer() {
+        let sanitizer = UserContentSanitizer::new().max_length(10);
+        assert_eq!(sanitizer.sanitize("hi\u{0}\u{202E}there"), "hithere");
+        assert_eq!(sanitizer.sanitize("cost $5 {name}"), "cost ＄5 ｛n…");
+        assert_eq!(sanitizer.sanitize("ab\ncd"), "ab\ncd");
+    }
+
     #[test]
     fn test_sensitive_data_masker() {
         assert_eq!(
@@ -305,4 +1535,17 @@ mod tests {
         );
         assert_eq!(SensitiveDataMasker::mask_phone("0912345678"), "09***78");
     }
+
+    #[test]
+    fn test_mask_json_identifiers() {
+        let mut value = serde_json::json!({
+            "to": ["U1234567890abcdef1234567890abcdef"],
+            "messages": [{"type": "text", "text": "hello"}],
+            "source": {"userId": "U1234567890abcdef1234567890abcdef"}
+        });
+        SensitiveDataMasker::mask_json_identifiers(&mut value);
+        assert_eq!(value["to"][0], "U12...def");
+        assert_eq!(value["source"]["userId"], "U12...def");
+        assert_eq!(value["messages"][0]["text"], "hello");
+    }
 }
@@ -0,0 +1,85 @@
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tracing::{error, info};
+
+use crate::storage::{AuditEntry, AuditLog};
+
+/// 定期檢查檔案或目錄的修改時間，有變更時呼叫 `reload` 重新載入，並以 `kind` 寫入稽核紀錄
+///
+/// `reload` 回傳載入的筆數；`label` 用於日誌，例如 `reply templates`。
+pub fn watch_path<F, E>(
+    path: PathBuf,
+    interval: Duration,
+    kind: &'static str,
+    label: &'static str,
+    audit_log: Option<AuditLog>,
+    reload: F,
+) where
+    F: Fn(&Path) -> Result<usize, E> + Send + 'static,
+    E: Display,
+{
+    tokio::spawn(async move {
+        let mut last_modified = latest_modified(&path);
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let modified = latest_modified(&path);
+            if modified == last_modified {
+                continue;
+            }
+            last_modified = modified;
+            // 錯誤型別不一定是 Send，需在 await 前轉成紀錄
+            let entry = {
+                let result = reload(&path);
+                match &result {
+                    Ok(count) => info!("Reloaded {} {}", count, label),
+                    Err(e) => error!("Failed to reload {}: {}", label, e),
+                }
+                AuditEntry::reload(kind, &path, result)
+            };
+            if let Some(audit_log) = &audit_log {
+                audit_log.record(entry).await;
+            }
+        }
+    });
+}
+
+/// 檔案的修改時間；目錄則遞迴取其中最新的修改時間
+fn latest_modified(path: &Path) -> Option<SystemTime> {
+    let mut latest = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    if let Ok(entries) = std::fs::read_dir(path) {
+        for entry in entries.flatten() {
+            latest = latest.max(latest_modified(&entry.path()));
+        }
+    }
+    latest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latest_modified_includes_nested_files() {
+        let dir = std::env::temp_dir().join(format!("linebot-watch-{}", std::process::id()));
+        let nested = dir.join("nested");
+        std::fs::create_dir_all(&nested).unwrap();
+        let file = nested.join("a.txt");
+        std::fs::write(&file, "a").unwrap();
+        let old = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        for path in [&dir, &nested] {
+            std::fs::File::open(path)
+                .unwrap()
+                .set_modified(old)
+                .unwrap();
+        }
+
+        let file_modified = std::fs::metadata(&file).unwrap().modified().unwrap();
+        assert_eq!(latest_modified(&file), Some(file_modified));
+        assert_eq!(latest_modified(&dir), Some(file_modified));
+        assert_eq!(latest_modified(&dir.join("missing")), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,613 @@
+use axum::{
+    Json, Router,
+    body::Body,
+    extract::{OriginalUri, Path, Query, Request, State},
+    http::{StatusCode, header},
+    middleware::{self, Next},
+    response::{
+        Html, IntoResponse, Response,
+        sse::{self, KeepAlive, Sse},
+    },
+    routing::{delete, get, post},
+};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, convert::Infallible, sync::Arc};
+use tokio_stream::{
+    Stream, StreamExt,
+    wrappers::{BroadcastStream, ReceiverStream},
+};
+use tower_http::compression::CompressionLayer;
+use tracing::{info, warn};
+
+use crate::models::{MessageQuota, QuotaConsumption};
+use crate::storage::{
+    ADMIN_ACTOR_HEADER, AuditEntry, AuditQuery, ConversationEntry, ExportFormat, ExportKind,
+    HistoryQuery, admin_actor, export_conversations, with_audit_actor,
+};
+use crate::utils::{
+    AnalyticsReport, FlagRule, ModerationPolicy, SensitiveDataMasker, StatsSnapshot, token_matches,
+};
+use crate::webhook::handlers::handle_webhook_event;
+use crate::webhook::server::AppState;
+use crate::webhook::{RecordedWebhook, RecordingSummary};
+
+/// 管理端點統計回應
+#[derive(Debug, Serialize)]
+pub struct AdminStats {
+    #[serde(flatten)]
+    pub stats: StatsSnapshot,
+    pub quota: Option<QuotaUsage>,
+    pub analytics: Option<AnalyticsReport>,
+}
+
+/// LINE 訊息額度使用量
+#[derive(Debug, Serialize)]
+pub struct QuotaUsage {
+    pub quota: MessageQuota,
+    pub consumption: QuotaConsumption,
+}
+
+/// 建立 `/admin` 路由，所有端點皆需 Bearer token 驗證
+pub fn admin_router(state: Arc<AppState>) -> Router<Arc<AppState>> {
+    let compression_enabled = state.config.compression_enabled;
+
+    let router = Router::new()
+        .route("/stats", get(stats))
+        .route("/dashboard", get(dashboard))
+        .route("/insight/followers", get(follower_insight))
+        .route("/events/stream", get(event_stream))
+        .route("/conversations", get(conversations))
+        .route("/audit", get(audit_log))
+        .route("/users/:user_id", delete(purge_user))
+        .route("/export", get(export))
+        .route(
+            "/moderation/policy",
+            get(moderation_policy).put(update_moderation_policy),
+        )
+        .route("/recordings", get(recordings))
+        .route("/recordings/replay", post(replay_recordings))
+        .route("/recordings/:id", get(recording))
+        .route("/flags", get(feature_flags))
+        .route(
+            "/flags/:name",
+            axum::routing::put(set_feature_flag).delete(remove_feature_flag),
+        );
+    #[cfg(feature = "campaigns")]
+    let router = router.route("/campaigns", get(campaigns)).route(
+        "/campaigns/:id",
+        axum::routing::put(upsert_campaign).delete(remove_campaign),
+    );
+    #[cfg(feature = "graphql")]
+    let router = router.merge(crate::webhook::graphql::graphql_router(state.clone()));
+    // 稽核在驗證之內，未通過驗證的請求不會寫入，避免以偽造的操作者灌入紀錄
+    let router = router
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            admin_audit_middleware,
+        ))
+        .route_layer(middleware::from_fn_with_state(state, admin_auth_middleware));
+
+    // SSE 回應不會被壓縮（tower-http 預設排除 text/event-stream）
+    if compression_enabled {
+        router.layer(CompressionLayer::new())
+    } else {
+        router
+    }
+}
+
+async fn admin_auth_middleware(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(expected) = state.config.admin_token.as_deref() else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    if !token_matches(provided, expected) {
+        warn!("Rejected admin request to {}", request.uri().path());
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    next.run(request).await
+}
+
+/// 將每個管理 API 呼叫寫入稽核紀錄，處理期間觸發的群發等動作也以同一操作者記錄
+async fn admin_audit_middleware(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(audit_log) = state.audit_log.clone() else {
+        return next.run(request).await;
+    };
+
+    let actor = admin_actor(
+        request
+            .headers()
+            .get(ADMIN_ACTOR_HEADER)
+            .and_then(|value| value.to_str().ok()),
+    );
+    let path = request
+        .extensions()
+        .get::<OriginalUri>()
+        .map_or_else(|| request.uri().path(), |uri| uri.path());
+    let target = format!("{} {}", request.method(), path);
+
+    let response = with_audit_actor(actor.clone(), next.run(request)).await;
+    let status = response.status();
+    let entry = AuditEntry::new("admin.request")
+        .actor(actor)
+        .target(target)
+        .success(!status.is_client_error() && !status.is_server_error())
+        .detail(status.to_string());
+    audit_log.record(entry).await;
+    response
+}
+
+async fn collect_stats(state: &AppState) -> AdminStats {
+    AdminStats {
+        stats: state.stats.snapshot(),
+        quota: fetch_quota_usage(state).await,
+        analytics: state
+            .analytics
+            .report(state.storage.as_ref(), 24, 7)
+            .await
+            .inspect_err(|e| warn!("Failed to load analytics: {}", e))
+            .ok(),
+    }
+}
+
+async fn fetch_quota_usage(state: &AppState) -> Option<QuotaUsage> {
+    let quota = state.line_client.get_message_quota().await;
+    let consumption = state.line_client.get_message_quota_consumption().await;
+
+    match (quota, consumption) {
+        (Ok(quota), Ok(consumption)) => Some(QuotaUsage { quota, consumption }),
+        (Err(e), _) | (_, Err(e)) => {
+            warn!("Failed to fetch LINE quota usage: {}", e);
+            None
+        }
+    }
+}
+
+async fn stats(State(state): State<Arc<AppState>>) -> Json<AdminStats> {
+    Json(collect_stats(&state).await)
+}
+
+async fn dashboard(State(state): State<Arc<AppState>>) -> Html<String> {
+    Html(render_dashboard(&collect_stats(&state).await))
+}
+
+/// 好友數時間序列查詢參數，預設為到昨天為止的 30 天
+#[derive(Debug, Deserialize)]
+struct FollowerInsightQuery {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+}
+
+/// 逐日的好友數、可傳送人數與封鎖數，供儀表板繪圖
+async fn follower_insight(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<FollowerInsightQuery>,
+) -> Response {
+    // 當天的統計要到隔天才會計算完成
+    let to = query
+        .to
+        .unwrap_or_else(|| Utc::now().date_naive() - Duration::days(1));
+    let from = query.from.unwrap_or(to - Duration::days(29));
+    match state.line_client.get_follower_time_series(from, to).await {
+        Ok(series) => Json(series).into_response(),
+        Err(e) if e.status_code.is_none() && !e.network_error => {
+            (StatusCode::BAD_REQUEST, e.message).into_response()
+        }
+        Err(e) => {
+            warn!("Failed to fetch follower insight: {}", e);
+            StatusCode::BAD_GATEWAY.into_response()
+        }
+    }
+}
+
+/// 查詢使用者的對話紀錄（`user_id`，可選 `since`、`until`、`limit`）
+async fn conversations(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<Vec<ConversationEntry>>, StatusCode> {
+    state
+        .storage
+        .conversation_history(&query)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            warn!("Failed to query conversation history: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+/// 由新到舊查詢稽核紀錄
+async fn audit_log(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<AuditQuery>,
+) -> Result<Json<Vec<AuditEntry>>, StatusCode> {
+    state
+        .storage
+        .audit_log(&query)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            warn!("Failed to query audit log: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+/// 匯出查詢參數
+#[derive(Debug, Deserialize)]
+struct ExportQuery {
+    #[serde(default)]
+    format: ExportFormat,
+    #[serde(default)]
+    kind: ExportKind,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+}
+
+/// 以串流方式匯出對話紀錄與事件（JSONL 或 CSV）
+async fn export(State(state): State<Arc<AppState>>, Query(query): Query<ExportQuery>) -> Response {
+    let receiver = export_conversations(
+        state.storage.clone(),
+        query.format,
+        query.kind,
+        query.since,
+        query.until,
+    );
+    let disposition = format!(
+        "attachment; filename=\"conversations.{}\"",
+        query.format.file_extension()
+    );
+
+    (
+        [
+            (
+                header::CONTENT_TYPE,
+                query.format.content_type().to_string(),
+            ),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        Body::from_stream(ReceiverStream::new(receiver)),
+    )
+        .into_response()
+}
+
+/// 刪除使用者的所有資料
+async fn purge_user(State(state): State<Arc<AppState>>, Path(user_id): Path<String>) -> StatusCode {
+    match state.purge_user(&user_id).await {
+        Ok(()) => {
+            info!(
+                "Purged data for user {}",
+                SensitiveDataMasker::mask_user_id(&user_id)
+            );
+            StatusCode::NO_CONTENT
+        }
+        Err(e) => {
+            warn!("Failed to purge user data: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+async fn moderation_policy(State(state): State<Arc<AppState>>) -> Json<ModerationPolicy> {
+    Json(state.moderator.policy())
+}
+
+/// 在執行期間調整審核政策，重啟後會回到 `MODERATION_POLICY` 的設定
+async fn update_moderation_policy(
+    State(state): State<Arc<AppState>>,
+    Json(policy): Json<ModerationPolicy>,
+) -> Json<ModerationPolicy> {
+    info!("Moderation policy updated: {:?}", policy);
+    state.moderator.set_policy(policy);
+    Json(policy)
+}
+
+/// 目前生效的功能開關規則
+async fn feature_flags(State(state): State<Arc<AppState>>) -> Json<BTreeMap<String, FlagRule>> {
+    Json(state.feature_flags.rules())
+}
+
+/// 覆寫功能開關規則，保存在儲存後端，優先於 `FEATURE_FLAGS_FILE`
+async fn set_feature_flag(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Json(rule): Json<FlagRule>,
+) -> Response {
+    if rule.percentage > 100 {
+        return (
+            StatusCode::BAD_REQUEST,
+            "percentage must be between 0 and 100",
+        )
+            .into_response();
+    }
+    match state.feature_flags.set(&name, rule.clone()).await {
+        Ok(()) => {
+            info!("Feature flag {} updated: {:?}", name, rule);
+            Json(rule).into_response()
+        }
+        Err(e) => {
+            warn!("Failed to update feature flag: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// 移除覆寫，改回規則檔中的設定
+async fn remove_feature_flag(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> StatusCode {
+    match state.feature_flags.remove(&name).await {
+        Ok(true) => StatusCode::NO_CONTENT,
+        Ok(false) => StatusCode::NOT_FOUND,
+        Err(e) => {
+            warn!("Failed to remove feature flag: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// 錄製的 Webhook 清單，未啟用錄製時回傳 404
+async fn recordings(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<RecordingSummary>>, StatusCode> {
+    let recorder = state
+        .webhook_recorder
+        .as_ref()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    recorder.list().await.map(Json).map_err(|e| {
+        warn!("Failed to list webhook recordings: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+async fn recording(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<RecordedWebhook>, StatusCode> {
+    let recorder = state
+        .webhook_recorder
+        .as_ref()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    match recorder.get(&id).await {
+        Ok(Some(recorded)) => Ok(Json(recorded)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            warn!("Failed to load webhook recording: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ReplayRequest {
+    ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ReplayResult {
+    recordings: usize,
+    events: usize,
+}
+
+/// 依序將錄製的事件重新交給處理流程，不經過佇列與轉發；任一 ID 不存在時不處理任何事件
+///
+/// 重新處理會寫入儲存後端並呼叫 LINE API，只允許在 dry run 下執行，其餘情況回傳 409。
+async fn replay_recordings(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ReplayRequest>,
+) -> Response {
+    let Some(recorder) = &state.webhook_recorder else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    if !state.config.dry_run {
+        return (
+            StatusCode::CONFLICT,
+            "Replay is only allowed when DRY_RUN is enabled",
+        )
+            .into_response();
+    }
+
+    let mut requests = Vec::with_capacity(request.ids.len());
+    for id in &request.ids {
+        let recorded = match recorder.get(id).await {
+            Ok(Some(recorded)) => recorded,
+            Ok(None) => {
+                return (StatusCode::NOT_FOUND, format!("Recording {} not found", id))
+                    .into_response();
+            }
+            Err(e) => {
+                warn!("Failed to load webhook recording: {}", e);
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        };
+        match recorded.request() {
+            Ok(request) => requests.push(request),
+            Err(e) => {
+                return (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    format!("Recording {} is not a valid webhook: {}", id, e),
+                )
+                    .into_response();
+            }
+        }
+    }
+
+    let mut events = 0;
+    for request in requests {
+        for event in request.events {
+            handle_webhook_event(&state, event).await;
+            events += 1;
+        }
+    }
+    info!(
+        "Replayed {} webhook recordings ({} events)",
+        request.ids.len(),
+        events
+    );
+    Json(ReplayResult {
+        recordings: request.ids.len(),
+        events,
+    })
+    .into_response()
+}
+
+#[cfg(feature = "campaigns")]
+async fn campaigns(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<crate::campaigns::CampaignStatus>>, StatusCode> {
+    state.campaigns.campaigns().await.map(Json).map_err(|e| {
+        warn!("Failed to load campaigns: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+/// 新增或更新排程推播活動，ID 以路徑為準；新增回傳 201，更新回傳 200
+#[cfg(feature = "campaigns")]
+async fn upsert_campaign(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(mut campaign): Json<crate::campaigns::Campaign>,
+) -> Response {
+    campaign.id = id;
+    match state.campaigns.upsert(campaign).await {
+        Ok(true) => StatusCode::CREATED.into_response(),
+        Ok(false) => StatusCode::OK.into_response(),
+        Err(e) => campaign_error_response(e),
+    }
+}
+
+#[cfg(feature = "campaigns")]
+async fn remove_campaign(State(state): State<Arc<AppState>>, Path(id): Path<String>) -> Response {
+    match state.campaigns.remove(&id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => campaign_error_response(e),
+    }
+}
+
+#[cfg(feature = "campaigns")]
+fn campaign_error_response(error: crate::campaigns::CampaignError) -> Response {
+    use crate::campaigns::CampaignError;
+
+    let status = match &error {
+        CampaignError::Invalid(_) => StatusCode::BAD_REQUEST,
+        CampaignError::ReadOnly(_) => StatusCode::CONFLICT,
+        CampaignError::Storage(_) => {
+            warn!("Failed to update campaign: {}", error);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    };
+    (status, error.to_string()).into_response()
+}
+
+/// 以 Server-Sent Events 即時串流收到的 Webhook 事件（已遮罩）
+async fn event_stream(
+    State(state): State<Arc<AppState>>,
+) -> Sse<impl Stream<Item = Result<sse::Event, Infallible>>> {
+    let stream =
+        BroadcastStream::new(state.event_stream.subscribe()).filter_map(|item| match item {
+            Ok(value) => Some(Ok(sse::Event::default()
+                .event("webhook")
+                .data(value.to_string()))),
+            // 訂閱者處理太慢時會漏掉部分事件，僅記錄後繼續
+            Err(e) => {
+                warn!("Event stream subscriber lagged: {}", e);
+                None
+            }
+        });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+fn render_dashboard(stats: &AdminStats) -> String {
+    let snapshot = &stats.stats;
+
+    let event_rows: String = snapshot
+        .events
+        .iter()
+        .map(|(event_type, count)| format!("<tr><td>{}</td><td>{}</td></tr>", event_type, count))
+        .collect();
+
+    let quota = match &stats.quota {
+        Some(usage) => match usage.quota.value {
+            Some(limit) => format!("{} / {}", usage.consumption.total_usage, limit),
+            None => format!(
+                "{} / {}",
+                usage.consumption.total_usage, usage.quota.quota_type
+            ),
+        },
+        None => "unavailable".to_string(),
+    };
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head><meta charset="utf-8"><title>LINE Bot Dashboard</title></head>
+<body>
+<h1>LINE Bot Dashboard</h1>
+<table>
+<tr><th>Uptime (s)</th><td>{uptime}</td></tr>
+<tr><th>Total events</th><td>{total_events}</td></tr>
+<tr><th>Event errors</th><td>{event_errors} ({event_error_rate:.2}%)</td></tr>
+<tr><th>LINE API requests</th><td>{line_api_requests}</td></tr>
+<tr><th>LINE API errors</th><td>{line_api_errors} ({line_api_error_rate:.2}%)</td></tr>
+<tr><th>Rate limit hits</th><td>{rate_limit_hits}</td></tr>
+<tr><th>Message quota</th><td>{quota}</td></tr>
+</table>
+<h2>Events by type</h2>
+<table>
+<tr><th>Type</th><th>Count</th></tr>
+{event_rows}
+</table>
+</body>
+</html>"#,
+        uptime = snapshot.uptime_seconds,
+        total_events = snapshot.total_events,
+        event_errors = snapshot.event_errors,
+        event_error_rate = snapshot.event_error_rate * 100.0,
+        line_api_requests = snapshot.line_api_requests,
+        line_api_errors = snapshot.line_api_errors,
+        line_api_error_rate = snapshot.line_api_error_rate * 100.0,
+        rate_limit_hits = snapshot.rate_limit_hits,
+        quota = quota,
+        event_rows = event_rows,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::StatsAggregator;
+
+    #[test]
+    fn test_render_dashboard() {
+        let aggregator = StatsAggregator::new();
+        aggregator.record_event("message");
+        aggregator.record_rate_limit_hit();
+
+        let html = render_dashboard(&AdminStats {
+            stats: aggregator.snapshot(),
+            quota: None,
+            analytics: None,
+        });
+
+        assert!(html.contains("<tr><td>message</td><td>1</td></tr>"));
+        assert!(html.contains("<tr><th>Rate limit hits</th><td>1</td></tr>"));
+        assert!(html.contains("unavailable"));
+    }
+}
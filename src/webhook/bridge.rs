@@ -0,0 +1,271 @@
+//! 客服從 Slack 或 Discord 回覆使用者的端點
+//!
+//! 兩個平台都以 slash command `/line <使用者 ID> <訊息>` 回覆，訊息以 push 送出。
+//! Slack 以 signing secret 驗證，Discord 以應用程式的 Ed25519 public key 驗證；
+//! 未設定對應的密鑰時端點回傳 404。
+
+use axum::{
+    Json, Router,
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+};
+use chrono::Utc;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use sha2::Sha256;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::models::OutgoingMessage;
+use crate::utils::{SensitiveDataMasker, UserIdValidator};
+use crate::webhook::server::AppState;
+
+/// Slack 請求時間戳與現在的容許差距（秒），防止重放
+const SLACK_MAX_REQUEST_AGE_SECS: i64 = 300;
+
+/// Discord interaction 類型
+const DISCORD_PING: u64 = 1;
+const DISCORD_APPLICATION_COMMAND: u64 = 2;
+
+/// Discord 回應類型
+const DISCORD_PONG: u64 = 1;
+const DISCORD_CHANNEL_MESSAGE: u64 = 4;
+
+/// 只有操作者看得到的 Discord 訊息
+const DISCORD_EPHEMERAL_FLAG: u64 = 64;
+
+/// 建立 `/bridge` 路由
+pub fn bridge_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/slack", post(slack_command))
+        .route("/discord", post(discord_interaction))
+}
+
+#[derive(Deserialize)]
+struct SlackCommand {
+    text: String,
+    #[serde(default)]
+    user_name: String,
+}
+
+async fn slack_command(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let Some(secret) = state
+        .operator_bridge
+        .as_ref()
+        .and_then(|bridge| bridge.config().slack_signing_secret.as_deref())
+    else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    if !verify_slack_signature(secret, &headers, &body, Utc::now().timestamp()) {
+        warn!("Rejected Slack command with invalid signature");
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    let Ok(command) = serde_urlencoded::from_bytes::<SlackCommand>(&body) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+
+    let text = send_operator_reply(&state, &command.user_name, &command.text).await;
+    Json(json!({ "response_type": "ephemeral", "text": text })).into_response()
+}
+
+async fn discord_interaction(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let Some(public_key) = state
+        .operator_bridge
+        .as_ref()
+        .and_then(|bridge| bridge.config().discord_public_key.as_deref())
+    else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    if !verify_discord_signature(public_key, &headers, &body) {
+        warn!("Rejected Discord interaction with invalid signature");
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    let Ok(interaction) = serde_json::from_slice::<Value>(&body) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+
+    match interaction["type"].as_u64() {
+        Some(DISCORD_PING) => Json(json!({ "type": DISCORD_PONG })).into_response(),
+        Some(DISCORD_APPLICATION_COMMAND) => {
+            let option = |name: &str| {
+                interaction["data"]["options"]
+                    .as_array()
+                    .and_then(|options| options.iter().find(|option| option["name"] == name))
+                    .and_then(|option| option["value"].as_str())
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            let operator = interaction["member"]["user"]["username"]
+                .as_str()
+                .or_else(|| interaction["user"]["username"].as_str())
+                .unwrap_or_default();
+            let command = format!("{} {}", option("user_id"), option("message"));
+            let text = send_operator_reply(&state, operator, &command).await;
+            Json(json!({
+                "type": DISCORD_CHANNEL_MESSAGE,
+                "data": { "content": text, "flags": DISCORD_EPHEMERAL_FLAG },
+            }))
+            .into_response()
+        }
+        _ => StatusCode::BAD_REQUEST.into_response(),
+    }
+}
+
+/// 解析 `<使用者 ID> <訊息>` 並以 push 送出，回傳給操作者看的結果
+async fn send_operator_reply(state: &AppState, operator: &str, command: &str) -> String {
+    let (user_id, message) = command
+        .trim()
+        .split_once(char::is_whitespace)
+        .unwrap_or((command.trim(), ""));
+    let message = message.trim();
+    if UserIdValidator::validate(user_id).is_err() || message.is_empty() {
+        return "格式：/line <使用者 ID> <訊息>".to_string();
+    }
+
+    match state
+        .line_client
+        .push_message(user_id, vec![OutgoingMessage::text(message)])
+        .await
+    {
+        Ok(_) => {
+            info!(
+                "Operator {} replied to {}",
+                operator,
+                SensitiveDataMasker::mask_user_id(user_id)
+            );
+            format!("已傳送給 {}", user_id)
+        }
+        Err(e) => {
+            warn!("Failed to send operator reply: {}", e);
+            format!("傳送失敗：{}", e)
+        }
+    }
+}
+
+fn verify_slack_signature(secret: &str, headers: &HeaderMap, body: &[u8], now: i64) -> bool {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    let (Some(timestamp), Some(signature)) = (
+        header("x-slack-request-timestamp"),
+        header("x-slack-signature"),
+    ) else {
+        return false;
+    };
+    let Ok(sent_at) = timestamp.parse::<i64>() else {
+        return false;
+    };
+    if (now - sent_at).abs() > SLACK_MAX_REQUEST_AGE_SECS {
+        return false;
+    }
+    let Some(signature) = signature.strip_prefix("v0=").and_then(decode_hex) else {
+        return false;
+    };
+
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key size");
+    mac.update(format!("v0:{}:", timestamp).as_bytes());
+    mac.update(body);
+    mac.verify_slice(&signature).is_ok()
+}
+
+fn verify_discord_signature(public_key: &str, headers: &HeaderMap, body: &[u8]) -> bool {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    let (Some(timestamp), Some(signature)) = (
+        header("x-signature-timestamp"),
+        header("x-signature-ed25519"),
+    ) else {
+        return false;
+    };
+    let key = decode_hex(public_key)
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok());
+    let signature = decode_hex(signature)
+        .and_then(|bytes| <[u8; 64]>::try_from(bytes).ok())
+        .map(|bytes| Signature::from_bytes(&bytes));
+    let (Some(key), Some(signature)) = (key, signature) else {
+        return false;
+    };
+
+    let mut message = timestamp.as_bytes().to_vec();
+    message.extend_from_slice(body);
+    key.verify(&message, &signature).is_ok()
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn encode_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_verify_slack_signature() {
+        let body = b"command=%2Fline&text=U1+hi";
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"secret").unwrap();
+        mac.update(b"v0:1700000000:");
+        mac.update(body);
+        let signature = format!("v0={}", encode_hex(&mac.finalize().into_bytes()));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-slack-request-timestamp", "1700000000".parse().unwrap());
+        headers.insert("x-slack-signature", signature.parse().unwrap());
+        assert!(verify_slack_signature("secret", &headers, body, 1700000100));
+        assert!(!verify_slack_signature("other", &headers, body, 1700000100));
+        assert!(!verify_slack_signature(
+            "secret",
+            &headers,
+            b"tampered",
+            1700000100
+        ));
+        // 超過五分鐘的請求視為重放
+        assert!(!verify_slack_signature(
+            "secret", &headers, body, 1700000400
+        ));
+    }
+
+    #[test]
+    fn test_verify_discord_signature() {
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+        let public_key = encode_hex(signing_key.verifying_key().as_bytes());
+        let body = br#"{"type":1}"#;
+        let signature = signing_key.sign(&[b"1700000000".as_slice(), body].concat());
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-signature-timestamp", "1700000000".parse().unwrap());
+        headers.insert(
+            "x-signature-ed25519",
+            encode_hex(&signature.to_bytes()).parse().unwrap(),
+        );
+        assert!(verify_discord_signature(&public_key, &headers, body));
+        assert!(!verify_discord_signature(
+            &public_key,
+            &headers,
+            br#"{"type":2}"#
+        ));
+        assert!(!verify_discord_signature("zz", &headers, body));
+    }
+}
@@ -0,0 +1,84 @@
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+use crate::line_api::{LineApiClient, LineApiError};
+
+/// 確認 Webhook 的 `destination` 是這個 bot 的使用者 ID
+///
+/// 多個 bot 共用同一組基礎設施時，避免處理送錯或偽造的請求。未設定 `BOT_USER_ID` 時
+/// 以 `GET /v2/bot/info` 取得，成功後快取；啟動時取得失敗會在下一個 Webhook 重試。
+#[derive(Clone)]
+pub struct DestinationVerifier {
+    line_client: LineApiClient,
+    bot_user_id: Arc<OnceCell<String>>,
+}
+
+impl DestinationVerifier {
+    pub fn new(line_client: LineApiClient, bot_user_id: Option<String>) -> Self {
+        Self {
+            line_client,
+            bot_user_id: Arc::new(OnceCell::new_with(bot_user_id)),
+        }
+    }
+
+    /// Bot 的使用者 ID，尚未取得時呼叫 LINE API
+    pub async fn bot_user_id(&self) -> Result<&str, LineApiError> {
+        self.bot_user_id
+            .get_or_try_init(|| async { Ok(self.line_client.get_bot_info().await?.user_id) })
+            .await
+            .map(String::as_str)
+    }
+
+    pub async fn verify(&self, destination: &str) -> Result<bool, LineApiError> {
+        Ok(self.bot_user_id().await? == destination)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Json, Router, routing::get};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_destination_verifier_fetches_bot_info_once() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let app = Router::new().route(
+            "/v2/bot/info",
+            get(move || async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Json(serde_json::json!({
+                    "userId": "Ub9952f8a0bd9c5f9d3a4e4f4d2f3c4b1",
+                    "basicId": "@216ru...",
+                    "displayName": "Example name",
+                    "pictureUrl": "https://profile.line-scdn.net/example",
+                    "chatMode": "chat",
+                    "markAsReadMode": "manual"
+                }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = LineApiClient::builder("test_token")
+            .base_url(format!("http://{}/v2/bot", addr))
+            .build()
+            .unwrap();
+        let verifier = DestinationVerifier::new(client.clone(), None);
+        assert!(
+            verifier
+                .verify("Ub9952f8a0bd9c5f9d3a4e4f4d2f3c4b1")
+                .await
+                .unwrap()
+        );
+        assert!(!verifier.verify("Uother").await.unwrap());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // 已設定 ID 時不呼叫 API
+        let configured = DestinationVerifier::new(client, Some("Uconfigured".to_string()));
+        assert!(configured.verify("Uconfigured").await.unwrap());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
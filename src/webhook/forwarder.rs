@@ -0,0 +1,239 @@
+use bytes::Bytes;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::Value;
+use std::{collections::HashSet, sync::Arc, time::Duration};
+use tracing::{Instrument, Span, debug, warn};
+
+use crate::utils::generate_signature;
+use crate::webhook::REQUEST_ID_HEADER;
+
+/// 單次轉發的逾時，避免下游沒有回應時背景工作一直累積
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 轉發目標
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct ForwardTarget {
+    pub url: String,
+    /// 只轉發這些事件類型，未設定時轉發全部事件
+    pub event_types: Option<HashSet<String>>,
+}
+
+impl ForwardTarget {
+    /// 解析 `url` 或 `url|message,follow` 格式
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (url, types) = match spec.split_once('|') {
+            Some((url, types)) => (url, Some(types)),
+            None => (spec, None),
+        };
+
+        let url = url.trim();
+        if url.is_empty() {
+            return None;
+        }
+
+        let event_types = types.map(|types| {
+            types
+                .split(',')
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect()
+        });
+
+        Some(Self {
+            url: url.to_string(),
+            event_types,
+        })
+    }
+
+    fn accepts(&self, event: &Value) -> bool {
+        match &self.event_types {
+            Some(types) => event
+                .get("type")
+                .and_then(Value::as_str)
+                .is_some_and(|t| types.contains(t)),
+            None => true,
+        }
+    }
+}
+
+/// Webhook 轉發器
+///
+/// 將驗證過的 Webhook 內容以新的簽名轉送給下游（例如以正式環境流量測試 staging bot）。
+/// 轉發在背景執行，不影響回應 LINE Platform 的時間。
+#[derive(Clone)]
+pub struct WebhookForwarder {
+    client: Client,
+    targets: Arc<Vec<ForwardTarget>>,
+    secret: String,
+    max_retries: u32,
+}
+
+impl WebhookForwarder {
+    pub fn new(targets: Vec<ForwardTarget>, secret: String, max_retries: u32) -> Self {
+        Self {
+            client: Client::new(),
+            targets: Arc::new(targets),
+            secret,
+            max_retries,
+        }
+    }
+
+    /// 使用共用的 HTTP 用戶端
+    pub fn http_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.targets.is_empty()
+    }
+
+    /// 在背景轉發 Webhook 內容到所有目標，並帶上原請求的關聯 ID
+    pub fn forward(&self, body: Bytes, request_id: Option<&str>) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let payload: Value = match serde_json::from_slice(&body) {
+            Ok(payload) => payload,
+            Err(e) => {
+                warn!("Skipping webhook forwarding, invalid payload: {}", e);
+                return;
+            }
+        };
+
+        for target in self.targets.iter() {
+            let Some(body) = Self::payload_for(target, &payload) else {
+                debug!("No matching events for forward target {}", target.url);
+                continue;
+            };
+
+            let forwarder = self.clone();
+            let url = target.url.clone();
+            let request_id = request_id.map(str::to_string);
+            tokio::spawn(
+                async move {
+                    forwarder
+                        .send_with_retry(&url, body, request_id.as_deref())
+                        .await;
+                }
+                .instrument(Span::current()),
+            );
+        }
+    }
+
+    /// 依目標的事件過濾條件產生轉發內容，沒有符合的事件時回傳 None
+    fn payload_for(target: &ForwardTarget, payload: &Value) -> Option<String> {
+        if target.event_types.is_none() {
+            return Some(payload.to_string());
+        }
+
+        let events: Vec<Value> = payload
+            .get("events")?
+            .as_array()?
+            .iter()
+            .filter(|event| target.accepts(event))
+            .cloned()
+            .collect();
+
+        if events.is_empty() {
+            return None;
+        }
+
+        let mut filtered = payload.clone();
+        filtered["events"] = Value::Array(events);
+        Some(filtered.to_string())
+    }
+
+    async fn send_with_retry(&self, url: &str, body: String, request_id: Option<&str>) {
+        let signature = format!(
+            "sha256={}",
+            generate_signature(&self.secret, body.as_bytes())
+        );
+
+        for attempt in 0..=self.max_retries {
+            if attempt > 0 {
+                tokio::time::sleep(Duration::from_millis(500 * 2u64.pow(attempt - 1))).await;
+            }
+
+            let mut request = self
+                .client
+                .post(url)
+                .header("Content-Type", "application/json")
+                .header("x-line-signature", &signature)
+                .timeout(REQUEST_TIMEOUT)
+                .body(body.clone());
+            if let Some(request_id) = request_id {
+                request = request.header(REQUEST_ID_HEADER, request_id);
+            }
+            let result = request.send().await;
+
+            match result {
+                Ok(response) if response.status().is_success() => {
+                    debug!("Forwarded webhook to {}", url);
+                    return;
+                }
+                // 4xx 重試也不會成功
+                Ok(response) if response.status().is_client_error() => {
+                    warn!(
+                        "Forward target {} rejected webhook: {}",
+                        url,
+                        response.status()
+                    );
+                    return;
+                }
+                Ok(response) => {
+                    warn!(
+                        "Forward to {} failed with status {} (attempt {})",
+                        url,
+                        response.status(),
+                        attempt + 1
+                    );
+                }
+                Err(e) => {
+                    warn!("Forward to {} failed: {} (attempt {})", url, e, attempt + 1);
+                }
+            }
+        }
+
+        warn!("Giving up forwarding webhook to {}", url);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_forward_target_parse() {
+        let target = ForwardTarget::parse("https://staging.example.com/webhook").unwrap();
+        assert_eq!(target.url, "https://staging.example.com/webhook");
+        assert!(target.event_types.is_none());
+
+        let target = ForwardTarget::parse("https://a.example.com|message, follow").unwrap();
+        let types = target.event_types.unwrap();
+        assert!(types.contains("message"));
+        assert!(types.contains("follow"));
+
+        assert!(ForwardTarget::parse(" ").is_none());
+    }
+
+    #[test]
+    fn test_payload_filtered_by_event_type() {
+        let payload = json!({
+            "destination": "test",
+            "events": [{"type": "message"}, {"type": "follow"}]
+        });
+
+        let target = ForwardTarget::parse("https://a.example.com|follow").unwrap();
+        let body = WebhookForwarder::payload_for(&target, &payload).unwrap();
+        let forwarded: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(forwarded["events"], json!([{"type": "follow"}]));
+        assert_eq!(forwarded["destination"], "test");
+
+        let target = ForwardTarget::parse("https://a.example.com|postback").unwrap();
+        assert!(WebhookForwarder::payload_for(&target, &payload).is_none());
+    }
+}
@@ -0,0 +1,246 @@
+//! `/admin/graphql`：查詢統計、使用者與對話紀錄，送出訊息與管理 feed 訂閱
+//!
+//! 與其他 `/admin` 端點相同需要 Bearer token。請求與回應皆為 GraphQL over HTTP 的 JSON 格式。
+
+use async_graphql::{
+    Context, EmptySubscription, Error, Json as GraphQLJson, Object, Result, Schema, SimpleObject,
+};
+use axum::{Json, Router, routing::post};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::sync::Arc;
+
+use crate::line_api::SendOptions;
+use crate::models::OutgoingMessage;
+use crate::storage::{ConversationEntry, HistoryQuery, UserRecord};
+use crate::utils::{StatsSnapshot, UserIdValidator};
+use crate::webhook::server::AppState;
+
+pub type AdminSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+
+/// 查詢巢狀深度上限
+const MAX_QUERY_DEPTH: usize = 8;
+
+pub fn admin_schema(state: Arc<AppState>) -> AdminSchema {
+    Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+        .data(state)
+        .limit_depth(MAX_QUERY_DEPTH)
+        .finish()
+}
+
+/// 建立 `/graphql` 路由，由 `admin_router` 掛在 `/admin` 之下
+pub fn graphql_router(state: Arc<AppState>) -> Router<Arc<AppState>> {
+    let schema = admin_schema(state);
+    Router::new().route(
+        "/graphql",
+        post(move |Json(request): Json<async_graphql::Request>| {
+            let schema = schema.clone();
+            async move { Json(schema.execute(request).await) }
+        }),
+    )
+}
+
+fn app_state<'a>(ctx: &Context<'a>) -> &'a AppState {
+    ctx.data_unchecked::<Arc<AppState>>()
+}
+
+#[derive(SimpleObject)]
+pub struct Stats {
+    pub uptime_seconds: u64,
+    pub total_events: u64,
+    pub events: Vec<EventCount>,
+    pub event_errors: u64,
+    pub event_error_rate: f64,
+    pub line_api_requests: u64,
+    pub line_api_errors: u64,
+    pub line_api_error_rate: f64,
+    pub rate_limit_hits: u64,
+}
+
+#[derive(SimpleObject)]
+pub struct EventCount {
+    pub event_type: String,
+    pub count: u64,
+}
+
+impl From<StatsSnapshot> for Stats {
+    fn from(stats: StatsSnapshot) -> Self {
+        Self {
+            uptime_seconds: stats.uptime_seconds,
+            total_events: stats.total_events,
+            events: stats
+                .events
+                .into_iter()
+                .map(|(event_type, count)| EventCount { event_type, count })
+                .collect(),
+            event_errors: stats.event_errors,
+            event_error_rate: stats.event_error_rate,
+            line_api_requests: stats.line_api_requests,
+            line_api_errors: stats.line_api_errors,
+            line_api_error_rate: stats.line_api_error_rate,
+            rate_limit_hits: stats.rate_limit_hits,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct User {
+    pub user_id: String,
+    pub display_name: Option<String>,
+    pub language: Option<String>,
+    pub followed: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<UserRecord> for User {
+    fn from(user: UserRecord) -> Self {
+        Self {
+            user_id: user.user_id,
+            display_name: user.display_name,
+            language: user.language,
+            followed: user.followed,
+            updated_at: user.updated_at,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct Message {
+    pub user_id: String,
+    /// `incoming` 或 `outgoing`
+    pub direction: String,
+    /// 訊息內容（已依設定遮罩）
+    pub message: GraphQLJson<Value>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl From<ConversationEntry> for Message {
+    fn from(entry: ConversationEntry) -> Self {
+        Self {
+            user_id: entry.user_id,
+            direction: entry.direction.as_str().to_string(),
+            message: GraphQLJson(entry.message),
+            recorded_at: entry.recorded_at,
+        }
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn stats(&self, ctx: &Context<'_>) -> Stats {
+        app_state(ctx).stats.snapshot().into()
+    }
+
+    async fn user(&self, ctx: &Context<'_>, user_id: String) -> Result<Option<User>> {
+        let user = app_state(ctx).storage.get_user(&user_id).await?;
+        Ok(user.map(User::from))
+    }
+
+    /// 使用者的對話紀錄，時間區間為 `[since, until)`
+    async fn messages(
+        &self,
+        ctx: &Context<'_>,
+        user_id: String,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+        #[graphql(default = 100)] limit: u32,
+    ) -> Result<Vec<Message>> {
+        let query = HistoryQuery {
+            since,
+            until,
+            limit,
+            ..HistoryQuery::new(user_id)
+        };
+        let history = app_state(ctx).storage.conversation_history(&query).await?;
+        Ok(history.into_iter().map(Message::from).collect())
+    }
+
+    /// 訂閱 feed 的使用者（需啟用 `feeds` feature 並設定 `FEED_SOURCES`）
+    async fn subscribers(&self, ctx: &Context<'_>) -> Result<Vec<String>> {
+        feed_subscribers(app_state(ctx)).await
+    }
+}
+
+pub struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    /// 推播一則文字訊息
+    async fn send_message(
+        &self,
+        ctx: &Context<'_>,
+        to: String,
+        text: String,
+        #[graphql(default)] notification_disabled: bool,
+    ) -> Result<bool> {
+        let options = SendOptions::new().notification_disabled(notification_disabled);
+        app_state(ctx)
+            .line_client
+            .push_message_with_options(&to, vec![OutgoingMessage::text(text)], &options)
+            .await?;
+        Ok(true)
+    }
+
+    /// 推播一則文字訊息給所有好友
+    async fn broadcast(
+        &self,
+        ctx: &Context<'_>,
+        text: String,
+        #[graphql(default)] notification_disabled: bool,
+    ) -> Result<bool> {
+        let options = SendOptions::new().notification_disabled(notification_disabled);
+        app_state(ctx)
+            .line_client
+            .broadcast_message_with_options(vec![OutgoingMessage::text(text)], &options)
+            .await?;
+        Ok(true)
+    }
+
+    /// 訂閱 feed，回傳是否為新訂閱
+    async fn subscribe(&self, ctx: &Context<'_>, user_id: String) -> Result<bool> {
+        UserIdValidator::validate(&user_id)?;
+        update_subscription(app_state(ctx), &user_id, true).await
+    }
+
+    /// 取消訂閱 feed，回傳原本是否有訂閱
+    async fn unsubscribe(&self, ctx: &Context<'_>, user_id: String) -> Result<bool> {
+        UserIdValidator::validate(&user_id)?;
+        update_subscription(app_state(ctx), &user_id, false).await
+    }
+}
+
+#[cfg(feature = "feeds")]
+fn feed_scheduler(state: &AppState) -> Result<&crate::feeds::FeedScheduler> {
+    state
+        .feeds
+        .as_ref()
+        .ok_or_else(|| Error::new("Feed subscriptions are not configured"))
+}
+
+#[cfg(feature = "feeds")]
+async fn feed_subscribers(state: &AppState) -> Result<Vec<String>> {
+    Ok(feed_scheduler(state)?.subscribers().await?)
+}
+
+#[cfg(feature = "feeds")]
+async fn update_subscription(state: &AppState, user_id: &str, subscribe: bool) -> Result<bool> {
+    let feeds = feed_scheduler(state)?;
+    let changed = if subscribe {
+        feeds.subscribe(user_id).await?
+    } else {
+        feeds.unsubscribe(user_id).await?
+    };
+    Ok(changed)
+}
+
+#[cfg(not(feature = "feeds"))]
+async fn feed_subscribers(_state: &AppState) -> Result<Vec<String>> {
+    Err(Error::new("Feed subscriptions require the `feeds` feature"))
+}
+
+#[cfg(not(feature = "feeds"))]
+async fn update_subscription(_state: &AppState, _user_id: &str, _subscribe: bool) -> Result<bool> {
+    Err(Error::new("Feed subscriptions require the `feeds` feature"))
+}
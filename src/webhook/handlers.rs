@@ -1,104 +1,453 @@
-use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use axum::{Extension, extract::State, http::StatusCode, response::IntoResponse};
 use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::OnceCell;
+use tower_http::request_id::RequestId;
 use tracing::{error, info, warn};
 
-use crate::models::{Event, MessageEvent, MessageType, OutgoingMessage, WebhookRequest};
-use crate::utils::{ReplyTokenValidator, SensitiveDataMasker, TextValidator, record_webhook_event};
+use crate::line_api::SendOptions;
+use crate::media::{MediaPipeline, StoredMedia};
+use crate::messages;
+use crate::models::{
+    Event, MessageEvent, MessageType, OutgoingMessage, PostbackEvent, Source, WebhookRequest,
+    stickers,
+};
+use crate::nlu::NluConfig;
+use crate::plugins::{EventMiddleware, MiddlewareAction};
+use crate::storage::{DEFAULT_JOIN_WELCOME, GROUP_FEATURES, GroupSettings};
+use crate::utils::{
+    ErrorContext, ForbiddenWordList, LocalizedReplies, ModerationAction, ReplyText,
+    ReplyTokenValidator, SensitiveDataMasker, TextValidator,
+};
 use crate::webhook::server::AppState;
+use crate::webhook::signature::{VerifiedBody, VerifiedJson};
 
 pub async fn handle_webhook(
     State(state): State<Arc<AppState>>,
-    Json(payload): Json<WebhookRequest>,
+    Extension(VerifiedBody(body)): Extension<VerifiedBody>,
+    request_id: Option<Extension<RequestId>>,
+    VerifiedJson(payload): VerifiedJson<WebhookRequest>,
 ) -> impl IntoResponse {
+    if let Some(verifier) = &state.destination_verifier {
+        match verifier.verify(&payload.destination).await {
+            Ok(true) => {}
+            Ok(false) => {
+                warn!(
+                    "Rejected webhook for destination {}",
+                    SensitiveDataMasker::mask_user_id(&payload.destination)
+                );
+                return StatusCode::FORBIDDEN;
+            }
+            Err(e) => {
+                // 無法確認時回傳 503，讓 LINE 稍後重送
+                warn!("Failed to verify webhook destination: {}", e);
+                return StatusCode::SERVICE_UNAVAILABLE;
+            }
+        }
+    }
+
+    if payload.is_verification() {
+        info!("Received webhook verification request, skipping handlers");
+        return StatusCode::OK;
+    }
     info!("Received webhook with {} events", payload.events.len());
 
-    for event in payload.events {
-        if let Err(e) = process_event(&state, event).await {
-            error!("Failed to process event: {}", e);
+    // 錄製不影響回應時間
+    if let Some(recorder) = state.webhook_recorder.clone() {
+        let body = body.clone();
+        tokio::spawn(async move {
+            if let Err(e) = recorder.record(&body).await {
+                warn!("Failed to record webhook: {}", e);
+            }
+        });
+    }
+
+    let request_id = request_id
+        .as_ref()
+        .and_then(|Extension(id)| id.header_value().to_str().ok());
+
+    match &state.event_queue {
+        Some(queue) => {
+            if let Err(e) = queue.enqueue(payload.events).await {
+                // 回傳 503 讓 LINE 稍後重送，轉發也等到重送時才進行
+                warn!("{}, rejecting webhook", e);
+                return StatusCode::SERVICE_UNAVAILABLE;
+            }
+            state.forwarder.forward(body, request_id);
+        }
+        None => {
+            state.forwarder.forward(body, request_id);
+            for event in payload.events {
+                handle_webhook_event(&state, event).await;
+            }
         }
     }
 
     StatusCode::OK
 }
 
+/// 處理單一事件，錯誤只記錄與回報；啟用非同步處理時由 worker 呼叫
+pub async fn handle_webhook_event(state: &AppState, event: Event) {
+    state.event_stream.publish(&event);
+    if let Some(sinks) = &state.event_sinks {
+        sinks.record(&event);
+    }
+    let event_type = event.event_type();
+    if let Err(e) = process_event(state, event).await {
+        state.stats.record_event_error();
+        state.analytics.record_error();
+        error!("Failed to process event: {}", e);
+        if let Some(reporter) = &state.error_reporter {
+            reporter.report(e.as_ref(), &ErrorContext::event(event_type));
+        }
+    }
+}
+
 async fn process_event(state: &AppState, event: Event) -> Result<(), Box<dyn std::error::Error>> {
     // 記錄 webhook 事件指標
-    let event_type = match &event {
-        Event::Message(_) => "message",
-        Event::Follow(_) => "follow",
-        Event::Unfollow(_) => "unfollow",
-        Event::Join(_) => "join",
-        Event::Leave(_) => "leave",
-        Event::Postback(_) => "postback",
-    };
-    record_webhook_event(event_type);
+    let event_type = event.event_type();
+    state.metrics.record_webhook_event(event_type);
+    state.stats.record_event(event_type);
+
+    let start = Instant::now();
+    let result = dispatch_event(state, event).await;
+    state
+        .metrics
+        .record_event_processing(event_type, start.elapsed(), result.is_ok());
+    result
+}
 
+async fn dispatch_event(state: &AppState, event: Event) -> Result<(), Box<dyn std::error::Error>> {
+    // 重複訊息在外掛中介層之前略過
+    if let Some(filter) = &state.duplicate_filter
+        && filter.handle(state, &event).await == MiddlewareAction::Handled
+    {
+        return Ok(());
+    }
+    // 訊息在 `handle_message_event` 記錄，其他事件在中介層之前記錄
+    if !matches!(event, Event::Message(_))
+        && let Some(conversation_log) = &state.conversation_log
+        && let Some(user_id) = event.source().user_id()
+    {
+        conversation_log.record_event(user_id, &event).await;
+    }
+    // 訊息事件的中介層在記錄與審核之後才執行，見 `handle_message_event`
+    if !matches!(event, Event::Message(_))
+        && state.plugins.run_middlewares(state, &event).await == MiddlewareAction::Handled
+    {
+        return Ok(());
+    }
+    // 處理完可能改變狀態的事件後，依狀態切換 rich menu
+    let rich_menu_user = match &event {
+        Event::Message(_) | Event::Postback(_) | Event::Follow(_) => {
+            event.source().user_id().map(str::to_string)
+        }
+        _ => None,
+    };
     match event {
         Event::Message(message_event) => {
             handle_message_event(state, message_event).await?;
         }
         Event::Follow(follow_event) => {
             info!("User followed: {:?}", follow_event);
-            let welcome_message = OutgoingMessage::text("歡迎使用 LINE Bot！");
+            #[cfg(feature = "bridge")]
+            if let Some(bridge) = &state.operator_bridge
+                && let Some(user_id) = follow_event.source.user_id()
+            {
+                bridge.notify_follow(user_id);
+            }
+            let welcome_messages = match (&state.onboarding, follow_event.source.user_id()) {
+                (Some(onboarding), Some(user_id)) => {
+                    let profile = if onboarding.config().enrich_profile {
+                        state
+                            .line_client
+                            .get_profile(user_id)
+                            .await
+                            .inspect_err(|e| warn!("Failed to get profile for onboarding: {}", e))
+                            .ok()
+                    } else {
+                        None
+                    };
+                    onboarding.start(user_id, profile.as_ref()).await?
+                }
+                _ => {
+                    let locale = ReplyLocale::new(state, follow_event.source.user_id(), None);
+                    messages![locale.replies().await.text(ReplyText::Welcome)]
+                }
+            };
             state
                 .line_client
-                .reply_message(&follow_event.reply_token, vec![welcome_message])
+                .reply_message(&follow_event.reply_token, welcome_messages)
                 .await?;
         }
         Event::Unfollow(unfollow_event) => {
             info!("User unfollowed: {:?}", unfollow_event);
+            if state.config.purge_on_unfollow
+                && let Source::User { user_id } = &unfollow_event.source
+            {
+                state.purge_user(user_id).await?;
+                info!(
+                    "Purged data for unfollowed user {}",
+                    SensitiveDataMasker::mask_user_id(user_id)
+                );
+            }
         }
         Event::Join(join_event) => {
             info!("Bot joined: {:?}", join_event);
-            let welcome_message = OutgoingMessage::text("大家好！我是你們的 LINE Bot 助手！");
-            state
-                .line_client
-                .reply_message(&join_event.reply_token, vec![welcome_message])
-                .await?;
+            invalidate_group(state, &join_event.source);
+            let settings = chat_settings(state, &join_event.source).await;
+            if settings.is_enabled("welcome") {
+                let template = settings
+                    .welcome_template
+                    .as_deref()
+                    .unwrap_or(DEFAULT_JOIN_WELCOME);
+                let group_name = welcome_group_name(state, &join_event.source, template).await;
+                let welcome_message =
+                    localize(state, &settings, settings.join_welcome(&group_name)).await;
+                state
+                    .line_client
+                    .reply_message(&join_event.reply_token, welcome_message)
+                    .await?;
+            }
         }
         Event::Leave(leave_event) => {
             info!("Bot left: {:?}", leave_event);
+            invalidate_group(state, &leave_event.source);
         }
         Event::Postback(postback_event) => {
             info!("Postback received: {:?}", postback_event);
-            let response =
-                OutgoingMessage::text(format!("收到 postback: {}", postback_event.postback.data));
+            let responses =
+                if let Some(responses) = onboarding_postback_reply(state, &postback_event).await? {
+                    responses
+                } else {
+                    match payment_postback_reply(state, &postback_event).await {
+                        Some(response) => vec![response],
+                        None => vec![OutgoingMessage::echo(
+                            "收到 postback: ",
+                            &postback_event.postback.data,
+                        )],
+                    }
+                };
             state
                 .line_client
-                .reply_message(&postback_event.reply_token, vec![response])
+                .reply_message(&postback_event.reply_token, responses)
                 .await?;
         }
+        Event::MemberJoined(member_joined_event) => {
+            info!(
+                "{} members joined",
+                member_joined_event.joined.members.len()
+            );
+            invalidate_group(state, &member_joined_event.source);
+            let settings = chat_settings(state, &member_joined_event.source).await;
+            if settings.is_enabled("welcome")
+                && let Some(template) = &settings.member_welcome_template
+            {
+                let group_name =
+                    welcome_group_name(state, &member_joined_event.source, template).await;
+                let count = member_joined_event.joined.members.len();
+                if let Some(text) = settings.member_welcome(&group_name, count) {
+                    let welcome_message = localize(state, &settings, text).await;
+                    state
+                        .line_client
+                        .reply_message(&member_joined_event.reply_token, welcome_message)
+                        .await?;
+                }
+            }
+        }
+        Event::MemberLeft(member_left_event) => {
+            info!("{} members left", member_left_event.left.members.len());
+            invalidate_group(state, &member_left_event.source);
+        }
     }
 
+    if let (Some(rich_menus), Some(user_id)) = (&state.rich_menus, rich_menu_user)
+        && let Err(e) = rich_menus.sync(&user_id).await
+    {
+        warn!("Failed to sync rich menu state: {}", e);
+    }
     Ok(())
 }
 
+/// 群組或聊天室的設定，一對一聊天或讀取失敗時使用預設值
+async fn chat_settings(state: &AppState, source: &Source) -> GroupSettings {
+    if matches!(source, Source::User { .. }) {
+        return GroupSettings::default();
+    }
+    match state.group_settings.get(source.chat_id()).await {
+        Ok(settings) => settings,
+        Err(e) => {
+            warn!("Failed to load group settings: {}", e);
+            GroupSettings::default()
+        }
+    }
+}
+
+/// 樣板用到 `{group}` 時才查詢群組名稱，聊天室沒有名稱
+async fn welcome_group_name(state: &AppState, source: &Source, template: &str) -> String {
+    let Some(group_id) = source.group_id() else {
+        return String::new();
+    };
+    if !template.contains("{group}") {
+        return String::new();
+    }
+    match state.group_cache.get(group_id).await {
+        Ok(info) => info.summary.group_name,
+        Err(e) => {
+            warn!("Failed to get group name for welcome message: {}", e);
+            String::new()
+        }
+    }
+}
+
+/// 群組設定了語言且啟用翻譯時，把文字翻成該語言
+async fn localize(
+    state: &AppState,
+    settings: &GroupSettings,
+    text: String,
+) -> Vec<OutgoingMessage> {
+    let messages = vec![OutgoingMessage::text(text)];
+    match (&state.translation, &settings.language) {
+        (Some(translation), Some(language)) => translation.outbound(messages, language).await,
+        _ => messages,
+    }
+}
+
+/// 成員變動後清除群組快取，下次存取時重新取得
+fn invalidate_group(state: &AppState, source: &Source) {
+    if let Some(group_id) = source.group_id() {
+        state.group_cache.invalidate(group_id);
+    }
+}
+
 async fn handle_message_event(
     state: &AppState,
     event: MessageEvent,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    // 驗證 reply token
-    if let Err(validation_error) = ReplyTokenValidator::validate(&event.reply_token) {
-        warn!("Invalid reply token: {}", validation_error);
-        return Err(format!("Invalid reply token: {}", validation_error).into());
-    }
-
     // 記錄敏感資料（遮罩處理）
+    let user_id = get_user_id_from_source(&event.source);
     info!(
         "Processing message from user: {}",
-        SensitiveDataMasker::mask_user_id(&get_user_id_from_source(&event.source))
+        SensitiveDataMasker::mask_user_id(&user_id)
     );
 
-    let text_validator = TextValidator::new().max_length(1000);
+    if let Some(conversation_log) = &state.conversation_log {
+        conversation_log
+            .record_incoming(&user_id, &event.message)
+            .await;
+    }
+    state
+        .analytics
+        .record_message(&user_id, event.message.message_type());
+    let settings = chat_settings(state, &event.source).await;
+    let locale = ReplyLocale::new(state, event.source.user_id(), settings.language.as_deref());
+
+    // 文字訊息通過審核後才交給外掛中介層
+    if !matches!(event.message, MessageType::Text { .. })
+        && message_handled_by_plugins(state, &event).await
+    {
+        return Ok(());
+    }
+
+    // 禁用詞由 moderator 依政策處置
+    let text_validator = TextValidator::new()
+        .max_length(1000)
+        .forbidden_words(ForbiddenWordList::empty());
+    let mut reply_language = None;
     let response_messages = match &event.message {
         MessageType::Text { text } => {
             // 驗證文字輸入
             if let Err(validation_error) = text_validator.validate(text) {
                 warn!("Invalid text input: {}", validation_error);
-                vec![OutgoingMessage::text("抱歉，您的訊息包含無效內容。")]
+                messages![locale.replies().await.text(ReplyText::InvalidText)]
             } else {
-                info!("Received text message: {}", text);
-                handle_text_message(text)
+                #[cfg(feature = "bridge")]
+                if let Some(bridge) = &state.operator_bridge
+                    && let Some(user_id) = event.source.user_id()
+                {
+                    bridge.notify_message(user_id, text);
+                }
+                // 翻成工作語言後再比對指令；禁用詞同時比對原文與譯文，取較嚴重的結果
+                let original = text;
+                let text = match &state.translation {
+                    Some(translation) => {
+                        let translated = translation.inbound(event.source.user_id(), text).await;
+                        reply_language = settings.language.clone().or(translated.reply_language);
+                        translated.text
+                    }
+                    None => text.clone(),
+                };
+                let text = text.as_str();
+                let decision = state.moderator.check_all([original.as_str(), text]);
+                if let Some(decision) = decision {
+                    warn!(
+                        "Message matched {} severity forbidden words, action: {:?}",
+                        decision.severity.as_str(),
+                        decision.action
+                    );
+                    if let Some(reporter) = &state.moderation_reporter {
+                        reporter.report(&user_id, original, decision).await;
+                    }
+                }
+                match decision.map(|d| d.action) {
+                    Some(ModerationAction::WarnUser) => {
+                        messages![locale.replies().await.text(ReplyText::InappropriateText)]
+                    }
+                    Some(ModerationAction::DropMessage | ModerationAction::NotifyAdmin) => {
+                        Vec::new()
+                    }
+                    Some(ModerationAction::Ignore) | None => {
+                        info!("Received text message: {}", text);
+                        if message_handled_by_plugins(state, &event).await {
+                            Vec::new()
+                        } else if let Some(messages) = onboarding_reply(state, &event).await? {
+                            messages
+                        } else if let Some(messages) =
+                            group_settings_reply(state, &event, text).await
+                        {
+                            messages
+                        } else if let Some(result) =
+                            state.plugins.handle_command(state, &event, text).await
+                        {
+                            result?
+                        } else {
+                            let command = match command_name(text) {
+                                Some(command) => {
+                                    state.analytics.record_command(command);
+                                    Some(command.to_string())
+                                }
+                                None => resolve_intent_command(state, &user_id, text).await,
+                            };
+                            let command = command.as_deref();
+                            if let Some(command) = command
+                                && let Some(messages) =
+                                    feed_subscription_reply(state, event.source.user_id(), command)
+                                        .await
+                            {
+                                messages
+                            } else if settings.is_enabled("scripts")
+                                && let Some(messages) = script_reply(state, &user_id, text)
+                            {
+                                messages
+                            } else if command.is_none()
+                                && settings.is_enabled("llm")
+                                && let Some(messages) = llm_reply(state, &event).await
+                            {
+                                messages
+                            } else if !settings.is_enabled("replies") {
+                                Vec::new()
+                            } else if let Some(messages) =
+                                render_reply_template(state, &user_id, command, text).await
+                            {
+                                messages
+                            } else if let Some(command) = command {
+                                command_reply(command, text, &locale.replies().await)
+                            } else {
+                                handle_text_message(text, &locale.replies().await)
+                            }
+                        }
+                    }
+                }
             }
         }
         MessageType::Sticker {
@@ -109,52 +458,635 @@ async fn handle_message_event(
                 "Received sticker: package_id={}, sticker_id={}",
                 package_id, sticker_id
             );
-            vec![OutgoingMessage::text("收到貼圖！")]
+            messages![locale.replies().await.text(ReplyText::StickerReceived)]
         }
         MessageType::Image { .. } => {
             info!("Received image message");
-            vec![OutgoingMessage::text("收到圖片！")]
+            let replies = locale.replies().await;
+            if reply_after_storing_media(state, &event, &user_id, replies, ReplyText::ImageReceived)
+            {
+                Vec::new()
+            } else {
+                messages![replies.text(ReplyText::ImageReceived)]
+            }
+        }
+        MessageType::Video { .. } => {
+            info!("Received video message");
+            store_media_in_background(state, &event.message);
+            messages![locale.replies().await.text(ReplyText::VideoReceived)]
+        }
+        MessageType::Audio { .. } => {
+            info!("Received audio message");
+            store_media_in_background(state, &event.message);
+            messages![locale.replies().await.text(ReplyText::AudioReceived)]
+        }
+        MessageType::File { file_name, .. } => {
+            info!("Received file message: {}", file_name);
+            let replies = locale.replies().await;
+            if reply_after_storing_media(state, &event, &user_id, replies, ReplyText::FileReceived)
+            {
+                Vec::new()
+            } else {
+                messages![replies.text(ReplyText::FileReceived)]
+            }
+        }
+    };
+
+    let response_messages = match (&state.translation, &reply_language) {
+        (Some(translation), Some(language)) => {
+            translation.outbound(response_messages, language).await
         }
+        _ => response_messages,
     };
 
     if !response_messages.is_empty() {
+        // 回覆前才驗證 reply token，重新處理遮罩後的錄製事件時仍會經過審核與外掛
+        if let Err(validation_error) = ReplyTokenValidator::validate(&event.reply_token) {
+            warn!("Invalid reply token: {}", validation_error);
+            return Err(format!("Invalid reply token: {}", validation_error).into());
+        }
         state
             .line_client
-            .reply_message(&event.reply_token, response_messages)
+            .reply_message_with_options(
+                &event.reply_token,
+                response_messages,
+                &SendOptions::new().user_id(user_id.as_str()),
+            )
             .await?;
     }
 
     Ok(())
 }
 
-fn handle_text_message(text: &str) -> Vec<OutgoingMessage> {
-    match text.to_lowercase().trim() {
-        "hello" | "hi" | "你好" | "哈囉" => {
-            vec![OutgoingMessage::text("你好！有什麼可以幫助你的嗎？")]
+/// 執行外掛中介層，任一個處理了訊息時回傳 `true`
+async fn message_handled_by_plugins(state: &AppState, event: &MessageEvent) -> bool {
+    !state.plugins.is_empty()
+        && state
+            .plugins
+            .run_middlewares(state, &Event::Message(event.clone()))
+            .await
+            == MiddlewareAction::Handled
+}
+
+/// 引導進行中時把一對一聊天的文字當成回答；以原始文字比對，不經翻譯
+async fn onboarding_reply(
+    state: &AppState,
+    event: &MessageEvent,
+) -> Result<Option<Vec<OutgoingMessage>>, Box<dyn std::error::Error>> {
+    let (Some(onboarding), Source::User { user_id }, MessageType::Text { text, .. }) =
+        (&state.onboarding, &event.source, &event.message)
+    else {
+        return Ok(None);
+    };
+    Ok(onboarding.handle_text(user_id, text).await?)
+}
+
+/// 處理引導流程的語言選擇，不是引導的 postback 時回傳 `None`
+async fn onboarding_postback_reply(
+    state: &AppState,
+    event: &PostbackEvent,
+) -> Result<Option<Vec<OutgoingMessage>>, Box<dyn std::error::Error>> {
+    let (Some(onboarding), Some(user_id)) = (&state.onboarding, event.source.user_id()) else {
+        return Ok(None);
+    };
+    Ok(onboarding
+        .handle_postback(user_id, &event.postback.data)
+        .await?)
+}
+
+/// 群組設定指令的前綴
+const GROUP_SETTINGS_COMMAND: &str = "/group";
+
+/// 無法對應到已知指令的意圖在使用量統計中的名稱
+const UNKNOWN_COMMAND: &str = "unknown";
+
+/// 群組管理者以 `/group` 指令查看或變更群組設定；不是群組指令或未設定管理者時回傳 `None`
+async fn group_settings_reply(
+    state: &AppState,
+    event: &MessageEvent,
+    text: &str,
+) -> Option<Vec<OutgoingMessage>> {
+    let args = text
+        .trim()
+        .strip_prefix(GROUP_SETTINGS_COMMAND)
+        .filter(|args| args.is_empty() || args.starts_with(char::is_whitespace))?;
+    if matches!(event.source, Source::User { .. }) || state.config.group_admin_user_ids.is_empty() {
+        return None;
+    }
+    let is_admin = event.source.user_id().is_some_and(|user_id| {
+        state
+            .config
+            .group_admin_user_ids
+            .iter()
+            .any(|id| id == user_id)
+    });
+    if !is_admin {
+        return Some(messages!["只有管理者可以變更群組設定。"]);
+    }
+
+    let chat_id = event.source.chat_id();
+    let mut settings = match state.group_settings.get(chat_id).await {
+        Ok(settings) => settings,
+        Err(e) => {
+            error!("Failed to load group settings: {}", e);
+            return Some(messages!["目前無法讀取群組設定，請稍後再試。"]);
         }
-        "help" | "幫助" | "說明" => {
-            vec![OutgoingMessage::text(
-                "可用指令：\n• hello - 打招呼\n• help - 顯示說明\n• time - 顯示目前時間\n• sticker - 發送貼圖",
-            )]
+    };
+    let (text, changed) = apply_group_command(&mut settings, args.trim());
+    if changed && let Err(e) = state.group_settings.save(chat_id, &settings).await {
+        error!("Failed to save group settings: {}", e);
+        return Some(messages!["目前無法儲存群組設定，請稍後再試。"]);
+    }
+    Some(messages![text])
+}
+
+/// 把 `/group` 之後的參數套用到設定，回傳回覆文字與設定是否變更
+fn apply_group_command(settings: &mut GroupSettings, args: &str) -> (String, bool) {
+    let (action, value) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+    let value = value.trim();
+    let optional = |value: &str| (!value.is_empty() && value != "off").then(|| value.to_string());
+    match action.to_lowercase().as_str() {
+        "" => (describe_group_settings(settings), false),
+        "language" if !value.is_empty() => {
+            settings.language = optional(value);
+            ("已更新回覆語言。".to_string(), true)
         }
-        "time" | "時間" => {
-            let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC");
-            vec![OutgoingMessage::text(format!("目前時間：{}", now))]
+        "welcome" if !value.is_empty() => {
+            settings.welcome_template = optional(value);
+            ("已更新歡迎訊息。".to_string(), true)
         }
-        "sticker" | "貼圖" => {
-            vec![OutgoingMessage::sticker("1", "1")]
+        "member-welcome" if !value.is_empty() => {
+            settings.member_welcome_template = optional(value);
+            ("已更新新成員歡迎訊息。".to_string(), true)
         }
-        _ => {
-            if let Some(echo_text) = text.strip_prefix("echo ") {
-                vec![OutgoingMessage::text(format!("回音：{}", echo_text))]
-            } else if let Some(echo_text) = text.strip_prefix("回音 ") {
-                vec![OutgoingMessage::text(format!("回音：{}", echo_text))]
+        action @ ("enable" | "disable") if GROUP_FEATURES.contains(&value) => {
+            if action == "enable" {
+                settings.disabled_features.remove(value);
             } else {
-                vec![OutgoingMessage::text(
-                    "我不太理解你的意思，試試輸入 'help' 查看可用指令。",
-                )]
+                settings.disabled_features.insert(value.to_string());
+            }
+            (
+                format!(
+                    "已{}功能 {}。",
+                    if action == "enable" {
+                        "啟用"
+                    } else {
+                        "停用"
+                    },
+                    value
+                ),
+                true,
+            )
+        }
+        _ => (
+            format!(
+                "群組設定指令：\n\
+                 /group：查看設定\n\
+                 /group language <語言代碼|off>\n\
+                 /group welcome <訊息|off>\n\
+                 /group member-welcome <訊息|off>\n\
+                 /group enable|disable <功能>\n\
+                 訊息可使用 {{group}}（群組名稱）與 {{count}}（加入人數），功能：{}",
+                GROUP_FEATURES.join("、")
+            ),
+            false,
+        ),
+    }
+}
+
+fn describe_group_settings(settings: &GroupSettings) -> String {
+    let disabled: Vec<&str> = settings
+        .disabled_features
+        .iter()
+        .map(String::as_str)
+        .collect();
+    format!(
+        "群組設定\n語言：{}\n歡迎訊息：{}\n新成員歡迎訊息：{}\n停用功能：{}",
+        settings.language.as_deref().unwrap_or("未設定"),
+        settings
+            .welcome_template
+            .as_deref()
+            .unwrap_or(DEFAULT_JOIN_WELCOME),
+        settings
+            .member_welcome_template
+            .as_deref()
+            .unwrap_or("未設定"),
+        if disabled.is_empty() {
+            "無".to_string()
+        } else {
+            disabled.join("、")
+        }
+    )
+}
+
+/// 在背景下載並保存圖片或檔案，保存後再回覆；回覆附上保存的 https 網址
+///
+/// 未設定媒體儲存時回傳 `false`，由呼叫端直接回覆。
+fn reply_after_storing_media(
+    state: &AppState,
+    event: &MessageEvent,
+    user_id: &str,
+    replies: LocalizedReplies,
+    received: ReplyText,
+) -> bool {
+    let Some(pipeline) = state.media_pipeline.clone() else {
+        return false;
+    };
+    let client = state.line_client.clone();
+    let message = event.message.clone();
+    let reply_token = event.reply_token.clone();
+    let user_id = user_id.to_string();
+    tokio::spawn(async move {
+        let stored = process_media(&pipeline, &message).await;
+        let messages = media_reply(replies, received, stored.as_ref());
+        if let Err(validation_error) = ReplyTokenValidator::validate(&reply_token) {
+            warn!("Invalid reply token: {}", validation_error);
+            return;
+        }
+        let options = SendOptions::new().user_id(user_id);
+        if let Err(e) = client
+            .reply_message_with_options(&reply_token, messages, &options)
+            .await
+        {
+            error!("Failed to reply after storing media: {}", e);
+        }
+    });
+    true
+}
+
+fn media_reply(
+    replies: LocalizedReplies,
+    received: ReplyText,
+    stored: Option<&StoredMedia>,
+) -> Vec<OutgoingMessage> {
+    let mut messages = messages![replies.text(received)];
+    if let Some(media) = stored.filter(|media| media.url.starts_with("https://")) {
+        messages.push(OutgoingMessage::text(format!(
+            "{}{}",
+            replies.text(ReplyText::MediaStored),
+            media.url
+        )));
+    }
+    messages
+}
+
+/// 影片與音訊要等 LINE 轉檔完成才能下載，在背景保存以免延遲回覆
+fn store_media_in_background(state: &AppState, message: &MessageType) {
+    let Some(pipeline) = state.media_pipeline.clone() else {
+        return;
+    };
+    let message = message.clone();
+    tokio::spawn(async move {
+        process_media(&pipeline, &message).await;
+    });
+}
+
+async fn process_media(pipeline: &MediaPipeline, message: &MessageType) -> Option<StoredMedia> {
+    match pipeline.process(message).await {
+        Ok(stored) => {
+            if let Some(media) = &stored {
+                info!("Media stored at {}", media.url);
             }
+            stored
+        }
+        Err(e) => {
+            error!("Failed to store media: {}", e);
+            None
+        }
+    }
+}
+
+/// 指令比對失敗時以意圖解析服務判斷對應的指令
+async fn resolve_intent_command(state: &AppState, user_id: &str, text: &str) -> Option<String> {
+    let resolver = state.intent_resolver.as_ref()?;
+    let config = state.config.nlu.as_ref()?;
+    let session_id = if user_id.is_empty() {
+        "anonymous"
+    } else {
+        user_id
+    };
+    match resolver.resolve(session_id, text).await {
+        Ok(Some(intent)) if intent.confidence >= config.min_confidence => {
+            info!("Resolved intent {} ({:.2})", intent.name, intent.confidence);
+            state
+                .analytics
+                .record_command(intent_analytics_command(config, &intent.name));
+            Some(config.command_for(&intent.name).to_string())
+        }
+        Ok(_) => None,
+        Err(e) => {
+            warn!("{}", e);
+            None
+        }
+    }
+}
+
+/// 使用量統計記錄的意圖指令；未列在 `intent_commands` 又不是內建指令的意圖記為 `unknown`，
+/// 避免任意的意圖名稱讓統計項目無限增加
+fn intent_analytics_command<'a>(config: &'a NluConfig, intent: &'a str) -> &'a str {
+    let command = config.command_for(intent);
+    if config.intent_commands.contains_key(intent) || command_name(command).is_some() {
+        command
+    } else {
+        UNKNOWN_COMMAND
+    }
+}
+
+/// 文字對應的內建指令名稱，供使用量統計
+fn command_name(text: &str) -> Option<&'static str> {
+    match text.to_lowercase().trim() {
+        "hello" | "hi" | "你好" | "哈囉" => Some("hello"),
+        "help" | "幫助" | "說明" => Some("help"),
+        "time" | "時間" => Some("time"),
+        "sticker" | "貼圖" => Some("sticker"),
+        "subscribe" | "訂閱" => Some("subscribe"),
+        "unsubscribe" | "取消訂閱" => Some("unsubscribe"),
+        _ if text.starts_with("echo ") || text.starts_with("回音 ") => Some("echo"),
+        _ => None,
+    }
+}
+
+/// 處理 `subscribe`、`unsubscribe` 指令，未設定 feed 時回傳 `None`
+#[cfg(feature = "feeds")]
+async fn feed_subscription_reply(
+    state: &AppState,
+    user_id: Option<&str>,
+    command: &str,
+) -> Option<Vec<OutgoingMessage>> {
+    let feeds = state.feeds.as_ref()?;
+    let subscribe = match command {
+        "subscribe" => true,
+        "unsubscribe" => false,
+        _ => return None,
+    };
+    let Some(user_id) = user_id else {
+        return Some(messages!["無法取得你的使用者 ID，請加入好友後再試一次。"]);
+    };
+
+    let result = if subscribe {
+        feeds.subscribe(user_id).await
+    } else {
+        feeds.unsubscribe(user_id).await
+    };
+    let text = match (subscribe, result) {
+        (true, Ok(true)) => "已訂閱，有新內容時會通知你。",
+        (true, Ok(false)) => "你已經訂閱了。",
+        (false, Ok(true)) => "已取消訂閱。",
+        (false, Ok(false)) => "你目前沒有訂閱。",
+        (_, Err(e)) => {
+            error!("Failed to update feed subscription for {}: {}", user_id, e);
+            "目前無法處理訂閱，請稍後再試。"
+        }
+    };
+    Some(messages![text])
+}
+
+#[cfg(not(feature = "feeds"))]
+async fn feed_subscription_reply(
+    _state: &AppState,
+    _user_id: Option<&str>,
+    _command: &str,
+) -> Option<Vec<OutgoingMessage>> {
+    None
+}
+
+/// 處理 LINE Pay 付款卡片的「我已完成付款」，不是付款 postback 時回傳 `None`
+#[cfg(feature = "pay")]
+async fn payment_postback_reply(
+    state: &AppState,
+    event: &PostbackEvent,
+) -> Option<OutgoingMessage> {
+    let pay = state.line_pay.as_ref()?;
+    let user_id = event.source.user_id()?;
+    let text = match pay.handle_postback(user_id, &event.postback.data).await? {
+        Ok(payment) => {
+            info!(
+                "Payment {} confirmed for order {}",
+                payment.transaction_id, payment.order_id
+            );
+            format!(
+                "付款完成：{} {} {}",
+                payment.product_name, payment.amount, payment.currency
+            )
+        }
+        Err(e) => {
+            warn!("Failed to confirm payment: {}", e);
+            "尚未完成付款，請在 LINE Pay 核准後再按一次。".to_string()
+        }
+    };
+    Some(OutgoingMessage::text(text))
+}
+
+#[cfg(not(feature = "pay"))]
+async fn payment_postback_reply(
+    _state: &AppState,
+    _event: &PostbackEvent,
+) -> Option<OutgoingMessage> {
+    None
+}
+
+/// 以符合的回覆腳本回覆，沒有符合或執行失敗時回傳 `None` 繼續後面的處理
+#[cfg(feature = "scripting")]
+fn script_reply(state: &AppState, user_id: &str, text: &str) -> Option<Vec<OutgoingMessage>> {
+    let scripts = state.reply_scripts.as_ref()?;
+    match scripts.reply(user_id, text) {
+        Ok(messages) => messages,
+        Err(e) => {
+            error!("Reply script failed: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(not(feature = "scripting"))]
+fn script_reply(_state: &AppState, _user_id: &str, _text: &str) -> Option<Vec<OutgoingMessage>> {
+    None
+}
+
+/// 等待 LLM 回覆的時間，超過時先結束 Webhook 處理，完成後改以推播送出
+#[cfg(feature = "ai")]
+const LLM_REPLY_WAIT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// 未符合指令的文字交給 LLM 回覆，失敗時回傳 `None` 改用內建回覆
+///
+/// 超過 [`LLM_REPLY_WAIT`] 時回傳空的回覆，由背景工作在完成後推播到原本的聊天。
+#[cfg(feature = "ai")]
+async fn llm_reply(state: &AppState, event: &MessageEvent) -> Option<Vec<OutgoingMessage>> {
+    let handler = state.llm_handler.clone()?;
+    let MessageType::Text { text } = &event.message else {
+        return None;
+    };
+    let user_id = event.source.user_id().map(str::to_string);
+    let text = text.clone();
+    let mut completion =
+        tokio::spawn(async move { handler.complete(user_id.as_deref(), &text).await });
+    match tokio::time::timeout(LLM_REPLY_WAIT, &mut completion).await {
+        Ok(Ok(Ok(messages))) => return Some(messages),
+        Ok(Ok(Err(e))) => error!("LLM reply failed: {}", e),
+        Ok(Err(e)) => error!("LLM reply task failed: {}", e),
+        Err(_) => {
+            info!("LLM reply is slow, pushing it when ready");
+            let client = state.line_client.clone();
+            let to = event.source.chat_id().to_string();
+            let user_id = get_user_id_from_source(&event.source);
+            tokio::spawn(async move {
+                match completion.await {
+                    Ok(Ok(messages)) => {
+                        let options = SendOptions::new().user_id(user_id);
+                        if let Err(e) = client
+                            .push_message_with_options(&to, messages, &options)
+                            .await
+                        {
+                            error!("Failed to push LLM reply: {}", e);
+                        }
+                    }
+                    Ok(Err(e)) => error!("LLM reply failed: {}", e),
+                    Err(e) => error!("LLM reply task failed: {}", e),
+                }
+            });
+            return Some(Vec::new());
+        }
+    }
+    None
+}
+
+#[cfg(not(feature = "ai"))]
+async fn llm_reply(_state: &AppState, _event: &MessageEvent) -> Option<Vec<OutgoingMessage>> {
+    None
+}
+
+/// 有對應樣板（指令名稱，非指令時為 `unknown`）時以樣板回覆，否則回傳 `None` 使用內建回覆
+#[cfg(feature = "templates")]
+async fn render_reply_template(
+    state: &AppState,
+    user_id: &str,
+    command: Option<&str>,
+    text: &str,
+) -> Option<Vec<OutgoingMessage>> {
+    use crate::utils::TemplateContext;
+
+    let templates = state.reply_templates.as_ref()?;
+    let name = command.unwrap_or("unknown");
+    if !templates.has(name) {
+        return None;
+    }
+
+    let args = text
+        .trim()
+        .split_once(char::is_whitespace)
+        .map_or("", |(_, args)| args);
+    let now = chrono::Utc::now()
+        .format("%Y-%m-%d %H:%M:%S UTC")
+        .to_string();
+    let context = TemplateContext::new()
+        .user_name(&display_name(state, user_id).await)
+        .args(args)
+        .insert("text", text)
+        .insert_trusted("now", &now);
+    match templates.render_message(name, &context) {
+        Ok(message) => Some(message.into_iter().collect()),
+        Err(e) => {
+            error!("Failed to render reply template {}: {}", name, e);
+            None
+        }
+    }
+}
+
+#[cfg(not(feature = "templates"))]
+async fn render_reply_template(
+    _state: &AppState,
+    _user_id: &str,
+    _command: Option<&str>,
+    _text: &str,
+) -> Option<Vec<OutgoingMessage>> {
+    None
+}
+
+/// 取得使用者顯示名稱，失敗或 dry run 時回傳空字串
+#[cfg(feature = "templates")]
+async fn display_name(state: &AppState, user_id: &str) -> String {
+    if user_id.is_empty() || state.config.dry_run {
+        return String::new();
+    }
+    match state.line_client.get_profile(user_id).await {
+        Ok(profile) => profile["displayName"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        Err(e) => {
+            warn!("Failed to fetch profile for reply template: {}", e);
+            String::new()
+        }
+    }
+}
+
+/// 內建回覆的語系，第一次需要內建回覆時才查詢使用者個人資料
+struct ReplyLocale<'a> {
+    state: &'a AppState,
+    user_id: Option<&'a str>,
+    /// 群組設定的語言，優先於個人資料
+    preferred: Option<&'a str>,
+    replies: OnceCell<LocalizedReplies>,
+}
+
+impl<'a> ReplyLocale<'a> {
+    fn new(state: &'a AppState, user_id: Option<&'a str>, preferred: Option<&'a str>) -> Self {
+        Self {
+            state,
+            user_id,
+            preferred,
+            replies: OnceCell::new(),
+        }
+    }
+
+    /// 啟用翻譯時回覆會再翻成使用者的語言，文字維持預設語系，時間仍使用使用者語系的時區
+    async fn replies(&self) -> LocalizedReplies {
+        *self
+            .replies
+            .get_or_init(|| async {
+                let i18n = &self.state.i18n;
+                let locale = i18n.locale_for(self.user_id, self.preferred).await;
+                match &self.state.translation {
+                    Some(_) => {
+                        LocalizedReplies::new(i18n.default_locale(), i18n.utc_offset(locale))
+                    }
+                    None => i18n.replies(locale),
+                }
+            })
+            .await
+    }
+}
+
+fn handle_text_message(text: &str, replies: &LocalizedReplies) -> Vec<OutgoingMessage> {
+    match command_name(text) {
+        Some(command) => command_reply(command, text, replies),
+        None => messages![replies.text(ReplyText::UnknownCommand)],
+    }
+}
+
+/// 指令的內建回覆，未知的指令名稱（例如意圖對應設定錯誤）回覆說明
+fn command_reply(command: &str, text: &str, replies: &LocalizedReplies) -> Vec<OutgoingMessage> {
+    match command {
+        "hello" => messages![replies.text(ReplyText::Greeting)],
+        "time" => messages![format!(
+            "{}{}",
+            replies.text(ReplyText::CurrentTime),
+            replies.current_time()
+        )],
+        "sticker" => messages![stickers::BROWN_HELLO],
+        "subscribe" | "unsubscribe" => messages![replies.text(ReplyText::NoSubscription)],
+        "echo" => {
+            // 由意圖解析得到時訊息沒有指令前綴，回應整段文字
+            let echo_text = text
+                .strip_prefix("echo ")
+                .or_else(|| text.strip_prefix("回音 "))
+                .unwrap_or(text);
+            vec![OutgoingMessage::echo(
+                replies.text(ReplyText::Echo),
+                echo_text,
+            )]
         }
+        _ => messages![replies.text(ReplyText::Help)],
     }
 }
 
@@ -176,12 +1108,47 @@ fn get_user_id_from_source(source: &crate::models::Source) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::{I18n, I18nConfig, Locale};
+
+    fn replies() -> LocalizedReplies {
+        I18n::new(I18nConfig::default()).replies(Locale::ZhHant)
+    }
+
+    #[test]
+    fn test_media_reply_includes_public_url() {
+        let media = |url: &str| StoredMedia {
+            key: "100.jpg".to_string(),
+            url: url.to_string(),
+            content_type: Some("image/jpeg".to_string()),
+            size: 4,
+        };
+        assert_eq!(
+            media_reply(
+                replies(),
+                ReplyText::ImageReceived,
+                Some(&media("https://cdn.example.com/100.jpg"))
+            ),
+            messages!["收到圖片！", "已保存：https://cdn.example.com/100.jpg"]
+        );
+        assert_eq!(
+            media_reply(
+                replies(),
+                ReplyText::ImageReceived,
+                Some(&media("file:///tmp/100.jpg"))
+            ),
+            messages!["收到圖片！"]
+        );
+        assert_eq!(
+            media_reply(replies(), ReplyText::FileReceived, None),
+            messages!["收到檔案！"]
+        );
+    }
 
     #[test]
     fn test_handle_text_message_hello() {
-        let result = handle_text_message("hello");
+        let result = handle_text_message("hello", &replies());
         assert_eq!(result.len(), 1);
-        if let OutgoingMessage::Text { text } = &result[0] {
+        if let OutgoingMessage::Text { text, .. } = &result[0] {
             assert_eq!(text, "你好！有什麼可以幫助你的嗎？");
         } else {
             panic!("Expected text message");
@@ -190,9 +1157,9 @@ mod tests {
 
     #[test]
     fn test_handle_text_message_help() {
-        let result = handle_text_message("help");
+        let result = handle_text_message("help", &replies());
         assert_eq!(result.len(), 1);
-        if let OutgoingMessage::Text { text } = &result[0] {
+        if let OutgoingMessage::Text { text, .. } = &result[0] {
             assert!(text.contains("可用指令"));
         } else {
             panic!("Expected text message");
@@ -201,10 +1168,31 @@ mod tests {
 
     #[test]
     fn test_handle_text_message_time() {
-        let result = handle_text_message("time");
+        let result = handle_text_message("time", &replies());
         assert_eq!(result.len(), 1);
-        if let OutgoingMessage::Text { text } = &result[0] {
+        if let OutgoingMessage::Text { text, .. } = &result[0] {
             assert!(text.contains("目前時間"));
+            assert!(text.ends_with("(UTC+08:00)"));
+        } else {
+            panic!("Expected text message");
+        }
+    }
+
+    #[test]
+    fn test_handle_text_message_localized() {
+        let config = I18nConfig {
+            utc_offsets: I18nConfig::parse_utc_offsets("en=-05:00").unwrap(),
+            ..Default::default()
+        };
+        let replies = I18n::new(config).replies(Locale::En);
+        assert_eq!(
+            handle_text_message("hi", &replies),
+            messages!["Hello! How can I help you?"]
+        );
+        let result = handle_text_message("time", &replies);
+        if let OutgoingMessage::Text { text, .. } = &result[0] {
+            assert!(text.starts_with("Current time: "));
+            assert!(text.ends_with("(UTC-05:00)"));
         } else {
             panic!("Expected text message");
         }
@@ -212,7 +1200,7 @@ mod tests {
 
     #[test]
     fn test_handle_text_message_sticker() {
-        let result = handle_text_message("sticker");
+        let result = handle_text_message("sticker", &replies());
         assert_eq!(result.len(), 1);
         if let OutgoingMessage::Sticker {
             package_id,
@@ -228,9 +1216,9 @@ mod tests {
 
     #[test]
     fn test_handle_text_message_echo() {
-        let result = handle_text_message("echo test message");
+        let result = handle_text_message("echo test message", &replies());
         assert_eq!(result.len(), 1);
-        if let OutgoingMessage::Text { text } = &result[0] {
+        if let OutgoingMessage::Text { text, .. } = &result[0] {
             assert_eq!(text, "回音：test message");
         } else {
             panic!("Expected text message");
@@ -239,20 +1227,54 @@ mod tests {
 
     #[test]
     fn test_handle_text_message_echo_chinese() {
-        let result = handle_text_message("回音 測試訊息");
+        let result = handle_text_message("回音 測試訊息", &replies());
         assert_eq!(result.len(), 1);
-        if let OutgoingMessage::Text { text } = &result[0] {
+        if let OutgoingMessage::Text { text, .. } = &result[0] {
             assert_eq!(text, "回音：測試訊息");
         } else {
             panic!("Expected text message");
         }
     }
 
+    #[test]
+    fn test_command_name() {
+        assert_eq!(command_name("HELLO"), Some("hello"));
+        assert_eq!(command_name("回音 測試"), Some("echo"));
+        assert_eq!(command_name("unknown command"), None);
+    }
+
+    #[test]
+    fn test_command_reply_from_intent() {
+        use crate::nlu::{NluConfig, NluProvider};
+
+        let mut config = NluConfig::new(NluProvider::Rasa {
+            url: "http://rasa:5005".to_string(),
+            token: None,
+        });
+        config
+            .intent_commands
+            .insert("greet".to_string(), "hello".to_string());
+        assert_eq!(config.command_for("greet"), "hello");
+        assert_eq!(config.command_for("time"), "time");
+        assert_eq!(intent_analytics_command(&config, "greet"), "hello");
+        assert_eq!(intent_analytics_command(&config, "time"), "time");
+        assert_eq!(intent_analytics_command(&config, "book_flight"), "unknown");
+
+        assert_eq!(
+            command_reply(config.command_for("greet"), "早安", &replies()),
+            handle_text_message("hello", &replies())
+        );
+        assert_eq!(
+            command_reply("echo", "幫我重複這句", &replies()),
+            vec![OutgoingMessage::text("回音：幫我重複這句")]
+        );
+    }
+
     #[test]
     fn test_handle_text_message_unknown() {
-        let result = handle_text_message("unknown command");
+        let result = handle_text_message("unknown command", &replies());
         assert_eq!(result.len(), 1);
-        if let OutgoingMessage::Text { text } = &result[0] {
+        if let OutgoingMessage::Text { text, .. } = &result[0] {
             assert!(text.contains("我不太理解你的意思"));
         } else {
             panic!("Expected text message");
@@ -261,12 +1283,39 @@ mod tests {
 
     #[test]
     fn test_handle_text_message_case_insensitive() {
-        let result = handle_text_message("HELLO");
+        let result = handle_text_message("HELLO", &replies());
         assert_eq!(result.len(), 1);
-        if let OutgoingMessage::Text { text } = &result[0] {
+        if let OutgoingMessage::Text { text, .. } = &result[0] {
             assert_eq!(text, "你好！有什麼可以幫助你的嗎？");
         } else {
             panic!("Expected text message");
         }
     }
+
+    #[test]
+    fn test_apply_group_command() {
+        let mut settings = GroupSettings::default();
+        let (text, changed) = apply_group_command(&mut settings, "");
+        assert!(!changed);
+        assert!(text.contains("停用功能：無"));
+
+        assert!(apply_group_command(&mut settings, "welcome 歡迎來到{group}").1);
+        assert!(apply_group_command(&mut settings, "language en").1);
+        assert!(apply_group_command(&mut settings, "disable llm").1);
+        assert_eq!(
+            settings.welcome_template.as_deref(),
+            Some("歡迎來到{group}")
+        );
+        assert_eq!(settings.language.as_deref(), Some("en"));
+        assert!(!settings.is_enabled("llm"));
+
+        assert!(apply_group_command(&mut settings, "language off").1);
+        assert!(apply_group_command(&mut settings, "enable llm").1);
+        assert_eq!(settings.language, None);
+        assert!(settings.is_enabled("llm"));
+
+        // 未知的功能或缺少參數時只回傳說明
+        assert!(!apply_group_command(&mut settings, "disable unknown").1);
+        assert!(!apply_group_command(&mut settings, "welcome").1);
+    }
 }
@@ -0,0 +1,93 @@
+//! LIFF 前端登入驗證
+//!
+//! LIFF 以 `liff.getIDToken()` 取得 ID token 後送到 `POST /liff/verify`，
+//! 驗證通過時回傳對應的 LINE 使用者 ID 與個人資料。未設定 `LINE_LOGIN_CHANNEL_ID` 時端點回傳 404。
+
+use axum::{
+    Json, Router,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::post,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::utils::SensitiveDataMasker;
+use crate::webhook::server::AppState;
+
+/// 建立 `/liff` 路由
+pub fn liff_router() -> Router<Arc<AppState>> {
+    Router::new().route("/verify", post(verify_id_token))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VerifyRequest {
+    id_token: String,
+    /// 呼叫 `liff.init` 前產生的 nonce，有提供時一併檢查
+    nonce: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VerifiedUser {
+    user_id: String,
+    display_name: Option<String>,
+    picture_url: Option<String>,
+    email: Option<String>,
+    /// ID token 的到期時間（Unix 秒）
+    expires_at: i64,
+    /// 是否為 Bot 好友（依儲存的使用者紀錄）
+    followed: bool,
+}
+
+async fn verify_id_token(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<VerifyRequest>,
+) -> Response {
+    let Some(verifier) = &state.id_token_verifier else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let claims = match verifier
+        .verify(&request.id_token, request.nonce.as_deref())
+        .await
+    {
+        Ok(claims) => claims,
+        Err(e) if e.is_rejected() => {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(json!({ "error": e.message })),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            warn!("Failed to verify LIFF ID token: {}", e);
+            return StatusCode::BAD_GATEWAY.into_response();
+        }
+    };
+
+    let followed = match state.storage.get_user(&claims.sub).await {
+        Ok(user) => user.is_some_and(|user| user.followed),
+        Err(e) => {
+            warn!(
+                "Failed to load user {}: {}",
+                SensitiveDataMasker::mask_user_id(&claims.sub),
+                e
+            );
+            false
+        }
+    };
+    Json(VerifiedUser {
+        user_id: claims.sub,
+        display_name: claims.name,
+        picture_url: claims.picture,
+        email: claims.email,
+        expires_at: claims.exp,
+        followed,
+    })
+    .into_response()
+}
@@ -0,0 +1,191 @@
+//! 以 LINE Login 登入網頁管理介面
+//!
+//! `GET /auth/line/login` 轉址到 LINE 登入頁面，LINE 再導回 `GET /auth/line/callback`，
+//! 成功後以 HttpOnly cookie 保存 session 並導向 `LINE_LOGIN_SUCCESS_URL`。
+//! 前端以 `GET /auth/me` 取得登入的使用者，`POST /auth/logout` 登出。
+//! 未設定 `LINE_LOGIN_CHANNEL_SECRET` 與 `LINE_LOGIN_REDIRECT_URI` 時端點回傳 404。
+
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{AppendHeaders, IntoResponse, Redirect, Response},
+    routing::{get, post},
+};
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::auth::{AuthError, LineLoginFlow};
+use crate::utils::SensitiveDataMasker;
+use crate::webhook::server::AppState;
+
+/// 綁定登入流程與瀏覽器的 `state` cookie
+const STATE_COOKIE: &str = "linebot_login_state";
+const SESSION_COOKIE: &str = "linebot_session";
+
+/// 建立 `/auth` 路由
+pub fn login_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/line/login", get(login))
+        .route("/line/callback", get(callback))
+        .route("/me", get(me))
+        .route("/logout", post(logout))
+}
+
+#[derive(Deserialize)]
+struct CallbackQuery {
+    code: Option<String>,
+    state: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+async fn login(State(state): State<Arc<AppState>>) -> Response {
+    let Some(flow) = &state.line_login else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    match flow.start().await {
+        Ok(request) => (
+            AppendHeaders([(
+                header::SET_COOKIE,
+                cookie(flow, STATE_COOKIE, &request.state, None),
+            )]),
+            Redirect::to(&request.url),
+        )
+            .into_response(),
+        Err(e) => auth_error_response(e),
+    }
+}
+
+async fn callback(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<CallbackQuery>,
+) -> Response {
+    let Some(flow) = &state.line_login else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    // 使用者取消授權時 LINE 只帶回 error
+    if let Some(error) = query.error {
+        let message = query.error_description.unwrap_or(error);
+        return (StatusCode::BAD_REQUEST, message).into_response();
+    }
+    let (Some(code), Some(login_state)) = (query.code, query.state) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    if cookie_value(&headers, STATE_COOKIE) != Some(login_state.as_str()) {
+        warn!("Rejected LINE Login callback with mismatched state");
+        return (StatusCode::BAD_REQUEST, "Login state mismatch").into_response();
+    }
+
+    let (token, session) = match flow.finish(&login_state, &code).await {
+        Ok(result) => result,
+        Err(e) => return auth_error_response(e),
+    };
+    info!(
+        "User {} logged in with LINE Login",
+        SensitiveDataMasker::mask_user_id(&session.user_id)
+    );
+    let max_age = (session.expires_at - Utc::now()).num_seconds();
+    (
+        AppendHeaders([
+            (
+                header::SET_COOKIE,
+                cookie(flow, SESSION_COOKIE, &token, Some(max_age)),
+            ),
+            (header::SET_COOKIE, cookie(flow, STATE_COOKIE, "", Some(0))),
+        ]),
+        Redirect::to(flow.success_url()),
+    )
+        .into_response()
+}
+
+async fn me(State(state): State<Arc<AppState>>, headers: HeaderMap) -> Response {
+    let Some(flow) = &state.line_login else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let Some(token) = cookie_value(&headers, SESSION_COOKIE) else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+    match flow.session(token).await {
+        Ok(Some(session)) => Json(session).into_response(),
+        Ok(None) => StatusCode::UNAUTHORIZED.into_response(),
+        Err(e) => auth_error_response(e),
+    }
+}
+
+async fn logout(State(state): State<Arc<AppState>>, headers: HeaderMap) -> Response {
+    let Some(flow) = &state.line_login else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    if let Some(token) = cookie_value(&headers, SESSION_COOKIE)
+        && let Err(e) = flow.logout(token).await
+    {
+        return auth_error_response(e);
+    }
+    (
+        StatusCode::NO_CONTENT,
+        AppendHeaders([(
+            header::SET_COOKIE,
+            cookie(flow, SESSION_COOKIE, "", Some(0)),
+        )]),
+    )
+        .into_response()
+}
+
+fn auth_error_response(error: AuthError) -> Response {
+    if error.is_rejected() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": error.message })),
+        )
+            .into_response();
+    }
+    warn!("LINE Login failed: {}", error);
+    StatusCode::BAD_GATEWAY.into_response()
+}
+
+/// callback 為 https 時加上 `Secure`
+fn cookie(flow: &LineLoginFlow, name: &str, value: &str, max_age: Option<i64>) -> String {
+    let mut cookie = format!("{}={}; Path=/; HttpOnly; SameSite=Lax", name, value);
+    if let Some(max_age) = max_age {
+        cookie.push_str(&format!("; Max-Age={}", max_age));
+    }
+    if flow.client().redirect_uri().starts_with("https://") {
+        cookie.push_str("; Secure");
+    }
+    cookie
+}
+
+fn cookie_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cookie_value() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            "theme=dark; linebot_session=abc; linebot_login_state=xyz"
+                .parse()
+                .unwrap(),
+        );
+        assert_eq!(cookie_value(&headers, SESSION_COOKIE), Some("abc"));
+        assert_eq!(cookie_value(&headers, STATE_COOKIE), Some("xyz"));
+        assert_eq!(cookie_value(&headers, "missing"), None);
+    }
+}
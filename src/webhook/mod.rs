@@ -1,5 +1,36 @@
+#[cfg(feature = "server")]
+pub mod admin;
+#[cfg(feature = "bridge")]
+pub mod bridge;
+#[cfg(feature = "server")]
+pub mod destination;
+pub mod forwarder;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+#[cfg(feature = "server")]
 pub mod handlers;
+#[cfg(feature = "server")]
+pub mod liff;
+#[cfg(feature = "server")]
+pub mod login;
+pub mod queue;
+pub mod recorder;
+#[cfg(feature = "server")]
 pub mod server;
+#[cfg(feature = "server")]
+pub mod signature;
 
+#[cfg(feature = "server")]
+pub use destination::*;
+pub use forwarder::*;
+#[cfg(feature = "server")]
 pub use handlers::*;
+pub use queue::*;
+pub use recorder::*;
+#[cfg(feature = "server")]
 pub use server::*;
+#[cfg(feature = "server")]
+pub use signature::*;
+
+/// 關聯 ID 標頭，未提供時自動產生 UUID 並回傳於回應中
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
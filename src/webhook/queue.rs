@@ -0,0 +1,648 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::Value;
+use std::future::Future;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::sync::{Mutex, mpsc};
+use tracing::{Instrument, Span, debug, warn};
+
+use crate::models::Event;
+use crate::storage::{KvNamespace, Storage, StorageError, UserDataCleanup};
+use crate::utils::Metrics;
+
+/// 暫存事件在儲存後端中的命名空間，每個事件一個鍵，鍵依暫存順序排列
+const SPILL_NAMESPACE: &str = "webhook_queue";
+const SPILL_PREFIX: &str = "event:";
+
+/// 舊版以單一清單保存的暫存事件，啟動時轉為每個事件一個鍵
+const LEGACY_SPILL_KEY: &str = "spilled";
+
+/// 檢查暫存事件、移回佇列的間隔
+const SPILL_DRAIN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 佇列已滿時的處理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OverflowPolicy {
+    /// 最多等待 `block_timeout_ms`，仍沒有空位時回傳 503
+    Block,
+    /// 立即回傳 503，LINE 開啟重送時稍後會再送一次
+    Shed,
+    /// 寫入儲存後端，佇列有空位時再依序取回
+    Spill,
+}
+
+/// 非同步處理 Webhook 事件的設定
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct WebhookQueueConfig {
+    /// 等待處理的事件數上限，平均分配給各 worker
+    pub capacity: usize,
+    /// 同時處理事件的 worker 數，同一個聊天室的事件固定由同一個 worker 處理
+    pub workers: usize,
+    pub overflow: OverflowPolicy,
+    /// `Block` 時等待空位的時間（毫秒）
+    pub block_timeout_ms: u64,
+}
+
+impl Default for WebhookQueueConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 1000,
+            workers: 4,
+            overflow: OverflowPolicy::Block,
+            block_timeout_ms: 200,
+        }
+    }
+}
+
+/// 佇列已滿，事件沒有排入
+#[derive(Debug)]
+pub struct QueueFull;
+
+impl std::fmt::Display for QueueFull {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Webhook event queue is full")
+    }
+}
+
+impl std::error::Error for QueueFull {}
+
+/// 排入佇列的事件與排入時的 tracing span，worker 處理時沿用同一個 span
+struct QueuedEvent {
+    event: Event,
+    span: Span,
+}
+
+/// Webhook 與處理事件的 worker 之間的有界佇列
+///
+/// 收到 Webhook 後只把事件排入佇列就回應 200，由背景 worker 處理。佇列容量固定，
+/// 突發流量時依 [`OverflowPolicy`] 等待、拒絕或暫存到儲存後端，不會無限制佔用記憶體。
+/// 同一個 Webhook 的事件一起排入，拒絕時整批都不排入，LINE 重送時不會重複處理。
+///
+/// 事件依來源（使用者、群組或聊天室）分配到固定的 worker，各自有獨立的佇列：
+/// 不同聊天室的事件平行處理，同一個聊天室的事件依序處理，回覆不會錯亂。
+#[derive(Clone)]
+pub struct EventQueue {
+    shards: Arc<[mpsc::Sender<QueuedEvent>]>,
+    /// 尚未啟動的 worker 佇列，[`start`](Self::start) 時取走
+    receivers: Arc<std::sync::Mutex<Vec<mpsc::Receiver<QueuedEvent>>>>,
+    config: Arc<WebhookQueueConfig>,
+    spill: KvNamespace,
+    /// 暫存或移回事件時持有；沒有暫存事件時直接排入不需要取得
+    spill_lock: Arc<Mutex<()>>,
+    spilled: Arc<AtomicUsize>,
+    /// 下一個暫存事件的序號，以建立時的時間起算，重新啟動後仍排在上次留下的事件之後
+    next_spill_seq: Arc<AtomicU64>,
+    metrics: Metrics,
+}
+
+impl EventQueue {
+    pub fn new(config: WebhookQueueConfig, storage: Arc<dyn Storage>, metrics: Metrics) -> Self {
+        let workers = config.workers.max(1);
+        let shard_capacity = config.capacity.div_ceil(workers).max(1);
+        let (shards, receivers): (Vec<_>, Vec<_>) =
+            (0..workers).map(|_| mpsc::channel(shard_capacity)).unzip();
+        Self {
+            shards: shards.into(),
+            receivers: Arc::new(std::sync::Mutex::new(receivers)),
+            config: Arc::new(config),
+            spill: KvNamespace::new(storage, SPILL_NAMESPACE),
+            spill_lock: Arc::new(Mutex::new(())),
+            spilled: Arc::new(AtomicUsize::new(0)),
+            next_spill_seq: Arc::new(AtomicU64::new(Utc::now().timestamp_micros().max(0) as u64)),
+            metrics,
+        }
+    }
+
+    pub fn config(&self) -> &WebhookQueueConfig {
+        &self.config
+    }
+
+    /// 等待 worker 處理的事件數
+    pub fn depth(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.max_capacity() - shard.capacity())
+            .sum()
+    }
+
+    /// 事件所屬的 worker，以聊天室 ID 決定
+    fn shard_index(&self, event: &Event) -> usize {
+        let mut hasher = DefaultHasher::new();
+        event.source().chat_id().hash(&mut hasher);
+        hasher.finish() as usize % self.shards.len()
+    }
+
+    fn shard(&self, event: &Event) -> &mpsc::Sender<QueuedEvent> {
+        &self.shards[self.shard_index(event)]
+    }
+
+    /// 暫存在儲存後端、尚未移回佇列的事件數
+    pub fn spilled(&self) -> usize {
+        self.spilled.load(Ordering::Relaxed)
+    }
+
+    /// 排入同一個 Webhook 的所有事件，worker 處理時沿用目前的 tracing span
+    pub async fn enqueue(&self, events: Vec<Event>) -> Result<(), QueueFull> {
+        let span = Span::current();
+        let result = match self.config.overflow {
+            OverflowPolicy::Spill => self.enqueue_or_spill(events, span).await,
+            OverflowPolicy::Block | OverflowPolicy::Shed => {
+                let count = events.len();
+                let result = self.enqueue_all(events, span).await;
+                if result.is_err() {
+                    self.metrics.record_webhook_shed(count);
+                }
+                result
+            }
+        };
+        self.record_depth();
+        result
+    }
+
+    /// 先取得所有空位再送出，失敗時已取得的空位會隨 permit 釋放
+    ///
+    /// 同一個聊天室的事件多於該 worker 的佇列容量時，整批永遠放不下：
+    /// 取得整個佇列的空位後，超出的事件在送出時等待 worker 取走前面的事件。
+    async fn enqueue_all(&self, events: Vec<Event>, span: Span) -> Result<(), QueueFull> {
+        let mut reserved = vec![0; self.shards.len()];
+        let mut permits = Vec::with_capacity(events.len());
+        let mut overflow = Vec::new();
+        for event in events {
+            let index = self.shard_index(&event);
+            let shard = &self.shards[index];
+            if reserved[index] < shard.max_capacity() {
+                permits.push((self.reserve(shard).await?, event));
+                reserved[index] += 1;
+            } else {
+                overflow.push((index, event));
+            }
+        }
+        for (permit, event) in permits {
+            permit.send(QueuedEvent {
+                event,
+                span: span.clone(),
+            });
+        }
+        for (index, event) in overflow {
+            let queued = QueuedEvent {
+                event,
+                span: span.clone(),
+            };
+            if self.shards[index].send(queued).await.is_err() {
+                return Err(QueueFull);
+            }
+        }
+        Ok(())
+    }
+
+    async fn reserve<'a>(
+        &self,
+        shard: &'a mpsc::Sender<QueuedEvent>,
+    ) -> Result<mpsc::Permit<'a, QueuedEvent>, QueueFull> {
+        match shard.try_reserve() {
+            Ok(permit) => Ok(permit),
+            Err(_) if self.config.overflow == OverflowPolicy::Block => {
+                let timeout = Duration::from_millis(self.config.block_timeout_ms);
+                match tokio::time::timeout(timeout, shard.reserve()).await {
+                    Ok(Ok(permit)) => Ok(permit),
+                    _ => Err(QueueFull),
+                }
+            }
+            Err(_) => Err(QueueFull),
+        }
+    }
+
+    /// 整批都有空位時才取得所有空位
+    fn try_reserve_all(&self, events: &[Event]) -> Option<Vec<mpsc::Permit<'_, QueuedEvent>>> {
+        events
+            .iter()
+            .map(|event| self.shard(event).try_reserve().ok())
+            .collect()
+    }
+
+    async fn enqueue_or_spill(&self, events: Vec<Event>, span: Span) -> Result<(), QueueFull> {
+        // 沒有暫存事件且整批都有空位時直接排入，不必等待暫存的鎖
+        if self.spilled() == 0
+            && let Some(permits) = self.try_reserve_all(&events)
+        {
+            for (permit, event) in permits.into_iter().zip(events) {
+                permit.send(QueuedEvent {
+                    event,
+                    span: span.clone(),
+                });
+            }
+            return Ok(());
+        }
+
+        let _guard = self.spill_lock.lock().await;
+        let mut events = events.into_iter();
+        // 已有暫存事件時新事件也排在後面，維持順序
+        if self.spilled() == 0 {
+            for event in events.by_ref() {
+                match self.shard(&event).try_reserve() {
+                    Ok(permit) => permit.send(QueuedEvent {
+                        event,
+                        span: span.clone(),
+                    }),
+                    Err(_) => {
+                        return self
+                            .spill_events(std::iter::once(event).chain(events))
+                            .await;
+                    }
+                }
+            }
+            return Ok(());
+        }
+        self.spill_events(events).await
+    }
+
+    /// 每個事件寫入一個鍵，呼叫前須持有 `spill_lock`；儲存後端失敗時刪除這批已寫入的事件，視為佇列已滿
+    async fn spill_events(&self, events: impl Iterator<Item = Event>) -> Result<(), QueueFull> {
+        let values: Vec<Value> = events
+            .map(|event| match event.raw() {
+                Some(raw) => raw.clone(),
+                None => serde_json::to_value(&event).unwrap_or(Value::Null),
+            })
+            .collect();
+        let count = values.len();
+        match self.write_spilled(&values).await {
+            Ok(()) => {
+                self.spilled.fetch_add(count, Ordering::Relaxed);
+                Ok(())
+            }
+            Err(e) => {
+                warn!("Failed to spill {} webhook events: {}", count, e);
+                self.metrics.record_webhook_shed(count);
+                Err(QueueFull)
+            }
+        }
+    }
+
+    async fn write_spilled(&self, values: &[Value]) -> Result<(), StorageError> {
+        let mut written: Vec<String> = Vec::with_capacity(values.len());
+        for value in values {
+            let key = format!(
+                "{}{:020}",
+                SPILL_PREFIX,
+                self.next_spill_seq.fetch_add(1, Ordering::Relaxed)
+            );
+            if let Err(e) = self.spill.set(&key, value).await {
+                for key in &written {
+                    let _ = self.spill.delete(key).await;
+                }
+                return Err(e);
+            }
+            written.push(key);
+        }
+        Ok(())
+    }
+
+    /// 把暫存事件依序移回佇列，回傳移回的事件數
+    pub async fn drain_spilled(&self) -> usize {
+        if self.spilled() == 0 {
+            return 0;
+        }
+        let _guard = self.spill_lock.lock().await;
+        let keys = match self.spill.keys(SPILL_PREFIX).await {
+            Ok(keys) => keys,
+            Err(e) => {
+                warn!("Failed to list spilled webhook events: {}", e);
+                return 0;
+            }
+        };
+        let mut remaining = keys.len();
+        let mut moved = 0;
+        // 遇到佇列已滿的 worker 就停止，後面的事件可能屬於同一個聊天室
+        for key in keys {
+            let value = match self.spill.get::<Value>(&key).await {
+                Ok(Some(value)) => value,
+                Ok(None) => {
+                    remaining -= 1;
+                    continue;
+                }
+                Err(e) => {
+                    warn!("Failed to read spilled webhook event: {}", e);
+                    break;
+                }
+            };
+            let event = match Event::from_raw(value) {
+                Ok(event) => event,
+                Err(e) => {
+                    warn!("Dropping invalid spilled webhook event: {}", e);
+                    if self.spill.delete(&key).await.is_ok() {
+                        remaining -= 1;
+                    }
+                    continue;
+                }
+            };
+            let Ok(permit) = self.shard(&event).try_reserve() else {
+                break;
+            };
+            // 先刪除再排入，刪除失敗時留到下次
+            if let Err(e) = self.spill.delete(&key).await {
+                warn!("Failed to remove spilled webhook event: {}", e);
+                break;
+            }
+            permit.send(QueuedEvent {
+                event,
+                span: Span::none(),
+            });
+            remaining -= 1;
+            moved += 1;
+        }
+        self.spilled.store(remaining, Ordering::Relaxed);
+        if moved > 0 {
+            debug!("Moved {} spilled webhook events back to the queue", moved);
+        }
+        self.record_depth();
+        moved
+    }
+
+    /// 載入上次執行留下的暫存事件，舊版的單一清單改為每個事件一個鍵
+    async fn restore_spilled(&self) -> Result<(), StorageError> {
+        let _guard = self.spill_lock.lock().await;
+        if let Some(legacy) = self.spill.get::<Vec<Value>>(LEGACY_SPILL_KEY).await? {
+            self.write_spilled(&legacy).await?;
+            self.spill.delete(LEGACY_SPILL_KEY).await?;
+        }
+        let count = self.spill.keys(SPILL_PREFIX).await?.len();
+        self.spilled.store(count, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn record_depth(&self) {
+        self.metrics
+            .record_webhook_queue(self.depth(), self.spilled());
+    }
+
+    /// 啟動 worker；`Spill` 時也定期移回暫存事件，包含上次執行留下的事件
+    pub fn start<F, Fut>(&self, handler: F)
+    where
+        F: Fn(Event) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let handler = Arc::new(handler);
+        let receivers =
+            std::mem::take(&mut *self.receivers.lock().unwrap_or_else(|e| e.into_inner()));
+        for mut receiver in receivers {
+            let queue = self.clone();
+            let handler = handler.clone();
+            tokio::spawn(async move {
+                while let Some(QueuedEvent { event, span }) = receiver.recv().await {
+                    queue.record_depth();
+                    handler(event).instrument(span).await;
+                }
+            });
+        }
+
+        if self.config.overflow == OverflowPolicy::Spill {
+            let queue = self.clone();
+            tokio::spawn(async move {
+                if let Err(e) = queue.restore_spilled().await {
+                    warn!("Failed to restore spilled webhook events: {}", e);
+                }
+
+                let mut ticker = tokio::time::interval(SPILL_DRAIN_INTERVAL);
+                loop {
+                    ticker.tick().await;
+                    queue.drain_spilled().await;
+                }
+            });
+        }
+    }
+}
+
+#[async_trait]
+impl UserDataCleanup for EventQueue {
+    /// 刪除使用者尚未處理的暫存事件；已排入佇列的事件仍會照常處理
+    async fn purge_user(&self, user_id: &str) -> Result<(), StorageError> {
+        let _guard = self.spill_lock.lock().await;
+        let removed = self
+            .spill
+            .remove_where(|event: &Value| event["source"]["userId"] == user_id)
+            .await?;
+        if removed > 0 {
+            let count = self.spill.keys(SPILL_PREFIX).await?.len();
+            self.spilled.store(count, Ordering::Relaxed);
+            self.record_depth();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+    use serde_json::json;
+
+    fn event(user_id: &str, id: u32) -> Event {
+        Event::from_raw(json!({
+            "type": "follow",
+            "timestamp": id,
+            "source": { "type": "user", "userId": user_id },
+            "replyToken": format!("token-{}", id),
+            "mode": "active",
+        }))
+        .unwrap()
+    }
+
+    fn timestamp(event: &Event) -> u64 {
+        event.raw().unwrap()["timestamp"].as_u64().unwrap()
+    }
+
+    fn queue(overflow: OverflowPolicy, block_timeout_ms: u64) -> EventQueue {
+        EventQueue::new(
+            WebhookQueueConfig {
+                capacity: 2,
+                workers: 1,
+                overflow,
+                block_timeout_ms,
+            },
+            Arc::new(MemoryStorage::new()),
+            Metrics::new(),
+        )
+    }
+
+    fn take_receiver(queue: &EventQueue) -> mpsc::Receiver<QueuedEvent> {
+        queue.receivers.lock().unwrap().remove(0)
+    }
+
+    #[tokio::test]
+    async fn test_event_queue_overflow_policies() {
+        // 整批放不下時都不排入
+        let shed = queue(OverflowPolicy::Shed, 0);
+        shed.enqueue(vec![event("U1", 1)]).await.unwrap();
+        assert!(
+            shed.enqueue(vec![event("U1", 2), event("U1", 3)])
+                .await
+                .is_err()
+        );
+        assert_eq!(shed.depth(), 1);
+
+        // 等待期間 worker 取走事件就能排入
+        let block = queue(OverflowPolicy::Block, 1000);
+        block
+            .enqueue(vec![event("U1", 1), event("U1", 2)])
+            .await
+            .unwrap();
+        let mut receiver = take_receiver(&block);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            receiver.recv().await;
+            // 保留 receiver，避免佇列關閉
+            std::future::pending::<()>().await;
+        });
+        block.enqueue(vec![event("U1", 3)]).await.unwrap();
+        assert_eq!(block.depth(), 2);
+        let block = queue(OverflowPolicy::Block, 10);
+        block
+            .enqueue(vec![event("U1", 1), event("U1", 2)])
+            .await
+            .unwrap();
+        assert!(block.enqueue(vec![event("U1", 3)]).await.is_err());
+
+        // 暫存的事件依序移回，新事件排在暫存事件之後
+        let spill = queue(OverflowPolicy::Spill, 0);
+        spill
+            .enqueue(vec![event("U1", 1), event("U1", 2), event("U1", 3)])
+            .await
+            .unwrap();
+        spill.enqueue(vec![event("U1", 4)]).await.unwrap();
+        assert_eq!((spill.depth(), spill.spilled()), (2, 2));
+        assert_eq!(spill.drain_spilled().await, 0);
+        let mut receiver = take_receiver(&spill);
+        let mut received = Vec::new();
+        for _ in 0..2 {
+            received.push(timestamp(&receiver.recv().await.unwrap().event));
+        }
+        assert_eq!(spill.drain_spilled().await, 2);
+        assert_eq!(spill.spilled(), 0);
+        for _ in 0..2 {
+            received.push(timestamp(&receiver.recv().await.unwrap().event));
+        }
+        assert_eq!(received, vec![1, 2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_event_queue_accepts_batch_larger_than_shard() {
+        let queue = queue(OverflowPolicy::Shed, 0);
+        let mut receiver = take_receiver(&queue);
+        let consumer = tokio::spawn(async move {
+            let mut received = Vec::new();
+            while received.len() < 5 {
+                received.push(timestamp(&receiver.recv().await.unwrap().event));
+            }
+            received
+        });
+        queue
+            .enqueue((1..=5).map(|id| event("U1", id)).collect())
+            .await
+            .unwrap();
+        assert_eq!(consumer.await.unwrap(), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[tokio::test]
+    async fn test_spilled_events_survive_restart() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let config = WebhookQueueConfig {
+            capacity: 1,
+            workers: 1,
+            overflow: OverflowPolicy::Spill,
+            block_timeout_ms: 0,
+        };
+        let first = EventQueue::new(config.clone(), storage.clone(), Metrics::new());
+        first
+            .enqueue(vec![event("U1", 1), event("U1", 2), event("U1", 3)])
+            .await
+            .unwrap();
+        assert_eq!(first.spilled(), 2);
+
+        let second = EventQueue::new(config, storage, Metrics::new());
+        second.restore_spilled().await.unwrap();
+        assert_eq!(second.spilled(), 2);
+        let mut receiver = take_receiver(&second);
+        assert_eq!(second.drain_spilled().await, 1);
+        assert_eq!(timestamp(&receiver.recv().await.unwrap().event), 2);
+        assert_eq!(second.drain_spilled().await, 1);
+        assert_eq!(timestamp(&receiver.recv().await.unwrap().event), 3);
+        assert_eq!(second.spilled(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_event_queue_orders_per_source() {
+        let queue = EventQueue::new(
+            WebhookQueueConfig {
+                capacity: 100,
+                workers: 4,
+                ..Default::default()
+            },
+            Arc::new(MemoryStorage::new()),
+            Metrics::new(),
+        );
+        // 找出分配到不同 worker 的兩個使用者
+        let slow = "U0".to_string();
+        let fast = (1..)
+            .map(|n| format!("U{}", n))
+            .find(|user| !std::ptr::eq(queue.shard(&event(&slow, 0)), queue.shard(&event(user, 0))))
+            .unwrap();
+
+        let processed = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let fast_done = Arc::new(tokio::sync::Notify::new());
+        queue.start({
+            let processed = processed.clone();
+            let fast_done = fast_done.clone();
+            let slow = slow.clone();
+            move |event| {
+                let processed = processed.clone();
+                let fast_done = fast_done.clone();
+                let slow = slow.clone();
+                async move {
+                    let user = event.source().user_id().unwrap().to_string();
+                    // 慢的聊天室等另一個聊天室處理完才繼續，證明兩者平行處理
+                    if user == slow && timestamp(&event) == 1 {
+                        fast_done.notified().await;
+                    }
+                    processed.lock().unwrap().push((user, timestamp(&event)));
+                }
+            }
+        });
+
+        let events = (1..=3)
+            .flat_map(|id| [event(&slow, id), event(&fast, id)])
+            .collect();
+        queue.enqueue(events).await.unwrap();
+        for _ in 0..100 {
+            if processed.lock().unwrap().len() == 3 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        fast_done.notify_one();
+        for _ in 0..100 {
+            if processed.lock().unwrap().len() == 6 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let processed = processed.lock().unwrap().clone();
+        let order = |user: &str| -> Vec<u64> {
+            processed
+                .iter()
+                .filter(|(u, _)| u == user)
+                .map(|(_, id)| *id)
+                .collect()
+        };
+        assert_eq!(order(&slow), vec![1, 2, 3]);
+        assert_eq!(order(&fast), vec![1, 2, 3]);
+        // 另一個聊天室不必等慢的聊天室
+        assert!(processed[..3].iter().all(|(user, _)| *user == fast));
+    }
+}
@@ -0,0 +1,185 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::models::WebhookRequest;
+use crate::storage::{ConversationMasking, KvNamespace, Storage, StorageError};
+use crate::utils::SensitiveDataMasker;
+
+/// 錄製清單在 `webhook_recordings` 命名空間中的鍵
+const INDEX_KEY: &str = "index";
+
+/// Webhook 錄製設定
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct WebhookRecorderConfig {
+    /// 最多保留的筆數，超過時刪除最舊的
+    pub max_recordings: usize,
+    /// 訊息文字的遮罩規則；使用者、群組 ID 與 reply token 一律遮罩
+    pub masking: ConversationMasking,
+}
+
+impl Default for WebhookRecorderConfig {
+    fn default() -> Self {
+        Self {
+            max_recordings: 200,
+            masking: ConversationMasking::Pii,
+        }
+    }
+}
+
+/// 錄製清單中的一筆
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordingSummary {
+    pub id: String,
+    pub received_at: DateTime<Utc>,
+    pub event_types: Vec<String>,
+}
+
+/// 錄製的 Webhook 內容（已遮罩）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedWebhook {
+    #[serde(flatten)]
+    pub summary: RecordingSummary,
+    pub payload: Value,
+}
+
+impl RecordedWebhook {
+    /// 解析為 Webhook 請求以便重新處理
+    pub fn request(&self) -> Result<WebhookRequest, serde_json::Error> {
+        serde_json::from_value(self.payload.clone())
+    }
+}
+
+/// 將收到的 Webhook 內容遮罩後保存在儲存後端，供除錯與回歸測試時重新處理
+///
+/// 每筆內容存在 `webhook_recordings` 命名空間，另以清單記錄順序，超過
+/// `max_recordings` 時刪除最舊的。重新處理時 reply token 已遮罩，回覆會被 LINE 拒絕，
+/// 建議搭配 `DRY_RUN` 使用。
+#[derive(Clone)]
+pub struct WebhookRecorder {
+    config: Arc<WebhookRecorderConfig>,
+    kv: KvNamespace,
+    /// 清單以讀取後寫回的方式更新，同一實例內依序進行
+    index_lock: Arc<Mutex<()>>,
+}
+
+impl WebhookRecorder {
+    pub fn new(config: WebhookRecorderConfig, storage: Arc<dyn Storage>) -> Self {
+        Self {
+            config: Arc::new(config),
+            kv: KvNamespace::new(storage, "webhook_recordings"),
+            index_lock: Arc::default(),
+        }
+    }
+
+    /// 遮罩並保存原始請求內容
+    pub async fn record(&self, body: &[u8]) -> Result<RecordingSummary, StorageError> {
+        let mut payload: Value = serde_json::from_slice(body)?;
+        SensitiveDataMasker::mask_json_identifiers(&mut payload);
+        self.config.masking.apply(&mut payload);
+
+        let received_at = Utc::now();
+        let digest = Sha256::digest(body);
+        let hash: String = digest[..4].iter().map(|b| format!("{:02x}", b)).collect();
+        let summary = RecordingSummary {
+            id: format!("{}-{}", received_at.timestamp_millis(), hash),
+            received_at,
+            event_types: payload["events"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|event| event["type"].as_str().map(str::to_string))
+                .collect(),
+        };
+
+        let _guard = self.index_lock.lock().await;
+        let mut index = self.list().await?;
+        self.kv
+            .set(
+                &summary.id,
+                &RecordedWebhook {
+                    summary: summary.clone(),
+                    payload,
+                },
+            )
+            .await?;
+        index.push(summary.clone());
+        let excess = index.len().saturating_sub(self.config.max_recordings);
+        for removed in index.drain(..excess) {
+            self.kv.delete(&removed.id).await?;
+        }
+        self.kv.set(INDEX_KEY, &index).await?;
+        Ok(summary)
+    }
+
+    /// 所有錄製，由舊到新
+    pub async fn list(&self) -> Result<Vec<RecordingSummary>, StorageError> {
+        Ok(self.kv.get(INDEX_KEY).await?.unwrap_or_default())
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<RecordedWebhook>, StorageError> {
+        self.kv.get(id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_webhook_recorder() {
+        let recorder = WebhookRecorder::new(
+            WebhookRecorderConfig {
+                max_recordings: 2,
+                ..Default::default()
+            },
+            Arc::new(MemoryStorage::new()),
+        );
+        let body = |text: &str| {
+            json!({
+                "destination": "Ubot",
+                "events": [{
+                    "type": "message",
+                    "replyToken": "reply_token_123",
+                    "message": { "id": "1", "type": "text", "text": text },
+                    "timestamp": 1234567890,
+                    "source": { "type": "user", "userId": "U1234567890" },
+                    "mode": "active"
+                }]
+            })
+            .to_string()
+        };
+
+        let first = recorder
+            .record(body("mail me at alice@example.com").as_bytes())
+            .await
+            .unwrap();
+        assert_eq!(first.event_types, vec!["message"]);
+        let recorded = recorder.get(&first.id).await.unwrap().unwrap();
+        let event = &recorded.payload["events"][0];
+        assert_eq!(event["source"]["userId"], "U12...890");
+        assert_eq!(event["replyToken"], "rep...123");
+        assert_eq!(event["message"]["text"], "mail me at a***@example.com");
+        assert_eq!(recorded.request().unwrap().events.len(), 1);
+
+        // 超過上限時刪除最舊的
+        recorder.record(body("b").as_bytes()).await.unwrap();
+        let third = recorder.record(body("c").as_bytes()).await.unwrap();
+        let ids: Vec<_> = recorder
+            .list()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|summary| summary.id)
+            .collect();
+        assert_eq!(ids.len(), 2);
+        assert_eq!(ids[1], third.id);
+        assert!(recorder.get(&first.id).await.unwrap().is_none());
+    }
+}
@@ -1,101 +1,1183 @@
 use axum::{
     Router,
     extract::{Request, State},
-    http::{HeaderMap, StatusCode},
-    middleware::{self, Next},
-    response::{IntoResponse, Response},
+    http::{HeaderName, StatusCode},
+    middleware,
+    response::IntoResponse,
     routing::post,
 };
-use std::sync::Arc;
+use std::{fmt, net::SocketAddr, sync::Arc, time::Duration};
 use tower::ServiceBuilder;
-use tower_http::{cors::CorsLayer, trace::TraceLayer};
-use tracing::info;
+use tower_http::{
+    cors::CorsLayer,
+    decompression::RequestDecompressionLayer,
+    request_id::{MakeRequestUuid, PropagateRequestIdLayer, RequestId, SetRequestIdLayer},
+    trace::TraceLayer,
+};
+use tracing::{error, info, info_span, warn};
+
+use crate::auth::{IdTokenVerifier, LineLoginClient, LineLoginConfig, LineLoginFlow};
+use crate::line_api::{
+    GroupCache, LineApiClient, OfflineBuffer, ProfileLanguageCache, QuotaMonitor, RichMenuManager,
+};
+use crate::media::MediaPipeline;
+use crate::nlu::{DialogflowCxResolver, IntentResolver, NluConfig, NluProvider, RasaResolver};
+use crate::onboarding::Onboarding;
+use crate::plugins::{PluginError, PluginRegistry};
+use crate::sinks::{CsvFileSink, EventSink, EventSinkConfig, EventSinkWriter, GoogleSheetsSink};
+use crate::storage::{
+    AuditLog, ConversationLogger, GroupSettingsStore, KvNamespace, MemoryStorage, Storage,
+    StorageError, UserDataCleanups, connect_storage,
+};
+use crate::translation::{
+    GoogleTranslator, LibreTranslator, TranslationConfig, TranslationMiddleware,
+    TranslationProvider, Translator,
+};
+use crate::utils::Config;
+use crate::utils::{
+    AnalyticsAggregator, DuplicateFilter, ErrorReporter, EventBroadcaster, FeatureFlags,
+    ForbiddenWordList, I18n, Metrics, ModerationReporter, Moderator, OutgoingMessageValidator,
+    RateLimiter, SensitiveDataMasker, StatsAggregator, SystemMetrics, UrlValidator,
+    metrics_middleware, rate_limit_middleware, start_metrics_exporter, start_otlp_exporter,
+    start_statsd_exporter, systemd,
+};
+use crate::webhook::admin::admin_router;
+use crate::webhook::{
+    DestinationVerifier, EventQueue, LineSignatureLayer, OverflowPolicy, REQUEST_ID_HEADER,
+    WebhookForwarder, WebhookRecorder,
+};
+
+/// 行程記憶體與 CPU 指標的更新間隔
+const SYSTEM_METRICS_INTERVAL: Duration = Duration::from_secs(15);
+
+/// 檢查禁用詞清單檔案是否變更的間隔
+const FORBIDDEN_WORDS_RELOAD_INTERVAL: Duration = Duration::from_secs(10);
+
+/// 檢查回覆樣板目錄是否變更的間隔
+#[cfg(feature = "templates")]
+const REPLY_TEMPLATES_RELOAD_INTERVAL: Duration = Duration::from_secs(10);
+
+/// 檢查回覆腳本目錄是否變更的間隔
+#[cfg(feature = "scripting")]
+const REPLY_SCRIPTS_RELOAD_INTERVAL: Duration = Duration::from_secs(10);
+
+/// 檢查排程推播活動是否到期的間隔
+#[cfg(feature = "campaigns")]
+const CAMPAIGN_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 檢查是否換日、送出審核摘要的間隔
+const MODERATION_DIGEST_CHECK_INTERVAL: Duration = Duration::from_secs(300);
 
-use crate::utils::verify_signature;
-use crate::{Config, LineApiClient};
+/// 檢查功能開關規則檔是否變更的間隔
+const FEATURE_FLAGS_RELOAD_INTERVAL: Duration = Duration::from_secs(10);
+
+/// 同步其他實例寫入的功能開關覆寫的間隔
+const FEATURE_FLAGS_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 清除過期重複訊息記錄的間隔
+const DUPLICATE_FILTER_CLEANUP_INTERVAL: Duration = Duration::from_secs(300);
+
+/// 分析統計寫回儲存後端的間隔
+const ANALYTICS_FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 清除過期網頁登入狀態與 session 的間隔
+const LOGIN_CLEANUP_INTERVAL: Duration = Duration::from_secs(300);
+
+/// 清除逾時未確認 LINE Pay 付款的間隔
+#[cfg(feature = "pay")]
+const PAYMENT_CLEANUP_INTERVAL: Duration = Duration::from_secs(300);
 
 #[derive(Clone)]
 pub struct AppState {
     pub config: Config,
     pub line_client: LineApiClient,
+    /// 各元件共用的 HTTP 用戶端，外掛呼叫外部服務時也應使用
+    pub http_client: reqwest::Client,
+    pub stats: Arc<StatsAggregator>,
+    pub event_stream: EventBroadcaster,
+    pub forwarder: WebhookForwarder,
+    /// 啟用非同步處理時的事件佇列
+    pub event_queue: Option<EventQueue>,
+    /// 設定 `VERIFY_DESTINATION` 時檢查 Webhook 的 `destination`
+    pub destination_verifier: Option<DestinationVerifier>,
+    /// 啟用 Webhook 錄製時保存收到的內容
+    pub webhook_recorder: Option<WebhookRecorder>,
+    pub error_reporter: Option<Arc<dyn ErrorReporter>>,
+    pub storage: Arc<dyn Storage>,
+    pub conversation_log: Option<ConversationLogger>,
+    /// 設定 `AUDIT_LOG_ENABLED` 時記錄管理操作
+    pub audit_log: Option<AuditLog>,
+    pub media_pipeline: Option<MediaPipeline>,
+    pub group_cache: GroupCache,
+    pub analytics: Arc<AnalyticsAggregator>,
+    pub metrics: Metrics,
+    pub moderator: Moderator,
+    pub moderation_reporter: Option<ModerationReporter>,
+    pub duplicate_filter: Option<DuplicateFilter>,
+    /// 設定 `RATE_LIMIT_MAX_REQUESTS` 時套用在管理、LIFF、登入與橋接端點的速率限制器
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    pub rich_menus: Option<RichMenuManager>,
+    pub feature_flags: FeatureFlags,
+    pub group_settings: GroupSettingsStore,
+    pub onboarding: Option<Onboarding>,
+    /// 刪除使用者時各元件的清理工作，見 [`AppState::purge_user`]
+    pub user_cleanups: UserDataCleanups,
+    #[cfg(feature = "templates")]
+    pub reply_templates: Option<crate::utils::ReplyTemplates>,
+    #[cfg(feature = "scripting")]
+    pub reply_scripts: Option<crate::utils::ReplyScripts>,
+    #[cfg(feature = "ai")]
+    pub llm_handler: Option<crate::ai::LlmHandler>,
+    pub intent_resolver: Option<Arc<dyn IntentResolver>>,
+    pub translation: Option<TranslationMiddleware>,
+    /// 內建回覆的語系與時區
+    pub i18n: I18n,
+    pub event_sinks: Option<EventSinkWriter>,
+    pub plugins: PluginRegistry,
+    pub id_token_verifier: Option<IdTokenVerifier>,
+    pub line_login: Option<LineLoginFlow>,
+    #[cfg(feature = "feeds")]
+    pub feeds: Option<crate::feeds::FeedScheduler>,
+    #[cfg(feature = "campaigns")]
+    pub campaigns: crate::campaigns::CampaignScheduler,
+    #[cfg(feature = "bridge")]
+    pub operator_bridge: Option<crate::bridge::OperatorBridge>,
+    #[cfg(feature = "pay")]
+    pub line_pay: Option<crate::pay::LinePayCheckout>,
+}
+
+impl AppState {
+    /// 取得處理器專用的 key-value 命名空間
+    pub fn kv(&self, namespace: &str) -> KvNamespace {
+        KvNamespace::new(self.storage.clone(), namespace)
+    }
+
+    /// 刪除使用者在儲存後端的資料，再執行各元件註冊的清理工作
+    /// （引導流程的答案、feed 訂閱、rich menu 狀態、LLM 用量、語言快取與暫存的 Webhook 事件）
+    pub async fn purge_user(&self, user_id: &str) -> Result<(), StorageError> {
+        self.storage.purge_user(user_id).await?;
+        self.user_cleanups.purge_user(user_id).await
+    }
+
+    /// 功能開關是否對這位使用者開啟，規則見 [`FeatureFlags`]
+    pub fn flag(&self, name: &str, user_id: Option<&str>) -> bool {
+        self.feature_flags.is_enabled(name, user_id)
+    }
 }
 
-pub fn create_app(config: Config) -> Router {
-    let line_client = LineApiClient::new(config.channel_access_token.clone());
+/// 無法建立應用程式，例如設定檔載入失敗或外掛初始化失敗
+#[derive(Debug)]
+pub struct StartupError {
+    pub message: String,
+}
+
+impl StartupError {
+    pub fn new<T: Into<String>>(message: T) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for StartupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Startup Error: {}", self.message)
+    }
+}
+
+impl std::error::Error for StartupError {}
+
+impl From<PluginError> for StartupError {
+    fn from(error: PluginError) -> Self {
+        Self::new(error.to_string())
+    }
+}
+
+/// 使用記憶體儲存建立應用程式
+pub fn create_app(config: Config) -> Result<Router, StartupError> {
+    create_app_with_storage(config, Arc::new(MemoryStorage::new()))
+}
+
+/// 使用指定的儲存後端建立應用程式；內建外掛（例如問答遊戲）在背景初始化，失敗時只記錄錯誤
+pub fn create_app_with_storage(
+    config: Config,
+    storage: Arc<dyn Storage>,
+) -> Result<Router, StartupError> {
+    let (router, state) = build_app(config, storage, PluginRegistry::new())?;
+    start_background_tasks(&state);
+    if !state.plugins.is_empty() {
+        tokio::spawn(async move {
+            if let Err(e) = state.plugins.init(state.clone()).await {
+                error!("Failed to initialize plugins: {}", e);
+            }
+        });
+    }
+    Ok(router)
+}
+
+/// 建立應用程式並初始化外掛，外掛的排程工作在初始化後開始執行
+pub async fn create_app_with_plugins(
+    config: Config,
+    storage: Arc<dyn Storage>,
+    plugins: PluginRegistry,
+) -> Result<Router, StartupError> {
+    let (router, state) = build_app(config, storage, plugins)?;
+    start_background_tasks(&state);
+    init_plugins(&state).await?;
+    Ok(router)
+}
+
+/// 先取得 bot 資訊供 destination 驗證使用，再初始化外掛
+async fn init_plugins(state: &Arc<AppState>) -> Result<(), StartupError> {
+    if let Some(verifier) = &state.destination_verifier {
+        match verifier.bot_user_id().await {
+            Ok(bot_user_id) => info!(
+                "Verifying webhook destination against bot {}",
+                SensitiveDataMasker::mask_user_id(bot_user_id)
+            ),
+            Err(e) => warn!(
+                "Failed to fetch bot info for destination verification, retrying on next webhook: {}",
+                e
+            ),
+        }
+    }
+    state.plugins.init(state.clone()).await?;
+    Ok(())
+}
+
+/// 驗證設定並組裝各元件，不啟動任何背景工作，見 [`start_background_tasks`]
+fn build_app(
+    config: Config,
+    storage: Arc<dyn Storage>,
+    plugins: PluginRegistry,
+) -> Result<(Router, Arc<AppState>), StartupError> {
+    let stats = Arc::new(StatsAggregator::new());
+    let metrics = Metrics::default();
+    let error_reporter = create_error_reporter(&config);
+    let http_client = config
+        .http
+        .build()
+        .map_err(|e| StartupError::new(format!("Invalid HTTP client configuration: {}", e)))?;
+    #[cfg(feature = "bridge")]
+    let operator_bridge = config
+        .operator_bridge
+        .clone()
+        .map(|bridge| crate::bridge::OperatorBridge::new(bridge).http_client(http_client.clone()));
+    #[cfg(feature = "bridge")]
+    let error_reporter = match &operator_bridge {
+        Some(bridge) if bridge.config().notify_errors => {
+            let bridge: Arc<dyn ErrorReporter> = Arc::new(bridge.clone());
+            Some(match error_reporter {
+                Some(reporter) => {
+                    Arc::new(crate::utils::CompositeErrorReporter(vec![reporter, bridge]))
+                }
+                None => bridge,
+            })
+        }
+        _ => error_reporter,
+    };
+    let mut builder =
+        LineApiClient::builder(config.channel_access_token.clone()).dry_run(config.dry_run);
+    // 代理只套用在 LINE API，這時改用獨立的連線池
+    builder = match &config.line_api_proxy {
+        Some(proxy) => builder.proxy(proxy.clone()),
+        None => builder.http_client(http_client.clone()),
+    };
+    if let Some(base_url) = &config.line_api_base_url {
+        builder = builder.base_url(base_url.clone());
+    }
+    if let Some(data_base_url) = &config.line_data_api_base_url {
+        builder = builder.data_base_url(data_base_url.clone());
+    }
+    if !config.outgoing_url_allowed_hosts.is_empty() {
+        let hosts = config.outgoing_url_allowed_hosts.clone();
+        builder = builder.message_validator(
+            OutgoingMessageValidator::new()
+                .action_urls(UrlValidator::for_actions().allowed_hosts(hosts.clone()))
+                .media_urls(UrlValidator::for_media().allowed_hosts(hosts)),
+        );
+    }
+    if let Some(path) = &config.offline_buffer_path {
+        builder = builder.offline_buffer(OfflineBuffer::new(
+            path,
+            Duration::from_secs(config.offline_buffer_max_age_secs),
+        ));
+    }
+    let line_client = builder
+        .build()
+        .map_err(|e| StartupError::new(format!("Invalid LINE API client configuration: {}", e)))?;
+    let mut line_client = line_client
+        .with_stats(stats.clone())
+        .with_metrics(metrics.clone());
+    if let Some(reporter) = &error_reporter {
+        line_client = line_client.with_error_reporter(reporter.clone());
+    }
+    let audit_log = config
+        .audit_log_enabled
+        .then(|| AuditLog::new(storage.clone()));
+    if let Some(audit_log) = &audit_log {
+        line_client = line_client.with_audit_log(audit_log.clone());
+    }
+    let conversation_log = config
+        .conversation_log_enabled
+        .then(|| ConversationLogger::new(storage.clone(), config.conversation_log_masking));
+    if let Some(conversation_log) = &conversation_log {
+        line_client = line_client.with_conversation_log(conversation_log.clone());
+    }
+    let media_pipeline = config.media_store.as_ref().map(|store| {
+        MediaPipeline::new(line_client.clone(), store.build_with_client(&http_client))
+            .max_bytes(config.media_max_bytes)
+    });
+    let analytics = Arc::new(AnalyticsAggregator::new());
+    let forbidden_words = match &config.forbidden_words_path {
+        Some(path) => ForbiddenWordList::from_file(path).map_err(|e| {
+            StartupError::new(format!(
+                "Failed to load forbidden words from {}: {}",
+                path, e
+            ))
+        })?,
+        None => ForbiddenWordList::default(),
+    };
+    #[cfg(not(feature = "templates"))]
+    if let Some(dir) = &config.reply_templates_dir {
+        warn!(
+            "REPLY_TEMPLATES_DIR is set to {} but the `templates` feature is disabled, ignoring",
+            dir
+        );
+    }
+    #[cfg(not(feature = "scripting"))]
+    if let Some(dir) = &config.reply_scripts_dir {
+        warn!(
+            "REPLY_SCRIPTS_DIR is set to {} but the `scripting` feature is disabled, ignoring",
+            dir
+        );
+    }
+    #[cfg(not(feature = "ai"))]
+    if config.llm.is_some() {
+        warn!("LLM_MODEL is set but the `ai` feature is disabled, ignoring");
+    }
+    #[cfg(feature = "grpc")]
+    if config.grpc_bind.is_some() && config.admin_token.is_none() {
+        warn!("GRPC_BIND is set but ADMIN_TOKEN is not, gRPC admin service disabled");
+    }
+    #[cfg(not(feature = "grpc"))]
+    if let Some(bind) = config.grpc_bind {
+        warn!(
+            "GRPC_BIND is set to {} but the `grpc` feature is disabled, ignoring",
+            bind
+        );
+    }
+    #[cfg(not(feature = "bridge"))]
+    if config.operator_bridge.is_some() {
+        warn!("OPERATOR_WEBHOOK_URL is set but the `bridge` feature is disabled, ignoring");
+    }
+    #[cfg(not(feature = "pay"))]
+    if config.line_pay.is_some() {
+        warn!("LINE_PAY_CHANNEL_ID is set but the `pay` feature is disabled, ignoring");
+    }
+    #[cfg(feature = "games")]
+    let plugins = match &config.quiz {
+        Some(quiz) => plugins.register(create_quiz_plugin(quiz, storage.clone())?),
+        None => plugins,
+    };
+    #[cfg(not(feature = "games"))]
+    if let Some(quiz) = &config.quiz {
+        warn!(
+            "QUIZ_QUESTIONS_PATH is set to {} but the `games` feature is disabled, ignoring",
+            quiz.questions_path
+        );
+    }
+    #[cfg(not(feature = "campaigns"))]
+    if !config.campaigns.is_empty() {
+        warn!("CAMPAIGNS_FILE is set but the `campaigns` feature is disabled, ignoring");
+    }
+    #[cfg(not(feature = "feeds"))]
+    if !config.feed_sources.is_empty() {
+        warn!("FEED_SOURCES is set but the `feeds` feature is disabled, ignoring");
+    }
+    if config.insecure_skip_signature {
+        warn!(
+            "!!! WEBHOOK SIGNATURE VERIFICATION IS DISABLED (LINEBOT_INSECURE_SKIP_SIGNATURE) — \
+             anyone who can reach this server can send fake events. Never use this in production !!!"
+        );
+    }
+    let moderation_reporter = create_moderation_reporter(&config, &line_client);
+    let duplicate_filter = config.duplicate_filter.clone().map(DuplicateFilter::new);
+    let rate_limiter = config
+        .rate_limit
+        .clone()
+        .map(|rate_limit| Arc::new(RateLimiter::new(rate_limit).with_stats(stats.clone())));
+    let feature_flags = FeatureFlags::new(storage.clone());
+    if let Some(path) = &config.feature_flags_path {
+        feature_flags.load_file(path).map_err(|e| {
+            StartupError::new(format!("Failed to load feature flags from {}: {}", path, e))
+        })?;
+    }
+    let rich_menus = config.rich_menu_states.clone().map(|rich_menu_states| {
+        RichMenuManager::new(line_client.clone(), storage.clone(), rich_menu_states)
+    });
+    let group_cache = GroupCache::new(
+        line_client.clone(),
+        Duration::from_secs(config.group_cache_ttl_secs),
+    );
+
+    // dry run 不呼叫 LINE API，翻譯只以翻譯服務偵測語言，內建回覆使用預設語系
+    let profile_languages =
+        (!config.dry_run).then(|| ProfileLanguageCache::new(line_client.clone()));
+    let translation = config.translation.as_ref().map(|translation| {
+        let middleware = create_translation_middleware(translation, &http_client);
+        match &profile_languages {
+            Some(cache) => middleware.profile_languages(cache.clone()),
+            None => middleware,
+        }
+    });
+    let i18n = I18n::new(config.i18n.clone());
+    let i18n = match &profile_languages {
+        Some(cache) => i18n.profile_languages(cache.clone()),
+        None => i18n,
+    };
+
+    #[cfg(feature = "feeds")]
+    let feeds = (!config.feed_sources.is_empty()).then(|| {
+        crate::feeds::FeedScheduler::new(
+            line_client.clone(),
+            storage.clone(),
+            config.feed_sources.clone(),
+        )
+        .http_client(http_client.clone())
+    });
+    #[cfg(feature = "campaigns")]
+    let campaigns = crate::campaigns::CampaignScheduler::new(
+        line_client.clone(),
+        storage.clone(),
+        config.campaigns.clone(),
+    )
+    .map_err(|e| StartupError::new(format!("Invalid campaign configuration: {}", e)))?;
+
+    #[cfg(feature = "pay")]
+    let line_pay = config.line_pay.as_ref().map(|pay| {
+        crate::pay::LinePayCheckout::new(
+            crate::pay::LinePayClient::new(&pay.channel_id, &pay.channel_secret)
+                .sandbox(pay.sandbox)
+                .http_client(http_client.clone()),
+            storage.clone(),
+            crate::pay::RedirectUrls {
+                confirm_url: pay.confirm_url.clone(),
+                cancel_url: pay
+                    .cancel_url
+                    .clone()
+                    .unwrap_or_else(|| pay.confirm_url.clone()),
+            },
+            &pay.currency,
+        )
+    });
+
+    let event_queue = config
+        .webhook_queue
+        .clone()
+        .map(|queue| EventQueue::new(queue, storage.clone(), metrics.clone()));
+
+    let destination_verifier = config
+        .verify_destination
+        .then(|| DestinationVerifier::new(line_client.clone(), config.bot_user_id.clone()));
+
+    let onboarding = config
+        .onboarding
+        .clone()
+        .map(|onboarding| Onboarding::new(onboarding, storage.clone()));
+    #[cfg(feature = "ai")]
+    let llm_handler = config.llm.clone().map(|llm| {
+        crate::ai::LlmHandler::new(llm, storage.clone()).http_client(http_client.clone())
+    });
+
+    // 在儲存後端的使用者資料以外保存個人資料的元件，刪除使用者時一併清除
+    let mut user_cleanups = UserDataCleanups::new();
+    if let Some(onboarding) = &onboarding {
+        user_cleanups.register(onboarding.clone());
+    }
+    #[cfg(feature = "feeds")]
+    if let Some(feeds) = &feeds {
+        user_cleanups.register(feeds.clone());
+    }
+    if let Some(rich_menus) = &rich_menus {
+        user_cleanups.register(rich_menus.clone());
+    }
+    #[cfg(feature = "ai")]
+    if let Some(llm_handler) = &llm_handler {
+        user_cleanups.register(llm_handler.clone());
+    }
+    if let Some(profile_languages) = profile_languages {
+        user_cleanups.register(profile_languages);
+    }
+    if let Some(queue) = &event_queue
+        && queue.config().overflow == OverflowPolicy::Spill
+    {
+        user_cleanups.register(queue.clone());
+    }
 
     let state = Arc::new(AppState {
         config: config.clone(),
         line_client,
+        http_client: http_client.clone(),
+        stats,
+        event_stream: EventBroadcaster::default(),
+        forwarder: WebhookForwarder::new(
+            config.forward_targets.clone(),
+            config
+                .forward_secret
+                .clone()
+                .unwrap_or_else(|| config.channel_secret.clone()),
+            config.forward_max_retries,
+        )
+        .http_client(http_client.clone()),
+        event_queue,
+        destination_verifier,
+        webhook_recorder: config
+            .webhook_recording
+            .clone()
+            .map(|recording| WebhookRecorder::new(recording, storage.clone())),
+        error_reporter,
+        media_pipeline,
+        group_cache,
+        analytics,
+        metrics: metrics.clone(),
+        moderator: Moderator::new(forbidden_words, config.moderation_policy),
+        moderation_reporter,
+        duplicate_filter,
+        rate_limiter,
+        rich_menus,
+        feature_flags,
+        group_settings: GroupSettingsStore::new(storage.clone()),
+        onboarding,
+        user_cleanups,
+        #[cfg(feature = "templates")]
+        reply_templates: create_reply_templates(&config)?,
+        #[cfg(feature = "scripting")]
+        reply_scripts: create_reply_scripts(&config)?,
+        #[cfg(feature = "ai")]
+        llm_handler,
+        intent_resolver: config
+            .nlu
+            .as_ref()
+            .map(|nlu| create_intent_resolver(nlu, &http_client)),
+        translation,
+        i18n,
+        event_sinks: config
+            .event_sinks
+            .as_ref()
+            .map(|sinks| create_event_sink_writer(sinks, &http_client)),
+        plugins,
+        id_token_verifier: config
+            .line_login
+            .as_ref()
+            .map(|login| IdTokenVerifier::new(&login.channel_id).http_client(http_client.clone())),
+        line_login: config
+            .line_login
+            .as_ref()
+            .and_then(|login| create_line_login_flow(login, storage.clone(), &http_client)),
+        #[cfg(feature = "feeds")]
+        feeds,
+        #[cfg(feature = "campaigns")]
+        campaigns,
+        #[cfg(feature = "bridge")]
+        operator_bridge,
+        #[cfg(feature = "pay")]
+        line_pay,
+        conversation_log,
+        audit_log,
+        storage,
     });
 
-    Router::new()
-        .route("/webhook", post(crate::webhook::handlers::handle_webhook))
-        .route("/health", axum::routing::get(health_check))
-        .layer(
-            ServiceBuilder::new()
-                .layer(TraceLayer::new_for_http())
-                .layer(CorsLayer::permissive())
-                .layer(middleware::from_fn_with_state(
-                    state.clone(),
-                    signature_middleware,
-                )),
+    let router = Router::new()
+        .route(
+            "/webhook",
+            post(crate::webhook::handlers::handle_webhook).route_layer(
+                LineSignatureLayer::new(config.channel_secret.clone())
+                    .secondary_secrets(config.secondary_channel_secrets.clone())
+                    .require_prefix(config.signature_require_prefix)
+                    .insecure_skip_verification(config.insecure_skip_signature)
+                    .max_body_bytes(config.webhook_max_body_bytes)
+                    .metrics(metrics.clone()),
+            ),
         )
-        .with_state(state)
-}
+        .route("/health", axum::routing::get(health_check));
+    let limited = Router::new()
+        .nest("/admin", admin_router(state.clone()))
+        .nest("/liff", crate::webhook::liff::liff_router())
+        .nest("/auth", crate::webhook::login::login_router());
+    #[cfg(feature = "bridge")]
+    let limited = limited.nest("/bridge", crate::webhook::bridge::bridge_router());
+    let limited = match &state.rate_limiter {
+        Some(rate_limiter) => limited.layer(middleware::from_fn_with_state(
+            rate_limiter.clone(),
+            rate_limit_middleware,
+        )),
+        None => limited,
+    };
+    let router = router.merge(limited).layer(
+        ServiceBuilder::new()
+            .layer(SetRequestIdLayer::new(
+                HeaderName::from_static(REQUEST_ID_HEADER),
+                MakeRequestUuid,
+            ))
+            .layer(
+                TraceLayer::new_for_http().make_span_with(|request: &Request| {
+                    let request_id = request
+                        .extensions()
+                        .get::<RequestId>()
+                        .and_then(|id| id.header_value().to_str().ok())
+                        .unwrap_or("unknown");
+                    info_span!(
+                        "http_request",
+                        method = %request.method(),
+                        uri = %request.uri(),
+                        request_id = %request_id,
+                    )
+                }),
+            )
+            .layer(PropagateRequestIdLayer::new(HeaderName::from_static(
+                REQUEST_ID_HEADER,
+            )))
+            .layer(middleware::from_fn_with_state(metrics, metrics_middleware))
+            .layer(CorsLayer::permissive()),
+    );
 
-pub async fn start_server(config: Config) -> Result<(), Box<dyn std::error::Error>> {
-    let app = create_app(config.clone());
+    let router = if config.request_decompression_enabled {
+        router.layer(RequestDecompressionLayer::new())
+    } else {
+        router
+    };
 
-    let bind_address = format!("{}:{}", config.host, config.port);
-    info!("Starting server on {}", bind_address);
+    Ok((router.with_state(state.clone()), state))
+}
 
-    let listener = tokio::net::TcpListener::bind(&bind_address).await?;
-    axum::serve(listener, app).await?;
+/// 啟動各元件的背景工作：重送、定期寫回、設定檔重新載入與排程
+fn start_background_tasks(state: &Arc<AppState>) {
+    let config = &state.config;
+    state
+        .line_client
+        .start_offline_retry(Duration::from_secs(30));
+    state
+        .analytics
+        .start_flush_task(state.storage.clone(), ANALYTICS_FLUSH_INTERVAL);
+    if let Some(secs) = config.quota_poll_interval_secs
+        && !config.dry_run
+    {
+        QuotaMonitor::new(
+            state.line_client.clone(),
+            state.metrics.clone(),
+            f64::from(config.quota_warning_percent) / 100.0,
+        )
+        .alert_user_ids(config.quota_alert_user_ids.clone())
+        .start(Duration::from_secs(secs));
+    }
+    if let Some(path) = &config.forbidden_words_path {
+        state.moderator.words().watch(
+            path,
+            FORBIDDEN_WORDS_RELOAD_INTERVAL,
+            state.audit_log.clone(),
+        );
+    }
+    if let Some(reporter) = &state.moderation_reporter {
+        reporter.start_digest(MODERATION_DIGEST_CHECK_INTERVAL);
+    }
+    if let Some(filter) = &state.duplicate_filter {
+        filter.start_cleanup(DUPLICATE_FILTER_CLEANUP_INTERVAL);
+    }
+    if let Some(rate_limiter) = &state.rate_limiter {
+        rate_limiter.start_cleanup();
+    }
+    if let Some(path) = &config.feature_flags_path {
+        state
+            .feature_flags
+            .watch(path, FEATURE_FLAGS_RELOAD_INTERVAL, state.audit_log.clone());
+    }
+    state
+        .feature_flags
+        .start_refresh(FEATURE_FLAGS_REFRESH_INTERVAL);
+    if let Some(manager) = &state.rich_menus {
+        manager.start();
+    }
+    if let Some(login) = &state.line_login {
+        login.start_cleanup(LOGIN_CLEANUP_INTERVAL);
+    }
+    #[cfg(feature = "pay")]
+    if let Some(pay) = &state.line_pay {
+        pay.start_cleanup(PAYMENT_CLEANUP_INTERVAL);
+    }
+    #[cfg(feature = "templates")]
+    if let Some(templates) = &state.reply_templates {
+        templates.watch(REPLY_TEMPLATES_RELOAD_INTERVAL, state.audit_log.clone());
+    }
+    #[cfg(feature = "scripting")]
+    if let Some(scripts) = &state.reply_scripts {
+        scripts.watch(REPLY_SCRIPTS_RELOAD_INTERVAL, state.audit_log.clone());
+    }
+    #[cfg(feature = "feeds")]
+    if let Some(feeds) = &state.feeds {
+        feeds.start(Duration::from_secs(config.feed_poll_interval_secs));
+    }
+    #[cfg(feature = "campaigns")]
+    state.campaigns.start(CAMPAIGN_CHECK_INTERVAL);
+    if let Some(writer) = &state.event_sinks {
+        writer.start();
+    }
+    if let Some(queue) = &state.event_queue {
+        let worker_state = state.clone();
+        queue.start(move |event| {
+            let state = worker_state.clone();
+            async move { crate::webhook::handlers::handle_webhook_event(&state, event).await }
+        });
+    }
+}
 
+/// 在背景啟動 gRPC 管理服務，需同時設定 `GRPC_BIND` 與 `ADMIN_TOKEN`
+#[cfg(feature = "grpc")]
+async fn start_grpc_service(config: &Config, state: &AppState) -> std::io::Result<()> {
+    let (Some(bind), Some(token)) = (config.grpc_bind, &config.admin_token) else {
+        return Ok(());
+    };
+    let mut service =
+        crate::grpc::AdminGrpcService::new(state.line_client.clone(), state.stats.clone());
+    if let Some(audit_log) = &state.audit_log {
+        service = service.with_audit_log(audit_log.clone());
+    }
+    service.start(bind, token.clone()).await?;
     Ok(())
 }
 
-async fn health_check() -> impl IntoResponse {
-    (StatusCode::OK, "OK")
+fn create_error_reporter(config: &Config) -> Option<Arc<dyn ErrorReporter>> {
+    let dsn = config.sentry_dsn.as_deref()?;
+
+    #[cfg(feature = "sentry")]
+    {
+        Some(Arc::new(crate::utils::SentryErrorReporter::new(dsn)))
+    }
+
+    #[cfg(not(feature = "sentry"))]
+    {
+        warn!(
+            "SENTRY_DSN is set but the `sentry` feature is disabled, ignoring ({} chars)",
+            dsn.len()
+        );
+        None
+    }
 }
 
-async fn signature_middleware(
-    State(state): State<Arc<AppState>>,
-    headers: HeaderMap,
-    request: Request,
-    next: Next,
-) -> Response {
-    // Skip signature verification for health check
-    if request.uri().path() == "/health" {
-        return next.run(request).await;
+#[cfg(feature = "templates")]
+fn create_reply_templates(
+    config: &Config,
+) -> Result<Option<crate::utils::ReplyTemplates>, StartupError> {
+    let Some(dir) = config.reply_templates_dir.as_deref() else {
+        return Ok(None);
+    };
+    crate::utils::ReplyTemplates::from_dir(dir)
+        .map(Some)
+        .map_err(|e| {
+            StartupError::new(format!(
+                "Failed to load reply templates from {}: {}",
+                dir, e
+            ))
+        })
+}
+
+/// 有管理者或營運群組時回報審核結果並送出每日摘要
+fn create_moderation_reporter(
+    config: &Config,
+    line_client: &LineApiClient,
+) -> Option<ModerationReporter> {
+    let mut targets = config.moderation_alert_user_ids.clone();
+    targets.extend(config.moderation_report_group_id.clone());
+    if targets.is_empty() {
+        return None;
     }
+    Some(ModerationReporter::new(
+        line_client.clone(),
+        targets,
+        config.moderation_report_daily_limit,
+    ))
+}
 
-    let signature = match headers.get("x-line-signature") {
-        Some(sig) => match sig.to_str() {
-            Ok(s) => s,
-            Err(_) => {
-                return (StatusCode::BAD_REQUEST, "Invalid signature header").into_response();
-            }
-        },
+#[cfg(feature = "scripting")]
+fn create_reply_scripts(
+    config: &Config,
+) -> Result<Option<crate::utils::ReplyScripts>, StartupError> {
+    let Some(dir) = config.reply_scripts_dir.as_deref() else {
+        return Ok(None);
+    };
+    crate::utils::ReplyScripts::from_dir(dir)
+        .map(Some)
+        .map_err(|e| StartupError::new(format!("Failed to load reply scripts from {}: {}", dir, e)))
+}
+
+#[cfg(feature = "games")]
+fn create_quiz_plugin(
+    quiz: &crate::games::QuizConfig,
+    storage: Arc<dyn Storage>,
+) -> Result<crate::games::QuizPlugin, StartupError> {
+    let bank = crate::games::QuestionBank::from_path(&quiz.questions_path).map_err(|e| {
+        StartupError::new(format!(
+            "Failed to load quiz questions from {}: {}",
+            quiz.questions_path, e
+        ))
+    })?;
+    let engine = crate::games::QuizEngine::new(bank, storage)
+        .round_duration(Duration::from_secs(quiz.round_secs))
+        .questions_per_game(quiz.questions_per_game);
+    Ok(crate::games::QuizPlugin::new(engine))
+}
+
+/// 設定了 channel secret 與 redirect URI 時才開放網頁登入
+fn create_line_login_flow(
+    config: &LineLoginConfig,
+    storage: Arc<dyn Storage>,
+    http_client: &reqwest::Client,
+) -> Option<LineLoginFlow> {
+    let (Some(secret), Some(redirect_uri)) = (&config.channel_secret, &config.redirect_uri) else {
+        return None;
+    };
+    let client = LineLoginClient::new(&config.channel_id, secret, redirect_uri)
+        .scope(&config.scope)
+        .http_client(http_client.clone());
+    Some(LineLoginFlow::new(client, storage, &config.success_url))
+}
+
+fn create_event_sink_writer(
+    config: &EventSinkConfig,
+    http_client: &reqwest::Client,
+) -> EventSinkWriter {
+    let mut sinks: Vec<Arc<dyn EventSink>> = Vec::new();
+    if let Some(csv) = &config.csv {
+        let sink = CsvFileSink::new(&csv.dir).rotation(csv.rotation);
+        sinks.push(Arc::new(match csv.max_files {
+            Some(max_files) => sink.max_files(max_files),
+            None => sink,
+        }));
+    }
+    if let Some(sheets) = &config.google_sheets {
+        let sink = GoogleSheetsSink::new(&sheets.spreadsheet_id, &sheets.range)
+            .http_client(http_client.clone());
+        sinks.push(Arc::new(match &sheets.access_token {
+            Some(token) => sink.access_token(token),
+            None => sink,
+        }));
+    }
+    EventSinkWriter::new(
+        sinks,
+        config.masking,
+        Duration::from_secs(config.flush_interval_secs),
+    )
+}
+
+fn create_intent_resolver(
+    config: &NluConfig,
+    http_client: &reqwest::Client,
+) -> Arc<dyn IntentResolver> {
+    match &config.provider {
+        NluProvider::Rasa { url, token } => {
+            let resolver = RasaResolver::new(url.clone()).http_client(http_client.clone());
+            Arc::new(match token {
+                Some(token) => resolver.token(token.clone()),
+                None => resolver,
+            })
+        }
+        NluProvider::DialogflowCx {
+            agent,
+            language_code,
+            access_token,
+        } => {
+            let resolver = DialogflowCxResolver::new(agent.clone(), language_code.clone())
+                .http_client(http_client.clone());
+            Arc::new(match access_token {
+                Some(token) => resolver.access_token(token.clone()),
+                None => resolver,
+            })
+        }
+    }
+}
+
+fn create_translation_middleware(
+    config: &TranslationConfig,
+    http_client: &reqwest::Client,
+) -> TranslationMiddleware {
+    let translator: Arc<dyn Translator> = match &config.provider {
+        TranslationProvider::Google { api_key } => {
+            Arc::new(GoogleTranslator::new(api_key.clone()).http_client(http_client.clone()))
+        }
+        TranslationProvider::Libre { url, api_key } => {
+            let translator = LibreTranslator::new(url.clone()).http_client(http_client.clone());
+            Arc::new(match api_key {
+                Some(api_key) => translator.api_key(api_key.clone()),
+                None => translator,
+            })
+        }
+    };
+    TranslationMiddleware::new(translator, config.working_language.clone())
+}
+
+pub async fn start_server(config: Config) -> Result<(), Box<dyn std::error::Error>> {
+    start_server_with_plugins(config, PluginRegistry::new()).await
+}
+
+/// 啟動伺服器，收到 Ctrl-C 或 SIGTERM 時停止接受請求並關閉外掛
+pub async fn start_server_with_plugins(
+    config: Config,
+    plugins: PluginRegistry,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // metrics 只能安裝一個全域 recorder
+    let exporters = [
+        config.metrics_exporter.is_some(),
+        config.statsd_exporter.is_some(),
+        config.otlp_exporter.is_some(),
+    ];
+    if exporters.iter().filter(|enabled| **enabled).count() > 1 {
+        return Err(
+            "Only one of METRICS_BIND, STATSD_HOST and OTEL_EXPORTER_OTLP_ENDPOINT can be set"
+                .into(),
+        );
+    }
+    if let Some(exporter) = &config.metrics_exporter {
+        start_metrics_exporter(exporter).await?;
+    }
+    if let Some(exporter) = &config.statsd_exporter {
+        start_statsd_exporter(exporter)?;
+    }
+    if let Some(exporter) = &config.otlp_exporter {
+        start_otlp_exporter(exporter)?;
+    }
+    if exporters.contains(&true) {
+        SystemMetrics::new().start_periodic_update(SYSTEM_METRICS_INTERVAL);
+    }
+
+    let storage = connect_storage(config.storage_url.as_deref()).await?;
+    let (app, state) = build_app(config.clone(), storage, plugins.clone())?;
+    start_background_tasks(&state);
+    #[cfg(feature = "grpc")]
+    start_grpc_service(&config, &state).await?;
+    init_plugins(&state).await?;
+
+    let listener = match systemd::take_activated_listener()? {
+        Some(listener) => {
+            info!(
+                "Using socket passed by systemd on {}",
+                listener.local_addr()?
+            );
+            tokio::net::TcpListener::from_std(listener)?
+        }
         None => {
-            return (StatusCode::BAD_REQUEST, "Missing signature header").into_response();
+            let bind_address = format!("{}:{}", config.host, config.port);
+            info!("Starting server on {}", bind_address);
+            tokio::net::TcpListener::bind(&bind_address).await?
         }
     };
 
-    let (parts, body) = request.into_parts();
-    let body_bytes = match axum::body::to_bytes(body, usize::MAX).await {
-        Ok(bytes) => bytes,
-        Err(_) => {
-            return (StatusCode::BAD_REQUEST, "Failed to read body").into_response();
+    check_insecure_skip_signature(&config, listener.local_addr()?)?;
+
+    if systemd::notify_ready()? {
+        info!("Notified systemd readiness");
+    }
+
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
+    plugins.shutdown().await;
+
+    Ok(())
+}
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            warn!("Failed to listen for Ctrl-C: {}", e);
+            std::future::pending::<()>().await;
         }
     };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                warn!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+    info!("Shutting down server");
+}
+
+/// 略過簽名驗證時只允許綁定 loopback，除非明確強制
+fn check_insecure_skip_signature(config: &Config, addr: SocketAddr) -> Result<(), String> {
+    if !config.insecure_skip_signature {
+        return Ok(());
+    }
+    if !addr.ip().is_loopback() && !config.insecure_skip_signature_force {
+        return Err(format!(
+            "LINEBOT_INSECURE_SKIP_SIGNATURE is only allowed on loopback addresses, but the server \
+             is listening on {}; set LINEBOT_INSECURE_SKIP_SIGNATURE_FORCE=true to override",
+            addr
+        ));
+    }
+    Ok(())
+}
+
+async fn health_check(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    state.metrics.record_health_check(true);
+    (StatusCode::OK, "OK")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insecure_skip_signature_requires_loopback() {
+        let config = Config {
+            insecure_skip_signature: true,
+            ..Config::default()
+        };
+        let loopback: SocketAddr = "127.0.0.1:3000".parse().unwrap();
+        let public: SocketAddr = "0.0.0.0:3000".parse().unwrap();
 
-    if !verify_signature(&state.config.channel_secret, &body_bytes, signature) {
-        return (StatusCode::UNAUTHORIZED, "Invalid signature").into_response();
+        assert!(check_insecure_skip_signature(&config, loopback).is_ok());
+        assert!(check_insecure_skip_signature(&config, public).is_err());
+
+        let forced = Config {
+            insecure_skip_signature_force: true,
+            ..config
+        };
+        assert!(check_insecure_skip_signature(&forced, public).is_ok());
+        assert!(check_insecure_skip_signature(&Config::default(), public).is_ok());
     }
 
-    // Reconstruct the request with the body
-    let new_request = Request::from_parts(parts, axum::body::Body::from(body_bytes));
+    #[tokio::test]
+    async fn test_purge_user_runs_component_cleanups() {
+        use crate::ai::LlmConfig;
+        use crate::feeds::FeedSource;
+        use crate::line_api::RichMenuStateConfig;
+        use crate::onboarding::OnboardingConfig;
+        use crate::webhook::WebhookQueueConfig;
+        use axum::{Json, extract::Path, routing::get};
+        use serde_json::{Value, json};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        const USER: &str = "U1234567890abcdef1234567890abcdef";
+        const OTHER: &str = "U2234567890abcdef1234567890abcdef";
+
+        let profile_calls = Arc::new(AtomicUsize::new(0));
+        let counter = profile_calls.clone();
+        let line_api = Router::new().route(
+            "/v2/bot/profile/:id",
+            get(move |Path(id): Path<String>| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                async move { Json(json!({"userId": id, "language": "ja"})) }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, line_api).await.unwrap() });
 
-    next.run(new_request).await
+        let config = Config {
+            line_api_base_url: Some(format!("http://{}/v2/bot", addr)),
+            onboarding: Some(OnboardingConfig::default()),
+            rich_menu_states: Some(RichMenuStateConfig::default()),
+            webhook_queue: Some(WebhookQueueConfig {
+                overflow: OverflowPolicy::Spill,
+                ..Default::default()
+            }),
+            feed_sources: vec![FeedSource::parse("http://127.0.0.1:1/feed.xml").unwrap()],
+            llm: Some(LlmConfig::new("test-model")),
+            ..Config::default()
+        };
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let (_, state) = build_app(config, storage, PluginRegistry::new()).unwrap();
+
+        state
+            .kv("profile")
+            .set(USER, &json!({"city": "Taipei"}))
+            .await
+            .unwrap();
+        let rich_menus = state.rich_menus.as_ref().unwrap();
+        rich_menus.set_state(USER, Some("member")).await.unwrap();
+        state
+            .kv("rich_menu_linked")
+            .set(USER, "member")
+            .await
+            .unwrap();
+        for user_id in [USER, OTHER] {
+            state
+                .kv("llm_usage")
+                .set(&format!("{}:2024-01-01", user_id), &10)
+                .await
+                .unwrap();
+        }
+        #[cfg(feature = "feeds")]
+        state.feeds.as_ref().unwrap().subscribe(USER).await.unwrap();
+        for (seq, user_id) in [USER, OTHER].into_iter().enumerate() {
+            state
+                .kv("webhook_queue")
+                .set(
+                    &format!("event:{:020}", seq),
+                    &json!({"type": "follow", "source": {"type": "user", "userId": user_id}}),
+                )
+                .await
+                .unwrap();
+        }
+        state.i18n.locale_for(Some(USER), None).await;
+        state.i18n.locale_for(Some(USER), None).await;
+        assert_eq!(profile_calls.load(Ordering::SeqCst), 1);
+
+        state.purge_user(USER).await.unwrap();
+
+        assert!(
+            state
+                .kv("profile")
+                .get::<Value>(USER)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(rich_menus.state(USER).await.unwrap().is_none());
+        assert_eq!(rich_menus.pending_count(), 0);
+        assert!(
+            state
+                .kv("rich_menu_linked")
+                .get::<String>(USER)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        #[cfg(feature = "ai")]
+        assert_eq!(
+            state.kv("llm_usage").keys("").await.unwrap(),
+            [format!("{}:2024-01-01", OTHER)]
+        );
+        #[cfg(feature = "feeds")]
+        assert!(
+            state
+                .feeds
+                .as_ref()
+                .unwrap()
+                .subscribers()
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            state.kv("webhook_queue").keys("event:").await.unwrap(),
+            [format!("event:{:020}", 1)]
+        );
+        assert_eq!(state.event_queue.as_ref().unwrap().spilled(), 1);
+        // 語言快取已清除，會重新查詢
+        state.i18n.locale_for(Some(USER), None).await;
+        assert_eq!(profile_calls.load(Ordering::SeqCst), 2);
+    }
 }
@@ -0,0 +1,473 @@
+use axum::{
+    async_trait,
+    body::{Body, Bytes},
+    extract::{FromRequestParts, Request},
+    http::header::CONTENT_LENGTH,
+    http::{StatusCode, request::Parts},
+    response::{IntoResponse, Json, Response},
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, error::Category};
+use std::{
+    convert::Infallible,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio_stream::StreamExt;
+use tower::{Layer, Service};
+use tower_http::request_id::RequestId;
+use tracing::warn;
+
+use crate::utils::{Metrics, SignatureHasher};
+
+/// Webhook 內容的預設大小上限（1 MiB），LINE 實際送出的事件遠小於此
+pub const DEFAULT_MAX_WEBHOOK_BODY_BYTES: usize = 1024 * 1024;
+
+/// 通過簽名驗證的原始 Webhook 內容，供需要原始位元組的處理（例如轉發）使用
+#[derive(Clone)]
+pub struct VerifiedBody(pub Bytes);
+
+/// 驗證 `x-line-signature` 的 tower Layer
+///
+/// 邊讀取請求內容邊以 channel secret 計算簽名，超過 [`max_body_bytes`](Self::max_body_bytes)
+/// 時立即以 413 拒絕；通過後將內容放入 [`VerifiedBody`] extension 再交給下一層，
+/// 可直接套用在自己的 axum router 上：
+///
+/// ```ignore
+/// Router::new()
+///     .route("/callback", post(handler))
+///     .route_layer(LineSignatureLayer::new(channel_secret));
+/// ```
+///
+/// 輪替 secret 期間可用 [`secondary_secrets`](Self::secondary_secrets) 同時接受舊 secret。
+#[derive(Clone)]
+pub struct LineSignatureLayer {
+    channel_secrets: Arc<[String]>,
+    require_prefix: bool,
+    skip_verification: bool,
+    max_body_bytes: usize,
+    metrics: Metrics,
+}
+
+impl LineSignatureLayer {
+    pub fn new(channel_secret: impl Into<String>) -> Self {
+        Self {
+            channel_secrets: Arc::from([channel_secret.into()]),
+            require_prefix: false,
+            skip_verification: false,
+            max_body_bytes: DEFAULT_MAX_WEBHOOK_BODY_BYTES,
+            metrics: Metrics::noop(),
+        }
+    }
+
+    /// 主要 secret 不符時依序嘗試的其他 secret
+    pub fn secondary_secrets(mut self, secrets: impl IntoIterator<Item = String>) -> Self {
+        let mut all = vec![self.channel_secrets[0].clone()];
+        all.extend(secrets);
+        self.channel_secrets = all.into();
+        self
+    }
+
+    /// 僅供本機開發：不檢查簽名，所有請求都視為已驗證
+    pub fn insecure_skip_verification(mut self, skip: bool) -> Self {
+        self.skip_verification = skip;
+        self
+    }
+
+    /// 記錄相符的 secret 索引（`webhook_signature_matches_total`）
+    pub fn metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// 只接受帶 `sha256=` 前綴的簽名
+    pub fn require_prefix(mut self, require: bool) -> Self {
+        self.require_prefix = require;
+        self
+    }
+
+    /// 請求內容的大小上限（位元組）
+    pub fn max_body_bytes(mut self, max: usize) -> Self {
+        self.max_body_bytes = max;
+        self
+    }
+
+    fn verify(&self, hasher: SignatureHasher, signature: &str) -> bool {
+        match hasher.find_match(signature, self.require_prefix) {
+            Some(index) => {
+                self.metrics.record_signature_match(index);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<S> Layer<S> for LineSignatureLayer {
+    type Service = LineSignatureService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        LineSignatureService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct LineSignatureService<S> {
+    inner: S,
+    layer: LineSignatureLayer,
+}
+
+impl<S> Service<Request> for LineSignatureService<S>
+where
+    S: Service<Request, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, Infallible>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        // 使用已 ready 的 inner，留下複本給下一次呼叫
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let layer = self.layer.clone();
+
+        Box::pin(async move {
+            let signature = match request.headers().get("x-line-signature") {
+                _ if layer.skip_verification => None,
+                Some(sig) => match sig.to_str() {
+                    Ok(s) => Some(s.to_string()),
+                    Err(_) => {
+                        return Ok(
+                            (StatusCode::BAD_REQUEST, "Invalid signature header").into_response()
+                        );
+                    }
+                },
+                None => {
+                    return Ok(
+                        (StatusCode::BAD_REQUEST, "Missing signature header").into_response()
+                    );
+                }
+            };
+
+            let too_large = || (StatusCode::PAYLOAD_TOO_LARGE, "Body too large").into_response();
+            let declared_length = request
+                .headers()
+                .get(CONTENT_LENGTH)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<usize>().ok());
+            if declared_length.is_some_and(|length| length > layer.max_body_bytes) {
+                return Ok(too_large());
+            }
+
+            // 邊讀邊計算 HMAC，超過上限就停止讀取，不會先緩衝整個內容
+            let (mut parts, body) = request.into_parts();
+            let mut hasher = SignatureHasher::new(&layer.channel_secrets);
+            let mut buffer =
+                Vec::with_capacity(declared_length.unwrap_or(0).min(layer.max_body_bytes));
+            let mut stream = body.into_data_stream();
+            while let Some(chunk) = stream.next().await {
+                let Ok(chunk) = chunk else {
+                    return Ok((StatusCode::BAD_REQUEST, "Failed to read body").into_response());
+                };
+                if buffer.len() + chunk.len() > layer.max_body_bytes {
+                    return Ok(too_large());
+                }
+                hasher.update(&chunk);
+                buffer.extend_from_slice(&chunk);
+            }
+            let body_bytes = Bytes::from(buffer);
+
+            if let Some(signature) = signature
+                && !layer.verify(hasher, &signature)
+            {
+                return Ok((StatusCode::UNAUTHORIZED, "Invalid signature").into_response());
+            }
+
+            parts.extensions.insert(VerifiedBody(body_bytes.clone()));
+            inner
+                .call(Request::from_parts(parts, Body::from(body_bytes)))
+                .await
+        })
+    }
+}
+
+/// Webhook 請求被拒絕時回傳的 JSON
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookErrorResponse {
+    /// `signature_not_verified` 或 `invalid_body`
+    pub code: String,
+    pub message: String,
+    /// 請求的 `x-request-id`，與伺服器日誌對照用
+    pub correlation_id: Option<String>,
+}
+
+impl WebhookErrorResponse {
+    fn into_response(self, status: StatusCode) -> Response {
+        (status, Json(self)).into_response()
+    }
+}
+
+/// 從通過 [`LineSignatureLayer`] 驗證的內容解析 JSON
+///
+/// 未套用 Layer 時一律拒絕，避免誤把未驗證的請求當成 LINE 送來的事件。
+pub struct VerifiedJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for VerifiedJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let correlation_id = parts
+            .extensions
+            .get::<RequestId>()
+            .and_then(|id| id.header_value().to_str().ok())
+            .map(str::to_string);
+        let Some(VerifiedBody(body)) = parts.extensions.get::<VerifiedBody>() else {
+            return Err(WebhookErrorResponse {
+                code: "signature_not_verified".to_string(),
+                message: "Signature not verified".to_string(),
+                correlation_id,
+            }
+            .into_response(StatusCode::UNAUTHORIZED));
+        };
+        serde_json::from_slice(body).map(VerifiedJson).map_err(|e| {
+            // serde 的錯誤訊息可能帶有使用者輸入的值，回應與日誌只保留錯誤種類、位置與欄位結構
+            let shape = serde_json::from_slice::<Value>(body)
+                .map(|value| json_shape(&value).to_string())
+                .unwrap_or_else(|_| "not JSON".to_string());
+            let category = error_category(&e);
+            warn!(
+                correlation_id = correlation_id.as_deref().unwrap_or("-"),
+                "Rejected webhook body: {} error at line {} column {} (shape: {})",
+                category,
+                e.line(),
+                e.column(),
+                shape
+            );
+            WebhookErrorResponse {
+                code: "invalid_body".to_string(),
+                message: format!(
+                    "Invalid JSON body ({}) at line {} column {}",
+                    category,
+                    e.line(),
+                    e.column()
+                ),
+                correlation_id,
+            }
+            .into_response(StatusCode::BAD_REQUEST)
+        })
+    }
+}
+
+fn error_category(error: &serde_json::Error) -> &'static str {
+    match error.classify() {
+        Category::Io => "io",
+        Category::Syntax => "syntax",
+        Category::Data => "data",
+        Category::Eof => "eof",
+    }
+}
+
+/// 把 JSON 的值換成型別名稱，只保留鍵與 `type` 欄位（事件與訊息種類）
+fn json_shape(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, field)| {
+                    let shape = match field {
+                        Value::String(kind) if key == "type" => Value::String(kind.clone()),
+                        _ => json_shape(field),
+                    };
+                    (key.clone(), shape)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(json_shape).collect()),
+        Value::String(_) => Value::from("string"),
+        Value::Number(_) => Value::from("number"),
+        Value::Bool(_) => Value::from("bool"),
+        Value::Null => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::generate_signature;
+    use axum::{Router, routing::post};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route(
+                "/callback",
+                post(
+                    |VerifiedJson(payload): VerifiedJson<serde_json::Value>| async move {
+                        payload["destination"]
+                            .as_str()
+                            .unwrap_or_default()
+                            .to_string()
+                    },
+                ),
+            )
+            .route_layer(LineSignatureLayer::new("test_secret"))
+    }
+
+    fn request(signature: Option<&str>, body: &'static str) -> Request {
+        let mut builder = Request::post("/callback");
+        if let Some(signature) = signature {
+            builder = builder.header("x-line-signature", signature);
+        }
+        builder.body(Body::from(body)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_layer_verifies_and_extracts_json() {
+        let body = r#"{"destination":"U123","events":[]}"#;
+        let signature = generate_signature("test_secret", body.as_bytes());
+
+        let response = app()
+            .oneshot(request(Some(&signature), body))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let text = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&text[..], b"U123");
+
+        let response = app().oneshot(request(None, body)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let wrong = generate_signature("other_secret", body.as_bytes());
+        let response = app().oneshot(request(Some(&wrong), body)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_secondary_secret_accepted_and_recorded() {
+        let metrics = Metrics::new();
+        let app = Router::new()
+            .route("/callback", post(|| async {}))
+            .route_layer(
+                LineSignatureLayer::new("new_secret")
+                    .secondary_secrets(["old_secret".to_string()])
+                    .metrics(metrics.clone()),
+            );
+        let body = "{}";
+
+        for secret in ["new_secret", "old_secret"] {
+            let signature = generate_signature(secret, body.as_bytes());
+            let response = app
+                .clone()
+                .oneshot(request(Some(&signature), body))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        assert_eq!(metrics.snapshot().secondary_secret_matches, 1);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_body_returns_structured_error() {
+        #[derive(serde::Deserialize)]
+        struct Payload {
+            #[allow(dead_code)]
+            events: Vec<u32>,
+        }
+
+        let app = Router::new()
+            .route("/callback", post(|_: VerifiedJson<Payload>| async {}))
+            .route_layer(LineSignatureLayer::new("test_secret"));
+        let body = r#"{"events":[{"type":"unsend","userId":"U123"}]}"#;
+        let signature = generate_signature("test_secret", body.as_bytes());
+        let mut request = request(Some(&signature), body);
+        request
+            .extensions_mut()
+            .insert(RequestId::new(axum::http::HeaderValue::from_static(
+                "req-123",
+            )));
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let error: WebhookErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error.code, "invalid_body");
+        assert!(
+            error
+                .message
+                .starts_with("Invalid JSON body (data) at line 1 column ")
+        );
+        assert_eq!(error.correlation_id.as_deref(), Some("req-123"));
+
+        assert_eq!(
+            json_shape(&serde_json::json!({"type": "unsend", "userId": "U123", "n": [1, true]})),
+            serde_json::json!({"type": "unsend", "userId": "string", "n": ["number", "bool"]})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_body_size_cap() {
+        let app = Router::new()
+            .route("/callback", post(|| async {}))
+            .route_layer(LineSignatureLayer::new("test_secret").max_body_bytes(16));
+        let small = "{}";
+        let signature = generate_signature("test_secret", small.as_bytes());
+        let response = app
+            .clone()
+            .oneshot(request(Some(&signature), small))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // 宣告的 Content-Length 超過上限時不讀取內容
+        let large = r#"{"destination":"U123","events":[]}"#;
+        let signature = generate_signature("test_secret", large.as_bytes());
+        let mut oversized = request(Some(&signature), large);
+        oversized
+            .headers_mut()
+            .insert(CONTENT_LENGTH, large.len().into());
+        let response = app.clone().oneshot(oversized).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        // 沒有 Content-Length 的串流內容也會在超過上限時拒絕
+        let chunks = tokio_stream::iter(vec![
+            Ok::<_, Infallible>(Bytes::from_static(b"{\"destination\":")),
+            Ok(Bytes::from_static(b"\"U123\",\"events\":[]}")),
+        ]);
+        let request = Request::post("/callback")
+            .header("x-line-signature", signature)
+            .body(Body::from_stream(chunks))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_verified_json_requires_layer() {
+        let app = Router::new().route(
+            "/callback",
+            post(|_: VerifiedJson<serde_json::Value>| async {}),
+        );
+        let response = app.oneshot(request(None, "{}")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
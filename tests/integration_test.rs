@@ -12,6 +12,7 @@ fn create_test_config() -> Config {
         channel_secret: "test_channel_secret".to_string(),
         port: 3000,
         host: "0.0.0.0".to_string(),
+        ..Config::default()
     }
 }
 
@@ -32,7 +33,7 @@ fn create_test_signature(channel_secret: &str, body: &str) -> String {
 #[tokio::test]
 async fn test_health_check() {
     let config = create_test_config();
-    let app = create_app(config.clone());
+    let app = create_app(config.clone()).unwrap();
 
     let request = Request::builder()
         .method(Method::GET)
@@ -52,7 +53,7 @@ async fn test_health_check() {
 #[tokio::test]
 async fn test_webhook_missing_signature() {
     let config = create_test_config();
-    let app = create_app(config.clone());
+    let app = create_app(config.clone()).unwrap();
 
     let body = json!({
         "destination": "test",
@@ -74,7 +75,7 @@ async fn test_webhook_missing_signature() {
 #[tokio::test]
 async fn test_webhook_invalid_signature() {
     let config = create_test_config();
-    let app = create_app(config.clone());
+    let app = create_app(config.clone()).unwrap();
 
     let body = json!({
         "destination": "test",
@@ -97,7 +98,7 @@ async fn test_webhook_invalid_signature() {
 #[tokio::test]
 async fn test_webhook_valid_signature() {
     let config = create_test_config();
-    let app = create_app(config.clone());
+    let app = create_app(config.clone()).unwrap();
 
     let body = json!({
         "destination": "test",
@@ -119,10 +120,87 @@ async fn test_webhook_valid_signature() {
     assert_eq!(response.status(), StatusCode::OK);
 }
 
+#[tokio::test]
+async fn test_webhook_unprefixed_signature() {
+    let body = json!({
+        "destination": "test",
+        "events": []
+    })
+    .to_string();
+    let prefixed = create_test_signature("test_channel_secret", &body);
+    let raw = prefixed.trim_start_matches("sha256=").to_string();
+
+    let request = |signature: &str| {
+        Request::builder()
+            .method(Method::POST)
+            .uri("/webhook")
+            .header("content-type", "application/json")
+            .header("x-line-signature", signature)
+            .body(Body::from(body.clone()))
+            .unwrap()
+    };
+
+    // LINE 實際送出的格式沒有 `sha256=` 前綴
+    let response = create_app(create_test_config())
+        .unwrap()
+        .oneshot(request(&raw))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let strict = Config {
+        signature_require_prefix: true,
+        ..create_test_config()
+    };
+    let response = create_app(strict.clone())
+        .unwrap()
+        .oneshot(request(&raw))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let response = create_app(strict)
+        .unwrap()
+        .oneshot(request(&prefixed))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_create_app_rejects_missing_config_file() {
+    let config = Config {
+        forbidden_words_path: Some("/nonexistent/forbidden_words.txt".to_string()),
+        ..create_test_config()
+    };
+    let error = create_app(config).unwrap_err();
+    assert!(error.message.contains("/nonexistent/forbidden_words.txt"));
+}
+
+#[tokio::test]
+async fn test_webhook_insecure_skip_signature() {
+    let config = Config {
+        insecure_skip_signature: true,
+        ..create_test_config()
+    };
+    let app = create_app(config).unwrap();
+
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/webhook")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({"destination": "test", "events": []}).to_string(),
+        ))
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
 #[tokio::test]
 async fn test_webhook_text_message() {
     let config = create_test_config();
-    let app = create_app(config.clone());
+    let app = create_app(config.clone()).unwrap();
 
     let body = json!({
         "destination": "test",
@@ -160,7 +238,7 @@ async fn test_webhook_text_message() {
 #[tokio::test]
 async fn test_webhook_follow_event() {
     let config = create_test_config();
-    let app = create_app(config.clone());
+    let app = create_app(config.clone()).unwrap();
 
     let body = json!({
         "destination": "test",
@@ -194,7 +272,7 @@ async fn test_webhook_follow_event() {
 #[tokio::test]
 async fn test_webhook_sticker_message() {
     let config = create_test_config();
-    let app = create_app(config.clone());
+    let app = create_app(config.clone()).unwrap();
 
     let body = json!({
         "destination": "test",
@@ -229,3 +307,985 @@ async fn test_webhook_sticker_message() {
     let response = app.oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::OK);
 }
+
+#[tokio::test]
+async fn test_admin_routes_disabled_without_token() {
+    let app = create_app(create_test_config()).unwrap();
+
+    let request = Request::builder()
+        .method(Method::GET)
+        .uri("/admin/stats")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_liff_verify_disabled_without_channel_id() {
+    let app = create_app(create_test_config()).unwrap();
+
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/liff/verify")
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "idToken": "token" }).to_string()))
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_admin_stats_requires_token() {
+    let config = Config {
+        admin_token: Some("admin_secret".to_string()),
+        ..create_test_config()
+    };
+    let app = create_app(config).unwrap();
+
+    let request = Request::builder()
+        .method(Method::GET)
+        .uri("/admin/stats")
+        .header("authorization", "Bearer wrong")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_rate_limit_hits_shown_on_dashboard() {
+    use linebot_rs::utils::RateLimitConfig;
+
+    let config = Config {
+        admin_token: Some("admin_secret".to_string()),
+        rate_limit: Some(RateLimitConfig {
+            max_requests: 2,
+            ..RateLimitConfig::default()
+        }),
+        ..create_test_config()
+    };
+    let app = create_app(config).unwrap();
+    let admin_request = |uri: &str, ip: &str| {
+        Request::builder()
+            .method(Method::GET)
+            .uri(uri)
+            .header("authorization", "Bearer admin_secret")
+            .header("x-forwarded-for", ip)
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    for expected in [
+        StatusCode::OK,
+        StatusCode::OK,
+        StatusCode::TOO_MANY_REQUESTS,
+    ] {
+        let response = app
+            .clone()
+            .oneshot(admin_request("/admin/stats", "203.0.113.1"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), expected);
+    }
+
+    // Webhook 與健康檢查不受限制
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/health")
+                .header("x-forwarded-for", "203.0.113.1")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .oneshot(admin_request("/admin/dashboard", "203.0.113.2"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let html = String::from_utf8(body.to_vec()).unwrap();
+    assert!(html.contains("<tr><th>Rate limit hits</th><td>1</td></tr>"));
+}
+
+#[tokio::test]
+async fn test_admin_event_stream_is_sse() {
+    let config = Config {
+        admin_token: Some("admin_secret".to_string()),
+        ..create_test_config()
+    };
+    let app = create_app(config).unwrap();
+
+    let request = Request::builder()
+        .method(Method::GET)
+        .uri("/admin/events/stream")
+        .header("authorization", "Bearer admin_secret")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "text/event-stream"
+    );
+}
+
+#[tokio::test]
+async fn test_request_id_generated() {
+    let app = create_app(create_test_config()).unwrap();
+
+    let request = Request::builder()
+        .method(Method::GET)
+        .uri("/health")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    let request_id = response.headers().get("x-request-id").unwrap();
+    assert!(!request_id.is_empty());
+}
+
+#[tokio::test]
+async fn test_request_id_propagated() {
+    let app = create_app(create_test_config()).unwrap();
+
+    let request = Request::builder()
+        .method(Method::GET)
+        .uri("/health")
+        .header("x-request-id", "req-123")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.headers().get("x-request-id").unwrap(), "req-123");
+}
+
+#[tokio::test]
+async fn test_webhook_gzip_request_body() {
+    use flate2::{Compression, write::GzEncoder};
+    use std::io::Write;
+
+    let config = create_test_config();
+    let app = create_app(config.clone()).unwrap();
+
+    let body = json!({
+        "destination": "test",
+        "events": []
+    })
+    .to_string();
+
+    // LINE 的簽名是針對未壓縮內容計算
+    let signature = create_test_signature(&config.channel_secret, &body);
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(body.as_bytes()).unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/webhook")
+        .header("content-type", "application/json")
+        .header("content-encoding", "gzip")
+        .header("x-line-signature", signature)
+        .body(Body::from(compressed))
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_admin_conversation_history() {
+    use linebot_rs::create_app_with_storage;
+    use linebot_rs::storage::{ConversationDirection, ConversationEntry, MemoryStorage, Storage};
+    use std::sync::Arc;
+
+    let storage = Arc::new(MemoryStorage::new());
+    storage
+        .append_conversation(&ConversationEntry {
+            user_id: "U1".to_string(),
+            direction: ConversationDirection::Incoming,
+            message: json!({"type": "text", "text": "hi"}),
+            recorded_at: chrono::Utc::now(),
+        })
+        .await
+        .unwrap();
+
+    let config = Config {
+        admin_token: Some("admin_secret".to_string()),
+        ..create_test_config()
+    };
+    let app = create_app_with_storage(config, storage).unwrap();
+
+    let request = Request::builder()
+        .method(Method::GET)
+        .uri("/admin/conversations?user_id=U1&limit=10")
+        .header("authorization", "Bearer admin_secret")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let history: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(history[0]["direction"], "incoming");
+    assert_eq!(history[0]["message"]["text"], "hi");
+}
+
+#[tokio::test]
+async fn test_admin_audit_log() {
+    use linebot_rs::create_app_with_storage;
+    use linebot_rs::storage::{AuditQuery, MemoryStorage, Storage};
+    use std::sync::Arc;
+
+    let storage = Arc::new(MemoryStorage::new());
+    let config = Config {
+        admin_token: Some("admin_secret".to_string()),
+        audit_log_enabled: true,
+        ..create_test_config()
+    };
+    let app = create_app_with_storage(config, storage.clone()).unwrap();
+
+    let request = Request::builder()
+        .method(Method::PUT)
+        .uri("/admin/flags/new_menu")
+        .header("authorization", "Bearer admin_secret")
+        .header("x-admin-actor", "alice")
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "enabled": true }).to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert!(response.status().is_success());
+
+    let request = Request::builder()
+        .method(Method::GET)
+        .uri("/admin/flags")
+        .header("authorization", "Bearer wrong")
+        .header("x-admin-actor", "mallory")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let request = Request::builder()
+        .method(Method::GET)
+        .uri("/admin/audit?action=admin.&limit=10")
+        .header("authorization", "Bearer admin_secret")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let entries: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    // 未通過驗證的請求不會寫入
+    assert_eq!(entries.as_array().unwrap().len(), 1);
+    assert_eq!(entries[0]["actor"], "alice");
+    assert_eq!(entries[0]["target"], "PUT /admin/flags/new_menu");
+
+    // 查詢本身也會記錄，未帶操作者時為 admin
+    let entries = storage
+        .audit_log(&AuditQuery {
+            actor: Some("admin".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].target.as_deref(), Some("GET /admin/audit"));
+}
+
+#[tokio::test]
+async fn test_admin_purge_user() {
+    use linebot_rs::create_app_with_storage;
+    use linebot_rs::storage::{MemoryStorage, Session, Storage};
+    use std::sync::Arc;
+
+    let storage = Arc::new(MemoryStorage::new());
+    storage
+        .save_session(&Session {
+            user_id: "U1".to_string(),
+            data: json!({"step": 1}),
+            expires_at: None,
+        })
+        .await
+        .unwrap();
+
+    let config = Config {
+        admin_token: Some("admin_secret".to_string()),
+        ..create_test_config()
+    };
+    let app = create_app_with_storage(config, storage.clone()).unwrap();
+
+    let request = Request::builder()
+        .method(Method::DELETE)
+        .uri("/admin/users/U1")
+        .header("authorization", "Bearer admin_secret")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(storage.get_session("U1").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_admin_export_csv() {
+    use linebot_rs::create_app_with_storage;
+    use linebot_rs::storage::{ConversationDirection, ConversationEntry, MemoryStorage, Storage};
+    use std::sync::Arc;
+
+    let storage = Arc::new(MemoryStorage::new());
+    storage
+        .append_conversation(&ConversationEntry {
+            user_id: "U1".to_string(),
+            direction: ConversationDirection::Outgoing,
+            message: json!({"type": "text", "text": "hello"}),
+            recorded_at: chrono::Utc::now(),
+        })
+        .await
+        .unwrap();
+    storage
+        .append_conversation(&ConversationEntry {
+            user_id: "U1".to_string(),
+            direction: ConversationDirection::Event,
+            message: json!({"type": "follow"}),
+            recorded_at: chrono::Utc::now(),
+        })
+        .await
+        .unwrap();
+
+    let config = Config {
+        admin_token: Some("admin_secret".to_string()),
+        ..create_test_config()
+    };
+    let app = create_app_with_storage(config, storage).unwrap();
+
+    let request = Request::builder()
+        .method(Method::GET)
+        .uri("/admin/export?format=csv&kind=events")
+        .header("authorization", "Bearer admin_secret")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let csv = String::from_utf8(body.to_vec()).unwrap();
+    assert_eq!(csv.lines().count(), 2);
+    assert!(csv.lines().nth(1).unwrap().contains(",U1,event,follow,,"));
+
+    let request = Request::builder()
+        .method(Method::GET)
+        .uri("/admin/export?format=csv")
+        .header("authorization", "Bearer admin_secret")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "text/csv; charset=utf-8"
+    );
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let csv = String::from_utf8(body.to_vec()).unwrap();
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next(),
+        Some("recorded_at,user_id,direction,type,text,message")
+    );
+    assert!(lines.next().unwrap().contains(",U1,outgoing,text,hello,"));
+    assert!(lines.next().unwrap().contains(",U1,event,follow,,"));
+}
+
+#[tokio::test]
+async fn test_admin_update_moderation_policy() {
+    let config = Config {
+        admin_token: Some("admin_secret".to_string()),
+        ..create_test_config()
+    };
+    let app = create_app(config).unwrap();
+
+    let request = Request::builder()
+        .method(Method::PUT)
+        .uri("/admin/moderation/policy")
+        .header("authorization", "Bearer admin_secret")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({"low": "warn_user", "medium": "drop_message", "high": "notify_admin"})
+                .to_string(),
+        ))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = Request::builder()
+        .uri("/admin/moderation/policy")
+        .header("authorization", "Bearer admin_secret")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let policy: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(policy["medium"], "drop_message");
+    assert_eq!(policy["high"], "notify_admin");
+}
+
+#[tokio::test]
+async fn test_admin_feature_flags() {
+    let config = Config {
+        admin_token: Some("admin_secret".to_string()),
+        ..create_test_config()
+    };
+    let app = create_app(config).unwrap();
+
+    let request = Request::builder()
+        .method(Method::PUT)
+        .uri("/admin/flags/new_flow")
+        .header("authorization", "Bearer admin_secret")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({"percentage": 20, "users": ["U1234"]}).to_string(),
+        ))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = Request::builder()
+        .uri("/admin/flags")
+        .header("authorization", "Bearer admin_secret")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let flags: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(flags["new_flow"]["percentage"], 20);
+    assert_eq!(flags["new_flow"]["enabled"], true);
+
+    let request = Request::builder()
+        .method(Method::PUT)
+        .uri("/admin/flags/bad")
+        .header("authorization", "Bearer admin_secret")
+        .header("content-type", "application/json")
+        .body(Body::from(json!({"percentage": 150}).to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    for expected in [StatusCode::NO_CONTENT, StatusCode::NOT_FOUND] {
+        let request = Request::builder()
+            .method(Method::DELETE)
+            .uri("/admin/flags/new_flow")
+            .header("authorization", "Bearer admin_secret")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), expected);
+    }
+}
+
+#[cfg(feature = "graphql")]
+#[tokio::test]
+async fn test_admin_graphql() {
+    use linebot_rs::create_app_with_storage;
+    use linebot_rs::storage::{
+        ConversationDirection, ConversationEntry, MemoryStorage, Storage, UserRecord,
+    };
+    use std::sync::Arc;
+
+    let storage = Arc::new(MemoryStorage::new());
+    storage
+        .upsert_user(&UserRecord {
+            user_id: "U1".to_string(),
+            display_name: Some("Alice".to_string()),
+            language: None,
+            followed: true,
+            updated_at: chrono::Utc::now(),
+        })
+        .await
+        .unwrap();
+    storage
+        .append_conversation(&ConversationEntry {
+            user_id: "U1".to_string(),
+            direction: ConversationDirection::Incoming,
+            message: json!({"type": "text", "text": "hi"}),
+            recorded_at: chrono::Utc::now(),
+        })
+        .await
+        .unwrap();
+
+    let config = Config {
+        admin_token: Some("admin_secret".to_string()),
+        ..create_test_config()
+    };
+    let app = create_app_with_storage(config, storage).unwrap();
+    let query = json!({
+        "query": "{ stats { totalEvents } user(userId: \"U1\") { displayName followed } \
+                  messages(userId: \"U1\", limit: 10) { direction message } }"
+    });
+
+    let unauthorized = Request::builder()
+        .method(Method::POST)
+        .uri("/admin/graphql")
+        .header("content-type", "application/json")
+        .body(Body::from(query.to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(unauthorized).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/admin/graphql")
+        .header("authorization", "Bearer admin_secret")
+        .header("content-type", "application/json")
+        .body(Body::from(query.to_string()))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let result: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(result["errors"], serde_json::Value::Null);
+    assert_eq!(result["data"]["stats"]["totalEvents"], 0);
+    assert_eq!(result["data"]["user"]["displayName"], "Alice");
+    assert_eq!(result["data"]["messages"][0]["direction"], "incoming");
+    assert_eq!(result["data"]["messages"][0]["message"]["text"], "hi");
+}
+
+#[tokio::test]
+async fn test_plugin_commands_and_middlewares() {
+    use async_trait::async_trait;
+    use linebot_rs::create_app_with_plugins;
+    use linebot_rs::models::{Event, MessageEvent, MessageType, OutgoingMessage};
+    use linebot_rs::plugins::{
+        CommandHandler, EventMiddleware, MiddlewareAction, Plugin, PluginError, PluginRegistry,
+    };
+    use linebot_rs::storage::MemoryStorage;
+    use linebot_rs::webhook::AppState;
+    use std::sync::{Arc, Mutex};
+
+    type Calls = Arc<Mutex<Vec<String>>>;
+
+    struct Dice(Calls);
+
+    #[async_trait]
+    impl CommandHandler for Dice {
+        async fn handle(
+            &self,
+            _state: &AppState,
+            _event: &MessageEvent,
+            args: &str,
+        ) -> Result<Vec<OutgoingMessage>, PluginError> {
+            self.0.lock().unwrap().push(format!("dice {}", args));
+            Ok(vec![OutgoingMessage::text("4")])
+        }
+    }
+
+    struct Block(Calls);
+
+    #[async_trait]
+    impl EventMiddleware for Block {
+        async fn handle(&self, _state: &AppState, event: &Event) -> MiddlewareAction {
+            match event {
+                Event::Message(MessageEvent {
+                    message: MessageType::Text { text },
+                    ..
+                }) => {
+                    self.0.lock().unwrap().push(format!("middleware {}", text));
+                    if text == "blocked" {
+                        MiddlewareAction::Handled
+                    } else {
+                        MiddlewareAction::Continue
+                    }
+                }
+                _ => MiddlewareAction::Continue,
+            }
+        }
+    }
+
+    struct GamePlugin(Calls);
+
+    #[async_trait]
+    impl Plugin for GamePlugin {
+        fn name(&self) -> &str {
+            "game"
+        }
+
+        fn commands(&self) -> Vec<(String, Arc<dyn CommandHandler>)> {
+            vec![("Dice".to_string(), Arc::new(Dice(self.0.clone())))]
+        }
+
+        fn middlewares(&self) -> Vec<Arc<dyn EventMiddleware>> {
+            vec![Arc::new(Block(self.0.clone()))]
+        }
+
+        async fn init(&self, _state: Arc<AppState>) -> Result<(), PluginError> {
+            self.0.lock().unwrap().push("init".to_string());
+            Ok(())
+        }
+    }
+
+    let calls: Calls = Arc::default();
+    let config = Config {
+        dry_run: true,
+        ..create_test_config()
+    };
+    let app = create_app_with_plugins(
+        config.clone(),
+        Arc::new(MemoryStorage::new()),
+        PluginRegistry::new().register(GamePlugin(calls.clone())),
+    )
+    .await
+    .unwrap();
+
+    for text in ["dice 2d6", "blocked", "spam", "hello"] {
+        let body = json!({
+            "destination": "test",
+            "events": [{
+                "type": "message",
+                "replyToken": "reply_token_123",
+                "message": { "type": "text", "text": text },
+                "timestamp": 1234567890,
+                "source": { "type": "user", "userId": "user_123" },
+                "mode": "active"
+            }]
+        })
+        .to_string();
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/webhook")
+            .header("content-type", "application/json")
+            .header(
+                "x-line-signature",
+                create_test_signature(&config.channel_secret, &body),
+            )
+            .body(Body::from(body))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    // 被禁用詞擋下的訊息不會交給外掛
+    assert_eq!(
+        *calls.lock().unwrap(),
+        vec![
+            "init",
+            "middleware dice 2d6",
+            "dice 2d6",
+            "middleware blocked",
+            "middleware hello"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_webhook_async_queue() {
+    use async_trait::async_trait;
+    use linebot_rs::create_app_with_plugins;
+    use linebot_rs::models::{Event, MessageEvent, MessageType};
+    use linebot_rs::plugins::{EventMiddleware, MiddlewareAction, Plugin, PluginRegistry};
+    use linebot_rs::storage::MemoryStorage;
+    use linebot_rs::webhook::{AppState, OverflowPolicy, WebhookQueueConfig};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    type Calls = Arc<Mutex<Vec<String>>>;
+
+    struct Record(Calls);
+
+    #[async_trait]
+    impl EventMiddleware for Record {
+        async fn handle(&self, _state: &AppState, event: &Event) -> MiddlewareAction {
+            if let Event::Message(MessageEvent {
+                message: MessageType::Text { text },
+                ..
+            }) = event
+            {
+                self.0.lock().unwrap().push(text.clone());
+            }
+            MiddlewareAction::Handled
+        }
+    }
+
+    struct RecordPlugin(Calls);
+
+    impl Plugin for RecordPlugin {
+        fn name(&self) -> &str {
+            "record"
+        }
+
+        fn middlewares(&self) -> Vec<Arc<dyn EventMiddleware>> {
+            vec![Arc::new(Record(self.0.clone()))]
+        }
+    }
+
+    let calls: Calls = Arc::default();
+    let config = Config {
+        dry_run: true,
+        webhook_queue: Some(WebhookQueueConfig {
+            capacity: 1,
+            workers: 1,
+            overflow: OverflowPolicy::Shed,
+            ..Default::default()
+        }),
+        ..create_test_config()
+    };
+    let app = create_app_with_plugins(
+        config.clone(),
+        Arc::new(MemoryStorage::new()),
+        PluginRegistry::new().register(RecordPlugin(calls.clone())),
+    )
+    .await
+    .unwrap();
+
+    let send = |texts: &[&str]| {
+        let events: Vec<_> = texts
+            .iter()
+            .map(|text| {
+                json!({
+                    "type": "message",
+                    "replyToken": "reply_token_123",
+                    "message": { "type": "text", "text": text },
+                    "timestamp": 1234567890,
+                    "source": { "type": "user", "userId": "user_123" },
+                    "mode": "active"
+                })
+            })
+            .collect();
+        let body = json!({ "destination": "test", "events": events }).to_string();
+        Request::builder()
+            .method(Method::POST)
+            .uri("/webhook")
+            .header("content-type", "application/json")
+            .header(
+                "x-line-signature",
+                create_test_signature(&config.channel_secret, &body),
+            )
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    // 同一個聊天室的事件多於佇列容量時，等 worker 取走後依序排入
+    let response = app.clone().oneshot(send(&["a", "b"])).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let wait_for = |count: usize| {
+        let calls = calls.clone();
+        async move {
+            for _ in 0..100 {
+                if calls.lock().unwrap().len() == count {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }
+    };
+    wait_for(2).await;
+
+    let response = app.clone().oneshot(send(&["c"])).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    wait_for(3).await;
+    assert_eq!(*calls.lock().unwrap(), vec!["a", "b", "c"]);
+}
+
+#[tokio::test]
+async fn test_webhook_recording_replay() {
+    use async_trait::async_trait;
+    use linebot_rs::create_app_with_plugins;
+    use linebot_rs::models::{Event, MessageEvent, MessageType};
+    use linebot_rs::plugins::{EventMiddleware, MiddlewareAction, Plugin, PluginRegistry};
+    use linebot_rs::storage::MemoryStorage;
+    use linebot_rs::webhook::{AppState, WebhookRecorderConfig};
+    use std::sync::{Arc, Mutex};
+
+    type Calls = Arc<Mutex<Vec<String>>>;
+
+    struct Record(Calls);
+
+    #[async_trait]
+    impl EventMiddleware for Record {
+        async fn handle(&self, _state: &AppState, event: &Event) -> MiddlewareAction {
+            if let Event::Message(MessageEvent {
+                message: MessageType::Text { text },
+                ..
+            }) = event
+            {
+                self.0.lock().unwrap().push(text.clone());
+            }
+            MiddlewareAction::Handled
+        }
+    }
+
+    struct RecordPlugin(Calls);
+
+    impl Plugin for RecordPlugin {
+        fn name(&self) -> &str {
+            "record"
+        }
+
+        fn middlewares(&self) -> Vec<Arc<dyn EventMiddleware>> {
+            vec![Arc::new(Record(self.0.clone()))]
+        }
+    }
+
+    let calls: Calls = Arc::default();
+    let config = Config {
+        dry_run: true,
+        admin_token: Some("admin_secret".to_string()),
+        webhook_recording: Some(WebhookRecorderConfig::default()),
+        ..create_test_config()
+    };
+    let app = create_app_with_plugins(
+        config.clone(),
+        Arc::new(MemoryStorage::new()),
+        PluginRegistry::new().register(RecordPlugin(calls.clone())),
+    )
+    .await
+    .unwrap();
+
+    let body = json!({
+        "destination": "test",
+        "events": [{
+            "type": "message",
+            "replyToken": "reply_token_123",
+            "message": { "type": "text", "text": "call 0912345678" },
+            "timestamp": 1234567890,
+            "source": { "type": "user", "userId": "user_123" },
+            "mode": "active"
+        }]
+    })
+    .to_string();
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/webhook")
+        .header("content-type", "application/json")
+        .header(
+            "x-line-signature",
+            create_test_signature(&config.channel_secret, &body),
+        )
+        .body(Body::from(body))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // 錄製在背景寫入
+    let mut recordings = serde_json::Value::Null;
+    for _ in 0..50 {
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri("/admin/recordings")
+            .header("authorization", "Bearer admin_secret")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        recordings = serde_json::from_slice(&body).unwrap();
+        if !recordings.as_array().unwrap().is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    assert_eq!(recordings[0]["event_types"], json!(["message"]));
+    let id = recordings[0]["id"].as_str().unwrap().to_string();
+
+    let replay = |ids: serde_json::Value| {
+        Request::builder()
+            .method(Method::POST)
+            .uri("/admin/recordings/replay")
+            .header("authorization", "Bearer admin_secret")
+            .header("content-type", "application/json")
+            .body(Body::from(json!({ "ids": ids }).to_string()))
+            .unwrap()
+    };
+    let response = app
+        .clone()
+        .oneshot(replay(json!([id.clone(), "missing"])))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = app.clone().oneshot(replay(json!([id]))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let result: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(result, json!({ "recordings": 1, "events": 1 }));
+    // 重新處理的是遮罩後的內容
+    assert_eq!(
+        *calls.lock().unwrap(),
+        vec!["call 0912345678", "call 09***78"]
+    );
+
+    // 非 dry run 時不允許重新處理
+    let app = create_app_with_plugins(
+        Config {
+            dry_run: false,
+            ..config
+        },
+        Arc::new(MemoryStorage::new()),
+        PluginRegistry::new(),
+    )
+    .await
+    .unwrap();
+    let response = app.oneshot(replay(json!([id]))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn test_webhook_destination_verification() {
+    let config = Config {
+        dry_run: true,
+        verify_destination: true,
+        bot_user_id: Some("Ubot".to_string()),
+        ..create_test_config()
+    };
+    let app = create_app(config.clone()).unwrap();
+
+    let send = |destination: &str| {
+        let body = json!({ "destination": destination, "events": [] }).to_string();
+        Request::builder()
+            .method(Method::POST)
+            .uri("/webhook")
+            .header("content-type", "application/json")
+            .header(
+                "x-line-signature",
+                create_test_signature(&config.channel_secret, &body),
+            )
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    let response = app.clone().oneshot(send("Uother")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = app.oneshot(send("Ubot")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}